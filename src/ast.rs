@@ -1,4 +1,4 @@
-use crate::types::{Type, ParameterInfo, ClassInfo, MethodInfo};
+use crate::types::{Type, ParameterInfo, ClassInfo, MethodInfo, TypeParamInfo};
 use crate::error::SourceLocation;
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -12,9 +12,34 @@ pub trait HasLocation {
 pub struct Program {
     pub classes: Vec<ClassDecl>,
     pub interfaces: Vec<InterfaceDecl>,
+    pub enums: Vec<EnumDecl>,
+    pub structs: Vec<StructDecl>,
     pub top_level_functions: Vec<TopLevelFunction>,
     pub extern_declarations: Vec<ExternDecl>,  // FFI extern 声明
     pub type_aliases: Vec<TypeAliasDecl>,      // 类型别名声明 (type X = Y)
+    pub package: Option<String>,               // 包声明 (package a.b;)，默认包为 None
+    pub imports: Vec<ImportDecl>,              // 导入声明 (import a.b.Class;)
+    /// 包中声明的类、接口、枚举和结构体: 限定名 -> 所属包（合并导入模块后包含所有模块的声明）
+    pub declaration_packages: std::collections::HashMap<String, String>,
+}
+
+/// 导入声明 - import a.b.Class; 或 import a.b.*;
+#[derive(Debug, Clone)]
+pub struct ImportDecl {
+    pub package: String,
+    /// 导入的类型名，通配导入 (import a.b.*;) 为 None
+    pub name: Option<String>,
+    pub loc: SourceLocation,
+}
+
+impl ImportDecl {
+    /// 导入路径的文本形式
+    pub fn path(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{}.{}", self.package, name),
+            None => format!("{}.*", self.package),
+        }
+    }
 }
 
 /// 类型别名声明 - type Name = Type;
@@ -67,17 +92,62 @@ pub enum CallingConvention {
 pub struct InterfaceDecl {
     pub name: String,
     pub modifiers: Vec<Modifier>,
+    pub type_params: Vec<TypeParamInfo>,  // 泛型类型参数: interface Comparable<T>
+    pub permits: Vec<String>,  // sealed 接口允许的实现类
+    pub annotations: Vec<Annotation>,
     pub methods: Vec<MethodDecl>,
     pub loc: SourceLocation,
 }
 
+/// 枚举声明 - enum Shape { Circle(double r), Rect(double w, double h) }
+#[derive(Debug, Clone)]
+pub struct EnumDecl {
+    pub name: String,
+    pub modifiers: Vec<Modifier>,
+    pub variants: Vec<EnumVariant>,
+    pub loc: SourceLocation,
+}
+
+/// 枚举变体，fields 为空时是无负载变体（C 风格枚举常量）
+#[derive(Debug, Clone)]
+pub struct EnumVariant {
+    pub name: String,
+    pub fields: Vec<ParameterInfo>,
+    pub loc: SourceLocation,
+}
+
+/// 结构体声明 - struct Point { double x; double y; }
+///
+/// 结构体是值类型：赋值、传参和返回时复制整个值，字段按声明顺序以 C 的方式布局。
+#[derive(Debug, Clone)]
+pub struct StructDecl {
+    pub name: String,
+    pub modifiers: Vec<Modifier>,
+    pub fields: Vec<StructField>,
+    pub loc: SourceLocation,
+}
+
+/// 结构体字段
+#[derive(Debug, Clone)]
+pub struct StructField {
+    pub name: String,
+    pub field_type: Type,
+    pub loc: SourceLocation,
+}
+
 #[derive(Debug, Clone)]
 pub struct ClassDecl {
     pub name: String,
     pub modifiers: Vec<Modifier>,
+    pub type_params: Vec<TypeParamInfo>,  // 泛型类型参数: class ArrayList<T, A: Allocator>
     pub parent: Option<String>,
     pub interfaces: Vec<String>,  // 实现的接口列表
+    pub generic_supertypes: Vec<Type>,  // 泛型父类/接口（parent/interfaces 中为其单态化名称）
+    pub permits: Vec<String>,  // sealed 类允许的直接子类
+    pub annotations: Vec<Annotation>,
     pub members: Vec<ClassMember>,
+    /// 嵌套类、内部类和匿名类的外围类（名称为 Outer$Inner / Outer$1 形式）
+    pub enclosing_class: Option<String>,
     pub loc: SourceLocation,
 }
 
@@ -89,12 +159,16 @@ pub enum ClassMember {
     Destructor(DestructorDecl),
     InstanceInitializer(Block),  // 实例初始化块 { ... }
     StaticInitializer(Block),    // 静态初始化块 static { ... }
+    Class(ClassDecl),            // 嵌套类 static class Inner { ... }（解析结束时提升到顶层）
+    Property(Box<PropertyDecl>), // 属性 property int count { get; private set; }（访问方法紧随其后）
 }
 
 #[derive(Debug, Clone)]
 pub struct MethodDecl {
     pub name: String,
     pub modifiers: Vec<Modifier>,
+    pub annotations: Vec<Annotation>,
+    pub type_params: Vec<TypeParamInfo>,  // 泛型方法类型参数: <T> T id(T x)
     pub return_type: Type,
     pub params: Vec<ParameterInfo>,
     pub body: Option<Block>,
@@ -106,10 +180,51 @@ pub struct FieldDecl {
     pub name: String,
     pub field_type: Type,
     pub modifiers: Vec<Modifier>,
+    pub annotations: Vec<Annotation>,
     pub initializer: Option<Expr>,
     pub loc: SourceLocation,
 }
 
+/// 属性声明: property int count { get; private set; }
+///
+/// 解析时展开为 `getCount()` / `setCount(value)` 访问方法，自动属性还有同名的私有字段；
+/// 展开出的成员紧跟在属性之后，这里保留的声明供语义分析解析 `obj.count`。
+#[derive(Debug, Clone)]
+pub struct PropertyDecl {
+    pub name: String,
+    pub property_type: Type,
+    pub modifiers: Vec<Modifier>,
+    pub annotations: Vec<Annotation>,
+    pub getter: Option<PropertyAccessor>,
+    pub setter: Option<PropertyAccessor>,
+    pub initializer: Option<Expr>,  // 只有自动属性可以有初始值
+    pub loc: SourceLocation,
+}
+
+/// 属性的 get / set 访问器
+#[derive(Debug, Clone)]
+pub struct PropertyAccessor {
+    pub modifiers: Vec<Modifier>,  // 只能是访问修饰符，省略时与属性相同
+    pub body: Option<Block>,  // None 表示自动访问器 `get;` / `set;`
+    pub loc: SourceLocation,
+}
+
+impl PropertyDecl {
+    /// 自动属性: 访问器都没有方法体，读写同名的私有字段
+    pub fn is_auto(&self) -> bool {
+        self.getter.iter().chain(self.setter.iter()).all(|accessor| accessor.body.is_none())
+    }
+
+    /// 访问方法名: count -> getCount / setCount
+    pub fn accessor_name(&self, prefix: &str) -> String {
+        let mut chars = self.name.chars();
+        match chars.next() {
+            Some(first) => format!("{}{}{}", prefix, first.to_uppercase(), chars.as_str()),
+            None => prefix.to_string(),
+        }
+    }
+}
+
 /// 构造函数声明
 #[derive(Debug, Clone)]
 pub struct ConstructorDecl {
@@ -143,9 +258,118 @@ pub enum Modifier {
     Static,
     Final,
     Abstract,
+    Sealed,    // 只允许 permits 列出的类继承或实现
     Native,
+    Const,     // const 函数，可以在编译期求值
     Main,      // 标记主类，用于解决多main冲突
     Override,  // @Override 注解，标记方法重写
+    Default,   // 接口的默认方法
+}
+
+/// 注解 - @Name 或 @Name(args)，可用于类、接口、方法、字段和参数
+///
+/// 参数只能是字面量，可以带名称: @Export("add")、@Since(version = 2)。
+/// @main 和 @Override 仍是修饰符（Modifier::Main / Modifier::Override），不出现在注解列表中。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub name: String,
+    pub args: Vec<AnnotationArg>,
+    pub loc: SourceLocation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotationArg {
+    pub name: Option<String>,
+    pub value: AnnotationValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnnotationValue {
+    Int(i64),
+    String(String),
+    Bool(bool),
+    Char(char),
+}
+
+impl Annotation {
+    /// 内置注解名
+    pub const DEPRECATED: &'static str = "Deprecated";
+    pub const INLINE: &'static str = "Inline";
+    pub const NO_INLINE: &'static str = "NoInline";
+    pub const EXPORT: &'static str = "Export";
+    pub const TEST: &'static str = "Test";
+
+    /// 指定名称的参数
+    pub fn arg(&self, name: &str) -> Option<&AnnotationValue> {
+        self.args.iter().find(|arg| arg.name.as_deref() == Some(name)).map(|arg| &arg.value)
+    }
+
+    /// 注解的主值：第一个未命名参数或名为 value 的参数
+    pub fn value(&self) -> Option<&AnnotationValue> {
+        self.args.iter().find(|arg| arg.name.is_none()).map(|arg| &arg.value).or_else(|| self.arg("value"))
+    }
+
+    /// 主值为字符串时返回该字符串
+    pub fn string_value(&self) -> Option<&str> {
+        match self.value() {
+            Some(AnnotationValue::String(value)) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AnnotationValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnnotationValue::Int(value) => write!(f, "{}", value),
+            AnnotationValue::String(value) => write!(f, "\"{}\"", value),
+            AnnotationValue::Bool(value) => write!(f, "{}", value),
+            AnnotationValue::Char(value) => write!(f, "'{}'", value),
+        }
+    }
+}
+
+/// 带注解的声明，供语义分析和代码生成查询注解
+pub trait Annotated {
+    fn annotations(&self) -> &[Annotation];
+
+    fn annotation(&self, name: &str) -> Option<&Annotation> {
+        self.annotations().iter().find(|annotation| annotation.name == name)
+    }
+
+    fn has_annotation(&self, name: &str) -> bool {
+        self.annotation(name).is_some()
+    }
+}
+
+impl Annotated for ClassDecl {
+    fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+}
+
+impl Annotated for InterfaceDecl {
+    fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+}
+
+impl Annotated for MethodDecl {
+    fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+}
+
+impl Annotated for FieldDecl {
+    fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+}
+
+impl Annotated for PropertyDecl {
+    fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -162,13 +386,17 @@ pub enum Stmt {
     If(IfStmt),
     While(WhileStmt),
     For(ForStmt),
+    ForEach(ForEachStmt),  // 增强 for 循环: for (T x : iterable)
     DoWhile(DoWhileStmt),
     Switch(SwitchStmt),
     Block(Block),
     Scope(ScopeStmt),  // 0.5.0.0: scope 栈分配块
+    Defer(DeferStmt),  // defer 延迟执行语句
+    TryWithResources(TryStmt),  // try-with-resources 语句
     Break(Option<String>),  // 可选的标签
     Continue(Option<String>),  // 可选的标签
     InlineIr(InlineIrStmt),  // 内联IR语句块
+    Error(SourceLocation),  // 语法错误恢复时代替出错的语句
 }
 
 /// 内联IR语句 - __ir { ... }
@@ -186,6 +414,23 @@ pub struct ScopeStmt {
     pub loc: SourceLocation,
 }
 
+/// defer 语句 - `defer <stmt>;`
+/// 所在代码块退出时（正常结束或 return、break、continue）执行，多个 defer 按后进先出顺序执行
+#[derive(Debug, Clone)]
+pub struct DeferStmt {
+    pub body: Box<Stmt>,
+    pub loc: SourceLocation,
+}
+
+/// try-with-resources 语句 - `try (File f = ...; File g = ...) { ... }`
+/// 代码块退出时按声明的相反顺序调用各资源的 close()
+#[derive(Debug, Clone)]
+pub struct TryStmt {
+    pub resources: Vec<VarDecl>,
+    pub body: Block,
+    pub loc: SourceLocation,
+}
+
 #[derive(Debug, Clone)]
 pub struct VarDecl {
     pub name: String,
@@ -221,6 +466,83 @@ pub struct ForStmt {
     pub loc: SourceLocation,
 }
 
+/// 增强 for 循环: for (T x : iterable)
+///
+/// iterable 可以是数组、内置的 `range(a, b[, step])`，或者提供 `iterator()` 方法的对象
+/// （返回带 `hasNext()`/`next()` 的迭代器），以及本身带 `hasNext()`/`next()` 的迭代器对象。
+/// 语义分析把具体形式记录在 `TypeRegistry::for_each_loops` 中，代码生成据此展开为计数循环
+/// 或 hasNext()/next() 循环，过程中使用 `hidden_name` 命名的局部变量。
+#[derive(Debug, Clone)]
+pub struct ForEachStmt {
+    pub var_type: Type,  // var/let/auto 时为 Type::Auto
+    pub var_name: String,
+    pub is_final: bool,
+    pub iterable: Expr,
+    pub body: Box<Stmt>,
+    pub label: Option<String>,
+    pub loc: SourceLocation,
+}
+
+impl ForEachStmt {
+    /// 循环使用的隐藏局部变量名（role: iter、index、end、step）
+    pub fn hidden_name(&self, role: &str) -> String {
+        format!("__for_each_{}_{}_{}", role, self.loc.line, self.loc.column)
+    }
+
+    /// `range(a, b)` 或 `range(a, b, step)` 的参数
+    pub fn range_args(&self) -> Option<&[Expr]> {
+        match &self.iterable {
+            Expr::Call(call) if matches!(call.callee.as_ref(), Expr::Identifier(name) if name.name == "range")
+                && (call.args.len() == 2 || call.args.len() == 3) => Some(&call.args),
+            _ => None,
+        }
+    }
+
+    /// 可迭代对象上的 `iterable.iterator()` 调用
+    pub fn iterator_expr(&self) -> Expr {
+        Self::method_call(self.iterable.clone(), "iterator", &self.call_loc(1))
+    }
+
+    /// 隐藏的迭代器变量上的 `hasNext()` 调用
+    pub fn has_next_expr(&self) -> Expr {
+        Self::method_call(self.iterator_var(), "hasNext", &self.call_loc(2))
+    }
+
+    /// 隐藏的迭代器变量上的 `next()` 调用
+    pub fn next_expr(&self) -> Expr {
+        Self::method_call(self.iterator_var(), "next", &self.call_loc(3))
+    }
+
+    fn iterator_var(&self) -> Expr {
+        Expr::Identifier(IdentifierExpr {
+            name: self.hidden_name("iter"),
+            loc: self.loc.clone(),
+        })
+    }
+
+    /// 生成的调用位于 `for` 关键字内部的各列，与源码中的表达式和彼此都不重合
+    /// （语义分析按调用位置记录泛型方法调用等信息）
+    fn call_loc(&self, offset: usize) -> SourceLocation {
+        SourceLocation {
+            file: self.loc.file.clone(),
+            line: self.loc.line,
+            column: self.loc.column + offset,
+        }
+    }
+
+    fn method_call(object: Expr, method: &str, loc: &SourceLocation) -> Expr {
+        Expr::Call(CallExpr {
+            callee: Box::new(Expr::MemberAccess(MemberAccessExpr {
+                object: Box::new(object),
+                member: method.to_string(),
+                loc: loc.clone(),
+            })),
+            args: Vec::new(),
+            loc: loc.clone(),
+        })
+    }
+}
+
 /// do-while 循环语句
 #[derive(Debug, Clone)]
 pub struct DoWhileStmt {
@@ -233,10 +555,77 @@ pub struct DoWhileStmt {
 /// switch case 分支
 #[derive(Debug, Clone)]
 pub struct Case {
-    pub value: i64,
+    pub labels: Vec<CaseLabel>,  // 一个分支可以有多个标签: case 1, 2, 3:
+    pub guard: Option<Expr>,     // 守卫条件: case "get" when verbose:
     pub body: Vec<Stmt>,
 }
 
+impl Case {
+    /// 分支的枚举变体模式（绑定负载字段的分支只有一个标签）
+    pub fn pattern(&self) -> Option<&EnumPattern> {
+        self.labels.iter().find_map(CaseLabel::pattern)
+    }
+
+    /// 分支的类型模式（绑定变量的分支只有一个标签）
+    pub fn type_pattern(&self) -> Option<&TypePattern> {
+        self.labels.iter().find_map(CaseLabel::type_pattern)
+    }
+}
+
+/// case 标签
+#[derive(Debug, Clone)]
+pub enum CaseLabel {
+    Int(i64),
+    Char(char),
+    String(String),
+    /// 以标识符开头的标签：switch 枚举值时是变体模式，否则是 static final 常量: case MAX: / case Limits.MAX:
+    Pattern(EnumPattern),
+    /// 类型模式: case Circle c:
+    Type(TypePattern),
+    /// 常量表达式: case 1 << 4: / case BASE + 1: / case square(3):
+    Const(ConstLabel),
+}
+
+impl CaseLabel {
+    pub fn pattern(&self) -> Option<&EnumPattern> {
+        match self {
+            CaseLabel::Pattern(pattern) => Some(pattern),
+            _ => None,
+        }
+    }
+
+    pub fn type_pattern(&self) -> Option<&TypePattern> {
+        match self {
+            CaseLabel::Type(pattern) => Some(pattern),
+            _ => None,
+        }
+    }
+}
+
+/// 常量表达式标签，值在语义分析阶段求出
+#[derive(Debug, Clone)]
+pub struct ConstLabel {
+    pub expr: Expr,
+    pub loc: SourceLocation,
+}
+
+/// 类型模式 - case Circle c: 值是 Circle 的实例时匹配，并绑定为 Circle 类型的变量 c
+#[derive(Debug, Clone)]
+pub struct TypePattern {
+    pub target_type: Type,
+    pub binding: String,
+    pub loc: SourceLocation,
+}
+
+/// 枚举变体模式 - case Circle(r): 或 case Shape.Rect(w, h):
+#[derive(Debug, Clone)]
+pub struct EnumPattern {
+    pub enum_name: Option<String>,  // 限定写法 Shape.Rect 中的枚举名
+    pub variant: String,
+    pub bindings: Vec<String>,  // 按顺序绑定负载字段
+    pub loc: SourceLocation,
+}
+
 /// switch 语句
 #[derive(Debug, Clone)]
 pub struct SwitchStmt {
@@ -246,6 +635,34 @@ pub struct SwitchStmt {
     pub loc: SourceLocation,
 }
 
+/// switch 表达式: switch (cmd) { case "get" -> 1; default -> 0; }
+#[derive(Debug, Clone)]
+pub struct SwitchExpr {
+    pub expr: Box<Expr>,
+    pub arms: Vec<SwitchArm>,
+    pub default: Option<Box<Expr>>,
+    pub loc: SourceLocation,
+}
+
+/// switch 表达式的分支: case 1, 2 -> expr
+#[derive(Debug, Clone)]
+pub struct SwitchArm {
+    pub labels: Vec<CaseLabel>,
+    pub guard: Option<Expr>,
+    pub value: Expr,
+}
+
+impl SwitchArm {
+    pub fn pattern(&self) -> Option<&EnumPattern> {
+        self.labels.iter().find_map(CaseLabel::pattern)
+    }
+
+    /// 分支的类型模式（绑定变量的分支只有一个标签）
+    pub fn type_pattern(&self) -> Option<&TypePattern> {
+        self.labels.iter().find_map(CaseLabel::type_pattern)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Expr {
     Literal(LiteralValue),
@@ -266,6 +683,10 @@ pub enum Expr {
     InstanceOf(InstanceOfExpr), // instanceof 运算符: obj instanceof Type
     Alloc(AllocExpr),          // 0.5.0.0: 内存分配表达式: __cay_alloc(size)
     Dealloc(DeallocExpr),      // 0.5.0.0: 内存释放表达式: __cay_free(ptr)
+    Try(TryExpr),              // 错误传播: expr?
+    Switch(SwitchExpr),        // switch 表达式: switch (x) { case 1 -> a; default -> b; }
+    SafeCall(SafeCallExpr),    // 安全调用: obj?.member 或 obj?.method(args)
+    Elvis(ElvisExpr),          // elvis 运算符: value ?: default
 }
 
 impl HasLocation for Expr {
@@ -293,6 +714,10 @@ impl HasLocation for Expr {
             Expr::InstanceOf(instance) => &instance.loc,
             Expr::Alloc(alloc) => &alloc.loc,
             Expr::Dealloc(dealloc) => &dealloc.loc,
+            Expr::Try(try_expr) => &try_expr.loc,
+            Expr::Switch(switch) => &switch.loc,
+            Expr::SafeCall(safe_call) => &safe_call.loc,
+            Expr::Elvis(elvis) => &elvis.loc,
         }
     }
 }
@@ -312,6 +737,15 @@ pub struct DeallocExpr {
     pub loc: SourceLocation,
 }
 
+/// 错误传播表达式: expr?
+///
+/// 操作数为 Err/None 时直接从当前方法返回该值，否则取出 Ok/Some 的负载。
+#[derive(Debug, Clone)]
+pub struct TryExpr {
+    pub expr: Box<Expr>,
+    pub loc: SourceLocation,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct IdentifierExpr {
     pub name: String,
@@ -429,7 +863,10 @@ pub struct MemberAccessExpr {
 #[derive(Debug, Clone)]
 pub struct NewExpr {
     pub class_name: String,
+    pub type_args: Vec<Type>,  // 泛型类型实参: new Box<int>(...)
     pub args: Vec<Expr>,
+    /// 匿名类体: new Runnable() { ... }（解析结束时提升为具名类 Outer$1）
+    pub body: Option<Vec<ClassMember>>,
     pub loc: SourceLocation,
 }
 
@@ -522,15 +959,104 @@ pub struct TernaryExpr {
     pub loc: SourceLocation,
 }
 
-/// instanceof 表达式: obj instanceof Type
+/// 安全调用表达式: obj?.member 或 obj?.method(args)
+///
+/// 对象为 null 时整个表达式的值为 null，否则访问成员或调用方法。对象只求值一次，
+/// 结果绑定到 `receiver_name()` 命名的临时变量上，再按 `access_expr()` 访问成员。
+#[derive(Debug, Clone)]
+pub struct SafeCallExpr {
+    pub object: Box<Expr>,
+    pub member: String,
+    pub args: Option<Vec<Expr>>,  // None 表示字段访问
+    pub loc: SourceLocation,
+}
+
+impl SafeCallExpr {
+    /// 保存对象值的临时变量名
+    pub fn receiver_name(&self) -> String {
+        format!("__safe_call_{}_{}", self.loc.line, self.loc.column)
+    }
+
+    /// 对象非 null 时执行的普通成员访问或方法调用
+    pub fn access_expr(&self) -> Expr {
+        let receiver = Expr::Identifier(IdentifierExpr {
+            name: self.receiver_name(),
+            loc: self.loc.clone(),
+        });
+        let member = Expr::MemberAccess(MemberAccessExpr {
+            object: Box::new(receiver),
+            member: self.member.clone(),
+            loc: self.loc.clone(),
+        });
+        match &self.args {
+            Some(args) => Expr::Call(CallExpr {
+                callee: Box::new(member),
+                args: args.clone(),
+                loc: self.loc.clone(),
+            }),
+            None => member,
+        }
+    }
+}
+
+/// elvis 表达式: value ?: default
+///
+/// value 不为 null 时取 value，否则求值并取 default。
+#[derive(Debug, Clone)]
+pub struct ElvisExpr {
+    pub value: Box<Expr>,
+    pub default: Box<Expr>,
+    pub loc: SourceLocation,
+}
+
+/// instanceof 表达式: obj instanceof Type，或带模式变量的 obj instanceof Type name
 #[derive(Debug, Clone)]
 pub struct InstanceOfExpr {
     pub expr: Box<Expr>,
     pub target_type: crate::types::Type,
+    /// 匹配成功时绑定的变量，只在条件成立的代码中可见
+    pub binding: Option<String>,
     pub loc: SourceLocation,
 }
 
+impl Expr {
+    /// 条件为真、为假时分别引入的模式变量
+    ///
+    /// `o instanceof Circle c` 为真时引入 c；`!` 交换两者；`a && b` 为真时两侧的变量都已绑定，
+    /// `a || b` 为假时同理。
+    pub fn pattern_bindings(&self) -> (Vec<&InstanceOfExpr>, Vec<&InstanceOfExpr>) {
+        match self {
+            Expr::InstanceOf(instanceof) if instanceof.binding.is_some() => (vec![instanceof], Vec::new()),
+            Expr::Unary(unary) if unary.op == UnaryOp::Not => {
+                let (when_true, when_false) = unary.operand.pattern_bindings();
+                (when_false, when_true)
+            }
+            Expr::Binary(bin) if bin.op == BinaryOp::And => {
+                let (mut when_true, _) = bin.left.pattern_bindings();
+                when_true.extend(bin.right.pattern_bindings().0);
+                (when_true, Vec::new())
+            }
+            Expr::Binary(bin) if bin.op == BinaryOp::Or => {
+                let (_, mut when_false) = bin.left.pattern_bindings();
+                when_false.extend(bin.right.pattern_bindings().1);
+                (Vec::new(), when_false)
+            }
+            _ => (Vec::new(), Vec::new()),
+        }
+    }
+}
+
 impl Program {
+    /// 带有指定注解的方法（如 @Test），按类和声明顺序排列
+    pub fn annotated_methods(&self, annotation: &str) -> Vec<(&ClassDecl, &MethodDecl)> {
+        self.classes.iter()
+            .flat_map(|class| class.members.iter().filter_map(move |member| match member {
+                ClassMember::Method(method) if method.has_annotation(annotation) => Some((class, method)),
+                _ => None,
+            }))
+            .collect()
+    }
+
     pub fn find_main_class(&self) -> Option<&ClassDecl> {
         self.classes.iter().find(|c| {
             c.members.iter().any(|m| {
@@ -553,9 +1079,14 @@ impl Default for Program {
         Self {
             classes: Vec::new(),
             interfaces: Vec::new(),
+            enums: Vec::new(),
+            structs: Vec::new(),
             top_level_functions: Vec::new(),
             extern_declarations: Vec::new(),
             type_aliases: Vec::new(),
+            package: None,
+            imports: Vec::new(),
+            declaration_packages: std::collections::HashMap::new(),
         }
     }
 }
@@ -2,6 +2,7 @@ use std::env;
 use std::process;
 
 use anyhow::{Result, Context};
+use cavvy::error_format::ErrorFormat;
 
 // Cavly 版本 - 与 Cavvy 版本保持一致
 const VERSION: &str = env!("CAVLY_VERSION");
@@ -18,6 +19,7 @@ fn print_usage() {
     println!("  -v, --verbose     显示详细输出");
     println!("  -V, --version     显示版本号");
     println!("  -h, --help        显示帮助信息");
+    println!("  --error-format=<格式>  build 的诊断输出格式: human/short/json/sarif");
     println!();
     println!("命令:");
     println!("  init [名称]       初始化新可执行项目");
@@ -58,7 +60,7 @@ fn main() {
     
     let result = match command.as_str() {
         "init" => cmd_init(&args),
-        "build" => cmd_build(verbose),
+        "build" => parse_error_format(&args).and_then(|format| cmd_build(verbose, format)),
         "clean" => cmd_clean(verbose),
         "run" => cmd_run(verbose),
         "info" => cmd_info(),
@@ -114,15 +116,33 @@ fn cmd_init(args: &[String]) -> Result<()> {
     Ok(())
 }
 
+/// 解析 build 命令的 --error-format 选项
+fn parse_error_format(args: &[String]) -> Result<ErrorFormat> {
+    let mut format = ErrorFormat::Human;
+    let mut i = 2;
+    while i < args.len() {
+        if let Some(parsed) = ErrorFormat::from_args(args, &mut i) {
+            format = parsed.map_err(|e| anyhow::anyhow!(e))?;
+        }
+        i += 1;
+    }
+    Ok(format)
+}
+
 /// 构建项目
 /// 
+/// json 和 sarif 格式下标准输出只有 cayc 生成的诊断文档。
+/// 
 /// # 复杂度
 /// - 时间: O(n + m)，n 为源码大小，m 为链接复杂度
 /// - 空间: O(n)
-fn cmd_build(verbose: bool) -> Result<()> {
-    println!("Cavvy 包管理器 {}", VERSION);
-    println!("版权所有 (c) 2026, Ethernos Studio");
-    println!("使用 GNU 通用公共许可证 版本三 协议开源");
+fn cmd_build(verbose: bool, error_format: ErrorFormat) -> Result<()> {
+    let quiet = error_format.is_machine_readable();
+    if !quiet {
+        println!("Cavvy 包管理器 {}", VERSION);
+        println!("版权所有 (c) 2026, Ethernos Studio");
+        println!("使用 GNU 通用公共许可证 版本三 协议开源");
+    }
 
     let current_dir = env::current_dir()?;
     
@@ -157,11 +177,14 @@ fn cmd_build(verbose: bool) -> Result<()> {
     // 构建（使用 with_dependencies 自动解析和构建依赖）
     let mut builder = cavvy::cavly::builder::Builder::with_dependencies(
             project_root.clone(), config)?
-        .verbose(verbose);
+        .verbose(verbose)
+        .error_format(error_format);
     
     let output_path = builder.build()?;
     
-    println!("构建成功: {}", output_path.display());
+    if !quiet {
+        println!("构建成功: {}", output_path.display());
+    }
     
     Ok(())
 }
@@ -196,7 +219,7 @@ fn cmd_clean(verbose: bool) -> Result<()> {
 /// - 空间: O(n)
 fn cmd_run(verbose: bool) -> Result<()> {
     // 先构建
-    cmd_build(verbose)?;
+    cmd_build(verbose, ErrorFormat::Human)?;
     
     let current_dir = env::current_dir()?;
     let project_root = cavvy::cavly::find_project_root(&current_dir)
@@ -113,8 +113,8 @@ fn compile_to_bytecode(source: &str, source_path: &str) -> Result<BytecodeModule
     let tokens = cavvy::lexer::lex(source)
         .map_err(|e| format!("词法分析错误: {:?}", e))?;
 
-    // 2. 语法分析
-    let ast = cavvy::parser::parse(tokens)
+    // 2. 语法分析，并加载导入的模块
+    let ast = cavvy::modules::parse_program(tokens, source.to_string(), source_path, cavvy::modules::caylibs_paths())
         .map_err(|e| format!("语法分析错误: {:?}", e))?;
 
     // 3. 语义分析
@@ -2,57 +2,104 @@ use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process;
-use cavvy::error::{print_error_with_context, cayError, get_error_location, get_error_message, get_error_help};
+use cavvy::error::{cayError, print_error_with_context, print_warning_with_location};
+use cavvy::diagnostic::{Diagnostic, DiagnosticCollector, Severity};
+use cavvy::error_format::{self, ErrorFormat};
 use cavvy::lexer;
 use cavvy::parser;
+use cavvy::modules;
 use cavvy::preprocessor;
 use cavvy::semantic;
 
-/// 使用源映射打印错误信息
-fn print_error_with_source_map(
-    error: &cayError,
+/// 输出进度信息；使用 json/sarif 错误格式时标准输出只写诊断文档
+macro_rules! progress {
+    ($quiet:expr) => {
+        if !$quiet {
+            println!();
+        }
+    };
+    ($quiet:expr, $($arg:tt)*) => {
+        if !$quiet {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// 按 --error-format 输出预处理或词法分析阶段的单个错误
+fn report_error(format: ErrorFormat, error: &cayError, source: &str, source_path: &str) {
+    match format {
+        ErrorFormat::Human => print_error_with_context(error, source, source_path),
+        _ => error_format::emit(format, "cay-check", source_path, &[Diagnostic::from_error(error)]),
+    }
+}
+
+/// 按 --error-format 输出收集到的诊断；机器可读格式即使没有诊断也输出一个文档
+fn report_diagnostics(
+    format: ErrorFormat,
+    diagnostics: &DiagnosticCollector,
     processed_source: &str,
     source_path: &str,
-    source_map: &std::collections::HashMap<usize, (String, usize)>,
+    source_map: Option<&std::collections::HashMap<usize, (String, usize)>>,
 ) {
-    // 尝试获取错误位置
-    if let Some((line, column)) = get_error_location(error) {
-        if line > 0 {
-            // 查找源映射获取原始文件和行号
-            let (orig_file, orig_line) = if let Some((file, orig_ln)) = source_map.get(&line) {
-                (file.as_str(), *orig_ln)
-            } else {
-                (source_path, line)
-            };
-            
-            // 尝试读取原始源文件
-            let (source_to_use, filename_to_use, line_to_use) = 
-                if let Ok(file_content) = fs::read_to_string(orig_file) {
-                    (file_content, orig_file, orig_line)
-                } else {
-                    (processed_source.to_string(), source_path, line)
-                };
-            
-            print_error_with_location_fixed(error, &source_to_use, filename_to_use, line_to_use, column);
-            return;
+    match format {
+        ErrorFormat::Human => print_diagnostics(diagnostics, processed_source, source_path, source_map),
+        // 词法单元已带有原始文件中的行号，诊断位置不需要再经过源映射
+        _ => error_format::emit(format, "cay-check", source_path, diagnostics.diagnostics()),
+    }
+}
+
+/// 打印一条诊断信息，通过源映射定位到原始文件和行号
+fn print_diagnostic(
+    diagnostic: &Diagnostic,
+    processed_source: &str,
+    source_path: &str,
+    source_map: Option<&std::collections::HashMap<usize, (String, usize)>>,
+) {
+    let line = diagnostic.location.line;
+    let (file, line) = match source_map.and_then(|map| map.get(&line)) {
+        Some((file, orig_line)) => (file.clone(), *orig_line),
+        None => (diagnostic.file.clone().unwrap_or_else(|| source_path.to_string()), line),
+    };
+    // 源映射中的原始文件读取失败时使用预处理后的源码
+    let (source, file, line) = match fs::read_to_string(&file) {
+        Ok(content) => (content, file, line),
+        Err(_) => (processed_source.to_string(), source_path.to_string(), diagnostic.location.line),
+    };
+    let message = format!("[{}] {}", diagnostic.code, diagnostic.message);
+    print_error_with_location_fixed(&message, diagnostic.details.as_deref(), &source, &file, line, diagnostic.location.column);
+}
+
+/// 打印收集到的所有错误和警告，以及错误数量
+fn print_diagnostics(
+    diagnostics: &DiagnosticCollector,
+    processed_source: &str,
+    source_path: &str,
+    source_map: Option<&std::collections::HashMap<usize, (String, usize)>>,
+) {
+    for diagnostic in diagnostics.diagnostics() {
+        if diagnostic.severity == Severity::Warning {
+            let file = diagnostic.file.as_deref().unwrap_or(source_path);
+            print_warning_with_location(&diagnostic.message, file, diagnostic.location.line, diagnostic.location.column);
+        } else {
+            print_diagnostic(diagnostic, processed_source, source_path, source_map);
         }
     }
-    
-    // 没有位置信息的错误，使用默认方式
-    print_error_with_context(error, processed_source, source_path);
+    if diagnostics.has_errors() {
+        let limit = if diagnostics.is_max_errors_reached() { "（已达到错误数量上限，其余错误未显示）" } else { "" };
+        eprintln!("发现 {} 个错误{}", diagnostics.error_count(), limit);
+    }
 }
 
 /// 打印带有位置信息的错误（修复版）
 fn print_error_with_location_fixed(
-    error: &cayError,
+    message: &str,
+    help: Option<&str>,
     source: &str,
     filename: &str,
     line: usize,
     column: usize,
 ) {
-    let message = get_error_message(error);
-    let help = get_error_help(error);
-    
+
     // 使用 miette 风格格式
     eprintln!("\n  × {}", message);
     eprintln!("   ╭─[{}:{}:{}]", filename, line, column);
@@ -121,6 +168,8 @@ fn print_usage() {
     println!("  --lex-only            只进行词法分析");
     println!("  --parse-only          进行词法和语法分析（不进行语义分析）");
     println!("  --no-preprocess       跳过预处理阶段");
+    println!("  --max-errors <N>      最多报告 N 个错误（默认 100）");
+    println!("  --error-format=<fmt>  诊断输出格式: human (默认), short, json, sarif");
     println!("  --version, -v         显示版本号");
     println!("  --help, -h            显示帮助信息");
     println!("");
@@ -146,6 +195,8 @@ impl Default for CheckLevel {
 struct CheckOptions {
     level: CheckLevel,
     preprocess: bool,
+    max_errors: usize,
+    error_format: ErrorFormat,
 }
 
 impl Default for CheckOptions {
@@ -153,6 +204,8 @@ impl Default for CheckOptions {
         CheckOptions {
             level: CheckLevel::default(),
             preprocess: true,
+            max_errors: 100,
+            error_format: ErrorFormat::Human,
         }
     }
 }
@@ -183,6 +236,19 @@ fn parse_args(args: &[String]) -> Result<(CheckOptions, String), String> {
             "--no-preprocess" => {
                 options.preprocess = false;
             }
+            "--max-errors" => {
+                i += 1;
+                options.max_errors = match args.get(i).and_then(|value| value.parse().ok()) {
+                    Some(max) if max > 0 => max,
+                    _ => return Err("--max-errors 需要一个正整数".to_string()),
+                };
+            }
+            _ if arg.starts_with("--error-format") => {
+                options.error_format = match ErrorFormat::from_args(args, &mut i) {
+                    Some(format) => format?,
+                    None => return Err(format!("未知选项: {}", arg)),
+                };
+            }
             _ => {
                 if arg.starts_with('-') {
                     return Err(format!("未知选项: {}", arg));
@@ -214,15 +280,17 @@ fn main() {
         }
     };
 
-    println!("Cavvy Check v{}", VERSION);
-    println!("检查文件: {}", source_path);
-    println!("检查级别: {}", match options.level {
+    let format = options.error_format;
+    let quiet = format.is_machine_readable();
+    progress!(quiet, "Cavvy Check v{}", VERSION);
+    progress!(quiet, "检查文件: {}", source_path);
+    progress!(quiet, "检查级别: {}", match options.level {
         CheckLevel::LexOnly => "词法分析",
         CheckLevel::ParseOnly => "语法分析",
         CheckLevel::Full => "完整检查（预处理+词法+语法+语义）",
     });
-    println!("预处理: {}", if options.preprocess { "启用" } else { "跳过" });
-    println!("");
+    progress!(quiet, "预处理: {}", if options.preprocess { "启用" } else { "跳过" });
+    progress!(quiet);
 
     let source = match fs::read_to_string(&source_path) {
         Ok(content) => content,
@@ -236,7 +304,7 @@ fn main() {
 
     // 预处理阶段
     let (processed_source, source_map) = if options.preprocess {
-        println!("[0] 预处理...");
+        progress!(quiet, "[0] 预处理...");
         let base_dir = Path::new(&source_path)
             .parent()
             .map(|p| p.to_path_buf())
@@ -255,7 +323,7 @@ fn main() {
         
         match pp.process_with_source_map(&source, &source_path) {
             Ok(result) => {
-                println!("  [+] 预处理通过");
+                progress!(quiet, "  [+] 预处理通过");
                 // 转换源映射格式
                 let mut map = std::collections::HashMap::new();
                 for (idx, pos) in result.source_map.mappings.iter().enumerate() {
@@ -265,7 +333,7 @@ fn main() {
                 (result.code, Some(map))
             }
             Err(e) => {
-                print_error_with_context(&e, &source, &source_path);
+                report_error(format, &e, &source, &source_path);
                 process::exit(1);
             }
         }
@@ -276,9 +344,9 @@ fn main() {
     match options.level {
         CheckLevel::LexOnly => {
             if options.preprocess {
-                println!("");
+                progress!(quiet);
             }
-            println!("[1] 词法分析...");
+            progress!(quiet, "[1] 词法分析...");
             let lex_result = if let Some(ref map) = source_map {
                 lexer::lex_with_source_map(&processed_source, map.clone())
             } else {
@@ -287,22 +355,23 @@ fn main() {
             match lex_result {
                 Ok(tokens) => {
                     let elapsed = start_time.elapsed();
-                    println!("  [+] 词法分析通过");
-                    println!("      发现 {} 个 token", tokens.len());
-                    println!("");
-                    println!("[+] 语法检查完成! (耗时: {:?})", elapsed);
+                    report_diagnostics(format, &DiagnosticCollector::new(), &processed_source, &source_path, source_map.as_ref());
+                    progress!(quiet, "  [+] 词法分析通过");
+                    progress!(quiet, "      发现 {} 个 token", tokens.len());
+                    progress!(quiet);
+                    progress!(quiet, "[+] 语法检查完成! (耗时: {:?})", elapsed);
                 }
                 Err(e) => {
-                    print_error_with_context(&e, &processed_source, &source_path);
+                    report_error(format, &e, &processed_source, &source_path);
                     process::exit(1);
                 }
             }
         }
         CheckLevel::ParseOnly => {
             if options.preprocess {
-                println!("");
+                progress!(quiet);
             }
-            println!("[1] 词法分析...");
+            progress!(quiet, "[1] 词法分析...");
             let lex_result = if let Some(ref map) = source_map {
                 lexer::lex_with_source_map(&processed_source, map.clone())
             } else {
@@ -310,36 +379,38 @@ fn main() {
             };
             let tokens = match lex_result {
                 Ok(tokens) => {
-                    println!("  [+] 词法分析通过");
+                    progress!(quiet, "  [+] 词法分析通过");
                     tokens
                 }
                 Err(e) => {
-                    print_error_with_context(&e, &processed_source, &source_path);
+                    report_error(format, &e, &processed_source, &source_path);
                     process::exit(1);
                 }
             };
 
-            println!("");
-            println!("[2] 语法分析...");
-            match parser::parse(tokens) {
-                Ok(ast) => {
+            progress!(quiet);
+            progress!(quiet, "[2] 语法分析...");
+            let mut diagnostics = DiagnosticCollector::new().with_max_errors(options.max_errors);
+            match parser::parse_recovering(tokens, processed_source.clone(), &[], &mut diagnostics) {
+                Some(ast) if !diagnostics.has_errors() => {
                     let elapsed = start_time.elapsed();
-                    println!("  [+] 语法分析通过");
-                    println!("      发现 {} 个类定义", ast.classes.len());
-                    println!("");
-                    println!("[+] 语法检查完成! (耗时: {:?})", elapsed);
+                    report_diagnostics(format, &diagnostics, &processed_source, &source_path, source_map.as_ref());
+                    progress!(quiet, "  [+] 语法分析通过");
+                    progress!(quiet, "      发现 {} 个类定义", ast.classes.len());
+                    progress!(quiet);
+                    progress!(quiet, "[+] 语法检查完成! (耗时: {:?})", elapsed);
                 }
-                Err(e) => {
-                    print_error_with_context(&e, &processed_source, &source_path);
+                _ => {
+                    report_diagnostics(format, &diagnostics, &processed_source, &source_path, source_map.as_ref());
                     process::exit(1);
                 }
             }
         }
         CheckLevel::Full => {
             if options.preprocess {
-                println!("");
+                progress!(quiet);
             }
-            println!("[1] 词法分析...");
+            progress!(quiet, "[1] 词法分析...");
             let lex_result = if let Some(ref map) = source_map {
                 lexer::lex_with_source_map(&processed_source, map.clone())
             } else {
@@ -347,48 +418,42 @@ fn main() {
             };
             let tokens = match lex_result {
                 Ok(tokens) => {
-                    println!("  [+] 词法分析通过");
+                    progress!(quiet, "  [+] 词法分析通过");
                     tokens
                 }
                 Err(e) => {
-                    print_error_with_context(&e, &processed_source, &source_path);
+                    report_error(format, &e, &processed_source, &source_path);
                     process::exit(1);
                 }
             };
 
-            println!("");
-            println!("[2] 语法分析...");
-            let ast = match parser::parse(tokens) {
-                Ok(ast) => {
-                    println!("  [+] 语法分析通过");
+            progress!(quiet);
+            progress!(quiet, "[2] 语法分析...");
+            // 语法错误后继续解析，报告所有语法错误；有语法错误时不做语义分析
+            let mut diagnostics = DiagnosticCollector::new().with_max_errors(options.max_errors);
+            let ast = match modules::parse_program_recovering(tokens, processed_source.clone(), &source_path, get_system_include_paths(), &mut diagnostics) {
+                Some(ast) if !diagnostics.has_errors() => {
+                    progress!(quiet, "  [+] 语法分析通过");
                     ast
                 }
-                Err(e) => {
-                    print_error_with_context(&e, &processed_source, &source_path);
+                _ => {
+                    report_diagnostics(format, &diagnostics, &processed_source, &source_path, source_map.as_ref());
                     process::exit(1);
                 }
             };
 
-            println!("");
-            println!("[3] 语义分析...");
+            progress!(quiet);
+            progress!(quiet, "[3] 语义分析...");
             let mut analyzer = semantic::SemanticAnalyzer::new();
-            match analyzer.analyze(&ast) {
-                Ok(_) => {
-                    let elapsed = start_time.elapsed();
-                    println!("  [+] 语义分析通过");
-                    println!("");
-                    println!("[+] 语法检查完成! (耗时: {:?})", elapsed);
-                }
-                Err(e) => {
-                    // 使用源映射报告错误
-                    if let Some(ref map) = source_map {
-                        print_error_with_source_map(&e, &processed_source, &source_path, map);
-                    } else {
-                        print_error_with_context(&e, &processed_source, &source_path);
-                    }
-                    process::exit(1);
-                }
+            analyzer.analyze_collecting(&ast, &mut diagnostics);
+            report_diagnostics(format, &diagnostics, &processed_source, &source_path, source_map.as_ref());
+            if diagnostics.has_errors() {
+                process::exit(1);
             }
+            let elapsed = start_time.elapsed();
+            progress!(quiet, "  [+] 语义分析通过");
+            progress!(quiet);
+            progress!(quiet, "[+] 语法检查完成! (耗时: {:?})", elapsed);
         }
     }
 }
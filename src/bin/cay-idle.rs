@@ -123,7 +123,7 @@ fn run_cli_mode(input_file: Option<String>) {
         
         // 使用编译器API进行语法检查
         use cavvy::lexer;
-        use cavvy::parser;
+        use cavvy::modules;
         use cavvy::semantic;
         
         // 词法分析
@@ -131,8 +131,8 @@ fn run_cli_mode(input_file: Option<String>) {
             Ok(tokens) => {
                 println!("✓ 词法分析通过 ({} 个token)", tokens.len());
                 
-                // 语法分析
-                match parser::parse(tokens) {
+                // 语法分析，并加载导入的模块
+                match modules::parse_program(tokens, content.clone(), &file, modules::caylibs_paths()) {
                     Ok(_ast) => {
                         println!("✓ 语法分析通过");
                         
@@ -262,7 +262,7 @@ fn main() {
         defines: options.defines,
         undefines: options.undefines,
         obfuscate: options.obfuscate,
-        include_paths: Vec::new(),
+        include_paths: options.include_paths,
     };
 
     // 编译 Cavvy → IR
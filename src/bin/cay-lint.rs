@@ -0,0 +1,255 @@
+// cay-lint: Cavvy 代码检查工具
+// 语义分析通过后运行 lint 规则，规则级别来自 cavly.toml 的 [lints] 表和命令行选项
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use cavvy::cavly::CONFIG_FILE;
+use cavvy::cavly::config::CavlyConfig;
+use cavvy::diagnostic::DiagnosticCollector;
+use cavvy::error::print_error_with_context;
+use cavvy::lint::{LintConfig, LintDiagnostic, LintLevel, Linter, Suppressions, LINT_RULES};
+use cavvy::{lexer, modules, preprocessor, semantic};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+fn print_usage(program: &str) {
+    eprintln!("Cavvy Lint v{}", VERSION);
+    eprintln!("用法: {} [选项] <源文件.cay>", program);
+    eprintln!();
+    eprintln!("选项:");
+    eprintln!("  -A, --allow <规则>    关闭规则");
+    eprintln!("  -W, --warn <规则>     规则报告为警告");
+    eprintln!("  -D, --deny <规则>     规则报告为错误（有错误时以状态 1 退出）");
+    eprintln!("  --config <文件>       使用指定的 cavly.toml（默认从源文件所在目录向上查找）");
+    eprintln!("  --list                列出所有规则及其级别");
+    eprintln!("  --no-preprocess       跳过预处理阶段");
+    eprintln!("  -h, --help            显示帮助信息");
+    eprintln!("  -v, --version         显示版本信息");
+    eprintln!();
+    eprintln!("在源码中用 // cay-lint: allow(规则, ...) 关闭所在行或下一行代码的规则");
+}
+
+#[derive(Debug, Default)]
+struct Options {
+    file_path: Option<String>,
+    config_path: Option<PathBuf>,
+    /// 命令行指定的级别，按出现顺序覆盖配置文件
+    levels: Vec<(String, LintLevel)>,
+    list: bool,
+    no_preprocess: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut i = 1;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        let level = match arg {
+            "-A" | "--allow" => Some(LintLevel::Allow),
+            "-W" | "--warn" => Some(LintLevel::Warn),
+            "-D" | "--deny" => Some(LintLevel::Deny),
+            _ => None,
+        };
+        match (arg, level) {
+            (_, Some(level)) => {
+                i += 1;
+                let rule = args.get(i).ok_or_else(|| format!("{} 需要一个规则名", arg))?;
+                options.levels.push((rule.clone(), level));
+            }
+            ("--config", None) => {
+                i += 1;
+                let path = args.get(i).ok_or("--config 需要一个文件路径")?;
+                options.config_path = Some(PathBuf::from(path));
+            }
+            ("--list", None) => options.list = true,
+            ("--no-preprocess", None) => options.no_preprocess = true,
+            _ if arg.starts_with('-') => return Err(format!("未知选项: {}", arg)),
+            _ => {
+                if options.file_path.is_some() {
+                    return Err("只能指定一个源文件".to_string());
+                }
+                options.file_path = Some(arg.to_string());
+            }
+        }
+        i += 1;
+    }
+    Ok(options)
+}
+
+/// 从源文件所在目录向上查找 cavly.toml
+fn find_config(source_path: &Path) -> Option<PathBuf> {
+    let dir = source_path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    dir.ancestors().map(|ancestor| ancestor.join(CONFIG_FILE)).find(|path| path.is_file())
+}
+
+/// 合并配置文件和命令行中的规则级别；未知规则只给出警告
+fn load_lint_config(options: &Options, source_path: Option<&Path>) -> Result<LintConfig, String> {
+    let mut config = LintConfig::new();
+    let config_path = options.config_path.clone().or_else(|| source_path.and_then(find_config));
+    if let Some(path) = config_path {
+        let cavly_config = CavlyConfig::from_file(&path).map_err(|e| format!("{:#}", e))?;
+        let mut levels: Vec<_> = cavly_config.lints.into_iter().collect();
+        levels.sort();
+        for (rule, level) in levels {
+            if let Err(e) = config.set_level(&rule, level) {
+                eprintln!("警告: {} ({})", e, path.display());
+            }
+        }
+    }
+    for (rule, level) in &options.levels {
+        if let Err(e) = config.set_level(rule, *level) {
+            eprintln!("警告: {}", e);
+        }
+    }
+    Ok(config)
+}
+
+fn print_rules(config: &LintConfig) {
+    println!("Cavvy Lint v{} 规则:", VERSION);
+    let width = LINT_RULES.iter().map(|rule| rule.name.len()).max().unwrap_or(0);
+    for rule in LINT_RULES {
+        println!("  {:width$}  {:5}  {}", rule.name, config.level(rule.name).to_string(), rule.description, width = width);
+    }
+}
+
+fn print_lint(diagnostic: &LintDiagnostic, default_file: &str) {
+    let file = diagnostic.loc.file.as_deref().unwrap_or(default_file);
+    match diagnostic.level {
+        LintLevel::Deny => eprintln!("  × error[{}]: {}", diagnostic.rule, diagnostic.message),
+        _ => eprintln!("  ⚠ warning[{}]: {}", diagnostic.rule, diagnostic.message),
+    }
+    eprintln!("     位置: {}:{}:{}", file, diagnostic.loc.line, diagnostic.loc.column);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let program = args[0].clone();
+
+    if args.iter().skip(1).any(|arg| arg == "-h" || arg == "--help") {
+        print_usage(&program);
+        process::exit(0);
+    }
+    if args.iter().skip(1).any(|arg| arg == "-v" || arg == "--version") {
+        println!("cay-lint v{}", VERSION);
+        process::exit(0);
+    }
+
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            print_usage(&program);
+            process::exit(1);
+        }
+    };
+
+    let lint_config = match load_lint_config(&options, options.file_path.as_deref().map(Path::new)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            process::exit(1);
+        }
+    };
+    if options.list {
+        print_rules(&lint_config);
+        process::exit(0);
+    }
+
+    let source_path = match &options.file_path {
+        Some(path) => path.clone(),
+        None => {
+            eprintln!("错误: 未指定源文件");
+            print_usage(&program);
+            process::exit(1);
+        }
+    };
+    let source = match fs::read_to_string(&source_path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("错误: 无法读取文件 '{}': {}", source_path, e);
+            process::exit(1);
+        }
+    };
+
+    // 预处理（带源映射，诊断位置指向原始文件）
+    let (processed_source, source_map) = if options.no_preprocess {
+        (source.clone(), HashMap::new())
+    } else {
+        let base_dir = Path::new(&source_path).parent().map(|p| p.to_path_buf()).unwrap_or_else(|| PathBuf::from("."));
+        let mut pp = preprocessor::Preprocessor::with_include_paths(base_dir, modules::caylibs_paths());
+        match pp.process_with_source_map(&source, &source_path) {
+            Ok(result) => (result.code, result.source_map.to_line_map()),
+            Err(e) => {
+                print_error_with_context(&e, &source, &source_path);
+                process::exit(1);
+            }
+        }
+    };
+
+    let tokens = match lexer::lex_with_source_map(&processed_source, source_map.clone()) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            print_error_with_context(&e, &processed_source, &source_path);
+            process::exit(1);
+        }
+    };
+
+    // 有语法或语义错误时不做检查
+    let mut diagnostics = DiagnosticCollector::new();
+    let ast = modules::parse_program_recovering(tokens, processed_source.clone(), &source_path, modules::caylibs_paths(), &mut diagnostics);
+    let mut analyzer = semantic::SemanticAnalyzer::new();
+    match &ast {
+        Some(ast) if !diagnostics.has_errors() => analyzer.analyze_collecting(ast, &mut diagnostics),
+        _ => {}
+    }
+    let ast = match ast {
+        Some(ast) if !diagnostics.has_errors() => ast,
+        _ => {
+            for diagnostic in diagnostics.diagnostics().iter().filter(|d| d.severity >= cavvy::diagnostic::Severity::Error) {
+                let file = diagnostic.file.as_deref().unwrap_or(&source_path);
+                eprintln!("  × [{}] {}", diagnostic.code, diagnostic.message);
+                eprintln!("     位置: {}:{}:{}", file, diagnostic.location.line, diagnostic.location.column);
+            }
+            eprintln!("发现 {} 个错误，未进行代码检查", diagnostics.error_count());
+            process::exit(1);
+        }
+    };
+
+    // 只报告主文件及其 #include 的项目文件，不报告 import 的模块和 caylibs 中的标准库
+    let library_dirs = modules::caylibs_paths();
+    let own_files: HashSet<&str> = source_map.values().map(|(file, _)| file.as_str())
+        .filter(|file| !library_dirs.iter().any(|dir| Path::new(file).starts_with(dir)))
+        .chain(std::iter::once(source_path.as_str()))
+        .collect();
+    let mut suppressions: HashMap<String, Suppressions> = HashMap::new();
+    let lints: Vec<LintDiagnostic> = Linter::new(analyzer.get_type_registry(), &lint_config).lint(&ast)
+        .into_iter()
+        .filter(|lint| lint.loc.file.as_deref().is_none_or(|file| own_files.contains(file)))
+        .filter(|lint| {
+            let file = lint.loc.file.clone().unwrap_or_else(|| source_path.clone());
+            let file_suppressions = suppressions.entry(file.clone()).or_insert_with(|| {
+                let text = if file == source_path { source.clone() } else { fs::read_to_string(&file).unwrap_or_default() };
+                Suppressions::parse(&text)
+            });
+            !file_suppressions.is_allowed(lint.rule, lint.loc.line)
+        })
+        .collect();
+
+    for lint in &lints {
+        print_lint(lint, &source_path);
+    }
+    let errors = lints.iter().filter(|lint| lint.level == LintLevel::Deny).count();
+    let warnings = lints.len() - errors;
+    if lints.is_empty() {
+        println!("[+] {}: 未发现问题", source_path);
+    } else {
+        eprintln!("{}: {} 个警告，{} 个错误", source_path, warnings, errors);
+    }
+    if errors > 0 {
+        process::exit(1);
+    }
+}
@@ -8,8 +8,9 @@ use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 
+use cavvy::diagnostic::{DiagnosticCollector, Severity};
 use cavvy::lexer;
-use cavvy::parser;
+use cavvy::modules;
 use cavvy::preprocessor;
 use cavvy::semantic;
 
@@ -263,12 +264,23 @@ impl LanguageServer for CavvyLanguageServer {
                     ("static", "静态修饰符"),
                     ("final", "最终修饰符"),
                     ("abstract", "抽象修饰符"),
+                    ("sealed", "密封修饰符"),
+                    ("permits", "列出密封类型允许的子类"),
                     ("extends", "继承"),
                     ("implements", "实现接口"),
                     ("interface", "定义接口"),
+                    ("enum", "定义枚举"),
+                    ("struct", "定义结构体"),
+                    ("record", "定义记录类"),
                     ("void", "无返回值类型"),
                     ("int", "整数类型"),
                     ("long", "长整数类型"),
+                    ("byte", "8 位有符号整数类型"),
+                    ("ubyte", "8 位无符号整数类型"),
+                    ("short", "16 位有符号整数类型"),
+                    ("ushort", "16 位无符号整数类型"),
+                    ("uint", "32 位无符号整数类型"),
+                    ("ulong", "64 位无符号整数类型"),
                     ("float", "单精度浮点类型"),
                     ("double", "双精度浮点类型"),
                     ("boolean", "布尔类型"),
@@ -395,25 +407,17 @@ impl CavvyLanguageServer {
             }
         };
 
-        // 3. 语法分析
-        let ast = match parser::parse(tokens) {
-            Ok(ast) => ast,
-            Err(e) => {
-                if let Some(diagnostic) = error_to_diagnostic(&e, &processed_content) {
-                    diagnostics.push(diagnostic);
-                }
-                return diagnostics;
-            }
-        };
+        // 3. 语法分析，并加载文档导入的模块；语法错误后继续解析，报告所有语法错误
+        let mut collector = DiagnosticCollector::new();
+        let ast = modules::parse_program_recovering(tokens, processed_content.clone(), file_path, modules::caylibs_paths(), &mut collector);
 
-        // 4. 语义分析
-        let mut analyzer = semantic::SemanticAnalyzer::new();
-        if let Err(e) = analyzer.analyze(&ast) {
-            if let Some(diagnostic) = error_to_diagnostic(&e, &processed_content) {
-                diagnostics.push(diagnostic);
-            }
+        // 4. 语义分析（有语法错误时跳过）
+        if let Some(ast) = ast.filter(|_| !collector.has_errors()) {
+            let mut analyzer = semantic::SemanticAnalyzer::new();
+            analyzer.analyze_collecting(&ast, &mut collector);
         }
 
+        diagnostics.extend(collector.diagnostics().iter().map(|diagnostic| to_lsp_diagnostic(diagnostic, &processed_content)));
         diagnostics
     }
 
@@ -444,7 +448,7 @@ impl CavvyLanguageServer {
         };
         
         // 语法分析
-        let ast = match parser::parse(tokens) {
+        let ast = match modules::parse_program(tokens, processed.clone(), file_path, modules::caylibs_paths()) {
             Ok(a) => a,
             Err(e) => return Err(format!("语法分析失败: {:?}", e)),
         };
@@ -550,6 +554,33 @@ fn error_to_diagnostic(error: &cavvy::error::cayError, source: &str) -> Option<D
     })
 }
 
+/// 将编译器的诊断信息转换为 LSP 诊断
+fn to_lsp_diagnostic(diagnostic: &cavvy::diagnostic::Diagnostic, source: &str) -> Diagnostic {
+    let line = diagnostic.location.line;
+    let column = diagnostic.location.column;
+    let line_len = source.lines().nth(line.saturating_sub(1)).map(|l| l.len()).unwrap_or(0) as u32;
+    let severity = match diagnostic.severity {
+        Severity::Warning => DiagnosticSeverity::WARNING,
+        Severity::Note => DiagnosticSeverity::INFORMATION,
+        Severity::Error | Severity::Fatal => DiagnosticSeverity::ERROR,
+    };
+
+    Diagnostic {
+        range: Range {
+            start: Position::new(line.saturating_sub(1) as u32, column.saturating_sub(1) as u32),
+            end: Position::new(line.saturating_sub(1) as u32, line_len),
+        },
+        severity: Some(severity),
+        code: Some(NumberOrString::String(diagnostic.code.clone())),
+        code_description: None,
+        source: Some("cavvy".to_string()),
+        message: diagnostic.message.clone(),
+        related_information: None,
+        tags: None,
+        data: None,
+    }
+}
+
 /// 提取类成员符号
 fn extract_class_members(class: &cavvy::ast::ClassDecl) -> Vec<DocumentSymbol> {
     let mut members = Vec::new();
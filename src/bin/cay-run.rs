@@ -5,8 +5,10 @@ use std::process::{self, Command, Stdio};
 use cavvy::Compiler;
 use cavvy::bytecode::{serializer, jit};
 use cavvy::bytecode::obfuscator;
+use cavvy::diagnostic::{Diagnostic, DiagnosticCollector};
 use cavvy::error::{print_error_with_context, print_miette_error, print_tool_error, print_warning};
 use cavvy::error::cayError;
+use cavvy::error_format::{self, ErrorFormat};
 
 const VERSION: &str = "0.4.7";
 
@@ -22,6 +24,7 @@ struct RunOptions {
     lib_paths: Vec<String>,    // -L: 库搜索路径
     optimize: String,          // -O: 优化级别
     features: Vec<String>,     // -F/--feature: 启用的语言特性
+    error_format: ErrorFormat, // --error-format: 诊断输出格式
 }
 
 impl Default for RunOptions {
@@ -37,6 +40,7 @@ impl Default for RunOptions {
             lib_paths: Vec::new(),
             optimize: "-O2".to_string(),
             features: Vec::new(),
+            error_format: ErrorFormat::Human,
         }
     }
 }
@@ -60,6 +64,8 @@ fn print_usage() {
     println!("  -O<level>              优化级别 (0, 1, 2, 3, s, z)");
     println!("  -F<feature>            启用语言特性 (如: -F=top_level_function)");
     println!("  --keep-temp            保留临时文件");
+    println!("  --error-format=<fmt>   诊断输出格式: human (默认), short, json, sarif");
+    println!("                         json/sarif 文档写到标准输出，位于程序输出之前");
     println!("  --verbose, -v          显示详细编译信息");
     println!("  --version, -V          显示版本号");
     println!("  --help, -h             显示帮助信息");
@@ -90,6 +96,11 @@ fn parse_args(args: &[String]) -> Result<(RunOptions, String), String> {
         } else if arg.starts_with("-O") && arg.len() > 1 {
             // -Oxxx 格式
             options.optimize = format!("-O{}", &arg[2..]);
+        } else if arg.starts_with("--error-format") {
+            options.error_format = match ErrorFormat::from_args(args, &mut i) {
+                Some(format) => format?,
+                None => return Err(format!("未知选项: {}", arg)),
+            };
         } else if arg.starts_with("-F") {
             // -F<feature> 或 -F=<feature> 格式
             let feature = if arg.starts_with("-F=") {
@@ -251,7 +262,9 @@ fn get_system_include_paths() -> Vec<PathBuf> {
 }
 
 /// 编译Cay源码为IR
-fn compile_cay_to_ir(source_path: &str, options: &RunOptions) -> Result<String, cayError> {
+///
+/// 使用机器可读的错误格式时，警告收集到 `diagnostics` 中而不是直接打印。
+fn compile_cay_to_ir(source_path: &str, options: &RunOptions, diagnostics: &mut DiagnosticCollector) -> Result<String, cayError> {
     let source = fs::read_to_string(source_path)
         .map_err(|e| cayError::Io(format!("读取源文件失败: {}", e)))?;
 
@@ -274,6 +287,7 @@ fn compile_cay_to_ir(source_path: &str, options: &RunOptions) -> Result<String,
         pp.process_with_source_map(&source, source_path)
     }
     .map_err(|e| cayError::Preprocessor {
+        code: e.code(),
         file: Some(source_path.to_string()),
         line: 0,
         column: 0,
@@ -305,7 +319,11 @@ fn compile_cay_to_ir(source_path: &str, options: &RunOptions) -> Result<String,
 
     // 使用临时文件
     let temp_ir_file = generate_unique_filename("cay", "ll");
-    compiler.compile_with_source_map(&preprocess_result.code, source_map, temp_ir_file.to_str().unwrap())?;
+    let main_file = Some(source_path.to_string());
+    match options.error_format {
+        ErrorFormat::Human => compiler.compile_with_source_map_and_main_file(&preprocess_result.code, source_map, temp_ir_file.to_str().unwrap(), main_file)?,
+        _ => compiler.compile_with_diagnostics(&preprocess_result.code, source_map, temp_ir_file.to_str().unwrap(), main_file, diagnostics)?,
+    }
 
     let ir = fs::read_to_string(&temp_ir_file)
         .map_err(|e| cayError::Io(format!("读取IR文件失败: {}", e)))?;
@@ -326,8 +344,8 @@ fn compile_cay_to_bytecode(source_path: &str, options: &RunOptions) -> Result<ca
     let tokens = cavvy::lexer::lex(&source)
         .map_err(|e| format!("词法分析错误: {:?}", e))?;
 
-    // 语法分析
-    let ast = cavvy::parser::parse(tokens)
+    // 语法分析，并加载导入的模块
+    let ast = cavvy::modules::parse_program(tokens, source.clone(), source_path, get_system_include_paths())
         .map_err(|e| format!("语法分析错误: {:?}", e))?;
 
     // 语义分析
@@ -620,11 +638,22 @@ fn main() {
 
                 ir
             } else {
-                match compile_cay_to_ir(&input_path, &options) {
+                let mut diagnostics = DiagnosticCollector::new();
+                let result = compile_cay_to_ir(&input_path, &options, &mut diagnostics);
+                if options.error_format != ErrorFormat::Human {
+                    // 编译阶段的错误已经逐个加入 diagnostics，这里只补充读取和预处理源文件时的错误
+                    if let (Err(e), false) = (&result, diagnostics.has_errors()) {
+                        diagnostics.add(Diagnostic::from_error(e));
+                    }
+                    error_format::emit(options.error_format, "cay-run", &input_path, diagnostics.diagnostics());
+                }
+                match result {
                     Ok(ir) => ir,
                     Err(e) => {
-                        let source = fs::read_to_string(&input_path).unwrap_or_default();
-                        print_error_with_context(&e, &source, &input_path);
+                        if options.error_format == ErrorFormat::Human {
+                            let source = fs::read_to_string(&input_path).unwrap_or_default();
+                            print_error_with_context(&e, &source, &input_path);
+                        }
                         process::exit(1);
                     }
                 }
@@ -693,7 +722,7 @@ fn main() {
     } else {
         if options.verbose {
             println!("可执行文件已生成: {}", output_exe);
-        } else {
+        } else if !options.error_format.is_machine_readable() {
             println!("已生成: {}", output_exe);
         }
     }
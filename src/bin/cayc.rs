@@ -3,7 +3,24 @@ use std::fs;
 use std::process;
 use std::path::{Path, PathBuf};
 use cavvy::Compiler;
+use cavvy::diagnostic::DiagnosticCollector;
 use cavvy::error::{print_error_with_context, print_miette_error, print_tool_error, print_warning};
+use cavvy::error_format::{self, ErrorFormat};
+use cavvy::explain::Explanation;
+
+/// 输出进度信息；使用 json/sarif 错误格式时标准输出只写诊断文档
+macro_rules! progress {
+    ($quiet:expr) => {
+        if !$quiet {
+            println!();
+        }
+    };
+    ($quiet:expr, $($arg:tt)*) => {
+        if !$quiet {
+            println!($($arg)*);
+        }
+    };
+}
 
 /// 根据平台获取 llvm-minimal 下的 clang 路径
 #[cfg(target_os = "windows")]
@@ -86,6 +103,8 @@ struct CompileOptions {
     fslp_vectorize: bool,         // -fslp-vectorize
     // 语言特性
     features: Vec<String>,        // -F/--feature=<feature>
+    // 诊断输出
+    error_format: ErrorFormat,    // --error-format=human|short|json|sarif
 }
 
 /// 根据当前操作系统自动选择默认目标平台
@@ -144,6 +163,7 @@ impl Default for CompileOptions {
             fvectorize: false,
             fslp_vectorize: false,
             features: Vec::new(),
+            error_format: ErrorFormat::Human,
         }
     }
 }
@@ -151,6 +171,7 @@ impl Default for CompileOptions {
 fn print_usage() {
     println!("Cavvy Compiler v{}", VERSION);
     println!("Usage: cayc [options] <source_file.cay> [output_file.exe]");
+    println!("       cayc --explain <CODE>");
     println!("");
     println!("Optimization Options:");
     println!("  -O0, -O1, -O2, -O3    优化级别 (默认: -O2)");
@@ -190,6 +211,11 @@ fn print_usage() {
     println!("  -F<feature>, --feature=<feature>  启用语言特性");
     println!("                                     top_level_function - 允许顶层函数");
     println!("");
+    println!("Diagnostics:");
+    println!("  --error-format=<fmt>  诊断输出格式: human (默认), short, json, sarif");
+    println!("                        json/sarif 写到标准输出，格式说明见 cavvy::error_format");
+    println!("  --explain <CODE>      显示错误代码的详细解释和示例 (如 cayc --explain E4001)");
+    println!("");
     println!("Other Options:");
     println!("  --version, -v         显示版本号");
     println!("  --help, -h            显示帮助信息");
@@ -202,6 +228,24 @@ fn print_usage() {
     println!("  cayc --static -O2 -L./libs -lmylib app.cay app.exe");
 }
 
+/// 打印错误代码的详细解释后退出
+fn explain(code: &str) -> ! {
+    match Explanation::find(code) {
+        Some(explanation) => {
+            print!("{}", explanation);
+            process::exit(0);
+        }
+        None => {
+            print_miette_error(
+                "cavvy::argument_error",
+                &format!("未知的错误代码 '{}'", code),
+                Some("错误代码形如 E4001，显示在诊断信息的标题中")
+            );
+            process::exit(1);
+        }
+    }
+}
+
 fn parse_args(args: &[String]) -> Result<(CompileOptions, String, String), String> {
     let mut options = CompileOptions::default();
     let mut input_file: Option<String> = None;
@@ -220,6 +264,13 @@ fn parse_args(args: &[String]) -> Result<(CompileOptions, String, String), Strin
                 print_usage();
                 process::exit(0);
             }
+            "--explain" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--explain 需要错误代码参数".to_string());
+                }
+                explain(&args[i]);
+            }
             "-O0" | "-O1" | "-O2" | "-O3" | "-Os" | "-Oz" => {
                 options.optimization = arg.clone();
             }
@@ -382,6 +433,12 @@ fn parse_args(args: &[String]) -> Result<(CompileOptions, String, String), Strin
                 // --feature=<feature> 格式
                 options.features.push(arg[10..].to_string());
             }
+            _ if arg.starts_with("--error-format") => {
+                options.error_format = match ErrorFormat::from_args(args, &mut i) {
+                    Some(format) => format?,
+                    None => return Err(format!("未知选项: {}", arg)),
+                };
+            }
             _ => {
                 if arg.starts_with('-') {
                     return Err(format!("未知选项: {}", arg));
@@ -444,27 +501,8 @@ fn optimize_ir(ir_file: &str, opt_level: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    let (options, source_path, exe_output) = match parse_args(&args) {
-        Ok(result) => result,
-        Err(e) => {
-            print_miette_error(
-                "cavvy::argument_error",
-                &e,
-                Some("请检查命令行参数是否正确")
-            );
-            print_usage();
-            process::exit(1);
-        }
-    };
-
-    let ir_file = Path::new(&exe_output)
-        .with_extension("ll")
-        .to_string_lossy()
-        .to_string();
-
+/// 打印编译器版本和本次编译的选项
+fn print_banner(options: &CompileOptions, source_path: &str, exe_output: &str) {
     println!("Cavvy 编译器 v{}", VERSION);
     println!("源文件: {}", source_path);
     println!("输出: {}", exe_output);
@@ -527,9 +565,36 @@ fn main() {
         println!("链接模式: 静态链接");
     }
     println!("");
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let (options, source_path, exe_output) = match parse_args(&args) {
+        Ok(result) => result,
+        Err(e) => {
+            print_miette_error(
+                "cavvy::argument_error",
+                &e,
+                Some("请检查命令行参数是否正确")
+            );
+            print_usage();
+            process::exit(1);
+        }
+    };
+
+    let ir_file = Path::new(&exe_output)
+        .with_extension("ll")
+        .to_string_lossy()
+        .to_string();
+
+    let quiet = options.error_format.is_machine_readable();
+    if !quiet {
+        print_banner(&options, &source_path, &exe_output);
+    }
 
     // 1. Cavvy → IR
-    println!("[1] Cavvy → IR 编译...");
+    progress!(quiet, "[1] Cavvy → IR 编译...");
     let source = match fs::read_to_string(&source_path) {
         Ok(content) => content,
         Err(e) => {
@@ -553,35 +618,40 @@ fn main() {
         include_paths: options.include_paths.clone(),
     };
     let compiler = cavvy::Compiler::with_options(compiler_options);
-    match compiler.compile_file(&source_path, &ir_file) {
-        Ok(_) => {
-            println!("  [+] Cavvy 编译成功");
-        }
-        Err(e) => {
+    if options.error_format == ErrorFormat::Human {
+        if let Err(e) = compiler.compile_file(&source_path, &ir_file) {
             print_error_with_context(&e, &source, &source_path);
             process::exit(1);
         }
+    } else {
+        let mut diagnostics = DiagnosticCollector::new();
+        let result = compiler.compile_file_with_diagnostics(&source_path, &ir_file, &mut diagnostics);
+        error_format::emit(options.error_format, "cayc", &source_path, diagnostics.diagnostics());
+        if result.is_err() {
+            process::exit(1);
+        }
     }
+    progress!(quiet, "  [+] Cavvy 编译成功");
 
     // 2. IR 优化 (如果启用)
     if options.opt_ir {
-        println!("");
-        println!("[2] IR 优化 ({})...", options.optimization);
+        progress!(quiet);
+        progress!(quiet, "[2] IR 优化 ({})...", options.optimization);
         match optimize_ir(&ir_file, &options.optimization) {
             Ok(_) => {
-                println!("  [+] IR 优化完成");
+                progress!(quiet, "  [+] IR 优化完成");
             }
             Err(e) => {
                 print_warning(&format!("IR 优化失败: {}", e));
-                println!("  [I] 继续编译未优化的 IR");
+                progress!(quiet, "  [I] 继续编译未优化的 IR");
             }
         }
     }
 
     // 3. IR → EXE (调用ir2exe)
-    println!("");
+    progress!(quiet);
     let step_num = if options.opt_ir { "[3]" } else { "[2]" };
-    println!("{} IR → EXE 编译...", step_num);
+    progress!(quiet, "{} IR → EXE 编译...", step_num);
 
     let current_exe = match env::current_exe() {
         Ok(path) => path,
@@ -748,10 +818,10 @@ fn main() {
     ir2exe_args.push(exe_output.clone());
 
     // 调试：显示实际调用的命令
-    println!("  [D] 调用: {} {}", ir2exe_path.display(), ir2exe_args.join(" "));
+    progress!(quiet, "  [D] 调用: {} {}", ir2exe_path.display(), ir2exe_args.join(" "));
     
     // 调试：显示实际调用的命令
-    println!("  [D] 调用: {} {}", ir2exe_path.display(), ir2exe_args.join(" "));
+    progress!(quiet, "  [D] 调用: {} {}", ir2exe_path.display(), ir2exe_args.join(" "));
     
     // 调用ir2exe
     let output = process::Command::new(&ir2exe_path)
@@ -780,11 +850,11 @@ fn main() {
             print_warning(&format!("无法清理临时文件 {}: {}", ir_file, e));
         }
     } else {
-        println!("");
-        println!("[I] 保留 IR 文件: {}", ir_file);
+        progress!(quiet);
+        progress!(quiet, "[I] 保留 IR 文件: {}", ir_file);
     }
 
-    println!("");
-    println!("[+] 编译完成!");
-    println!("生成: {}", exe_output);
+    progress!(quiet);
+    progress!(quiet, "[+] 编译完成!");
+    progress!(quiet, "生成: {}", exe_output);
 }
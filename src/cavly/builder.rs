@@ -4,6 +4,7 @@ use anyhow::{Result, Context, bail};
 use crate::cavly::config::{CavlyConfig, ProjectType};
 use crate::cavly::workspace::{WorkspaceResolver, ResolvedDependency, topological_sort};
 use crate::cavly::{ensure_dir, TARGET_DIR};
+use crate::error_format::ErrorFormat;
 
 /// 构建器状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -27,6 +28,8 @@ pub struct Builder {
     state: BuildState,
     /// 是否 verbose 模式
     verbose: bool,
+    /// 传给 cayc 的诊断输出格式
+    error_format: ErrorFormat,
     /// 解析后的依赖列表
     dependencies: Vec<ResolvedDependency>,
 }
@@ -43,6 +46,7 @@ impl Builder {
             config,
             state: BuildState::Idle,
             verbose: false,
+            error_format: ErrorFormat::Human,
             dependencies: Vec::new(),
         }
     }
@@ -69,6 +73,7 @@ impl Builder {
             config,
             state: BuildState::Idle,
             verbose: false,
+            error_format: ErrorFormat::Human,
             dependencies: sorted_deps,
         })
     }
@@ -79,6 +84,14 @@ impl Builder {
         self
     }
     
+    /// 设置诊断输出格式
+    ///
+    /// json 和 sarif 格式下 cayc 的标准输出只有诊断文档，构建时原样转发到标准输出。
+    pub fn error_format(mut self, format: ErrorFormat) -> Self {
+        self.error_format = format;
+        self
+    }
+
     /// 获取当前状态
     pub fn state(&self) -> BuildState {
         self.state
@@ -148,9 +161,16 @@ impl Builder {
             .current_dir(&self.project_root)
             .output()
             .with_context(|| format!("执行 cayc 失败: {}", cayc_path.display()))?;
-        
+
+        if self.error_format.is_machine_readable() {
+            print!("{}", String::from_utf8_lossy(&output.stdout));
+        }
+
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
+            if self.error_format.is_machine_readable() {
+                bail!("编译失败:\nstderr:\n{}", stderr);
+            }
             let stdout = String::from_utf8_lossy(&output.stdout);
             bail!("编译失败:\nstdout:\n{}\nstderr:\n{}", stdout, stderr);
         }
@@ -424,6 +444,11 @@ impl Builder {
             args.push(self.config.build.ldflags.join(" "));
         }
         
+        // 诊断输出格式
+        if self.error_format != ErrorFormat::Human {
+            args.push(format!("--error-format={}", self.error_format.name()));
+        }
+
         // 输入文件（相对于项目根目录的路径）
         args.push(source_path.to_string_lossy().to_string());
         
@@ -608,5 +633,18 @@ mod tests {
         assert!(args.contains(&"-lm".to_string()));
         assert!(args.contains(&"src/main.cay".to_string()));
         assert!(args.contains(&"target/test.exe".to_string()));
+        assert!(!args.iter().any(|arg| arg.starts_with("--error-format")));
+    }
+
+    #[test]
+    fn test_build_cayc_args_error_format() {
+        let temp = TempDir::new().unwrap();
+        let config = create_test_config();
+        let builder = Builder::new(temp.path().to_path_buf(), config)
+            .error_format(ErrorFormat::Sarif);
+        
+        let args = builder.build_cayc_args(Path::new("src/main.cay"), Path::new("target/test.exe")).unwrap();
+        
+        assert!(args.contains(&"--error-format=sarif".to_string()));
     }
 }
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use anyhow::{Result, Context};
+use crate::lint::LintLevel;
 
 /// 项目类型
 #[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
@@ -44,6 +45,10 @@ pub struct CavlyConfig {
     #[serde(default)]
     pub lib: LibConfig,
     
+    /// 代码检查规则级别（cay-lint 使用）: 规则名 -> allow / warn / deny
+    #[serde(default)]
+    pub lints: HashMap<String, LintLevel>,
+    
     /// 自定义配置段
     #[serde(flatten)]
     pub extra: HashMap<String, toml::Value>,
@@ -613,6 +618,11 @@ link_options = []
 # 额外的库搜索路径
 # lib_paths = ["./lib", "/usr/local/lib"]
 
+[lints]
+# 代码检查规则级别: allow、warn 或 deny（运行 cay-lint --list 查看所有规则）
+# unused_parameter = "allow"
+# string_equality = "deny"
+
 [dependencies]
 # 依赖其他 Cavvy 包
 # example = "1.0.0"
@@ -724,6 +734,26 @@ static_lib = true
         assert_eq!(config.ffi.system_libs, vec!["m", "pthread"]);
     }
 
+    #[test]
+    fn test_config_lints() {
+        let toml_content = r#"
+[package]
+name = "lint-project"
+version = "1.0.0"
+
+[lints]
+unused_parameter = "allow"
+string_equality = "deny"
+"#;
+        let config: CavlyConfig = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.lints.get("unused_parameter"), Some(&LintLevel::Allow));
+        assert_eq!(config.lints.get("string_equality"), Some(&LintLevel::Deny));
+        assert!(!config.extra.contains_key("lints"));
+
+        let config: CavlyConfig = toml::from_str(&default_config_template("demo")).unwrap();
+        assert!(config.lints.is_empty());
+    }
+
     #[test]
     fn test_config_validation_empty_name() {
         let config = CavlyConfig {
@@ -111,6 +111,7 @@ impl InlineIrBridge {
         eprintln!("DEBUG bridge: raw_text = '{}'", raw_text);
         let parsed_block = self.parser.parse(&raw_text, &ir_inputs, &[])
             .map_err(|e| crate::error::cayError::CodeGen {
+                code: crate::diagnostic::ErrorCodes::CODEGEN_LLVM_ERROR,
                 message: format!("Inline IR parse error: {}", e),
                 suggestion: "Check your inline IR syntax and variable references".to_string(),
             })?;
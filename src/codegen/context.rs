@@ -9,6 +9,21 @@ pub struct LoopContext {
     pub cond_label: String,  // continue 跳转的目标（条件检查）
     pub end_label: String,   // break 跳转的目标（循环结束）
     pub label: Option<String>,  // 循环标签（用于带标签的 break/continue）
+    pub cleanup_depth: usize,  // 进入循环时清理栈的层数，break/continue 只执行更内层的清理
+}
+
+/// 代码块退出时要执行的清理动作
+///
+/// 清理动作在代码块的每个出口（正常结束、return、break、continue）处重新生成。
+/// `scopes` 是登记时的变量作用域，生成时换入，避免被出口处同名的内层变量遮蔽。
+#[derive(Debug, Clone)]
+pub enum Cleanup {
+    /// defer 语句
+    Defer { stmt: Box<crate::ast::Stmt>, scopes: Vec<HashMap<String, VarScope>> },
+    /// try-with-resources 的资源，非 null 时调用 close()
+    Close { var: String, loc: crate::error::SourceLocation, scopes: Vec<HashMap<String, VarScope>> },
+    /// scope 块中的栈对象，非 null 时调用析构函数
+    Destroy { llvm_name: String, class_name: String },
 }
 
 /// 静态字段信息
@@ -123,6 +138,18 @@ impl ScopeManager {
         None
     }
 
+    /// 以指定的 LLVM 名称声明变量（在当前作用域）
+    pub fn declare_var_as(&mut self, name: &str, llvm_name: &str, var_type: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), VarScope {
+                name: name.to_string(),
+                llvm_name: llvm_name.to_string(),
+                var_type: var_type.to_string(),
+                is_parameter: false,
+            });
+        }
+    }
+
     /// 获取变量类型
     pub fn get_var_type(&self, name: &str) -> Option<String> {
         self.lookup_var(name).map(|v| v.var_type.clone())
@@ -143,6 +170,16 @@ impl ScopeManager {
         self.scopes.last().map_or(false, |s| s.contains_key(name))
     }
 
+    /// 当前的作用域栈快照
+    pub fn snapshot(&self) -> Vec<HashMap<String, VarScope>> {
+        self.scopes.clone()
+    }
+
+    /// 换入作用域栈并返回原来的作用域栈；作用域计数器继续递增，新声明的变量不会重名
+    pub fn swap_scopes(&mut self, scopes: Vec<HashMap<String, VarScope>>) -> Vec<HashMap<String, VarScope>> {
+        std::mem::replace(&mut self.scopes, scopes)
+    }
+
     /// 重置（用于新函数）
     pub fn reset(&mut self) {
         self.scopes.clear();
@@ -188,17 +225,23 @@ pub struct IRGenerator {
     pub global_counter: usize,
     pub current_function: String,
     pub current_class: String,
+    pub current_interface: String,  // 正在生成其 default 或 static 方法体的接口（否则为空）
     pub current_return_type: String,
     pub var_types: HashMap<String, String>,
     pub var_cay_types: HashMap<String, crate::types::Type>,  // 变量名到Cavvy类型的映射
     pub var_class_map: HashMap<String, String>,
     pub loop_stack: Vec<LoopContext>,
+    pub cleanup_stack: Vec<Vec<Cleanup>>,  // 各层代码块登记的清理动作（defer、资源关闭、析构）
+    pub pattern_slots: HashMap<crate::error::SourceLocation, String>,  // 预先分配的 instanceof 模式变量: instanceof 位置 -> LLVM 名称
     pub target_triple: String,
     pub static_fields: Vec<StaticFieldInfo>,
     pub static_field_map: HashMap<String, StaticFieldInfo>,
     pub type_registry: Option<TypeRegistry>,
     pub scope_manager: ScopeManager,
     pub lambda_functions: Vec<String>,
+    pub lambda_counter: usize,  // Lambda 函数编号（全局唯一）
+    pub closure_globals: HashSet<String>,  // 已生成的闭包记录类型和常量闭包
+    pub closure_type_decls: Vec<String>,  // 闭包记录和 Lambda 环境结构体的类型声明
     pub code: String,
     pub method_declarations: Vec<String>,
     pub type_id_map: HashMap<String, TypeIdInfo>,
@@ -236,17 +279,23 @@ impl IRGenerator {
             global_counter: 0,
             current_function: String::new(),
             current_class: String::new(),
+            current_interface: String::new(),
             current_return_type: String::new(),
             var_types: HashMap::new(),
             var_cay_types: HashMap::new(),
             var_class_map: HashMap::new(),
             loop_stack: Vec::new(),
+            cleanup_stack: Vec::new(),
+            pattern_slots: HashMap::new(),
             target_triple,
             static_fields: Vec::new(),
             static_field_map: HashMap::new(),
             type_registry: None,
             scope_manager: ScopeManager::new(),
             lambda_functions: Vec::new(),
+            lambda_counter: 0,
+            closure_globals: HashSet::new(),
+            closure_type_decls: Vec::new(),
             code: String::new(),
             method_declarations: Vec::new(),
             type_id_map: HashMap::new(),
@@ -448,6 +497,7 @@ impl IRGenerator {
             "i32" | "float" => 4,  // float 是 4 字节对齐！
             "i64" | "double" => 8,
             t if t.ends_with("*") => 8,  // 所有指针都是 8 字节（64位系统）
+            t if Self::llvm_struct_name(t).is_some() => self.llvm_type_size_align(t).1 as u32,
             _ => 8, // 默认 8 字节
         }
     }
@@ -468,7 +518,8 @@ impl IRGenerator {
 
     /// 进入循环上下文
     pub fn enter_loop(&mut self, cond_label: String, end_label: String, label: Option<String>) {
-        self.loop_stack.push(LoopContext { cond_label, end_label, label });
+        let cleanup_depth = self.cleanup_stack.len();
+        self.loop_stack.push(LoopContext { cond_label, end_label, label, cleanup_depth });
     }
 
     /// 退出循环上下文
@@ -492,6 +543,26 @@ impl IRGenerator {
         use crate::ast::*;
         use crate::types::Type;
 
+        // 重载运算符的类型是对应方法调用的结果类型
+        let operator_loc = match expr {
+            Expr::Binary(bin) => Some(&bin.loc),
+            Expr::Unary(unary) => Some(&unary.loc),
+            Expr::ArrayAccess(arr) => Some(&arr.loc),
+            _ => None,
+        };
+        if let Some(call) = operator_loc.and_then(|loc| self.operator_call_at(loc)) {
+            return Some(call.result_type);
+        }
+        // 属性读取的类型是属性类型
+        let property_loc = match expr {
+            Expr::MemberAccess(member) => Some(&member.loc),
+            Expr::Identifier(name) => Some(&name.loc),
+            _ => None,
+        };
+        if let Some(call) = property_loc.and_then(|loc| self.property_read_at(loc)) {
+            return Some(call.property_type);
+        }
+
         match expr {
             Expr::Literal(lit) => match lit {
                 LiteralValue::Int32(_) => Some(Type::Int32),
@@ -508,6 +579,10 @@ impl IRGenerator {
                 if let Some(cay_type) = self.var_cay_types.get(name.as_ref()) {
                     return Some(cay_type.clone());
                 }
+                // 当前类的静态字段
+                if let Some(field) = self.static_field_map.get(&format!("{}.{}", self.current_class, name)) {
+                    return Some(field.field_type.clone());
+                }
                 // 回退到LLVM类型映射
                 if let Some(llvm_type) = self.var_types.get(name.as_ref()) {
                     Self::map_llvm_type_to_cay(llvm_type)
@@ -521,6 +596,14 @@ impl IRGenerator {
                     match obj_type {
                         Type::Array(_) if member.member == "length" => Some(Type::Int32),
                         Type::String if member.member == "length" => Some(Type::Int32),
+                        Type::Object(class_name) => self.type_registry.as_ref()?
+                            .get_class(&class_name)?
+                            .fields.get(&member.member)
+                            .map(|field| field.field_type.clone()),
+                        Type::Struct(struct_name) => self.type_registry.as_ref()?
+                            .get_struct(&struct_name)?
+                            .find_field(&member.member)
+                            .map(|(_, field)| field.param_type.clone()),
                         _ => None,
                     }
                 }).or_else(|| {
@@ -554,9 +637,68 @@ impl IRGenerator {
                 })
             },
             Expr::New(new_expr) => {
-                // New 表达式返回对象类型
+                // New 表达式返回对象类型，结构体构造返回结构体值
+                if self.type_registry.as_ref().is_some_and(|r| r.get_struct(&new_expr.class_name).is_some()) {
+                    return Some(Type::Struct(new_expr.class_name.clone()));
+                }
                 Some(Type::Object(new_expr.class_name.clone()))
             },
+            Expr::Call(call) => {
+                // 方法调用返回方法的返回类型（用于链式调用 a.get().get()）
+                let registry = self.type_registry.as_ref()?;
+                match call.callee.as_ref() {
+                    Expr::MemberAccess(member) => {
+                        if let Some(Type::Object(class_name)) = self.get_expression_type(&member.object) {
+                            return registry.get_method(&class_name, &member.member)
+                                .or_else(|| registry.get_interface_method(&class_name, &member.member))
+                                .map(|method| method.return_type.clone());
+                        }
+                        // 静态方法调用: ClassName.method()、Interface.method()
+                        if let Expr::Identifier(class_name) = member.object.as_ref() {
+                            return registry.get_method(class_name.as_ref(), &member.member)
+                                .or_else(|| registry.get_interface_method(class_name.as_ref(), &member.member))
+                                .map(|method| method.return_type.clone());
+                        }
+                        None
+                    }
+                    Expr::Identifier(name) => registry.get_method(&self.current_class, name.as_ref())
+                        .map(|method| method.return_type.clone()),
+                    _ => None,
+                }
+            },
+            Expr::Try(try_expr) => {
+                // `?` 的值是 Ok/Some 的负载
+                let registry = self.type_registry.as_ref()?;
+                let enum_name = registry.try_operators.get(&try_expr.loc)?;
+                registry.get_enum(enum_name)?.variants.first()?.fields.first().map(|f| f.param_type.clone())
+            },
+            Expr::Cast(cast) => Some(cast.target_type.non_null().clone()),
+            Expr::Binary(bin) => {
+                // byte、short、uint、ulong 等参与的运算由语义分析记录了操作数类型
+                let operation = self.integer_operation_at(&bin.loc)?;
+                match bin.op {
+                    BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => Some(Type::Bool),
+                    _ => Some(operation.operand_type),
+                }
+            },
+            Expr::Unary(unary) => match unary.op {
+                UnaryOp::Not => Some(Type::Bool),
+                UnaryOp::Neg | UnaryOp::BitNot | UnaryOp::PreInc | UnaryOp::PreDec
+                | UnaryOp::PostInc | UnaryOp::PostDec => self.get_expression_type(&unary.operand),
+                _ => None,
+            },
+            Expr::Ternary(ternary) => self.get_expression_type(&ternary.true_branch),
+            Expr::SafeCall(safe_call) => self.type_registry.as_ref()?
+                .safe_calls.get(&safe_call.loc).map(|info| info.result.clone()),
+            Expr::Elvis(elvis) => self.type_registry.as_ref()?.elvis_types.get(&elvis.loc).cloned(),
+            _ => None,
+        }
+    }
+
+    /// 表达式的值所属的类（用于 this.outer.count 这类链式字段访问）
+    pub fn expression_class_name(&self, expr: &crate::ast::Expr) -> Option<String> {
+        match self.get_expression_type(expr) {
+            Some(crate::types::Type::Object(class_name)) => Some(class_name),
             _ => None,
         }
     }
@@ -692,6 +834,12 @@ impl IRGenerator {
             Type::Void => "v".to_string(),
             Type::Int32 => "i".to_string(),
             Type::Int64 => "l".to_string(),
+            Type::Int8 => "i8".to_string(),
+            Type::UInt8 => "u8".to_string(),
+            Type::Int16 => "i16".to_string(),
+            Type::UInt16 => "u16".to_string(),
+            Type::UInt32 => "u32".to_string(),
+            Type::UInt64 => "u64".to_string(),
             Type::Float32 => "f".to_string(),
             Type::Float64 => "d".to_string(),
             Type::Bool => "b".to_string(),
@@ -729,6 +877,12 @@ impl IRGenerator {
             // FFI 指针和结构体
             Type::Pointer(inner) => format!("p{}", self.type_to_signature(inner)),
             Type::Struct(name) => format!("st{}", name),
+            // 泛型实例使用单态化名称
+            Type::TypeParam(name) => format!("o{}", name),
+            Type::Generic(_, _) => self.type_to_signature(&resolved_ty.resolve_generics()),
+            // 枚举与类共享命名空间，与泛型枚举实例单态化后的 Object 签名一致
+            Type::Enum(name) => format!("o{}", name),
+            Type::Nullable(inner) => self.type_to_signature(inner),
         }
     }
 
@@ -838,7 +992,7 @@ impl IRGenerator {
             }
 
             let llvm_type = self.type_to_llvm(&field.field_type);
-            let size = field.field_type.size_in_bytes();
+            let size = self.value_size_in_bytes(&field.field_type);
             
             // 对齐处理
             let align = self.get_type_align(&llvm_type) as usize;
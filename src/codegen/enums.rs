@@ -0,0 +1,310 @@
+//! 枚举代码生成
+//!
+//! 每个变体降级为一个带标签的 LLVM 结构体，首字段为 i32 标签（变体下标），
+//! 其后依次是负载字段：`%enum.Shape.Circle = type { i32, double }`。
+//! 枚举值统一以 `i8*` 传递：无负载变体指向一个全局单例（因此可以用 `==` 比较），
+//! 带负载变体在构造时分配在堆上。
+//!
+//! 内置泛型枚举按单态化名称生成（`%enum.Result_i32_String.Ok`），省略枚举名的
+//! `Ok(x)`/`None` 和 `?` 运算符使用语义分析按位置记录的具体实例。
+
+use crate::codegen::context::IRGenerator;
+use crate::ast::*;
+use crate::types::{Type, EnumInfo};
+use crate::error::{cayResult, codegen_error, SourceLocation};
+use crate::diagnostic::ErrorCodes;
+
+impl IRGenerator {
+    /// 生成枚举变体的结构体类型和无负载变体的全局单例
+    ///
+    /// 从类型注册表生成，以包含语义分析登记的泛型枚举实例。
+    pub(super) fn emit_enum_declarations(&mut self) {
+        let mut enums: Vec<EnumInfo> = match &self.type_registry {
+            Some(registry) => registry.enums.values().cloned().collect(),
+            None => return,
+        };
+        if enums.is_empty() {
+            return;
+        }
+        enums.sort_by(|a, b| a.name.cmp(&b.name));
+
+        self.emit_raw("; Enum declarations");
+        for enum_info in enums {
+            self.emit_raw(&format!("%enum.{} = type {{ i32 }}", enum_info.name));
+            for (tag, variant) in enum_info.variants.iter().enumerate() {
+                let struct_type = Self::enum_variant_struct(&enum_info.name, &variant.name);
+                let mut field_types = vec!["i32".to_string()];
+                for field in &variant.fields {
+                    field_types.push(self.type_to_llvm(&field.param_type));
+                }
+                self.emit_raw(&format!("{} = type {{ {} }}", struct_type, field_types.join(", ")));
+                if variant.fields.is_empty() {
+                    self.emit_raw(&format!("@enum.{}.{} = internal global {} {{ i32 {} }}, align 8",
+                        enum_info.name, variant.name, struct_type, tag));
+                }
+            }
+        }
+        self.emit_raw("");
+    }
+
+    /// 变体结构体的 LLVM 类型名
+    fn enum_variant_struct(enum_name: &str, variant_name: &str) -> String {
+        format!("%enum.{}.{}", enum_name, variant_name)
+    }
+
+    /// 从类型注册表获取枚举信息
+    pub fn get_enum_info(&self, enum_name: &str) -> Option<EnumInfo> {
+        self.type_registry.as_ref().and_then(|registry| registry.get_enum(enum_name).cloned())
+    }
+
+    /// 如果表达式是未被局部变量遮蔽的枚举名，返回枚举名
+    pub fn as_enum_name(&self, expr: &Expr) -> Option<String> {
+        if let Expr::Identifier(name) = expr {
+            let is_local_var = self.scope_manager.get_var_type(name.as_ref()).is_some()
+                || self.var_types.contains_key(name.as_ref());
+            if !is_local_var && self.get_enum_info(name.as_ref()).is_some() {
+                return Some(name.as_ref().to_string());
+            }
+        }
+        None
+    }
+
+    /// 语义分析为省略枚举名的内置变体（Ok(x)、None 等）确定的具体枚举
+    pub fn builtin_variant_enum(&self, loc: &crate::error::SourceLocation) -> Option<String> {
+        self.type_registry.as_ref().and_then(|registry| registry.variant_constructions.get(loc).cloned())
+    }
+
+    /// 识别 Color.Red、Shape.Circle(...) 以及 Ok(x)、None 形式的枚举值表达式，返回其枚举类型
+    pub fn enum_value_type(&self, expr: &Expr) -> Option<Type> {
+        let member = match expr {
+            Expr::MemberAccess(member) => member,
+            Expr::Call(call) => match call.callee.as_ref() {
+                Expr::MemberAccess(member) => member,
+                Expr::Identifier(_) => return self.builtin_variant_enum(&call.loc).map(Type::Enum),
+                _ => return None,
+            },
+            Expr::Identifier(ident) => return self.builtin_variant_enum(&ident.loc).map(Type::Enum),
+            _ => return None,
+        };
+        self.as_enum_name(&member.object).map(Type::Enum)
+    }
+
+    /// 类型对应的枚举名：单态化后的泛型枚举实例可能以 Generic 或 Object 形式出现
+    pub fn enum_type_name(&self, ty: &Type) -> Option<String> {
+        let name = match ty {
+            Type::Enum(name) => name.clone(),
+            Type::Object(name) => name.clone(),
+            Type::Generic(_, _) => ty.resolve_generics().to_string(),
+            _ => return None,
+        };
+        self.get_enum_info(&name).map(|_| name)
+    }
+
+    /// 生成无负载变体的值: Color.Red
+    pub fn generate_enum_variant_access(&mut self, enum_name: &str, variant_name: &str) -> cayResult<String> {
+        let enum_info = self.get_enum_info(enum_name)
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Unknown enum '{}'", enum_name)))?;
+        if enum_info.find_variant(variant_name).is_none() {
+            return Err(codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Enum '{}' has no variant '{}'", enum_name, variant_name)));
+        }
+        let struct_type = Self::enum_variant_struct(enum_name, variant_name);
+        let temp = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast {}* @enum.{}.{} to i8*",
+            temp, struct_type, enum_name, variant_name));
+        Ok(format!("i8* {}", temp))
+    }
+
+    /// 生成带负载变体的构造: Shape.Circle(1.0)
+    pub fn generate_enum_variant_construction(&mut self, enum_name: &str, variant_name: &str, args: &[Expr]) -> cayResult<String> {
+        let enum_info = self.get_enum_info(enum_name)
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Unknown enum '{}'", enum_name)))?;
+        let (tag, variant) = enum_info.find_variant(variant_name)
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Enum '{}' has no variant '{}'", enum_name, variant_name)))?;
+        let struct_type = Self::enum_variant_struct(enum_name, variant_name);
+
+        // 先求值参数，保证求值顺序与书写顺序一致
+        let mut values = Vec::new();
+        for (arg, field) in args.iter().zip(variant.fields.iter()) {
+            let value = self.generate_expression(arg)?;
+            let (value_type, val) = self.parse_typed_value(&value);
+            let field_type = self.type_to_llvm(&field.param_type);
+            values.push((self.coerce_to_llvm_type(&value_type, &val, &field_type), field_type));
+        }
+
+        // 通过 getelementptr null, 1 计算结构体大小
+        let size_ptr = self.new_temp();
+        self.emit_line(&format!("  {} = getelementptr {}, {}* null, i32 1", size_ptr, struct_type, struct_type));
+        let size = self.new_temp();
+        self.emit_line(&format!("  {} = ptrtoint {}* {} to i64", size, struct_type, size_ptr));
+        let raw = self.new_temp();
+        self.emit_line(&format!("  {} = call i8* @calloc(i64 1, i64 {})", raw, size));
+        let typed = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast i8* {} to {}*", typed, raw, struct_type));
+
+        let tag_ptr = self.new_temp();
+        self.emit_line(&format!("  {} = getelementptr inbounds {}, {}* {}, i32 0, i32 0",
+            tag_ptr, struct_type, struct_type, typed));
+        self.emit_line(&format!("  store i32 {}, i32* {}, align 4", tag, tag_ptr));
+
+        for (i, (val, field_type)) in values.into_iter().enumerate() {
+            let field_ptr = self.new_temp();
+            self.emit_line(&format!("  {} = getelementptr inbounds {}, {}* {}, i32 0, i32 {}",
+                field_ptr, struct_type, struct_type, typed, i + 1));
+            let align = self.get_type_align(&field_type);
+            self.emit_line(&format!("  store {} {}, {}* {}, align {}", field_type, val, field_type, field_ptr, align));
+        }
+
+        Ok(format!("i8* {}", raw))
+    }
+
+    /// 将值转换为目标 LLVM 类型（变体字段、switch 表达式的结果）
+    pub(super) fn coerce_to_llvm_type(&mut self, value_type: &str, val: &str, field_type: &str) -> String {
+        if value_type == field_type {
+            return val.to_string();
+        }
+        let temp = self.new_temp();
+        if value_type.starts_with('i') && !value_type.ends_with('*') && (field_type == "float" || field_type == "double") {
+            self.emit_line(&format!("  {} = sitofp {} {} to {}", temp, value_type, val, field_type));
+        } else if value_type == "float" && field_type == "double" {
+            self.emit_line(&format!("  {} = fpext float {} to double", temp, val));
+        } else if value_type == "double" && field_type == "float" {
+            self.emit_line(&format!("  {} = fptrunc double {} to float", temp, val));
+        } else if value_type.starts_with('i') && field_type.starts_with('i') && !value_type.ends_with('*') && !field_type.ends_with('*') {
+            let from_bits: u32 = value_type.trim_start_matches('i').parse().unwrap_or(64);
+            let to_bits: u32 = field_type.trim_start_matches('i').parse().unwrap_or(64);
+            let op = if to_bits > from_bits { "sext" } else { "trunc" };
+            self.emit_line(&format!("  {} = {} {} {} to {}", temp, op, value_type, val, field_type));
+        } else if val == "null" || val == "0" {
+            return "null".to_string();
+        } else {
+            self.emit_line(&format!("  {} = bitcast {} {} to {}", temp, value_type, val, field_type));
+        }
+        temp
+    }
+
+    /// 确定 switch 所匹配的枚举：优先使用语义分析的记录和表达式类型，否则根据 case 中的变体名查找
+    pub fn enum_switch_info<'a>(&self, loc: &SourceLocation, selector: &Expr, labels: impl Iterator<Item = &'a CaseLabel>) -> Option<EnumInfo> {
+        let patterns: Vec<&EnumPattern> = labels.filter_map(CaseLabel::pattern).collect();
+        if patterns.is_empty() {
+            return None;
+        }
+        let registry = self.type_registry.as_ref()?;
+        if let Some(name) = registry.enum_switches.get(loc) {
+            return self.get_enum_info(name);
+        }
+        // 引用 static final 常量的标签说明匹配的不是枚举
+        if patterns.iter().any(|p| registry.case_constants.contains_key(&p.loc)) {
+            return None;
+        }
+        if let Some(name) = self.get_expression_type(selector)
+            .or_else(|| self.enum_value_type(selector))
+            .and_then(|ty| self.enum_type_name(&ty))
+        {
+            return self.get_enum_info(&name);
+        }
+        if let Some(name) = patterns.iter().find_map(|p| p.enum_name.clone()) {
+            return self.get_enum_info(&name);
+        }
+        let mut names: Vec<&String> = registry.enums.keys().collect();
+        names.sort();
+        names.into_iter()
+            .filter_map(|name| registry.get_enum(name))
+            .find(|info| patterns.iter().all(|p| info.find_variant(&p.variant).is_some()))
+            .cloned()
+    }
+
+    /// 读取枚举值的标签并扩展为 i64，作为 switch 的条件值
+    pub fn load_enum_tag(&mut self, enum_val: &str) -> String {
+        let tag_ptr = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast i8* {} to i32*", tag_ptr, enum_val));
+        let tag = self.new_temp();
+        self.emit_line(&format!("  {} = load i32, i32* {}, align 4", tag, tag_ptr));
+        let tag64 = self.new_temp();
+        self.emit_line(&format!("  {} = sext i32 {} to i64", tag64, tag));
+        tag64
+    }
+
+    /// 将变体负载字段绑定为当前作用域的局部变量: case Rect(w, h):
+    pub fn bind_enum_pattern(&mut self, enum_info: &EnumInfo, pattern: &EnumPattern, enum_val: &str) -> cayResult<()> {
+        let variant = match enum_info.find_variant(&pattern.variant) {
+            Some((_, variant)) => variant.clone(),
+            None => return Err(codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Enum '{}' has no variant '{}'", enum_info.name, pattern.variant))),
+        };
+        if pattern.bindings.is_empty() {
+            return Ok(());
+        }
+        let struct_type = Self::enum_variant_struct(&enum_info.name, &variant.name);
+        let typed = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast i8* {} to {}*", typed, enum_val, struct_type));
+
+        for (i, (binding, field)) in pattern.bindings.iter().zip(variant.fields.iter()).enumerate() {
+            let field_type = self.type_to_llvm(&field.param_type);
+            let align = self.get_type_align(&field_type);
+            let field_ptr = self.new_temp();
+            self.emit_line(&format!("  {} = getelementptr inbounds {}, {}* {}, i32 0, i32 {}",
+                field_ptr, struct_type, struct_type, typed, i + 1));
+            let value = self.new_temp();
+            self.emit_line(&format!("  {} = load {}, {}* {}, align {}", value, field_type, field_type, field_ptr, align));
+
+            let llvm_name = self.scope_manager.declare_var(binding, &field_type);
+            self.emit_line(&format!("  %{} = alloca {}, align {}", llvm_name, field_type, align));
+            self.emit_line(&format!("  store {} {}, {}* %{}, align {}", field_type, value, field_type, llvm_name, align));
+            self.var_types.insert(binding.clone(), field_type);
+            self.var_cay_types.insert(binding.clone(), field.param_type.clone());
+            if let Type::Object(class_name) = &field.param_type {
+                self.var_class_map.insert(binding.clone(), class_name.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// 生成错误传播表达式 `expr?`
+    ///
+    /// 比较标签：Err/None（标签 1）时执行所有待执行的清理动作，再把操作数原样作为当前方法的返回值——
+    /// 返回类型与操作数是同一种内置枚举且错误负载类型相同，内存布局一致；
+    /// 否则取出 Ok/Some 的负载作为表达式的值。不涉及栈展开。
+    pub fn generate_try_expression(&mut self, try_expr: &TryExpr) -> cayResult<String> {
+        let enum_name = self.type_registry.as_ref()
+            .and_then(|registry| registry.try_operators.get(&try_expr.loc).cloned())
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "'?' 运算符缺少语义分析记录的操作数类型"))?;
+        let enum_info = self.get_enum_info(&enum_name)
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Unknown enum '{}'", enum_name)))?;
+        let success = enum_info.variants[0].clone();
+        let payload = success.fields.first()
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Variant '{}.{}' has no payload", enum_name, success.name)))?;
+
+        let value = self.generate_expression(&try_expr.expr)?;
+        let (_, val) = self.parse_typed_value(&value);
+        let tag = self.load_enum_tag(&val);
+        let is_error = self.new_temp();
+        self.emit_line(&format!("  {} = icmp eq i64 {}, 1", is_error, tag));
+        let error_label = self.new_label("try.err");
+        let ok_label = self.new_label("try.ok");
+        self.emit_line(&format!("  br i1 {}, label %{}, label %{}", is_error, error_label, ok_label));
+
+        self.emit_line(&format!("{}:", error_label));
+        // 提前返回同样要执行 defer、资源关闭等清理动作
+        self.emit_cleanups_from(0)?;
+        let ret_type = self.current_return_type.clone();
+        if ret_type == "i8*" {
+            self.emit_line(&format!("  ret i8* {}", val));
+        } else {
+            let cast = self.new_temp();
+            self.emit_line(&format!("  {} = bitcast i8* {} to {}", cast, val, ret_type));
+            self.emit_line(&format!("  ret {} {}", ret_type, cast));
+        }
+
+        self.emit_line(&format!("{}:", ok_label));
+        let struct_type = Self::enum_variant_struct(&enum_name, &success.name);
+        let typed = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast i8* {} to {}*", typed, val, struct_type));
+        let field_type = self.type_to_llvm(&payload.param_type);
+        let align = self.get_type_align(&field_type);
+        let field_ptr = self.new_temp();
+        self.emit_line(&format!("  {} = getelementptr inbounds {}, {}* {}, i32 0, i32 1",
+            field_ptr, struct_type, struct_type, typed));
+        let result = self.new_temp();
+        self.emit_line(&format!("  {} = load {}, {}* {}, align {}", result, field_type, field_type, field_ptr, align));
+        Ok(format!("{} {}", field_type, result))
+    }
+}
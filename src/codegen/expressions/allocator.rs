@@ -27,15 +27,15 @@ impl IRGenerator {
     /// # Returns
     /// 格式为 "i64 value" 的 LLVM IR 值字符串（指针作为 long 返回）
     pub fn generate_alloc_expression(&mut self, alloc: &AllocExpr) -> cayResult<String> {
-        // 生成大小表达式
+        // 生成大小表达式，int 等较窄的整数扩展为 i64
         let size_val = self.generate_expression(&alloc.size)?;
-        
-        // 提取大小值（去掉类型前缀）
-        let size = if size_val.contains(' ') {
-            size_val.split_whitespace().nth(1).unwrap_or("0")
-        } else {
-            &size_val
-        };
+        let size_val = self.widen_unsigned_value(&alloc.size, &size_val, "i64");
+        let (size_type, mut size) = self.parse_typed_value(&size_val);
+        if size_type != "i64" {
+            let size_i64 = self.new_temp();
+            self.emit_line(&format!("  {} = sext {} {} to i64", size_i64, size_type, size));
+            size = size_i64;
+        }
         
         // 调用 malloc 分配内存
         let malloc_temp = self.new_temp();
@@ -51,7 +51,7 @@ impl IRGenerator {
             ptr_int, malloc_temp
         ));
         
-        Ok(format!("i64 {}", ptr_int.replace('%', "")))
+        Ok(format!("i64 {}", ptr_int))
     }
     
     /// 生成内存释放表达式的 LLVM IR
@@ -6,6 +6,7 @@ use crate::codegen::context::IRGenerator;
 use crate::ast::*;
 use crate::types::Type;
 use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 生成数组创建表达式代码: new Type[size] 或 new Type[size1][size2]...
@@ -36,7 +37,7 @@ impl IRGenerator {
         
         // 确保大小是整数类型
         if !size_type.starts_with("i") {
-            return Err(codegen_error(format!("Array size must be integer, got {}", size_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Array size must be integer, got {}", size_type)));
         }
         
         // 将大小转换为 i64（用于内存分配）
@@ -71,6 +72,7 @@ impl IRGenerator {
             Type::String => 8, // 指针大小
             Type::Object(_) => 8, // 指针大小
             Type::Array(_) => 8, // 指针大小
+            Type::Struct(_) => self.value_size_in_bytes(element_type) as i64, // 结构体按值存放
             _ => 8, // 默认
         };
         
@@ -120,7 +122,7 @@ impl IRGenerator {
         // 2. 不自动分配子数组，由用户后续手动分配
 
         if sizes.len() < 2 {
-            return Err(codegen_error("Multidimensional array needs at least 2 dimensions".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "Multidimensional array needs at least 2 dimensions".to_string()));
         }
 
         // 检查是否有空维度（不规则数组）
@@ -260,11 +262,12 @@ impl IRGenerator {
 
         // 生成索引表达式
         let index_expr = self.generate_expression(&arr.index)?;
+        let index_expr = self.widen_unsigned_value(&arr.index, &index_expr, "i64");
         let (index_type, index_val) = self.parse_typed_value(&index_expr);
 
         // 确保索引是整数类型
         if !index_type.starts_with("i") {
-            return Err(codegen_error(format!("Array index must be integer, got {}", index_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Array index must be integer, got {}", index_type)));
         }
 
         // 将索引转换为 i64
@@ -300,6 +303,9 @@ impl IRGenerator {
     /// # Arguments
     /// * `arr` - 数组访问表达式
     pub fn generate_array_access(&mut self, arr: &ArrayAccessExpr) -> cayResult<String> {
+        if let Some(call) = self.operator_call_at(&arr.loc) {
+            return self.generate_index_operator_call(arr, &call);
+        }
         let (elem_type, elem_ptr_temp, _) = self.get_array_element_ptr(arr)?;
         
         // 加载元素值
@@ -317,7 +323,7 @@ impl IRGenerator {
     /// * `init` - 数组初始化表达式
     pub fn generate_array_init(&mut self, init: &ArrayInitExpr) -> cayResult<String> {
         if init.elements.is_empty() {
-            return Err(codegen_error("Cannot generate code for empty array initializer".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, "Cannot generate code for empty array initializer".to_string()));
         }
         
         // 推断元素类型（从第一个元素）
@@ -332,6 +338,7 @@ impl IRGenerator {
             "i64" => 8,
             "float" => 4,
             "double" => 8,
+            t if Self::llvm_struct_name(t).is_some() => self.llvm_type_size_align(t).0 as i64,
             _ => 8, // 指针类型
         };
         
@@ -385,7 +392,7 @@ impl IRGenerator {
     /// * `target_type` - 目标数组类型
     pub fn generate_array_init_with_type(&mut self, init: &ArrayInitExpr, target_type: &Type) -> cayResult<String> {
         if init.elements.is_empty() {
-            return Err(codegen_error("Cannot generate code for empty array initializer".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, "Cannot generate code for empty array initializer".to_string()));
         }
 
         // 从目标类型获取元素类型
@@ -406,6 +413,7 @@ impl IRGenerator {
             "i64" => 8,
             "float" => 4,
             "double" => 8,
+            t if Self::llvm_struct_name(t).is_some() => self.llvm_type_size_align(t).0 as i64,
             _ => 8, // 指针类型
         };
 
@@ -589,6 +597,6 @@ impl IRGenerator {
             }
         }
 
-        Err(codegen_error(format!("Cannot get array pointer for member access: {}", member.member)))
+        Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, format!("Cannot get array pointer for member access: {}", member.member)))
     }
 }
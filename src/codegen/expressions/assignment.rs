@@ -5,6 +5,7 @@
 use crate::codegen::context::IRGenerator;
 use crate::ast::*;
 use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 生成赋值表达式代码
@@ -12,25 +13,70 @@ impl IRGenerator {
     /// # Arguments
     /// * `assign` - 赋值表达式
     pub fn generate_assignment(&mut self, assign: &AssignmentExpr) -> cayResult<String> {
-        let value = self.generate_expression(&assign.value)?;
+        if let (Expr::ArrayAccess(arr), Some(call)) = (assign.target.as_ref(), self.operator_call_at(&assign.loc)) {
+            return self.generate_index_assignment_call(arr, &assign.value, &call);
+        }
+        if let Some(call) = self.property_write_at(&assign.loc) {
+            match assign.target.as_ref() {
+                Expr::MemberAccess(member) => return self.generate_property_set(Some(&member.object), assign, &call),
+                Expr::Identifier(_) => return self.generate_property_set(None, assign, &call),
+                _ => {}
+            }
+        }
+        let mut value = self.generate_expression(&assign.value)?;
+        if let Some(target_type) = self.get_expression_type(&assign.target) {
+            let target_llvm = self.type_to_llvm(&target_type);
+            value = self.widen_unsigned_value(&assign.value, &value, &target_llvm);
+        }
         let (value_type, val) = self.parse_typed_value(&value);
         
         match assign.target.as_ref() {
             Expr::MemberAccess(member) => {
                 self.generate_member_assignment(member, &value_type, &val, &value)
             }
-            Expr::Identifier(name) => {
-                self.generate_variable_assignment(name.as_ref(), &value_type, &val, &value)
-            }
+            Expr::Identifier(name) => match self.implicit_this_field(name) {
+                Some(member) => self.generate_member_assignment(&member, &value_type, &val, &value),
+                None => self.generate_variable_assignment(name.as_ref(), &value_type, &val, &value),
+            },
             Expr::ArrayAccess(arr_access) => {
                 self.generate_array_assignment(arr_access, &value_type, &val, &value)
             }
-            _ => Err(codegen_error("Invalid assignment target".to_string()))
+            _ => Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, "Invalid assignment target".to_string()))
         }
     }
 
+    /// 类体中直接用名称赋值的实例字段（包括自动属性的字段），改写为 `this.name`
+    ///
+    /// 局部变量和静态字段优先；不是当前类的实例字段时返回 None。
+    fn implicit_this_field(&self, name: &IdentifierExpr) -> Option<MemberAccessExpr> {
+        if self.current_class.is_empty() || self.scope_manager.get_var_type(name.as_str()).is_some() {
+            return None;
+        }
+        let static_key = format!("{}.{}", self.current_class, name);
+        if self.static_field_map.contains_key(&static_key) {
+            return None;
+        }
+        self.get_instance_field(&self.current_class, name.as_str())?;
+        Some(MemberAccessExpr {
+            object: Box::new(Expr::Identifier(IdentifierExpr { name: "this".to_string(), loc: name.loc.clone() })),
+            member: name.name.clone(),
+            loc: name.loc.clone(),
+        })
+    }
+
     /// 生成成员赋值（静态字段或实例字段赋值）
     fn generate_member_assignment(&mut self, member: &MemberAccessExpr, value_type: &str, val: &str, value: &str) -> cayResult<String> {
+        // 结构体字段赋值: p.x = value，直接写入结构体所在的位置
+        if self.expression_struct_name(&member.object).is_some() {
+            let (field_type, field_ptr) = self.get_member_field_pointer(member)?;
+            if value_type != field_type {
+                return self.generate_array_assignment_with_conversion(&field_type, &field_ptr, value_type, val, value);
+            }
+            let align = self.get_type_align(&field_type);
+            self.emit_line(&format!("  store {} {}, {}* {}, align {}", field_type, val, field_type, field_ptr, align));
+            return Ok(value.to_string());
+        }
+
         // 检查是否是静态字段赋值: ClassName.fieldName = value
         if let Expr::Identifier(class_name) = &*member.object {
             let static_key = format!("{}.{}", class_name, member.member);
@@ -128,7 +174,7 @@ impl IRGenerator {
                 self.var_class_map.get(name_str).cloned()
             }
         } else {
-            None
+            self.expression_class_name(&member.object)
         };
         
         if let Some(class_name) = class_name_opt {
@@ -218,7 +264,7 @@ impl IRGenerator {
                     }
                     else {
                         // 其他不支持的类型转换，报错
-                        return Err(codegen_error(format!(
+                        return Err(codegen_error(ErrorCodes::CODEGEN_TYPE_CONVERSION_ERROR, format!(
                             "Cannot convert {} to {} for field assignment (field: {}, class: {})",
                             value_type, field_info.llvm_type, field_info.name, class_name
                         )));
@@ -234,7 +280,7 @@ impl IRGenerator {
             }
         }
         
-        Err(codegen_error("Invalid member access assignment target".to_string()))
+        Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, "Invalid member access assignment target".to_string()))
     }
 
     /// 生成变量赋值
@@ -256,7 +302,7 @@ impl IRGenerator {
             }
             // 回退到旧系统
             let var_type = self.var_types.get(name)
-                .ok_or_else(|| codegen_error(format!("Variable '{}' not found", name)))?
+                .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Variable '{}' not found", name)))?
                 .clone();
             (var_type, name.to_string())
         };
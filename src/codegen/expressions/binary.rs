@@ -5,6 +5,7 @@
 use crate::codegen::context::IRGenerator;
 use crate::ast::*;
 use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
 
 /// 检查类型是否为整数类型（不包括指针）
 fn is_integer_type(ty: &str) -> bool {
@@ -50,16 +51,26 @@ impl IRGenerator {
     /// # Arguments
     /// * `bin` - 二元表达式
     pub fn generate_binary_expression(&mut self, bin: &BinaryExpr) -> cayResult<String> {
+        if let Some(call) = self.operator_call_at(&bin.loc) {
+            return self.generate_binary_operator_call(bin, &call);
+        }
+        if let Some(operation) = self.integer_operation_at(&bin.loc) {
+            return self.generate_integer_binary(bin, &operation);
+        }
         let left = self.generate_expression(&bin.left)?;
         let right = self.generate_expression(&bin.right)?;
-        
+        self.generate_binary_values(&bin.op, &left, &right)
+    }
+
+    /// 对已经求值的两个操作数（"type value" 形式）生成二元运算
+    pub(crate) fn generate_binary_values(&mut self, op: &BinaryOp, left: &str, right: &str) -> cayResult<String> {
         // 解析类型和值
-        let (left_type, left_val) = self.parse_typed_value(&left);
-        let (right_type, right_val) = self.parse_typed_value(&right);
+        let (left_type, left_val) = self.parse_typed_value(left);
+        let (right_type, right_val) = self.parse_typed_value(right);
         
         let temp = self.new_temp();
         
-        match bin.op {
+        match op {
             BinaryOp::Add => self.generate_add(&left_type, &left_val, &right_type, &right_val, &temp),
             BinaryOp::Sub => self.generate_sub(&left_type, &left_val, &right_type, &right_val, &temp),
             BinaryOp::Mul => self.generate_mul(&left_type, &left_val, &right_type, &right_val, &temp),
@@ -83,7 +94,7 @@ impl IRGenerator {
     }
 
     /// 生成加法表达式
-    fn generate_add(&mut self, left_type: &str, left_val: &str, right_type: &str, right_val: &str, temp: &str) -> cayResult<String> {
+    pub(super) fn generate_add(&mut self, left_type: &str, left_val: &str, right_type: &str, right_val: &str, temp: &str) -> cayResult<String> {
         // 字符串拼接处理
         if left_type == "i8*" && right_type == "i8*" {
             // 调用内建的字符串拼接函数
@@ -123,22 +134,32 @@ impl IRGenerator {
                 temp, bool_as_string, right_val));
             return Ok(format!("i8* {}", temp));
         } else if left_type == "i8*" && is_integer_type(right_type) {
-            // 字符串 + 整数：先将整数转换为i32，然后转换为字符串，然后拼接
+            // 字符串 + 整数：先将整数转换为字符串（long 保留 64 位），然后拼接
             let int_as_string = self.new_temp();
-            // 将任意整数类型转换为i32（使用正确的转换指令：sext或trunc）
-            let int_val = generate_int_cast(self, right_type, right_val, 32);
-            self.emit_line(&format!("  {} = call i8* @__cay_int_to_string(i32 {})",
-                int_as_string, int_val));
+            if right_type == "i64" {
+                self.emit_line(&format!("  {} = call i8* @__cay_long_to_string(i64 {})",
+                    int_as_string, right_val));
+            } else {
+                // 将其他整数类型转换为i32（使用正确的转换指令：sext或trunc）
+                let int_val = generate_int_cast(self, right_type, right_val, 32);
+                self.emit_line(&format!("  {} = call i8* @__cay_int_to_string(i32 {})",
+                    int_as_string, int_val));
+            }
             self.emit_line(&format!("  {} = call i8* @__cay_string_concat(i8* {}, i8* {})",
                 temp, left_val, int_as_string));
             return Ok(format!("i8* {}", temp));
         } else if is_integer_type(left_type) && right_type == "i8*" {
-            // 整数 + 字符串：先将整数转换为i32，然后转换为字符串，然后拼接
+            // 整数 + 字符串：先将整数转换为字符串（long 保留 64 位），然后拼接
             let int_as_string = self.new_temp();
-            // 将任意整数类型转换为i32（使用正确的转换指令：sext或trunc）
-            let int_val = generate_int_cast(self, left_type, left_val, 32);
-            self.emit_line(&format!("  {} = call i8* @__cay_int_to_string(i32 {})",
-                int_as_string, int_val));
+            if left_type == "i64" {
+                self.emit_line(&format!("  {} = call i8* @__cay_long_to_string(i64 {})",
+                    int_as_string, left_val));
+            } else {
+                // 将其他整数类型转换为i32（使用正确的转换指令：sext或trunc）
+                let int_val = generate_int_cast(self, left_type, left_val, 32);
+                self.emit_line(&format!("  {} = call i8* @__cay_int_to_string(i32 {})",
+                    int_as_string, int_val));
+            }
             self.emit_line(&format!("  {} = call i8* @__cay_string_concat(i8* {}, i8* {})",
                 temp, int_as_string, right_val));
             return Ok(format!("i8* {}", temp));
@@ -207,7 +228,7 @@ impl IRGenerator {
                 temp, promoted_type, promoted_left, converted_right));
             return Ok(format!("{} {}", promoted_type, temp));
         } else {
-            return Err(codegen_error(format!("Unsupported addition types: {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported addition types: {} and {}", left_type, right_type)));
         }
     }
 
@@ -231,7 +252,7 @@ impl IRGenerator {
                 temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("{} {}", promoted_type, temp));
         } else {
-            return Err(codegen_error(format!("Unsupported subtraction types: {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported subtraction types: {} and {}", left_type, right_type)));
         }
     }
 
@@ -255,7 +276,7 @@ impl IRGenerator {
                 temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("{} {}", promoted_type, temp));
         } else {
-            return Err(codegen_error(format!("Unsupported multiplication types: {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported multiplication types: {} and {}", left_type, right_type)));
         }
     }
 
@@ -281,7 +302,7 @@ impl IRGenerator {
                 temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("{} {}", promoted_type, temp));
         } else {
-            return Err(codegen_error(format!("Unsupported division types: {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported division types: {} and {}", left_type, right_type)));
         }
     }
 
@@ -296,7 +317,7 @@ impl IRGenerator {
                 temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("{} {}", promoted_type, temp));
         } else {
-            return Err(codegen_error(format!("Unsupported modulo types: {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported modulo types: {} and {}", left_type, right_type)));
         }
     }
 
@@ -327,7 +348,7 @@ impl IRGenerator {
             self.emit_line(&format!("  {} = fcmp oeq {} {}, {}", temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("i1 {}", temp));
         } else {
-            return Err(codegen_error(format!("Unsupported equality comparison types: {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported equality comparison types: {} and {}", left_type, right_type)));
         }
     }
 
@@ -358,7 +379,7 @@ impl IRGenerator {
             self.emit_line(&format!("  {} = fcmp one {} {}, {}", temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("i1 {}", temp));
         } else {
-            return Err(codegen_error(format!("Unsupported inequality comparison types: {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported inequality comparison types: {} and {}", left_type, right_type)));
         }
     }
 
@@ -377,7 +398,7 @@ impl IRGenerator {
             self.emit_line(&format!("  {} = fcmp olt {} {}, {}", temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("i1 {}", temp));
         } else {
-            return Err(codegen_error(format!("Unsupported less-than comparison types: {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported less-than comparison types: {} and {}", left_type, right_type)));
         }
     }
 
@@ -396,7 +417,7 @@ impl IRGenerator {
             self.emit_line(&format!("  {} = fcmp ole {} {}, {}", temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("i1 {}", temp));
         } else {
-            return Err(codegen_error(format!("Unsupported less-or-equal comparison types: {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported less-or-equal comparison types: {} and {}", left_type, right_type)));
         }
     }
 
@@ -416,7 +437,7 @@ impl IRGenerator {
             // 混合类型：整数和浮点数
             self.emit_line(&format!("  {} = fcmp ogt {} {}, {}", temp, promoted_type, promoted_left, promoted_right));
         } else {
-            return Err(codegen_error(format!("Unsupported greater-than comparison types: {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported greater-than comparison types: {} and {}", left_type, right_type)));
         }
         Ok(format!("i1 {}", temp))
     }
@@ -437,7 +458,7 @@ impl IRGenerator {
             // 混合类型：整数和浮点数
             self.emit_line(&format!("  {} = fcmp oge {} {}, {}", temp, promoted_type, promoted_left, promoted_right));
         } else {
-            return Err(codegen_error(format!("Unsupported greater-than-or-equal comparison types: {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported greater-than-or-equal comparison types: {} and {}", left_type, right_type)));
         }
         Ok(format!("i1 {}", temp))
     }
@@ -495,7 +516,7 @@ impl IRGenerator {
                 temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("{} {}", promoted_type, temp));
         } else {
-            return Err(codegen_error(format!("Bitwise AND requires integer operands, got {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Bitwise AND requires integer operands, got {} and {}", left_type, right_type)));
         }
     }
 
@@ -508,7 +529,7 @@ impl IRGenerator {
                 temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("{} {}", promoted_type, temp));
         } else {
-            return Err(codegen_error(format!("Bitwise OR requires integer operands, got {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Bitwise OR requires integer operands, got {} and {}", left_type, right_type)));
         }
     }
 
@@ -521,7 +542,7 @@ impl IRGenerator {
                 temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("{} {}", promoted_type, temp));
         } else {
-            return Err(codegen_error(format!("Bitwise XOR requires integer operands, got {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Bitwise XOR requires integer operands, got {} and {}", left_type, right_type)));
         }
     }
 
@@ -534,7 +555,7 @@ impl IRGenerator {
                 temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("{} {}", promoted_type, temp));
         } else {
-            return Err(codegen_error(format!("Shift left requires integer operands, got {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Shift left requires integer operands, got {} and {}", left_type, right_type)));
         }
     }
 
@@ -547,7 +568,7 @@ impl IRGenerator {
                 temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("{} {}", promoted_type, temp));
         } else {
-            return Err(codegen_error(format!("Arithmetic shift right requires integer operands, got {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Arithmetic shift right requires integer operands, got {} and {}", left_type, right_type)));
         }
     }
 
@@ -560,7 +581,7 @@ impl IRGenerator {
                 temp, promoted_type, promoted_left, promoted_right));
             return Ok(format!("{} {}", promoted_type, temp));
         } else {
-            return Err(codegen_error(format!("Unsigned shift right requires integer operands, got {} and {}", left_type, right_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsigned shift right requires integer operands, got {} and {}", left_type, right_type)));
         }
     }
 }
@@ -5,6 +5,7 @@
 use crate::codegen::context::IRGenerator;
 use crate::ast::*;
 use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
 
 /// 格式化字符串占位符类型
 #[derive(Debug, Clone)]
@@ -56,6 +57,21 @@ impl IRGenerator {
 
     /// 生成简单的单参数打印（保持向后兼容）
     fn generate_simple_print(&mut self, arg: &Expr, newline: bool) -> cayResult<String> {
+        // byte、short、uint、ulong 等按数值打印，无符号整数按无符号格式
+        if let Some(integer_type) = self.get_expression_type(arg).filter(|ty| ty.is_narrow_or_unsigned()) {
+            let value = self.generate_expression(arg)?;
+            let text = self.integer_as_string(&integer_type, &value);
+            let (_, text_val) = self.parse_typed_value(&text);
+            let fmt_str = if newline { "%s\n" } else { "%s" };
+            let fmt_name = self.get_or_create_string_constant(fmt_str);
+            let fmt_len = fmt_str.len() + 1;
+            let fmt_ptr = self.new_temp();
+            self.emit_line(&format!("  {} = getelementptr [{} x i8], [{} x i8]* {}, i64 0, i64 0",
+                fmt_ptr, fmt_len, fmt_len, fmt_name));
+            self.emit_line(&format!("  call i32 (i8*, ...) @printf(i8* {}, i8* {})", fmt_ptr, text_val));
+            return Ok("i64 0".to_string());
+        }
+
         match arg {
             Expr::Literal(LiteralValue::String(s)) => {
                 let global_name = self.get_or_create_string_constant(s);
@@ -198,7 +214,7 @@ impl IRGenerator {
 
         // 检查参数数量是否匹配
         if placeholders.len() != args.len() - 1 {
-            return Err(codegen_error(format!(
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, format!(
                 "Format string expects {} arguments, but {} provided",
                 placeholders.len(),
                 args.len() - 1
@@ -208,7 +224,17 @@ impl IRGenerator {
         // 首先生成所有参数的值并确定其类型
         let mut arg_types_and_values: Vec<(String, String)> = Vec::new();
         for i in 1..args.len() {
-            let value = self.generate_expression(&args[i])?;
+            let mut value = self.generate_expression(&args[i])?;
+            if let Some(integer_type) = self.get_expression_type(&args[i]).filter(|ty| ty.is_narrow_or_unsigned()) {
+                // {} 占位符按数值输出；C 风格占位符按符号扩展到 i64，由 %u、%x 等自行解释
+                value = match placeholders[i - 1] {
+                    Placeholder::CStyle(_) => {
+                        let target = if integer_type.is_unsigned() { crate::types::Type::UInt64 } else { crate::types::Type::Int64 };
+                        self.generate_integer_type_cast(Some(&integer_type), &value, &target).unwrap_or(value)
+                    }
+                    _ => self.integer_as_string(&integer_type, &value),
+                };
+            }
             let (type_str, val) = self.parse_typed_value(&value);
             arg_types_and_values.push((type_str, val));
         }
@@ -572,7 +598,7 @@ impl IRGenerator {
     pub fn generate_read_int_call(&mut self, args: &[Expr]) -> cayResult<String> {
         // readInt 应该没有参数
         if !args.is_empty() {
-            return Err(codegen_error("readInt() takes no arguments".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "readInt() takes no arguments".to_string()));
         }
 
         // 为输入缓冲区分配空间
@@ -615,7 +641,7 @@ impl IRGenerator {
     pub fn generate_read_float_call(&mut self, args: &[Expr]) -> cayResult<String> {
         // readFloat 应该没有参数
         if !args.is_empty() {
-            return Err(codegen_error("readFloat() takes no arguments".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "readFloat() takes no arguments".to_string()));
         }
 
         // 为输入缓冲区分配空间
@@ -658,7 +684,7 @@ impl IRGenerator {
     pub fn generate_read_double_call(&mut self, args: &[Expr]) -> cayResult<String> {
         // readDouble 应该没有参数
         if !args.is_empty() {
-            return Err(codegen_error("readDouble() takes no arguments".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "readDouble() takes no arguments".to_string()));
         }
 
         // 为输入缓冲区分配空间
@@ -710,7 +736,7 @@ impl IRGenerator {
     pub fn generate_read_char_call(&mut self, args: &[Expr]) -> cayResult<String> {
         // readChar 应该没有参数
         if !args.is_empty() {
-            return Err(codegen_error("readChar() takes no arguments".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "readChar() takes no arguments".to_string()));
         }
 
         // 为输入缓冲区分配空间
@@ -753,7 +779,7 @@ impl IRGenerator {
     pub fn generate_read_line_call(&mut self, args: &[Expr]) -> cayResult<String> {
         // readLine 应该没有参数
         if !args.is_empty() {
-            return Err(codegen_error("readLine() takes no arguments".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "readLine() takes no arguments".to_string()));
         }
 
         // 分配缓冲区
@@ -5,6 +5,7 @@
 use crate::codegen::context::IRGenerator;
 use crate::ast::*;
 use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 生成函数调用表达式代码
@@ -12,6 +13,11 @@ impl IRGenerator {
     /// # Arguments
     /// * `call` - 函数调用表达式
     pub fn generate_call_expression(&mut self, call: &CallExpr) -> cayResult<String> {
+        // 省略枚举名的内置变体构造: Ok(x)、Err(e)、Some(x)
+        if let (Expr::Identifier(name), Some(enum_name)) = (call.callee.as_ref(), self.builtin_variant_enum(&call.loc)) {
+            return self.generate_enum_variant_construction(&enum_name, name.as_ref(), &call.args);
+        }
+
         // 处理 print 和 println 函数
         if let Expr::Identifier(name) = call.callee.as_ref() {
             match name.as_str() {
@@ -33,8 +39,18 @@ impl IRGenerator {
             }
         }
 
+        // 接口方法调用: static 方法直接调用，实例方法经 itable 分派
+        if let Some(result) = self.try_generate_interface_call(call)? {
+            return Ok(result);
+        }
+
         // 处理 String 方法调用: str.method(args)
         if let Expr::MemberAccess(member) = call.callee.as_ref() {
+            // 枚举带负载变体构造: Shape.Circle(1.0)
+            if let Some(enum_name) = self.as_enum_name(&member.object) {
+                return self.generate_enum_variant_construction(&enum_name, &member.member, &call.args);
+            }
+
             // 检查是否是 String 方法调用
             if let Some(method_result) = self.try_generate_string_method_call(member, &call.args)? {
                 return Ok(method_result);
@@ -157,14 +173,14 @@ impl IRGenerator {
                                     (class_name, member.member.clone(), Some(member.object.clone()))
                                 }
                                 _ => {
-                                    return Err(codegen_error(format!(
+                                    return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, format!(
                                         "Cannot call method '{}' on non-class type",
                                         member.member
                                     )));
                                 }
                             }
                         } else {
-                            return Err(codegen_error(format!(
+                            return Err(codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, format!(
                                 "Cannot determine type for method call '{}'",
                                 member.member
                             )));
@@ -172,7 +188,7 @@ impl IRGenerator {
                     }
                 }
             }
-            _ => return Err(codegen_error("Invalid function call".to_string())),
+            _ => return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, "Invalid function call".to_string())),
         };
 
         // 检查是否是可变参数方法（根据方法名推断）
@@ -250,6 +266,13 @@ impl IRGenerator {
             // 检查是否需要类型转换
             if idx < param_types.len() {
                 let param_llvm_type = self.type_to_llvm(&param_types[idx]);
+                let (arg_type, arg_val) = match call.args.get(idx) {
+                    Some(arg) if !has_varargs_array => {
+                        let widened = self.widen_unsigned_value(arg, arg_str, &param_llvm_type);
+                        self.parse_typed_value(&widened)
+                    }
+                    _ => (arg_type, arg_val),
+                };
                 let converted_arg = self.convert_arg_type(&arg_type, &arg_val, &param_llvm_type);
                 final_args.push(converted_arg);
             } else {
@@ -399,6 +422,32 @@ impl IRGenerator {
         format!("{}.__{}_{}", class_name, method_name, param_types.join("_"))
     }
 
+    /// 调用语义分析已确定的方法
+    ///
+    /// `callee` 为 `@函数名` 或函数指针，实例方法以 `receiver` 作为 this，实参转换为形参类型。
+    pub fn emit_resolved_method_call(&mut self, callee: &str, receiver: Option<&str>, method: &crate::types::MethodInfo, args: &[String]) -> String {
+        let mut final_args = Vec::new();
+        if let Some(receiver) = receiver {
+            let (_, receiver_val) = self.parse_typed_value(receiver);
+            final_args.push(format!("i8* {}", receiver_val));
+        }
+        for (arg, param) in args.iter().zip(method.params.iter()) {
+            let (arg_type, arg_val) = self.parse_typed_value(arg);
+            let param_type = self.type_to_llvm(&param.param_type);
+            let converted = self.coerce_to_llvm_type(&arg_type, &arg_val, &param_type);
+            final_args.push(format!("{} {}", param_type, converted));
+        }
+
+        let return_type = self.type_to_llvm(&method.return_type);
+        if return_type == "void" {
+            self.emit_line(&format!("  call void {}({})", callee, final_args.join(", ")));
+            return "void %dummy".to_string();
+        }
+        let temp = self.new_temp();
+        self.emit_line(&format!("  {} = call {} {}({})", temp, return_type, callee, final_args.join(", ")));
+        format!("{} {}", return_type, temp)
+    }
+
     /// 将参数类型转换为签名
     fn param_type_to_signature(&self, ty: &crate::types::Type, is_varargs_array: bool) -> String {
         if is_varargs_array {
@@ -410,6 +459,12 @@ impl IRGenerator {
             crate::types::Type::Void => "v".to_string(),
             crate::types::Type::Int32 => "i".to_string(),
             crate::types::Type::Int64 => "l".to_string(),
+            crate::types::Type::Int8 => "i8".to_string(),
+            crate::types::Type::UInt8 => "u8".to_string(),
+            crate::types::Type::Int16 => "i16".to_string(),
+            crate::types::Type::UInt16 => "u16".to_string(),
+            crate::types::Type::UInt32 => "u32".to_string(),
+            crate::types::Type::UInt64 => "u64".to_string(),
             crate::types::Type::Float32 => "f".to_string(),
             crate::types::Type::Float64 => "d".to_string(),
             crate::types::Type::Bool => "b".to_string(),
@@ -445,6 +500,8 @@ impl IRGenerator {
                 }
                 sig
             }
+            crate::types::Type::Enum(name) => format!("o{}", name),
+            crate::types::Type::Struct(name) => format!("st{}", name),
             _ => "x".to_string(),
         }
     }
@@ -489,7 +546,13 @@ impl IRGenerator {
             .collect();
         
         if let Some(ref registry) = self.type_registry {
-            if let Some(class_info) = registry.get_class(class_name) {
+            // 与 generate_function_name 相同，在当前类中找不到时沿父类链查找
+            let mut owner = registry.get_class(class_name);
+            while let Some(class_info) = owner.filter(|c| !c.methods.contains_key(method_name)) {
+                owner = class_info.parent.as_deref().and_then(|parent| registry.get_class(parent));
+            }
+            if let Some(class_info) = owner {
+                let class_name = class_info.name.as_str();
                 if let Some(methods) = class_info.methods.get(method_name) {
                     let arg_count = processed_args.len();
                     
@@ -873,7 +936,7 @@ impl IRGenerator {
             let found = self.get_extern_function(func_name);
             match found {
                 Some(f) => f.clone(),
-                None => return Err(codegen_error(format!("Extern function '{}' not found", func_name))),
+                None => return Err(codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Extern function '{}' not found", func_name))),
             }
         };
 
@@ -893,6 +956,21 @@ impl IRGenerator {
                 let param_type = &extern_func.params[idx].param_type;
                 let llvm_param_type = self.type_to_llvm(param_type);
                 
+                // 结构体按 C 调用约定拆分或通过指针传递
+                if let crate::types::Type::Struct(struct_name) = self.resolve_type(param_type) {
+                    processed_args.extend(self.lower_c_struct_arg(&struct_name, &arg_val));
+                    continue;
+                }
+
+                // C 回调只接收函数指针，从闭包记录中取出
+                let is_callback = matches!(self.resolve_type(param_type), crate::types::Type::Function(_))
+                    || matches!(self.get_expression_type(&args[idx]), Some(crate::types::Type::Function(_)));
+                if is_callback && arg_type == "i8*" {
+                    let fn_ptr = self.closure_code_ptr(&arg_val);
+                    processed_args.push(format!("i8* {}", fn_ptr));
+                    continue;
+                }
+
                 // 进行类型转换
                 let converted_arg = self.convert_arg_type(&arg_type, &arg_val, &llvm_param_type);
                 processed_args.push(converted_arg);
@@ -902,19 +980,35 @@ impl IRGenerator {
             }
         }
 
-        // 获取返回类型
-        let llvm_ret_type = self.type_to_llvm(&extern_func.return_type);
-
         // 检查是否是可变参数函数
         let is_varargs = extern_func.params.iter().any(|p| p.is_varargs);
 
+        // 返回结构体时按 C 调用约定还原返回值
+        if let crate::types::Type::Struct(struct_name) = self.resolve_type(&extern_func.return_type) {
+            let callee = if is_varargs {
+                let (_, sret_param) = self.extern_return_llvm_type(&extern_func.return_type);
+                let mut param_types: Vec<String> = sret_param.into_iter().collect();
+                for p in extern_func.params.iter().filter(|p| !p.is_varargs) {
+                    param_types.extend(self.extern_param_llvm_types(&p.param_type));
+                }
+                param_types.push("...".to_string());
+                format!("({}) @{}", param_types.join(", "), func_name)
+            } else {
+                format!("@{}", func_name)
+            };
+            return Ok(self.generate_c_struct_returning_call(&struct_name, &callee, processed_args));
+        }
+
+        // 获取返回类型
+        let llvm_ret_type = self.type_to_llvm(&extern_func.return_type);
+
         // 直接调用 extern 函数（不创建包装函数）
         if llvm_ret_type == "void" {
             if is_varargs {
                 // 可变参数函数需要显式类型签名
                 let param_types: Vec<String> = extern_func.params.iter()
                     .filter(|p| !p.is_varargs)
-                    .map(|p| self.type_to_llvm(&p.param_type))
+                    .flat_map(|p| self.extern_param_llvm_types(&p.param_type))
                     .collect();
                 let type_sig = if param_types.is_empty() {
                     "(...)".to_string()
@@ -934,7 +1028,7 @@ impl IRGenerator {
                 // 可变参数函数需要显式类型签名
                 let param_types: Vec<String> = extern_func.params.iter()
                     .filter(|p| !p.is_varargs)
-                    .map(|p| self.type_to_llvm(&p.param_type))
+                    .flat_map(|p| self.extern_param_llvm_types(&p.param_type))
                     .collect();
                 let type_sig = if param_types.is_empty() {
                     format!("{} (...)", llvm_ret_type)
@@ -955,7 +1049,7 @@ impl IRGenerator {
     /// 这个函数在运行时模块中已经定义，返回 i8* (String)
     fn generate_buffer_to_string_call(&mut self, args: &[Expr]) -> cayResult<String> {
         if args.len() != 2 {
-            return Err(codegen_error("__cay_buffer_to_string requires 2 arguments".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "__cay_buffer_to_string requires 2 arguments".to_string()));
         }
 
         // 生成参数
@@ -981,7 +1075,7 @@ impl IRGenerator {
     /// 这个函数在运行时模块中已经定义，返回 i64
     fn generate_read_ptr_call(&mut self, args: &[Expr]) -> cayResult<String> {
         if args.len() != 1 {
-            return Err(codegen_error("__cay_read_ptr requires 1 argument".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "__cay_read_ptr requires 1 argument".to_string()));
         }
 
         // 生成参数
@@ -1004,7 +1098,7 @@ impl IRGenerator {
     /// 这个函数在运行时模块中已经定义，返回 i8* (String)
     fn generate_ptr_to_string_call(&mut self, args: &[Expr]) -> cayResult<String> {
         if args.len() != 1 {
-            return Err(codegen_error("__cay_ptr_to_string requires 1 argument".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "__cay_ptr_to_string requires 1 argument".to_string()));
         }
 
         // 生成参数
@@ -1027,7 +1121,7 @@ impl IRGenerator {
     /// 这个函数在运行时模块中已经定义，返回 void
     fn generate_write_ptr_call(&mut self, args: &[Expr]) -> cayResult<String> {
         if args.len() != 2 {
-            return Err(codegen_error("__cay_write_ptr requires 2 arguments".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "__cay_write_ptr requires 2 arguments".to_string()));
         }
 
         // 生成参数
@@ -1054,7 +1148,7 @@ impl IRGenerator {
     /// 这个函数在运行时模块中已经定义，返回 void
     fn generate_write_int_call(&mut self, args: &[Expr]) -> cayResult<String> {
         if args.len() != 2 {
-            return Err(codegen_error("__cay_write_int requires 2 arguments".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "__cay_write_int requires 2 arguments".to_string()));
         }
 
         // 生成参数
@@ -1081,7 +1175,7 @@ impl IRGenerator {
     /// 这个函数在运行时模块中已经定义，返回 i32
     fn generate_cay_read_int_call(&mut self, args: &[Expr]) -> cayResult<String> {
         if args.len() != 1 {
-            return Err(codegen_error("__cay_read_int requires 1 argument".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "__cay_read_int requires 1 argument".to_string()));
         }
 
         // 生成参数
@@ -1103,10 +1197,16 @@ impl IRGenerator {
     }
 
     /// 生成 String.valueOf() 静态方法调用
-    /// 支持多种类型：int, long, float, double, bool, char
+    /// 支持多种类型：int, long, float, double, bool, char，以及 byte、short 和无符号整数
     fn generate_string_valueof_call(&mut self, args: &[Expr]) -> cayResult<String> {
         if args.len() != 1 {
-            return Err(codegen_error("String.valueOf() takes exactly 1 argument".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.valueOf() takes exactly 1 argument".to_string()));
+        }
+
+        // byte、short、uint、ulong 等按数值转换，无符号整数零扩展后按无符号格式输出
+        if let Some(integer_type) = self.get_expression_type(&args[0]).filter(|ty| ty.is_narrow_or_unsigned()) {
+            let value = self.generate_expression(&args[0])?;
+            return Ok(self.integer_as_string(&integer_type, &value));
         }
 
         // 生成参数
@@ -1152,7 +1252,7 @@ impl IRGenerator {
                 return Ok(format!("i8* {}", arg_val));
             }
             _ => {
-                return Err(codegen_error(format!("String.valueOf() does not support type: {}", arg_type)));
+                return Err(codegen_error(ErrorCodes::CODEGEN_TYPE_CONVERSION_ERROR, format!("String.valueOf() does not support type: {}", arg_type)));
             }
         }
 
@@ -1163,7 +1263,7 @@ impl IRGenerator {
     /// 将 String 转换为 int
     fn generate_integer_parseint_call(&mut self, args: &[Expr]) -> cayResult<String> {
         if args.len() != 1 {
-            return Err(codegen_error("Integer.parseInt() takes exactly 1 argument".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "Integer.parseInt() takes exactly 1 argument".to_string()));
         }
 
         // 生成参数（String）
@@ -1172,7 +1272,7 @@ impl IRGenerator {
 
         // 检查参数类型是否为 String (i8*)
         if arg_type != "i8*" {
-            return Err(codegen_error(format!("Integer.parseInt() expects String, got {}", arg_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, format!("Integer.parseInt() expects String, got {}", arg_type)));
         }
 
         let temp = self.new_temp();
@@ -1197,12 +1297,12 @@ impl IRGenerator {
         let (param_types, ret_type) = if let Type::Function(func) = func_type {
             (func.params.clone(), *func.return_type.clone())
         } else {
-            return Err(codegen_error(format!("Variable '{}' is not a function pointer", var_name)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, format!("Variable '{}' is not a function pointer", var_name)));
         };
         
         // 检查参数数量
         if args.len() != param_types.len() {
-            return Err(codegen_error(format!(
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, format!(
                 "Function pointer call requires {} arguments, but got {}",
                 param_types.len(), args.len()
             )));
@@ -1220,7 +1320,7 @@ impl IRGenerator {
         
         // 获取函数指针变量
         let llvm_name = self.scope_manager.get_llvm_name(var_name)
-            .ok_or_else(|| codegen_error(format!("Undefined function pointer variable: {}", var_name)))?;
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Undefined function pointer variable: {}", var_name)))?;
         
         // 加载函数指针
         let func_ptr_temp = self.new_temp();
@@ -1228,18 +1328,10 @@ impl IRGenerator {
         self.emit_line(&format!("  {} = load {}, {}* %{}, align 8",
             func_ptr_temp, func_ptr_type, func_ptr_type, llvm_name));
         
-        // 生成调用
+        // 通过闭包记录调用（有捕获的 Lambda 额外传入环境指针）
         let llvm_ret_type = self.type_to_llvm(&ret_type);
-        if llvm_ret_type == "void" {
-            self.emit_line(&format!("  call void {}({})",
-                func_ptr_temp, arg_values.join(", ")));
-            Ok("void %dummy".to_string())
-        } else {
-            let temp = self.new_temp();
-            self.emit_line(&format!("  {} = call {} {}({})",
-                temp, llvm_ret_type, func_ptr_temp, arg_values.join(", ")));
-            Ok(format!("{} {}", llvm_ret_type, temp))
-        }
+        let param_llvm_types: Vec<String> = param_types.iter().map(|t| self.type_to_llvm(t)).collect();
+        Ok(self.call_closure(&func_ptr_temp, &llvm_ret_type, &param_llvm_types, &arg_values))
     }
 
     /// 获取类的字段类型
@@ -1263,12 +1355,12 @@ impl IRGenerator {
         let (param_types, ret_type) = if let Type::Function(func) = func_type {
             (func.params.clone(), *func.return_type.clone())
         } else {
-            return Err(codegen_error(format!("Field '{}' is not a function pointer", member.member)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, format!("Field '{}' is not a function pointer", member.member)));
         };
 
         // 检查参数数量
         if args.len() != param_types.len() {
-            return Err(codegen_error(format!(
+            return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, format!(
                 "Function pointer call requires {} arguments, but got {}",
                 param_types.len(), args.len()
             )));
@@ -1297,26 +1389,26 @@ impl IRGenerator {
                 if let Type::Object(name) = obj_type {
                     name
                 } else {
-                    return Err(codegen_error("Object is not a class instance".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, "Object is not a class instance".to_string()));
                 }
             } else {
-                return Err(codegen_error("Cannot determine object type".to_string()));
+                return Err(codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "Cannot determine object type".to_string()));
             }
         } else if let Some(obj_type) = self.get_expression_type(&member.object) {
             if let Type::Object(name) = obj_type {
                 name
             } else {
-                return Err(codegen_error("Object is not a class instance".to_string()));
+                return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, "Object is not a class instance".to_string()));
             }
         } else {
-            return Err(codegen_error("Cannot determine object type".to_string()));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "Cannot determine object type".to_string()));
         };
 
         // 获取字段信息（使用类布局信息获取偏移量）
         let field_offset = if let Some(field_info) = self.get_instance_field(&class_name, &member.member) {
             field_info.offset
         } else {
-            return Err(codegen_error(format!("Field '{}' not found in class '{}'", member.member, class_name)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Field '{}' not found in class '{}'", member.member, class_name)));
         };
 
         // 计算字段地址
@@ -1334,17 +1426,9 @@ impl IRGenerator {
         self.emit_line(&format!("  {} = load {}, {}* {}, align 8",
             loaded_func_ptr, func_ptr_type, func_ptr_type, func_ptr_temp));
 
-        // 生成调用
+        // 通过闭包记录调用（有捕获的 Lambda 额外传入环境指针）
         let llvm_ret_type = self.type_to_llvm(&ret_type);
-        if llvm_ret_type == "void" {
-            self.emit_line(&format!("  call void {}({})",
-                loaded_func_ptr, arg_values.join(", ")));
-            Ok("void %dummy".to_string())
-        } else {
-            let temp = self.new_temp();
-            self.emit_line(&format!("  {} = call {} {}({})",
-                temp, llvm_ret_type, loaded_func_ptr, arg_values.join(", ")));
-            Ok(format!("{} {}", llvm_ret_type, temp))
-        }
+        let param_llvm_types: Vec<String> = param_types.iter().map(|t| self.type_to_llvm(t)).collect();
+        Ok(self.call_closure(&loaded_func_ptr, &llvm_ret_type, &param_llvm_types, &arg_values))
     }
 }
@@ -5,6 +5,7 @@
 use crate::codegen::context::IRGenerator;
 use crate::ast::*;
 use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 生成类型转换表达式代码
@@ -15,6 +16,12 @@ impl IRGenerator {
         let expr_value = self.generate_expression(&cast.expr)?;
         let (from_type, val) = self.parse_typed_value(&expr_value);
         let to_type = self.type_to_llvm(&cast.target_type);
+
+        // byte、short、uint、ulong 等需要按符号选择扩展方式
+        let source_type = self.get_expression_type(&cast.expr);
+        if let Some(result) = self.generate_integer_type_cast(source_type.as_ref(), &expr_value, &cast.target_type) {
+            return Ok(result);
+        }
         
         let temp = self.new_temp();
         
@@ -74,6 +81,11 @@ impl IRGenerator {
             return Ok(format!("{} {}", to_type, temp));
         }
         
+        // 字符串到整数按数字解析，不是取指针的地址
+        if source_type == Some(crate::types::Type::String) && to_type.starts_with("i") && !to_type.ends_with("*") {
+            return Ok(self.generate_string_to_int(&val, &to_type));
+        }
+
         // 指针到整数的转换（ptrtoint）- 优先检查
         if from_type.ends_with("*") && to_type.starts_with("i") && !to_type.ends_with("*") {
             self.emit_line(&format!("  {} = ptrtoint {} {} to {}",
@@ -211,28 +223,24 @@ impl IRGenerator {
         
         // 字符串到整数（String -> int）- 使用 atoi
         if from_type == "i8*" && to_type.starts_with("i") && !to_type.ends_with("*") {
-            // 调用 atoi 函数将字符串转换为整数
-            let atoi_result = self.new_temp();
-            self.emit_line(&format!("  {} = call i32 @atoi(i8* {})", atoi_result, val));
-
-            // 如果目标类型不是 i32，需要转换
-            if to_type == "i32" {
-                return Ok(format!("i32 {}", atoi_result));
-            } else {
-                // 转换到目标整数类型
-                let final_result = self.new_temp();
-                let to_bits: u32 = to_type.trim_start_matches('i').parse().unwrap_or(32);
-                if to_bits > 32 {
-                    self.emit_line(&format!("  {} = sext i32 {} to {}", final_result, atoi_result, to_type));
-                } else if to_bits < 32 {
-                    self.emit_line(&format!("  {} = trunc i32 {} to {}", final_result, atoi_result, to_type));
-                } else {
-                    return Ok(format!("i32 {}", atoi_result));
-                }
-                return Ok(format!("{} {}", to_type, final_result));
-            }
+            return Ok(self.generate_string_to_int(&val, &to_type));
         }
 
-        Err(codegen_error(format!("Unsupported cast from {} to {}", from_type, to_type)))
+        Err(codegen_error(ErrorCodes::CODEGEN_TYPE_CONVERSION_ERROR, format!("Unsupported cast from {} to {}", from_type, to_type)))
+    }
+
+    /// 调用 atoi 把字符串转换为整数，再扩展或截断到目标整数类型
+    fn generate_string_to_int(&mut self, val: &str, to_type: &str) -> String {
+        let atoi_result = self.new_temp();
+        self.emit_line(&format!("  {} = call i32 @atoi(i8* {})", atoi_result, val));
+
+        let to_bits: u32 = to_type.trim_start_matches('i').parse().unwrap_or(32);
+        if to_bits == 32 {
+            return format!("i32 {}", atoi_result);
+        }
+        let final_result = self.new_temp();
+        let op = if to_bits > 32 { "sext" } else { "trunc" };
+        self.emit_line(&format!("  {} = {} i32 {} to {}", final_result, op, atoi_result, to_type));
+        format!("{} {}", to_type, final_result)
     }
 }
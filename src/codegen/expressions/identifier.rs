@@ -28,15 +28,15 @@ impl IRGenerator {
         if self.is_top_level_function(name) {
             // 顶层函数作为函数指针使用
             let func_ptr_type = self.get_top_level_function_type(name);
-            let llvm_func_type = self.type_to_llvm(&func_ptr_type);
+            let fn_type = self.function_llvm_signature(&func_ptr_type);
             let func_name = format!("__toplevel_{}", name);
-            // 返回函数指针 - 在 LLVM IR 中直接使用函数名作为指针
-            return Ok(format!("{} @{}", llvm_func_type, func_name));
+            // 返回指向常量闭包记录的指针
+            return Ok(self.static_closure(&func_name, &fn_type));
         }
 
         // 检查是否是类名（静态成员访问的上下文）
         if let Some(ref registry) = self.type_registry {
-            if registry.class_exists(name) {
+            if registry.class_exists(name) || registry.interface_exists(name) {
                 // 类名不应该单独作为表达式使用
                 // 返回一个占位符，实际使用应该在 MemberAccess 中处理
                 return Ok("i8* null".to_string());
@@ -1,20 +1,43 @@
 //! instanceof 表达式代码生成
 //!
-//! 处理类型检查表达式。
+//! 处理类型检查表达式和 instanceof 引入的模式变量。
 
 use crate::codegen::context::IRGenerator;
 use crate::ast::*;
+use crate::types::Type;
 use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 生成 instanceof 表达式代码
     ///
+    /// 带模式变量时（`o instanceof Circle c`），先把值存入变量再测试：只有测试成立的路径会读取它。
+    ///
     /// # Arguments
     /// * `instanceof` - instanceof 表达式
     pub fn generate_instanceof_expression(&mut self, instanceof: &InstanceOfExpr) -> cayResult<String> {
         let expr_result = self.generate_expression(&instanceof.expr)?;
         let (expr_type, expr_val) = self.parse_typed_value(&expr_result);
 
+        let target_class = match &instanceof.target_type {
+            Type::Object(name) => name.clone(),
+            _ => return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, "instanceof target must be an object type".to_string())),
+        };
+
+        if let Some(binding) = &instanceof.binding {
+            let slot = match self.pattern_slots.remove(&instanceof.loc) {
+                Some(slot) => slot,
+                None => self.declare_pattern_variable(binding, &instanceof.target_type),
+            };
+            self.emit_line(&format!("  store {} {}, {}* %{}", expr_type, expr_val, expr_type, slot));
+        }
+
+        let result_temp = self.emit_instance_check(&expr_type, &expr_val, &target_class)?;
+        Ok(format!("i1 {}", result_temp))
+    }
+
+    /// 测试值是否为 `target_class`（类或接口）的实例，null 不是任何类型的实例，返回 i1 临时变量
+    pub fn emit_instance_check(&mut self, expr_type: &str, expr_val: &str, target_class: &str) -> cayResult<String> {
         let null_label = self.new_label("instanceof.null");
         let check_label = self.new_label("instanceof.check");
         let true_label = self.new_label("instanceof.true");
@@ -41,20 +64,14 @@ impl IRGenerator {
         let actual_type_id = self.new_temp();
         self.emit_line(&format!("  {} = load i32, i32* {}", actual_type_id, type_id_ptr));
 
-        let target_type = &instanceof.target_type;
-        let target_class = match target_type {
-            crate::types::Type::Object(name) => name.clone(),
-            _ => return Err(codegen_error("instanceof target must be an object type".to_string())),
-        };
-
         let is_interface = self.type_registry.as_ref()
-            .map(|r| r.get_interface(&target_class).is_some())
+            .map(|r| r.get_interface(target_class).is_some())
             .unwrap_or(false);
 
         if is_interface {
-            self.generate_interface_check(&actual_type_id, &target_class, &true_label, &false_label)?;
+            self.generate_interface_check(&actual_type_id, target_class, &true_label, &false_label);
         } else {
-            self.generate_type_check(&actual_type_id, &target_class, &true_label, &false_label)?;
+            self.generate_type_check(&actual_type_id, target_class, &true_label, &false_label)?;
         }
 
         self.emit_line(&format!("\n{}:", true_label));
@@ -68,7 +85,41 @@ impl IRGenerator {
         self.emit_line(&format!("  {} = phi i1 [ 1, %{} ], [ 0, %{} ]",
             result_temp, true_label, false_label));
 
-        Ok(format!("i1 {}", result_temp))
+        Ok(result_temp)
+    }
+
+    /// 在求值条件之前为其中的模式变量分配存储
+    ///
+    /// `if`/`while`/`for` 和三元表达式的分支可能经由 `&&`、`||` 的合并块到达，
+    /// 在 instanceof 所在的块中分配的变量不能支配这些分支。
+    pub fn declare_pattern_slots(&mut self, condition: &Expr) {
+        let (when_true, when_false) = condition.pattern_bindings();
+        for instanceof in when_true.into_iter().chain(when_false) {
+            if let Some(binding) = &instanceof.binding {
+                let slot = self.declare_pattern_variable(binding, &instanceof.target_type);
+                self.pattern_slots.insert(instanceof.loc.clone(), slot);
+            }
+        }
+    }
+
+    /// 在当前作用域声明模式变量并分配存储，返回 LLVM 名称
+    ///
+    /// 同一作用域中可能多次出现同名的模式变量，LLVM 名称按标签计数器取唯一值。
+    pub fn declare_pattern_variable(&mut self, name: &str, target_type: &Type) -> String {
+        let llvm_name = self.new_label(name);
+        self.emit_line(&format!("  %{} = alloca i8*", llvm_name));
+        self.bind_pattern_variable(name, &llvm_name, target_type);
+        llvm_name
+    }
+
+    /// 把模式变量名绑定到已分配的存储
+    pub fn bind_pattern_variable(&mut self, name: &str, llvm_name: &str, target_type: &Type) {
+        self.scope_manager.declare_var_as(name, llvm_name, "i8*");
+        self.var_types.insert(name.to_string(), "i8*".to_string());
+        self.var_cay_types.insert(name.to_string(), target_type.clone());
+        if let Type::Object(class_name) = target_type {
+            self.var_class_map.insert(name.to_string(), class_name.clone());
+        }
     }
 
     /// 生成类型检查代码（用于类继承）
@@ -119,58 +170,4 @@ impl IRGenerator {
 
         Ok(())
     }
-
-    /// 生成接口检查代码
-    fn generate_interface_check(&mut self, actual_type_id: &str, interface_name: &str, true_label: &str, false_label: &str) -> cayResult<()> {
-        let implementing_type_ids: Vec<i32> = if let Some(ref registry) = self.type_registry {
-            registry.classes.values()
-                .filter(|c| {
-                    if c.interfaces.contains(&interface_name.to_string()) {
-                        return true;
-                    }
-                    let mut current = c.parent.as_ref().map(|p| p.as_str());
-                    while let Some(parent_name) = current {
-                        if let Some(parent_class) = registry.get_class(parent_name) {
-                            if parent_class.interfaces.contains(&interface_name.to_string()) {
-                                return true;
-                            }
-                            current = parent_class.parent.as_ref().map(|p| p.as_str());
-                        } else {
-                            break;
-                        }
-                    }
-                    false
-                })
-                .filter_map(|c| self.get_type_id_value(&c.name))
-                .collect()
-        } else {
-            Vec::new()
-        };
-
-        if implementing_type_ids.is_empty() {
-            self.emit_line(&format!("  br label %{}", false_label));
-            return Ok(());
-        }
-
-        for (i, type_id_value) in implementing_type_ids.iter().enumerate() {
-            let is_match = self.new_temp();
-            self.emit_line(&format!("  {} = icmp eq i32 {}, {}",
-                is_match, actual_type_id, type_id_value));
-
-            let next_check_label = if i < implementing_type_ids.len() - 1 {
-                self.new_label(&format!("interface_check.class{}", i))
-            } else {
-                false_label.to_string()
-            };
-
-            self.emit_line(&format!("  br i1 {}, label %{}, label %{}",
-                is_match, true_label, next_check_label));
-
-            if i < implementing_type_ids.len() - 1 {
-                self.emit_line(&format!("\n{}:", next_check_label));
-            }
-        }
-
-        Ok(())
-    }
 }
@@ -0,0 +1,223 @@
+//! byte、short、uint、ulong 等整数的代码生成
+//!
+//! LLVM 整数不区分符号，ubyte 与 byte 都是 i8。语义分析把这些类型参与的二元运算记录在
+//! `integer_operations` 中，这里按 Cavvy 类型选择零扩展或符号扩展，无符号运算使用
+//! `udiv`、`urem`、`icmp ult`、`lshr` 和 `uitofp`，转换为字符串时按无符号格式输出。
+
+use crate::codegen::context::IRGenerator;
+use crate::ast::*;
+use crate::types::{Type, IntegerOperation};
+use crate::error::{cayResult, codegen_error, SourceLocation};
+use crate::diagnostic::ErrorCodes;
+
+impl IRGenerator {
+    /// 语义分析在该位置记录的整数运算
+    pub fn integer_operation_at(&self, loc: &SourceLocation) -> Option<IntegerOperation> {
+        self.type_registry.as_ref()
+            .and_then(|registry| registry.integer_operations.get(loc).cloned())
+    }
+
+    /// 表达式的值是否为无符号整数（ubyte、ushort、uint、ulong）
+    pub fn is_unsigned_expression(&self, expr: &Expr) -> bool {
+        self.get_expression_type(expr).is_some_and(|ty| ty.is_unsigned())
+    }
+
+    /// 无符号整数转换为更宽的整数或浮点数时使用零扩展和 uitofp
+    ///
+    /// 在各处通用的（按有符号处理的）类型转换之前调用，其余情况原样返回。
+    pub fn widen_unsigned_value(&mut self, expr: &Expr, value: &str, target_type: &str) -> String {
+        if !self.is_unsigned_expression(expr) {
+            return value.to_string();
+        }
+        let (value_type, val) = self.parse_typed_value(value);
+        let (from_bits, to_bits) = match (Self::llvm_int_bits(&value_type), Self::llvm_int_bits(target_type)) {
+            (Some(from_bits), to_bits) => (from_bits, to_bits),
+            _ => return value.to_string(),
+        };
+        let temp = self.new_temp();
+        match to_bits {
+            Some(to_bits) if to_bits > from_bits => {
+                self.emit_line(&format!("  {} = zext {} {} to {}", temp, value_type, val, target_type));
+            }
+            None if target_type == "float" || target_type == "double" => {
+                self.emit_line(&format!("  {} = uitofp {} {} to {}", temp, value_type, val, target_type));
+            }
+            _ => return value.to_string(),
+        }
+        format!("{} {}", target_type, temp)
+    }
+
+    /// 生成有 byte、short、uint、ulong 等类型操作数的二元运算
+    pub fn generate_integer_binary(&mut self, bin: &BinaryExpr, operation: &IntegerOperation) -> cayResult<String> {
+        let left = self.generate_expression(&bin.left)?;
+        let right = self.generate_expression(&bin.right)?;
+
+        // 字符串拼接: 整数按数值（而不是字符）转换为字符串
+        if operation.operand_type == Type::String {
+            let left = self.integer_as_string(&operation.left, &left);
+            let right = self.integer_as_string(&operation.right, &right);
+            let (_, left_val) = self.parse_typed_value(&left);
+            let (_, right_val) = self.parse_typed_value(&right);
+            if left.starts_with("i8* ") && right.starts_with("i8* ") {
+                let temp = self.new_temp();
+                self.emit_line(&format!("  {} = call i8* @__cay_string_concat(i8* {}, i8* {})", temp, left_val, right_val));
+                return Ok(format!("i8* {}", temp));
+            }
+            // 另一侧是 char、bool 等，交给通用的字符串拼接
+            let (left_type, left_val) = self.parse_typed_value(&left);
+            let (right_type, right_val) = self.parse_typed_value(&right);
+            let temp = self.new_temp();
+            return self.generate_add(&left_type, &left_val, &right_type, &right_val, &temp);
+        }
+
+        let operand_llvm = self.type_to_llvm(&operation.operand_type);
+        let is_float = matches!(operation.operand_type, Type::Float32 | Type::Float64);
+        let unsigned = operation.operand_type.is_unsigned();
+        // 移位的右操作数只提供位数，同样转换为运算类型
+        let left_val = self.convert_integer_operand(&operation.left, &left, &operand_llvm);
+        let right_val = self.convert_integer_operand(&operation.right, &right, &operand_llvm);
+
+        let temp = self.new_temp();
+        let instruction = match (bin.op, is_float) {
+            (BinaryOp::Add, false) => "add",
+            (BinaryOp::Sub, false) => "sub",
+            (BinaryOp::Mul, false) => "mul",
+            (BinaryOp::Div, false) | (BinaryOp::Mod, false) => {
+                self.generate_division_by_zero_check(&operand_llvm, &right_val)?;
+                match (bin.op, unsigned) {
+                    (BinaryOp::Div, true) => "udiv",
+                    (BinaryOp::Div, false) => "sdiv",
+                    (_, true) => "urem",
+                    _ => "srem",
+                }
+            }
+            (BinaryOp::Add, true) => "fadd",
+            (BinaryOp::Sub, true) => "fsub",
+            (BinaryOp::Mul, true) => "fmul",
+            (BinaryOp::Div, true) => "fdiv",
+            (BinaryOp::Mod, true) => "frem",
+            (BinaryOp::BitAnd, _) => "and",
+            (BinaryOp::BitOr, _) => "or",
+            (BinaryOp::BitXor, _) => "xor",
+            (BinaryOp::Shl, _) => "shl",
+            (BinaryOp::Shr, _) if !unsigned => "ashr",
+            (BinaryOp::Shr, _) | (BinaryOp::UnsignedShr, _) => "lshr",
+            (op, _) => {
+                let predicate = Self::integer_comparison_predicate(&op, unsigned, is_float)
+                    .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Unsupported integer operation: {:?}", op)))?;
+                let compare = if is_float { "fcmp" } else { "icmp" };
+                self.emit_line(&format!("  {} = {} {} {} {}, {}", temp, compare, predicate, operand_llvm, left_val, right_val));
+                return Ok(format!("i1 {}", temp));
+            }
+        };
+        self.emit_line(&format!("  {} = {} {} {}, {}", temp, instruction, operand_llvm, left_val, right_val));
+        Ok(format!("{} {}", operand_llvm, temp))
+    }
+
+    /// 整数值转换为字符串，无符号整数按无符号格式输出；其他值原样返回
+    pub fn integer_as_string(&mut self, ty: &Type, value: &str) -> String {
+        if !ty.is_narrow_or_unsigned() {
+            return value.to_string();
+        }
+        let (value_type, val) = self.parse_typed_value(value);
+        let wide = if value_type == "i64" {
+            val
+        } else {
+            let temp = self.new_temp();
+            let ext = if ty.is_unsigned() { "zext" } else { "sext" };
+            self.emit_line(&format!("  {} = {} {} {} to i64", temp, ext, value_type, val));
+            temp
+        };
+        let function = if ty.is_unsigned() { "__cay_ulong_to_string" } else { "__cay_long_to_string" };
+        let temp = self.new_temp();
+        self.emit_line(&format!("  {} = call i8* @{}(i64 {})", temp, function, wide));
+        format!("i8* {}", temp)
+    }
+
+    /// byte、short、uint、ulong 等类型的显式转换；不涉及这些类型时返回 None
+    pub fn generate_integer_type_cast(&mut self, source: Option<&Type>, value: &str, target: &Type) -> Option<String> {
+        let source_narrow = source.is_some_and(|ty| ty.is_narrow_or_unsigned());
+        if !source_narrow && !target.is_narrow_or_unsigned() {
+            return None;
+        }
+        let (value_type, val) = self.parse_typed_value(value);
+        let target_llvm = self.type_to_llvm(target);
+        if *target == Type::String {
+            return source.map(|ty| self.integer_as_string(ty, value));
+        }
+        let is_float = |ty: &str| ty == "float" || ty == "double";
+        let unsigned_source = source.is_some_and(|ty| ty.is_unsigned());
+        let temp = self.new_temp();
+        match (Self::llvm_int_bits(&value_type), Self::llvm_int_bits(&target_llvm)) {
+            (Some(from_bits), Some(to_bits)) if value_type != "i1" => {
+                if from_bits == to_bits {
+                    return Some(format!("{} {}", target_llvm, val));
+                }
+                let op = match (to_bits > from_bits, unsigned_source) {
+                    (true, true) => "zext",
+                    (true, false) => "sext",
+                    _ => "trunc",
+                };
+                self.emit_line(&format!("  {} = {} {} {} to {}", temp, op, value_type, val, target_llvm));
+            }
+            (Some(_), None) if value_type != "i1" && is_float(&target_llvm) => {
+                let op = if unsigned_source { "uitofp" } else { "sitofp" };
+                self.emit_line(&format!("  {} = {} {} {} to {}", temp, op, value_type, val, target_llvm));
+            }
+            (None, Some(_)) if is_float(&value_type) => {
+                let op = if target.is_unsigned() { "fptoui" } else { "fptosi" };
+                self.emit_line(&format!("  {} = {} {} {} to {}", temp, op, value_type, val, target_llvm));
+            }
+            _ => return None,
+        }
+        Some(format!("{} {}", target_llvm, temp))
+    }
+
+    /// 把操作数转换为运算类型：整数按自身的 Cavvy 类型零扩展或符号扩展，浮点运算时转换为浮点数
+    fn convert_integer_operand(&mut self, ty: &Type, value: &str, operand_llvm: &str) -> String {
+        let (value_type, val) = self.parse_typed_value(value);
+        if value_type == operand_llvm {
+            return val;
+        }
+        let temp = self.new_temp();
+        let op = match (Self::llvm_int_bits(&value_type), Self::llvm_int_bits(operand_llvm)) {
+            (Some(from_bits), Some(to_bits)) if to_bits > from_bits => if ty.is_unsigned() { "zext" } else { "sext" },
+            (Some(_), Some(_)) => "trunc",
+            (Some(_), None) => if ty.is_unsigned() { "uitofp" } else { "sitofp" },
+            (None, _) => if operand_llvm == "double" { "fpext" } else { "fptrunc" },
+        };
+        self.emit_line(&format!("  {} = {} {} {} to {}", temp, op, value_type, val, operand_llvm));
+        temp
+    }
+
+    fn integer_comparison_predicate(op: &BinaryOp, unsigned: bool, is_float: bool) -> Option<&'static str> {
+        let predicate = match (op, is_float, unsigned) {
+            (BinaryOp::Eq, false, _) => "eq",
+            (BinaryOp::Ne, false, _) => "ne",
+            (BinaryOp::Lt, false, true) => "ult",
+            (BinaryOp::Le, false, true) => "ule",
+            (BinaryOp::Gt, false, true) => "ugt",
+            (BinaryOp::Ge, false, true) => "uge",
+            (BinaryOp::Lt, false, false) => "slt",
+            (BinaryOp::Le, false, false) => "sle",
+            (BinaryOp::Gt, false, false) => "sgt",
+            (BinaryOp::Ge, false, false) => "sge",
+            (BinaryOp::Eq, true, _) => "oeq",
+            (BinaryOp::Ne, true, _) => "one",
+            (BinaryOp::Lt, true, _) => "olt",
+            (BinaryOp::Le, true, _) => "ole",
+            (BinaryOp::Gt, true, _) => "ogt",
+            (BinaryOp::Ge, true, _) => "oge",
+            _ => return None,
+        };
+        Some(predicate)
+    }
+
+    /// LLVM 整数类型的位宽（不包括指针）
+    fn llvm_int_bits(llvm_type: &str) -> Option<u32> {
+        if llvm_type.ends_with('*') {
+            return None;
+        }
+        llvm_type.strip_prefix('i').and_then(|bits| bits.parse().ok())
+    }
+}
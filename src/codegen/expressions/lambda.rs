@@ -6,55 +6,105 @@ use crate::codegen::context::IRGenerator;
 use crate::ast::*;
 use crate::error::cayResult;
 use crate::types::Type;
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 生成 Lambda 表达式代码
     /// Lambda: (params) -> { body }
     ///
+    /// 函数类型的值是指向闭包记录 `%__cay_closure = { i8* fn, i8* env }` 的指针。
+    /// 没有捕获的 Lambda 生成普通函数和常量闭包记录（env 为 null），
+    /// 有捕获的 Lambda 生成以 `i8* %env` 为首参的函数，并在堆上构造环境结构体：
+    /// 按值捕获的字段保存变量值，按引用捕获的字段保存变量（已堆分配）的地址。
+    ///
     /// # Arguments
     /// * `lambda` - Lambda 表达式
     pub fn generate_lambda(&mut self, lambda: &LambdaExpr) -> cayResult<String> {
         // 生成唯一的 Lambda 函数名
-        let current_class = self.current_class.clone();
-        let temp = self.new_temp().replace("%", "");
-        let lambda_name = format!("__lambda_{}_{}", current_class, temp);
-
-        // 推断返回类型
-        let return_type = self.infer_lambda_return_type(lambda)?;
+        let lambda_name = format!("__lambda_{}_{}", self.current_class, self.lambda_counter);
+        self.lambda_counter += 1;
+
+        // 签名和捕获列表由语义分析记录
+        let info = self.type_registry.as_ref().and_then(|r| r.lambdas.get(&lambda.loc)).cloned();
+        let (param_types, return_type, captures) = match info {
+            Some(info) => (info.signature.params, *info.signature.return_type, info.captures),
+            None => (
+                lambda.params.iter().map(|p| p.param_type.clone().unwrap_or(Type::Int32)).collect(),
+                self.infer_lambda_return_type(lambda)?,
+                Vec::new(),
+            ),
+        };
         let llvm_return_type = self.type_to_llvm(&return_type);
-
-        // 保存当前代码缓冲区
+        let param_llvm_types: Vec<String> = param_types.iter().map(|t| self.type_to_llvm(t)).collect();
+        let env_type = format!("%__env.{}", lambda_name);
+        let env_fields: Vec<String> = captures.iter().map(|c| {
+            let ty = self.type_to_llvm(&c.var_type);
+            if c.by_ref { format!("{}*", ty) } else { ty }
+        }).collect();
+
+        // 保存外层函数的代码生成状态
         let saved_code = std::mem::take(&mut self.code);
         let saved_temp_counter = self.temp_counter;
+        let saved_return_type = std::mem::replace(&mut self.current_return_type, llvm_return_type.clone());
+        let saved_var_types = std::mem::take(&mut self.var_types);
+        let saved_var_cay_types = std::mem::take(&mut self.var_cay_types);
+        let saved_var_class_map = std::mem::take(&mut self.var_class_map);
+        let saved_loop_stack = std::mem::take(&mut self.loop_stack);
+        let saved_cleanup_stack = std::mem::take(&mut self.cleanup_stack);
 
         // 重置临时变量计数器
         self.temp_counter = 0;
 
-        // 生成 Lambda 参数类型
-        let mut param_types = Vec::new();
-        let mut param_names = Vec::new();
-
-        for (i, param) in lambda.params.iter().enumerate() {
-            let param_type = param.param_type.as_ref()
-                .map(|t| self.type_to_llvm(t))
-                .unwrap_or_else(|| "i64".to_string());
-            param_types.push(format!("{} %param{}", param_type, i));
-            param_names.push((param.name.clone(), param_type, format!("%param{}", i)));
+        // 生成 Lambda 参数列表
+        let mut params = Vec::new();
+        if !captures.is_empty() {
+            params.push("i8* %env".to_string());
+        }
+        for (i, ty) in param_llvm_types.iter().enumerate() {
+            params.push(format!("{} %param{}", ty, i));
         }
 
         // 生成 Lambda 函数头
-        self.emit_line(&format!("\ndefine {} @{}({}) {{", llvm_return_type, lambda_name, param_types.join(", ")));
+        if !captures.is_empty() {
+            self.closure_type_decls.push(format!("{} = type {{ {} }}", env_type, env_fields.join(", ")));
+        }
+        self.emit_line(&format!("\ndefine {} @{}({}) {{", llvm_return_type, lambda_name, params.join(", ")));
         self.emit_line("entry:");
 
         // 创建新的作用域
         self.scope_manager.enter_scope();
 
+        // 从环境结构体中取出捕获变量
+        if !captures.is_empty() {
+            let env_ptr = self.new_temp();
+            self.emit_line(&format!("  {} = bitcast i8* %env to {}*", env_ptr, env_type));
+            for (i, (capture, field_type)) in captures.iter().zip(env_fields.iter()).enumerate() {
+                let field_ptr = self.new_temp();
+                self.emit_line(&format!("  {} = getelementptr inbounds {}, {}* {}, i32 0, i32 {}",
+                    field_ptr, env_type, env_type, env_ptr, i));
+                let ty = self.type_to_llvm(&capture.var_type);
+                let llvm_name = self.scope_manager.declare_var(&capture.name, &ty);
+                if capture.by_ref {
+                    // 按引用捕获：变量名直接指向共享存储
+                    self.emit_line(&format!("  %{} = load {}, {}* {}, align 8", llvm_name, field_type, field_type, field_ptr));
+                } else {
+                    let value = self.new_temp();
+                    let align = self.get_type_align(&ty);
+                    self.emit_line(&format!("  {} = load {}, {}* {}, align {}", value, ty, ty, field_ptr, align));
+                    self.emit_line(&format!("  %{} = alloca {}, align {}", llvm_name, ty, align));
+                    self.emit_line(&format!("  store {} {}, {}* %{}, align {}", ty, value, ty, llvm_name, align));
+                }
+                self.bind_lambda_var(&capture.name, &ty, &capture.var_type);
+            }
+        }
+
         // 添加参数到作用域
-        for (name, ty, llvm_name) in &param_names {
-            let local_temp = self.new_temp();
-            self.emit_line(&format!("  {} = alloca {}, align {}", local_temp, ty, self.get_type_align(ty)));
-            self.emit_line(&format!("  store {} {}, {}* {}, align {}", ty, llvm_name, ty, local_temp, self.get_type_align(ty)));
-            self.scope_manager.declare_var(name, ty);
+        for (i, (param, ty)) in lambda.params.iter().zip(param_llvm_types.iter()).enumerate() {
+            let llvm_name = self.scope_manager.declare_var_with_flag(&param.name, ty, true);
+            let align = self.get_type_align(ty);
+            self.emit_line(&format!("  %{} = alloca {}, align {}", llvm_name, ty, align));
+            self.emit_line(&format!("  store {} %param{}, {}* %{}, align {}", ty, i, ty, llvm_name, align));
+            self.bind_lambda_var(&param.name, ty, &param_types[i]);
         }
 
         // 生成 Lambda 体
@@ -68,21 +118,198 @@ impl IRGenerator {
         // 获取 Lambda 函数代码
         let lambda_code = std::mem::take(&mut self.code);
 
-        // 恢复之前的代码缓冲区
+        // 恢复之前的代码生成状态
         self.code = saved_code;
         self.temp_counter = saved_temp_counter;
+        self.current_return_type = saved_return_type;
+        self.var_types = saved_var_types;
+        self.var_cay_types = saved_var_cay_types;
+        self.var_class_map = saved_var_class_map;
+        self.loop_stack = saved_loop_stack;
+        self.cleanup_stack = saved_cleanup_stack;
 
         // 将 Lambda 函数代码存储到全局函数列表
         self.lambda_functions.push(lambda_code);
 
-        // 返回函数指针
+        let fn_type = format!("{} ({})", llvm_return_type, param_llvm_types.join(", "));
+        if captures.is_empty() {
+            return Ok(self.static_closure(&lambda_name, &fn_type));
+        }
+
+        // 构造环境结构体：按值捕获复制当前值，按引用捕获保存变量地址
+        let (env_raw, env_typed) = self.calloc_struct(&env_type);
+        for (i, (capture, field_type)) in captures.iter().zip(env_fields.iter()).enumerate() {
+            let llvm_name = self.scope_manager.get_llvm_name(&capture.name)
+                .ok_or_else(|| crate::error::codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Undefined captured variable: {}", capture.name)))?;
+            let value = if capture.by_ref {
+                format!("%{}", llvm_name)
+            } else {
+                let temp = self.new_temp();
+                self.emit_line(&format!("  {} = load {}, {}* %{}, align {}",
+                    temp, field_type, field_type, llvm_name, self.get_type_align(field_type)));
+                temp
+            };
+            let field_ptr = self.new_temp();
+            self.emit_line(&format!("  {} = getelementptr inbounds {}, {}* {}, i32 0, i32 {}",
+                field_ptr, env_type, env_type, env_typed, i));
+            self.emit_line(&format!("  store {} {}, {}* {}", field_type, value, field_type, field_ptr));
+        }
+
+        // 构造闭包记录 { fn, env }
+        self.ensure_closure_type();
+        let (closure_raw, closure_typed) = self.calloc_struct("%__cay_closure");
+        let fn_ptr = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast {} (i8*{}{})* @{} to i8*", fn_ptr, llvm_return_type,
+            if param_llvm_types.is_empty() { "" } else { ", " }, param_llvm_types.join(", "), lambda_name));
+        for (i, value) in [fn_ptr, env_raw].iter().enumerate() {
+            let field_ptr = self.new_temp();
+            self.emit_line(&format!("  {} = getelementptr inbounds %__cay_closure, %__cay_closure* {}, i32 0, i32 {}",
+                field_ptr, closure_typed, i));
+            self.emit_line(&format!("  store i8* {}, i8** {}", value, field_ptr));
+        }
+
+        Ok(format!("i8* {}", closure_raw))
+    }
+
+    /// 在 Lambda 函数中登记变量的类型信息
+    fn bind_lambda_var(&mut self, name: &str, llvm_type: &str, ty: &Type) {
+        self.var_types.insert(name.to_string(), llvm_type.to_string());
+        let ty = ty.non_null();
+        self.var_cay_types.insert(name.to_string(), ty.clone());
+        if let Type::Object(class_name) = ty {
+            self.var_class_map.insert(name.to_string(), class_name.clone());
+        }
+    }
+
+    /// 在堆上分配一个清零的结构体，返回 (i8* 指针, 结构体指针)
+    fn calloc_struct(&mut self, struct_type: &str) -> (String, String) {
+        let raw = self.calloc_value(struct_type);
+        let typed = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast i8* {} to {}*", typed, raw, struct_type));
+        (raw, typed)
+    }
+
+    /// 在堆上分配一个清零的 `llvm_type` 值，返回 i8* 指针
+    fn calloc_value(&mut self, llvm_type: &str) -> String {
+        // 通过 getelementptr null, 1 计算类型大小
+        let size_ptr = self.new_temp();
+        self.emit_line(&format!("  {} = getelementptr {}, {}* null, i32 1", size_ptr, llvm_type, llvm_type));
+        let size = self.new_temp();
+        self.emit_line(&format!("  {} = ptrtoint {}* {} to i64", size, llvm_type, size_ptr));
+        let raw = self.new_temp();
+        self.emit_line(&format!("  {} = call i8* @calloc(i64 1, i64 {})", raw, size));
+        raw
+    }
+
+    /// 确保闭包记录类型已声明
+    fn ensure_closure_type(&mut self) {
+        if self.closure_globals.insert("%__cay_closure".to_string()) {
+            self.closure_type_decls.push("%__cay_closure = type { i8*, i8* }".to_string());
+        }
+    }
+
+    /// 为不需要环境的函数（静态方法、顶层函数、无捕获 Lambda）生成常量闭包记录
+    ///
+    /// # Arguments
+    /// * `fn_name` - 函数名（不含 `@`）
+    /// * `fn_type` - 函数的 LLVM 类型，如 `i32 (i32, i32)`
+    pub fn static_closure(&mut self, fn_name: &str, fn_type: &str) -> String {
+        self.ensure_closure_type();
+        let global = format!("@__closure.{}", fn_name);
+        if self.closure_globals.insert(global.clone()) {
+            self.lambda_functions.push(format!(
+                "{} = private constant %__cay_closure {{ i8* bitcast ({}* @{} to i8*), i8* null }}\n",
+                global, fn_type, fn_name));
+        }
         let temp = self.new_temp();
-        self.emit_line(&format!("  {} = bitcast {} ({})* @{} to i8*", 
-            temp, llvm_return_type, 
-            param_types.iter().map(|p| p.split_whitespace().next().unwrap_or("i64")).collect::<Vec<_>>().join(", "),
-            lambda_name));
+        self.emit_line(&format!("  {} = bitcast %__cay_closure* {} to i8*", temp, global));
+        format!("i8* {}", temp)
+    }
 
-        Ok(format!("i8* {}", temp))
+    /// 通过闭包调用函数
+    ///
+    /// env 为 null 时按普通函数调用，否则把 env 作为首个实参传入。
+    ///
+    /// # Arguments
+    /// * `closure` - 闭包记录指针（i8* 值）
+    /// * `ret_type` - 返回值的 LLVM 类型
+    /// * `param_types` - 参数的 LLVM 类型
+    /// * `args` - 已转换为参数类型的带类型实参，如 `i32 %t1`
+    pub fn call_closure(&mut self, closure: &str, ret_type: &str, param_types: &[String], args: &[String]) -> String {
+        self.ensure_closure_type();
+        let record = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast i8* {} to %__cay_closure*", record, closure));
+        let mut fields = Vec::new();
+        for i in 0..2 {
+            let field_ptr = self.new_temp();
+            self.emit_line(&format!("  {} = getelementptr inbounds %__cay_closure, %__cay_closure* {}, i32 0, i32 {}",
+                field_ptr, record, i));
+            let field = self.new_temp();
+            self.emit_line(&format!("  {} = load i8*, i8** {}, align 8", field, field_ptr));
+            fields.push(field);
+        }
+        let (fn_ptr, env) = (&fields[0], &fields[1]);
+
+        let env_label = self.new_label("closure.env");
+        let plain_label = self.new_label("closure.plain");
+        let end_label = self.new_label("closure.end");
+        let has_env = self.new_temp();
+        self.emit_line(&format!("  {} = icmp ne i8* {}, null", has_env, env));
+        self.emit_line(&format!("  br i1 {}, label %{}, label %{}", has_env, env_label, plain_label));
+
+        let mut env_params = vec!["i8*".to_string()];
+        env_params.extend(param_types.iter().cloned());
+        let mut env_args = vec![format!("i8* {}", env)];
+        env_args.extend(args.iter().cloned());
+
+        let mut results = Vec::new();
+        for (label, params, call_args) in [(&env_label, &env_params[..], &env_args[..]), (&plain_label, param_types, args)] {
+            self.emit_line(&format!("{}:", label));
+            let typed_fn = self.new_temp();
+            self.emit_line(&format!("  {} = bitcast i8* {} to {} ({})*", typed_fn, fn_ptr, ret_type, params.join(", ")));
+            if ret_type == "void" {
+                self.emit_line(&format!("  call void {}({})", typed_fn, call_args.join(", ")));
+            } else {
+                let result = self.new_temp();
+                self.emit_line(&format!("  {} = call {} {}({})", result, ret_type, typed_fn, call_args.join(", ")));
+                results.push((result, label.clone()));
+            }
+            self.emit_line(&format!("  br label %{}", end_label));
+        }
+
+        self.emit_line(&format!("{}:", end_label));
+        if ret_type == "void" {
+            return "void %dummy".to_string();
+        }
+        let phi = self.new_temp();
+        self.emit_line(&format!("  {} = phi {} [ {}, %{} ], [ {}, %{} ]",
+            phi, ret_type, results[0].0, results[0].1, results[1].0, results[1].1));
+        format!("{} {}", ret_type, phi)
+    }
+
+    /// 取出闭包中的函数指针（传给 C 函数作为回调）
+    pub fn closure_code_ptr(&mut self, closure: &str) -> String {
+        self.ensure_closure_type();
+        let record = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast i8* {} to %__cay_closure*", record, closure));
+        let field_ptr = self.new_temp();
+        self.emit_line(&format!("  {} = getelementptr inbounds %__cay_closure, %__cay_closure* {}, i32 0, i32 0",
+            field_ptr, record));
+        let fn_ptr = self.new_temp();
+        self.emit_line(&format!("  {} = load i8*, i8** {}, align 8", fn_ptr, field_ptr));
+        fn_ptr
+    }
+
+    /// 变量是否被 Lambda 按引用捕获（需要堆分配）
+    pub fn is_boxed_local(&self, var: &VarDecl) -> bool {
+        self.type_registry.as_ref()
+            .is_some_and(|r| r.boxed_locals.contains(&(var.loc.clone(), var.name.clone())))
+    }
+
+    /// 在堆上为局部变量分配存储，`%llvm_name` 与 alloca 一样是指向变量的指针
+    pub fn emit_boxed_alloca(&mut self, llvm_name: &str, var_type: &str) {
+        let raw = self.calloc_value(var_type);
+        self.emit_line(&format!("  %{} = bitcast i8* {} to {}*", llvm_name, raw, var_type));
     }
 
     /// 推断 Lambda 表达式的返回类型
@@ -180,8 +407,11 @@ impl IRGenerator {
                 let val = self.generate_expression(expr)?;
                 let (value_type, val_str) = self.parse_typed_value(&val);
                 
-                // 如果表达式类型与返回类型不匹配，进行转换
-                if value_type != llvm_return_type {
+                // void Lambda 的表达式体只为副作用求值
+                if llvm_return_type == "void" {
+                    self.emit_line("  ret void");
+                } else if value_type != llvm_return_type {
+                    // 如果表达式类型与返回类型不匹配，进行转换
                     let converted = self.convert_type(&val_str, &value_type, llvm_return_type)?;
                     self.emit_line(&format!("  ret {} {}", llvm_return_type, converted));
                 } else {
@@ -192,12 +422,14 @@ impl IRGenerator {
             LambdaBody::Block(block) => {
                 // 生成块中的语句
                 let mut has_return = false;
+                self.enter_cleanup_frame();
                 for stmt in &block.statements {
                     if matches!(stmt, Stmt::Return(_)) {
                         has_return = true;
                     }
                     self.generate_statement(stmt)?;
                 }
+                self.exit_cleanup_frame()?;
                 
                 // 如果没有显式 return，添加默认返回
                 if !has_return {
@@ -298,7 +530,7 @@ impl IRGenerator {
         }
 
         // 其他不支持的转换
-        Err(crate::error::codegen_error(format!(
+        Err(crate::error::codegen_error(ErrorCodes::CODEGEN_TYPE_CONVERSION_ERROR, format!(
             "Unsupported type conversion from {} to {}", from_type, to_type
         )))
     }
@@ -332,39 +564,25 @@ impl IRGenerator {
     /// # Arguments
     /// * `method_ref` - 方法引用表达式
     pub fn generate_method_ref(&mut self, method_ref: &MethodRefExpr) -> cayResult<String> {
-        let temp = self.new_temp();
-
         if let Some(ref class_name) = method_ref.class_name {
-            // 静态方法引用: ClassName::methodName
-            // 尝试从类型注册表获取方法签名
-            let fn_name = format!("{}.{}", class_name, method_ref.method_name);
-            
-            // 尝试推断返回类型和参数类型
-            let (return_type, param_types) = if let Some(ref registry) = self.type_registry {
-                if let Some(method_info) = registry.get_method(class_name, &method_ref.method_name) {
-                    let ret = self.type_to_llvm(&method_info.return_type);
-                    let params: Vec<String> = method_info.params.iter()
-                        .map(|p| self.type_to_llvm(&p.param_type))
-                        .collect();
-                    (ret, params)
-                } else {
-                    ("i64".to_string(), vec!["i64".to_string()])
-                }
-            } else {
-                ("i64".to_string(), vec!["i64".to_string()])
-            };
-
-            let fn_type = format!("{} ({})", return_type, param_types.join(", "));
-            self.emit_line(&format!("  {} = bitcast {}* @{} to i8*", temp, fn_type, fn_name));
-        } else if let Some(_object) = &method_ref.object {
-            // 实例方法引用: obj::methodName
-            // 返回空指针（暂不支持）
-            self.emit_line(&format!("  {} = inttoptr i64 0 to i8*", temp));
-        } else {
-            // 未知类型，返回空指针
-            self.emit_line(&format!("  {} = inttoptr i64 0 to i8*", temp));
+            // 静态方法引用: ClassName::methodName，生成常量闭包记录
+            let method_info = self.type_registry.as_ref()
+                .and_then(|r| r.get_method(class_name, &method_ref.method_name))
+                .cloned();
+            if let Some(method_info) = method_info {
+                let fn_name = self.build_function_name_from_method(class_name, &method_ref.method_name, &method_info.params, false);
+                let ret = self.type_to_llvm(&method_info.return_type);
+                let params: Vec<String> = method_info.params.iter()
+                    .map(|p| self.type_to_llvm(&p.param_type))
+                    .collect();
+                let fn_type = format!("{} ({})", ret, params.join(", "));
+                return Ok(self.static_closure(&fn_name, &fn_type));
+            }
         }
 
+        // 实例方法引用: obj::methodName（暂不支持），返回空指针
+        let temp = self.new_temp();
+        self.emit_line(&format!("  {} = inttoptr i64 0 to i8*", temp));
         Ok(format!("i8* {}", temp))
     }
 }
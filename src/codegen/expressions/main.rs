@@ -21,8 +21,12 @@ impl IRGenerator {
             // 字面量
             Expr::Literal(lit) => self.generate_literal(lit),
             
-            // 标识符（变量访问）
-            Expr::Identifier(name) => self.generate_identifier(name.as_ref()),
+            // 标识符（变量访问），省略枚举名的内置变体 None，或类体中直接用名称访问的属性
+            Expr::Identifier(name) => match (self.builtin_variant_enum(&name.loc), self.property_read_at(&name.loc)) {
+                (Some(enum_name), _) => self.generate_enum_variant_access(&enum_name, name.as_ref()),
+                (None, Some(call)) => self.generate_property_get(None, &call),
+                (None, None) => self.generate_identifier(name.as_ref()),
+            },
             
             // 二元表达式
             Expr::Binary(bin) => self.generate_binary_expression(bin),
@@ -71,6 +75,16 @@ impl IRGenerator {
             
             // 0.5.0.0: 内存释放表达式
             Expr::Dealloc(dealloc) => self.generate_dealloc_expression(dealloc),
+
+            // 错误传播 expr?
+            Expr::Try(try_expr) => self.generate_try_expression(try_expr),
+
+            // switch 表达式
+            Expr::Switch(switch) => self.generate_switch_expression(switch),
+
+            // 安全调用 obj?.member 和 elvis value ?: default
+            Expr::SafeCall(safe_call) => self.generate_safe_call_expression(safe_call),
+            Expr::Elvis(elvis) => self.generate_elvis_expression(elvis),
         }
     }
 }
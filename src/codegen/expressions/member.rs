@@ -40,6 +40,21 @@ impl IRGenerator {
     /// # Arguments
     /// * `member` - 成员访问表达式
     pub fn generate_member_access(&mut self, member: &MemberAccessExpr) -> cayResult<String> {
+        // 带访问器体的属性: obj.area 调用 getter
+        if let Some(call) = self.property_read_at(&member.loc) {
+            return self.generate_property_get(Some(&member.object), &call);
+        }
+
+        // 枚举无负载变体: Color.Red
+        if let Some(enum_name) = self.as_enum_name(&member.object) {
+            return self.generate_enum_variant_access(&enum_name, &member.member);
+        }
+
+        // 结构体字段: p.x
+        if let Some(struct_name) = self.expression_struct_name(&member.object) {
+            return self.generate_struct_field_access(&struct_name, member);
+        }
+
         // 检查是否是类名.静态方法访问: ClassName.methodName
         if let Expr::Identifier(class_name) = &*member.object {
             // 首先检查是否是静态方法访问（返回函数指针）
@@ -65,8 +80,8 @@ impl IRGenerator {
                                 return_type: Box::new(method_info.return_type.clone()),
                                 is_static: true,
                             }));
-                            let llvm_func_type = self.type_to_llvm(&func_type);
-                            return Ok(format!("{} @{}", llvm_func_type, func_name));
+                            let fn_type = self.function_llvm_signature(&func_type);
+                            return Ok(self.static_closure(&func_name, &fn_type));
                         }
                     }
                 }
@@ -117,7 +132,7 @@ impl IRGenerator {
                 self.var_class_map.get(name_str).cloned()
             }
         } else {
-            None
+            self.expression_class_name(&member.object)
         };
         
         // 特殊处理数组的 .length 属性（但优先检查是否是对象的字段）
@@ -10,6 +10,8 @@
 //! - `identifier`: 标识符/变量访问
 //! - `binary`: 二元表达式
 //! - `unary`: 一元表达式
+//! - `operators`: 类的重载运算符
+//! - `integers`: byte、short、uint、ulong 等整数的运算和转换
 //! - `call`: 函数/方法调用
 //! - `builtin`: 内置函数（print/read 等）
 //! - `string_methods`: String 方法调用
@@ -17,10 +19,12 @@
 //! - `cast`: 类型转换
 //! - `member`: 成员访问
 //! - `assignment`: 赋值表达式
+//! - `properties`: 属性访问（访问方法调用）
 //! - `new`: new 表达式
 //! - `lambda`: Lambda 表达式和方法引用
 //! - `ternary`: 三元运算符
 //! - `instanceof`: instanceof 表达式
+//! - `null_safety`: 安全调用 `?.` 和 elvis 运算符 `?:`
 
 // 工具模块（需要最先加载）
 mod utils;
@@ -33,6 +37,8 @@ mod identifier;
 // 运算符
 mod binary;
 mod unary;
+mod operators;
+mod integers;
 
 // 调用相关
 mod call;
@@ -46,12 +52,14 @@ mod array;
 mod cast;
 mod member;
 mod assignment;
+mod properties;
 mod new;
 
 // 高级特性
 mod lambda;
 mod ternary;
 mod instanceof;
+mod null_safety;
 
 // 0.5.0.0: 内存分配器
 mod allocator;
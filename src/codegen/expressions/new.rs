@@ -12,6 +12,11 @@ impl IRGenerator {
     /// # Arguments
     /// * `new_expr` - new 表达式
     pub fn generate_new_expression(&mut self, new_expr: &NewExpr) -> cayResult<String> {
+        // 结构体是值类型，不分配堆内存
+        if self.get_struct_info(&new_expr.class_name).is_some() {
+            return self.generate_struct_construction(new_expr);
+        }
+
         let class_name = &new_expr.class_name;
         let type_id_value = self.get_type_id_value(class_name).unwrap_or(0);
 
@@ -60,6 +65,9 @@ impl IRGenerator {
     
     /// 推断参数类型（返回类型签名）
     fn infer_argument_type(&self, expr: &Expr) -> String {
+        if let Some(enum_type) = self.enum_value_type(expr) {
+            return self.type_to_signature(&enum_type);
+        }
         match expr {
             Expr::Literal(lit) => {
                 match lit {
@@ -77,6 +85,8 @@ impl IRGenerator {
                 // 查找变量类型
                 if let Some(cay_type) = self.var_cay_types.get(&ident.name) {
                     self.type_to_signature(cay_type)
+                } else if ident.name == "this" {
+                    self.type_to_signature(&crate::types::Type::Object(self.current_class.clone()))
                 } else {
                     "i".to_string() // 默认int
                 }
@@ -107,6 +117,8 @@ impl IRGenerator {
                     "i".to_string() // 默认int
                 }
             }
+            Expr::New(inner) if self.get_struct_info(&inner.class_name).is_some() => format!("st{}", inner.class_name),
+            Expr::New(inner) => format!("o{}", inner.class_name),
             _ => "i".to_string(), // 默认int
         }
     }
@@ -155,6 +167,9 @@ impl IRGenerator {
                 // 数组的 length 属性返回 int
                 Some(Type::Int32)
             }
+            Type::Struct(struct_name) => self.get_struct_info(&struct_name)?
+                .find_field(&member.member)
+                .map(|(_, field)| field.param_type.clone()),
             _ => None,
         }
     }
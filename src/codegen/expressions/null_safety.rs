@@ -0,0 +1,138 @@
+//! 安全调用 `?.` 和 elvis 运算符 `?:` 的代码生成
+//!
+//! 两者都先求值左侧并与 null 比较：安全调用在对象非 null 时把对象绑定到临时变量再访问成员，
+//! 否则结果为 null（作为 elvis 的左侧时改为求值默认值）；elvis 在值为 null 时求值默认值。
+//! 各分支结束后跳到只含跳转的块再汇合，phi 的前驱不受分支内部控制流的影响。
+
+use crate::codegen::context::IRGenerator;
+use crate::ast::*;
+use crate::types::{Type, SafeCallInfo};
+use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
+
+impl IRGenerator {
+    /// 生成安全调用 `obj?.member`，对象为 null 时结果为 null
+    pub fn generate_safe_call_expression(&mut self, safe_call: &SafeCallExpr) -> cayResult<String> {
+        self.generate_safe_call_or_default(safe_call, None)
+    }
+
+    /// 生成 elvis 表达式 `value ?: default`
+    pub fn generate_elvis_expression(&mut self, elvis: &ElvisExpr) -> cayResult<String> {
+        if let Expr::SafeCall(safe_call) = elvis.value.as_ref() {
+            return self.generate_safe_call_or_default(safe_call, Some(&elvis.default));
+        }
+        let result_type = self.type_registry.as_ref()
+            .and_then(|registry| registry.elvis_types.get(&elvis.loc).cloned())
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "'?:' 运算符缺少语义分析记录的结果类型"))?;
+        let result_llvm = self.type_to_llvm(&result_type);
+
+        let value = self.generate_expression(&elvis.value)?;
+        let (value_type, value_val) = self.parse_typed_value(&value);
+        let value_val = self.coerce_to_llvm_type(&value_type, &value_val, &result_llvm);
+        let is_null = self.new_temp();
+        self.emit_line(&format!("  {} = icmp eq {} {}, null", is_null, result_llvm, value_val));
+        let present_label = self.new_label("elvis.value");
+        let default_label = self.new_label("elvis.default");
+        let default_done_label = self.new_label("elvis.default.done");
+        let end_label = self.new_label("elvis.end");
+        self.emit_line(&format!("  br i1 {}, label %{}, label %{}", is_null, default_label, present_label));
+
+        self.emit_line(&format!("\n{}:", present_label));
+        self.emit_line(&format!("  br label %{}", end_label));
+
+        self.emit_line(&format!("\n{}:", default_label));
+        let default = self.generate_expression(&elvis.default)?;
+        let (default_type, default_val) = self.parse_typed_value(&default);
+        let default_val = self.coerce_to_llvm_type(&default_type, &default_val, &result_llvm);
+        self.emit_line(&format!("  br label %{}", default_done_label));
+        self.emit_line(&format!("\n{}:", default_done_label));
+        self.emit_line(&format!("  br label %{}", end_label));
+
+        self.emit_line(&format!("\n{}:", end_label));
+        let result = self.new_temp();
+        self.emit_line(&format!("  {} = phi {} [ {}, %{} ], [ {}, %{} ]",
+            result, result_llvm, value_val, present_label, default_val, default_done_label));
+        Ok(format!("{} {}", result_llvm, result))
+    }
+
+    /// 生成安全调用；对象或引用类型的成员值为 null 时求值 `default`，没有默认值时结果为 null
+    fn generate_safe_call_or_default(&mut self, safe_call: &SafeCallExpr, default: Option<&Expr>) -> cayResult<String> {
+        let info: SafeCallInfo = self.type_registry.as_ref()
+            .and_then(|registry| registry.safe_calls.get(&safe_call.loc).cloned())
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "'?.' 运算符缺少语义分析记录的类型"))?;
+        let is_void = info.result == Type::Void;
+        let result_llvm = self.type_to_llvm(&info.result);
+
+        let object = self.generate_expression(&safe_call.object)?;
+        let (object_type, object_val) = self.parse_typed_value(&object);
+        let is_null = self.new_temp();
+        self.emit_line(&format!("  {} = icmp eq {} {}, null", is_null, object_type, object_val));
+        let access_label = self.new_label("safe.access");
+        let value_label = self.new_label("safe.value");
+        let null_label = self.new_label("safe.null");
+        let null_done_label = self.new_label("safe.null.done");
+        let end_label = self.new_label("safe.end");
+        self.emit_line(&format!("  br i1 {}, label %{}, label %{}", is_null, null_label, access_label));
+
+        // 对象非 null: 绑定到临时变量后按普通成员访问生成
+        self.emit_line(&format!("\n{}:", access_label));
+        self.scope_manager.enter_scope();
+        self.bind_safe_call_receiver(safe_call, &info.receiver, &object_type, &object_val);
+        let value = self.generate_expression(&safe_call.access_expr());
+        self.scope_manager.exit_scope();
+        let value = value?;
+        let (value_type, value_val) = self.parse_typed_value(&value);
+        let value_val = if is_void {
+            value_val
+        } else {
+            self.coerce_to_llvm_type(&value_type, &value_val, &result_llvm)
+        };
+        if default.is_some() && result_llvm.ends_with('*') {
+            // 成员本身为 null 时同样使用默认值
+            let value_is_null = self.new_temp();
+            self.emit_line(&format!("  {} = icmp eq {} {}, null", value_is_null, result_llvm, value_val));
+            self.emit_line(&format!("  br i1 {}, label %{}, label %{}", value_is_null, null_label, value_label));
+        } else {
+            self.emit_line(&format!("  br label %{}", value_label));
+        }
+        self.emit_line(&format!("\n{}:", value_label));
+        self.emit_line(&format!("  br label %{}", end_label));
+
+        // 对象为 null: 求值默认值，或以 null 作为结果
+        self.emit_line(&format!("\n{}:", null_label));
+        let null_val = match default {
+            Some(default) => {
+                let default = self.generate_expression(default)?;
+                let (default_type, default_val) = self.parse_typed_value(&default);
+                self.coerce_to_llvm_type(&default_type, &default_val, &result_llvm)
+            }
+            None => "null".to_string(),
+        };
+        self.emit_line(&format!("  br label %{}", null_done_label));
+        self.emit_line(&format!("\n{}:", null_done_label));
+        self.emit_line(&format!("  br label %{}", end_label));
+
+        self.emit_line(&format!("\n{}:", end_label));
+        if is_void {
+            return Ok("void %dummy".to_string());
+        }
+        let result = self.new_temp();
+        self.emit_line(&format!("  {} = phi {} [ {}, %{} ], [ {}, %{} ]",
+            result, result_llvm, value_val, value_label, null_val, null_done_label));
+        Ok(format!("{} {}", result_llvm, result))
+    }
+
+    /// 把安全调用的对象值存入 `receiver_name()` 命名的局部变量
+    fn bind_safe_call_receiver(&mut self, safe_call: &SafeCallExpr, receiver: &Type, object_type: &str, object_val: &str) {
+        let name = safe_call.receiver_name();
+        let align = self.get_type_align(object_type);
+        let llvm_name = self.scope_manager.declare_var(&name, object_type);
+        self.emit_line(&format!("  %{} = alloca {}, align {}", llvm_name, object_type, align));
+        self.emit_line(&format!("  store {} {}, {}* %{}, align {}", object_type, object_val, object_type, llvm_name, align));
+        self.var_types.insert(name.clone(), object_type.to_string());
+        self.var_cay_types.insert(name.clone(), receiver.clone());
+        if let Type::Object(class_name) = receiver {
+            self.var_class_map.insert(name, class_name.clone());
+        }
+    }
+}
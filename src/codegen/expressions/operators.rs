@@ -0,0 +1,126 @@
+//! 重载运算符代码生成
+//!
+//! 语义分析把类实例上的运算符记录为方法调用，这里把它们降级为普通的实例方法调用：
+//! `a + b` 即 `a.plus(b)`，`a != b` 即 `!a.equals(b)`（任一侧为 null 时比较引用），`a < b` 即 `a.compareTo(b) < 0`，
+//! `a[i] = v` 调用 `a.set(i, v)` 并以 `v` 作为赋值表达式的值。
+
+use crate::codegen::context::IRGenerator;
+use crate::ast::*;
+use crate::types::{MethodInfo, OperatorCall};
+use crate::error::{cayResult, SourceLocation};
+
+impl IRGenerator {
+    /// 语义分析在该位置记录的重载运算符
+    pub fn operator_call_at(&self, loc: &SourceLocation) -> Option<OperatorCall> {
+        self.type_registry.as_ref()
+            .and_then(|registry| registry.operator_calls.get(loc).cloned())
+    }
+
+    /// 生成二元重载运算符: a + b、a == b、a < b
+    pub fn generate_binary_operator_call(&mut self, bin: &BinaryExpr, call: &OperatorCall) -> cayResult<String> {
+        if matches!(bin.op, BinaryOp::Eq | BinaryOp::Ne) {
+            return self.generate_equality_operator_call(bin, call);
+        }
+        let result = self.generate_operator_method_call(&bin.left, &call.method, &[bin.right.as_ref()])?;
+        let (result_type, result_val) = self.parse_typed_value(&result);
+        let predicate = match bin.op {
+            BinaryOp::Ne => {
+                let temp = self.new_temp();
+                self.emit_line(&format!("  {} = xor i1 {}, true", temp, result_val));
+                return Ok(format!("i1 {}", temp));
+            }
+            BinaryOp::Lt => "slt",
+            BinaryOp::Le => "sle",
+            BinaryOp::Gt => "sgt",
+            BinaryOp::Ge => "sge",
+            _ => return Ok(result),
+        };
+        // compareTo 的结果与 0 比较
+        let temp = self.new_temp();
+        self.emit_line(&format!("  {} = icmp {} {} {}, 0", temp, predicate, result_type, result_val));
+        Ok(format!("i1 {}", temp))
+    }
+
+    /// 生成 `a == b`、`a != b`：两侧都为 null 时相等，只有一侧为 null 时不等，否则调用 `a.equals(b)`
+    fn generate_equality_operator_call(&mut self, bin: &BinaryExpr, call: &OperatorCall) -> cayResult<String> {
+        let receiver = self.generate_expression(&bin.left)?;
+        let argument = self.generate_expression(&bin.right)?;
+        let (receiver_type, receiver_val) = self.parse_typed_value(&receiver);
+        let (argument_type, argument_val) = self.parse_typed_value(&argument);
+
+        let left_null = self.new_temp();
+        self.emit_line(&format!("  {} = icmp eq {} {}, null", left_null, receiver_type, receiver_val));
+        let right_null = if argument_type.ends_with('*') {
+            let temp = self.new_temp();
+            self.emit_line(&format!("  {} = icmp eq {} {}, null", temp, argument_type, argument_val));
+            temp
+        } else {
+            "false".to_string()
+        };
+        let any_null = self.new_temp();
+        self.emit_line(&format!("  {} = or i1 {}, {}", any_null, left_null, right_null));
+        let null_label = self.new_label("eq.null");
+        let call_label = self.new_label("eq.call");
+        let call_done_label = self.new_label("eq.call.done");
+        let end_label = self.new_label("eq.end");
+        self.emit_line(&format!("  br i1 {}, label %{}, label %{}", any_null, null_label, call_label));
+
+        self.emit_line(&format!("\n{}:", null_label));
+        let both_null = self.new_temp();
+        self.emit_line(&format!("  {} = and i1 {}, {}", both_null, left_null, right_null));
+        self.emit_line(&format!("  br label %{}", end_label));
+
+        self.emit_line(&format!("\n{}:", call_label));
+        let result = self.emit_operator_method_call(&receiver, &call.method, &[argument]);
+        let (_, result_val) = self.parse_typed_value(&result);
+        self.emit_line(&format!("  br label %{}", call_done_label));
+        self.emit_line(&format!("\n{}:", call_done_label));
+        self.emit_line(&format!("  br label %{}", end_label));
+
+        self.emit_line(&format!("\n{}:", end_label));
+        let equal = self.new_temp();
+        self.emit_line(&format!("  {} = phi i1 [ {}, %{} ], [ {}, %{} ]",
+            equal, both_null, null_label, result_val, call_done_label));
+        if bin.op == BinaryOp::Ne {
+            let temp = self.new_temp();
+            self.emit_line(&format!("  {} = xor i1 {}, true", temp, equal));
+            return Ok(format!("i1 {}", temp));
+        }
+        Ok(format!("i1 {}", equal))
+    }
+
+    /// 生成一元负号重载: -a
+    pub fn generate_unary_operator_call(&mut self, unary: &UnaryExpr, call: &OperatorCall) -> cayResult<String> {
+        self.generate_operator_method_call(&unary.operand, &call.method, &[])
+    }
+
+    /// 生成下标读取重载: a[i]
+    pub fn generate_index_operator_call(&mut self, arr: &ArrayAccessExpr, call: &OperatorCall) -> cayResult<String> {
+        self.generate_operator_method_call(&arr.array, &call.method, &[arr.index.as_ref()])
+    }
+
+    /// 生成下标赋值重载: a[i] = v，表达式的值为 v
+    pub fn generate_index_assignment_call(&mut self, arr: &ArrayAccessExpr, value: &Expr, call: &OperatorCall) -> cayResult<String> {
+        let receiver = self.generate_expression(&arr.array)?;
+        let index = self.generate_expression(&arr.index)?;
+        let value_result = self.generate_expression(value)?;
+        self.emit_operator_method_call(&receiver, &call.method, &[index, value_result.clone()]);
+        Ok(value_result)
+    }
+
+    /// 依次求值接收者和参数，然后调用 receiver.method(args)
+    fn generate_operator_method_call(&mut self, receiver: &Expr, method: &MethodInfo, args: &[&Expr]) -> cayResult<String> {
+        let receiver = self.generate_expression(receiver)?;
+        let mut arg_results = Vec::new();
+        for arg in args {
+            arg_results.push(self.generate_expression(arg)?);
+        }
+        Ok(self.emit_operator_method_call(&receiver, method, &arg_results))
+    }
+
+    /// 直接调用语义分析选定的方法
+    fn emit_operator_method_call(&mut self, receiver: &str, method: &MethodInfo, args: &[String]) -> String {
+        let callee = format!("@{}", self.build_function_name_from_method(&method.class_name, &method.name, &method.params, false));
+        self.emit_resolved_method_call(&callee, Some(receiver), method, args)
+    }
+}
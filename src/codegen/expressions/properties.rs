@@ -0,0 +1,86 @@
+//! 属性访问代码生成
+//!
+//! 语义分析把带访问器体的属性读写记录为访问方法调用：`obj.area` 即 `obj.getArea()`，
+//! `obj.width = v` 调用 `obj.setWidth(v)` 并以 `v` 作为赋值表达式的值。
+//! 自动属性没有记录，按同名字段直接读写。类体中直接用名称访问的属性以 `this` 作为接收者。
+
+use crate::codegen::context::IRGenerator;
+use crate::ast::*;
+use crate::types::PropertyCall;
+use crate::error::{cayResult, codegen_error, SourceLocation};
+use crate::diagnostic::ErrorCodes;
+
+impl IRGenerator {
+    /// 语义分析在该位置记录的属性读取（getter 调用）
+    pub fn property_read_at(&self, loc: &SourceLocation) -> Option<PropertyCall> {
+        self.type_registry.as_ref()
+            .and_then(|registry| registry.property_reads.get(loc).cloned())
+    }
+
+    /// 语义分析在该赋值位置记录的属性赋值（setter 调用）
+    pub fn property_write_at(&self, loc: &SourceLocation) -> Option<PropertyCall> {
+        self.type_registry.as_ref()
+            .and_then(|registry| registry.property_writes.get(loc).cloned())
+    }
+
+    /// 生成属性读取: obj.name 即 obj.getName()；`object` 为 None 表示类体中直接用名称访问（隐式 this）
+    pub fn generate_property_get(&mut self, object: Option<&Expr>, call: &PropertyCall) -> cayResult<String> {
+        let receiver = self.generate_property_receiver(object, call)?;
+        Ok(self.emit_property_method_call(receiver.as_deref(), call, &[]))
+    }
+
+    /// 生成属性赋值: obj.name = v 即 obj.setName(v)
+    ///
+    /// 接收者只求值一次；复合赋值在同一个接收者上先调用 getter 读取当前值，再调用 setter。
+    pub fn generate_property_set(&mut self, object: Option<&Expr>, assign: &AssignmentExpr, call: &PropertyCall) -> cayResult<String> {
+        let op = match assign.op {
+            AssignOp::Assign => None,
+            AssignOp::AddAssign => Some(BinaryOp::Add),
+            AssignOp::SubAssign => Some(BinaryOp::Sub),
+            AssignOp::MulAssign => Some(BinaryOp::Mul),
+            AssignOp::DivAssign => Some(BinaryOp::Div),
+            AssignOp::ModAssign => Some(BinaryOp::Mod),
+        };
+        let receiver = self.generate_property_receiver(object, call)?;
+        let mut value_result = match op {
+            Some(op) => {
+                // 语义分析在赋值目标的位置记录 getter 调用
+                let target_loc = match assign.target.as_ref() {
+                    Expr::MemberAccess(member) => &member.loc,
+                    Expr::Identifier(ident) => &ident.loc,
+                    _ => &assign.loc,
+                };
+                let getter = self.property_read_at(target_loc).ok_or_else(|| codegen_error(
+                    ErrorCodes::CODEGEN_INTERNAL_ERROR,
+                    format!("No getter recorded for compound assignment to property '{}'", call.method.name),
+                ))?;
+                let current = self.emit_property_method_call(receiver.as_deref(), &getter, &[]);
+                let right = self.generate_expression(&assign.value)?;
+                self.generate_binary_values(&op, &current, &right)?
+            }
+            None => self.generate_expression(&assign.value)?,
+        };
+        let property_llvm = self.type_to_llvm(&call.property_type);
+        value_result = self.widen_unsigned_value(&assign.value, &value_result, &property_llvm);
+        self.emit_property_method_call(receiver.as_deref(), call, std::slice::from_ref(&value_result));
+        Ok(value_result)
+    }
+
+    /// 实例属性求值接收者，静态属性没有接收者
+    fn generate_property_receiver(&mut self, object: Option<&Expr>, call: &PropertyCall) -> cayResult<Option<String>> {
+        if call.method.is_static {
+            return Ok(None);
+        }
+        match object {
+            Some(object) => self.generate_expression(object).map(Some),
+            None => self.generate_identifier("this").map(Some),
+        }
+    }
+
+    /// 直接调用语义分析选定的访问方法
+    fn emit_property_method_call(&mut self, receiver: Option<&str>, call: &PropertyCall, args: &[String]) -> String {
+        let method = &call.method;
+        let callee = format!("@{}", self.build_function_name_from_method(&method.class_name, &method.name, &method.params, false));
+        self.emit_resolved_method_call(&callee, receiver, method, args)
+    }
+}
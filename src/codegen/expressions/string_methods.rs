@@ -1,10 +1,11 @@
 //! String 方法调用代码生成
 //!
-//! 处理 String 类型的方法调用（length, substring, indexOf, charAt, replace）。
+//! 处理 String 类型的方法调用（length, substring, indexOf, charAt, replace, hashCode 等）。
 
 use crate::codegen::context::IRGenerator;
 use crate::ast::*;
 use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 尝试生成 String 方法调用代码
@@ -14,6 +15,15 @@ impl IRGenerator {
     /// * `member` - 成员访问表达式
     /// * `args` - 参数列表
     pub fn try_generate_string_method_call(&mut self, member: &MemberAccessExpr, args: &[Expr]) -> cayResult<Option<String>> {
+        // 对象和字符串同为 i8*，类自己声明的同名方法（equals、hashCode 等）不按字符串方法处理
+        if let Some(crate::types::Type::Object(class_name)) = self.get_expression_type(&member.object) {
+            let declares_method = self.type_registry.as_ref()
+                .is_some_and(|registry| registry.get_method(&class_name, &member.member).is_some());
+            if declares_method {
+                return Ok(None);
+            }
+        }
+
         // 生成对象表达式（字符串）
         let obj_result = self.generate_expression(&member.object)?;
         let (obj_type, obj_val) = self.parse_typed_value(&obj_result);
@@ -30,7 +40,7 @@ impl IRGenerator {
             "length" => {
                 // length() - 无参数，返回 i32
                 if !args.is_empty() {
-                    return Err(codegen_error("String.length() takes no arguments".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.length() takes no arguments".to_string()));
                 }
                 self.emit_line(&format!("  {} = call i32 @__cay_string_length(i8* {})",
                     temp, obj_val));
@@ -39,7 +49,7 @@ impl IRGenerator {
             "substring" => {
                 // substring(beginIndex) 或 substring(beginIndex, endIndex)
                 if args.is_empty() || args.len() > 2 {
-                    return Err(codegen_error("String.substring() takes 1 or 2 arguments".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.substring() takes 1 or 2 arguments".to_string()));
                 }
 
                 // 生成 beginIndex 参数
@@ -79,14 +89,14 @@ impl IRGenerator {
             "indexOf" => {
                 // indexOf(substr) - 返回子串首次出现的位置
                 if args.len() != 1 {
-                    return Err(codegen_error("String.indexOf() takes 1 argument".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.indexOf() takes 1 argument".to_string()));
                 }
 
                 let substr_result = self.generate_expression(&args[0])?;
                 let (substr_type, substr_val) = self.parse_typed_value(&substr_result);
 
                 if substr_type != "i8*" {
-                    return Err(codegen_error("String.indexOf() argument must be a string".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.indexOf() argument must be a string".to_string()));
                 }
 
                 self.emit_line(&format!("  {} = call i32 @__cay_string_indexof(i8* {}, i8* {})",
@@ -96,14 +106,14 @@ impl IRGenerator {
             "lastIndexOf" => {
                 // lastIndexOf(substr) - 返回子串最后一次出现的位置
                 if args.len() != 1 {
-                    return Err(codegen_error("String.lastIndexOf() takes 1 argument".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.lastIndexOf() takes 1 argument".to_string()));
                 }
 
                 let substr_result = self.generate_expression(&args[0])?;
                 let (substr_type, substr_val) = self.parse_typed_value(&substr_result);
 
                 if substr_type != "i8*" {
-                    return Err(codegen_error("String.lastIndexOf() argument must be a string".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.lastIndexOf() argument must be a string".to_string()));
                 }
 
                 self.emit_line(&format!("  {} = call i32 @__cay_string_lastindexof(i8* {}, i8* {})",
@@ -113,7 +123,7 @@ impl IRGenerator {
             "charAt" => {
                 // charAt(index) - 返回指定位置的字符
                 if args.len() != 1 {
-                    return Err(codegen_error("String.charAt() takes 1 argument".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.charAt() takes 1 argument".to_string()));
                 }
 
                 let index_result = self.generate_expression(&args[0])?;
@@ -133,7 +143,7 @@ impl IRGenerator {
             "replace" => {
                 // replace(oldStr, newStr) - 替换所有出现的子串
                 if args.len() != 2 {
-                    return Err(codegen_error("String.replace() takes 2 arguments".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.replace() takes 2 arguments".to_string()));
                 }
 
                 let old_result = self.generate_expression(&args[0])?;
@@ -142,7 +152,7 @@ impl IRGenerator {
                 let (new_type, new_val) = self.parse_typed_value(&new_result);
 
                 if old_type != "i8*" || new_type != "i8*" {
-                    return Err(codegen_error("String.replace() arguments must be strings".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.replace() arguments must be strings".to_string()));
                 }
 
                 self.emit_line(&format!("  {} = call i8* @__cay_string_replace(i8* {}, i8* {}, i8* {})",
@@ -152,23 +162,32 @@ impl IRGenerator {
             "isEmpty" => {
                 // isEmpty() - 无参数，返回 boolean (i1)
                 if !args.is_empty() {
-                    return Err(codegen_error("String.isEmpty() takes no arguments".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.isEmpty() takes no arguments".to_string()));
                 }
                 self.emit_line(&format!("  {} = call i1 @__cay_string_isempty(i8* {})",
                     temp, obj_val));
                 Ok(Some(format!("i1 {}", temp)))
             }
+            "hashCode" => {
+                // hashCode() - 无参数，返回 i32，null 的哈希值为 0
+                if !args.is_empty() {
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.hashCode() takes no arguments".to_string()));
+                }
+                self.emit_line(&format!("  {} = call i32 @__cay_string_hash(i8* {})",
+                    temp, obj_val));
+                Ok(Some(format!("i32 {}", temp)))
+            }
             "equals" => {
                 // equals(other) - 比较两个字符串是否相等，返回 boolean (i1)
                 if args.len() != 1 {
-                    return Err(codegen_error("String.equals() takes 1 argument".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.equals() takes 1 argument".to_string()));
                 }
 
                 let other_result = self.generate_expression(&args[0])?;
                 let (other_type, other_val) = self.parse_typed_value(&other_result);
 
                 if other_type != "i8*" {
-                    return Err(codegen_error("String.equals() argument must be a string".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.equals() argument must be a string".to_string()));
                 }
 
                 self.emit_line(&format!("  {} = call i1 @__cay_string_equals(i8* {}, i8* {})",
@@ -179,7 +198,7 @@ impl IRGenerator {
                 // c_str() - 返回C字符串指针 (i8*)
                 // 在Cavvy中，String本身就是i8*，所以直接返回
                 if !args.is_empty() {
-                    return Err(codegen_error("String.c_str() takes no arguments".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.c_str() takes no arguments".to_string()));
                 }
                 // String在Cavvy内部就是i8*，直接返回对象值
                 Ok(Some(format!("i8* {}", obj_val)))
@@ -187,14 +206,14 @@ impl IRGenerator {
             "startsWith" => {
                 // startsWith(prefix) - 检查字符串是否以指定前缀开头
                 if args.len() != 1 {
-                    return Err(codegen_error("String.startsWith() takes 1 argument".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.startsWith() takes 1 argument".to_string()));
                 }
 
                 let prefix_result = self.generate_expression(&args[0])?;
                 let (prefix_type, prefix_val) = self.parse_typed_value(&prefix_result);
 
                 if prefix_type != "i8*" {
-                    return Err(codegen_error("String.startsWith() argument must be a string".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.startsWith() argument must be a string".to_string()));
                 }
 
                 self.emit_line(&format!("  {} = call i1 @__cay_string_startswith(i8* {}, i8* {})",
@@ -204,14 +223,14 @@ impl IRGenerator {
             "endsWith" => {
                 // endsWith(suffix) - 检查字符串是否以指定后缀结尾
                 if args.len() != 1 {
-                    return Err(codegen_error("String.endsWith() takes 1 argument".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.endsWith() takes 1 argument".to_string()));
                 }
 
                 let suffix_result = self.generate_expression(&args[0])?;
                 let (suffix_type, suffix_val) = self.parse_typed_value(&suffix_result);
 
                 if suffix_type != "i8*" {
-                    return Err(codegen_error("String.endsWith() argument must be a string".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "String.endsWith() argument must be a string".to_string()));
                 }
 
                 self.emit_line(&format!("  {} = call i1 @__cay_string_endswith(i8* {}, i8* {})",
@@ -17,7 +17,8 @@ impl IRGenerator {
         let else_label = self.new_label("ternary.else");
         let end_label = self.new_label("ternary.end");
 
-        // 生成条件表达式
+        // 生成条件表达式，其中的模式变量在两个分支中都可能被读取
+        self.declare_pattern_slots(&ternary.condition);
         let cond_result = self.generate_expression(&ternary.condition)?;
         let (cond_type, cond_val) = self.parse_typed_value(&cond_result);
         let cond_reg = self.new_temp();
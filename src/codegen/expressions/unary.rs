@@ -5,6 +5,7 @@
 use crate::codegen::context::IRGenerator;
 use crate::ast::*;
 use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 生成一元表达式代码
@@ -12,6 +13,9 @@ impl IRGenerator {
     /// # Arguments
     /// * `unary` - 一元表达式
     pub fn generate_unary_expression(&mut self, unary: &UnaryExpr) -> cayResult<String> {
+        if let Some(call) = self.operator_call_at(&unary.loc) {
+            return self.generate_unary_operator_call(unary, &call);
+        }
         let operand = self.generate_expression(&unary.operand)?;
         let (op_type, op_val) = self.parse_typed_value(&operand);
         let temp = self.new_temp();
@@ -45,7 +49,7 @@ impl IRGenerator {
                         temp, op_type, op_val));
                 } else {
                     // 浮点数不支持位取反，但类型系统应该已经阻止了这种情况
-                    return Err(codegen_error("Bitwise NOT not supported for floating point".to_string()));
+                    return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, "Bitwise NOT not supported for floating point".to_string()));
                 }
             }
             UnaryOp::PreInc | UnaryOp::PostInc | UnaryOp::PreDec | UnaryOp::PostDec => {
@@ -160,7 +164,7 @@ impl IRGenerator {
         // 解析指针类型，获取指向的类型
         // op_type 应该是 "i32*" 或 "i64*" 等格式
         if !op_type.ends_with('*') {
-            return Err(codegen_error(format!("Cannot dereference non-pointer type: {}", op_type)));
+            return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERAND, format!("Cannot dereference non-pointer type: {}", op_type)));
         }
         
         // 提取指向的类型（去掉末尾的*）
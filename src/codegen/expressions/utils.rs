@@ -5,6 +5,7 @@
 use crate::codegen::context::IRGenerator;
 use crate::ast::*;
 use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 提升整数操作数到相同类型
@@ -169,7 +170,7 @@ impl IRGenerator {
                     }
                     // 回退到旧系统
                     let var_type = self.var_types.get(name_str)
-                        .ok_or_else(|| codegen_error(format!("Variable '{}' not found", name_str)))?
+                        .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Variable '{}' not found", name_str)))?
                         .clone();
                     (var_type, name_str.to_string(), false)
                 };
@@ -184,7 +185,7 @@ impl IRGenerator {
                 let (ty, ptr) = self.get_member_field_pointer(member)?;
                 Ok((ty, ptr, false))
             }
-            _ => Err(codegen_error("Invalid lvalue expression".to_string()))
+            _ => Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, "Invalid lvalue expression".to_string()))
         }
     }
     
@@ -238,6 +239,7 @@ impl IRGenerator {
             "i8*" => "s".to_string(),
             "i8" => "c".to_string(),
             t if t.ends_with("*") => "o".to_string(), // 对象/数组指针
+            t if Self::llvm_struct_name(t).is_some() => format!("st{}", &t["%struct.".len()..]),
             _ => "x".to_string(), // 未知类型
         }
     }
@@ -255,11 +257,24 @@ impl IRGenerator {
     /// 获取成员字段的指针（用于左值）
     ///
     /// # Arguments
-    /// * `member` - 成员访问表达式（如 this.sp）
+    /// * `member` - 成员访问表达式（如 this.sp、Counter.total）
     ///
     /// # Returns
     /// (LLVM类型字符串, 指针字符串)
     pub fn get_member_field_pointer(&mut self, member: &MemberAccessExpr) -> cayResult<(String, String)> {
+        // 静态字段: ClassName.fieldName
+        if let Expr::Identifier(class_name) = member.object.as_ref() {
+            let static_key = format!("{}.{}", class_name, member.member);
+            if let Some(field_info) = self.static_field_map.get(&static_key) {
+                return Ok((field_info.llvm_type.clone(), field_info.name.clone()));
+            }
+        }
+
+        // 结构体字段: p.x、pts[i].x
+        if let Some(struct_name) = self.expression_struct_name(&member.object) {
+            return self.struct_field_pointer(&struct_name, member);
+        }
+
         // 确定对象所属的类
         let class_name_opt: Option<String> = if let Expr::Identifier(name) = member.object.as_ref() {
             let name_str = name.as_ref();
@@ -269,7 +284,7 @@ impl IRGenerator {
                 self.var_class_map.get(name_str).cloned()
             }
         } else {
-            None
+            self.expression_class_name(&member.object)
         };
 
         if let Some(class_name) = class_name_opt {
@@ -305,7 +320,7 @@ impl IRGenerator {
             }
         }
 
-        Err(codegen_error(format!("Cannot get field pointer for member access: {}", member.member)))
+        Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, format!("Cannot get field pointer for member access: {}", member.member)))
     }
 
 }
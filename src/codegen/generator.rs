@@ -87,6 +87,10 @@ impl IRGenerator {
     pub fn generate(&mut self, program: &Program, source_file: &str) -> cayResult<String> {
         // 设置源文件路径
         self.source_file = source_file.to_string();
+
+        // 泛型单态化：展开后的程序不再包含类型参数
+        let monomorphized = self.monomorphize(program)?;
+        let program = monomorphized.as_ref().unwrap_or(program);
         
         self.emit_header();
 
@@ -183,7 +187,10 @@ impl IRGenerator {
         }
 
         self.emit_static_field_declarations();
+        self.emit_enum_declarations();
+        self.emit_struct_declarations();
         self.register_type_identifiers(program);
+        self.emit_interface_tables(program);
 
         // 生成 extern 函数声明
         for extern_decl in &program.extern_declarations {
@@ -198,6 +205,7 @@ impl IRGenerator {
         for class in &program.classes {
             self.generate_class(class)?;
         }
+        self.generate_interface_methods(program)?;
 
         self.output.push_str(&self.code);
 
@@ -357,6 +365,11 @@ impl IRGenerator {
             .unwrap_or(output.len());
 
         let mut decls = String::new();
+        // 类型须在 getelementptr 使用前声明
+        for type_decl in &self.closure_type_decls {
+            decls.push_str(type_decl);
+            decls.push('\n');
+        }
         if !type_id_decls.is_empty() {
             decls.push_str(&type_id_decls);
             decls.push_str("\n");
@@ -403,7 +416,7 @@ impl IRGenerator {
         } else {
             base_llvm_type
         };
-        let size = field.field_type.size_in_bytes();
+        let size = self.value_size_in_bytes(&field.field_type);
 
         let field_info = crate::codegen::context::StaticFieldInfo {
             name: full_name.clone(),
@@ -432,11 +445,7 @@ impl IRGenerator {
         for field in fields {
             let align = self.get_type_align(&field.llvm_type);
             
-            let init_value = if let Some(init) = &field.initializer {
-                self.evaluate_const_initializer(init, &field.llvm_type)
-            } else {
-                None
-            };
+            let init_value = self.static_field_initializer(&field);
             
             if let Some(val) = init_value {
                 self.emit_raw(&format!(
@@ -464,48 +473,43 @@ impl IRGenerator {
         }
     }
 
-    fn evaluate_const_initializer(&self, expr: &Expr, llvm_type: &str) -> Option<String> {
-        match expr {
-            Expr::Literal(crate::ast::LiteralValue::Int32(n)) => Some(n.to_string()),
-            Expr::Literal(crate::ast::LiteralValue::Int64(n)) => Some(n.to_string()),
-            Expr::Literal(crate::ast::LiteralValue::Float32(f)) => {
-                if f.is_nan() {
-                    Some("0x7FC00000".to_string())
-                } else if f.is_infinite() {
-                    if *f > 0.0 {
-                        Some("0x7F800000".to_string())
-                    } else {
-                        Some("0xFF800000".to_string())
-                    }
-                } else {
-                    Some(format!("{:.6e}", f))
-                }
-            }
-            Expr::Literal(crate::ast::LiteralValue::Float64(f)) => {
-                if f.is_nan() {
-                    Some("0x7FF8000000000000".to_string())
-                } else if f.is_infinite() {
-                    if *f > 0.0 {
-                        Some("0x7FF0000000000000".to_string())
-                    } else {
-                        Some("0xFFF0000000000000".to_string())
-                    }
-                } else {
-                    Some(format!("{:.6e}", f))
-                }
-            }
-            Expr::Literal(crate::ast::LiteralValue::Bool(b)) => Some(if *b { "1".to_string() } else { "0".to_string() }),
-            Expr::Binary(binary) => {
-                let left = self.evaluate_const_int(&binary.left)?;
-                let right = self.evaluate_const_int(&binary.right)?;
-                let result = match binary.op {
-                    crate::ast::BinaryOp::Add => left + right,
-                    crate::ast::BinaryOp::Sub => left - right,
-                    crate::ast::BinaryOp::Mul => left * right,
-                    crate::ast::BinaryOp::Div => if right != 0 { left / right } else { return None },
-                    _ => return None,
-                };
-                Some(result.to_string())
+    /// 静态字段初始值的 LLVM 常量；语义分析已把可在编译期求值的初始值折叠为字面量
+    fn static_field_initializer(&mut self, field: &crate::codegen::context::StaticFieldInfo) -> Option<String> {
+        let key = format!("{}.{}", field.class_name, field.field_name);
+        let value = match self.type_registry.as_ref().and_then(|r| r.static_constants.get(&key)) {
+            Some(value) => value.clone(),
+            None => match &field.initializer {
+                Some(Expr::Literal(value)) => value.clone(),
+                _ => return None,
+            },
+        };
+        self.llvm_constant(&value, &field.llvm_type)
+    }
+
+    /// 把编译期常量转换为指定 LLVM 类型的常量，类型不符时返回 None
+    fn llvm_constant(&mut self, value: &crate::ast::LiteralValue, llvm_type: &str) -> Option<String> {
+        use crate::ast::LiteralValue;
+        let integer = match value {
+            LiteralValue::Int32(n) => Some(*n as i64),
+            LiteralValue::Int64(n) => Some(*n),
+            LiteralValue::Char(c) => Some((*c as u8) as i8 as i64),
+            _ => None,
+        };
+        let float = match value {
+            LiteralValue::Float32(f) => Some(*f as f64),
+            LiteralValue::Float64(f) => Some(*f),
+            _ => integer.map(|n| n as f64),
+        };
+        match (llvm_type, value) {
+            ("i1", LiteralValue::Bool(b)) => Some(if *b { "1".to_string() } else { "0".to_string() }),
+            ("i8" | "i16" | "i32" | "i64", _) => integer.map(|n| n.to_string()),
+            // 浮点常量使用 double 的十六进制表示，float 先舍入到单精度
+            ("float", _) => float.map(|f| format!("0x{:016X}", (f as f32 as f64).to_bits())),
+            ("double", _) => float.map(|f| format!("0x{:016X}", f.to_bits())),
+            ("i8*", LiteralValue::String(s)) => {
+                let global_name = self.get_or_create_string_constant(s);
+                let len = s.len() + 1;
+                Some(format!("getelementptr inbounds ([{} x i8], [{} x i8]* {}, i64 0, i64 0)", len, len, global_name))
             }
             _ => None,
         }
@@ -518,7 +522,8 @@ impl IRGenerator {
                 if let Some(init) = &field.initializer {
                     if let Expr::ArrayCreation(array_creation) = init {
                         if !array_creation.sizes.is_empty() {
-                            if let Some(size_val) = self.evaluate_const_int(&array_creation.sizes[0]) {
+                            let size = self.type_registry.as_ref().and_then(|r| r.array_sizes.get(&array_creation.loc).copied());
+                            if let Some(size_val) = size {
                                 let elem_llvm_type = self.type_to_llvm(elem_type);
                                 let elem_size = self.get_type_size(&elem_llvm_type);
                                 // 包含8字节头部（长度+填充）+ 数据
@@ -568,25 +573,6 @@ impl IRGenerator {
         }
     }
 
-    fn evaluate_const_int(&self, expr: &Expr) -> Option<i64> {
-        match expr {
-            Expr::Literal(crate::ast::LiteralValue::Int32(n)) => Some(*n as i64),
-            Expr::Literal(crate::ast::LiteralValue::Int64(n)) => Some(*n),
-            Expr::Binary(binary) => {
-                let left = self.evaluate_const_int(&binary.left)?;
-                let right = self.evaluate_const_int(&binary.right)?;
-                match binary.op {
-                    crate::ast::BinaryOp::Add => Some(left + right),
-                    crate::ast::BinaryOp::Sub => Some(left - right),
-                    crate::ast::BinaryOp::Mul => Some(left * right),
-                    crate::ast::BinaryOp::Div => if right != 0 { Some(left / right) } else { None },
-                    _ => None,
-                }
-            }
-            _ => None,
-        }
-    }
-
     fn get_type_size(&self, llvm_type: &str) -> i64 {
         match llvm_type {
             "i1" => 1,
@@ -595,6 +581,7 @@ impl IRGenerator {
             "i64" => 8,
             "float" => 4,
             "double" => 8,
+            t if Self::llvm_struct_name(t).is_some() => self.llvm_type_size_align(t).0 as i64,
             _ => 8,
         }
     }
@@ -669,6 +656,7 @@ impl IRGenerator {
                 ClassMember::StaticInitializer(block) => {
                     self.generate_static_initializer(&class.name, block)?;
                 }
+                ClassMember::Class(_) | ClassMember::Property(_) => {}
             }
         }
         
@@ -680,7 +668,7 @@ impl IRGenerator {
         Ok(())
     }
 
-    fn generate_method(&mut self, class_name: &str, method: &MethodDecl) -> cayResult<()> {
+    pub(super) fn generate_method(&mut self, class_name: &str, method: &MethodDecl) -> cayResult<()> {
         // 跳过 native 方法的定义（它们在运行时或由外部提供）
         if method.modifiers.contains(&Modifier::Native) {
             return Ok(());
@@ -695,6 +683,7 @@ impl IRGenerator {
         self.var_types.clear();
         self.scope_manager.reset();
         self.loop_stack.clear();
+        self.cleanup_stack.clear();
         
         // 设置当前函数参数顺序（用于内联IR）
         self.current_param_order = method.params.iter().map(|p| p.name.clone()).collect();
@@ -703,10 +692,12 @@ impl IRGenerator {
         let is_static = method.modifiers.contains(&Modifier::Static);
         
         let mut params: Vec<String> = Vec::new();
+        let mut param_types: Vec<String> = Vec::new();
         
         // 实例方法添加 this 参数
         if !is_static {
             params.push("i8* %this".to_string());
+            param_types.push("i8*".to_string());
         }
         
         for param in &method.params {
@@ -717,10 +708,11 @@ impl IRGenerator {
                 self.type_to_llvm(&param.param_type)
             };
             params.push(format!("{} %{}.{}", param_llvm_type, class_name, param.name));
+            param_types.push(param_llvm_type);
         }
 
-        self.emit_line(&format!("define {} @{}({}) {{",
-            ret_type, fn_name, params.join(", ")));
+        self.emit_line(&format!("define {} @{}({}){} {{",
+            ret_type, fn_name, params.join(", "), inline_attribute(method)));
         self.indent += 1;
 
         self.emit_line("entry:");
@@ -760,9 +752,9 @@ impl IRGenerator {
                 
                 self.var_types.insert(param.name.clone(), array_type.clone());
                 // 存储Cavvy类型信息，用于准确的类型推断
-                self.var_cay_types.insert(param.name.clone(), param.param_type.clone());
+                self.var_cay_types.insert(param.name.clone(), param.param_type.non_null().clone());
                 // 如果参数类型是对象，记录其类名以便后续方法调用解析
-                if let crate::types::Type::Object(ref class_name) = param.param_type {
+                if let crate::types::Type::Object(class_name) = param.param_type.non_null() {
                     self.var_class_map.insert(param.name.clone(), class_name.clone());
                 }
             } else {
@@ -773,9 +765,9 @@ impl IRGenerator {
                     param_type, class_name, param.name, param_type, llvm_name));
                 self.var_types.insert(param.name.clone(), param_type.clone());
                 // 存储Cavvy类型信息，用于准确的类型推断
-                self.var_cay_types.insert(param.name.clone(), param.param_type.clone());
+                self.var_cay_types.insert(param.name.clone(), param.param_type.non_null().clone());
                 // 如果参数类型是对象，记录其类名以便后续方法调用解析
-                if let crate::types::Type::Object(ref class_name) = param.param_type {
+                if let crate::types::Type::Object(class_name) = param.param_type.non_null() {
                     self.var_class_map.insert(param.name.clone(), class_name.clone());
                 }
             }
@@ -789,6 +781,9 @@ impl IRGenerator {
 
         if method.return_type == Type::Void {
             self.emit_line("  ret void");
+        } else if !self.code_terminated() {
+            // 语义分析已保证非 void 方法不会正常结束（例如以无限循环结尾）
+            self.emit_line("  unreachable");
         }
         
         // 退出函数作用域
@@ -798,6 +793,13 @@ impl IRGenerator {
         self.emit_line("}");
         self.emit_line("");
 
+        // @Export("c_name")：以 C 名称导出，作为方法的别名
+        if let Some(c_name) = method.annotation(Annotation::EXPORT).and_then(|export| export.string_value()) {
+            let fn_type = format!("{} ({})", ret_type, param_types.join(", "));
+            self.emit_line(&format!("@{} = alias {}, {}* @{}", c_name, fn_type, fn_type, fn_name));
+            self.emit_line("");
+        }
+
         Ok(())
     }
 
@@ -811,6 +813,7 @@ impl IRGenerator {
         self.var_types.clear();
         self.scope_manager.reset();
         self.loop_stack.clear();
+        self.cleanup_stack.clear();
 
         let params: Vec<String> = ctor.params.iter()
             .map(|p| format!("{} %{}.{}_param", self.type_to_llvm(&p.param_type), class_name, p.name))
@@ -840,7 +843,7 @@ impl IRGenerator {
             self.emit_line(&format!("  store {} %{}.{}_param, {}* %{}",
                 param_type, class_name, param.name, param_type, llvm_name));
             self.var_types.insert(param.name.clone(), param_type.clone());
-            self.var_cay_types.insert(param.name.clone(), param.param_type.clone());
+            self.var_cay_types.insert(param.name.clone(), param.param_type.non_null().clone());
         }
 
         if let Some(ref call) = ctor.constructor_call {
@@ -911,6 +914,7 @@ impl IRGenerator {
         self.var_types.clear();
         self.scope_manager.reset();
         self.loop_stack.clear();
+        self.cleanup_stack.clear();
 
         self.emit_line(&format!("define void @{}(i8* %this) {{", fn_name));
         self.indent += 1;
@@ -956,12 +960,16 @@ impl IRGenerator {
         self.var_types.clear();
         self.scope_manager.reset();
         self.loop_stack.clear();
+        self.cleanup_stack.clear();
 
         self.emit_line(&format!("define void @{}(i8* %this) {{", fn_name));
         self.indent += 1;
 
         self.emit_line("entry:");
 
+        // 进入函数作用域，避免 this 变量与 %this 参数同名
+        self.scope_manager.enter_scope();
+
         let this_llvm_name = self.scope_manager.declare_var("this", "i8*");
         self.emit_line(&format!("  %{} = alloca i8*", this_llvm_name));
         self.emit_line(&format!("  store i8* %this, i8** %{}", this_llvm_name));
@@ -971,6 +979,8 @@ impl IRGenerator {
 
         self.emit_line("  ret void");
 
+        self.scope_manager.exit_scope();
+
         self.indent -= 1;
         self.emit_line("}");
         self.emit_line("");
@@ -988,6 +998,7 @@ impl IRGenerator {
         self.var_types.clear();
         self.scope_manager.reset();
         self.loop_stack.clear();
+        self.cleanup_stack.clear();
 
         self.emit_line(&format!("define void @{}() {{", fn_name));
         self.indent += 1;
@@ -1086,6 +1097,7 @@ impl IRGenerator {
                     "i".to_string() // 默认int
                 }
             }
+            Expr::New(inner) => format!("o{}", inner.class_name),
             _ => "i".to_string(), // 默认int
         }
     }
@@ -1148,6 +1160,7 @@ impl IRGenerator {
         self.var_types.clear();
         self.scope_manager.reset();
         self.loop_stack.clear();
+        self.cleanup_stack.clear();
 
         let ret_type = self.current_return_type.clone();
         let params: Vec<String> = func.params.iter()
@@ -1168,13 +1181,15 @@ impl IRGenerator {
                 param_type, param.name, param_type, llvm_name));
             self.var_types.insert(param.name.clone(), param_type);
             // 同时保存Cavvy类型用于函数指针识别
-            self.var_cay_types.insert(param.name.clone(), param.param_type.clone());
+            self.var_cay_types.insert(param.name.clone(), param.param_type.non_null().clone());
         }
 
         self.generate_block(&func.body)?;
 
         if func.return_type == Type::Void {
             self.emit_line("  ret void");
+        } else if !self.code_terminated() {
+            self.emit_line("  unreachable");
         }
 
         self.indent -= 1;
@@ -1210,18 +1225,18 @@ impl IRGenerator {
             return Ok(());
         }
         
-        let ret_type = self.type_to_llvm(&func.return_type);
+        // 结构体参数和返回值按 C 调用约定降级
+        let (ret_type, sret_param) = self.extern_return_llvm_type(&func.return_type);
 
         // 构建参数列表，支持可变参数
-        let params: Vec<String> = func.params.iter()
-            .map(|p| {
-                if p.is_varargs {
-                    "...".to_string()
-                } else {
-                    self.type_to_llvm(&p.param_type)
-                }
-            })
-            .collect();
+        let mut params: Vec<String> = sret_param.into_iter().collect();
+        for p in &func.params {
+            if p.is_varargs {
+                params.push("...".to_string());
+            } else {
+                params.extend(self.extern_param_llvm_types(&p.param_type));
+            }
+        }
 
         // 获取调用约定属性
         let cc_attr = self.calling_convention_to_llvm_attr(calling_conv);
@@ -1275,3 +1290,14 @@ impl IRGenerator {
         }
     }
 }
+
+/// @Inline / @NoInline 对应的 LLVM 函数属性
+fn inline_attribute(method: &MethodDecl) -> &'static str {
+    if method.has_annotation(Annotation::INLINE) {
+        " alwaysinline"
+    } else if method.has_annotation(Annotation::NO_INLINE) {
+        " noinline"
+    } else {
+        ""
+    }
+}
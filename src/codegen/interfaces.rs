@@ -0,0 +1,330 @@
+//! 接口代码生成
+//!
+//! 每个接口的实例方法按名称排序后编号，作为接口方法表（itable）中的槽位。
+//! 实现了接口的每个类有一张 itable：`@__itable.Circle.Shape = [k x i8*]`，
+//! 依次存放该类对各方法的实现；接口另有一张按类型标识符索引的总表
+//! `@__itables.Shape = [N x i8*]`，未实现接口的类型对应 `null`。
+//!
+//! 接口类型接收者上的调用从对象头读取类型标识符，经总表找到类的 itable 再取出槽位中的
+//! 函数指针调用；`instanceof` 接口只需检查总表中的项是否为空。
+//! 类继承的默认方法以该类为 `this` 重新生成一份方法体，static 接口方法生成为 `@Shape.unit`。
+//! sealed 接口的实现类在编译期全部已知，接收者上的调用按类型标识符直接调用各实现，不经过 itable。
+
+use std::collections::HashSet;
+use crate::codegen::context::IRGenerator;
+use crate::ast::*;
+use crate::types::{Type, MethodInfo};
+use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
+
+impl IRGenerator {
+    /// 生成所有接口的方法表
+    pub(super) fn emit_interface_tables(&mut self, program: &Program) {
+        if program.interfaces.is_empty() {
+            return;
+        }
+        let defined = self.defined_method_names(program);
+        let type_count = self.type_id_counter;
+
+        let mut lines = Vec::new();
+        for interface in &program.interfaces {
+            let slots = self.interface_slots(&interface.name);
+            let mut table = vec!["i8* null".to_string(); type_count];
+            for class in &program.classes {
+                if !self.is_subtype(&class.name, &interface.name) {
+                    continue;
+                }
+                let entries: Vec<String> = slots.iter()
+                    .map(|method| self.itable_entry(&class.name, method, &defined))
+                    .collect();
+                let itable = format!("@__itable.{}.{}", class.name, interface.name);
+                let init = if entries.is_empty() {
+                    "zeroinitializer".to_string()
+                } else {
+                    format!("[{}]", entries.join(", "))
+                };
+                lines.push(format!("{} = private constant [{} x i8*] {}", itable, entries.len(), init));
+                if let Some(type_id) = self.get_type_id_value(&class.name) {
+                    table[type_id as usize] = format!("i8* bitcast ([{} x i8*]* {} to i8*)", entries.len(), itable);
+                }
+            }
+            lines.push(format!(
+                "@__itables.{} = private constant [{} x i8*] [{}]",
+                interface.name, type_count, table.join(", ")
+            ));
+        }
+
+        self.emit_raw("; Interface method tables");
+        for line in lines {
+            self.emit_raw(&line);
+        }
+        self.emit_raw("");
+    }
+
+    /// 生成 static 接口方法以及类继承的默认方法
+    pub(super) fn generate_interface_methods(&mut self, program: &Program) -> cayResult<()> {
+        for interface in &program.interfaces {
+            self.current_interface = interface.name.clone();
+            for method in interface.methods.iter().filter(|m| m.modifiers.contains(&Modifier::Static) && m.body.is_some()) {
+                self.generate_method(&interface.name, method)?;
+            }
+        }
+
+        let mut inherited: Vec<(String, Vec<(String, String)>)> = self.type_registry.as_ref()
+            .map(|r| r.inherited_defaults.iter().map(|(c, d)| (c.clone(), d.clone())).collect())
+            .unwrap_or_default();
+        inherited.sort();
+        for (class_name, defaults) in inherited {
+            for (interface_name, method_name) in defaults {
+                let method = program.interfaces.iter()
+                    .find(|i| i.name == interface_name)
+                    .and_then(|i| i.methods.iter().find(|m| m.name == method_name))
+                    .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, format!("未找到接口默认方法 '{}.{}'", interface_name, method_name)))?;
+                self.current_interface = interface_name.clone();
+                self.generate_method(&class_name, method)?;
+            }
+        }
+        self.current_interface.clear();
+        Ok(())
+    }
+
+    /// 生成接口方法调用，不是接口方法调用时返回 None
+    ///
+    /// 处理 `Shape.unit()`、接口类型接收者上的 `s.area()`，以及接口方法体中不带接收者的 static 方法调用。
+    pub fn try_generate_interface_call(&mut self, call: &CallExpr) -> cayResult<Option<String>> {
+        match call.callee.as_ref() {
+            Expr::Identifier(name) if !self.current_interface.is_empty() => {
+                let interface_name = self.current_interface.clone();
+                match self.interface_method(&interface_name, name.as_ref()) {
+                    Some(method) if method.is_static => {
+                        self.generate_interface_static_call(&interface_name, &method, &call.args).map(Some)
+                    }
+                    _ => Ok(None),
+                }
+            }
+            Expr::MemberAccess(member) => {
+                // Interface.method()
+                let static_call = match member.object.as_ref() {
+                    Expr::Identifier(name) if self.scope_manager.get_llvm_name(name.as_ref()).is_none() => self
+                        .interface_method(name.as_ref(), &member.member)
+                        .filter(|m| m.is_static)
+                        .map(|m| (name.as_ref().to_string(), m)),
+                    _ => None,
+                };
+                if let Some((interface_name, method)) = static_call {
+                    return self.generate_interface_static_call(&interface_name, &method, &call.args).map(Some);
+                }
+                // 接口类型的接收者
+                let interface_name = match self.get_expression_type(&member.object) {
+                    Some(Type::Object(name)) => name,
+                    _ => return Ok(None),
+                };
+                match self.interface_method(&interface_name, &member.member) {
+                    Some(method) => self.generate_interface_dispatch(&interface_name, &method, &member.object, &call.args).map(Some),
+                    None => Ok(None),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 生成 `instanceof` 接口检查：类型的 itable 不为空即实现了接口
+    pub fn generate_interface_check(&mut self, actual_type_id: &str, interface_name: &str, true_label: &str, false_label: &str) {
+        let itable = self.load_itable(actual_type_id, interface_name);
+        let implements = self.new_temp();
+        self.emit_line(&format!("  {} = icmp ne i8* {}, null", implements, itable));
+        self.emit_line(&format!("  br i1 {}, label %{}, label %{}", implements, true_label, false_label));
+    }
+
+    /// 经接收者的 itable 调用接口方法
+    fn generate_interface_dispatch(&mut self, interface_name: &str, method: &MethodInfo, receiver: &Expr, args: &[Expr]) -> cayResult<String> {
+        let slot = self.interface_slots(interface_name).iter()
+            .position(|m| m.name == method.name)
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, format!("接口 '{}' 没有实例方法 '{}'", interface_name, method.name)))?;
+        let receiver = self.generate_expression(receiver)?;
+        let mut arg_results = Vec::new();
+        for arg in args {
+            arg_results.push(self.generate_expression(arg)?);
+        }
+
+        let (_, receiver_val) = self.parse_typed_value(&receiver);
+        let type_id_ptr = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast i8* {} to i32*", type_id_ptr, receiver_val));
+        let type_id = self.new_temp();
+        self.emit_line(&format!("  {} = load i32, i32* {}", type_id, type_id_ptr));
+        if let Some(implementations) = self.sealed_implementations(interface_name, method) {
+            return Ok(self.emit_devirtualized_call(&type_id, &implementations, &receiver, method, &arg_results));
+        }
+        let itable = self.load_itable(&type_id, interface_name);
+
+        let slots = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast i8* {} to i8**", slots, itable));
+        let slot_ptr = self.new_temp();
+        self.emit_line(&format!("  {} = getelementptr i8*, i8** {}, i32 {}", slot_ptr, slots, slot));
+        let raw_fn = self.new_temp();
+        self.emit_line(&format!("  {} = load i8*, i8** {}", raw_fn, slot_ptr));
+        let fn_ptr = self.new_temp();
+        let fn_type = self.method_pointer_type(method);
+        self.emit_line(&format!("  {} = bitcast i8* {} to {}", fn_ptr, raw_fn, fn_type));
+
+        Ok(self.emit_resolved_method_call(&fn_ptr, Some(&receiver), method, &arg_results))
+    }
+
+    /// sealed 接口的所有实现类都已知时，返回各可实例化实现类的 (类型标识符, 被调函数)
+    ///
+    /// 被调函数按接口方法的签名引用，与 itable 中的项一致。
+    fn sealed_implementations(&self, interface_name: &str, method: &MethodInfo) -> Option<Vec<(i32, String)>> {
+        let registry = self.type_registry.as_ref()?;
+        registry.sealed_permits(interface_name)?;
+        let param_types: Vec<Type> = method.params.iter().map(|p| p.param_type.clone()).collect();
+        let fn_type = self.method_pointer_type(method);
+        let mut implementations = Vec::new();
+        for class in registry.classes.values() {
+            if class.is_abstract || !self.is_subtype(&class.name, interface_name) {
+                continue;
+            }
+            let type_id = self.get_type_id_value(&class.name)?;
+            let implementation = registry.find_method(&class.name, &method.name, &param_types)
+                .filter(|m| !m.is_static)?;
+            let fn_name = self.build_function_name_from_method(&implementation.class_name, &implementation.name, &implementation.params, false);
+            let impl_type = self.method_pointer_type(implementation);
+            let callee = if impl_type == fn_type {
+                format!("@{}", fn_name)
+            } else {
+                format!("bitcast ({} @{} to {})", impl_type, fn_name, fn_type)
+            };
+            implementations.push((type_id, callee));
+        }
+        implementations.sort();
+        Some(implementations)
+    }
+
+    /// 按类型标识符分派到各实现类的方法，只有一个实现类时直接调用
+    fn emit_devirtualized_call(&mut self, type_id: &str, implementations: &[(i32, String)], receiver: &str, method: &MethodInfo, args: &[String]) -> String {
+        if let [(_, callee)] = implementations {
+            return self.emit_resolved_method_call(callee, Some(receiver), method, args);
+        }
+
+        let end_label = self.new_label("sealed.end");
+        let default_label = self.new_label("sealed.default");
+        let case_labels: Vec<String> = implementations.iter().map(|_| self.new_label("sealed.case")).collect();
+        self.emit_line(&format!("  switch i32 {}, label %{} [", type_id, default_label));
+        for ((id, _), label) in implementations.iter().zip(&case_labels) {
+            self.emit_line(&format!("    i32 {}, label %{}", id, label));
+        }
+        self.emit_line("  ]");
+
+        let mut incoming = Vec::new();
+        for ((_, callee), label) in implementations.iter().zip(&case_labels) {
+            self.emit_line(&format!("{}:", label));
+            let result = self.emit_resolved_method_call(callee, Some(receiver), method, args);
+            self.emit_line(&format!("  br label %{}", end_label));
+            incoming.push((result, label.clone()));
+        }
+        // 语义分析保证只有 permits 列出的类及其子类实现了 sealed 接口
+        self.emit_line(&format!("{}:", default_label));
+        self.emit_line("  unreachable");
+
+        self.emit_line(&format!("{}:", end_label));
+        let return_type = self.type_to_llvm(&method.return_type);
+        if return_type == "void" {
+            return "void %dummy".to_string();
+        }
+        if incoming.is_empty() {
+            return format!("{} undef", return_type);
+        }
+        let phi_args: Vec<String> = incoming.iter()
+            .map(|(result, label)| format!("[ {}, %{} ]", self.parse_typed_value(result).1, label))
+            .collect();
+        let result = self.new_temp();
+        self.emit_line(&format!("  {} = phi {} {}", result, return_type, phi_args.join(", ")));
+        format!("{} {}", return_type, result)
+    }
+
+    /// 直接调用 static 接口方法
+    fn generate_interface_static_call(&mut self, interface_name: &str, method: &MethodInfo, args: &[Expr]) -> cayResult<String> {
+        let mut arg_results = Vec::new();
+        for arg in args {
+            arg_results.push(self.generate_expression(arg)?);
+        }
+        let callee = format!("@{}", self.build_function_name_from_method(interface_name, &method.name, &method.params, false));
+        Ok(self.emit_resolved_method_call(&callee, None, method, &arg_results))
+    }
+
+    /// 从接口总表取出类型标识符对应的 itable（未实现接口时为 null）
+    fn load_itable(&mut self, type_id: &str, interface_name: &str) -> String {
+        let type_count = self.type_id_counter;
+        let entry_ptr = self.new_temp();
+        self.emit_line(&format!(
+            "  {} = getelementptr [{} x i8*], [{} x i8*]* @__itables.{}, i32 0, i32 {}",
+            entry_ptr, type_count, type_count, interface_name, type_id
+        ));
+        let itable = self.new_temp();
+        self.emit_line(&format!("  {} = load i8*, i8** {}", itable, entry_ptr));
+        itable
+    }
+
+    fn interface_method(&self, interface_name: &str, method_name: &str) -> Option<MethodInfo> {
+        self.type_registry.as_ref()?.get_interface_method(interface_name, method_name).cloned()
+    }
+
+    /// 接口的实例方法，按名称排序即 itable 的槽位顺序
+    fn interface_slots(&self, interface_name: &str) -> Vec<MethodInfo> {
+        let mut slots: Vec<MethodInfo> = self.type_registry.as_ref()
+            .and_then(|r| r.get_interface(interface_name))
+            .map(|i| i.methods.values().filter(|m| !m.is_static).cloned().collect())
+            .unwrap_or_default();
+        slots.sort_by(|a, b| a.name.cmp(&b.name));
+        slots
+    }
+
+    /// 类对接口方法的实现；抽象类未实现的方法为 null
+    fn itable_entry(&self, class_name: &str, method: &MethodInfo, defined: &HashSet<String>) -> String {
+        let param_types: Vec<Type> = method.params.iter().map(|p| p.param_type.clone()).collect();
+        let implementation = self.type_registry.as_ref()
+            .and_then(|r| r.find_method(class_name, &method.name, &param_types))
+            .filter(|m| !m.is_static);
+        if let Some(implementation) = implementation {
+            let fn_name = self.build_function_name_from_method(&implementation.class_name, &implementation.name, &implementation.params, false);
+            if defined.contains(&fn_name) {
+                return format!("i8* bitcast ({} @{} to i8*)", self.method_pointer_type(implementation), fn_name);
+            }
+        }
+        "i8* null".to_string()
+    }
+
+    /// 实例方法的函数指针类型: RET (i8*, P1, ...)*
+    fn method_pointer_type(&self, method: &MethodInfo) -> String {
+        let mut params = vec!["i8*".to_string()];
+        params.extend(method.params.iter().map(|p| self.type_to_llvm(&p.param_type)));
+        format!("{} ({})*", self.type_to_llvm(&method.return_type), params.join(", "))
+    }
+
+    /// 程序中带方法体的实例方法（包括类继承的默认方法）生成的函数名
+    fn defined_method_names(&self, program: &Program) -> HashSet<String> {
+        let mut defined = HashSet::new();
+        for class in &program.classes {
+            for member in &class.members {
+                match member {
+                    ClassMember::Method(method) if method.body.is_some() && !method.modifiers.contains(&Modifier::Native) => {
+                        defined.insert(self.generate_method_name(&class.name, method));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if let Some(registry) = &self.type_registry {
+            for (class_name, defaults) in &registry.inherited_defaults {
+                for (interface_name, method_name) in defaults {
+                    let method = program.interfaces.iter()
+                        .find(|i| &i.name == interface_name)
+                        .and_then(|i| i.methods.iter().find(|m| &m.name == method_name));
+                    if let Some(method) = method {
+                        defined.insert(self.generate_method_name(class_name, method));
+                    }
+                }
+            }
+        }
+        defined
+    }
+}
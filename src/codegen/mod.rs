@@ -18,6 +18,10 @@ mod expressions;
 mod statements;
 pub mod runtime;
 mod generator;
+mod monomorphize;
+mod enums;
+mod structs;
+mod interfaces;
 mod platform;
 pub mod obfuscator;
 pub mod source_map;
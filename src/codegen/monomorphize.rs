@@ -0,0 +1,674 @@
+//! 泛型单态化
+//!
+//! 在生成 IR 之前把泛型模板展开为具体类：`ArrayList<int>` 生成类 `ArrayList$i32`，
+//! 泛型方法 `<T> T max(T a, T b)` 按调用处推断出的类型实参生成 `max$i32` 等特化方法。
+//! 展开后的程序不再包含类型参数，后续代码生成按普通类处理。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::ast::*;
+use crate::types::{Type, TypeRegistry, ClassInfo, MethodInfo, mangle_generic_name, generic_bindings};
+use crate::error::{cayResult, codegen_error};
+use super::context::IRGenerator;
+use crate::diagnostic::ErrorCodes;
+
+/// 当前正在展开的类
+struct MonoScope {
+    /// 泛型模板名（非泛型类为 None）
+    origin: Option<String>,
+    /// 展开后的类名
+    class_name: String,
+    /// 类型参数 -> 类型实参
+    bindings: HashMap<String, Type>,
+}
+
+/// 待生成的泛型方法特化
+struct MethodRequest {
+    class_name: String,
+    method_name: String,
+    type_args: Vec<Type>,
+}
+
+/// 单态化器
+struct Monomorphizer<'a> {
+    registry: &'a TypeRegistry,
+    /// 所有类的原始声明（泛型方法模板从这里查找）
+    source_classes: HashMap<String, ClassDecl>,
+    class_templates: HashMap<String, ClassDecl>,
+    interface_templates: HashMap<String, InterfaceDecl>,
+    class_queue: VecDeque<(String, String, Vec<Type>)>,
+    method_queue: VecDeque<MethodRequest>,
+    seen_types: HashSet<String>,
+    seen_methods: HashSet<(String, String)>,
+    classes: Vec<ClassDecl>,
+    interfaces: Vec<InterfaceDecl>,
+    top_level_functions: Vec<TopLevelFunction>,
+    /// 新生成的类信息（单态化实例）
+    class_infos: Vec<ClassInfo>,
+    /// 新生成的特化方法信息: (类名, 方法信息)
+    method_infos: Vec<(String, MethodInfo)>,
+}
+
+impl IRGenerator {
+    /// 单态化泛型类、接口和方法
+    ///
+    /// 程序中没有泛型时返回 None，调用方继续使用原始程序。
+    pub(super) fn monomorphize(&mut self, program: &Program) -> cayResult<Option<Program>> {
+        let has_generics = program.classes.iter().any(|c| {
+            !c.type_params.is_empty()
+                || !c.generic_supertypes.is_empty()
+                || c.members.iter().any(|m| matches!(m, ClassMember::Method(method) if !method.type_params.is_empty()))
+        }) || program.interfaces.iter().any(|i| !i.type_params.is_empty());
+        if !has_generics {
+            return Ok(None);
+        }
+
+        let registry = match &self.type_registry {
+            Some(registry) => registry.clone(),
+            None => return Err(codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "泛型单态化需要语义分析得到的类型注册表")),
+        };
+
+        let mut mono = Monomorphizer {
+            registry: &registry,
+            source_classes: program.classes.iter()
+                .map(|c| (c.name.clone(), c.clone()))
+                .collect(),
+            class_templates: program.classes.iter()
+                .filter(|c| !c.type_params.is_empty())
+                .map(|c| (c.name.clone(), c.clone()))
+                .collect(),
+            interface_templates: program.interfaces.iter()
+                .filter(|i| !i.type_params.is_empty())
+                .map(|i| (i.name.clone(), i.clone()))
+                .collect(),
+            class_queue: VecDeque::new(),
+            method_queue: VecDeque::new(),
+            seen_types: HashSet::new(),
+            seen_methods: HashSet::new(),
+            classes: Vec::new(),
+            interfaces: Vec::new(),
+            top_level_functions: Vec::new(),
+            class_infos: Vec::new(),
+            method_infos: Vec::new(),
+        };
+        mono.run(program)?;
+
+        // 把新生成的实例和特化方法登记到代码生成使用的类型注册表
+        if let Some(type_registry) = &mut self.type_registry {
+            for info in mono.class_infos {
+                type_registry.classes.entry(info.name.clone()).or_insert(info);
+            }
+            for (class_name, method) in mono.method_infos {
+                if let Some(class_info) = type_registry.classes.get_mut(&class_name) {
+                    class_info.add_method(method);
+                }
+            }
+        }
+
+        let mut mono_program = program.clone();
+        mono_program.classes = mono.classes;
+        mono_program.interfaces = mono.interfaces;
+        mono_program.top_level_functions = mono.top_level_functions;
+        Ok(Some(mono_program))
+    }
+}
+
+impl<'a> Monomorphizer<'a> {
+    fn run(&mut self, program: &Program) -> cayResult<()> {
+        // 非泛型接口和类原样保留（改写其中引用的泛型类型）
+        for interface in program.interfaces.iter().filter(|i| i.type_params.is_empty()) {
+            let scope = MonoScope { origin: None, class_name: interface.name.clone(), bindings: HashMap::new() };
+            let interface = self.instantiate_interface(interface, &scope);
+            self.interfaces.push(interface);
+        }
+        for class in program.classes.iter().filter(|c| c.type_params.is_empty()) {
+            let scope = MonoScope { origin: None, class_name: class.name.clone(), bindings: HashMap::new() };
+            let class = self.instantiate_class(class, &scope);
+            self.classes.push(class);
+        }
+        for func in &program.top_level_functions {
+            let scope = MonoScope { origin: None, class_name: String::new(), bindings: HashMap::new() };
+            let mut func = func.clone();
+            for param in &mut func.params {
+                param.param_type = self.mono_type(&param.param_type, &scope);
+            }
+            func.return_type = self.mono_type(&func.return_type, &scope);
+            self.rewrite_block(&mut func.body, &scope);
+            self.top_level_functions.push(func);
+        }
+
+        // 语义分析登记的所有具体实例
+        let mut instances: Vec<_> = self.registry.generic_instances.iter()
+            .map(|(name, (origin, args))| (name.clone(), origin.clone(), args.clone()))
+            .collect();
+        instances.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, origin, args) in instances {
+            if !args.iter().any(|a| a.is_generic()) {
+                self.enqueue_instance(name, origin, args);
+            }
+        }
+
+        loop {
+            if let Some((name, origin, args)) = self.class_queue.pop_front() {
+                self.expand_instance(&name, &origin, &args)?;
+                continue;
+            }
+            if let Some(request) = self.method_queue.pop_front() {
+                self.expand_method(request)?;
+                continue;
+            }
+            break;
+        }
+        Ok(())
+    }
+
+    /// 登记一个待展开的泛型实例（重复登记会被忽略）
+    ///
+    /// 泛型枚举实例在语义分析阶段已经登记为具体枚举，不需要展开。
+    fn enqueue_instance(&mut self, name: String, origin: String, args: Vec<Type>) {
+        if self.registry.generic_enum_templates.contains_key(&origin) {
+            return;
+        }
+        if self.seen_types.insert(name.clone()) {
+            self.class_queue.push_back((name, origin, args));
+        }
+    }
+
+    /// 展开一个泛型类或接口实例
+    fn expand_instance(&mut self, name: &str, origin: &str, args: &[Type]) -> cayResult<()> {
+        if let Some(template) = self.class_templates.get(origin).cloned() {
+            let scope = MonoScope {
+                origin: Some(origin.to_string()),
+                class_name: name.to_string(),
+                bindings: generic_bindings(&template.type_params, args),
+            };
+            let class = self.instantiate_class(&template, &scope);
+            self.classes.push(class);
+            if !self.registry.classes.contains_key(name) {
+                // 仅在方法体中出现的实例没有经过语义分析登记，这里补充类信息
+                if let Some(info) = self.registry.generic_templates.get(origin) {
+                    let mut info = info.instantiate(name, args);
+                    info.map_types(&mut |t| t.resolve_generics());
+                    self.class_infos.push(info);
+                }
+            }
+            Ok(())
+        } else if let Some(template) = self.interface_templates.get(origin).cloned() {
+            let scope = MonoScope {
+                origin: Some(origin.to_string()),
+                class_name: name.to_string(),
+                bindings: generic_bindings(&template.type_params, args),
+            };
+            let interface = self.instantiate_interface(&template, &scope);
+            self.interfaces.push(interface);
+            Ok(())
+        } else {
+            Err(codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, format!("未找到泛型模板 '{}'", origin)))
+        }
+    }
+
+    /// 生成泛型方法的特化版本并加入所属类
+    fn expand_method(&mut self, request: MethodRequest) -> cayResult<()> {
+        let specialized_name = mangle_generic_name(&request.method_name, &request.type_args);
+        if !self.seen_methods.insert((request.class_name.clone(), specialized_name.clone())) {
+            return Ok(());
+        }
+
+        // 所属类为泛型实例时，方法模板来自泛型类模板，并叠加类的类型实参
+        let (origin, mut bindings) = match self.registry.generic_instances.get(&request.class_name) {
+            Some((origin, args)) => {
+                let params = self.class_templates.get(origin)
+                    .map(|c| c.type_params.clone())
+                    .unwrap_or_default();
+                (Some(origin.clone()), generic_bindings(&params, args))
+            }
+            None => (None, HashMap::new()),
+        };
+        let template_class = origin.as_deref().unwrap_or(&request.class_name);
+        let template = self.find_method_template(template_class, &request.method_name)
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, format!(
+                "未找到泛型方法模板 '{}.{}'", request.class_name, request.method_name
+            )))?;
+        bindings.extend(generic_bindings(&template.type_params, &request.type_args));
+
+        let scope = MonoScope { origin, class_name: request.class_name.clone(), bindings };
+        let mut method = self.instantiate_method(&template, &scope);
+        method.name = specialized_name;
+
+        self.method_infos.push((request.class_name.clone(), method_info_from_decl(&request.class_name, &method)));
+        let class = self.classes.iter_mut()
+            .find(|c| c.name == request.class_name)
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, format!("未找到泛型方法所属的类 '{}'", request.class_name)))?;
+        class.members.push(ClassMember::Method(method));
+        Ok(())
+    }
+
+    /// 在类（或其父类）的原始声明中查找泛型方法模板
+    fn find_method_template(&self, class_name: &str, method_name: &str) -> Option<MethodDecl> {
+        let class = self.source_classes.get(class_name)?;
+        let found = class.members.iter().find_map(|m| match m {
+            ClassMember::Method(method) if method.name == method_name && !method.type_params.is_empty() => Some(method.clone()),
+            _ => None,
+        });
+        found.or_else(|| {
+            let parent = class.parent.clone()?;
+            self.find_method_template(&parent, method_name)
+        })
+    }
+
+    /// 以替换表展开类声明（泛型方法留待调用时按需展开）
+    fn instantiate_class(&mut self, template: &ClassDecl, scope: &MonoScope) -> ClassDecl {
+        let mut class = template.clone();
+        class.name = scope.class_name.clone();
+        class.type_params = Vec::new();
+
+        // 泛型父类/接口替换为对应实例名
+        for supertype in &template.generic_supertypes {
+            let old_name = supertype.resolve_generics().to_string();
+            let new_name = self.mono_type(supertype, scope).to_string();
+            if class.parent.as_deref() == Some(old_name.as_str()) {
+                class.parent = Some(new_name.clone());
+            }
+            for interface in &mut class.interfaces {
+                if *interface == old_name {
+                    *interface = new_name.clone();
+                }
+            }
+        }
+        class.generic_supertypes = Vec::new();
+
+        let mut members = Vec::new();
+        for member in &template.members {
+            let member = match member {
+                ClassMember::Method(method) if !method.type_params.is_empty() => continue,
+                ClassMember::Method(method) => ClassMember::Method(self.instantiate_method(method, scope)),
+                ClassMember::Field(field) => {
+                    let mut field = field.clone();
+                    field.field_type = self.mono_type(&field.field_type, scope);
+                    if let Some(init) = &mut field.initializer {
+                        self.rewrite_expr(init, scope);
+                    }
+                    ClassMember::Field(field)
+                }
+                ClassMember::Constructor(ctor) => {
+                    let mut ctor = ctor.clone();
+                    for param in &mut ctor.params {
+                        param.param_type = self.mono_type(&param.param_type, scope);
+                    }
+                    match &mut ctor.constructor_call {
+                        Some(ConstructorCall::This(args)) | Some(ConstructorCall::Super(args)) => {
+                            for arg in args {
+                                self.rewrite_expr(arg, scope);
+                            }
+                        }
+                        None => {}
+                    }
+                    self.rewrite_block(&mut ctor.body, scope);
+                    ClassMember::Constructor(ctor)
+                }
+                ClassMember::Destructor(dtor) => {
+                    let mut dtor = dtor.clone();
+                    self.rewrite_block(&mut dtor.body, scope);
+                    ClassMember::Destructor(dtor)
+                }
+                ClassMember::InstanceInitializer(block) => {
+                    let mut block = block.clone();
+                    self.rewrite_block(&mut block, scope);
+                    ClassMember::InstanceInitializer(block)
+                }
+                ClassMember::StaticInitializer(block) => {
+                    let mut block = block.clone();
+                    self.rewrite_block(&mut block, scope);
+                    ClassMember::StaticInitializer(block)
+                }
+                ClassMember::Property(property) => {
+                    let mut property = property.clone();
+                    property.property_type = self.mono_type(&property.property_type, scope);
+                    ClassMember::Property(property)
+                }
+                // 嵌套类在解析结束时已提升到顶层
+                ClassMember::Class(_) => continue,
+            };
+            members.push(member);
+        }
+        class.members = members;
+        class
+    }
+
+    /// 以替换表展开接口声明
+    fn instantiate_interface(&mut self, template: &InterfaceDecl, scope: &MonoScope) -> InterfaceDecl {
+        let mut interface = template.clone();
+        interface.name = scope.class_name.clone();
+        interface.type_params = Vec::new();
+        interface.methods = template.methods.iter()
+            .map(|m| self.instantiate_method(m, scope))
+            .collect();
+        interface
+    }
+
+    /// 以替换表展开方法声明
+    fn instantiate_method(&mut self, template: &MethodDecl, scope: &MonoScope) -> MethodDecl {
+        let mut method = template.clone();
+        method.type_params = Vec::new();
+        for param in &mut method.params {
+            param.param_type = self.mono_type(&param.param_type, scope);
+        }
+        method.return_type = self.mono_type(&method.return_type, scope);
+        if let Some(body) = &mut method.body {
+            self.rewrite_block(body, scope);
+        }
+        method
+    }
+
+    /// 替换类型参数、登记用到的泛型实例并转换为单态化类型
+    fn mono_type(&mut self, ty: &Type, scope: &MonoScope) -> Type {
+        let ty = ty.substitute(&scope.bindings);
+        self.collect_instances(&ty);
+        self.map_self_reference(ty.resolve_generics(), scope)
+    }
+
+    /// 模板内部不带类型实参引用自身（Box）时指向当前实例
+    fn map_self_reference(&self, ty: Type, scope: &MonoScope) -> Type {
+        match ty {
+            Type::Object(name) if scope.origin.as_deref() == Some(name.as_str()) => Type::Object(scope.class_name.clone()),
+            Type::Array(inner) => Type::Array(Box::new(self.map_self_reference(*inner, scope))),
+            other => other,
+        }
+    }
+
+    /// 登记类型中出现的具体泛型实例
+    fn collect_instances(&mut self, ty: &Type) {
+        match ty {
+            Type::Generic(name, args) => {
+                for arg in args {
+                    self.collect_instances(arg);
+                }
+                let resolved_args: Vec<Type> = args.iter().map(|a| a.resolve_generics()).collect();
+                if resolved_args.iter().any(|a| a.is_generic()) {
+                    return;
+                }
+                let mangled = mangle_generic_name(name, &resolved_args);
+                self.enqueue_instance(mangled, name.clone(), resolved_args);
+            }
+            Type::Array(inner) | Type::Pointer(inner) => self.collect_instances(inner),
+            Type::Function(func_type) => {
+                for param in &func_type.params {
+                    self.collect_instances(param);
+                }
+                self.collect_instances(&func_type.return_type);
+            }
+            _ => {}
+        }
+    }
+
+    /// 单态化名称对应的具体类名：模板自身映射到当前实例，含类型参数的实例按替换表重新计算
+    fn concrete_class_name(&mut self, class_name: &str, scope: &MonoScope) -> String {
+        if scope.origin.as_deref() == Some(class_name) {
+            return scope.class_name.clone();
+        }
+        if let Some((origin, args)) = self.registry.generic_instances.get(class_name) {
+            if args.iter().any(|a| a.is_generic()) {
+                let generic = Type::Generic(origin.clone(), args.clone());
+                return self.mono_type(&generic, scope).to_string();
+            }
+        }
+        class_name.to_string()
+    }
+
+    fn rewrite_block(&mut self, block: &mut Block, scope: &MonoScope) {
+        for stmt in &mut block.statements {
+            self.rewrite_stmt(stmt, scope);
+        }
+    }
+
+    fn rewrite_stmt(&mut self, stmt: &mut Stmt, scope: &MonoScope) {
+        match stmt {
+            Stmt::Expr(expr) => self.rewrite_expr(expr, scope),
+            Stmt::VarDecl(var) => {
+                var.var_type = self.mono_type(&var.var_type, scope);
+                if let Some(init) = &mut var.initializer {
+                    self.rewrite_expr(init, scope);
+                }
+            }
+            Stmt::Return(expr) => {
+                if let Some(expr) = expr {
+                    self.rewrite_expr(expr, scope);
+                }
+            }
+            Stmt::If(if_stmt) => {
+                self.rewrite_expr(&mut if_stmt.condition, scope);
+                self.rewrite_stmt(&mut if_stmt.then_branch, scope);
+                if let Some(else_branch) = &mut if_stmt.else_branch {
+                    self.rewrite_stmt(else_branch, scope);
+                }
+            }
+            Stmt::While(while_stmt) => {
+                self.rewrite_expr(&mut while_stmt.condition, scope);
+                self.rewrite_stmt(&mut while_stmt.body, scope);
+            }
+            Stmt::For(for_stmt) => {
+                if let Some(init) = &mut for_stmt.init {
+                    self.rewrite_stmt(init, scope);
+                }
+                if let Some(condition) = &mut for_stmt.condition {
+                    self.rewrite_expr(condition, scope);
+                }
+                if let Some(update) = &mut for_stmt.update {
+                    self.rewrite_expr(update, scope);
+                }
+                self.rewrite_stmt(&mut for_stmt.body, scope);
+            }
+            Stmt::ForEach(for_each) => {
+                for_each.var_type = self.mono_type(&for_each.var_type, scope);
+                self.rewrite_expr(&mut for_each.iterable, scope);
+                self.rewrite_stmt(&mut for_each.body, scope);
+            }
+            Stmt::DoWhile(do_while) => {
+                self.rewrite_stmt(&mut do_while.body, scope);
+                self.rewrite_expr(&mut do_while.condition, scope);
+            }
+            Stmt::Switch(switch) => {
+                self.rewrite_expr(&mut switch.expr, scope);
+                for case in &mut switch.cases {
+                    self.rewrite_case_labels(&mut case.labels, scope);
+                    if let Some(guard) = &mut case.guard {
+                        self.rewrite_expr(guard, scope);
+                    }
+                    for stmt in &mut case.body {
+                        self.rewrite_stmt(stmt, scope);
+                    }
+                }
+                if let Some(default) = &mut switch.default {
+                    for stmt in default {
+                        self.rewrite_stmt(stmt, scope);
+                    }
+                }
+            }
+            Stmt::Block(block) => self.rewrite_block(block, scope),
+            Stmt::Scope(scope_stmt) => self.rewrite_block(&mut scope_stmt.body, scope),
+            Stmt::Defer(defer) => self.rewrite_stmt(&mut defer.body, scope),
+            Stmt::TryWithResources(try_stmt) => {
+                for resource in &mut try_stmt.resources {
+                    resource.var_type = self.mono_type(&resource.var_type, scope);
+                    if let Some(init) = &mut resource.initializer {
+                        self.rewrite_expr(init, scope);
+                    }
+                }
+                self.rewrite_block(&mut try_stmt.body, scope);
+            }
+            Stmt::Break(_) | Stmt::Continue(_) | Stmt::InlineIr(_) | Stmt::Error(_) => {}
+        }
+    }
+
+    fn rewrite_expr(&mut self, expr: &mut Expr, scope: &MonoScope) {
+        match expr {
+            Expr::Literal(_) => {}
+            Expr::Identifier(ident) => {
+                // 模板内以类名访问静态成员（Box.count）指向当前实例
+                if scope.origin.as_deref() == Some(ident.name.as_str()) {
+                    ident.name = scope.class_name.clone();
+                }
+            }
+            Expr::Binary(bin) => {
+                self.rewrite_expr(&mut bin.left, scope);
+                self.rewrite_expr(&mut bin.right, scope);
+            }
+            Expr::Unary(unary) => self.rewrite_expr(&mut unary.operand, scope),
+            Expr::Call(call) => {
+                self.rewrite_expr(&mut call.callee, scope);
+                for arg in &mut call.args {
+                    self.rewrite_expr(arg, scope);
+                }
+                self.rewrite_generic_call(call, scope);
+            }
+            Expr::MemberAccess(member) => self.rewrite_expr(&mut member.object, scope),
+            Expr::New(new_expr) => {
+                if !new_expr.type_args.is_empty() {
+                    let generic = Type::Generic(new_expr.class_name.clone(), new_expr.type_args.clone());
+                    new_expr.class_name = self.mono_type(&generic, scope).to_string();
+                    new_expr.type_args = Vec::new();
+                } else if scope.origin.as_deref() == Some(new_expr.class_name.as_str()) {
+                    new_expr.class_name = scope.class_name.clone();
+                }
+                for arg in &mut new_expr.args {
+                    self.rewrite_expr(arg, scope);
+                }
+            }
+            Expr::Assignment(assign) => {
+                self.rewrite_expr(&mut assign.target, scope);
+                self.rewrite_expr(&mut assign.value, scope);
+            }
+            Expr::Cast(cast) => {
+                cast.target_type = self.mono_type(&cast.target_type, scope);
+                self.rewrite_expr(&mut cast.expr, scope);
+            }
+            Expr::ArrayCreation(arr) => {
+                arr.element_type = self.mono_type(&arr.element_type, scope);
+                for size in &mut arr.sizes {
+                    self.rewrite_expr(size, scope);
+                }
+            }
+            Expr::ArrayAccess(arr) => {
+                self.rewrite_expr(&mut arr.array, scope);
+                self.rewrite_expr(&mut arr.index, scope);
+            }
+            Expr::ArrayInit(init) => {
+                for element in &mut init.elements {
+                    self.rewrite_expr(element, scope);
+                }
+            }
+            Expr::MethodRef(method_ref) => {
+                if let Some(object) = &mut method_ref.object {
+                    self.rewrite_expr(object, scope);
+                }
+                if let Some(class_name) = &method_ref.class_name {
+                    if scope.origin.as_deref() == Some(class_name.as_str()) {
+                        method_ref.class_name = Some(scope.class_name.clone());
+                    }
+                }
+            }
+            Expr::Lambda(lambda) => {
+                for param in &mut lambda.params {
+                    if let Some(param_type) = &param.param_type {
+                        param.param_type = Some(self.mono_type(param_type, scope));
+                    }
+                }
+                match &mut lambda.body {
+                    LambdaBody::Expr(body) => self.rewrite_expr(body, scope),
+                    LambdaBody::Block(block) => self.rewrite_block(block, scope),
+                }
+            }
+            Expr::Ternary(ternary) => {
+                self.rewrite_expr(&mut ternary.condition, scope);
+                self.rewrite_expr(&mut ternary.true_branch, scope);
+                self.rewrite_expr(&mut ternary.false_branch, scope);
+            }
+            Expr::InstanceOf(instanceof) => {
+                instanceof.target_type = self.mono_type(&instanceof.target_type, scope);
+                self.rewrite_expr(&mut instanceof.expr, scope);
+            }
+            Expr::Alloc(alloc) => {
+                self.rewrite_expr(&mut alloc.size, scope);
+                if let Some(align) = &mut alloc.align {
+                    self.rewrite_expr(align, scope);
+                }
+            }
+            Expr::Dealloc(dealloc) => self.rewrite_expr(&mut dealloc.ptr, scope),
+            Expr::Try(try_expr) => self.rewrite_expr(&mut try_expr.expr, scope),
+            Expr::Switch(switch) => {
+                self.rewrite_expr(&mut switch.expr, scope);
+                for arm in &mut switch.arms {
+                    self.rewrite_case_labels(&mut arm.labels, scope);
+                    if let Some(guard) = &mut arm.guard {
+                        self.rewrite_expr(guard, scope);
+                    }
+                    self.rewrite_expr(&mut arm.value, scope);
+                }
+                if let Some(default) = &mut switch.default {
+                    self.rewrite_expr(default, scope);
+                }
+            }
+            Expr::SafeCall(safe_call) => {
+                self.rewrite_expr(&mut safe_call.object, scope);
+                for arg in safe_call.args.iter_mut().flatten() {
+                    self.rewrite_expr(arg, scope);
+                }
+            }
+            Expr::Elvis(elvis) => {
+                self.rewrite_expr(&mut elvis.value, scope);
+                self.rewrite_expr(&mut elvis.default, scope);
+            }
+        }
+    }
+
+    /// 类型模式中的类型实参替换为具体类型
+    fn rewrite_case_labels(&mut self, labels: &mut [CaseLabel], scope: &MonoScope) {
+        for label in labels {
+            if let CaseLabel::Type(pattern) = label {
+                pattern.target_type = self.mono_type(&pattern.target_type, scope);
+            }
+        }
+    }
+
+    /// 把泛型方法调用改写为对特化方法的调用（max(a, b) -> max$i32(a, b)）
+    fn rewrite_generic_call(&mut self, call: &mut CallExpr, scope: &MonoScope) {
+        let generic_call = match self.registry.generic_method_calls.get(&call.loc) {
+            Some(generic_call) => generic_call.clone(),
+            None => return,
+        };
+        let type_args: Vec<Type> = generic_call.type_args.iter()
+            .map(|t| self.mono_type(t, scope))
+            .collect();
+        let class_name = self.concrete_class_name(&generic_call.class_name, scope);
+        let specialized_name = mangle_generic_name(&generic_call.method_name, &type_args);
+
+        match call.callee.as_mut() {
+            Expr::Identifier(ident) => ident.name = specialized_name,
+            Expr::MemberAccess(member) => member.member = specialized_name,
+            _ => return,
+        }
+        self.method_queue.push_back(MethodRequest {
+            class_name,
+            method_name: generic_call.method_name,
+            type_args,
+        });
+    }
+}
+
+/// 根据方法声明构建方法信息（与语义分析收集方法时一致）
+fn method_info_from_decl(class_name: &str, method: &MethodDecl) -> MethodInfo {
+    MethodInfo {
+        name: method.name.clone(),
+        class_name: class_name.to_string(),
+        params: method.params.clone(),
+        return_type: method.return_type.clone(),
+        is_public: method.modifiers.contains(&Modifier::Public),
+        is_private: method.modifiers.contains(&Modifier::Private),
+        is_protected: method.modifiers.contains(&Modifier::Protected),
+        is_static: method.modifiers.contains(&Modifier::Static),
+        is_native: method.modifiers.contains(&Modifier::Native),
+        is_override: method.modifiers.contains(&Modifier::Override),
+        is_final: method.modifiers.contains(&Modifier::Final),
+        type_params: Vec::new(),
+        annotations: method.annotations.clone(),
+    }
+}
@@ -26,5 +26,15 @@ impl IRGenerator {
         self.emit_raw("  ret i8* %buf");
         self.emit_raw("}");
         self.emit_raw("");
+
+        // u64 -> String (ulong)，无符号整数都先零扩展到 i64
+        self.emit_raw("define i8* @__cay_ulong_to_string(i64 %value) {");
+        self.emit_raw("entry:");
+        self.emit_raw("  %buf = call i8* @calloc(i64 1, i64 32)");
+        self.emit_raw("  ; 使用 %llu 格式打印无符号整数");
+        self.emit_raw("  call i32 (i8*, i64, i8*, ...) @snprintf(i8* %buf, i64 32, i8* getelementptr ([5 x i8], [5 x i8]* @.str.ulong_fmt, i64 0, i64 0), i64 %value)");
+        self.emit_raw("  ret i8* %buf");
+        self.emit_raw("}");
+        self.emit_raw("");
     }
 }
@@ -21,6 +21,7 @@ mod string_charat;
 mod string_replace;
 mod string_isempty;
 mod string_equals;
+mod string_hash;
 mod buffer_to_string;
 mod ptr_operations;
 mod args_support;
@@ -123,6 +124,8 @@ impl IRGenerator {
             ("strlen", "i64", vec!["i8*"], "declare i64 @strlen(i8*)"),
             ("strcmp", "i32", vec!["i8*", "i8*"], "declare i32 @strcmp(i8*, i8*)"),
             ("calloc", "i8*", vec!["i64", "i64"], "declare i8* @calloc(i64, i64)"),
+            ("malloc", "i8*", vec!["i64"], "declare i8* @malloc(i64)"),
+            ("free", "void", vec!["i8*"], "declare void @free(i8*)"),
             ("exit", "void", vec!["i32"], "declare void @exit(i32)"),
             ("atoi", "i32", vec!["i8*"], "declare i32 @atoi(i8*)"),
             ("snprintf", "i32", vec!["i8*", "i64", "i8*", "..."], "declare i32 @snprintf(i8*, i64, i8*, ...)"),
@@ -160,6 +163,7 @@ impl IRGenerator {
         self.emit_raw("@.str.double_fmt = private unnamed_addr constant [4 x i8] c\"%lf\\00\", align 1");
         self.emit_raw("@.str.int_fmt = private unnamed_addr constant [3 x i8] c\"%d\\00\", align 1");
         self.emit_raw("@.str.long_fmt = private unnamed_addr constant [5 x i8] c\"%lld\\00\", align 1");
+        self.emit_raw("@.str.ulong_fmt = private unnamed_addr constant [5 x i8] c\"%llu\\00\", align 1");
         self.emit_raw("@.str.true_str = private unnamed_addr constant [5 x i8] c\"true\\00\", align 1");
         self.emit_raw("@.str.false_str = private unnamed_addr constant [6 x i8] c\"false\\00\", align 1");
         self.emit_raw("");
@@ -184,6 +188,7 @@ impl IRGenerator {
         self.emit_string_replace_runtime();
         self.emit_string_isempty_runtime();
         self.emit_string_equals_runtime();
+        self.emit_string_hash_runtime();
         self.emit_buffer_to_string_runtime();
 
         // 生成指针操作运行时函数
@@ -0,0 +1,38 @@
+//! String hashCode 运行时函数
+//!
+//! 实现 __cay_string_hash 函数，按 s[0]*31^(n-1) + ... + s[n-1] 计算字符串的哈希值。
+
+use crate::codegen::context::IRGenerator;
+
+impl IRGenerator {
+    /// 生成 string_hash 运行时函数
+    /// null 的哈希值为 0，字节按无符号值参与计算
+    pub(super) fn emit_string_hash_runtime(&mut self) {
+        self.emit_raw("; String.hashCode() 运行时函数");
+        self.emit_raw("define i32 @__cay_string_hash(i8* %str) {");
+        self.emit_raw("entry:");
+        self.emit_raw("  %is_null = icmp eq i8* %str, null");
+        self.emit_raw("  br i1 %is_null, label %done, label %loop");
+        self.emit_raw("");
+        self.emit_raw("loop:");
+        self.emit_raw("  %index = phi i64 [ 0, %entry ], [ %next_index, %body ]");
+        self.emit_raw("  %hash = phi i32 [ 0, %entry ], [ %next_hash, %body ]");
+        self.emit_raw("  %char_ptr = getelementptr i8, i8* %str, i64 %index");
+        self.emit_raw("  %char = load i8, i8* %char_ptr");
+        self.emit_raw("  %is_end = icmp eq i8 %char, 0");
+        self.emit_raw("  br i1 %is_end, label %done, label %body");
+        self.emit_raw("");
+        self.emit_raw("body:");
+        self.emit_raw("  %char_i32 = zext i8 %char to i32");
+        self.emit_raw("  %scaled = mul i32 %hash, 31");
+        self.emit_raw("  %next_hash = add i32 %scaled, %char_i32");
+        self.emit_raw("  %next_index = add i64 %index, 1");
+        self.emit_raw("  br label %loop");
+        self.emit_raw("");
+        self.emit_raw("done:");
+        self.emit_raw("  %result = phi i32 [ 0, %entry ], [ %hash, %loop ]");
+        self.emit_raw("  ret i32 %result");
+        self.emit_raw("}");
+        self.emit_raw("");
+    }
+}
@@ -11,12 +11,14 @@ impl IRGenerator {
     pub fn generate_block(&mut self, block: &Block) -> cayResult<()> {
         // 进入新作用域
         self.scope_manager.enter_scope();
+        self.enter_cleanup_frame();
 
         for stmt in &block.statements {
             self.generate_statement(stmt)?;
         }
 
-        // 退出作用域
+        // 退出作用域，末尾可达时先执行本块登记的清理动作
+        self.exit_cleanup_frame()?;
         self.scope_manager.exit_scope();
         Ok(())
     }
@@ -0,0 +1,165 @@
+//! defer、try-with-resources 与代码块清理的代码生成
+//!
+//! 每个代码块在 `cleanup_stack` 上有一层，defer 语句、try 的资源和 scope 块中的栈对象
+//! 登记到当前层。代码块正常结束时执行本层的清理动作；return 执行所有层，break/continue
+//! 执行到目标循环为止的各层。清理动作在每个出口处重新生成，顺序为由内向外、每层后进先出。
+
+use crate::ast::*;
+use crate::codegen::context::{Cleanup, IRGenerator};
+use crate::error::{cayResult, SourceLocation};
+use crate::types::Type;
+
+impl IRGenerator {
+    /// 生成 defer 语句：只登记到当前代码块，在代码块的各个出口处生成
+    pub fn generate_defer_statement(&mut self, defer: &DeferStmt) -> cayResult<()> {
+        let scopes = self.scope_manager.snapshot();
+        self.push_cleanup(Cleanup::Defer { stmt: defer.body.clone(), scopes });
+        Ok(())
+    }
+
+    /// 生成 try-with-resources 语句：资源声明后即登记 close()，代码块退出时按相反顺序关闭
+    pub fn generate_try_with_resources(&mut self, try_stmt: &TryStmt) -> cayResult<()> {
+        self.scope_manager.enter_scope();
+        self.enter_cleanup_frame();
+
+        for resource in &try_stmt.resources {
+            self.generate_var_decl(resource)?;
+            let scopes = self.scope_manager.snapshot();
+            self.push_cleanup(Cleanup::Close { var: resource.name.clone(), loc: resource.loc.clone(), scopes });
+        }
+        self.generate_block(&try_stmt.body)?;
+
+        self.exit_cleanup_frame()?;
+        self.scope_manager.exit_scope();
+        Ok(())
+    }
+
+    /// scope 块中声明的对象在离开 scope 时调用析构函数
+    pub(super) fn register_scope_destructor(&mut self, var: &VarDecl) {
+        let class_name = match &var.var_type {
+            Type::Object(name) => self.find_destructor_class(name),
+            _ => None,
+        };
+        let llvm_name = self.scope_manager.get_llvm_name(&var.name);
+        if let (Some(class_name), Some(llvm_name)) = (class_name, llvm_name) {
+            self.push_cleanup(Cleanup::Destroy { llvm_name, class_name });
+        }
+    }
+
+    /// 进入一层代码块的清理作用域
+    pub fn enter_cleanup_frame(&mut self) {
+        self.cleanup_stack.push(Vec::new());
+    }
+
+    /// 离开代码块：末尾可达时按后进先出执行本层登记的清理动作
+    pub fn exit_cleanup_frame(&mut self) -> cayResult<()> {
+        let frame = self.cleanup_stack.pop().unwrap_or_default();
+        if frame.is_empty() || self.code_terminated() {
+            return Ok(());
+        }
+        for cleanup in frame.iter().rev() {
+            self.emit_cleanup(cleanup)?;
+        }
+        Ok(())
+    }
+
+    /// 跳出代码块前执行第 `depth` 层及更内层的清理动作
+    pub fn emit_cleanups_from(&mut self, depth: usize) -> cayResult<()> {
+        let pending: Vec<Cleanup> = self.cleanup_stack.iter()
+            .skip(depth)
+            .rev()
+            .flat_map(|frame| frame.iter().rev().cloned())
+            .collect();
+        for cleanup in &pending {
+            self.emit_cleanup(cleanup)?;
+        }
+        Ok(())
+    }
+
+    fn push_cleanup(&mut self, cleanup: Cleanup) {
+        match self.cleanup_stack.last_mut() {
+            Some(frame) => frame.push(cleanup),
+            None => self.cleanup_stack.push(vec![cleanup]),
+        }
+    }
+
+    fn emit_cleanup(&mut self, cleanup: &Cleanup) -> cayResult<()> {
+        match cleanup {
+            Cleanup::Defer { stmt, scopes } => {
+                self.emit_line("  ; defer");
+                let current = self.scope_manager.swap_scopes(scopes.clone());
+                let result = self.generate_statement(stmt);
+                self.scope_manager.swap_scopes(current);
+                result
+            }
+            Cleanup::Close { var, loc, scopes } => {
+                let current = self.scope_manager.swap_scopes(scopes.clone());
+                let result = self.emit_resource_close(var, loc);
+                self.scope_manager.swap_scopes(current);
+                result
+            }
+            Cleanup::Destroy { llvm_name, class_name } => {
+                let object = self.new_temp();
+                self.emit_line(&format!("  {} = load i8*, i8** %{}", object, llvm_name));
+                let dtor = format!("@{}.__dtor", class_name);
+                self.emit_when_non_null(&object, |generator| {
+                    generator.emit_line(&format!("  call void {}(i8* {})", dtor, object));
+                    Ok(())
+                })
+            }
+        }
+    }
+
+    /// 资源非 null 时调用 `resource.close()`
+    fn emit_resource_close(&mut self, var: &str, loc: &SourceLocation) -> cayResult<()> {
+        let resource = Expr::Identifier(IdentifierExpr { name: var.to_string(), loc: loc.clone() });
+        let value = self.generate_expression(&resource)?;
+        let (_, object) = self.parse_typed_value(&value);
+        let close = Expr::Call(CallExpr {
+            callee: Box::new(Expr::MemberAccess(MemberAccessExpr {
+                object: Box::new(resource),
+                member: "close".to_string(),
+                loc: loc.clone(),
+            })),
+            args: Vec::new(),
+            loc: loc.clone(),
+        });
+        self.emit_when_non_null(&object, |generator| generator.generate_expression(&close).map(|_| ()))
+    }
+
+    /// 对象不为 null 时执行 `body`，之后在汇合块中继续生成代码
+    fn emit_when_non_null(&mut self, object: &str, body: impl FnOnce(&mut Self) -> cayResult<()>) -> cayResult<()> {
+        let run_label = self.new_label("cleanup.run");
+        let done_label = self.new_label("cleanup.done");
+        let is_null = self.new_temp();
+        self.emit_line(&format!("  {} = icmp eq i8* {}, null", is_null, object));
+        self.emit_line(&format!("  br i1 {}, label %{}, label %{}", is_null, done_label, run_label));
+        self.emit_line(&format!("{}:", run_label));
+        body(self)?;
+        self.emit_line(&format!("  br label %{}", done_label));
+        self.emit_line(&format!("{}:", done_label));
+        Ok(())
+    }
+
+    /// 类或其最近的父类中定义了析构函数的类名
+    fn find_destructor_class(&self, class_name: &str) -> Option<String> {
+        let registry = self.type_registry.as_ref()?;
+        let mut current = registry.get_class(class_name);
+        while let Some(info) = current {
+            if info.has_destructor {
+                return Some(info.name.clone());
+            }
+            current = info.parent.as_deref().and_then(|p| registry.get_class(p));
+        }
+        None
+    }
+
+    /// 已生成的代码是否以终止指令结束（之后的代码不可达）
+    pub(crate) fn code_terminated(&self) -> bool {
+        self.code.lines()
+            .rev()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with(';'))
+            .is_some_and(|line| line.starts_with("ret") || line.starts_with("br ") || line.starts_with("unreachable"))
+    }
+}
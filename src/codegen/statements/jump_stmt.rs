@@ -1,9 +1,10 @@
 //! 跳转语句代码生成
 //!
-//! 处理break和continue语句的代码生成。
+//! 处理break和continue语句的代码生成，跳转前执行目标循环内各层代码块的清理动作。
 
 use crate::codegen::context::IRGenerator;
 use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 生成 break 语句代码
@@ -11,12 +12,15 @@ impl IRGenerator {
         let loop_ctx = if let Some(label_name) = label {
             // 带标签的 break
             self.get_loop_by_label(label_name)
-                .ok_or_else(|| codegen_error(format!("break label '{}' not found", label_name)))?
+                .cloned()
+                .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("break label '{}' not found", label_name)))?
         } else {
             // 不带标签的 break
             self.current_loop()
-                .ok_or_else(|| codegen_error("break statement outside of loop".to_string()))?
+                .cloned()
+                .ok_or_else(|| codegen_error(ErrorCodes::SEMANTIC_BREAK_OUTSIDE_LOOP, "break statement outside of loop".to_string()))?
         };
+        self.emit_cleanups_from(loop_ctx.cleanup_depth)?;
         self.emit_line(&format!("  br label %{}", loop_ctx.end_label));
         Ok(())
     }
@@ -26,12 +30,15 @@ impl IRGenerator {
         let loop_ctx = if let Some(label_name) = label {
             // 带标签的 continue
             self.get_loop_by_label(label_name)
-                .ok_or_else(|| codegen_error(format!("continue label '{}' not found", label_name)))?
+                .cloned()
+                .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("continue label '{}' not found", label_name)))?
         } else {
             // 不带标签的 continue
             self.current_loop()
-                .ok_or_else(|| codegen_error("continue statement outside of loop".to_string()))?
+                .cloned()
+                .ok_or_else(|| codegen_error(ErrorCodes::SEMANTIC_CONTINUE_OUTSIDE_LOOP, "continue statement outside of loop".to_string()))?
         };
+        self.emit_cleanups_from(loop_ctx.cleanup_depth)?;
         self.emit_line(&format!("  br label %{}", loop_ctx.cond_label));
         Ok(())
     }
@@ -1,10 +1,14 @@
 //! 循环语句代码生成
 //!
 //! 处理while、for、do-while循环的代码生成。
+//! 增强 for 循环展开为普通 for 循环：数组按下标遍历，`range(a, b[, step])` 展开为计数循环，
+//! 可迭代对象循环调用 `hasNext()`/`next()`。
 
 use crate::codegen::context::IRGenerator;
 use crate::ast::*;
-use crate::error::cayResult;
+use crate::types::{Type, ForEachKind};
+use crate::error::{cayResult, codegen_error, SourceLocation};
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 生成 while 语句代码
@@ -33,7 +37,9 @@ impl IRGenerator {
 
         // 循环体
         self.emit_line(&format!("{}:", body_label));
+        self.enter_cleanup_frame();
         self.generate_statement(&while_stmt.body)?;
+        self.exit_cleanup_frame()?;
         self.emit_line(&format!("  br label %{}", cond_label));
 
         // 结束块
@@ -82,7 +88,9 @@ impl IRGenerator {
 
         // 循环体
         self.emit_line(&format!("{}:", body_label));
+        self.enter_cleanup_frame();
         self.generate_statement(&for_stmt.body)?;
+        self.exit_cleanup_frame()?;
         self.emit_line(&format!("  br label %{}", update_label));
 
         // 更新块
@@ -113,7 +121,9 @@ impl IRGenerator {
         // 先执行循环体
         self.emit_line(&format!("  br label %{}", body_label));
         self.emit_line(&format!("{}:", body_label));
+        self.enter_cleanup_frame();
         self.generate_statement(&do_while_stmt.body)?;
+        self.exit_cleanup_frame()?;
         self.emit_line(&format!("  br label %{}", cond_label));
 
         // 条件检查
@@ -137,4 +147,148 @@ impl IRGenerator {
 
         Ok(())
     }
+
+    /// 生成增强 for 循环代码
+    ///
+    /// 数据源和计数器保存在隐藏局部变量中，循环变量在每次迭代开始时声明并赋值。
+    pub fn generate_for_each_statement(&mut self, for_each: &ForEachStmt) -> cayResult<()> {
+        let info = self.type_registry.as_ref()
+            .and_then(|registry| registry.for_each_loops.get(&for_each.loc).cloned())
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, format!(
+                "for-each loop at line {} was not analyzed", for_each.loc.line
+            )))?;
+        // 单态化后的声明类型已替换类型参数，优先使用
+        let variable_type = if for_each.var_type == Type::Auto {
+            info.variable.clone()
+        } else {
+            for_each.var_type.clone()
+        };
+
+        self.scope_manager.enter_scope();
+        let (condition, update, element) = match info.kind {
+            ForEachKind::Array => {
+                self.declare_hidden_local(for_each, "iter", info.source.clone(), for_each.iterable.clone())?;
+                self.declare_hidden_local(for_each, "index", Type::Int32, Expr::Literal(LiteralValue::Int32(0)))?;
+                let index = Self::hidden_var(for_each, "index");
+                let length = Expr::MemberAccess(MemberAccessExpr {
+                    object: Box::new(Self::hidden_var(for_each, "iter")),
+                    member: "length".to_string(),
+                    loc: for_each.loc.clone(),
+                });
+                let element = Expr::ArrayAccess(ArrayAccessExpr {
+                    array: Box::new(Self::hidden_var(for_each, "iter")),
+                    index: Box::new(index.clone()),
+                    loc: for_each.loc.clone(),
+                });
+                let update = Self::add_assign(index.clone(), Expr::Literal(LiteralValue::Int32(1)), &for_each.loc);
+                (Self::binary(index, BinaryOp::Lt, length, &for_each.loc), Some(update), element)
+            }
+            ForEachKind::Range => {
+                let args = for_each.range_args()
+                    .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS, "range() loop without arguments".to_string()))?;
+                let counter = Self::hidden_var(for_each, "iter");
+                let end = Self::hidden_var(for_each, "end");
+                let step = args.get(2).cloned().unwrap_or(Expr::Literal(LiteralValue::Int32(1)));
+                let step_sign = Self::literal_sign(&step);
+                self.declare_hidden_local(for_each, "iter", info.source.clone(), args[0].clone())?;
+                self.declare_hidden_local(for_each, "end", info.source.clone(), args[1].clone())?;
+                self.declare_hidden_local(for_each, "step", info.source.clone(), step)?;
+                let step = Self::hidden_var(for_each, "step");
+
+                let ascending = Self::binary(counter.clone(), BinaryOp::Lt, end.clone(), &for_each.loc);
+                let descending = Self::binary(counter.clone(), BinaryOp::Gt, end, &for_each.loc);
+                // 步长为字面量时只需比较一个方向，否则按运行时步长的符号选择
+                let condition = match step_sign {
+                    Some(sign) if sign > 0 => ascending,
+                    Some(sign) if sign < 0 => descending,
+                    _ => {
+                        let zero = Expr::Literal(LiteralValue::Int32(0));
+                        let up = Self::binary(
+                            Self::binary(step.clone(), BinaryOp::Gt, zero.clone(), &for_each.loc),
+                            BinaryOp::And, ascending, &for_each.loc,
+                        );
+                        let down = Self::binary(
+                            Self::binary(step.clone(), BinaryOp::Lt, zero, &for_each.loc),
+                            BinaryOp::And, descending, &for_each.loc,
+                        );
+                        Self::binary(up, BinaryOp::Or, down, &for_each.loc)
+                    }
+                };
+                let update = Self::add_assign(counter.clone(), step, &for_each.loc);
+                (condition, Some(update), counter)
+            }
+            ForEachKind::Iterable | ForEachKind::Iterator => {
+                let source = if info.kind == ForEachKind::Iterable {
+                    for_each.iterator_expr()
+                } else {
+                    for_each.iterable.clone()
+                };
+                self.declare_hidden_local(for_each, "iter", info.source.clone(), source)?;
+                (for_each.has_next_expr(), None, for_each.next_expr())
+            }
+        };
+
+        let loop_var = Stmt::VarDecl(VarDecl {
+            name: for_each.var_name.clone(),
+            var_type: variable_type,
+            initializer: Some(element),
+            is_final: for_each.is_final,
+            loc: for_each.loc.clone(),
+        });
+        let result = self.generate_for_statement(&ForStmt {
+            init: None,
+            condition: Some(condition),
+            update,
+            body: Box::new(Stmt::Block(Block {
+                statements: vec![loop_var, for_each.body.as_ref().clone()],
+                loc: for_each.loc.clone(),
+            })),
+            label: for_each.label.clone(),
+            loc: for_each.loc.clone(),
+        });
+        self.scope_manager.exit_scope();
+        result
+    }
+
+    /// 声明增强 for 循环使用的隐藏局部变量
+    fn declare_hidden_local(&mut self, for_each: &ForEachStmt, role: &str, var_type: Type, initializer: Expr) -> cayResult<()> {
+        self.generate_var_decl(&VarDecl {
+            name: for_each.hidden_name(role),
+            var_type,
+            initializer: Some(initializer),
+            is_final: false,
+            loc: for_each.loc.clone(),
+        })
+    }
+
+    fn hidden_var(for_each: &ForEachStmt, role: &str) -> Expr {
+        Expr::Identifier(IdentifierExpr {
+            name: for_each.hidden_name(role),
+            loc: for_each.loc.clone(),
+        })
+    }
+
+    fn binary(left: Expr, op: BinaryOp, right: Expr, loc: &SourceLocation) -> Expr {
+        Expr::Binary(BinaryExpr { left: Box::new(left), op, right: Box::new(right), loc: loc.clone() })
+    }
+
+    /// `target = target + value`
+    fn add_assign(target: Expr, value: Expr, loc: &SourceLocation) -> Expr {
+        Expr::Assignment(AssignmentExpr {
+            target: Box::new(target.clone()),
+            value: Box::new(Self::binary(target, BinaryOp::Add, value, loc)),
+            op: AssignOp::Assign,
+            loc: loc.clone(),
+        })
+    }
+
+    /// 整数字面量（含取负）的符号，其他表达式返回 None
+    fn literal_sign(expr: &Expr) -> Option<i64> {
+        match expr {
+            Expr::Literal(LiteralValue::Int32(v)) => Some((*v as i64).signum()),
+            Expr::Literal(LiteralValue::Int64(v)) => Some(v.signum()),
+            Expr::Unary(unary) if unary.op == UnaryOp::Neg => Self::literal_sign(&unary.operand).map(|sign| -sign),
+            _ => None,
+        }
+    }
 }
@@ -10,6 +10,7 @@ mod loops;
 mod switch_stmt;
 mod jump_stmt;
 mod scope_stmt;
+mod defer_stmt;
 mod statement;
 
 pub use block::*;
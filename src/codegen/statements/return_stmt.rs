@@ -10,9 +10,13 @@ impl IRGenerator {
     /// 生成return语句代码
     pub fn generate_return_statement(&mut self, expr: &Option<Expr>) -> cayResult<()> {
         if let Some(e) = expr.as_ref() {
+            let ret_type = self.current_return_type.clone();
             let value = self.generate_expression(e)?;
+            let value = self.widen_unsigned_value(e, &value, &ret_type);
             let (value_type, val) = self.parse_typed_value(&value);
-            let ret_type = self.current_return_type.clone();
+
+            // 返回值先求值，再执行所有代码块登记的清理动作
+            self.emit_cleanups_from(0)?;
 
             // 如果返回类型是 void，但表达式非空，这是错误（但由语义分析处理）
             if ret_type == "void" {
@@ -76,6 +80,7 @@ impl IRGenerator {
                 self.emit_line(&format!("  ret {}", value));
             }
         } else {
+            self.emit_cleanups_from(0)?;
             self.emit_line("  ret void");
         }
 
@@ -1,9 +1,9 @@
 //! 0.5.0.0: scope 语句代码生成
 //!
 //! scope 语句创建一个栈作用域，用于在栈上分配临时对象。
-//! 在 scope 结束时，所有分配的栈内存自动释放。
+//! 在 scope 结束时，所有分配的栈内存自动释放，声明的对象调用析构函数。
 
-use crate::ast::ScopeStmt;
+use crate::ast::{ScopeStmt, Stmt};
 use crate::codegen::context::IRGenerator;
 use crate::error::cayResult;
 
@@ -61,16 +61,29 @@ impl IRGenerator {
         // 在 scope 开始时创建一个新的作用域层级
         // 这使得 scope 内部声明的变量不会与外部冲突
         self.scope_manager.enter_scope();
+        self.enter_cleanup_frame();
         
-        // 生成 scope 体内的所有语句
+        // 生成 scope 体内的所有语句，声明的对象登记析构函数调用
         for statement in &stmt.body.statements {
             self.generate_statement(statement)?;
+            match statement {
+                Stmt::VarDecl(var) => self.register_scope_destructor(var),
+                Stmt::Block(block) if block.statements.iter().all(|s| matches!(s, Stmt::VarDecl(_))) => {
+                    for var in block.statements.iter().filter_map(|s| match s {
+                        Stmt::VarDecl(var) => Some(var),
+                        _ => None,
+                    }) {
+                        self.register_scope_destructor(var);
+                    }
+                }
+                _ => {}
+            }
         }
         
         // scope 结束时，生成清理代码
-        // 注意：对于栈分配（alloca），实际上不需要显式释放
-        // LLVM 在函数返回时自动清理所有 alloca
-        // 但为了完整性，我们记录 scope 的结束
+        // 栈内存（alloca）在函数返回时由 LLVM 自动释放，对象的析构函数与 defer 一样
+        // 在 scope 的每个出口（正常结束、return、break、continue）按声明的相反顺序调用
+        self.exit_cleanup_frame()?;
         self.emit_line(&format!("; ====== scope {} end ======", scope_id));
         
         // 弹出 scope 层级
@@ -6,6 +6,7 @@ use crate::codegen::context::IRGenerator;
 use crate::codegen::bridge::{InlineIrBridge, InlineIrResult};
 use crate::ast::*;
 use crate::error::cayResult;
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 生成单个语句代码
@@ -46,16 +47,25 @@ impl IRGenerator {
             }
             Stmt::If(if_stmt) => {
                 self.set_source_from_loc(&if_stmt.loc, &source_file);
+                self.declare_pattern_slots(&if_stmt.condition);
                 self.generate_if_statement(if_stmt)?;
             }
             Stmt::While(while_stmt) => {
                 self.set_source_from_loc(&while_stmt.loc, &source_file);
+                self.declare_pattern_slots(&while_stmt.condition);
                 self.generate_while_statement(while_stmt)?;
             }
             Stmt::For(for_stmt) => {
                 self.set_source_from_loc(&for_stmt.loc, &source_file);
+                if let Some(condition) = &for_stmt.condition {
+                    self.declare_pattern_slots(condition);
+                }
                 self.generate_for_statement(for_stmt)?;
             }
+            Stmt::ForEach(for_each) => {
+                self.set_source_from_loc(&for_each.loc, &source_file);
+                self.generate_for_each_statement(for_each)?;
+            }
             Stmt::DoWhile(do_while_stmt) => {
                 self.set_source_from_loc(&do_while_stmt.loc, &source_file);
                 self.generate_do_while_statement(do_while_stmt)?;
@@ -68,6 +78,13 @@ impl IRGenerator {
                 self.set_source_from_loc(&scope_stmt.loc, &source_file);
                 self.generate_scope(scope_stmt)?;
             }
+            Stmt::Defer(defer) => {
+                self.generate_defer_statement(defer)?;
+            }
+            Stmt::TryWithResources(try_stmt) => {
+                self.set_source_from_loc(&try_stmt.loc, &source_file);
+                self.generate_try_with_resources(try_stmt)?;
+            }
             Stmt::Break(label) => {
                 self.generate_break_statement(label)?;
             }
@@ -79,6 +96,9 @@ impl IRGenerator {
                 // 0.5.0.0: 使用CodeGen-IR Builder协作桥处理内联IR
                 self.generate_inline_ir(inline_ir)?;
             }
+            Stmt::Error(_) => {
+                return Err(crate::error::codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "Cannot generate code for a statement with syntax errors".to_string()));
+            }
         }
         Ok(())
     }
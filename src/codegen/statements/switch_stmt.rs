@@ -1,167 +1,500 @@
 //! Switch语句代码生成
 //!
-//! 处理switch-case语句的代码生成。
+//! 处理switch-case语句和switch表达式的代码生成。
+//!
+//! 整数、字符和枚举（按变体标签）用 LLVM `switch` 指令分派；字符串先按长度分派，
+//! 再在长度相同的标签中依次调用 `__cay_string_equals` 比较。带守卫的分支在守卫为假时
+//! 继续尝试同一分派值下的后续分支，全部失败时进入 default。类或接口上的 switch 按书写顺序
+//! 逐个测试类型模式，匹配的值存入该分支的模式变量，守卫和分支体都可以读取它。
 
 use crate::codegen::context::IRGenerator;
 use crate::ast::*;
-use crate::error::cayResult;
+use crate::types::{Type, EnumInfo};
+use crate::error::{cayResult, codegen_error, SourceLocation};
+use crate::diagnostic::ErrorCodes;
+
+/// 分支的标签与守卫
+type CaseHead<'a> = (&'a [CaseLabel], Option<&'a Expr>);
+
+/// 分派值相同的一组匹配候选: (分支下标, 需要比较的字符串标签)
+type DispatchBucket = (i64, Vec<(usize, Option<String>)>);
 
 impl IRGenerator {
     /// 生成 switch 语句代码
     pub fn generate_switch_statement(&mut self, switch_stmt: &SwitchStmt) -> cayResult<()> {
+        // 枚举上的 switch 按变体标签分派
+        let enum_info = self.enum_switch_info(&switch_stmt.loc, &switch_stmt.expr,
+            switch_stmt.cases.iter().flat_map(|c| c.labels.iter()));
+        let heads: Vec<CaseHead> = switch_stmt.cases.iter().map(|c| (c.labels.as_slice(), c.guard.as_ref())).collect();
+        // 覆盖所有变体且没有 default 的枚举 switch，默认分支不可达
+        let exhaustive_enum = switch_stmt.default.is_none()
+            && enum_info.as_ref().is_some_and(|info| Self::covers_all_variants(info, &heads));
+        // 没有 default 的类型模式 switch 覆盖了所有类型，只有 null 会到达默认分支
+        let type_switch = self.is_type_switch(&switch_stmt.loc);
+
         let end_label = self.new_label("switch.end");
-        let default_label = if switch_stmt.default.is_some() {
+        let default_label = if switch_stmt.default.is_some() || exhaustive_enum || type_switch {
             self.new_label("switch.default")
         } else {
             end_label.clone()
         };
+        // 没有 default 时未匹配的值直接到达结束块
+        let mut end_reachable = default_label == end_label;
+
+        let case_labels: Vec<String> = (0..switch_stmt.cases.len())
+            .map(|_| self.new_label("switch.case"))
+            .collect();
+        let (selector, type_slots) = if type_switch {
+            (String::new(), self.emit_type_switch_dispatch(&switch_stmt.expr, &heads, &case_labels, &default_label)?)
+        } else {
+            let selector = self.emit_switch_dispatch(&switch_stmt.loc, &switch_stmt.expr, &heads,
+                &case_labels, &default_label, enum_info.as_ref())?;
+            (selector, Vec::new())
+        };
+
+        // 生成 case 块，未以 break/return 结束的 case 穿透到下一个 case
+        for (i, case) in switch_stmt.cases.iter().enumerate() {
+            self.emit_line(&format!("{}:", case_labels[i]));
 
-        // 生成条件表达式
-        let expr = self.generate_expression(&switch_stmt.expr)?;
-        let (expr_type, expr_val) = self.parse_typed_value(&expr);
+            // 枚举变体分支拥有独立作用域，先绑定负载字段
+            let pattern = match &enum_info {
+                Some(info) => case.pattern().map(|pattern| (info, pattern)),
+                None => None,
+            };
+            if let Some((info, pattern)) = pattern {
+                self.scope_manager.enter_scope();
+                self.bind_enum_pattern(info, pattern, &selector)?;
+            }
+            let type_binding = self.enter_type_case(case.type_pattern(), &type_slots, i);
+
+            let terminated = self.generate_case_body(&case.body, &end_label, &mut end_reachable)?;
+
+            if pattern.is_some() || type_binding {
+                self.scope_manager.exit_scope();
+            }
 
-        // 创建 case 标签
-        let mut case_labels: Vec<(i64, String, usize)> = Vec::new();
-        for (idx, case) in switch_stmt.cases.iter().enumerate() {
-            let label = self.new_label(&format!("switch.case.{}", case.value));
-            case_labels.push((case.value, label, idx));
+            if !terminated {
+                // 最后一个 case 没有 break，穿透到 default 或结束
+                let next_label = match (case_labels.get(i + 1), &switch_stmt.default) {
+                    (Some(next), _) => next.clone(),
+                    (None, Some(_)) => default_label.clone(),
+                    (None, None) => end_label.clone(),
+                };
+                if next_label == end_label {
+                    end_reachable = true;
+                }
+                self.emit_line(&format!("  br label %{}", next_label));
+            }
         }
 
-        // 将表达式值转换为 i64（如果还不是的话）
-        let switch_val = if expr_type == "i64" {
-            expr_val.to_string()
+        // 生成 default 块
+        if let Some(default_body) = switch_stmt.default.as_ref() {
+            self.emit_line(&format!("{}:", default_label));
+            if !self.generate_case_body(default_body, &end_label, &mut end_reachable)? {
+                self.emit_line(&format!("  br label %{}", end_label));
+                end_reachable = true;
+            }
+        } else if exhaustive_enum {
+            self.emit_line(&format!("{}:", default_label));
+            self.emit_line("  unreachable");
+        } else if type_switch {
+            self.emit_unmatched_switch_block(&default_label);
+        }
+
+        // 结束块 - 只有当并非所有分支都 return 时才生成
+        if end_reachable {
+            self.emit_line(&format!("{}:", end_label));
+        }
+
+        Ok(())
+    }
+
+    /// 生成 switch 表达式代码，各分支的值经 phi 合并
+    pub fn generate_switch_expression(&mut self, switch: &SwitchExpr) -> cayResult<String> {
+        let result_type = match self.type_registry.as_ref().and_then(|r| r.switch_types.get(&switch.loc)) {
+            Some(ty) => self.type_to_llvm(ty),
+            None => return Err(codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, format!(
+                "Unknown type of switch expression at line {}", switch.loc.line
+            ))),
+        };
+        let enum_info = self.enum_switch_info(&switch.loc, &switch.expr,
+            switch.arms.iter().flat_map(|arm| arm.labels.iter()));
+        let heads: Vec<CaseHead> = switch.arms.iter().map(|arm| (arm.labels.as_slice(), arm.guard.as_ref())).collect();
+
+        let end_label = self.new_label("switch.end");
+        let default_label = self.new_label("switch.default");
+        let arm_labels: Vec<String> = (0..switch.arms.len())
+            .map(|_| self.new_label("switch.case"))
+            .collect();
+        let (selector, type_slots) = if self.is_type_switch(&switch.loc) {
+            (String::new(), self.emit_type_switch_dispatch(&switch.expr, &heads, &arm_labels, &default_label)?)
         } else {
-            let temp = self.new_temp();
-            self.emit_line(&format!("  {} = sext {} {} to i64", temp, expr_type, expr_val));
-            temp
+            let selector = self.emit_switch_dispatch(&switch.loc, &switch.expr, &heads,
+                &arm_labels, &default_label, enum_info.as_ref())?;
+            (selector, Vec::new())
         };
 
-        // 生成 switch 指令
-        self.emit_line(&format!("  switch i64 {}, label %{} [", switch_val, default_label));
-        for (value, label, _) in &case_labels {
-            self.emit_line(&format!("    i64 {}, label %{}", value, label));
+        let mut incoming = Vec::new();
+        for (i, arm) in switch.arms.iter().enumerate() {
+            self.emit_line(&format!("{}:", arm_labels[i]));
+            let pattern = match &enum_info {
+                Some(info) => arm.pattern().map(|pattern| (info, pattern)),
+                None => None,
+            };
+            if let Some((info, pattern)) = pattern {
+                self.scope_manager.enter_scope();
+                self.bind_enum_pattern(info, pattern, &selector)?;
+            }
+            let type_binding = self.enter_type_case(arm.type_pattern(), &type_slots, i);
+            incoming.push(self.emit_switch_arm_value(&arm.value, &result_type, &end_label)?);
+            if pattern.is_some() || type_binding {
+                self.scope_manager.exit_scope();
+            }
         }
-        self.emit_line("  ]");
 
-        // 跟踪是否所有分支都终止（return）- break 不算终止，因为它只是跳转到 switch.end
-        let mut all_cases_terminate = true;
-
-        // 生成 case 块
-        let mut fallthrough = false;
-        for i in 0..case_labels.len() {
-            let (value, label, case_idx) = &case_labels[i];
-            let case = &switch_stmt.cases[*case_idx];
-            self.emit_line(&format!("{}:", label));
-
-            // 执行 case 体
-            if case.body.is_empty() {
-                // 空的 case 体，直接穿透到下一个 case
-                fallthrough = true;
-                all_cases_terminate = false;
-            } else {
-                let mut has_return = false;
-                for (j, stmt) in case.body.iter().enumerate() {
-                    match stmt {
-                        Stmt::Break(label) => {
-                            // 带标签的 break 跳出对应的循环，不带标签的 break 跳出 switch
-                            if label.is_some() {
-                                // 带标签的 break，使用通用处理
-                                self.generate_break_statement(label)?;
-                            } else {
-                                // 不带标签的 break，跳出 switch
-                                self.emit_line(&format!("  br label %{}", end_label));
-                            }
-                            fallthrough = false;
-                            // break 不算函数终止，只是跳转到 switch.end
-                            all_cases_terminate = false;
-                            break;
-                        }
-                        Stmt::Return(_) => {
-                            // return 语句终止执行，不需要生成 br
-                            self.generate_statement(stmt)?;
-                            fallthrough = false;
-                            has_return = true;
-                            break;
-                        }
-                        _ => {
-                            self.generate_statement(stmt)?;
-                            // 如果不是最后一条，继续执行
-                            if j == case.body.len() - 1 {
-                                // 最后一条语句，检查是否需要穿透
-                                fallthrough = true;
-                            }
-                        }
-                    }
+        match &switch.default {
+            Some(default) => {
+                self.emit_line(&format!("{}:", default_label));
+                incoming.push(self.emit_switch_arm_value(default, &result_type, &end_label)?);
+            }
+            // 类型模式覆盖了所有类型时只有 null 会到达默认分支
+            None if self.is_type_switch(&switch.loc) => self.emit_unmatched_switch_block(&default_label),
+            // 语义分析保证没有 default 的 switch 表达式覆盖了枚举的所有变体
+            None => {
+                self.emit_line(&format!("{}:", default_label));
+                self.emit_line("  unreachable");
+            }
+        }
+
+        self.emit_line(&format!("{}:", end_label));
+        let result = self.new_temp();
+        let phi_args: Vec<String> = incoming.iter()
+            .map(|(value, label)| format!("[ {}, %{} ]", value, label))
+            .collect();
+        self.emit_line(&format!("  {} = phi {} {}", result, result_type, phi_args.join(", ")));
+        Ok(format!("{} {}", result_type, result))
+    }
+
+    /// 计算 switch 表达式分支的值并跳转到结束块，返回 (值, 前驱块标签)
+    fn emit_switch_arm_value(&mut self, value: &Expr, result_type: &str, end_label: &str) -> cayResult<(String, String)> {
+        let typed = self.generate_expression(value)?;
+        let (value_type, val) = self.parse_typed_value(&typed);
+        let val = self.coerce_to_llvm_type(&value_type, &val, result_type);
+        // 分支值可能包含控制流，单独的出口块作为 phi 的前驱
+        let exit_label = self.new_label("switch.value");
+        self.emit_line(&format!("  br label %{}", exit_label));
+        self.emit_line(&format!("{}:", exit_label));
+        self.emit_line(&format!("  br label %{}", end_label));
+        Ok((val, exit_label))
+    }
+
+    /// 生成 case 体，返回末尾是否已经跳出（break、return 等）
+    fn generate_case_body(&mut self, body: &[Stmt], end_label: &str, end_reachable: &mut bool) -> cayResult<bool> {
+        // case 体拥有独立的清理层，其中的 defer 在跳出或穿透 case 时执行
+        self.enter_cleanup_frame();
+        for stmt in body {
+            match stmt {
+                // 不带标签的 break 跳出 switch；带标签的 break 跳出对应的循环
+                Stmt::Break(None) => {
+                    self.exit_cleanup_frame()?;
+                    self.emit_line(&format!("  br label %{}", end_label));
+                    *end_reachable = true;
+                    return Ok(true);
                 }
-                // 如果 case 体有 return，跳过 br 生成
-                if has_return {
-                    fallthrough = false;
-                } else {
-                    all_cases_terminate = false;
+                _ => {
+                    let before = self.code.len();
+                    self.generate_statement(stmt)?;
+                    if self.code_terminated_since(before) {
+                        self.cleanup_stack.pop();
+                        return Ok(true);
+                    }
                 }
             }
+        }
+        self.exit_cleanup_frame()?;
+        Ok(false)
+    }
 
-            // 如果不是 return，穿透到下一个 case
-            if fallthrough && i < case_labels.len() - 1 {
-                let (_, next_label, _) = &case_labels[i + 1];
-                self.emit_line(&format!("  br label %{}", next_label));
-                fallthrough = false;
-                all_cases_terminate = false;
-            } else if fallthrough {
-                // 最后一个 case 没有 break，穿透到 default 或结束
-                if switch_stmt.default.is_some() {
-                    self.emit_line(&format!("  br label %{}", default_label));
-                } else {
-                    self.emit_line(&format!("  br label %{}", end_label));
+    /// 自 `start` 以来生成的代码是否以终止指令结束
+    fn code_terminated_since(&self, start: usize) -> bool {
+        match self.code[start..].trim().lines().last() {
+            Some(last_line) => {
+                let trimmed = last_line.trim();
+                trimmed.starts_with("ret") || trimmed.starts_with("br ") || trimmed.starts_with("unreachable")
+            }
+            None => false,
+        }
+    }
+
+    /// 没有守卫的变体模式是否覆盖了枚举的所有变体
+    fn covers_all_variants(info: &EnumInfo, heads: &[CaseHead]) -> bool {
+        info.variants.iter().all(|v| heads.iter().any(|(labels, guard)| {
+            guard.is_none() && labels.iter().any(|label| label.pattern().is_some_and(|p| p.variant == v.name))
+        }))
+    }
+
+    /// 生成 switch 分派：计算分派值，跳转到匹配的分支标签，返回 switch 值本身（用于绑定负载）
+    fn emit_switch_dispatch(
+        &mut self,
+        loc: &SourceLocation,
+        selector: &Expr,
+        heads: &[CaseHead],
+        case_labels: &[String],
+        default_label: &str,
+        enum_info: Option<&EnumInfo>,
+    ) -> cayResult<String> {
+        let typed = self.generate_expression(selector)?;
+        let typed = self.widen_unsigned_value(selector, &typed, "i64");
+        let (selector_type, selector_val) = self.parse_typed_value(&typed);
+        let is_string = selector_type == "i8*" && enum_info.is_none();
+
+        // 将 switch 值转换为 i64 分派值：枚举取标签，字符串取长度
+        let key = if enum_info.is_some() {
+            self.load_enum_tag(&selector_val)
+        } else if is_string {
+            let len = self.new_temp();
+            self.emit_line(&format!("  {} = call i32 @__cay_string_length(i8* {})", len, selector_val));
+            let len64 = self.new_temp();
+            self.emit_line(&format!("  {} = sext i32 {} to i64", len64, len));
+            len64
+        } else if selector_type == "i64" {
+            selector_val.to_string()
+        } else {
+            let temp = self.new_temp();
+            self.emit_line(&format!("  {} = sext {} {} to i64", temp, selector_type, selector_val));
+            temp
+        };
+
+        // 按分派值分组，组内保持分支的书写顺序
+        let mut buckets: Vec<DispatchBucket> = Vec::new();
+        for (idx, (labels, _)) in heads.iter().enumerate() {
+            for label in labels.iter() {
+                let (value, text) = self.case_dispatch_value(loc, label, enum_info)?;
+                match buckets.iter_mut().find(|(v, _)| *v == value) {
+                    Some((_, candidates)) => candidates.push((idx, text)),
+                    None => buckets.push((value, vec![(idx, text)])),
                 }
-                fallthrough = false;
-                all_cases_terminate = false;
             }
         }
 
-        // 生成 default 块
-        if let Some(default_body) = switch_stmt.default.as_ref() {
-            self.emit_line(&format!("{}:", default_label));
-            let mut has_return = false;
-            for stmt in default_body {
-                match stmt {
-                    Stmt::Break(label) => {
-                        // 带标签的 break 跳出对应的循环，不带标签的 break 跳出 switch
-                        if label.is_some() {
-                            self.generate_break_statement(label)?;
-                        } else {
-                            self.emit_line(&format!("  br label %{}", end_label));
-                        }
-                        // break 不算函数终止
-                        all_cases_terminate = false;
-                        break;
+        // 只有一个无需比较、没有守卫的候选时直接跳转到分支，否则先进入匹配块
+        let targets: Vec<String> = buckets.iter()
+            .map(|(_, candidates)| match candidates.as_slice() {
+                [(idx, None)] if heads[*idx].1.is_none() => case_labels[*idx].clone(),
+                _ => self.new_label("switch.match"),
+            })
+            .collect();
+
+        self.emit_line(&format!("  switch i64 {}, label %{} [", key, default_label));
+        for ((value, _), target) in buckets.iter().zip(&targets) {
+            self.emit_line(&format!("    i64 {}, label %{}", value, target));
+        }
+        self.emit_line("  ]");
+
+        for ((_, candidates), target) in buckets.iter().zip(&targets) {
+            if case_labels.contains(target) {
+                continue;
+            }
+            self.emit_line(&format!("{}:", target));
+            for (i, (idx, text)) in candidates.iter().enumerate() {
+                let next_label = if i + 1 < candidates.len() {
+                    self.new_label("switch.next")
+                } else {
+                    default_label.to_string()
+                };
+                if let Some(text) = text {
+                    let literal = self.generate_expression(&Expr::Literal(LiteralValue::String(text.clone())))?;
+                    let equal = self.new_temp();
+                    self.emit_line(&format!("  {} = call i1 @__cay_string_equals(i8* {}, {})", equal, selector_val, literal));
+                    let matched_label = match heads[*idx].1 {
+                        Some(_) => self.new_label("switch.guard"),
+                        None => case_labels[*idx].clone(),
+                    };
+                    self.emit_line(&format!("  br i1 {}, label %{}, label %{}", equal, matched_label, next_label));
+                    if heads[*idx].1.is_some() {
+                        self.emit_line(&format!("{}:", matched_label));
                     }
-                    Stmt::Return(_) => {
-                        // return 语句终止执行，不需要生成 br
-                        self.generate_statement(stmt)?;
-                        has_return = true;
-                        break;
+                }
+                match heads[*idx].1 {
+                    Some(guard) => {
+                        let holds = self.emit_case_guard(heads[*idx].0, guard, &selector_val, enum_info)?;
+                        self.emit_line(&format!("  br i1 {}, label %{}, label %{}", holds, case_labels[*idx], next_label));
                     }
-                    _ => {
-                        self.generate_statement(stmt)?;
+                    None if text.is_none() => {
+                        self.emit_line(&format!("  br label %{}", case_labels[*idx]));
                     }
+                    None => {}
+                }
+                if i + 1 < candidates.len() {
+                    self.emit_line(&format!("{}:", next_label));
                 }
             }
-            // 如果 default 体没有 return，跳转到结束
-            if !has_return {
-                self.emit_line(&format!("  br label %{}", end_label));
-                all_cases_terminate = false;
+        }
+
+        Ok(selector_val.to_string())
+    }
+
+    /// 计算守卫条件；变体模式的绑定在守卫中可见，分支体中会重新绑定
+    fn emit_case_guard(&mut self, labels: &[CaseLabel], guard: &Expr, selector_val: &str, enum_info: Option<&EnumInfo>) -> cayResult<String> {
+        let pattern = match enum_info {
+            Some(info) => labels.iter().find_map(CaseLabel::pattern).map(|pattern| (info, pattern)),
+            None => None,
+        };
+        if let Some((info, pattern)) = pattern {
+            self.scope_manager.enter_scope();
+            self.bind_enum_pattern(info, pattern, selector_val)?;
+        }
+        let holds = self.emit_guard_condition(guard);
+        if pattern.is_some() {
+            self.scope_manager.exit_scope();
+        }
+        holds
+    }
+
+    /// 计算守卫条件的 i1 值
+    fn emit_guard_condition(&mut self, guard: &Expr) -> cayResult<String> {
+        let cond = self.generate_expression(guard)?;
+        let (cond_type, cond_val) = self.parse_typed_value(&cond);
+        if cond_type == "i1" {
+            return Ok(cond_val.to_string());
+        }
+        let holds = self.new_temp();
+        self.emit_line(&format!("  {} = icmp ne {} {}, 0", holds, cond_type, cond_val));
+        Ok(holds)
+    }
+
+    /// 语义分析是否把该 switch 记录为类型模式 switch
+    fn is_type_switch(&self, loc: &SourceLocation) -> bool {
+        self.type_registry.as_ref().is_some_and(|r| r.type_switches.contains_key(loc))
+    }
+
+    /// 生成类型模式 switch 的分派：按书写顺序测试各分支的类型和守卫，返回各分支模式变量的 LLVM 名称
+    fn emit_type_switch_dispatch(
+        &mut self,
+        selector: &Expr,
+        heads: &[CaseHead],
+        case_labels: &[String],
+        default_label: &str,
+    ) -> cayResult<Vec<Option<String>>> {
+        let typed = self.generate_expression(selector)?;
+        let (selector_type, selector_val) = self.parse_typed_value(&typed);
+
+        // 模式变量在分派之前分配并保存 switch 值，以支配守卫和分支体
+        let mut slots = Vec::new();
+        for (labels, _) in heads {
+            let slot = match labels.iter().find_map(CaseLabel::type_pattern) {
+                Some(pattern) => {
+                    let slot = self.new_label(&pattern.binding);
+                    self.emit_line(&format!("  %{} = alloca i8*", slot));
+                    self.emit_line(&format!("  store {} {}, {}* %{}", selector_type, selector_val, selector_type, slot));
+                    Some(slot)
+                }
+                None => None,
+            };
+            slots.push(slot);
+        }
+
+        let mut next_label = self.new_label("switch.test");
+        self.emit_line(&format!("  br label %{}", next_label));
+        for (idx, (labels, guard)) in heads.iter().enumerate() {
+            self.emit_line(&format!("{}:", next_label));
+            next_label = if idx + 1 < heads.len() {
+                self.new_label("switch.test")
+            } else {
+                default_label.to_string()
+            };
+            let pattern = labels.iter().find_map(CaseLabel::type_pattern)
+                .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, "Switch on a class or interface value requires type patterns".to_string()))?;
+            let target_class = match &pattern.target_type {
+                Type::Object(name) => name.clone(),
+                other => return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, format!("Type pattern requires a class or interface type, got {}", other))),
+            };
+            let matched = self.emit_instance_check(&selector_type, &selector_val, &target_class)?;
+            match guard {
+                Some(guard) => {
+                    let guard_label = self.new_label("switch.guard");
+                    self.emit_line(&format!("  br i1 {}, label %{}, label %{}", matched, guard_label, next_label));
+                    self.emit_line(&format!("{}:", guard_label));
+                    self.enter_type_case(Some(pattern), &slots, idx);
+                    let holds = self.emit_guard_condition(guard);
+                    self.scope_manager.exit_scope();
+                    self.emit_line(&format!("  br i1 {}, label %{}, label %{}", holds?, case_labels[idx], next_label));
+                }
+                None => {
+                    self.emit_line(&format!("  br i1 {}, label %{}, label %{}", matched, case_labels[idx], next_label));
+                }
             }
-        } else {
-            // 没有 default，不是所有分支都终止
-            all_cases_terminate = false;
+        }
+        if heads.is_empty() {
+            self.emit_line(&format!("{}:", next_label));
+            self.emit_line(&format!("  br label %{}", default_label));
         }
 
-        // 结束块 - 只有当并非所有分支都 return 时才生成
-        if !all_cases_terminate {
-            self.emit_line(&format!("{}:", end_label));
+        Ok(slots)
+    }
+
+    /// 进入类型模式分支的作用域并绑定模式变量，返回是否进入了作用域
+    fn enter_type_case(&mut self, pattern: Option<&TypePattern>, slots: &[Option<String>], idx: usize) -> bool {
+        match (pattern, slots.get(idx)) {
+            (Some(pattern), Some(Some(slot))) => {
+                let slot = slot.clone();
+                self.scope_manager.enter_scope();
+                self.bind_pattern_variable(&pattern.binding, &slot, &pattern.target_type);
+                true
+            }
+            _ => false,
         }
+    }
 
-        Ok(())
+    /// 生成没有分支匹配时的默认块（switch 值为 null），报错并退出程序
+    fn emit_unmatched_switch_block(&mut self, label: &str) {
+        self.emit_line(&format!("{}:", label));
+        let error_msg = self.get_or_create_string_constant("Error: No switch case matches null\n");
+        self.emit_line(&format!("  call i32 (i8*, ...) @printf(i8* {})", error_msg));
+        self.emit_line("  call void @exit(i32 1)");
+        self.emit_line("  unreachable");
+    }
+
+    /// case 标签的分派值；字符串标签的分派值是字节长度，并附带需要比较的文本
+    fn case_dispatch_value(&self, loc: &SourceLocation, label: &CaseLabel, enum_info: Option<&EnumInfo>) -> cayResult<(i64, Option<String>)> {
+        let constant;
+        let literal = match label {
+            CaseLabel::Int(v) => return Ok((*v, None)),
+            CaseLabel::Char(c) => return Ok(((*c as u8) as i8 as i64, None)),
+            CaseLabel::String(s) => return Ok((s.len() as i64, Some(s.clone()))),
+            CaseLabel::Type(pattern) => return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, format!(
+                "Type pattern '{}' at line {} requires a switch on a class or interface value", pattern.binding, loc.line
+            ))),
+            CaseLabel::Pattern(pattern) => {
+                if let Some(info) = enum_info {
+                    return info.find_variant(&pattern.variant)
+                        .map(|(tag, _)| (tag as i64, None))
+                        .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Enum '{}' has no variant '{}'", info.name, pattern.variant)));
+                }
+                constant = self.type_registry.as_ref().and_then(|r| r.case_constants.get(&pattern.loc).cloned());
+                match &constant {
+                    Some(value) => value,
+                    None => return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, format!(
+                        "Case label '{}' at line {} is not a constant", pattern.variant, loc.line
+                    ))),
+                }
+            }
+            CaseLabel::Const(label) => {
+                constant = self.type_registry.as_ref().and_then(|r| r.case_constants.get(&label.loc).cloned());
+                match &constant {
+                    Some(value) => value,
+                    None => return Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, format!(
+                        "Case label at line {} is not a constant", label.loc.line
+                    ))),
+                }
+            }
+        };
+        match literal {
+            LiteralValue::Int32(v) => Ok((*v as i64, None)),
+            LiteralValue::Int64(v) => Ok((*v, None)),
+            LiteralValue::Char(c) => Ok(((*c as u8) as i8 as i64, None)),
+            LiteralValue::String(s) => Ok((s.len() as i64, Some(s.clone()))),
+            _ => Err(codegen_error(ErrorCodes::CODEGEN_INVALID_OPERATION, format!("Unsupported case label at line {}", loc.line))),
+        }
     }
 }
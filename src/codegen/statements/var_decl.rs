@@ -6,10 +6,14 @@ use crate::codegen::context::IRGenerator;
 use crate::ast::*;
 use crate::types::Type;
 use crate::error::cayResult;
+use crate::diagnostic::ErrorCodes;
 
 impl IRGenerator {
     /// 从表达式推断类型
     fn infer_type_from_expr(&self, expr: &Expr) -> Option<Type> {
+        if let Some(enum_type) = self.enum_value_type(expr) {
+            return Some(enum_type);
+        }
         match expr {
             Expr::Literal(lit) => match lit {
                 LiteralValue::Int32(_) => Some(Type::Int32),
@@ -46,12 +50,18 @@ impl IRGenerator {
                         return self.infer_method_return_type(class_name, &member.member);
                     }
                 }
-                None
+                self.get_expression_type(expr)
             }
             Expr::New(new_expr) => {
-                // new 表达式返回对象类型
+                // new 表达式返回对象类型，结构体构造返回结构体值
+                if self.get_struct_info(&new_expr.class_name).is_some() {
+                    return Some(Type::Struct(new_expr.class_name.clone()));
+                }
                 Some(Type::Object(new_expr.class_name.clone()))
             }
+            Expr::Lambda(lambda) => self.type_registry.as_ref()
+                .and_then(|r| r.lambdas.get(&lambda.loc))
+                .map(|info| Type::Function(Box::new(info.signature.clone()))),
             _ => None, // 无法推断，返回 None
         }
     }
@@ -120,6 +130,9 @@ impl IRGenerator {
             "i8*" => Some(Type::String),
             "void" => Some(Type::Void),
             _ => {
+                if let Some(struct_name) = Self::llvm_struct_name(llvm_type) {
+                    return Some(Type::Struct(struct_name.to_string()));
+                }
                 // 检查是否是对象指针类型
                 if llvm_type.starts_with("%") && llvm_type.ends_with("*") {
                     let class_name = llvm_type.trim_start_matches('%').trim_end_matches('*');
@@ -140,12 +153,13 @@ impl IRGenerator {
                 self.infer_type_from_expr(init).unwrap_or(Type::Int32)
             } else {
                 return Err(crate::error::semantic_error(
+                    ErrorCodes::SEMANTIC_TYPE_INFERENCE,
                     var.loc.line, var.loc.column,
                     "'auto' variable declaration requires an initializer".to_string()
                 ));
             }
         } else {
-            var.var_type.clone()
+            var.var_type.non_null().clone()
         };
 
         let var_type = self.type_to_llvm(&actual_type);
@@ -154,7 +168,12 @@ impl IRGenerator {
         // 使用作用域管理器生成唯一的 LLVM 变量名
         let llvm_name = self.scope_manager.declare_var(&var.name, &var_type);
 
-        self.emit_line(&format!("  %{} = alloca {}, align {}", llvm_name, var_type, align));
+        if self.is_boxed_local(var) {
+            // 被 Lambda 按引用捕获的变量分配在堆上，方法返回后闭包仍可访问
+            self.emit_boxed_alloca(&llvm_name, &var_type);
+        } else {
+            self.emit_line(&format!("  %{} = alloca {}, align {}", llvm_name, var_type, align));
+        }
         // 同时存储到旧系统以保持兼容性
         self.var_types.insert(var.name.clone(), var_type.clone());
         // 存储Cavvy类型信息，用于准确的类型推断
@@ -164,6 +183,11 @@ impl IRGenerator {
             self.var_class_map.insert(var.name.clone(), class_name.clone());
         }
 
+        if var.initializer.is_none() && matches!(actual_type, Type::Struct(_)) {
+            // 未初始化的结构体变量所有字段为零值
+            self.emit_line(&format!("  store {} zeroinitializer, {}* %{}, align {}", var_type, var_type, llvm_name, align));
+        }
+
         if let Some(init) = var.initializer.as_ref() {
             // 特殊处理数组初始化，传递目标类型信息
             if let Expr::ArrayInit(array_init) = init {
@@ -172,6 +196,7 @@ impl IRGenerator {
                     value, var_type, llvm_name));
             } else {
                 let value = self.generate_expression(init)?;
+                let value = self.widen_unsigned_value(init, &value, &var_type);
                 let (value_type, val) = self.parse_typed_value(&value);
 
                 // 如果值类型与变量类型不匹配，需要转换
@@ -246,6 +271,7 @@ impl IRGenerator {
                     else {
                         // 类型不兼容，报错
                         return Err(crate::error::codegen_error(
+                            ErrorCodes::CODEGEN_TYPE_CONVERSION_ERROR,
                             format!("Cannot convert {} to {} in variable initialization '{}' at line {}", 
                                 value_type, var_type, var.name, var.loc.line)
                         ));
@@ -0,0 +1,328 @@
+//! 结构体代码生成
+//!
+//! 结构体降级为 LLVM 命名类型 `%struct.Point = type { double, double }`，字段按声明顺序
+//! 排列，大小和对齐遵循 C 规则，因此与同样声明的 C 结构体布局相同。结构体值在 SSA 中是
+//! 一等聚合值：构造用 `insertvalue`，读字段用 `extractvalue`，局部变量、对象字段和数组
+//! 元素直接存放整个结构体，不在堆上分配。给字段赋值时通过 `getelementptr` 定位到字段。
+//!
+//! 调用 extern 函数时结构体参数和返回值按目标平台的 C 调用约定传递：x86-64 System V 下
+//! 不超过 16 字节的结构体按 8 字节拆分为整数或浮点寄存器，更大的通过内存传递；Windows x64
+//! 下大小为 1、2、4、8 字节的结构体作为整数传递，其余通过指向副本的指针传递。
+
+use crate::codegen::context::IRGenerator;
+use crate::ast::*;
+use crate::types::{Type, StructInfo};
+use crate::error::{cayResult, codegen_error};
+use crate::diagnostic::ErrorCodes;
+
+/// 结构体在 C 调用约定中的传递方式
+pub(super) enum CStructAbi {
+    /// 拆成若干寄存器大小的标量直接传递
+    Direct(Vec<String>),
+    /// 通过指向副本的指针传递，返回值由调用者提供存放位置
+    Indirect,
+}
+
+impl IRGenerator {
+    /// 生成结构体的 LLVM 类型定义
+    pub(super) fn emit_struct_declarations(&mut self) {
+        let mut structs: Vec<StructInfo> = match &self.type_registry {
+            Some(registry) => registry.structs.values().cloned().collect(),
+            None => return,
+        };
+        if structs.is_empty() {
+            return;
+        }
+        structs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        self.emit_raw("; Struct declarations");
+        for struct_info in structs {
+            let field_types: Vec<String> = struct_info.fields.iter()
+                .map(|field| self.type_to_llvm(&field.param_type))
+                .collect();
+            self.emit_raw(&format!("%struct.{} = type {{ {} }}", struct_info.name, field_types.join(", ")));
+        }
+        self.emit_raw("");
+    }
+
+    /// 从类型注册表获取结构体信息
+    pub fn get_struct_info(&self, struct_name: &str) -> Option<StructInfo> {
+        self.type_registry.as_ref().and_then(|registry| registry.get_struct(struct_name).cloned())
+    }
+
+    /// 表达式的值是结构体时返回结构体名
+    pub fn expression_struct_name(&self, expr: &Expr) -> Option<String> {
+        match self.get_expression_type(expr) {
+            Some(Type::Struct(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// 按 C 规则计算 LLVM 类型的大小和对齐（字节）
+    pub fn llvm_type_size_align(&self, llvm_type: &str) -> (usize, usize) {
+        if let Some(struct_name) = Self::llvm_struct_name(llvm_type) {
+            return self.struct_size_align(struct_name);
+        }
+        match llvm_type {
+            "i1" | "i8" => (1, 1),
+            "i16" => (2, 2),
+            "i32" | "float" => (4, 4),
+            _ => (8, 8),
+        }
+    }
+
+    /// Cavvy 类型按值存放时占用的字节数
+    pub fn value_size_in_bytes(&self, ty: &Type) -> usize {
+        match ty {
+            Type::Struct(name) => self.struct_size_align(name).0,
+            other => other.size_in_bytes(),
+        }
+    }
+
+    /// `%struct.Name` 中的结构体名（结构体指针不算）
+    pub fn llvm_struct_name(llvm_type: &str) -> Option<&str> {
+        llvm_type.strip_prefix("%struct.").filter(|name| !name.ends_with('*'))
+    }
+
+    fn struct_size_align(&self, struct_name: &str) -> (usize, usize) {
+        let (_, size, align) = self.struct_field_offsets(struct_name);
+        (size, align)
+    }
+
+    /// 各字段的 (偏移, LLVM 类型)，以及结构体的大小和对齐
+    fn struct_field_offsets(&self, struct_name: &str) -> (Vec<(usize, String)>, usize, usize) {
+        let struct_info = match self.get_struct_info(struct_name) {
+            Some(info) => info,
+            None => return (Vec::new(), 8, 8),
+        };
+        let mut fields = Vec::new();
+        let mut offset = 0;
+        let mut max_align = 1;
+        for field in &struct_info.fields {
+            let llvm_type = self.type_to_llvm(&field.param_type);
+            let (size, align) = self.llvm_type_size_align(&llvm_type);
+            offset = (offset + align - 1) & !(align - 1);
+            fields.push((offset, llvm_type));
+            offset += size;
+            max_align = max_align.max(align);
+        }
+        let size = (offset + max_align - 1) & !(max_align - 1);
+        (fields, size, max_align)
+    }
+
+    /// 生成结构体构造: new Point(1.0, 2.0) 或全零的 new Point()
+    pub fn generate_struct_construction(&mut self, new_expr: &NewExpr) -> cayResult<String> {
+        let struct_info = self.get_struct_info(&new_expr.class_name)
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Unknown struct '{}'", new_expr.class_name)))?;
+        let struct_type = format!("%struct.{}", struct_info.name);
+        if new_expr.args.is_empty() {
+            return Ok(format!("{} zeroinitializer", struct_type));
+        }
+
+        let mut current = "undef".to_string();
+        for (i, (arg, field)) in new_expr.args.iter().zip(struct_info.fields.iter()).enumerate() {
+            let value = self.generate_expression(arg)?;
+            let (value_type, val) = self.parse_typed_value(&value);
+            let field_type = self.type_to_llvm(&field.param_type);
+            let val = self.coerce_to_llvm_type(&value_type, &val, &field_type);
+            let temp = self.new_temp();
+            self.emit_line(&format!("  {} = insertvalue {} {}, {} {}, {}",
+                temp, struct_type, current, field_type, val, i));
+            current = temp;
+        }
+        Ok(format!("{} {}", struct_type, current))
+    }
+
+    /// 生成结构体字段读取: p.x
+    pub fn generate_struct_field_access(&mut self, struct_name: &str, member: &MemberAccessExpr) -> cayResult<String> {
+        let (index, field_type) = self.struct_field(struct_name, &member.member)?;
+        let value = self.generate_expression(&member.object)?;
+        let (value_type, val) = self.parse_typed_value(&value);
+        let temp = self.new_temp();
+        self.emit_line(&format!("  {} = extractvalue {} {}, {}", temp, value_type, val, index));
+        Ok(format!("{} {}", field_type, temp))
+    }
+
+    /// 结构体字段的指针（用于左值）: p.x、pts[i].x、this.box.min.x
+    pub fn struct_field_pointer(&mut self, struct_name: &str, member: &MemberAccessExpr) -> cayResult<(String, String)> {
+        let (index, field_type) = self.struct_field(struct_name, &member.member)?;
+        let (_, struct_ptr) = self.get_lvalue_info(&member.object)?;
+        let struct_type = format!("%struct.{}", struct_name);
+        let field_ptr = self.new_temp();
+        self.emit_line(&format!("  {} = getelementptr inbounds {}, {}* {}, i32 0, i32 {}",
+            field_ptr, struct_type, struct_type, struct_ptr, index));
+        Ok((field_type, field_ptr))
+    }
+
+    /// 字段下标及其 LLVM 类型
+    fn struct_field(&self, struct_name: &str, field_name: &str) -> cayResult<(usize, String)> {
+        let struct_info = self.get_struct_info(struct_name)
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Unknown struct '{}'", struct_name)))?;
+        let (index, field) = struct_info.find_field(field_name)
+            .ok_or_else(|| codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("Struct '{}' has no field '{}'", struct_name, field_name)))?;
+        Ok((index, self.type_to_llvm(&field.param_type)))
+    }
+
+    /// 结构体在目标平台 C 调用约定中的传递方式
+    pub(super) fn c_struct_abi(&self, struct_name: &str) -> CStructAbi {
+        let (fields, size, _) = self.struct_field_offsets(struct_name);
+        if self.is_windows_target() {
+            return match size {
+                1 | 2 | 4 | 8 => CStructAbi::Direct(vec![format!("i{}", size * 8)]),
+                _ => CStructAbi::Indirect,
+            };
+        }
+        if size > 16 {
+            return CStructAbi::Indirect;
+        }
+
+        // System V: 每 8 字节只含 float/double 时用浮点寄存器，否则用整数寄存器
+        let scalars = self.flatten_struct_fields(&fields, 0);
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        while start < size {
+            let end = (start + 8).min(size);
+            let in_piece: Vec<&String> = scalars.iter()
+                .filter(|(offset, _)| *offset >= start && *offset < end)
+                .map(|(_, ty)| ty)
+                .collect();
+            let is_sse = !in_piece.is_empty() && in_piece.iter().all(|ty| *ty == "float" || *ty == "double");
+            let piece = if !is_sse {
+                format!("i{}", (end - start) * 8)
+            } else if in_piece.iter().any(|ty| *ty == "double") {
+                "double".to_string()
+            } else if end - start <= 4 || in_piece.len() == 1 {
+                "float".to_string()
+            } else {
+                "<2 x float>".to_string()
+            };
+            pieces.push(piece);
+            start = end;
+        }
+        CStructAbi::Direct(pieces)
+    }
+
+    /// 把嵌套结构体展开为 (偏移, 标量类型) 列表
+    fn flatten_struct_fields(&self, fields: &[(usize, String)], base: usize) -> Vec<(usize, String)> {
+        let mut scalars = Vec::new();
+        for (offset, llvm_type) in fields {
+            match Self::llvm_struct_name(llvm_type) {
+                Some(inner) => {
+                    let (inner_fields, _, _) = self.struct_field_offsets(inner);
+                    scalars.extend(self.flatten_struct_fields(&inner_fields, base + offset));
+                }
+                None => scalars.push((base + offset, llvm_type.clone())),
+            }
+        }
+        scalars
+    }
+
+    /// 拆分后的标量组成的 LLVM 类型
+    fn c_abi_aggregate(pieces: &[String]) -> String {
+        if pieces.len() == 1 {
+            pieces[0].clone()
+        } else {
+            format!("{{ {} }}", pieces.join(", "))
+        }
+    }
+
+    /// extern 函数声明中参数的 LLVM 类型，结构体参数可能展开为多个
+    pub(super) fn extern_param_llvm_types(&self, ty: &Type) -> Vec<String> {
+        let struct_name = match self.resolve_type(ty) {
+            Type::Struct(name) => name,
+            _ => return vec![self.type_to_llvm(ty)],
+        };
+        let struct_type = format!("%struct.{}", struct_name);
+        match self.c_struct_abi(&struct_name) {
+            CStructAbi::Direct(pieces) => pieces,
+            CStructAbi::Indirect if self.is_windows_target() => vec![format!("{}*", struct_type)],
+            CStructAbi::Indirect => {
+                let align = self.llvm_type_size_align(&struct_type).1.max(8);
+                vec![format!("{}* byval({}) align {}", struct_type, struct_type, align)]
+            }
+        }
+    }
+
+    /// extern 函数声明的返回类型，以及通过内存返回结构体时的隐藏首参数
+    pub(super) fn extern_return_llvm_type(&self, ty: &Type) -> (String, Option<String>) {
+        let struct_name = match self.resolve_type(ty) {
+            Type::Struct(name) => name,
+            _ => return (self.type_to_llvm(ty), None),
+        };
+        match self.c_struct_abi(&struct_name) {
+            CStructAbi::Direct(pieces) => (Self::c_abi_aggregate(&pieces), None),
+            CStructAbi::Indirect => {
+                let struct_type = format!("%struct.{}", struct_name);
+                ("void".to_string(), Some(format!("{}* sret({})", struct_type, struct_type)))
+            }
+        }
+    }
+
+    /// 按 C 调用约定降级传给 extern 函数的结构体实参
+    pub(super) fn lower_c_struct_arg(&mut self, struct_name: &str, val: &str) -> Vec<String> {
+        let struct_type = format!("%struct.{}", struct_name);
+        match self.c_struct_abi(struct_name) {
+            CStructAbi::Direct(pieces) => {
+                let coerced = Self::c_abi_aggregate(&pieces);
+                let slot = self.c_abi_slot(&struct_type, &coerced);
+                self.emit_line(&format!("  store {} {}, {}* {}", struct_type, val, struct_type, slot.1));
+                let loaded = self.new_temp();
+                self.emit_line(&format!("  {} = load {}, {}* {}, align 8", loaded, coerced, coerced, slot.0));
+                if pieces.len() == 1 {
+                    return vec![format!("{} {}", coerced, loaded)];
+                }
+                let mut args = Vec::new();
+                for (i, piece) in pieces.iter().enumerate() {
+                    let temp = self.new_temp();
+                    self.emit_line(&format!("  {} = extractvalue {} {}, {}", temp, coerced, loaded, i));
+                    args.push(format!("{} {}", piece, temp));
+                }
+                args
+            }
+            CStructAbi::Indirect => {
+                let copy = self.new_temp();
+                self.emit_line(&format!("  {} = alloca {}, align 8", copy, struct_type));
+                self.emit_line(&format!("  store {} {}, {}* {}", struct_type, val, struct_type, copy));
+                let param_type = self.extern_param_llvm_types(&Type::Struct(struct_name.to_string())).remove(0);
+                vec![format!("{} {}", param_type, copy)]
+            }
+        }
+    }
+
+    /// 调用返回结构体的 extern 函数，把按 C 调用约定返回的值还原为结构体
+    ///
+    /// `callee` 是 `@name`，可变参数函数为 `(参数类型, ...) @name`
+    pub(super) fn generate_c_struct_returning_call(&mut self, struct_name: &str, callee: &str, mut args: Vec<String>) -> String {
+        let struct_type = format!("%struct.{}", struct_name);
+        match self.c_struct_abi(struct_name) {
+            CStructAbi::Direct(pieces) => {
+                let coerced = Self::c_abi_aggregate(&pieces);
+                let returned = self.new_temp();
+                self.emit_line(&format!("  {} = call {} {}({})", returned, coerced, callee, args.join(", ")));
+                let slot = self.c_abi_slot(&struct_type, &coerced);
+                self.emit_line(&format!("  store {} {}, {}* {}, align 8", coerced, returned, coerced, slot.0));
+                let value = self.new_temp();
+                self.emit_line(&format!("  {} = load {}, {}* {}", value, struct_type, struct_type, slot.1));
+                format!("{} {}", struct_type, value)
+            }
+            CStructAbi::Indirect => {
+                let result = self.new_temp();
+                self.emit_line(&format!("  {} = alloca {}, align 8", result, struct_type));
+                args.insert(0, format!("{}* sret({}) {}", struct_type, struct_type, result));
+                self.emit_line(&format!("  call void {}({})", callee, args.join(", ")));
+                let value = self.new_temp();
+                self.emit_line(&format!("  {} = load {}, {}* {}", value, struct_type, struct_type, result));
+                format!("{} {}", struct_type, value)
+            }
+        }
+    }
+
+    /// 同时容纳结构体和拆分后标量的栈空间，返回 (标量视图指针, 结构体视图指针)
+    fn c_abi_slot(&mut self, struct_type: &str, coerced: &str) -> (String, String) {
+        let slot = self.new_temp();
+        self.emit_line(&format!("  {} = alloca {}, align 8", slot, coerced));
+        let view = self.new_temp();
+        self.emit_line(&format!("  {} = bitcast {}* {} to {}*", view, coerced, slot, struct_type));
+        (slot, view)
+    }
+}
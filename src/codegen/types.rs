@@ -3,9 +3,10 @@ use crate::codegen::context::IRGenerator;
 use crate::types::Type;
 
 impl IRGenerator {
-    /// 解析类型（包括类型别名）
+    /// 解析类型（包括类型别名），可空类型与内部类型的表示相同
     pub fn resolve_type(&self, ty: &Type) -> Type {
         match ty {
+            Type::Nullable(inner) => self.resolve_type(inner),
             Type::Object(name) => {
                 // 检查是否是类型别名
                 if let Some(aliased_type) = self.type_aliases.get(name) {
@@ -27,6 +28,10 @@ impl IRGenerator {
             Type::Void => "void".to_string(),
             Type::Int32 => "i32".to_string(),
             Type::Int64 => "i64".to_string(),
+            Type::Int8 | Type::UInt8 => "i8".to_string(),
+            Type::Int16 | Type::UInt16 => "i16".to_string(),
+            Type::UInt32 => "i32".to_string(),
+            Type::UInt64 => "i64".to_string(),
             Type::Float32 => "float".to_string(),
             Type::Float64 => "double".to_string(),
             Type::Bool => "i1".to_string(),
@@ -69,6 +74,22 @@ impl IRGenerator {
                 }
             },
             Type::Struct(name) => format!("%struct.{}", name),                // 命名结构体
+            // 泛型在单态化后不会出现，未替换时按对象指针处理
+            Type::TypeParam(_) | Type::Generic(_, _) => "i8*".to_string(),
+            // 枚举值是指向带标签结构体的指针
+            Type::Enum(_) => "i8*".to_string(),
+            Type::Nullable(inner) => self.type_to_llvm(inner),
+        }
+    }
+
+    /// 函数类型对应的 LLVM 函数签名，如 `i32 (i32, i32)`
+    pub fn function_llvm_signature(&self, ty: &Type) -> String {
+        match self.resolve_type(ty) {
+            Type::Function(func) => {
+                let params: Vec<String> = func.params.iter().map(|p| self.type_to_llvm(p)).collect();
+                format!("{} ({})", self.type_to_llvm(&func.return_type), params.join(", "))
+            }
+            _ => "void ()".to_string(),
         }
     }
 
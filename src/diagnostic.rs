@@ -5,6 +5,7 @@
 
 use std::fmt;
 use std::collections::HashMap;
+use crate::error::cayError;
 
 /// 错误严重程度
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -19,6 +20,18 @@ pub enum Severity {
     Fatal,
 }
 
+impl Severity {
+    /// 机器可读输出中使用的名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Fatal => "fatal",
+        }
+    }
+}
+
 impl fmt::Display for Severity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -47,6 +60,20 @@ pub enum CompilationPhase {
     Linker,
 }
 
+impl CompilationPhase {
+    /// 机器可读输出中使用的名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompilationPhase::Preprocessor => "preprocessor",
+            CompilationPhase::Lexer => "lexer",
+            CompilationPhase::Parser => "parser",
+            CompilationPhase::Semantic => "semantic",
+            CompilationPhase::CodeGen => "codegen",
+            CompilationPhase::Linker => "linker",
+        }
+    }
+}
+
 impl fmt::Display for CompilationPhase {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -150,6 +177,8 @@ pub struct Diagnostic {
     pub suggestions: Vec<FixSuggestion>,
     /// 相关上下文信息
     pub related_info: Vec<RelatedInfo>,
+    /// 所在源文件（导入的模块或 include 的文件），None 表示正在编译的主文件
+    pub file: Option<String>,
 }
 
 /// 相关信息（用于提供额外的上下文）
@@ -178,9 +207,42 @@ impl Diagnostic {
             span: None,
             suggestions: Vec::new(),
             related_info: Vec::new(),
+            file: None,
         }
     }
 
+    /// 由编译错误创建错误级别的诊断，使用错误创建时给出的错误代码
+    pub fn from_error(error: &cayError) -> Self {
+        let (phase, code) = (error_phase(error), error.code());
+        let location = crate::error::get_error_location(error)
+            .map(|(line, column)| SourceLocation::new(line, column))
+            .unwrap_or_default();
+        let message = match error {
+            cayError::Lexer { message, .. }
+            | cayError::Parser { message, .. }
+            | cayError::Semantic { message, .. }
+            | cayError::TypeMismatch { message, .. }
+            | cayError::CodeGen { message, .. }
+            | cayError::Preprocessor { message, .. }
+            | cayError::Io(message)
+            | cayError::Llvm(message) => message.clone(),
+            cayError::UndefinedIdentifier { name, .. } => format!("未定义标识符: '{}'", name),
+            cayError::DuplicateDefinition { name, .. } => format!("重复定义: '{}'", name),
+        };
+        let mut diagnostic = Self::error(code, phase, message, location);
+        diagnostic.file = crate::error::get_error_file(error);
+        match crate::error::get_error_help(error) {
+            Some(help) if !help.is_empty() => diagnostic.with_details(help),
+            _ => diagnostic,
+        }
+    }
+
+    /// 设置所在源文件
+    pub fn with_file(mut self, file: Option<String>) -> Self {
+        self.file = file;
+        self
+    }
+
     /// 创建错误级别的诊断
     pub fn error(
         code: impl Into<String>,
@@ -315,10 +377,27 @@ impl DiagnosticCollector {
     }
 }
 
+/// 编译错误所属的阶段
+fn error_phase(error: &cayError) -> CompilationPhase {
+    match error {
+        cayError::Preprocessor { .. } => CompilationPhase::Preprocessor,
+        cayError::Lexer { .. } => CompilationPhase::Lexer,
+        cayError::Parser { .. } => CompilationPhase::Parser,
+        cayError::Semantic { .. }
+        | cayError::TypeMismatch { .. }
+        | cayError::UndefinedIdentifier { .. }
+        | cayError::DuplicateDefinition { .. } => CompilationPhase::Semantic,
+        cayError::CodeGen { .. } | cayError::Io(_) | cayError::Llvm(_) => CompilationPhase::CodeGen,
+    }
+}
+
 /// 错误代码定义
 pub struct ErrorCodes;
 
 impl ErrorCodes {
+    // 通用错误 (E0xxx)
+    pub const IO_ERROR: &'static str = "E0001";
+
     // 预处理器错误 (E1xxx)
     pub const PREPROCESSOR_DEFINE_ERROR: &'static str = "E1001";
     pub const PREPROCESSOR_IFDEF_ERROR: &'static str = "E1002";
@@ -326,6 +405,11 @@ impl ErrorCodes {
     pub const PREPROCESSOR_UNCLOSED_DIRECTIVE: &'static str = "E1004";
     pub const PREPROCESSOR_CIRCULAR_INCLUDE: &'static str = "E1005";
     pub const PREPROCESSOR_INVALID_MACRO: &'static str = "E1006";
+    pub const PREPROCESSOR_UNKNOWN_DIRECTIVE: &'static str = "E1007";
+    pub const PREPROCESSOR_USER_ERROR: &'static str = "E1008";
+    pub const IMPORT_NOT_FOUND: &'static str = "E1009";
+    pub const IMPORT_PACKAGE_MISMATCH: &'static str = "E1010";
+    pub const IMPORT_DUPLICATE_TYPE: &'static str = "E1011";
 
     // 词法错误 (E2xxx)
     pub const LEXER_INVALID_CHARACTER: &'static str = "E2001";
@@ -346,6 +430,12 @@ impl ErrorCodes {
     pub const PARSER_INVALID_EXPRESSION: &'static str = "E3008";
     pub const PARSER_MISSING_MAIN: &'static str = "E3009";
     pub const PARSER_MULTIPLE_MAIN: &'static str = "E3010";
+    pub const PARSER_INVALID_DECLARATION: &'static str = "E3011";
+    pub const PARSER_INVALID_MODIFIER: &'static str = "E3012";
+    pub const PARSER_INVALID_ANNOTATION: &'static str = "E3013";
+    pub const PARSER_LITERAL_OUT_OF_RANGE: &'static str = "E3014";
+    pub const PARSER_INVALID_IMPORT: &'static str = "E3015";
+    pub const PARSER_NESTED_CLASS: &'static str = "E3016";
 
     // 语义错误 (E4xxx)
     pub const SEMANTIC_UNDEFINED_IDENTIFIER: &'static str = "E4001";
@@ -377,6 +467,26 @@ impl ErrorCodes {
     pub const SEMANTIC_DIVISION_BY_ZERO: &'static str = "E4027";
     pub const SEMANTIC_UNREACHABLE_CODE: &'static str = "E4028";
     pub const SEMANTIC_UNUSED_VARIABLE: &'static str = "E4029";
+    pub const SEMANTIC_DEPRECATED_USAGE: &'static str = "E4030";
+    pub const SEMANTIC_SEALED_HIERARCHY: &'static str = "E4031";
+    pub const SEMANTIC_MAIN_CLASS: &'static str = "E4032";
+    pub const SEMANTIC_FEATURE_DISABLED: &'static str = "E4033";
+    pub const SEMANTIC_CAPTURED_ASSIGNMENT: &'static str = "E4034";
+    pub const SEMANTIC_DEFER_ERROR: &'static str = "E4035";
+    pub const SEMANTIC_TRY_OPERATOR: &'static str = "E4036";
+    pub const SEMANTIC_INVALID_OPERAND: &'static str = "E4037";
+    pub const SEMANTIC_NULL_SAFETY: &'static str = "E4038";
+    pub const SEMANTIC_GENERIC_ERROR: &'static str = "E4039";
+    pub const SEMANTIC_SWITCH_ERROR: &'static str = "E4040";
+    pub const SEMANTIC_NON_EXHAUSTIVE_SWITCH: &'static str = "E4041";
+    pub const SEMANTIC_NOT_ITERABLE: &'static str = "E4042";
+    pub const SEMANTIC_STRUCT_ERROR: &'static str = "E4043";
+    pub const SEMANTIC_OPERATOR_OVERLOAD: &'static str = "E4044";
+    pub const SEMANTIC_CONST_EVAL: &'static str = "E4045";
+    pub const SEMANTIC_ANNOTATION_ERROR: &'static str = "E4046";
+    pub const SEMANTIC_PROPERTY_ERROR: &'static str = "E4047";
+    pub const SEMANTIC_TYPE_INFERENCE: &'static str = "E4048";
+    pub const SEMANTIC_INTERFACE_METHOD: &'static str = "E4049";
 
     // 代码生成错误 (E5xxx)
     pub const CODEGEN_UNSUPPORTED_FEATURE: &'static str = "E5001";
@@ -384,15 +494,129 @@ impl ErrorCodes {
     pub const CODEGEN_SYMBOL_NOT_FOUND: &'static str = "E5003";
     pub const CODEGEN_INVALID_OPERATION: &'static str = "E5004";
     pub const CODEGEN_LLVM_ERROR: &'static str = "E5005";
+    pub const CODEGEN_INVALID_OPERAND: &'static str = "E5006";
+    pub const CODEGEN_BUILTIN_ARGUMENTS: &'static str = "E5007";
+    pub const CODEGEN_INTERNAL_ERROR: &'static str = "E5008";
 
     // 链接错误 (E6xxx)
     pub const LINKER_SYMBOL_NOT_FOUND: &'static str = "E6001";
     pub const LINKER_MULTIPLE_DEFINITION: &'static str = "E6002";
     pub const LINKER_LIBRARY_NOT_FOUND: &'static str = "E6003";
 
+    /// 全部错误代码，按代码排序
+    pub const ALL: &'static [&'static str] = &[
+        Self::IO_ERROR,
+
+        Self::PREPROCESSOR_DEFINE_ERROR,
+        Self::PREPROCESSOR_IFDEF_ERROR,
+        Self::PREPROCESSOR_INCLUDE_ERROR,
+        Self::PREPROCESSOR_UNCLOSED_DIRECTIVE,
+        Self::PREPROCESSOR_CIRCULAR_INCLUDE,
+        Self::PREPROCESSOR_INVALID_MACRO,
+        Self::PREPROCESSOR_UNKNOWN_DIRECTIVE,
+        Self::PREPROCESSOR_USER_ERROR,
+        Self::IMPORT_NOT_FOUND,
+        Self::IMPORT_PACKAGE_MISMATCH,
+        Self::IMPORT_DUPLICATE_TYPE,
+
+        Self::LEXER_INVALID_CHARACTER,
+        Self::LEXER_UNTERMINATED_STRING,
+        Self::LEXER_INVALID_ESCAPE_SEQUENCE,
+        Self::LEXER_INVALID_NUMBER_LITERAL,
+        Self::LEXER_UNTERMINATED_COMMENT,
+        Self::LEXER_INVALID_IDENTIFIER,
+
+        Self::PARSER_UNEXPECTED_TOKEN,
+        Self::PARSER_EXPECTED_SEMICOLON,
+        Self::PARSER_EXPECTED_BRACE,
+        Self::PARSER_EXPECTED_PAREN,
+        Self::PARSER_EXPECTED_IDENTIFIER,
+        Self::PARSER_EXPECTED_TYPE,
+        Self::PARSER_INVALID_STATEMENT,
+        Self::PARSER_INVALID_EXPRESSION,
+        Self::PARSER_MISSING_MAIN,
+        Self::PARSER_MULTIPLE_MAIN,
+        Self::PARSER_INVALID_DECLARATION,
+        Self::PARSER_INVALID_MODIFIER,
+        Self::PARSER_INVALID_ANNOTATION,
+        Self::PARSER_LITERAL_OUT_OF_RANGE,
+        Self::PARSER_INVALID_IMPORT,
+        Self::PARSER_NESTED_CLASS,
+
+        Self::SEMANTIC_UNDEFINED_IDENTIFIER,
+        Self::SEMANTIC_DUPLICATE_DEFINITION,
+        Self::SEMANTIC_TYPE_MISMATCH,
+        Self::SEMANTIC_INVALID_CAST,
+        Self::SEMANTIC_INCOMPATIBLE_TYPES,
+        Self::SEMANTIC_UNINITIALIZED_VARIABLE,
+        Self::SEMANTIC_INVALID_OPERATION,
+        Self::SEMANTIC_ACCESS_VIOLATION,
+        Self::SEMANTIC_STATIC_CONTEXT,
+        Self::SEMANTIC_FINAL_REASSIGNMENT,
+        Self::SEMANTIC_MISSING_RETURN,
+        Self::SEMANTIC_RETURN_TYPE_MISMATCH,
+        Self::SEMANTIC_BREAK_OUTSIDE_LOOP,
+        Self::SEMANTIC_CONTINUE_OUTSIDE_LOOP,
+        Self::SEMANTIC_INVALID_ARRAY_SIZE,
+        Self::SEMANTIC_ARRAY_INDEX_TYPE,
+        Self::SEMANTIC_METHOD_NOT_FOUND,
+        Self::SEMANTIC_WRONG_ARGUMENT_COUNT,
+        Self::SEMANTIC_ARGUMENT_TYPE_MISMATCH,
+        Self::SEMANTIC_ABSTRACT_CLASS_INSTANCE,
+        Self::SEMANTIC_OVERRIDE_ERROR,
+        Self::SEMANTIC_INHERITANCE_ERROR,
+        Self::SEMANTIC_CIRCULAR_INHERITANCE,
+        Self::SEMANTIC_FINAL_CLASS_INHERITANCE,
+        Self::SEMANTIC_INTERFACE_IMPL_ERROR,
+        Self::SEMANTIC_VOID_ASSIGNMENT,
+        Self::SEMANTIC_DIVISION_BY_ZERO,
+        Self::SEMANTIC_UNREACHABLE_CODE,
+        Self::SEMANTIC_UNUSED_VARIABLE,
+        Self::SEMANTIC_DEPRECATED_USAGE,
+        Self::SEMANTIC_SEALED_HIERARCHY,
+        Self::SEMANTIC_MAIN_CLASS,
+        Self::SEMANTIC_FEATURE_DISABLED,
+        Self::SEMANTIC_CAPTURED_ASSIGNMENT,
+        Self::SEMANTIC_DEFER_ERROR,
+        Self::SEMANTIC_TRY_OPERATOR,
+        Self::SEMANTIC_INVALID_OPERAND,
+        Self::SEMANTIC_NULL_SAFETY,
+        Self::SEMANTIC_GENERIC_ERROR,
+        Self::SEMANTIC_SWITCH_ERROR,
+        Self::SEMANTIC_NON_EXHAUSTIVE_SWITCH,
+        Self::SEMANTIC_NOT_ITERABLE,
+        Self::SEMANTIC_STRUCT_ERROR,
+        Self::SEMANTIC_OPERATOR_OVERLOAD,
+        Self::SEMANTIC_CONST_EVAL,
+        Self::SEMANTIC_ANNOTATION_ERROR,
+        Self::SEMANTIC_PROPERTY_ERROR,
+        Self::SEMANTIC_TYPE_INFERENCE,
+        Self::SEMANTIC_INTERFACE_METHOD,
+
+        Self::CODEGEN_UNSUPPORTED_FEATURE,
+        Self::CODEGEN_TYPE_CONVERSION_ERROR,
+        Self::CODEGEN_SYMBOL_NOT_FOUND,
+        Self::CODEGEN_INVALID_OPERATION,
+        Self::CODEGEN_LLVM_ERROR,
+        Self::CODEGEN_INVALID_OPERAND,
+        Self::CODEGEN_BUILTIN_ARGUMENTS,
+        Self::CODEGEN_INTERNAL_ERROR,
+
+        Self::LINKER_SYMBOL_NOT_FOUND,
+        Self::LINKER_MULTIPLE_DEFINITION,
+        Self::LINKER_LIBRARY_NOT_FOUND,
+    ];
+
+    /// 查找错误代码（不区分大小写），返回 `ALL` 中对应的代码
+    pub fn find(code: &str) -> Option<&'static str> {
+        Self::ALL.iter().copied().find(|known| known.eq_ignore_ascii_case(code.trim()))
+    }
+
     /// 获取错误代码的详细说明
     pub fn get_description(code: &str) -> &'static str {
         match code {
+            Self::IO_ERROR => "文件读写错误",
+
             // 预处理器
             Self::PREPROCESSOR_DEFINE_ERROR => "宏定义错误",
             Self::PREPROCESSOR_IFDEF_ERROR => "条件编译指令错误",
@@ -400,6 +624,11 @@ impl ErrorCodes {
             Self::PREPROCESSOR_UNCLOSED_DIRECTIVE => "未闭合的预处理器指令",
             Self::PREPROCESSOR_CIRCULAR_INCLUDE => "循环包含错误",
             Self::PREPROCESSOR_INVALID_MACRO => "无效的宏定义",
+            Self::PREPROCESSOR_UNKNOWN_DIRECTIVE => "未知的预处理器指令",
+            Self::PREPROCESSOR_USER_ERROR => "#error 指令",
+            Self::IMPORT_NOT_FOUND => "无法解析导入",
+            Self::IMPORT_PACKAGE_MISMATCH => "模块所属的包与导入路径不符",
+            Self::IMPORT_DUPLICATE_TYPE => "类型名冲突",
 
             // 词法
             Self::LEXER_INVALID_CHARACTER => "非法字符",
@@ -420,6 +649,12 @@ impl ErrorCodes {
             Self::PARSER_INVALID_EXPRESSION => "无效的表达式",
             Self::PARSER_MISSING_MAIN => "缺少主函数",
             Self::PARSER_MULTIPLE_MAIN => "多个主函数",
+            Self::PARSER_INVALID_DECLARATION => "无效的声明",
+            Self::PARSER_INVALID_MODIFIER => "无效的修饰符",
+            Self::PARSER_INVALID_ANNOTATION => "无效的注解",
+            Self::PARSER_LITERAL_OUT_OF_RANGE => "整数字面量超出范围",
+            Self::PARSER_INVALID_IMPORT => "无效的包或导入声明",
+            Self::PARSER_NESTED_CLASS => "嵌套类或匿名类使用错误",
 
             // 语义
             Self::SEMANTIC_UNDEFINED_IDENTIFIER => "未定义的标识符",
@@ -451,6 +686,26 @@ impl ErrorCodes {
             Self::SEMANTIC_DIVISION_BY_ZERO => "除零错误",
             Self::SEMANTIC_UNREACHABLE_CODE => "不可达代码",
             Self::SEMANTIC_UNUSED_VARIABLE => "未使用的变量",
+            Self::SEMANTIC_DEPRECATED_USAGE => "使用已弃用的声明",
+            Self::SEMANTIC_SEALED_HIERARCHY => "密封类型层次错误",
+            Self::SEMANTIC_MAIN_CLASS => "主类不明确",
+            Self::SEMANTIC_FEATURE_DISABLED => "语言特性未启用",
+            Self::SEMANTIC_CAPTURED_ASSIGNMENT => "给捕获的变量赋值",
+            Self::SEMANTIC_DEFER_ERROR => "defer 或 try 资源使用错误",
+            Self::SEMANTIC_TRY_OPERATOR => "'?' 运算符使用错误",
+            Self::SEMANTIC_INVALID_OPERAND => "运算符的操作数类型错误",
+            Self::SEMANTIC_NULL_SAFETY => "空安全检查失败",
+            Self::SEMANTIC_GENERIC_ERROR => "泛型使用错误",
+            Self::SEMANTIC_SWITCH_ERROR => "switch 语句错误",
+            Self::SEMANTIC_NON_EXHAUSTIVE_SWITCH => "switch 分支不完整",
+            Self::SEMANTIC_NOT_ITERABLE => "for-each 的对象不可迭代",
+            Self::SEMANTIC_STRUCT_ERROR => "结构体使用错误",
+            Self::SEMANTIC_OPERATOR_OVERLOAD => "运算符重载错误",
+            Self::SEMANTIC_CONST_EVAL => "编译期求值错误",
+            Self::SEMANTIC_ANNOTATION_ERROR => "注解使用错误",
+            Self::SEMANTIC_PROPERTY_ERROR => "属性使用错误",
+            Self::SEMANTIC_TYPE_INFERENCE => "无法推断类型",
+            Self::SEMANTIC_INTERFACE_METHOD => "接口方法使用错误",
 
             // 代码生成
             Self::CODEGEN_UNSUPPORTED_FEATURE => "不支持的功能",
@@ -458,6 +713,9 @@ impl ErrorCodes {
             Self::CODEGEN_SYMBOL_NOT_FOUND => "符号未找到",
             Self::CODEGEN_INVALID_OPERATION => "无效的操作",
             Self::CODEGEN_LLVM_ERROR => "LLVM错误",
+            Self::CODEGEN_INVALID_OPERAND => "不支持的操作数类型",
+            Self::CODEGEN_BUILTIN_ARGUMENTS => "内置函数参数错误",
+            Self::CODEGEN_INTERNAL_ERROR => "编译器内部错误",
 
             // 链接
             Self::LINKER_SYMBOL_NOT_FOUND => "链接符号未找到",
@@ -2,11 +2,13 @@ use thiserror::Error;
 use std::fmt;
 use miette::{Diagnostic, NamedSource, SourceSpan};
 use std::sync::Arc;
+use crate::diagnostic::ErrorCodes;
 
 #[derive(Error, Debug, Clone)]
 pub enum cayError {
     #[error("词法错误 [{}:{line}:{column}]: {message}", file.as_deref().unwrap_or("<unknown>"))]
     Lexer { 
+        code: &'static str,
         file: Option<String>,
         line: usize, 
         column: usize, 
@@ -16,6 +18,7 @@ pub enum cayError {
     
     #[error("语法错误 [{}:{line}:{column}]: {message}", file.as_deref().unwrap_or("<unknown>"))]
     Parser { 
+        code: &'static str,
         file: Option<String>,
         line: usize, 
         column: usize, 
@@ -25,6 +28,7 @@ pub enum cayError {
     
     #[error("语义错误 [{}:{line}:{column}]: {message}", file.as_deref().unwrap_or("<unknown>"))]
     Semantic { 
+        code: &'static str,
         file: Option<String>,
         line: usize, 
         column: usize, 
@@ -34,6 +38,7 @@ pub enum cayError {
     
     #[error("代码生成错误: {message}")]
     CodeGen { 
+        code: &'static str,
         message: String,
         suggestion: String,
     },
@@ -75,6 +80,7 @@ pub enum cayError {
 
     #[error("预处理器错误 [{}:{line}:{column}]: {message}", file.as_deref().unwrap_or("<unknown>"))]
     Preprocessor { 
+        code: &'static str,
         file: Option<String>,
         line: usize, 
         column: usize, 
@@ -85,6 +91,24 @@ pub enum cayError {
 
 pub type cayResult<T> = Result<T, cayError>;
 
+impl cayError {
+    /// 错误代码（见 `diagnostic::ErrorCodes`），可用 `cayc --explain` 查看详细解释
+    pub fn code(&self) -> &'static str {
+        match self {
+            cayError::Lexer { code, .. }
+            | cayError::Parser { code, .. }
+            | cayError::Semantic { code, .. }
+            | cayError::CodeGen { code, .. }
+            | cayError::Preprocessor { code, .. } => code,
+            cayError::TypeMismatch { .. } => ErrorCodes::SEMANTIC_TYPE_MISMATCH,
+            cayError::UndefinedIdentifier { .. } => ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
+            cayError::DuplicateDefinition { .. } => ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
+            cayError::Io(_) => ErrorCodes::IO_ERROR,
+            cayError::Llvm(_) => ErrorCodes::CODEGEN_LLVM_ERROR,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SourceLocation {
     pub file: Option<String>,  // 源文件路径（用于多文件include场景）
@@ -156,14 +180,15 @@ impl FullSourceLocation {
 }
 
 // 词法错误
-pub fn lexer_error(line: usize, column: usize, message: impl Into<String>) -> cayError {
-    lexer_error_with_file(None, line, column, message)
+pub fn lexer_error(code: &'static str, line: usize, column: usize, message: impl Into<String>) -> cayError {
+    lexer_error_with_file(code, None, line, column, message)
 }
 
-pub fn lexer_error_with_file(file: Option<String>, line: usize, column: usize, message: impl Into<String>) -> cayError {
+pub fn lexer_error_with_file(code: &'static str, file: Option<String>, line: usize, column: usize, message: impl Into<String>) -> cayError {
     let msg = message.into();
     let suggestion = get_lexer_suggestion(&msg);
     cayError::Lexer {
+        code,
         file,
         line,
         column,
@@ -173,14 +198,15 @@ pub fn lexer_error_with_file(file: Option<String>, line: usize, column: usize, m
 }
 
 // 语法错误
-pub fn parser_error(line: usize, column: usize, message: impl Into<String>) -> cayError {
-    parser_error_with_file(None, line, column, message)
+pub fn parser_error(code: &'static str, line: usize, column: usize, message: impl Into<String>) -> cayError {
+    parser_error_with_file(code, None, line, column, message)
 }
 
-pub fn parser_error_with_file(file: Option<String>, line: usize, column: usize, message: impl Into<String>) -> cayError {
+pub fn parser_error_with_file(code: &'static str, file: Option<String>, line: usize, column: usize, message: impl Into<String>) -> cayError {
     let msg = message.into();
     let suggestion = get_parser_suggestion(&msg);
     cayError::Parser {
+        code,
         file,
         line,
         column,
@@ -190,14 +216,15 @@ pub fn parser_error_with_file(file: Option<String>, line: usize, column: usize,
 }
 
 // 语义错误
-pub fn semantic_error(line: usize, column: usize, message: impl Into<String>) -> cayError {
-    semantic_error_with_file(None, line, column, message)
+pub fn semantic_error(code: &'static str, line: usize, column: usize, message: impl Into<String>) -> cayError {
+    semantic_error_with_file(code, None, line, column, message)
 }
 
-pub fn semantic_error_with_file(file: Option<String>, line: usize, column: usize, message: impl Into<String>) -> cayError {
+pub fn semantic_error_with_file(code: &'static str, file: Option<String>, line: usize, column: usize, message: impl Into<String>) -> cayError {
     let msg = message.into();
     let suggestion = get_semantic_suggestion(&msg);
     cayError::Semantic {
+        code,
         file,
         line,
         column,
@@ -207,10 +234,11 @@ pub fn semantic_error_with_file(file: Option<String>, line: usize, column: usize
 }
 
 // 代码生成错误
-pub fn codegen_error(message: impl Into<String>) -> cayError {
+pub fn codegen_error(code: &'static str, message: impl Into<String>) -> cayError {
     let msg = message.into();
     let suggestion = get_codegen_suggestion(&msg);
     cayError::CodeGen {
+        code,
         message: msg,
         suggestion,
     }
@@ -468,7 +496,7 @@ pub fn get_error_location(error: &cayError) -> Option<(usize, usize)> {
 }
 
 /// 获取错误文件路径
-fn get_error_file(error: &cayError) -> Option<String> {
+pub fn get_error_file(error: &cayError) -> Option<String> {
     match error {
         cayError::Lexer { file, .. } => file.clone(),
         cayError::Parser { file, .. } => file.clone(),
@@ -490,8 +518,9 @@ fn get_error_file(error: &cayError) -> Option<String> {
 /// 
 /// # Example
 /// ```
+/// use cavvy::diagnostic::ErrorCodes;
 /// use cavvy::error::{lexer_error, print_error_with_context};
-/// let error = lexer_error(1, 1, "无效的字符");
+/// let error = lexer_error(ErrorCodes::LEXER_INVALID_CHARACTER, 1, 1, "无效的字符");
 /// print_error_with_context(&error, "let x = @", "test.cay");
 /// ```
 pub fn print_error_with_context(error: &cayError, source: &str, filename: &str) {
@@ -539,7 +568,7 @@ fn print_error_with_location(
     let help = get_error_help(error);
     
     // 使用 miette 风格格式
-    eprintln!("\n  × {}: {}", code, message);
+    eprintln!("\n  × {}[{}]: {}", code, error.code(), message);
     eprintln!("   ╭─[{}:{}:{}]", filename, line, column);
     
     // 打印源代码上下文（前后3行）
@@ -581,7 +610,7 @@ fn print_error_without_location(error: &cayError) {
     let message = get_error_message(error);
     let help = get_error_help(error);
     
-    eprintln!("\n  × {}: {}", code, message);
+    eprintln!("\n  × {}[{}]: {}", code, error.code(), message);
     
     if let Some(help_text) = help {
         if !help_text.is_empty() {
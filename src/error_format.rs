@@ -0,0 +1,304 @@
+//! 机器可读的诊断输出（`--error-format`）
+//!
+//! `cayc`、`cay-check`、`cay-run` 和 `cavly build`（转发给 cayc）支持以下格式：
+//!
+//! - `human`（默认）：带源码上下文的彩色文本，由各工具自行输出
+//! - `short`：每条诊断一行，写到标准错误，`文件:行:列: 级别[代码]: 消息`
+//! - `json`：向标准输出写一个 JSON 文档，格式见下文
+//! - `sarif`：向标准输出写一个 SARIF 2.1.0 日志，可直接上传给代码扫描服务
+//!
+//! 使用 json 和 sarif 时，工具不再向标准输出打印进度信息。
+//!
+//! # JSON 格式（版本 1）
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "tool": { "name": "cayc", "version": "5.1.0-a.2" },
+//!   "diagnostics": [
+//!     {
+//!       "code": "E4001",
+//!       "severity": "error",
+//!       "phase": "semantic",
+//!       "message": "未定义标识符: 'x'",
+//!       "description": "未定义的标识符",
+//!       "details": null,
+//!       "file": "src/main.cay",
+//!       "location": { "line": 3, "column": 9 },
+//!       "span": { "start": { "line": 3, "column": 9 }, "end": { "line": 3, "column": 10 } },
+//!       "suggestions": [
+//!         { "description": "...", "replacement": "y", "span": { "start": ..., "end": ... } }
+//!       ],
+//!       "related": [
+//!         { "message": "...", "location": { "line": 1, "column": 5 } }
+//!       ]
+//!     }
+//!   ],
+//!   "summary": { "errors": 1, "warnings": 0 }
+//! }
+//! ```
+//!
+//! - `severity` 取 `note`、`warning`、`error`、`fatal`
+//! - `phase` 取 `preprocessor`、`lexer`、`parser`、`semantic`、`codegen`、`linker`
+//! - `location` 在位置未知时为 `null`；`span`、`details`、`replacement` 可能为 `null`
+//! - 行号和列号从 1 开始，`related` 中的位置和所在诊断属于同一文件
+//!
+//! 只增加字段时不改变版本号；删除或修改已有字段时 [`SCHEMA_VERSION`] 加一。
+
+use serde_json::{json, Value};
+use crate::diagnostic::{Diagnostic, ErrorCodes, Severity, SourceLocation, SourceSpan};
+
+/// JSON 诊断格式的版本号
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// SARIF 日志的 schema 地址
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// 诊断输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorFormat {
+    #[default]
+    Human,
+    Short,
+    Json,
+    Sarif,
+}
+
+impl ErrorFormat {
+    /// 解析 `--error-format` 的取值
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "human" => Some(ErrorFormat::Human),
+            "short" => Some(ErrorFormat::Short),
+            "json" => Some(ErrorFormat::Json),
+            "sarif" => Some(ErrorFormat::Sarif),
+            _ => None,
+        }
+    }
+
+    /// 选项取值，与 [`ErrorFormat::parse`] 对应
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorFormat::Human => "human",
+            ErrorFormat::Short => "short",
+            ErrorFormat::Json => "json",
+            ErrorFormat::Sarif => "sarif",
+        }
+    }
+
+    /// 解析 `--error-format=<格式>` 或 `--error-format <格式>`
+    ///
+    /// 参数不是这个选项时返回 None；`i` 指向当前参数，使用了下一个参数时向后移动。
+    pub fn from_args(args: &[String], i: &mut usize) -> Option<Result<Self, String>> {
+        let arg = args[*i].as_str();
+        let value = if arg == "--error-format" {
+            *i += 1;
+            match args.get(*i) {
+                Some(value) => value.as_str(),
+                None => return Some(Err("--error-format 需要参数 (human/short/json/sarif)".to_string())),
+            }
+        } else {
+            arg.strip_prefix("--error-format=")?
+        };
+        Some(Self::parse(value).ok_or_else(|| format!("未知的错误格式: {} (可选: human/short/json/sarif)", value)))
+    }
+
+    /// 是否向标准输出写 JSON 文档（此时工具不打印进度信息）
+    pub fn is_machine_readable(self) -> bool {
+        matches!(self, ErrorFormat::Json | ErrorFormat::Sarif)
+    }
+}
+
+/// 按格式输出诊断
+///
+/// human 格式需要源码上下文，由各工具自行输出，这里按 short 格式处理。
+pub fn emit(format: ErrorFormat, tool: &str, main_file: &str, diagnostics: &[Diagnostic]) {
+    match format {
+        ErrorFormat::Json => println!("{}", to_pretty(&to_json(tool, main_file, diagnostics))),
+        ErrorFormat::Sarif => println!("{}", to_pretty(&to_sarif(tool, main_file, diagnostics))),
+        ErrorFormat::Human | ErrorFormat::Short => {
+            for diagnostic in diagnostics {
+                eprintln!("{}", format_short(diagnostic, main_file));
+            }
+        }
+    }
+}
+
+fn to_pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// 单行格式：`文件:行:列: 级别[代码]: 消息`
+pub fn format_short(diagnostic: &Diagnostic, main_file: &str) -> String {
+    let file = diagnostic.file.as_deref().unwrap_or(main_file);
+    let location = diagnostic.location;
+    let position = if location.line == 0 {
+        file.to_string()
+    } else {
+        format!("{}:{}:{}", file, location.line, location.column)
+    };
+    // 多行消息（例如带示例代码的语法错误）只取第一行
+    let message = diagnostic.message.lines().next().unwrap_or_default();
+    format!("{}: {}[{}]: {}", position, diagnostic.severity.name(), diagnostic.code, message)
+}
+
+/// 生成 JSON 格式的诊断文档
+pub fn to_json(tool: &str, main_file: &str, diagnostics: &[Diagnostic]) -> Value {
+    let errors = diagnostics.iter().filter(|d| d.severity >= Severity::Error).count();
+    let warnings = diagnostics.iter().filter(|d| d.severity == Severity::Warning).count();
+    json!({
+        "version": SCHEMA_VERSION,
+        "tool": { "name": tool, "version": env!("CARGO_PKG_VERSION") },
+        "diagnostics": diagnostics.iter().map(|d| diagnostic_json(d, main_file)).collect::<Vec<_>>(),
+        "summary": { "errors": errors, "warnings": warnings },
+    })
+}
+
+fn diagnostic_json(diagnostic: &Diagnostic, main_file: &str) -> Value {
+    json!({
+        "code": diagnostic.code,
+        "severity": diagnostic.severity.name(),
+        "phase": diagnostic.phase.name(),
+        "message": diagnostic.message,
+        "description": ErrorCodes::get_description(&diagnostic.code),
+        "details": diagnostic.details,
+        "file": diagnostic.file.as_deref().unwrap_or(main_file),
+        "location": location_json(diagnostic.location),
+        "span": diagnostic.span.map(span_json),
+        "suggestions": diagnostic.suggestions.iter().map(|s| json!({
+            "description": s.description,
+            "replacement": s.replacement,
+            "span": s.span.map(span_json),
+        })).collect::<Vec<_>>(),
+        "related": diagnostic.related_info.iter().map(|info| json!({
+            "message": info.message,
+            "location": location_json(info.location),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn location_json(location: SourceLocation) -> Value {
+    if location.line == 0 {
+        Value::Null
+    } else {
+        json!({ "line": location.line, "column": location.column })
+    }
+}
+
+fn span_json(span: SourceSpan) -> Value {
+    json!({ "start": location_json(span.start), "end": location_json(span.end) })
+}
+
+/// 生成 SARIF 2.1.0 日志
+///
+/// 错误代码作为规则 ID，修复建议中有替换代码的转为 `fixes`，其余放在 `properties.suggestions` 中。
+pub fn to_sarif(tool: &str, main_file: &str, diagnostics: &[Diagnostic]) -> Value {
+    let mut codes: Vec<&str> = diagnostics.iter().map(|d| d.code.as_str()).collect();
+    codes.sort();
+    codes.dedup();
+    let rules: Vec<Value> = codes.iter().map(|code| json!({
+        "id": code,
+        "shortDescription": { "text": ErrorCodes::get_description(code) },
+        "help": { "text": ErrorCodes::get_suggestion(code) },
+    })).collect();
+
+    let results: Vec<Value> = diagnostics.iter().map(|diagnostic| {
+        let uri = artifact_uri(diagnostic.file.as_deref().unwrap_or(main_file));
+        let rule_index = codes.iter().position(|code| *code == diagnostic.code).unwrap_or(0);
+        let level = match diagnostic.severity {
+            Severity::Note => "note",
+            Severity::Warning => "warning",
+            Severity::Error | Severity::Fatal => "error",
+        };
+        let mut result = json!({
+            "ruleId": diagnostic.code,
+            "ruleIndex": rule_index,
+            "level": level,
+            "message": { "text": diagnostic.message },
+            "locations": [{ "physicalLocation": physical_location(&uri, diagnostic.location, diagnostic.span) }],
+            "properties": {
+                "phase": diagnostic.phase.name(),
+                "severity": diagnostic.severity.name(),
+            },
+        });
+        if let Some(details) = &diagnostic.details {
+            result["properties"]["details"] = json!(details);
+        }
+        let fixes: Vec<Value> = diagnostic.suggestions.iter()
+            .filter_map(|s| match (&s.replacement, s.span) {
+                (Some(replacement), Some(span)) => Some(json!({
+                    "description": { "text": s.description },
+                    "artifactChanges": [{
+                        "artifactLocation": { "uri": uri },
+                        "replacements": [{
+                            "deletedRegion": region(span.start, Some(span)),
+                            "insertedContent": { "text": replacement },
+                        }],
+                    }],
+                })),
+                _ => None,
+            })
+            .collect();
+        if !fixes.is_empty() {
+            result["fixes"] = json!(fixes);
+        }
+        let suggestions: Vec<&str> = diagnostic.suggestions.iter()
+            .filter(|s| s.replacement.is_none() || s.span.is_none())
+            .map(|s| s.description.as_str())
+            .collect();
+        if !suggestions.is_empty() {
+            result["properties"]["suggestions"] = json!(suggestions);
+        }
+        if !diagnostic.related_info.is_empty() {
+            result["relatedLocations"] = json!(diagnostic.related_info.iter().enumerate().map(|(id, info)| json!({
+                "id": id,
+                "message": { "text": info.message },
+                "physicalLocation": physical_location(&uri, info.location, None),
+            })).collect::<Vec<_>>());
+        }
+        result
+    }).collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": tool,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// SARIF 要求使用 URI 形式的路径分隔符
+fn artifact_uri(file: &str) -> String {
+    file.replace('\\', "/")
+}
+
+fn physical_location(uri: &str, location: SourceLocation, span: Option<SourceSpan>) -> Value {
+    let mut physical = json!({ "artifactLocation": { "uri": uri } });
+    if location.line > 0 {
+        physical["region"] = region(location, span);
+    }
+    physical
+}
+
+/// SARIF 的行列号从 1 开始，为 0 的列号省略
+fn region(start: SourceLocation, span: Option<SourceSpan>) -> Value {
+    let mut region = json!({ "startLine": start.line.max(1) });
+    if start.column > 0 {
+        region["startColumn"] = json!(start.column);
+    }
+    if let Some(span) = span.filter(|span| span.end.line >= start.line && span.end.line > 0) {
+        region["endLine"] = json!(span.end.line);
+        if span.end.column > 0 {
+            region["endColumn"] = json!(span.end.column);
+        }
+    }
+    region
+}
@@ -0,0 +1,2065 @@
+//! 错误代码的详细解释（`cayc --explain <CODE>`）
+//!
+//! `ErrorCodes` 中的每个代码在这里都有一条说明：错误的含义、常见原因，
+//! 以及一个会触发该错误的示例和修正后的示例。
+//!
+//! ```
+//! use cavvy::explain::Explanation;
+//!
+//! let explanation = Explanation::find("e4001").unwrap();
+//! assert_eq!(explanation.code, "E4001");
+//! ```
+
+use std::fmt;
+use crate::diagnostic::ErrorCodes;
+
+/// 错误代码的详细解释
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Explanation {
+    pub code: &'static str,
+    /// 错误的含义和常见原因
+    pub explanation: &'static str,
+    /// 触发该错误的示例
+    pub failing: &'static str,
+    /// 修正后的示例
+    pub fixed: &'static str,
+}
+
+impl Explanation {
+    /// 按错误代码查找解释（不区分大小写）
+    pub fn find(code: &str) -> Option<&'static Explanation> {
+        let code = ErrorCodes::find(code)?;
+        EXPLANATIONS.iter().find(|explanation| explanation.code == code)
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.code, ErrorCodes::get_description(self.code))?;
+        writeln!(f)?;
+        writeln!(f, "{}", self.explanation)?;
+        writeln!(f)?;
+        writeln!(f, "错误示例:")?;
+        writeln!(f)?;
+        for line in self.failing.lines() {
+            writeln!(f, "    {}", line)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "修正示例:")?;
+        writeln!(f)?;
+        for line in self.fixed.lines() {
+            writeln!(f, "    {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// 所有错误代码的解释，顺序与 `ErrorCodes::ALL` 相同
+pub const EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        code: ErrorCodes::IO_ERROR,
+        explanation: "编译器无法读取源文件或写入输出文件。常见原因是路径拼写错误、文件不存在、\n\
+                      没有读写权限，或输出目录不存在。",
+        failing: r#"$ cayc hello.cay
+# hello.cay 不在当前目录中"#,
+        fixed: r#"$ cayc examples/hello.cay hello.exe"#,
+    },
+
+    // ==================== 预处理器 ====================
+    Explanation {
+        code: ErrorCodes::PREPROCESSOR_DEFINE_ERROR,
+        explanation: "#define 指令后面没有任何内容。#define 至少需要一个宏名称，\n\
+                      后面可以跟替换文本。",
+        failing: r#"#define
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"#define GREETING "hello"
+public class Main {
+    public static void main() {
+        println(GREETING);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PREPROCESSOR_IFDEF_ERROR,
+        explanation: "条件编译指令使用不当：#ifdef/#ifndef 缺少宏名称，或者 #else、#endif\n\
+                      没有对应的 #ifdef/#ifndef。",
+        failing: r#"public class Main {
+    public static void main() {
+        println("hello");
+    }
+}
+#endif"#,
+        fixed: r#"#ifdef DEBUG
+public class Debug {
+}
+#endif
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PREPROCESSOR_INCLUDE_ERROR,
+        explanation: "#include 指令无法找到或读取要包含的文件，或者语法不正确。\n\
+                      引号形式 #include \"path\" 相对当前文件查找，尖括号形式 #include <path>\n\
+                      在系统包含路径（caylibs 和 -I 参数）中查找。",
+        failing: r#"#include "missing_helper.cay"
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PREPROCESSOR_UNCLOSED_DIRECTIVE,
+        explanation: "文件结束时仍有 #ifdef 或 #ifndef 没有被 #endif 关闭。\n\
+                      每个条件编译块都必须以 #endif 结束。",
+        failing: r#"#ifdef DEBUG
+public class Debug {
+}
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"#ifdef DEBUG
+public class Debug {
+}
+#endif
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PREPROCESSOR_CIRCULAR_INCLUDE,
+        explanation: "两个或多个文件通过 #include 相互包含，形成了循环。\n\
+                      把共享的声明提取到单独的文件中，或者用 #ifndef 包含保护避免重复展开。",
+        failing: r#"// a.cay
+#include "b.cay"
+
+// b.cay
+#include "a.cay""#,
+        fixed: r#"// a.cay
+#include "common.cay"
+
+// b.cay
+#include "common.cay""#,
+    },
+    Explanation {
+        code: ErrorCodes::PREPROCESSOR_INVALID_MACRO,
+        explanation: "#define 后面只有注释，去掉注释后宏名称为空。",
+        failing: r#"#define // 调试开关
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"#define DEBUG // 调试开关
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PREPROCESSOR_UNKNOWN_DIRECTIVE,
+        explanation: "以 # 开头的行不是已知的预处理指令。支持的指令有 #include、#define、\n\
+                      #undef、#ifdef、#ifndef、#else、#elif、#endif、#error 和 #warning。",
+        failing: r#"#defne DEBUG
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"#define DEBUG
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PREPROCESSOR_USER_ERROR,
+        explanation: "源代码中的 #error 指令被执行。#error 通常放在条件编译块中，\n\
+                      用来拒绝不支持的配置；检查是哪个条件让它生效。",
+        failing: r#"#error 此版本需要定义 PLATFORM
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"#ifndef PLATFORM
+#define PLATFORM "linux"
+#endif
+public class Main {
+    public static void main() {
+        println(PLATFORM);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::IMPORT_NOT_FOUND,
+        explanation: "import 声明引用的类型或包找不到。导入路径按包名对应到目录，\n\
+                      在主文件所在目录、caylibs 目录和 -I 参数指定的目录下查找。",
+        failing: r#"import geometry.Missing;
+
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"import geometry.shapes.Shape;
+
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::IMPORT_PACKAGE_MISMATCH,
+        explanation: "导入路径找到的文件中，package 声明与导入路径不一致。\n\
+                      文件的 package 声明必须与它所在的目录结构相同。",
+        failing: r#"// geometry/Point.cay
+package shapes;
+public class Point {
+}
+
+// main.cay
+import geometry.Point;"#,
+        fixed: r#"// geometry/Point.cay
+package geometry;
+public class Point {
+}
+
+// main.cay
+import geometry.Point;"#,
+    },
+    Explanation {
+        code: ErrorCodes::IMPORT_DUPLICATE_TYPE,
+        explanation: "一个简单类型名对应了多个包中的类型：两个单类型导入同名、单类型导入与本文件\n\
+                      声明的类型同名，或者用简单名使用了多个通配符导入都提供的类型；\n\
+                      同一个包中的类型在多个文件中声明也会报告此错误。\n\
+                      不同包中的同名类型可以共存，只导入其中一个，另一个用限定名访问。",
+        failing: r#"// a/Util.cay 和 b/Util.cay 都声明了 public class Util
+import a.Util;
+import b.Util;"#,
+        fixed: r#"import a.Util;
+
+// b.Util 用限定名访问
+b.Util other = new b.Util();"#,
+    },
+
+    // ==================== 词法分析 ====================
+    Explanation {
+        code: ErrorCodes::LEXER_INVALID_CHARACTER,
+        explanation: "源代码中出现了 Cavvy 不认识的字符，例如反引号或全角标点。\n\
+                      字符串和注释之外只能使用 ASCII 运算符和标点。",
+        failing: r#"public class Main {
+    public static void main() {
+        int x = 1 ` 2;
+        println(x);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int x = 1 + 2;
+        println(x);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::LEXER_UNTERMINATED_STRING,
+        explanation: "字符串字面量缺少结束的双引号。字符串不能跨行，\n\
+                      需要换行时使用转义序列 \\n。",
+        failing: r#"public class Main {
+    public static void main() {
+        println("hello);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::LEXER_INVALID_ESCAPE_SEQUENCE,
+        explanation: "字符串或字符字面量中使用了不支持的转义序列。支持的转义序列有\n\
+                      \\n、\\t、\\r、\\\"、\\'、\\\\ 和 \\0。目前词法分析器把这类字面量\n\
+                      整体报告为 E2001 或 E2002，此代码保留给更精确的诊断。",
+        failing: r#"println("C:\qtemp");"#,
+        fixed: r#"println("C:\\qtemp");"#,
+    },
+    Explanation {
+        code: ErrorCodes::LEXER_INVALID_NUMBER_LITERAL,
+        explanation: "数字字面量的格式不正确，例如十六进制字面量中出现了非十六进制数字。\n\
+                      目前词法分析器把这类输入拆分成多个记号，由语法分析报告错误，\n\
+                      此代码保留给更精确的诊断。",
+        failing: r#"int mask = 0xFG;"#,
+        fixed: r#"int mask = 0xFF;"#,
+    },
+    Explanation {
+        code: ErrorCodes::LEXER_UNTERMINATED_COMMENT,
+        explanation: "块注释以 /* 开始，但没有找到配对的 */。目前词法分析器在这种情况下\n\
+                      报告 E2001，此代码保留给更精确的诊断。",
+        failing: r#"/* 计算总和
+int total = 0;"#,
+        fixed: r#"/* 计算总和 */
+int total = 0;"#,
+    },
+    Explanation {
+        code: ErrorCodes::LEXER_INVALID_IDENTIFIER,
+        explanation: "标识符必须以字母或下划线开头，只能包含字母、数字和下划线。\n\
+                      目前词法分析器把以数字开头的名称拆分成数字和标识符两个记号，\n\
+                      由语法分析报告错误，此代码保留给更精确的诊断。",
+        failing: r#"int 2fast = 1;"#,
+        fixed: r#"int fast2 = 1;"#,
+    },
+
+    // ==================== 语法分析 ====================
+    Explanation {
+        code: ErrorCodes::PARSER_UNEXPECTED_TOKEN,
+        explanation: "语法分析器在当前位置遇到了不能出现在这里的记号，例如多余的右大括号、\n\
+                      文件顶层的表达式，或 switch 中既不是 case 也不是 default 的内容。",
+        failing: r#"public class Main {
+    public static void main() {
+        println("hello");
+    }
+}
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_EXPECTED_SEMICOLON,
+        explanation: "语句末尾缺少分号。变量声明、表达式语句、return、break 和 continue\n\
+                      都必须以分号结束。",
+        failing: r#"public class Main {
+    public static void main() {
+        int x = 1
+        println(x);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int x = 1;
+        println(x);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_EXPECTED_BRACE,
+        explanation: "缺少左大括号或右大括号。类体、方法体和代码块都必须用一对大括号包围，\n\
+                      检查大括号是否成对出现。",
+        failing: r#"public class Main
+    public static void main() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_EXPECTED_PAREN,
+        explanation: "缺少左括号或右括号。方法调用的参数列表，以及 if、while、for\n\
+                      的条件都必须用括号包围。",
+        failing: r#"public class Main {
+    public static void main() {
+        println("hello";
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_EXPECTED_IDENTIFIER,
+        explanation: "在需要名称的位置（类名、方法名、变量名、参数名等）没有找到标识符，\n\
+                      常见原因是漏写了名称，或者把关键字用作了名称。",
+        failing: r#"public class {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_EXPECTED_TYPE,
+        explanation: "在需要类型的位置（字段、参数、返回值、泛型参数等）没有找到类型名，\n\
+                      例如参数列表末尾多了一个逗号。",
+        failing: r#"public class Main {
+    public static void show(int x, ) {
+        println(x);
+    }
+
+    public static void main() {
+        show(1);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void show(int x) {
+        println(x);
+    }
+
+    public static void main() {
+        show(1);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_INVALID_STATEMENT,
+        explanation: "语句的结构不合法，例如 switch 中混用 ':' 和 '->' 形式的分支、\n\
+                      嵌套的 defer、延迟执行变量声明，或没有资源声明的 try。",
+        failing: r#"public class Main {
+    public static void main() {
+        int x = 1;
+        switch (x) {
+            case 1:
+                println("one");
+                break;
+            case 2 -> println("two");
+        }
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int x = 1;
+        switch (x) {
+            case 1 -> println("one");
+            case 2 -> println("two");
+        }
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_INVALID_EXPRESSION,
+        explanation: "在需要表达式的位置遇到了不能开始表达式的记号，例如运算符缺少操作数，\n\
+                      或赋值号右边为空。",
+        failing: r#"public class Main {
+    public static void main() {
+        int x = 3 + ;
+        println(x);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int x = 3 + 2;
+        println(x);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_MISSING_MAIN,
+        explanation: "程序中没有 main 方法。可执行程序需要一个 public static void main()\n\
+                      作为入口。目前缺少入口会在链接阶段报告，此代码保留给编译期检查。",
+        failing: r#"public class Main {
+    public static void run() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_MULTIPLE_MAIN,
+        explanation: "多个类声明了 main 方法。目前这种情况由语义分析报告为 E4032，\n\
+                      此代码保留给语法分析阶段的检查。",
+        failing: r#"public class A {
+    public static void main() {
+    }
+}
+public class B {
+    public static void main() {
+    }
+}"#,
+        fixed: r#"@main
+public class A {
+    public static void main() {
+    }
+}
+public class B {
+    public static void main() {
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_INVALID_DECLARATION,
+        explanation: "声明本身的写法不被允许，例如接口方法带有方法体、基本类型声明为可空类型、\n\
+                      记录中声明实例字段、属性缺少 get 访问器，或重载比较运算符。",
+        failing: r#"public class Main {
+    public static void main() {
+        int? count = null;
+        println(count);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        String? name = null;
+        println(name ?: "none");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_INVALID_MODIFIER,
+        explanation: "修饰符不能用在这里，例如给属性加 final、给属性访问器加 static，\n\
+                      或 sealed 类型缺少 permits 列表。",
+        failing: r#"public sealed interface Shape {
+}
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"public sealed interface Shape permits Circle {
+}
+public class Circle implements Shape {
+}
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_INVALID_ANNOTATION,
+        explanation: "注解用在了不支持的位置，或者注解参数不是字面量。注解只能用于类、接口、\n\
+                      方法、字段和参数，参数只能是整数、字符串、字符或布尔字面量。",
+        failing: r#"@Flags
+enum Color {
+    Red,
+    Green
+}
+public class Main {
+    public static void main() {
+        println("colors");
+    }
+}"#,
+        fixed: r#"enum Color {
+    Red,
+    Green
+}
+public class Main {
+    public static void main() {
+        println("colors");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_LITERAL_OUT_OF_RANGE,
+        explanation: "带类型后缀的整数字面量超出了该类型的取值范围，例如 300uy 超出 ubyte\n\
+                      的范围 [0, 255]。需要截断高位时请显式转换。",
+        failing: r#"public class Main {
+    public static void main() {
+        ubyte value = 300uy;
+        println(value);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        ubyte value = 255uy;
+        println(value);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_INVALID_IMPORT,
+        explanation: "package 或 import 声明的位置或写法不正确。package 声明必须是文件中的\n\
+                      第一条声明，import 声明紧随其后并位于所有类型声明之前。",
+        failing: r#"public class Main {
+    public static void main() {
+        println("hello");
+    }
+}
+import geometry.shapes.Shape;"#,
+        fixed: r#"import geometry.shapes.Shape;
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::PARSER_NESTED_CLASS,
+        explanation: "嵌套类、内部类或匿名类的用法不被支持，例如匿名类声明构造函数、\n\
+                      捕获类型推断的局部变量，或在没有外部类实例的地方创建内部类。",
+        failing: r#"interface Task {
+    int run(int x);
+}
+public class Main {
+    public static void main() {
+        var offset = 10;
+        Task task = new Task() {
+            public int run(int x) { return x + offset; }
+        };
+        println(task.run(1));
+    }
+}"#,
+        fixed: r#"interface Task {
+    int run(int x);
+}
+public class Main {
+    public static void main() {
+        int offset = 10;
+        Task task = new Task() {
+            public int run(int x) { return x + offset; }
+        };
+        println(task.run(1));
+    }
+}"#,
+    },
+
+    // ==================== 语义分析 ====================
+    Explanation {
+        code: ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
+        explanation: "使用了没有声明的变量、类、字段或枚举变体。检查名称的拼写，\n\
+                      以及声明是否在当前作用域中可见。",
+        failing: r#"public class Main {
+    public static void main() {
+        int count = 1;
+        int total = cout + 1;
+        println(total);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int count = 1;
+        int total = count + 1;
+        println(total);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
+        explanation: "同一作用域中出现了同名的类、方法、字段或变量。为其中一个改名，\n\
+                      或删除重复的声明。",
+        failing: r#"public class Main {
+    public static void main() {
+        int x = 1;
+        int x = 2;
+        println(x);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int x = 1;
+        int y = 2;
+        println(x + y);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_TYPE_MISMATCH,
+        explanation: "值的类型与期望的类型不兼容，例如把字符串赋给 int 变量，\n\
+                      或整数字面量超出目标类型的取值范围。",
+        failing: r#"public class Main {
+    public static void main() {
+        int x = "42";
+        println(x);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int x = 42;
+        println(x);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_INVALID_CAST,
+        explanation: "两个类型之间不能进行强制类型转换，例如把字符串或数组转换为 int。\n\
+                      字符串需要用解析函数转换为数字。",
+        failing: r#"public class Main {
+    public static void main() {
+        int[] values = new int[3];
+        int n = (int) values;
+        println(n);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int[] values = new int[3];
+        int n = values.length;
+        println(n);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_INCOMPATIBLE_TYPES,
+        explanation: "同一表达式的各个分支类型不兼容，例如三元运算符或 switch 表达式的\n\
+                      分支分别产生字符串和整数，或 instanceof 的左侧永远不可能是右侧的类型。",
+        failing: r#"public class Main {
+    public static void main() {
+        boolean flag = true;
+        auto value = flag ? 1 : "one";
+        println(value);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        boolean flag = true;
+        auto value = flag ? "1" : "one";
+        println(value);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_UNINITIALIZED_VARIABLE,
+        explanation: "局部变量在某条执行路径上没有被赋值就被读取。在声明时给出初始值，\n\
+                      或确保每个分支都为它赋值。",
+        failing: r#"public class Main {
+    public static int classify(int n) {
+        int result;
+        if (n > 0) {
+            result = 1;
+        }
+        return result;
+    }
+
+    public static void main() {
+        println(classify(5));
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static int classify(int n) {
+        int result = 0;
+        if (n > 0) {
+            result = 1;
+        }
+        return result;
+    }
+
+    public static void main() {
+        println(classify(5));
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_INVALID_OPERATION,
+        explanation: "操作在当前上下文中无效，例如在类外使用 this、在没有父类的类中使用 super，\n\
+                      或访问基本类型值的成员。",
+        failing: r#"public class Main {
+    public static void main() {
+        int x = 5;
+        int y = x.value;
+        println(y);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int x = 5;
+        int y = x;
+        println(y);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_ACCESS_VIOLATION,
+        explanation: "访问了当前位置不可见的成员：其他类的 private 成员、包外的 protected\n\
+                      成员，或其他包中非 public 的类型和成员。",
+        failing: r#"public class Account {
+    private int balance;
+}
+public class Main {
+    public static void main() {
+        Account a = new Account();
+        int b = a.balance;
+        println(b);
+    }
+}"#,
+        fixed: r#"public class Account {
+    private int balance;
+
+    public int getBalance() {
+        return balance;
+    }
+}
+public class Main {
+    public static void main() {
+        Account a = new Account();
+        int b = a.getBalance();
+        println(b);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_STATIC_CONTEXT,
+        explanation: "在静态方法中访问了实例字段、实例方法或 this。静态方法不属于任何实例，\n\
+                      把成员声明为 static，或先创建对象再通过对象访问。",
+        failing: r#"public class Main {
+    private int value = 1;
+
+    public static void main() {
+        int x = value;
+        println(x);
+    }
+}"#,
+        fixed: r#"public class Main {
+    private static int value = 1;
+
+    public static void main() {
+        int x = value;
+        println(x);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_FINAL_REASSIGNMENT,
+        explanation: "给 final 变量或 final 字段再次赋值。final 的值只能初始化一次，\n\
+                      需要修改时去掉 final，或使用新的变量。",
+        failing: r#"public class Main {
+    public static void main() {
+        final int limit = 10;
+        limit = 20;
+        println(limit);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int limit = 10;
+        limit = 20;
+        println(limit);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_MISSING_RETURN,
+        explanation: "有返回值的方法存在没有 return 语句的执行路径。在每个分支的末尾返回值，\n\
+                      或在方法末尾添加 return。",
+        failing: r#"public class Main {
+    public static int sign(int n) {
+        if (n > 0) {
+            return 1;
+        }
+    }
+
+    public static void main() {
+        println(sign(5));
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static int sign(int n) {
+        if (n > 0) {
+            return 1;
+        }
+        return 0;
+    }
+
+    public static void main() {
+        println(sign(5));
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_RETURN_TYPE_MISMATCH,
+        explanation: "return 语句返回的值与方法或 lambda 声明的返回类型不兼容。",
+        failing: r#"public class Main {
+    public static int count() {
+        return "three";
+    }
+
+    public static void main() {
+        println(count());
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static int count() {
+        return 3;
+    }
+
+    public static void main() {
+        println(count());
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_BREAK_OUTSIDE_LOOP,
+        explanation: "break 语句不在循环或 switch 之中。目前这个错误在代码生成阶段报告。",
+        failing: r#"public class Main {
+    public static void main() {
+        println("start");
+        break;
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        for (int i = 0; i < 10; i++) {
+            if (i == 3) {
+                break;
+            }
+            println(i);
+        }
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_CONTINUE_OUTSIDE_LOOP,
+        explanation: "continue 语句不在循环之中。目前这个错误在代码生成阶段报告。",
+        failing: r#"public class Main {
+    public static void main() {
+        println("start");
+        continue;
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        for (int i = 0; i < 10; i++) {
+            if (i % 2 == 0) {
+                continue;
+            }
+            println(i);
+        }
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_INVALID_ARRAY_SIZE,
+        explanation: "数组大小不是整数，或者是可以在编译期算出的负数。",
+        failing: r#"public class Main {
+    public static void main() {
+        int[] values = new int[-5];
+        println(values.length);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int[] values = new int[5];
+        println(values.length);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_ARRAY_INDEX_TYPE,
+        explanation: "数组下标必须是整数类型（int、long 等），不能是字符串、浮点数或布尔值。",
+        failing: r#"public class Main {
+    public static void main() {
+        int[] values = new int[10];
+        int first = values["first"];
+        println(first);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int[] values = new int[10];
+        int first = values[0];
+        println(first);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_METHOD_NOT_FOUND,
+        explanation: "调用的方法在类、接口或类型参数的约束中不存在。检查方法名的拼写，\n\
+                      以及方法是否定义在正确的类中。",
+        failing: r#"public class Greeter {
+    public void greet() {
+        println("hello");
+    }
+}
+public class Main {
+    public static void main() {
+        Greeter g = new Greeter();
+        g.great();
+    }
+}"#,
+        fixed: r#"public class Greeter {
+    public void greet() {
+        println("hello");
+    }
+}
+public class Main {
+    public static void main() {
+        Greeter g = new Greeter();
+        g.greet();
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT,
+        explanation: "调用时传入的参数个数与内置函数、String 方法、函数指针、结构体或枚举变体\n\
+                      要求的个数不一致。普通方法的参数不匹配报告为 E4019。",
+        failing: r#"public class Main {
+    public static void main() {
+        String text = "hello";
+        int n = text.length(1);
+        println(n);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        String text = "hello";
+        int n = text.length();
+        println(n);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH,
+        explanation: "调用时参数与声明的参数类型不兼容，或者找不到参数个数和类型都匹配的方法重载。",
+        failing: r#"public class Main {
+    public static void main() {
+        String text = "hello";
+        char c = text.charAt("0");
+        println(c);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        String text = "hello";
+        char c = text.charAt(0);
+        println(c);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_ABSTRACT_CLASS_INSTANCE,
+        explanation: "抽象类不能直接实例化。创建一个实现了全部抽象方法的子类，\n\
+                      然后实例化这个子类。",
+        failing: r#"public abstract class Shape {
+    public abstract double area();
+}
+public class Main {
+    public static void main() {
+        Shape s = new Shape();
+    }
+}"#,
+        fixed: r#"public abstract class Shape {
+    public abstract double area();
+}
+public class Square extends Shape {
+    public double area() {
+        return 4.0;
+    }
+}
+public class Main {
+    public static void main() {
+        Shape s = new Square();
+        println(s.area());
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_OVERRIDE_ERROR,
+        explanation: "方法重写不合法：标注了 @Override 但父类中没有同名方法，\n\
+                      或者试图重写父类的 final 方法。",
+        failing: r#"public class Base {
+    public void run() {
+    }
+}
+public class Derived extends Base {
+    @Override
+    public void runn() {
+    }
+}
+public class Main {
+    public static void main() {
+        Derived d = new Derived();
+    }
+}"#,
+        fixed: r#"public class Base {
+    public void run() {
+    }
+}
+public class Derived extends Base {
+    @Override
+    public void run() {
+    }
+}
+public class Main {
+    public static void main() {
+        Derived d = new Derived();
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_INHERITANCE_ERROR,
+        explanation: "extends 或 implements 引用的父类型不存在，或者不能被继承。",
+        failing: r#"public class Dog extends Animal {
+}
+public class Main {
+    public static void main() {
+        Dog d = new Dog();
+    }
+}"#,
+        fixed: r#"public class Animal {
+}
+public class Dog extends Animal {
+}
+public class Main {
+    public static void main() {
+        Dog d = new Dog();
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_CIRCULAR_INHERITANCE,
+        explanation: "类的继承链形成了环，例如 A 继承 B，B 又继承 A。继承关系必须是一棵树。",
+        failing: r#"public class A extends B {
+}
+public class B extends A {
+}
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+        fixed: r#"public class A {
+}
+public class B extends A {
+}
+public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_FINAL_CLASS_INHERITANCE,
+        explanation: "final 类不能被继承。去掉父类的 final 修饰符，或改用组合代替继承。",
+        failing: r#"public final class Point {
+}
+public class Point3 extends Point {
+}
+public class Main {
+    public static void main() {
+        Point3 p = new Point3();
+    }
+}"#,
+        fixed: r#"public class Point {
+}
+public class Point3 extends Point {
+}
+public class Main {
+    public static void main() {
+        Point3 p = new Point3();
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_INTERFACE_IMPL_ERROR,
+        explanation: "类没有正确实现接口：缺少接口要求的方法，或者从多个接口继承了\n\
+                      相互冲突的默认方法而没有自行重写。",
+        failing: r#"public interface Flyer {
+    default void describe() {
+        println("I can fly");
+    }
+}
+public interface Swimmer {
+    default void describe() {
+        println("I can swim");
+    }
+}
+public class Duck implements Flyer, Swimmer {
+}
+public class Main {
+    public static void main() {
+        Duck duck = new Duck();
+        duck.describe();
+    }
+}"#,
+        fixed: r#"public interface Flyer {
+    default void describe() {
+        println("I can fly");
+    }
+}
+public interface Swimmer {
+    default void describe() {
+        println("I can swim");
+    }
+}
+public class Duck implements Flyer, Swimmer {
+    public void describe() {
+        println("I can fly and swim");
+    }
+}
+public class Main {
+    public static void main() {
+        Duck duck = new Duck();
+        duck.describe();
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_VOID_ASSIGNMENT,
+        explanation: "void 表达式没有值，不能用来构造 Option 或 Result 等值。",
+        failing: r#"public class Main {
+    public static void log() {
+        println("log");
+    }
+
+    public static void main() {
+        auto value = Some(log());
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static int answer() {
+        return 42;
+    }
+
+    public static void main() {
+        auto value = Some(answer());
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_DIVISION_BY_ZERO,
+        explanation: "编译期可以确定的整数除法或取模的除数为零。",
+        failing: r#"public class Main {
+    public static void main() {
+        int x = 10 / 0;
+        println(x);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int x = 10 / 2;
+        println(x);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_UNREACHABLE_CODE,
+        explanation: "（警告）语句位于 return、break、continue 或死循环之后，永远不会执行。\n\
+                      删除这些语句，或调整控制流。",
+        failing: r#"public class Main {
+    public static int one() {
+        return 1;
+        println("done");
+    }
+
+    public static void main() {
+        println(one());
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static int one() {
+        println("done");
+        return 1;
+    }
+
+    public static void main() {
+        println(one());
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_UNUSED_VARIABLE,
+        explanation: "局部变量声明后从未被读取。编译器本身不报告此代码，\n\
+                      未使用的变量由 cay-lint 的 unused_variable 规则检查。",
+        failing: r#"public class Main {
+    public static void main() {
+        int unused = 1;
+        println("hello");
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        println("hello");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_DEPRECATED_USAGE,
+        explanation: "（警告）调用了标注 @Deprecated 的方法或使用了标注 @Deprecated 的类型。\n\
+                      改用注解消息中推荐的替代 API。",
+        failing: r#"public class Util {
+    @Deprecated("use sum")
+    public static int add(int a, int b) {
+        return a + b;
+    }
+
+    public static int sum(int a, int b) {
+        return a + b;
+    }
+}
+public class Main {
+    public static void main() {
+        println(Util.add(1, 2));
+    }
+}"#,
+        fixed: r#"public class Util {
+    @Deprecated("use sum")
+    public static int add(int a, int b) {
+        return a + b;
+    }
+
+    public static int sum(int a, int b) {
+        return a + b;
+    }
+}
+public class Main {
+    public static void main() {
+        println(Util.sum(1, 2));
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_SEALED_HIERARCHY,
+        explanation: "违反了 sealed 类型的继承限制：不在 permits 列表中的类继承或实现了\n\
+                      sealed 类型，或者 permits 列表中的类没有直接继承它。",
+        failing: r#"public sealed interface Shape permits Circle {
+    double area();
+}
+public class Circle implements Shape {
+    public double area() {
+        return 3.0;
+    }
+}
+public class Hexagon implements Shape {
+    public double area() {
+        return 6.0;
+    }
+}
+public class Main {
+    public static void main() {
+        Shape s = new Circle();
+        println(s.area());
+    }
+}"#,
+        fixed: r#"public sealed interface Shape permits Circle, Hexagon {
+    double area();
+}
+public class Circle implements Shape {
+    public double area() {
+        return 3.0;
+    }
+}
+public class Hexagon implements Shape {
+    public double area() {
+        return 6.0;
+    }
+}
+public class Main {
+    public static void main() {
+        Shape s = new Circle();
+        println(s.area());
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_MAIN_CLASS,
+        explanation: "无法确定程序入口：多个类都有 main 方法且没有用 @main 指定主类，\n\
+                      或者多个类都标记了 @main。",
+        failing: r#"public class A {
+    public static void main() {
+        println("A");
+    }
+}
+public class B {
+    public static void main() {
+        println("B");
+    }
+}"#,
+        fixed: r#"@main
+public class A {
+    public static void main() {
+        println("A");
+    }
+}
+public class B {
+    public static void main() {
+        println("B");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_FEATURE_DISABLED,
+        explanation: "使用了默认关闭的语言特性，例如顶层函数。把代码移到类中，\n\
+                      或用 -F=<feature> 启用该特性。",
+        failing: r#"int twice(int x) {
+    return x * 2;
+}
+public class Main {
+    public static void main() {
+        println(twice(2));
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static int twice(int x) {
+        return x * 2;
+    }
+
+    public static void main() {
+        println(twice(2));
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_CAPTURED_ASSIGNMENT,
+        explanation: "lambda 按值捕获外部的参数和局部变量，因此不能在 lambda 内给它们赋值。\n\
+                      改用 lambda 内部的局部变量。",
+        failing: r#"alias IntOp = fn(int) -> int;
+public class Main {
+    public static IntOp accumulate(int total) {
+        return (x) -> {
+            total = total + x;
+            return total;
+        };
+    }
+
+    public static void main() {
+        IntOp acc = accumulate(1);
+        println(acc(2));
+    }
+}"#,
+        fixed: r#"alias IntOp = fn(int) -> int;
+public class Main {
+    public static IntOp accumulate(int total) {
+        return (x) -> {
+            int sum = total + x;
+            return sum;
+        };
+    }
+
+    public static void main() {
+        IntOp acc = accumulate(1);
+        println(acc(2));
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_DEFER_ERROR,
+        explanation: "defer 或 try-with-resources 的用法不正确：延迟执行的代码中不能使用\n\
+                      return、break 或 continue 跳出，try 的资源类型必须有 close() 方法。",
+        failing: r#"public class Main {
+    public static int value() {
+        int result = 1;
+        defer {
+            return result;
+        }
+        return 0;
+    }
+
+    public static void main() {
+        println(value());
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static int value() {
+        int result = 1;
+        defer {
+            println(result);
+        }
+        return 0;
+    }
+
+    public static void main() {
+        println(value());
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_TRY_OPERATOR,
+        explanation: "'?' 运算符用法不正确：操作数必须是 Result 或 Option，所在方法也必须\n\
+                      返回相同种类且错误类型兼容的 Result 或 Option。",
+        failing: r#"public class Main {
+    public static Result<int, String> parse(String s) {
+        if (s.length() == 0) {
+            return Err("empty input");
+        }
+        return Ok(s.length());
+    }
+
+    public static void main() {
+        int n = parse("abc")?;
+        println(n);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static Result<int, String> parse(String s) {
+        if (s.length() == 0) {
+            return Err("empty input");
+        }
+        return Ok(s.length());
+    }
+
+    public static Result<int, String> twice(String s) {
+        int n = parse(s)?;
+        return Ok(n * 2);
+    }
+
+    public static void main() {
+        println("ready");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_INVALID_OPERAND,
+        explanation: "运算符不能用于这些操作数类型，例如字符串与数字相加、对非数组下标、\n\
+                      对布尔值做算术运算，或用 == 比较两个结构体。",
+        failing: r#"public class Main {
+    public static void main() {
+        String s = "abc";
+        char c = s[0];
+        println(c);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        String s = "abc";
+        char c = s.charAt(0);
+        println(c);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_NULL_SAFETY,
+        explanation: "违反了空安全规则：对不可能为 null 的值使用 '?.' 和 '?:'，或者 '?:' 的默认值\n\
+                      类型不兼容。启用 -F=null_safety 后，把可能为 null 的值赋给非空类型或直接\n\
+                      解引用可空值也会报告此错误；可能为 null 的变量需要声明为 T?。",
+        failing: r#"public class Main {
+    public static void main() {
+        int count = 5;
+        int shown = count ?: 0;
+        println(shown);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        String? name = null;
+        String shown = name ?: "anonymous";
+        println(shown);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_GENERIC_ERROR,
+        explanation: "泛型的使用不正确：给非泛型类型传递了类型参数、类型参数个数不对，\n\
+                      或者类型实参不满足类型参数的约束。",
+        failing: r#"public class Box<T> {
+}
+public class Main {
+    public static void main() {
+        Box<int, String> b = new Box<int, String>();
+    }
+}"#,
+        fixed: r#"public class Box<T> {
+}
+public class Main {
+    public static void main() {
+        Box<int> b = new Box<int>();
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_SWITCH_ERROR,
+        explanation: "switch 的写法不正确：case 标签重复、标签类型与 switch 的值不匹配、\n\
+                      模式永远不会匹配，或被前面的 case 完全覆盖。",
+        failing: r#"public class Main {
+    public static void main() {
+        String method = "get";
+        switch (method) {
+            case "get", "head":
+                println("read");
+                break;
+            case "post", "get":
+                println("write");
+                break;
+            default:
+                println("unknown");
+        }
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        String method = "get";
+        switch (method) {
+            case "get", "head":
+                println("read");
+                break;
+            case "post", "put":
+                println("write");
+                break;
+            default:
+                println("unknown");
+        }
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_NON_EXHAUSTIVE_SWITCH,
+        explanation: "switch 没有覆盖所有可能的值：switch 表达式缺少 default 分支，\n\
+                      或者对枚举、sealed 类型的 switch 漏掉了某些变体或子类。",
+        failing: r#"public enum Shape {
+    Circle(double r),
+    Rect(double w, double h),
+    Empty
+}
+public class Main {
+    public static double area(Shape s) {
+        switch (s) {
+            case Circle(r):
+                return 3.0 * r * r;
+            case Empty:
+                return 0.0;
+        }
+        return 0.0;
+    }
+
+    public static void main() {
+        println(area(Shape.Circle(1.0)));
+    }
+}"#,
+        fixed: r#"public enum Shape {
+    Circle(double r),
+    Rect(double w, double h),
+    Empty
+}
+public class Main {
+    public static double area(Shape s) {
+        switch (s) {
+            case Circle(r):
+                return 3.0 * r * r;
+            case Rect(w, h):
+                return w * h;
+            case Empty:
+                return 0.0;
+        }
+    }
+
+    public static void main() {
+        println(area(Shape.Circle(1.0)));
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_NOT_ITERABLE,
+        explanation: "for-each 循环的对象不可迭代。可迭代的值包括数组、range(...)，以及提供\n\
+                      iterator() 或 hasNext()/next() 方法的对象。",
+        failing: r#"public class Main {
+    public static void main() {
+        int count = 5;
+        for (int i : count) {
+            println(i);
+        }
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int count = 5;
+        for (int i : range(0, count)) {
+            println(i);
+        }
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_STRUCT_ERROR,
+        explanation: "结构体的声明或使用不正确：字段类型不被允许、结构体按值包含自身、\n\
+                      给临时结构体值的字段赋值，或用匿名类扩展结构体。",
+        failing: r#"struct Node {
+    int value;
+    Node next;
+}
+public class Main {
+    public static void main() {
+        Node n = new Node();
+    }
+}"#,
+        fixed: r#"struct Node {
+    int value;
+    int next;
+}
+public class Main {
+    public static void main() {
+        Node n = new Node();
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_OPERATOR_OVERLOAD,
+        explanation: "运算符重载不正确：类型没有定义所用的运算符、没有参数类型匹配的重载，\n\
+                      或者运算符方法是 static 的、返回类型不符合要求。",
+        failing: r#"public class Meters {
+    private double value;
+
+    public Meters(double value) {
+        this.value = value;
+    }
+}
+public class Main {
+    public static void main() {
+        Meters a = new Meters(1.0);
+        Meters b = new Meters(2.0);
+        Meters c = a + b;
+    }
+}"#,
+        fixed: r#"public class Meters {
+    private double value;
+
+    public Meters(double value) {
+        this.value = value;
+    }
+
+    public Meters operator+(Meters other) {
+        return new Meters(value + other.value);
+    }
+}
+public class Main {
+    public static void main() {
+        Meters a = new Meters(1.0);
+        Meters b = new Meters(2.0);
+        Meters c = a + b;
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_CONST_EVAL,
+        explanation: "编译期求值失败，或 const 的用法不正确：const 只能用于有方法体的静态方法，\n\
+                      const 函数只能调用其他 const 函数，求值也不能超过步数限制。",
+        failing: r#"public class Main {
+    const int twice(int x) {
+        return x * 2;
+    }
+
+    public static void main() {
+        println(1);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static const int twice(int x) {
+        return x * 2;
+    }
+
+    public static void main() {
+        println(twice(1));
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_ANNOTATION_ERROR,
+        explanation: "注解的组合或目标不正确，例如同一个方法同时标注 @Inline 和 @NoInline，\n\
+                      或 @Test 方法的签名不符合要求。",
+        failing: r#"public class MathUtils {
+    @Inline
+    @NoInline
+    public static int square(int x) {
+        return x * x;
+    }
+}
+public class Main {
+    public static void main() {
+        println(MathUtils.square(4));
+    }
+}"#,
+        fixed: r#"public class MathUtils {
+    @Inline
+    public static int square(int x) {
+        return x * x;
+    }
+}
+public class Main {
+    public static void main() {
+        println(MathUtils.square(4));
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_PROPERTY_ERROR,
+        explanation: "属性的使用不正确：给只读属性赋值、读取没有 getter 的属性，\n\
+                      或者属性生成的访问器与已有方法重名。",
+        failing: r#"public class Person {
+    public property String name { get; }
+
+    public Person(String name) {
+        this.name = name;
+    }
+
+    public void rename(String newName) {
+        this.name = newName;
+    }
+}
+public class Main {
+    public static void main() {
+        Person p = new Person("Ada");
+        p.rename("Grace");
+    }
+}"#,
+        fixed: r#"public class Person {
+    public property String name { get; private set; }
+
+    public Person(String name) {
+        this.name = name;
+    }
+
+    public void rename(String newName) {
+        this.name = newName;
+    }
+}
+public class Main {
+    public static void main() {
+        Person p = new Person("Ada");
+        p.rename("Grace");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_TYPE_INFERENCE,
+        explanation: "无法推断类型：auto 变量没有初始值、空数组初始化器，或 None 等泛型值\n\
+                      缺少上下文。写出显式类型即可。",
+        failing: r#"public class Main {
+    public static void main() {
+        auto missing = None;
+        println("unreachable");
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        Option<int> missing = None;
+        println("ok");
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::SEMANTIC_INTERFACE_METHOD,
+        explanation: "接口方法的声明或调用方式不正确：方法同时是 default 和 static、\n\
+                      通过实例调用接口的静态方法，或直接在接口名上调用实例方法。",
+        failing: r#"public interface Shape {
+    static Shape unit() {
+        return new Square();
+    }
+
+    double area();
+}
+public class Square implements Shape {
+    public double area() {
+        return 1.0;
+    }
+}
+public class Main {
+    public static void main() {
+        Shape s = new Square();
+        Shape u = s.unit();
+    }
+}"#,
+        fixed: r#"public interface Shape {
+    static Shape unit() {
+        return new Square();
+    }
+
+    double area();
+}
+public class Square implements Shape {
+    public double area() {
+        return 1.0;
+    }
+}
+public class Main {
+    public static void main() {
+        Shape u = Shape.unit();
+        println(u.area());
+    }
+}"#,
+    },
+
+    // ==================== 代码生成 ====================
+    Explanation {
+        code: ErrorCodes::CODEGEN_UNSUPPORTED_FEATURE,
+        explanation: "结构化 IR 构建器（`cavvy::ir::IrBuilder`）还不支持这种语句或表达式，\n\
+                      例如 defer、try-with-resources、for-each 和非整数的 case 标签。\n\
+                      cayc 使用的主代码生成器支持这些写法；直接使用 IrBuilder 时请改用等价的写法。",
+        failing: r#"public class Main {
+    public static void main() {
+        int[] values = new int[3];
+        for (int v : values) {
+            println(v);
+        }
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int[] values = new int[3];
+        for (int i = 0; i < values.length; i++) {
+            println(values[i]);
+        }
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::CODEGEN_TYPE_CONVERSION_ERROR,
+        explanation: "代码生成时无法在两种类型之间转换值。语义分析通常会先把这类写法报告为\n\
+                      E4003 或 E4004；代码生成阶段出现此错误说明前面的检查有遗漏，\n\
+                      请附上最小的复现程序报告问题，并暂时使用显式转换。",
+        failing: r#"public class Main {
+    public static void main() {
+        boolean flag = true;
+        long n = (long) flag;
+        println(n);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        boolean flag = true;
+        long n = flag ? 1L : 0L;
+        println(n);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND,
+        explanation: "代码生成时找不到变量、字段、结构体、枚举变体或外部函数的定义。\n\
+                      语义分析通常会先把这类写法报告为 E4001 或 E4017；代码生成阶段出现\n\
+                      此错误说明前面的检查有遗漏，请附上最小的复现程序报告问题。",
+        failing: r#"public class Main {
+    public static void main() {
+        int n = missing(1);
+        println(n);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static int present(int x) {
+        return x;
+    }
+
+    public static void main() {
+        int n = present(1);
+        println(n);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::CODEGEN_INVALID_OPERATION,
+        explanation: "代码生成时遇到无法生成代码的操作，例如调用不是函数指针的变量、\n\
+                      无效的赋值目标，或不是常量的 case 标签。语义分析通常会先报告这些错误；\n\
+                      代码生成阶段出现此错误说明前面的检查有遗漏，请报告问题。",
+        failing: r#"public class Main {
+    public static void main() {
+        int v = 3;
+        int limit = 3;
+        switch (v) {
+            case limit:
+                println("three");
+                break;
+            default:
+                println("other");
+        }
+    }
+}"#,
+        fixed: r#"public class Main {
+    static final int LIMIT = 3;
+
+    public static void main() {
+        int v = 3;
+        switch (v) {
+            case LIMIT:
+                println("three");
+                break;
+            default:
+                println("other");
+        }
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::CODEGEN_LLVM_ERROR,
+        explanation: "__ir 内联块中的 LLVM IR 无效或不被允许。内联 IR 只能使用算术、比较、\n\
+                      类型转换、内存访问和基本控制流指令，也不能调用 system、exec、dlopen\n\
+                      等危险函数。",
+        failing: r#"public class Main {
+    public static void main() {
+        int r = 0;
+        __ir {
+            %s = call i32 @system(i8* null)
+        }
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int r = 0;
+        __ir {
+            %s = add i32 1, 2
+        }
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::CODEGEN_INVALID_OPERAND,
+        explanation: "代码生成时运算符的操作数类型不正确，例如对浮点数做按位取反。\n\
+                      按位运算和移位只能用于整数。",
+        failing: r#"public class Main {
+    public static void main() {
+        double d = 1.5;
+        auto e = ~d;
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        int i = 3;
+        auto e = ~i;
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::CODEGEN_BUILTIN_ARGUMENTS,
+        explanation: "内置函数收到的参数个数或类型不正确，例如 println 的格式字符串中\n\
+                      占位符的个数与后面的参数个数不一致。",
+        failing: r#"public class Main {
+    public static void main() {
+        println("{} + {} = {}", 1, 2);
+    }
+}"#,
+        fixed: r#"public class Main {
+    public static void main() {
+        println("{} + {} = {}", 1, 2, 3);
+    }
+}"#,
+    },
+    Explanation {
+        code: ErrorCodes::CODEGEN_INTERNAL_ERROR,
+        explanation: "代码生成器内部状态不一致，例如缺少语义分析应当记录的类型信息，\n\
+                      或者程序中仍有语法错误。这是编译器的缺陷，没有固定的触发方式；\n\
+                      请附上最小的复现程序报告问题。",
+        failing: r#"// 没有固定的触发方式"#,
+        fixed: r#"// 没有固定的修正方式"#,
+    },
+
+    // ==================== 链接 ====================
+    Explanation {
+        code: ErrorCodes::LINKER_SYMBOL_NOT_FOUND,
+        explanation: "链接时找不到符号的定义，通常是 extern 声明的函数没有对应的库。\n\
+                      链接错误由 clang 报告，cayc 不会为它们生成此代码。",
+        failing: r#"$ cayc ffi_app.cay
+# ffi_app.cay 调用了 libmath 中的函数，但没有链接 libmath"#,
+        fixed: r#"$ cayc -L./libs -lmath ffi_app.cay"#,
+    },
+    Explanation {
+        code: ErrorCodes::LINKER_MULTIPLE_DEFINITION,
+        explanation: "同一个符号在多个目标文件或库中定义。链接错误由 clang 报告，\n\
+                      cayc 不会为它们生成此代码。",
+        failing: r#"$ cayc --ldflags "util.o util_copy.o" app.cay"#,
+        fixed: r#"$ cayc --ldflags "util.o" app.cay"#,
+    },
+    Explanation {
+        code: ErrorCodes::LINKER_LIBRARY_NOT_FOUND,
+        explanation: "链接器找不到 -l 指定的库。用 -L 添加库所在的目录。链接错误由 clang\n\
+                      报告，cayc 不会为它们生成此代码。",
+        failing: r#"$ cayc -lmylib app.cay"#,
+        fixed: r#"$ cayc -L./libs -lmylib app.cay"#,
+    },
+];
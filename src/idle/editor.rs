@@ -289,8 +289,8 @@ impl CodeEditor {
         
         // Cavvy关键字
         let keywords: Vec<&str> = vec![
-            "public", "private", "protected", "static", "final", "abstract",
-            "class", "interface", "extends", "implements", "void", "return",
+            "public", "private", "protected", "static", "final", "abstract", "sealed", "permits",
+            "class", "interface", "enum", "struct", "record", "extends", "implements", "void", "return",
             "if", "else", "while", "for", "do", "switch", "case", "default",
             "break", "continue", "new", "this", "super", "instanceof", "var", "let", "auto",
             "int", "long", "float", "double", "char", "boolean", "String",
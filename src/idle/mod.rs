@@ -370,7 +370,7 @@ impl IdleApp {
         let code = self.editor.get_text();
         let (sender, receiver) = channel();
         self.check_receiver = Some(receiver);
-        self.syntax_checker.check_async(code, sender);
+        self.syntax_checker.check_async(code, self.project.current_file.clone(), sender);
         self.ui_state.status_message = "正在检查语法...".to_string();
     }
     
@@ -2,6 +2,7 @@
 //!
 //! 集成cay-check功能，提供实时代码检查
 
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
 use std::thread;
 use crate::idle::editor::Diagnostic;
@@ -22,7 +23,9 @@ impl SyntaxChecker {
     }
     
     /// 异步检查代码
-    pub fn check_async(&mut self, code: String, sender: Sender<CheckResult>) {
+    ///
+    /// `source_path` 为编辑中的文件路径，用于解析 import 的源码根目录；未保存的新文件为 None。
+    pub fn check_async(&mut self, code: String, source_path: Option<PathBuf>, sender: Sender<CheckResult>) {
         if self.is_checking {
             return;
         }
@@ -30,14 +33,14 @@ impl SyntaxChecker {
         self.is_checking = true;
         
         thread::spawn(move || {
-            let result = Self::check_code(&code);
+            let result = Self::check_code(&code, source_path.as_deref());
             // 确保总是发送结果，即使发生错误
             let _ = sender.send(result);
         });
     }
     
     /// 同步检查代码（内部使用）
-    fn check_code(code: &str) -> CheckResult {
+    fn check_code(code: &str, source_path: Option<&Path>) -> CheckResult {
         // 创建临时文件
         let temp_dir = std::env::temp_dir();
         let temp_file = temp_dir.join(format!("cay_check_{}.cay", std::process::id()));
@@ -48,7 +51,7 @@ impl SyntaxChecker {
         }
         
         // 调用内部检查函数
-        let result = Self::check_file_internal(&temp_file);
+        let result = Self::check_file_internal(&temp_file, source_path.unwrap_or(&temp_file));
         
         // 清理临时文件
         let _ = std::fs::remove_file(&temp_file);
@@ -57,9 +60,9 @@ impl SyntaxChecker {
     }
     
     /// 内部检查实现（使用编译器API）
-    fn check_file_internal(path: &std::path::Path) -> CheckResult {
+    fn check_file_internal(path: &Path, source_path: &Path) -> CheckResult {
         use crate::lexer;
-        use crate::parser;
+        use crate::modules;
         use crate::semantic;
         
         // 读取文件
@@ -86,8 +89,9 @@ impl SyntaxChecker {
             }
         };
         
-        // 2. 语法分析
-        let ast = match parser::parse(tokens) {
+        // 2. 语法分析，并从编辑中文件所在的源码根目录加载导入的模块
+        let main_file = source_path.to_string_lossy();
+        let ast = match modules::parse_program(tokens, source.clone(), &main_file, modules::caylibs_paths()) {
             Ok(a) => a,
             Err(e) => {
                 let (line, col, msg) = Self::parse_error_message(&e.to_string());
@@ -178,7 +182,7 @@ pub fn quick_check(code: &str) -> Vec<Diagnostic> {
         }];
     }
     
-    let result = SyntaxChecker::check_file_internal(&temp_file);
+    let result = SyntaxChecker::check_file_internal(&temp_file, &temp_file);
     let _ = std::fs::remove_file(&temp_file);
     
     match result {
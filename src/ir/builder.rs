@@ -17,6 +17,7 @@ use crate::ast::*;
 use crate::types::{Type, TypeRegistry};
 use crate::error::cayResult;
 use std::collections::HashMap;
+use crate::diagnostic::ErrorCodes;
 
 /// 循环上下文（用于 break/continue）
 #[derive(Debug, Clone)]
@@ -267,7 +268,7 @@ impl IrBuilder {
     /// 在当前基本块中添加指令
     fn emit(&mut self, inst: IrInstruction) -> cayResult<()> {
         let block = self.current_block_mut()
-            .ok_or_else(|| crate::error::codegen_error("No current block".to_string()))?;
+            .ok_or_else(|| crate::error::codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "No current block".to_string()))?;
         block.push(inst);
         Ok(())
     }
@@ -275,7 +276,7 @@ impl IrBuilder {
     /// 设置当前块的终止指令
     fn set_terminator(&mut self, term: IrTerminator) -> cayResult<()> {
         let block = self.current_block_mut()
-            .ok_or_else(|| crate::error::codegen_error("No current block".to_string()))?;
+            .ok_or_else(|| crate::error::codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "No current block".to_string()))?;
         block.set_terminator(term);
         Ok(())
     }
@@ -283,7 +284,7 @@ impl IrBuilder {
     /// 创建新基本块并设置为当前块
     fn new_block(&mut self, label: String) -> cayResult<()> {
         let func = self.current_function.as_mut()
-            .ok_or_else(|| crate::error::codegen_error("No current function".to_string()))?;
+            .ok_or_else(|| crate::error::codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "No current function".to_string()))?;
         func.add_block(IrBasicBlock::new(label));
         Ok(())
     }
@@ -680,6 +681,15 @@ impl IrBuilder {
             Stmt::Break(label) => self.build_break(label)?,
             Stmt::Continue(label) => self.build_continue(label)?,
             Stmt::InlineIr(inline_ir) => self.build_inline_ir(inline_ir)?,
+            Stmt::Defer(_) | Stmt::TryWithResources(_) | Stmt::ForEach(_) => {
+                return Err(crate::error::codegen_error(
+                    ErrorCodes::CODEGEN_UNSUPPORTED_FEATURE,
+                    "IR builder does not support defer, try-with-resources or for-each statements".to_string()
+                ));
+            }
+            Stmt::Error(_) => {
+                return Err(crate::error::codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "Cannot build IR for a statement with syntax errors".to_string()));
+            }
         }
         Ok(())
     }
@@ -696,6 +706,7 @@ impl IrBuilder {
         
         if inline_ir.raw_lines.is_empty() {
             return Err(crate::error::cayError::CodeGen {
+                code: ErrorCodes::CODEGEN_INTERNAL_ERROR,
                 message: "Inline IR block has no lines".to_string(),
                 suggestion: "Check parser implementation".to_string(),
             });
@@ -714,6 +725,7 @@ impl IrBuilder {
         let raw_text = inline_ir.raw_lines.join("\n");
         let block = parser.parse(&raw_text, &inputs, &[])
             .map_err(|e| crate::error::cayError::CodeGen { 
+                code: ErrorCodes::CODEGEN_LLVM_ERROR,
                 message: format!("Inline IR error: {}", e),
                 suggestion: "Check your inline IR syntax".to_string(),
             })?;
@@ -973,9 +985,22 @@ impl IrBuilder {
         let end_label = self.new_label("switch.end");
         let default_label = self.new_label("switch.default");
         let mut case_labels: Vec<(i64, String)> = Vec::new();
+        let mut case_blocks: Vec<String> = Vec::new();
 
         for (i, case) in switch.cases.iter().enumerate() {
-            case_labels.push((case.value, self.new_label(&format!("switch.case{}", i))));
+            let label = self.new_label(&format!("switch.case{}", i));
+            case_blocks.push(label.clone());
+            for case_label in &case.labels {
+                let value = match case_label {
+                    CaseLabel::Int(v) => *v,
+                    CaseLabel::Char(c) => *c as i64,
+                    _ => return Err(crate::error::codegen_error(
+                        ErrorCodes::CODEGEN_UNSUPPORTED_FEATURE,
+                        "IR builder only supports integer and char case labels".to_string()
+                    )),
+                };
+                case_labels.push((value, label.clone()));
+            }
         }
 
         // 将 switch 结束标签压入栈，支持 break
@@ -1040,8 +1065,7 @@ impl IrBuilder {
         }
 
         // 各 case 块
-        for (i, case) in switch.cases.iter().enumerate() {
-            let label = &case_labels[i].1;
+        for (case, label) in switch.cases.iter().zip(&case_blocks) {
             self.new_block(label.clone())?;
             for stmt in &case.body {
                 self.build_statement(stmt)?;
@@ -1061,11 +1085,11 @@ impl IrBuilder {
             self.loop_stack.iter().rev()
                 .find(|ctx| ctx.label.as_deref() == Some(l.as_str()))
                 .map(|ctx| ctx.end_label.clone())
-                .ok_or_else(|| crate::error::codegen_error(format!("break label '{}' not found", l)))?
+                .ok_or_else(|| crate::error::codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("break label '{}' not found", l)))?
         } else {
             self.loop_stack.last()
                 .map(|ctx| ctx.end_label.clone())
-                .ok_or_else(|| crate::error::codegen_error("break outside loop".to_string()))?
+                .ok_or_else(|| crate::error::codegen_error(ErrorCodes::SEMANTIC_BREAK_OUTSIDE_LOOP, "break outside loop".to_string()))?
         };
         self.set_terminator(IrTerminator::Branch { target })?;
         Ok(())
@@ -1076,11 +1100,11 @@ impl IrBuilder {
             self.loop_stack.iter().rev()
                 .find(|ctx| ctx.label.as_deref() == Some(l.as_str()))
                 .map(|ctx| ctx.cond_label.clone())
-                .ok_or_else(|| crate::error::codegen_error(format!("continue label '{}' not found", l)))?
+                .ok_or_else(|| crate::error::codegen_error(ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND, format!("continue label '{}' not found", l)))?
         } else {
             self.loop_stack.last()
                 .map(|ctx| ctx.cond_label.clone())
-                .ok_or_else(|| crate::error::codegen_error("continue outside loop".to_string()))?
+                .ok_or_else(|| crate::error::codegen_error(ErrorCodes::SEMANTIC_CONTINUE_OUTSIDE_LOOP, "continue outside loop".to_string()))?
         };
         self.set_terminator(IrTerminator::Branch { target })?;
         Ok(())
@@ -1116,6 +1140,7 @@ impl IrBuilder {
             Expr::ArrayAccess(arr) => self.build_array_access(arr),
             Expr::ArrayInit(init) => self.build_array_init(init),
             _ => Err(crate::error::codegen_error(
+                ErrorCodes::CODEGEN_UNSUPPORTED_FEATURE,
                 format!("Expression type not yet implemented in IR builder")
             )),
         }
@@ -1318,6 +1343,7 @@ impl IrBuilder {
             }
             _ => {
                 return Err(crate::error::codegen_error(
+                    ErrorCodes::CODEGEN_UNSUPPORTED_FEATURE,
                     format!("Unary operator {:?} not yet implemented in IR builder", unary.op)
                 ));
             }
@@ -1345,6 +1371,7 @@ impl IrBuilder {
             }
             _ => {
                 Err(crate::error::codegen_error(
+                    ErrorCodes::CODEGEN_UNSUPPORTED_FEATURE,
                     "Complex assignment target not yet implemented in IR builder".to_string()
                 ))
             }
@@ -1392,7 +1419,7 @@ impl IrBuilder {
                 // obj.method() - 需要虚调用分派
                 format!("{}.{}", self.current_class, member.member)
             }
-            _ => return Err(crate::error::codegen_error("Complex callee not yet supported in IR builder".to_string())),
+            _ => return Err(crate::error::codegen_error(ErrorCodes::CODEGEN_UNSUPPORTED_FEATURE, "Complex callee not yet supported in IR builder".to_string())),
         };
 
         let mut args: Vec<IrValue> = Vec::new();
@@ -1474,6 +1501,7 @@ impl IrBuilder {
             }
             _ => {
                 return Err(crate::error::codegen_error(
+                    ErrorCodes::CODEGEN_UNSUPPORTED_FEATURE,
                     "Complex assignment target not yet implemented in IR builder".to_string()
                 ));
             }
@@ -1573,6 +1601,7 @@ impl IrBuilder {
 
         // 如果找不到字段，返回错误
         Err(crate::error::codegen_error(
+            ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND,
             format!("Field '{}' not found in class '{}'", member.member, class_name)
         ))
     }
@@ -1826,6 +1855,7 @@ impl IrBuilder {
             (_, IrType::Pointer(_)) if from.is_integer() => Ok(IrCastKind::IntToPtr),
 
             _ => Err(crate::error::codegen_error(
+                ErrorCodes::CODEGEN_TYPE_CONVERSION_ERROR,
                 format!("Cannot cast from {} to {}", from.to_llvm_str(), to.to_llvm_str())
             )),
         }
@@ -8,6 +8,7 @@ use super::function::{IrFunction, IrLinkage};
 use super::value::IrInstruction;
 use crate::error::cayResult;
 use std::collections::{HashMap, HashSet};
+use crate::diagnostic::ErrorCodes;
 
 /// 内联器配置
 #[derive(Debug, Clone)]
@@ -158,7 +159,7 @@ impl Inliner {
         // 克隆被调函数（因为需要借用 module）
         let callee = match module.find_function(callee_name) {
             Some(f) => f.clone(),
-            None => return Err(crate::error::codegen_error("Callee not found".to_string())),
+            None => return Err(crate::error::codegen_error(ErrorCodes::CODEGEN_INTERNAL_ERROR, "Callee not found".to_string())),
         };
 
         if callee.linkage == IrLinkage::Declare || callee.blocks.is_empty() {
@@ -150,6 +150,10 @@ impl From<&crate::types::Type> for IrType {
             Type::Void => IrType::Void,
             Type::Int32 => IrType::I32,
             Type::Int64 => IrType::I64,
+            Type::Int8 | Type::UInt8 => IrType::I8,
+            Type::Int16 | Type::UInt16 => IrType::I16,
+            Type::UInt32 => IrType::I32,
+            Type::UInt64 => IrType::I64,
             Type::Float32 => IrType::F32,
             Type::Float64 => IrType::F64,
             Type::Bool => IrType::I1,
@@ -178,6 +182,11 @@ impl From<&crate::types::Type> for IrType {
                 name: format!("struct.{}", name),
                 fields: Vec::new(),
             },
+            // 泛型在单态化后不会出现，未替换时按对象指针处理
+            Type::TypeParam(_) | Type::Generic(_, _) => IrType::Pointer(Box::new(IrType::I8)),
+            // 枚举值是指向带标签结构体的指针
+            Type::Enum(_) => IrType::Pointer(Box::new(IrType::I8)),
+            Type::Nullable(inner) => IrType::from(inner.as_ref()),
         }
     }
 }
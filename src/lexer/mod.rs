@@ -3,6 +3,18 @@ use crate::error::{cayResult, lexer_error};
 use crate::error::SourceLocation;
 use crate::diagnostic::{Diagnostic, DiagnosticCollector, ErrorCodes, CompilationPhase, SourceSpan, FixSuggestion};
 
+/// 整数字面量后缀
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntSuffix {
+    Long,    // 10L
+    Byte,    // 10y
+    UByte,   // 10uy
+    Short,   // 10s
+    UShort,  // 10us
+    UInt,    // 10u
+    ULong,   // 10ul
+}
+
 #[derive(Logos, Debug, Clone, PartialEq)]
 #[logos(skip r"[ \t\f]+")]
 #[logos(skip r"//.*")]
@@ -27,13 +39,21 @@ pub enum Token {
     Final,
     #[token("abstract")]
     Abstract,
+    #[token("sealed")]
+    Sealed,
+    #[token("permits")]
+    Permits,
     #[token("native")]
     Native,
-    // 注解 - 注意：@main 和 @Override 是完整的令牌，不是 @ + 标识符
+    #[token("const")]
+    Const,
+    // 注解 - 注意：@main 和 @Override 是完整的令牌，其他注解为 @ + 标识符
     #[token("@main")]
     AtMain,
     #[token("@Override")]
     AtOverride,
+    #[token("@")]
+    At,
     #[token("class")]
     Class,
     #[token("void")]
@@ -42,6 +62,18 @@ pub enum Token {
     Int,
     #[token("long")]
     Long,
+    #[token("byte")]
+    Byte,
+    #[token("ubyte")]
+    UByte,
+    #[token("short")]
+    Short,
+    #[token("ushort")]
+    UShort,
+    #[token("uint")]
+    UInt,
+    #[token("ulong")]
+    ULong,
     #[token("float")]
     Float,
     #[token("double")]
@@ -94,6 +126,14 @@ pub enum Token {
     Implements,
     #[token("interface")]
     Interface,
+    #[token("enum")]
+    Enum,
+    #[token("struct")]
+    Struct,
+    #[token("record")]
+    Record,
+    #[token("property")]
+    Property,
     #[token("instanceof")]
     InstanceOf,
     #[token("var")]
@@ -106,8 +146,16 @@ pub enum Token {
     Extern,
     #[token("scope")]
     Scope,
+    #[token("defer")]
+    Defer,
+    #[token("try")]
+    Try,
     #[token("__ir")]
     InlineIr,
+    #[token("package")]
+    Package,
+    #[token("import")]
+    Import,
 
     // FFI 类型关键字
     #[token("c_int")]
@@ -170,13 +218,22 @@ pub enum Token {
     Identifier(String),
     
     // 字面量
-    #[regex(r"(?:0[xX][0-9a-fA-F][0-9a-fA-F_]*|0[bB][01][01_]*|0[oO]?[0-7][0-7_]*|[0-9][0-9_]*)[Ll]?", |lex| {
+    #[regex(r"(?:0[xX][0-9a-fA-F][0-9a-fA-F_]*|0[bB][01][01_]*|0[oO]?[0-7][0-7_]*|[0-9][0-9_]*)(?:[Ll]|[Uu][Ll]?|[Uu]?[YySs])?", |lex| {
         let slice = lex.slice();
-        // 分离后缀
-        let (num_str, suffix) = if slice.ends_with('L') || slice.ends_with('l') {
-            (&slice[..slice.len()-1], Some(slice.chars().last().unwrap()))
-        } else {
-            (slice, None)
+        // 分离后缀：十六进制数字中没有 l/u/y/s，可以直接从末尾识别
+        let suffix_len = slice.chars().rev()
+            .take_while(|c| matches!(c, 'L' | 'l' | 'U' | 'u' | 'Y' | 'y' | 'S' | 's'))
+            .count();
+        let (num_str, suffix_str) = slice.split_at(slice.len() - suffix_len);
+        let suffix = match suffix_str.to_ascii_lowercase().as_str() {
+            "" => None,
+            "l" => Some(IntSuffix::Long),
+            "y" => Some(IntSuffix::Byte),
+            "uy" => Some(IntSuffix::UByte),
+            "s" => Some(IntSuffix::Short),
+            "us" => Some(IntSuffix::UShort),
+            "u" => Some(IntSuffix::UInt),
+            _ => Some(IntSuffix::ULong),
         };
         // 移除下划线
         let cleaned: String = num_str.chars().filter(|c| *c != '_').collect();
@@ -194,13 +251,17 @@ pub enum Token {
             10
         };
         let num = if radix == 10 {
-            cleaned.parse::<i64>().ok()
+            cleaned.parse::<u64>().ok()
         } else {
-            i64::from_str_radix(&cleaned[2..], radix).ok()
+            u64::from_str_radix(&cleaned[2..], radix).ok()
         };
-        num.map(|val| (val, suffix))
+        // ulong 字面量可以用满 64 位，按位模式保存在 i64 中
+        num.and_then(|val| match suffix {
+            Some(IntSuffix::ULong) => Some(val as i64),
+            _ => i64::try_from(val).ok(),
+        }).map(|val| (val, suffix))
     })]
-    IntegerLiteral(Option<(i64, Option<char>)>),
+    IntegerLiteral(Option<(i64, Option<IntSuffix>)>),
     
     #[regex(r"(?:[0-9][0-9_]*\.[0-9][0-9_]*|\.[0-9][0-9_]*|[0-9][0-9_]*\.)(?:[eE][+-]?[0-9][0-9_]*)?[FfDd]?", |lex| {
         let slice = lex.slice();
@@ -324,6 +385,10 @@ pub enum Token {
     Arrow,
     #[token("?")]
     Question,
+    #[token("?.")]
+    QuestionDot,
+    #[token("?:")]
+    Elvis,
 
     // 换行（用于跟踪行号）- 支持 Windows \r\n 和 Unix \n
     #[regex(r"\r?\n")]
@@ -664,6 +729,7 @@ impl<'a> Lexer<'a> {
                         // 立即返回错误（保持向后兼容）
                         if is_unterminated_string {
                             return Err(lexer_error(
+                                ErrorCodes::LEXER_UNTERMINATED_STRING,
                                 error_line,
                                 self.column,
                                 "未闭合的字符串字面量".to_string()
@@ -675,6 +741,7 @@ impl<'a> Lexer<'a> {
                             format!("Unexpected character: '{}' at line {}", error_char, error_line)
                         };
                         return Err(lexer_error(
+                            ErrorCodes::LEXER_INVALID_CHARACTER,
                             error_line,
                             self.column,
                             error_msg
@@ -686,7 +753,12 @@ impl<'a> Lexer<'a> {
 
         // 检查是否有收集到的错误
         if self.diagnostics.has_errors() {
+            // 使用第一个错误的代码
+            let code = self.diagnostics.diagnostics().first()
+                .and_then(|diagnostic| ErrorCodes::find(&diagnostic.code))
+                .unwrap_or(ErrorCodes::LEXER_INVALID_CHARACTER);
             return Err(lexer_error(
+                code,
                 self.line,
                 self.column,
                 format!("词法分析发现 {} 个错误", self.diagnostics.error_count())
@@ -764,6 +836,7 @@ impl<'a> Lexer<'a> {
                 };
 
                 Some(Err(lexer_error(
+                    ErrorCodes::LEXER_INVALID_CHARACTER,
                     error_line,
                     self.column,
                     error_msg
@@ -865,13 +938,23 @@ pub fn token_name(token: &Token) -> &'static str {
         Token::Static => "static",
         Token::Final => "final",
         Token::Abstract => "abstract",
+        Token::Sealed => "sealed",
+        Token::Permits => "permits",
         Token::Native => "native",
+        Token::Const => "const",
         Token::AtMain => "@main",
         Token::AtOverride => "@Override",
+        Token::At => "@",
         Token::Class => "class",
         Token::Void => "void",
         Token::Int => "int",
         Token::Long => "long",
+        Token::Byte => "byte",
+        Token::UByte => "ubyte",
+        Token::Short => "short",
+        Token::UShort => "ushort",
+        Token::UInt => "uint",
+        Token::ULong => "ulong",
         Token::Float => "float",
         Token::Double => "double",
         Token::Bool => "boolean",
@@ -897,13 +980,21 @@ pub fn token_name(token: &Token) -> &'static str {
         Token::Extends => "extends",
         Token::Implements => "implements",
         Token::Interface => "interface",
+        Token::Enum => "enum",
+        Token::Struct => "struct",
+        Token::Record => "record",
+        Token::Property => "property",
         Token::InstanceOf => "instanceof",
         Token::Var => "var",
         Token::Let => "let",
         Token::Auto => "auto",
         Token::Extern => "extern",
         Token::Scope => "scope",
+        Token::Defer => "defer",
+        Token::Try => "try",
         Token::InlineIr => "__ir",
+        Token::Package => "package",
+        Token::Import => "import",
         Token::CInt => "c_int",
         Token::CUInt => "c_uint",
         Token::CLong => "c_long",
@@ -977,6 +1068,8 @@ pub fn token_name(token: &Token) -> &'static str {
         Token::DoubleColon => "::",
         Token::Arrow => "->",
         Token::Question => "?",
+        Token::QuestionDot => "?.",
+        Token::Elvis => "?:",
         Token::Newline => "newline",
         Token::BlockComment(_) => "block comment",
     }
@@ -986,17 +1079,19 @@ pub fn token_name(token: &Token) -> &'static str {
 pub fn is_keyword(token: &Token) -> bool {
     matches!(token,
         Token::Public | Token::Private | Token::Protected |
-        Token::Static | Token::Final | Token::Abstract | Token::Native |
+        Token::Static | Token::Final | Token::Abstract | Token::Sealed | Token::Permits | Token::Native | Token::Const |
         Token::Class | Token::Void | Token::Int | Token::Long |
+        Token::Byte | Token::UByte | Token::Short | Token::UShort | Token::UInt | Token::ULong |
         Token::Float | Token::Double | Token::Bool | Token::String |
         Token::Char | Token::True | Token::False | Token::Null |
         Token::If | Token::Else | Token::While | Token::For |
         Token::Do | Token::Switch | Token::Case | Token::Default |
         Token::Return | Token::Break | Token::Continue |
         Token::New | Token::This | Token::Super |
-        Token::Extends | Token::Implements | Token::Interface | Token::InstanceOf |
+        Token::Extends | Token::Implements | Token::Interface | Token::Enum | Token::Struct | Token::Record | Token::Property | Token::InstanceOf |
         Token::Var | Token::Let | Token::Auto | Token::Extern | Token::Scope |
-        Token::InlineIr | Token::Alias | Token::Fn
+        Token::Defer | Token::Try | Token::InlineIr | Token::Alias | Token::Fn |
+        Token::Package | Token::Import
     )
 }
 
@@ -1004,7 +1099,7 @@ pub fn is_keyword(token: &Token) -> bool {
 pub fn keyword_priority(token: &Token) -> u8 {
     match token {
         Token::If | Token::Else | Token::While | Token::For | Token::Return => 10,
-        Token::Class | Token::Interface | Token::Extends | Token::Implements => 9,
+        Token::Class | Token::Interface | Token::Enum | Token::Struct | Token::Record | Token::Extends | Token::Implements => 9,
         Token::Public | Token::Private | Token::Protected | Token::Static | Token::Final => 8,
         Token::Int | Token::Long | Token::Float | Token::Double | Token::Bool | Token::String | Token::Void => 7,
         Token::New | Token::This | Token::Super => 6,
@@ -1119,7 +1214,7 @@ mod tests {
 
     #[test]
     fn test_invalid_character() {
-        let source = r#"int x = 42 @;"#;
+        let source = r#"int x = 42 `;"#;
         let result = tokenize(source);
         assert!(result.is_err());
     }
@@ -1182,6 +1277,15 @@ mod tests {
         assert!(matches!(tokens[1].token, Token::AtOverride));
     }
 
+    #[test]
+    fn test_user_annotations() {
+        let source = r#"@Deprecated("old")"#;
+        let tokens = tokenize(source).unwrap();
+        assert!(matches!(tokens[0].token, Token::At));
+        assert!(matches!(&tokens[1].token, Token::Identifier(name) if name == "Deprecated"));
+        assert!(matches!(tokens[2].token, Token::LParen));
+    }
+
     #[test]
     fn test_inline_ir_token() {
         let source = r#"__ir"#;
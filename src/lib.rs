@@ -1,9 +1,12 @@
 pub mod error;
 pub mod diagnostic;
+pub mod error_format;
+pub mod explain;
 pub mod miette_diagnostic;
 pub mod types;
 pub mod ast;
 pub mod preprocessor;
+pub mod modules;
 pub mod lexer;
 pub mod parser;
 pub mod semantic;
@@ -11,6 +14,7 @@ pub mod codegen;
 pub mod ir;
 pub mod rcpl;
 pub mod bytecode;
+pub mod lint;
 
 // GUI模块（cay-idle使用）
 pub mod idle;
@@ -20,6 +24,7 @@ pub mod cavly;
 
 use std::path::{Path, PathBuf};
 use error::cayResult;
+use diagnostic::DiagnosticCollector;
 
 /// 编译器配置选项
 #[derive(Debug, Clone)]
@@ -75,14 +80,14 @@ impl Compiler {
         // 1. 词法分析
         let tokens = lexer::lex(source)?;
 
-        // 调试：打印所有token
+        // 调试：打印所有token（写到标准错误，标准输出留给 json/sarif 诊断文档）
         #[cfg(debug_assertions)]
         {
-            println!("Tokens:");
+            eprintln!("Tokens:");
             for (i, t) in tokens.iter().enumerate() {
-                println!("  {}: {:?} at {}", i, t.token, t.loc);
+                eprintln!("  {}: {:?} at {}", i, t.token, t.loc);
             }
-            println!();
+            eprintln!();
         }
 
         // 2. 语法分析（传入源代码以支持内联IR解析）
@@ -93,6 +98,7 @@ impl Compiler {
         // 3. 语义分析
         let mut analyzer = semantic::SemanticAnalyzer::with_features(self.options.features.clone());
         analyzer.analyze(&ast)?;
+        report_warnings(&analyzer.warning_diagnostics(), "<source>");
 
         // 4. 代码生成 - 生成LLVM IR（字符串常量已在生成器内处理）
         let mut ir_gen = codegen::IRGenerator::new();
@@ -141,49 +147,95 @@ impl Compiler {
     /// # Returns
     /// 编译成功返回 Ok(())
     pub fn compile_with_source_map_and_main_file(&self, source: &str, source_map: std::collections::HashMap<usize, (String, usize)>, output_path: &str, main_file: Option<String>) -> cayResult<()> {
-        // 保留一份源映射用于语义分析错误定位
-        let source_map_for_analyzer = source_map.clone();
+        // 1. 词法分析（带源映射）
+        let tokens = Self::lex_with_source_map(source, source_map.clone())?;
+
+        // 2. 语法分析，并加载主文件导入的模块（传入源代码以支持内联IR解析）
+        let main_path = main_file.as_deref().unwrap_or("main.cay");
+        let ast = modules::parse_program(tokens, source.to_string(), main_path, self.system_include_paths())?;
+
+        // 3. 语义分析（所有语义错误合并为一个错误返回）
+        let mut analyzer = self.analyzer(&main_file, &source_map);
+        analyzer.analyze(&ast)?;
+        report_warnings(&analyzer.warning_diagnostics(), main_path);
 
+        // 4. 代码生成
+        self.generate_ir_file(&ast, &analyzer, source_map, main_file.as_deref(), output_path)
+    }
+
+    /// 使用源映射编译，错误和警告逐个收集到 `diagnostics` 中而不是直接打印
+    ///
+    /// 参数与 [`Compiler::compile_with_source_map_and_main_file`] 相同。与 cay-check 一样，
+    /// 语法错误后继续解析，每个语义错误单独报告。编译失败时返回第一个错误，
+    /// 返回的错误已经加入 `diagnostics`。
+    pub fn compile_with_diagnostics(&self, source: &str, source_map: std::collections::HashMap<usize, (String, usize)>, output_path: &str, main_file: Option<String>, diagnostics: &mut DiagnosticCollector) -> cayResult<()> {
         // 1. 词法分析（带源映射）
+        let tokens = Self::lex_with_source_map(source, source_map.clone())
+            .map_err(|e| record_error(diagnostics, e))?;
+
+        // 2. 语法分析，报告所有语法错误；有语法错误时不做语义分析
+        let main_path = main_file.as_deref().unwrap_or("main.cay");
+        let ast = match modules::parse_program_recovering(tokens, source.to_string(), main_path, self.system_include_paths(), diagnostics) {
+            Some(ast) if !diagnostics.has_errors() => ast,
+            _ => return Err(first_error(diagnostics)),
+        };
+
+        // 3. 语义分析，每个错误和警告分别加入 diagnostics
+        let mut analyzer = self.analyzer(&main_file, &source_map);
+        analyzer.analyze_collecting(&ast, diagnostics);
+        if diagnostics.has_errors() {
+            return Err(first_error(diagnostics));
+        }
+
+        // 4. 代码生成
+        self.generate_ir_file(&ast, &analyzer, source_map, main_file.as_deref(), output_path)
+            .map_err(|e| record_error(diagnostics, e))
+    }
+
+    /// 词法分析（带源映射）
+    fn lex_with_source_map(source: &str, source_map: std::collections::HashMap<usize, (String, usize)>) -> cayResult<Vec<lexer::TokenWithLocation>> {
         let tokens = lexer::lex_with_source_map(source, source_map)?;
 
-        // 调试：打印所有token
+        // 调试：打印所有token（写到标准错误，标准输出留给 json/sarif 诊断文档）
         #[cfg(debug_assertions)]
         {
-            println!("Tokens:");
+            eprintln!("Tokens:");
             for (i, t) in tokens.iter().enumerate() {
                 if let Some(ref file) = t.source_file {
-                    println!("  {}: {:?} at {}:{} (original: {})", i, t.token, file, t.source_line.unwrap_or(t.loc.line), t.loc);
+                    eprintln!("  {}: {:?} at {}:{} (original: {})", i, t.token, file, t.source_line.unwrap_or(t.loc.line), t.loc);
                 } else {
-                    println!("  {}: {:?} at {}", i, t.token, t.loc);
+                    eprintln!("  {}: {:?} at {}", i, t.token, t.loc);
                 }
             }
-            println!();
+            eprintln!();
         }
 
-        // 2. 语法分析（传入源代码以支持内联IR解析）
-        let ast = parser::parse_with_source(tokens, source.to_string())?;
+        Ok(tokens)
+    }
 
-        // 3. 语义分析
+    /// 创建语义分析器
+    fn analyzer(&self, main_file: &Option<String>, source_map: &std::collections::HashMap<usize, (String, usize)>) -> semantic::SemanticAnalyzer {
         let mut analyzer = semantic::SemanticAnalyzer::with_features(self.options.features.clone());
         analyzer.set_current_file(main_file.clone());
         // 传递源映射表以支持多文件include场景下的正确错误定位
-        analyzer.set_source_map(source_map_for_analyzer.clone());
-        analyzer.analyze(&ast)?;
+        analyzer.set_source_map(source_map.clone());
+        analyzer
+    }
 
-        // 4. 代码生成 - 生成LLVM IR（字符串常量已在生成器内处理）
+    /// 生成 LLVM IR 并写入输出文件
+    fn generate_ir_file(&self, ast: &ast::Program, analyzer: &semantic::SemanticAnalyzer, source_map: std::collections::HashMap<usize, (String, usize)>, main_file: Option<&str>, output_path: &str) -> cayResult<()> {
+        // 生成LLVM IR（字符串常量已在生成器内处理）
         let mut ir_gen = codegen::IRGenerator::new();
         // 传递多平台配置
         ir_gen.set_platform_config(&self.options);
         // 传递类型注册表以支持正确的方法名生成
         ir_gen.set_type_registry(analyzer.get_type_registry().clone());
         // 设置预处理器源映射（用于多文件include场景）
-        ir_gen.set_preprocessor_source_map(source_map_for_analyzer.clone());
+        ir_gen.set_preprocessor_source_map(source_map);
         // 设置源文件路径以启用源映射
-        let source_file = main_file.as_deref().unwrap_or("");
-        let mut ir = ir_gen.generate(&ast, source_file)?;
+        let mut ir = ir_gen.generate(ast, main_file.unwrap_or(""))?;
 
-        // 5. 如果启用了混淆，应用IR混淆
+        // 如果启用了混淆，应用IR混淆
         if self.options.obfuscate {
             use codegen::obfuscator::IRObfuscator;
             let mut obfuscator = IRObfuscator::new();
@@ -206,6 +258,23 @@ impl Compiler {
     /// # Returns
     /// 编译成功返回 Ok(())
     pub fn compile_file(&self, input_path: &str, output_path: &str) -> cayResult<()> {
+        let result = self.preprocess_file(input_path)?;
+        let source_map = Self::convert_source_map(&result.source_map);
+
+        // 编译预处理后的代码（带源映射和主文件路径）
+        self.compile_with_source_map_and_main_file(&result.code, source_map, output_path, Some(input_path.to_string()))
+    }
+
+    /// 从文件编译，错误和警告收集到 `diagnostics` 中而不是直接打印（见 [`Compiler::compile_with_diagnostics`]）
+    pub fn compile_file_with_diagnostics(&self, input_path: &str, output_path: &str, diagnostics: &mut DiagnosticCollector) -> cayResult<()> {
+        let result = self.preprocess_file(input_path)
+            .map_err(|e| record_error(diagnostics, e))?;
+        let source_map = Self::convert_source_map(&result.source_map);
+        self.compile_with_diagnostics(&result.code, source_map, output_path, Some(input_path.to_string()), diagnostics)
+    }
+
+    /// 读取并预处理源文件（带源映射）
+    fn preprocess_file(&self, input_path: &str) -> cayResult<preprocessor::PreprocessResult> {
         // 读取源文件
         let source = std::fs::read_to_string(input_path)
             .map_err(|e| error::cayError::Io(
@@ -218,24 +287,20 @@ impl Compiler {
             .map(|p| p.to_path_buf())
             .unwrap_or_else(|| PathBuf::from("."));
 
-        // 构建系统包含路径列表（包含 caylibs 目录）
-        let mut system_paths = Vec::new();
+        let system_paths = self.system_include_paths();
 
-        // 尝试获取可执行文件所在目录，并添加 caylibs 子目录
-        if let Ok(exe_path) = std::env::current_exe() {
-            if let Some(exe_dir) = exe_path.parent() {
-                let caylibs_dir = exe_dir.join("caylibs");
-                if caylibs_dir.exists() {
-                    system_paths.push(caylibs_dir);
-                }
-            }
-        }
+        // 使用带系统路径的预处理器（带源映射）
+        let mut pp = if system_paths.is_empty() {
+            preprocessor::Preprocessor::new(base_dir)
+        } else {
+            preprocessor::Preprocessor::with_include_paths(base_dir, system_paths)
+        };
+        pp.process_with_source_map(&source, input_path)
+    }
 
-        // 也尝试从当前工作目录添加 caylibs
-        let cwd_caylibs = PathBuf::from("caylibs");
-        if cwd_caylibs.exists() && !system_paths.contains(&cwd_caylibs) {
-            system_paths.push(cwd_caylibs);
-        }
+    /// 系统包含路径（caylibs 目录和 -I 参数），同时作为 import 的源码根目录
+    fn system_include_paths(&self) -> Vec<PathBuf> {
+        let mut system_paths = modules::caylibs_paths();
 
         // 添加 CompilerOptions 中指定的额外包含路径（-I 参数）
         for path in &self.options.include_paths {
@@ -245,30 +310,13 @@ impl Compiler {
             }
         }
 
-        // 使用带系统路径的预处理器（带源映射）
-
-        let mut pp = if system_paths.is_empty() {
-            preprocessor::Preprocessor::new(base_dir)
-        } else {
-            preprocessor::Preprocessor::with_include_paths(base_dir, system_paths)
-        };
-        let result = pp.process_with_source_map(&source, input_path)?;
-        let source_map = Self::convert_source_map(&result.source_map);
-
-        // 编译预处理后的代码（带源映射和主文件路径）
-        let main_file = Some(input_path.to_string());
-        self.compile_with_source_map_and_main_file(&result.code, source_map, output_path, main_file)
+        system_paths
     }
 
     /// 将预处理器源映射转换为HashMap格式
     fn convert_source_map(source_map: &preprocessor::SourceMap) -> std::collections::HashMap<usize, (String, usize)> {
-        let mut map = std::collections::HashMap::new();
-        for (idx, pos) in source_map.mappings.iter().enumerate() {
-            // idx + 1 是 1-based 的输出行号
-            // pos.line 已经是 1-based（预处理器使用 line_number + 1）
-            map.insert(idx + 1, (pos.file.clone(), pos.line));
-        }
-        map
+        // pos.line 已经是 1-based（预处理器使用 line_number + 1）
+        source_map.to_line_map()
     }
 }
 
@@ -278,6 +326,34 @@ impl Default for Compiler {
     }
 }
 
+/// 打印语义分析产生的警告，不影响编译结果
+fn report_warnings(warnings: &[diagnostic::Diagnostic], default_file: &str) {
+    for warning in warnings {
+        let file = warning.file.as_deref().unwrap_or(default_file);
+        error::print_warning_with_location(&warning.message, file, warning.location.line, warning.location.column);
+    }
+}
+
+/// 把中止编译的错误加入 `diagnostics`，再原样返回
+fn record_error(diagnostics: &mut DiagnosticCollector, error: error::cayError) -> error::cayError {
+    diagnostics.add(diagnostic::Diagnostic::from_error(&error));
+    error
+}
+
+/// `diagnostics` 中收集到的第一个错误，作为编译失败时的返回值
+fn first_error(diagnostics: &DiagnosticCollector) -> error::cayError {
+    let first = diagnostics.diagnostics().iter()
+        .find(|d| matches!(d.severity, diagnostic::Severity::Error | diagnostic::Severity::Fatal))
+        .expect("has_errors() implies at least one error diagnostic");
+    let code = diagnostic::ErrorCodes::find(&first.code).unwrap_or(diagnostic::ErrorCodes::CODEGEN_INTERNAL_ERROR);
+    let (file, line, column, message) = (first.file.clone(), first.location.line, first.location.column, first.message.clone());
+    match first.phase {
+        diagnostic::CompilationPhase::Lexer => error::lexer_error_with_file(code, file, line, column, message),
+        diagnostic::CompilationPhase::Parser => error::parser_error_with_file(code, file, line, column, message),
+        _ => error::semantic_error_with_file(code, file, line, column, message),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
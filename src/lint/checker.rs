@@ -0,0 +1,666 @@
+//! 检查规则的实现：遍历每个方法体，跟踪局部变量的读取、遮蔽和内存分配，
+//! 同时收集整个程序中引用过的成员名，最后据此报告未使用的 private 成员。
+
+use std::collections::HashSet;
+use crate::ast::*;
+use crate::error::SourceLocation;
+use crate::types::{ParameterInfo, Type, TypeRegistry};
+use super::LintRule;
+
+/// 检查结果: (规则名, 位置, 消息)
+pub(super) type Finding = (&'static str, SourceLocation, String);
+
+/// 方法体中的局部变量（含参数）
+struct Local {
+    name: String,
+    var_type: Type,
+    loc: SourceLocation,
+    is_param: bool,
+    /// 从未读取时是否报告（模式变量、资源变量和 Lambda 参数不报告）
+    report_unused: bool,
+    read: bool,
+    /// 初始值来自 __cay_alloc，且还没有释放或转移出作用域
+    unreleased_alloc: bool,
+}
+
+pub(super) struct Checker<'a> {
+    registry: &'a TypeRegistry,
+    class_name: Option<String>,
+    locals: Vec<Local>,
+    /// 每层作用域中声明的局部变量
+    scopes: Vec<Vec<usize>>,
+    /// 程序中以名称引用过的字段和方法（赋值目标不算）
+    used_members: HashSet<String>,
+    findings: Vec<Finding>,
+}
+
+impl<'a> Checker<'a> {
+    pub(super) fn new(registry: &'a TypeRegistry) -> Self {
+        // 重载运算符和属性访问在语义分析中解析为方法调用，源码中不出现方法名
+        let used_members = registry.operator_calls.values().map(|call| call.method.name.clone())
+            .chain(registry.property_reads.values().map(|call| call.method.name.clone()))
+            .chain(registry.property_writes.values().map(|call| call.method.name.clone()))
+            .collect();
+        Self {
+            registry,
+            class_name: None,
+            locals: Vec::new(),
+            scopes: Vec::new(),
+            used_members,
+            findings: Vec::new(),
+        }
+    }
+
+    pub(super) fn check_program(mut self, program: &Program) -> Vec<Finding> {
+        for class in &program.classes {
+            self.class_name = Some(class.name.clone());
+            for member in &class.members {
+                self.check_member(class, member);
+            }
+        }
+        for interface in &program.interfaces {
+            self.class_name = Some(interface.name.clone());
+            for method in &interface.methods {
+                if let Some(body) = &method.body {
+                    self.check_body(&method.params, body, None, &method.loc);
+                }
+            }
+        }
+        self.class_name = None;
+        for func in &program.top_level_functions {
+            self.check_body(&func.params, &func.body, Some(&func.name), &func.loc);
+        }
+
+        for class in &program.classes {
+            self.check_private_members(class);
+        }
+        self.findings
+    }
+
+    fn check_member(&mut self, class: &ClassDecl, member: &ClassMember) {
+        match member {
+            ClassMember::Method(method) => {
+                if let Some(body) = &method.body {
+                    let report_params = self.reports_unused_params(class, method).then_some(method.name.as_str());
+                    self.check_body(&method.params, body, report_params, &method.loc);
+                }
+            }
+            ClassMember::Field(field) => {
+                if let Some(initializer) = &field.initializer {
+                    self.check_initializer(initializer);
+                }
+            }
+            ClassMember::Constructor(ctor) => {
+                self.enter_body(&ctor.params, true, &ctor.loc);
+                match &ctor.constructor_call {
+                    Some(ConstructorCall::This(args)) | Some(ConstructorCall::Super(args)) => {
+                        for arg in args {
+                            self.check_expr(arg);
+                        }
+                    }
+                    None => {}
+                }
+                self.check_statements(&ctor.body.statements);
+                self.finish_body(&class.name);
+            }
+            ClassMember::Destructor(dtor) => self.check_body(&[], &dtor.body, None, &dtor.loc),
+            ClassMember::InstanceInitializer(block) | ClassMember::StaticInitializer(block) => {
+                self.check_body(&[], block, None, &block.loc);
+            }
+            // 属性的访问方法和嵌套类在解析时已展开为普通成员和顶层类
+            ClassMember::Property(property) => {
+                if let Some(initializer) = &property.initializer {
+                    self.check_initializer(initializer);
+                }
+            }
+            ClassMember::Class(_) => {}
+        }
+    }
+
+    /// 是否报告方法未使用的参数：重写方法、实现接口的方法、main 和 FFI 导出函数的签名不能随意修改
+    fn reports_unused_params(&self, class: &ClassDecl, method: &MethodDecl) -> bool {
+        if method.name == "main"
+            || method.modifiers.iter().any(|m| matches!(m, Modifier::Override | Modifier::Abstract | Modifier::Native))
+            || method.has_annotation(Annotation::EXPORT) {
+            return false;
+        }
+        !self.overrides(class.parent.as_deref(), &class.interfaces, &method.name)
+    }
+
+    /// 父类或实现的接口中是否有同名方法
+    fn overrides(&self, parent: Option<&str>, interfaces: &[String], name: &str) -> bool {
+        let in_interfaces = |interfaces: &[String]| interfaces.iter()
+            .any(|interface| self.registry.get_interface_method(interface, name).is_some());
+        if in_interfaces(interfaces) {
+            return true;
+        }
+        let mut current = parent.and_then(|p| self.registry.get_class(p));
+        while let Some(info) = current {
+            if info.methods.contains_key(name) || in_interfaces(&info.interfaces) {
+                return true;
+            }
+            current = info.parent.as_deref().and_then(|p| self.registry.get_class(p));
+        }
+        false
+    }
+
+    /// 报告从未引用的 private 字段和方法
+    fn check_private_members(&mut self, class: &ClassDecl) {
+        // 属性展开出的访问方法由 `obj.name` 调用，源码中不出现方法名
+        let accessors: HashSet<String> = class.members.iter()
+            .filter_map(|member| match member {
+                ClassMember::Property(property) => Some([property.accessor_name("get"), property.accessor_name("set")]),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+
+        for member in &class.members {
+            match member {
+                ClassMember::Field(field) if field.modifiers.contains(&Modifier::Private)
+                    && !self.used_members.contains(&field.name) => {
+                    let message = format!("Private field '{}.{}' is never read", class.name, field.name);
+                    self.report(LintRule::UNUSED_PRIVATE_FIELD, &field.loc, message);
+                }
+                ClassMember::Method(method) if method.modifiers.contains(&Modifier::Private)
+                    && !self.used_members.contains(&method.name)
+                    && !accessors.contains(&method.name)
+                    && !method.has_annotation(Annotation::TEST)
+                    && !method.has_annotation(Annotation::EXPORT) => {
+                    let message = format!("Private method '{}.{}' is never used", class.name, method.name);
+                    self.report(LintRule::UNUSED_PRIVATE_METHOD, &method.loc, message);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn report(&mut self, rule: &'static str, loc: &SourceLocation, message: String) {
+        self.findings.push((rule, loc.clone(), message));
+    }
+
+    // ==================== 方法体和局部变量 ====================
+
+    /// 检查一个方法体；`report_params` 为需要报告未使用参数的方法名
+    fn check_body(&mut self, params: &[ParameterInfo], body: &Block, report_params: Option<&str>, loc: &SourceLocation) {
+        self.enter_body(params, report_params.is_some(), loc);
+        self.check_statements(&body.statements);
+        self.finish_body(report_params.unwrap_or(""));
+    }
+
+    /// 字段和属性的初始值：没有局部变量
+    fn check_initializer(&mut self, initializer: &Expr) {
+        self.locals.clear();
+        self.scopes = vec![Vec::new()];
+        self.check_expr(initializer);
+        self.exit_scope();
+    }
+
+    fn enter_body(&mut self, params: &[ParameterInfo], report_params: bool, loc: &SourceLocation) {
+        self.locals.clear();
+        self.scopes = vec![Vec::new()];
+        for param in params {
+            let id = self.declare(&param.name, param.param_type.clone(), loc, false, report_params);
+            self.locals[id].is_param = true;
+        }
+    }
+
+    /// 离开方法体，按声明顺序报告未读取的局部变量和参数
+    fn finish_body(&mut self, method_name: &str) {
+        self.exit_scope();
+        let mut unused = Vec::new();
+        for local in &self.locals {
+            if local.read || !local.report_unused || local.name.starts_with('_') {
+                continue;
+            }
+            if local.is_param {
+                let message = format!("Parameter '{}' of '{}' is never used", local.name, method_name);
+                unused.push((LintRule::UNUSED_PARAMETER, local.loc.clone(), message));
+            } else {
+                let message = format!("Variable '{}' is never used", local.name);
+                unused.push((LintRule::UNUSED_VARIABLE, local.loc.clone(), message));
+            }
+        }
+        self.findings.extend(unused);
+    }
+
+    /// 声明局部变量；`check_shadow` 为 false 时不检查遮蔽（参数和模式变量）
+    fn declare(&mut self, name: &str, var_type: Type, loc: &SourceLocation, check_shadow: bool, report_unused: bool) -> usize {
+        if let Some(outer) = self.lookup(name).filter(|_| check_shadow) {
+            let message = format!("Variable '{}' shadows a variable declared at line {}", name, self.locals[outer].loc.line);
+            self.report(LintRule::SHADOWED_VARIABLE, loc, message);
+        }
+        let id = self.locals.len();
+        self.locals.push(Local {
+            name: name.to_string(),
+            var_type,
+            loc: loc.clone(),
+            is_param: false,
+            report_unused,
+            read: false,
+            unreleased_alloc: false,
+        });
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(id);
+        }
+        id
+    }
+
+    fn lookup(&self, name: &str) -> Option<usize> {
+        self.scopes.iter().rev()
+            .flat_map(|scope| scope.iter().rev())
+            .copied()
+            .find(|id| self.locals[*id].name == name)
+    }
+
+    fn with_scope(&mut self, check: impl FnOnce(&mut Self)) {
+        self.scopes.push(Vec::new());
+        check(self);
+        self.exit_scope();
+    }
+
+    /// 离开作用域，报告其中没有释放的分配
+    fn exit_scope(&mut self) {
+        let scope = self.scopes.pop().unwrap_or_default();
+        for id in scope {
+            if self.locals[id].unreleased_alloc {
+                let message = format!("Memory allocated to '{}' is not freed in this scope", self.locals[id].name);
+                let loc = self.locals[id].loc.clone();
+                self.report(LintRule::UNFREED_ALLOC, &loc, message);
+            }
+        }
+    }
+
+    /// 读取名称：局部变量标记为已读，否则记为引用的成员
+    fn read(&mut self, name: &str) {
+        match self.lookup(name) {
+            Some(id) => self.locals[id].read = true,
+            None => {
+                self.used_members.insert(name.to_string());
+            }
+        }
+    }
+
+    /// 分配的内存被释放或转移出作用域（返回、赋值给其他变量）
+    fn release(&mut self, expr: &Expr) {
+        let released = match strip_casts(expr) {
+            Expr::Identifier(ident) => self.lookup(&ident.name),
+            _ => None,
+        };
+        if let Some(id) = released {
+            self.locals[id].unreleased_alloc = false;
+        }
+    }
+
+    // ==================== 语句 ====================
+
+    fn check_statements(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            self.check_statement(stmt);
+        }
+    }
+
+    fn check_block(&mut self, block: &Block) {
+        self.with_scope(|this| this.check_statements(&block.statements));
+    }
+
+    /// 检查分支或循环体，它们有自己的作用域
+    fn check_nested(&mut self, stmt: &Stmt) {
+        self.with_scope(|this| this.check_statement(stmt));
+    }
+
+    fn check_statement(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Expr(expr) => self.check_expr(expr),
+            Stmt::VarDecl(var) => self.check_var_decl(var, true),
+            Stmt::Return(value) => {
+                if let Some(value) = value {
+                    self.check_expr(value);
+                    self.release(value);
+                }
+            }
+            Stmt::If(if_stmt) => {
+                self.check_expr(&if_stmt.condition);
+                if is_empty_block(&if_stmt.then_branch) {
+                    self.report(LintRule::EMPTY_BLOCK, &if_stmt.loc, "Empty if branch".to_string());
+                }
+                self.check_nested(&if_stmt.then_branch);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    match else_branch.as_ref() {
+                        Stmt::Block(block) if block.statements.is_empty() => {
+                            self.report(LintRule::EMPTY_BLOCK, &block.loc, "Empty else branch".to_string());
+                        }
+                        _ => {}
+                    }
+                    self.check_nested(else_branch);
+                }
+            }
+            Stmt::While(while_stmt) => {
+                self.check_expr(&while_stmt.condition);
+                self.check_nested(&while_stmt.body);
+            }
+            Stmt::DoWhile(do_while) => {
+                self.check_nested(&do_while.body);
+                self.check_expr(&do_while.condition);
+            }
+            Stmt::For(for_stmt) => self.with_scope(|this| {
+                if let Some(init) = &for_stmt.init {
+                    this.check_statement(init);
+                }
+                if let Some(condition) = &for_stmt.condition {
+                    this.check_expr(condition);
+                }
+                if let Some(update) = &for_stmt.update {
+                    this.check_expr(update);
+                }
+                this.check_nested(&for_stmt.body);
+            }),
+            Stmt::ForEach(for_each) => {
+                self.check_expr(&for_each.iterable);
+                if for_each.range_args().is_none() {
+                    for method in ["iterator", "hasNext", "next"] {
+                        self.used_members.insert(method.to_string());
+                    }
+                }
+                self.with_scope(|this| {
+                    this.declare(&for_each.var_name, for_each.var_type.clone(), &for_each.loc, true, true);
+                    this.check_nested(&for_each.body);
+                });
+            }
+            Stmt::Switch(switch_stmt) => self.check_switch(switch_stmt),
+            Stmt::Block(block) => self.check_block(block),
+            Stmt::Scope(scope) => self.check_block(&scope.body),
+            Stmt::Defer(defer) => self.check_statement(&defer.body),
+            Stmt::TryWithResources(try_stmt) => {
+                if try_stmt.body.statements.is_empty() {
+                    self.report(LintRule::EMPTY_BLOCK, &try_stmt.loc, "Empty try body".to_string());
+                }
+                self.used_members.insert("close".to_string());
+                self.with_scope(|this| {
+                    for resource in &try_stmt.resources {
+                        this.check_var_decl(resource, false);
+                    }
+                    this.check_block(&try_stmt.body);
+                });
+            }
+            // 内联 IR 可能读取或释放任何局部变量
+            Stmt::InlineIr(_) => {
+                for local in &mut self.locals {
+                    local.read = true;
+                    local.unreleased_alloc = false;
+                }
+            }
+            Stmt::Break(_) | Stmt::Continue(_) | Stmt::Error(_) => {}
+        }
+    }
+
+    fn check_var_decl(&mut self, var: &VarDecl, report_unused: bool) {
+        if let Some(initializer) = &var.initializer {
+            self.check_expr(initializer);
+            self.release(initializer);
+        }
+        let var_type = match (&var.var_type, &var.initializer) {
+            (Type::Auto, Some(initializer)) if self.is_string(initializer) => Type::String,
+            (var_type, _) => var_type.clone(),
+        };
+        let id = self.declare(&var.name, var_type, &var.loc, true, report_unused);
+        self.locals[id].unreleased_alloc = var.initializer.as_ref().is_some_and(is_alloc);
+    }
+
+    fn check_switch(&mut self, switch_stmt: &SwitchStmt) {
+        self.check_expr(&switch_stmt.expr);
+        // 枚举和 sealed 类型上的 switch 由语义分析检查是否覆盖所有情况
+        let exhaustive = self.registry.enum_switches.contains_key(&switch_stmt.loc)
+            || self.registry.type_switches.contains_key(&switch_stmt.loc);
+        if switch_stmt.default.is_none() && !exhaustive {
+            self.report(LintRule::MISSING_DEFAULT, &switch_stmt.loc, "switch statement has no default branch".to_string());
+        }
+        for case in &switch_stmt.cases {
+            self.with_scope(|this| {
+                this.check_case_labels(&case.labels);
+                if let Some(guard) = &case.guard {
+                    this.check_expr(guard);
+                }
+                this.check_statements(&case.body);
+            });
+        }
+        if let Some(default) = &switch_stmt.default {
+            self.with_scope(|this| this.check_statements(default));
+        }
+    }
+
+    /// case 标签中的常量名记为引用，模式变量在分支中声明
+    fn check_case_labels(&mut self, labels: &[CaseLabel]) {
+        for label in labels {
+            match label {
+                CaseLabel::Pattern(pattern) => {
+                    self.used_members.insert(pattern.variant.clone());
+                    for binding in &pattern.bindings {
+                        self.declare(binding, Type::Auto, &pattern.loc, false, false);
+                    }
+                }
+                CaseLabel::Type(pattern) => {
+                    self.declare(&pattern.binding, pattern.target_type.clone(), &pattern.loc, false, false);
+                }
+                CaseLabel::Const(label) => self.check_expr(&label.expr),
+                CaseLabel::Int(_) | CaseLabel::Char(_) | CaseLabel::String(_) => {}
+            }
+        }
+    }
+
+    // ==================== 表达式 ====================
+
+    fn check_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(_) => {}
+            Expr::Identifier(ident) => self.read(&ident.name),
+            Expr::Binary(bin) => {
+                if matches!(bin.op, BinaryOp::Eq | BinaryOp::Ne) && self.is_string(&bin.left) && self.is_string(&bin.right) {
+                    let op = if bin.op == BinaryOp::Eq { "==" } else { "!=" };
+                    let message = format!("Strings compared with '{}' compare references; use equals() instead", op);
+                    self.report(LintRule::STRING_EQUALITY, &bin.loc, message);
+                }
+                self.check_expr(&bin.left);
+                self.check_expr(&bin.right);
+            }
+            Expr::Unary(unary) => self.check_expr(&unary.operand),
+            Expr::Call(call) => {
+                self.check_expr(&call.callee);
+                for arg in &call.args {
+                    self.check_expr(arg);
+                }
+            }
+            Expr::MemberAccess(member) => {
+                self.check_expr(&member.object);
+                self.used_members.insert(member.member.clone());
+            }
+            Expr::New(new) => {
+                for arg in &new.args {
+                    self.check_expr(arg);
+                }
+            }
+            Expr::Assignment(assign) => self.check_assignment(assign),
+            Expr::Cast(cast) => self.check_expr(&cast.expr),
+            Expr::ArrayCreation(array) => {
+                for size in &array.sizes {
+                    self.check_expr(size);
+                }
+            }
+            Expr::ArrayAccess(access) => {
+                self.check_expr(&access.array);
+                self.check_expr(&access.index);
+            }
+            Expr::ArrayInit(init) => {
+                for element in &init.elements {
+                    self.check_expr(element);
+                }
+            }
+            Expr::MethodRef(method_ref) => {
+                if let Some(object) = &method_ref.object {
+                    self.check_expr(object);
+                }
+                self.used_members.insert(method_ref.method_name.clone());
+            }
+            Expr::Lambda(lambda) => self.with_scope(|this| {
+                for param in &lambda.params {
+                    let param_type = param.param_type.clone().unwrap_or(Type::Auto);
+                    this.declare(&param.name, param_type, &lambda.loc, true, false);
+                }
+                match &lambda.body {
+                    LambdaBody::Expr(body) => this.check_expr(body),
+                    LambdaBody::Block(block) => this.check_block(block),
+                }
+            }),
+            Expr::Ternary(ternary) => {
+                self.check_expr(&ternary.condition);
+                self.check_expr(&ternary.true_branch);
+                self.check_expr(&ternary.false_branch);
+            }
+            Expr::InstanceOf(instance_of) => {
+                self.check_expr(&instance_of.expr);
+                if let Some(binding) = &instance_of.binding {
+                    self.declare(binding, instance_of.target_type.clone(), &instance_of.loc, false, false);
+                }
+            }
+            Expr::Alloc(alloc) => {
+                self.check_expr(&alloc.size);
+                if let Some(align) = &alloc.align {
+                    self.check_expr(align);
+                }
+            }
+            Expr::Dealloc(dealloc) => {
+                self.check_expr(&dealloc.ptr);
+                self.release(&dealloc.ptr);
+            }
+            Expr::Try(try_expr) => self.check_expr(&try_expr.expr),
+            Expr::Switch(switch) => {
+                self.check_expr(&switch.expr);
+                for arm in &switch.arms {
+                    self.with_scope(|this| {
+                        this.check_case_labels(&arm.labels);
+                        if let Some(guard) = &arm.guard {
+                            this.check_expr(guard);
+                        }
+                        this.check_expr(&arm.value);
+                    });
+                }
+                if let Some(default) = &switch.default {
+                    self.check_expr(default);
+                }
+            }
+            Expr::SafeCall(safe_call) => {
+                self.check_expr(&safe_call.object);
+                self.used_members.insert(safe_call.member.clone());
+                for arg in safe_call.args.iter().flatten() {
+                    self.check_expr(arg);
+                }
+            }
+            Expr::Elvis(elvis) => {
+                self.check_expr(&elvis.value);
+                self.check_expr(&elvis.default);
+            }
+        }
+    }
+
+    /// 赋值：`=` 的目标只是写入，不算读取变量或字段
+    fn check_assignment(&mut self, assign: &AssignmentExpr) {
+        if assign.op == AssignOp::Assign && same_place(&assign.target, &assign.value) {
+            let message = format!("'{}' is assigned to itself", place_name(&assign.target));
+            self.report(LintRule::SELF_ASSIGNMENT, &assign.loc, message);
+        }
+        match (assign.op, assign.target.as_ref()) {
+            (AssignOp::Assign, Expr::Identifier(_)) => {}
+            (AssignOp::Assign, Expr::MemberAccess(member)) => self.check_expr(&member.object),
+            (_, target) => self.check_expr(target),
+        }
+        self.check_expr(&assign.value);
+        self.release(&assign.value);
+        let target = match (assign.op, assign.target.as_ref()) {
+            (AssignOp::Assign, Expr::Identifier(ident)) => self.lookup(&ident.name),
+            _ => None,
+        };
+        if let Some(id) = target {
+            self.locals[id].unreleased_alloc = is_alloc(&assign.value) || self.locals[id].unreleased_alloc;
+        }
+    }
+
+    // ==================== 字符串类型 ====================
+
+    /// 表达式是否一定是字符串（只识别字面量、局部变量、当前类的字段和方法以及字符串拼接）
+    fn is_string(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Literal(LiteralValue::String(_)) => true,
+            Expr::Identifier(ident) => match self.lookup(&ident.name) {
+                Some(id) => self.locals[id].var_type.non_null() == &Type::String,
+                None => self.field_type(&ident.name).is_some_and(|t| t.non_null() == &Type::String),
+            },
+            Expr::MemberAccess(member) if is_this(&member.object) => {
+                self.field_type(&member.member).is_some_and(|t| t.non_null() == &Type::String)
+            }
+            Expr::Binary(bin) if bin.op == BinaryOp::Add => self.is_string(&bin.left) || self.is_string(&bin.right),
+            Expr::Call(call) => {
+                let name = match call.callee.as_ref() {
+                    Expr::Identifier(ident) => &ident.name,
+                    Expr::MemberAccess(member) if is_this(&member.object) => &member.member,
+                    _ => return false,
+                };
+                let class_name = match &self.class_name {
+                    Some(class_name) => class_name,
+                    None => return false,
+                };
+                self.registry.get_method(class_name, name).is_some_and(|m| m.return_type.non_null() == &Type::String)
+            }
+            Expr::Ternary(ternary) => self.is_string(&ternary.true_branch) && self.is_string(&ternary.false_branch),
+            _ => false,
+        }
+    }
+
+    /// 当前类（含父类）中字段的类型
+    fn field_type(&self, name: &str) -> Option<&Type> {
+        let mut current = self.class_name.as_deref().and_then(|c| self.registry.get_class(c));
+        while let Some(info) = current {
+            if let Some(field) = info.fields.get(name) {
+                return Some(&field.field_type);
+            }
+            current = info.parent.as_deref().and_then(|p| self.registry.get_class(p));
+        }
+        None
+    }
+}
+
+fn is_this(expr: &Expr) -> bool {
+    matches!(expr, Expr::Identifier(ident) if ident.name == "this")
+}
+
+fn is_empty_block(stmt: &Stmt) -> bool {
+    matches!(stmt, Stmt::Block(block) if block.statements.is_empty())
+}
+
+fn strip_casts(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Cast(cast) => strip_casts(&cast.expr),
+        other => other,
+    }
+}
+
+/// `__cay_alloc(...)`（可以带类型转换）
+fn is_alloc(expr: &Expr) -> bool {
+    matches!(strip_casts(expr), Expr::Alloc(_))
+}
+
+/// 两个表达式是否指向同一个变量或字段（`x`、`this.x`、`a.b.c`）
+fn same_place(left: &Expr, right: &Expr) -> bool {
+    match (left, right) {
+        (Expr::Identifier(a), Expr::Identifier(b)) => a.name == b.name,
+        (Expr::MemberAccess(a), Expr::MemberAccess(b)) => a.member == b.member && same_place(&a.object, &b.object),
+        _ => false,
+    }
+}
+
+fn place_name(expr: &Expr) -> String {
+    match expr {
+        Expr::Identifier(ident) => ident.name.clone(),
+        Expr::MemberAccess(member) => format!("{}.{}", place_name(&member.object), member.member),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,203 @@
+//! Cavvy 代码检查（lint）
+//!
+//! 在语义分析通过后遍历 AST，结合 `TypeRegistry` 中的类型信息查找可疑但合法的代码，
+//! 如未使用的变量、用 `==` 比较字符串、没有 default 的 switch 等。
+//!
+//! 每条规则有默认级别，可以在 cavly.toml 的 `[lints]` 表中修改：
+//!
+//! ```toml
+//! [lints]
+//! unused_parameter = "allow"
+//! string_equality = "deny"
+//! ```
+//!
+//! 源码中的 `// cay-lint: allow(rule, ...)` 注释在局部关闭规则（见 [`Suppressions`]）。
+
+mod checker;
+mod suppress;
+
+pub use suppress::Suppressions;
+
+use std::collections::HashMap;
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::ast::Program;
+use crate::error::SourceLocation;
+use crate::types::TypeRegistry;
+use checker::Checker;
+
+/// 规则级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// 不检查
+    Allow,
+    /// 报告警告
+    Warn,
+    /// 报告错误，cay-lint 以非零状态退出
+    Deny,
+}
+
+impl LintLevel {
+    /// 从配置或命令行中的名称解析
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintLevel::Allow => write!(f, "allow"),
+            LintLevel::Warn => write!(f, "warn"),
+            LintLevel::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+/// 检查规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LintRule {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default_level: LintLevel,
+}
+
+impl LintRule {
+    pub const UNUSED_VARIABLE: &'static str = "unused_variable";
+    pub const UNUSED_PARAMETER: &'static str = "unused_parameter";
+    pub const UNUSED_PRIVATE_METHOD: &'static str = "unused_private_method";
+    pub const UNUSED_PRIVATE_FIELD: &'static str = "unused_private_field";
+    pub const SHADOWED_VARIABLE: &'static str = "shadowed_variable";
+    pub const STRING_EQUALITY: &'static str = "string_equality";
+    pub const EMPTY_BLOCK: &'static str = "empty_block";
+    pub const SELF_ASSIGNMENT: &'static str = "self_assignment";
+    pub const MISSING_DEFAULT: &'static str = "missing_default";
+    pub const UNFREED_ALLOC: &'static str = "unfreed_alloc";
+
+    /// 按名称查找规则
+    pub fn find(name: &str) -> Option<&'static LintRule> {
+        LINT_RULES.iter().find(|rule| rule.name == name)
+    }
+}
+
+/// 所有检查规则
+pub const LINT_RULES: &[LintRule] = &[
+    LintRule {
+        name: LintRule::UNUSED_VARIABLE,
+        description: "局部变量声明后从未被读取",
+        default_level: LintLevel::Warn,
+    },
+    LintRule {
+        name: LintRule::UNUSED_PARAMETER,
+        description: "方法参数从未被读取（不检查重写方法、接口方法和抽象方法）",
+        default_level: LintLevel::Warn,
+    },
+    LintRule {
+        name: LintRule::UNUSED_PRIVATE_METHOD,
+        description: "private 方法从未被调用",
+        default_level: LintLevel::Warn,
+    },
+    LintRule {
+        name: LintRule::UNUSED_PRIVATE_FIELD,
+        description: "private 字段从未被读取",
+        default_level: LintLevel::Warn,
+    },
+    LintRule {
+        name: LintRule::SHADOWED_VARIABLE,
+        description: "局部变量或 Lambda 参数与外层作用域中的变量同名",
+        default_level: LintLevel::Warn,
+    },
+    LintRule {
+        name: LintRule::STRING_EQUALITY,
+        description: "用 == 或 != 比较字符串（比较的是引用，应使用 equals）",
+        default_level: LintLevel::Warn,
+    },
+    LintRule {
+        name: LintRule::EMPTY_BLOCK,
+        description: "if/else 分支或 try 语句体为空",
+        default_level: LintLevel::Warn,
+    },
+    LintRule {
+        name: LintRule::SELF_ASSIGNMENT,
+        description: "变量或字段赋值给自身",
+        default_level: LintLevel::Warn,
+    },
+    LintRule {
+        name: LintRule::MISSING_DEFAULT,
+        description: "switch 语句没有 default 分支（枚举和 sealed 类型上的 switch 除外）",
+        default_level: LintLevel::Warn,
+    },
+    LintRule {
+        name: LintRule::UNFREED_ALLOC,
+        description: "__cay_alloc 分配的内存没有在同一作用域中用 __cay_free 释放",
+        default_level: LintLevel::Warn,
+    },
+];
+
+/// 各规则的级别（未配置的规则使用默认级别）
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: HashMap<&'static str, LintLevel>,
+}
+
+impl LintConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置规则级别，规则不存在时返回错误
+    pub fn set_level(&mut self, name: &str, level: LintLevel) -> Result<(), String> {
+        let rule = LintRule::find(name).ok_or_else(|| format!("未知的 lint 规则: {}", name))?;
+        self.levels.insert(rule.name, level);
+        Ok(())
+    }
+
+    /// 规则的当前级别
+    pub fn level(&self, name: &str) -> LintLevel {
+        match self.levels.get(name) {
+            Some(level) => *level,
+            None => LintRule::find(name).map(|rule| rule.default_level).unwrap_or(LintLevel::Allow),
+        }
+    }
+}
+
+/// 一条检查结果
+#[derive(Debug, Clone)]
+pub struct LintDiagnostic {
+    pub rule: &'static str,
+    pub level: LintLevel,
+    pub message: String,
+    pub loc: SourceLocation,
+}
+
+/// 代码检查器
+pub struct Linter<'a> {
+    registry: &'a TypeRegistry,
+    config: &'a LintConfig,
+}
+
+impl<'a> Linter<'a> {
+    /// `registry` 应来自对同一程序完成语义分析的分析器
+    pub fn new(registry: &'a TypeRegistry, config: &'a LintConfig) -> Self {
+        Self { registry, config }
+    }
+
+    /// 检查程序，按位置排序返回级别不是 allow 的结果
+    pub fn lint(&self, program: &Program) -> Vec<LintDiagnostic> {
+        let mut diagnostics: Vec<LintDiagnostic> = Checker::new(self.registry).check_program(program)
+            .into_iter()
+            .filter_map(|(rule, loc, message)| match self.config.level(rule) {
+                LintLevel::Allow => None,
+                level => Some(LintDiagnostic { rule, level, message, loc }),
+            })
+            .collect();
+        diagnostics.sort_by(|a, b| (&a.loc.file, a.loc.line, a.loc.column).cmp(&(&b.loc.file, b.loc.line, b.loc.column)));
+        diagnostics
+    }
+}
@@ -0,0 +1,76 @@
+//! `// cay-lint: allow(rule, ...)` 注释
+//!
+//! 注释写在代码行末尾时只作用于这一行；单独占一行时作用于下一行代码，
+//! 中间的空行、注释行和注解行（`@Test` 等）也包括在内。
+
+use std::collections::HashMap;
+use super::LintDiagnostic;
+
+const MARKER: &str = "// cay-lint:";
+
+/// 源文件中被注释关闭的规则
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions {
+    /// 行号（从 1 开始） -> 关闭的规则
+    lines: HashMap<usize, Vec<String>>,
+}
+
+impl Suppressions {
+    /// 扫描源码中的 cay-lint 注释
+    pub fn parse(source: &str) -> Self {
+        let mut suppressions = Self::default();
+        let mut pending: Vec<String> = Vec::new();
+        let mut pending_lines: Vec<usize> = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let trimmed = line.trim();
+            let (code, rules) = match trimmed.find(MARKER) {
+                Some(pos) => (trimmed[..pos].trim(), parse_allow(&trimmed[pos + MARKER.len()..])),
+                None => (trimmed, Vec::new()),
+            };
+
+            let is_code = !code.is_empty() && !code.starts_with("//") && !code.starts_with('@');
+            if !is_code {
+                pending.extend(rules);
+                if !pending.is_empty() {
+                    pending_lines.push(line_number);
+                }
+                continue;
+            }
+
+            let mut allowed = rules;
+            allowed.append(&mut pending);
+            if !allowed.is_empty() {
+                for pending_line in pending_lines.drain(..) {
+                    suppressions.lines.insert(pending_line, allowed.clone());
+                }
+                suppressions.lines.insert(line_number, allowed);
+            }
+        }
+        suppressions
+    }
+
+    /// 规则在这一行是否被关闭
+    pub fn is_allowed(&self, rule: &str, line: usize) -> bool {
+        self.lines.get(&line).is_some_and(|rules| rules.iter().any(|r| r == rule))
+    }
+
+    /// 去掉被注释关闭的检查结果
+    pub fn retain(&self, diagnostics: &mut Vec<LintDiagnostic>) {
+        diagnostics.retain(|diagnostic| !self.is_allowed(diagnostic.rule, diagnostic.loc.line));
+    }
+}
+
+/// 解析 `allow(a, b)`，格式不对时返回空列表
+fn parse_allow(text: &str) -> Vec<String> {
+    let text = text.trim();
+    let inner = match text.strip_prefix("allow(").and_then(|rest| rest.split(')').next()) {
+        Some(inner) => inner,
+        None => return Vec::new(),
+    };
+    inner.split(',')
+        .map(|rule| rule.trim().to_string())
+        .filter(|rule| !rule.is_empty())
+        .collect()
+}
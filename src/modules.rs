@@ -0,0 +1,444 @@
+//! 模块加载
+//!
+//! `import a.b.Class;` 在源码根目录下查找 `a/b/Class.cay`，`import a.b.*;` 加载 `a/b/`
+//! 目录中的所有 `.cay` 文件。源码根目录依次为主文件的根目录（主文件声明了 `package a.b;`
+//! 时为其所在目录向上两级）、caylibs 和 `-I` 指定的包含路径（cavly 通过它传入依赖的源码目录）。
+//!
+//! 每个模块单独预处理和解析，其依赖先于自身加载，最后与主程序合并为一个 `Program`。
+//! `#include` 仍按文本包含处理，被包含的声明属于包含它的文件所在的包。
+//!
+//! 包中声明的类型以限定名（`a.b.Class`）作为类型名，默认包中的类型仍为简单名。解析每个文件前，
+//! 把其中的简单类型名按本文件声明的类型、单类型导入、同一个包中的类型、通配符导入的顺序改写为
+//! 限定名，并把源码中写出的限定名 `a.b.Class` 合并为一个标识符，因此不同包中可以声明同名类型。
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use crate::ast::{ImportDecl, Program};
+use crate::error::{cayError, cayResult, SourceLocation};
+use crate::diagnostic::{Diagnostic, DiagnosticCollector, ErrorCodes};
+use crate::lexer::{Token, TokenWithLocation};
+use crate::{lexer, parser, preprocessor};
+
+/// 解析主文件，加载它导入的模块并合并为一个程序
+///
+/// `include_paths` 既用于预处理模块文件，也作为主文件根目录之后的源码根目录。
+pub fn parse_program(mut tokens: Vec<TokenWithLocation>, source: String, main_file: &str, include_paths: Vec<PathBuf>) -> cayResult<Program> {
+    let modules = load_imports(&mut tokens, main_file, include_paths)?;
+    let program = parser::parse_with_dependencies(tokens, source, &modules)?;
+    link(program, modules)
+}
+
+/// 与 `parse_program` 相同，但主文件中的语法错误不会中止解析，全部加入 `diagnostics`
+///
+/// 导入模块中的错误和链接错误只报告第一个。有语法错误时返回的程序不应继续做语义分析。
+pub fn parse_program_recovering(mut tokens: Vec<TokenWithLocation>, source: String, main_file: &str, include_paths: Vec<PathBuf>, diagnostics: &mut DiagnosticCollector) -> Option<Program> {
+    let modules = match load_imports(&mut tokens, main_file, include_paths) {
+        Ok(modules) => modules,
+        Err(error) => {
+            diagnostics.add(Diagnostic::from_error(&error));
+            return None;
+        }
+    };
+    let program = parser::parse_recovering(tokens, source, &modules, diagnostics)?;
+    match link(program, modules) {
+        Ok(program) => Some(program),
+        Err(error) => {
+            diagnostics.add(Diagnostic::from_error(&error));
+            None
+        }
+    }
+}
+
+/// 加载主文件导入的模块，并把主文件中的类型名改写为限定名
+fn load_imports(tokens: &mut Vec<TokenWithLocation>, main_file: &str, include_paths: Vec<PathBuf>) -> cayResult<Vec<Program>> {
+    let (package, imports) = parser::parse_module_header(tokens)?;
+    if imports.is_empty() && package.is_none() {
+        return Ok(Vec::new());
+    }
+    let mut roots = vec![ModuleLoader::source_root(Path::new(main_file), package.as_deref())];
+    roots.extend(include_paths.iter().cloned());
+    ModuleLoader::new(roots, include_paths).load(main_file, package.as_deref(), &imports, tokens)
+}
+
+/// 默认的 caylibs 目录（可执行文件所在目录和当前工作目录下），同时作为 import 的源码根目录
+pub fn caylibs_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let exe_caylibs = std::env::current_exe().ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("caylibs")));
+    if let Some(caylibs_dir) = exe_caylibs.filter(|dir| dir.exists()) {
+        paths.push(caylibs_dir);
+    }
+    let cwd_caylibs = PathBuf::from("caylibs");
+    if cwd_caylibs.exists() && !paths.contains(&cwd_caylibs) {
+        paths.push(cwd_caylibs);
+    }
+    paths
+}
+
+/// 模块加载器
+pub struct ModuleLoader {
+    /// 源码根目录，按优先级排列
+    roots: Vec<PathBuf>,
+    /// 预处理模块文件时使用的系统包含路径
+    include_paths: Vec<PathBuf>,
+    /// 已加载或正在加载的文件（循环导入时跳过）
+    visited: HashSet<PathBuf>,
+    /// 已加载、尚未解析的模块，依赖在前
+    pending: Vec<PendingModule>,
+    /// 包名 -> 已加载的模块在该包中声明的类型（简单名）
+    package_types: HashMap<String, BTreeSet<String>>,
+}
+
+/// 已词法分析、等待改写类型名并解析的模块
+///
+/// 同一个包中的类型无需导入即可使用，因此所有模块都加载完、知道每个包中的类型后才开始解析。
+struct PendingModule {
+    tokens: Vec<TokenWithLocation>,
+    source: String,
+    package: String,
+    imports: Vec<ImportDecl>,
+}
+
+impl ModuleLoader {
+    pub fn new(roots: Vec<PathBuf>, include_paths: Vec<PathBuf>) -> Self {
+        Self {
+            roots,
+            include_paths,
+            visited: HashSet::new(),
+            pending: Vec::new(),
+            package_types: HashMap::new(),
+        }
+    }
+
+    /// 计算文件所在的源码根目录：声明了 package a.b 的文件向上两级
+    pub fn source_root(file: &Path, package: Option<&str>) -> PathBuf {
+        let mut root = file.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+        let depth = package.map(|p| p.split('.').count()).unwrap_or(0);
+        for _ in 0..depth {
+            if !root.pop() {
+                break;
+            }
+        }
+        if root.as_os_str().is_empty() {
+            PathBuf::from(".")
+        } else {
+            root
+        }
+    }
+
+    /// 加载主文件导入的所有模块，返回按依赖顺序排列的模块
+    ///
+    /// 主文件的令牌 `tokens` 中的类型名同时改写为限定名。
+    pub fn load(mut self, main_file: &str, package: Option<&str>, imports: &[ImportDecl], tokens: &mut Vec<TokenWithLocation>) -> cayResult<Vec<Program>> {
+        self.visited.insert(canonical(Path::new(main_file)));
+        for import in imports {
+            self.load_import(import)?;
+        }
+
+        let mut modules = Vec::new();
+        for module in std::mem::take(&mut self.pending) {
+            let mut module_tokens = module.tokens;
+            qualify_type_names(&mut module_tokens, Some(&module.package), &module.imports, &self.package_types)?;
+            modules.push(parser::parse_with_dependencies(module_tokens, module.source, &modules)?);
+        }
+        qualify_type_names(tokens, package, imports, &self.package_types)?;
+        Ok(modules)
+    }
+
+    fn load_import(&mut self, import: &ImportDecl) -> cayResult<()> {
+        for file in self.resolve(import)? {
+            self.load_module(&file, import)?;
+        }
+        Ok(())
+    }
+
+    /// 在源码根目录中查找导入声明对应的文件
+    fn resolve(&self, import: &ImportDecl) -> cayResult<Vec<PathBuf>> {
+        let package_dir: PathBuf = import.package.split('.').collect();
+        for root in &self.roots {
+            let dir = root.join(&package_dir);
+            match &import.name {
+                Some(name) => {
+                    let file = dir.join(format!("{}.cay", name));
+                    if file.is_file() {
+                        return Ok(vec![file]);
+                    }
+                }
+                None => {
+                    let files = cay_files_in(&dir);
+                    if !files.is_empty() {
+                        return Ok(files);
+                    }
+                }
+            }
+        }
+
+        let expected = match &import.name {
+            Some(name) => package_dir.join(format!("{}.cay", name)),
+            None => package_dir,
+        };
+        let searched: Vec<String> = self.roots.iter().map(|r| r.display().to_string()).collect();
+        Err(import_error(
+            ErrorCodes::IMPORT_NOT_FOUND,
+            &import.loc,
+            format!("无法解析导入 '{}'", import.path()),
+            format!("在源码根目录中未找到 {}（已搜索: {}）；依赖库的源码目录可以通过 -I 添加", expected.display(), searched.join(", ")),
+        ))
+    }
+
+    /// 预处理一个模块文件并登记其中声明的类型，然后加载它导入的模块
+    fn load_module(&mut self, file: &Path, import: &ImportDecl) -> cayResult<()> {
+        if !self.visited.insert(canonical(file)) {
+            return Ok(());
+        }
+
+        let path = file.to_string_lossy().to_string();
+        let source = std::fs::read_to_string(file)
+            .map_err(|e| cayError::Io(format!("无法读取模块文件 '{}': {}", path, e)))?;
+        let base_dir = file.parent().map(|p| p.to_path_buf()).unwrap_or_else(|| PathBuf::from("."));
+        let mut pp = preprocessor::Preprocessor::with_include_paths(base_dir, self.include_paths.clone());
+        let result = pp.process_with_source_map(&source, &path)?;
+        let tokens = lexer::lex_with_source_map(&result.code, result.source_map.to_line_map())?;
+
+        let (package, imports) = parser::parse_module_header(&tokens)?;
+        if package.as_deref() != Some(import.package.as_str()) {
+            let declared = package.as_deref().unwrap_or("默认包");
+            return Err(import_error(
+                ErrorCodes::IMPORT_PACKAGE_MISMATCH,
+                &import.loc,
+                format!("模块 '{}' 属于 {}，与导入路径 '{}' 不符", path, declared, import.path()),
+                format!("在 {} 开头声明 package {};，或把文件移到与其包名对应的目录中", path, import.package),
+            ));
+        }
+        let declared = declared_type_names(&tokens[header_len(&tokens)..]);
+        let missing = import.name.as_ref().filter(|name| !declared.contains(*name));
+        if let Some(name) = missing {
+            return Err(import_error(
+                ErrorCodes::IMPORT_NOT_FOUND,
+                &import.loc,
+                format!("包 {} 中没有类型 '{}'", import.package, name),
+                format!("{} 中应声明 class、interface、enum 或 struct {}", path, name),
+            ));
+        }
+        self.package_types.entry(import.package.clone()).or_default().extend(declared);
+        for dependency in &imports {
+            self.load_import(dependency)?;
+        }
+
+        self.pending.push(PendingModule { tokens, source: result.code, package: import.package.clone(), imports });
+        Ok(())
+    }
+}
+
+/// 包中类型的限定名，默认包中的类型为其简单名
+fn qualified_name(package: &str, name: &str) -> String {
+    if package.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", package, name)
+    }
+}
+
+/// 把文件中引用的类型名改写为限定名
+///
+/// 简单名依次在本文件声明的类型、单类型导入、同一个包中的类型、通配符导入中查找（与 Java 相同）；
+/// 多个通配符导入提供同名类型时，使用该名称才报错。跟在 '.' 后的成员名不改写。
+fn qualify_type_names(tokens: &mut Vec<TokenWithLocation>, package: Option<&str>, imports: &[ImportDecl], package_types: &HashMap<String, BTreeSet<String>>) -> cayResult<()> {
+    let package = package.unwrap_or("");
+    let start = header_len(tokens);
+    let declared = declared_type_names(&tokens[start..]);
+    let types_in = |package: &str| package_types.get(package).into_iter().flatten();
+
+    // 通配符导入，同名时有歧义
+    let mut candidates: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for import in imports.iter().filter(|import| import.name.is_none()) {
+        for name in types_in(&import.package) {
+            candidates.entry(name.clone()).or_default().insert(qualified_name(&import.package, name));
+        }
+    }
+    let mut names: HashMap<String, String> = candidates.iter()
+        .filter(|(_, keys)| keys.len() == 1)
+        .filter_map(|(name, keys)| keys.first().map(|key| (name.clone(), key.clone())))
+        .collect();
+    if !package.is_empty() {
+        for name in types_in(package) {
+            names.insert(name.clone(), qualified_name(package, name));
+        }
+    }
+    let mut single_imports: HashMap<&str, String> = HashMap::new();
+    for import in imports {
+        let Some(name) = &import.name else { continue };
+        let key = qualified_name(&import.package, name);
+        let conflict = match single_imports.get(name.as_str()) {
+            Some(previous) if *previous != key => Some(previous.clone()),
+            _ if declared.contains(name) && qualified_name(package, name) != key => Some(qualified_name(package, name)),
+            _ => None,
+        };
+        if let Some(previous) = conflict {
+            return Err(import_error(
+                ErrorCodes::IMPORT_DUPLICATE_TYPE,
+                &import.loc,
+                format!("导入的类型 '{}' 与 '{}' 同名", key, previous),
+                format!("删除其中一个，另一个类型使用限定名访问，例如: {} x = new {}(...);", key, key),
+            ));
+        }
+        names.insert(name.clone(), key.clone());
+        single_imports.insert(name, key);
+    }
+    for name in &declared {
+        names.insert(name.clone(), qualified_name(package, name));
+    }
+
+    // 源码中写出的限定名 a.b.Class
+    let known: HashSet<String> = package_types.iter()
+        .flat_map(|(package, types)| types.iter().map(move |name| qualified_name(package, name)))
+        .chain(declared.iter().map(|name| qualified_name(package, name)))
+        .collect();
+
+    let original = tokens.split_off(start);
+    let mut i = 0;
+    while i < original.len() {
+        let mut token = original[i].clone();
+        let after_dot = i > 0 && matches!(original[i - 1].token, Token::Dot);
+        if let (Token::Identifier(name), false) = (&original[i].token, after_dot) {
+            let mut path = name.clone();
+            let mut end = i;
+            while let (Some(Token::Dot), Some(Token::Identifier(member))) = (
+                original.get(end + 1).map(|t| &t.token),
+                original.get(end + 2).map(|t| &t.token),
+            ) {
+                path = format!("{}.{}", path, member);
+                end += 2;
+                if known.contains(&path) {
+                    break;
+                }
+            }
+            if end > i && known.contains(&path) {
+                token.token = Token::Identifier(path);
+                i = end;
+            } else if let Some(key) = names.get(name) {
+                token.token = Token::Identifier(key.clone());
+            } else if let Some(keys) = candidates.get(name).filter(|keys| keys.len() > 1) {
+                let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+                return Err(import_error(
+                    ErrorCodes::IMPORT_DUPLICATE_TYPE,
+                    &token_location(&original[i]),
+                    format!("类型名 '{}' 不明确: {} 都通过通配符导入", name, keys.join(" 和 ")),
+                    format!("用单类型导入选择其中一个，例如: import {};，或者使用限定名", keys[0]),
+                ));
+            }
+        }
+        tokens.push(token);
+        i += 1;
+    }
+    Ok(())
+}
+
+/// 文件开头的包声明和导入声明所占的令牌数
+fn header_len(tokens: &[TokenWithLocation]) -> usize {
+    let mut len = 0;
+    while matches!(tokens.get(len).map(|t| &t.token), Some(Token::Package | Token::Import)) {
+        while !matches!(tokens.get(len).map(|t| &t.token), Some(Token::Semicolon) | None) {
+            len += 1;
+        }
+        len += 1;
+    }
+    len
+}
+
+/// 文件中声明的顶层类型（不含嵌套类）
+fn declared_type_names(tokens: &[TokenWithLocation]) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate() {
+        match &token.token {
+            Token::LBrace => depth += 1,
+            Token::RBrace => depth = depth.saturating_sub(1),
+            Token::Class | Token::Interface | Token::Enum | Token::Struct | Token::Record if depth == 0 => {
+                if let Some(Token::Identifier(name)) = tokens.get(i + 1).map(|t| &t.token) {
+                    names.insert(name.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+fn token_location(token: &TokenWithLocation) -> SourceLocation {
+    SourceLocation {
+        file: token.source_file.clone(),
+        line: token.source_line.unwrap_or(token.loc.line),
+        column: token.loc.column,
+    }
+}
+
+/// 把导入的模块合并进主程序
+///
+/// 类型以限定名登记，不同包中的同名类型互不冲突；同一个包中的类型在多个模块中声明时报错。
+/// 同一个文件内的重复声明留给语义分析报告。
+pub fn link(mut program: Program, modules: Vec<Program>) -> cayResult<Program> {
+    let mut declared: HashMap<String, usize> = HashMap::new();
+    for (index, module) in std::iter::once(&program).chain(modules.iter()).enumerate() {
+        let locations = module.classes.iter().map(|c| (&c.name, &c.loc))
+            .chain(module.interfaces.iter().map(|i| (&i.name, &i.loc)))
+            .chain(module.enums.iter().map(|e| (&e.name, &e.loc)))
+            .chain(module.structs.iter().map(|s| (&s.name, &s.loc)));
+        for (name, loc) in locations {
+            match declared.get(name) {
+                Some(previous_index) if *previous_index != index => {
+                    return Err(import_error(
+                        ErrorCodes::IMPORT_DUPLICATE_TYPE,
+                        loc,
+                        format!("类型 '{}' 在多个模块中重复声明", name),
+                        "同一个包中的类型不能同名；为其中一个改名或移到其他包中".to_string(),
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    declared.insert(name.clone(), index);
+                }
+            }
+        }
+    }
+
+    for module in modules {
+        program.classes.extend(module.classes);
+        program.interfaces.extend(module.interfaces);
+        program.enums.extend(module.enums);
+        program.structs.extend(module.structs);
+        program.top_level_functions.extend(module.top_level_functions);
+        program.extern_declarations.extend(module.extern_declarations);
+        program.type_aliases.extend(module.type_aliases);
+        program.declaration_packages.extend(module.declaration_packages);
+    }
+    Ok(program)
+}
+
+/// 目录中的所有 .cay 文件，按文件名排序
+fn cay_files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "cay"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn import_error(code: &'static str, loc: &SourceLocation, message: String, suggestion: String) -> cayError {
+    cayError::Preprocessor {
+        code,
+        file: loc.file.clone(),
+        line: loc.line,
+        column: loc.column,
+        message,
+        suggestion,
+    }
+}
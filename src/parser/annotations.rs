@@ -0,0 +1,86 @@
+//! 注解解析
+//!
+//! `@Name` 或 `@Name(args)` 写在类、接口、方法、字段和参数之前，可以与修饰符交替出现。
+//! 参数是字面量，可以带名称：`@Deprecated("use add")`、`@Since(version = 2)`。
+//! `@main` 和 `@Override` 由词法分析器识别为完整的令牌，仍按修饰符处理。
+
+use crate::ast::{Annotation, AnnotationArg, AnnotationValue};
+use crate::error::{cayError, cayResult, parser_error_with_file};
+use crate::lexer::Token;
+use super::Parser;
+use crate::diagnostic::ErrorCodes;
+
+/// 解析连续的注解
+pub fn parse_annotations(parser: &mut Parser) -> cayResult<Vec<Annotation>> {
+    let mut annotations = Vec::new();
+    while parser.check(&Token::At) {
+        annotations.push(parse_annotation(parser)?);
+    }
+    Ok(annotations)
+}
+
+/// 解析单个注解: @Name 或 @Name(arg, name = arg)
+pub fn parse_annotation(parser: &mut Parser) -> cayResult<Annotation> {
+    let loc = parser.current_loc();
+    parser.consume(&Token::At, "期望 '@'\n提示: 注解以 '@' 开头，例如: @Deprecated")?;
+    let name = parser.consume_identifier("期望注解名\n提示: '@' 后应跟注解名，例如: @Deprecated 或 @Export(\"add\")")?;
+
+    let mut args = Vec::new();
+    if parser.match_token(&Token::LParen) {
+        if !parser.check(&Token::RParen) {
+            loop {
+                args.push(parse_annotation_arg(parser, &name)?);
+                if !parser.match_token(&Token::Comma) {
+                    break;
+                }
+            }
+        }
+        parser.consume(&Token::RParen, &format!("期望 ')'\n提示: 注解 @{} 的参数列表应以 ')' 结束", name))?;
+    }
+
+    Ok(Annotation { name, args, loc })
+}
+
+/// 解析注解参数: 字面量或 名称 = 字面量
+fn parse_annotation_arg(parser: &mut Parser, annotation: &str) -> cayResult<AnnotationArg> {
+    let name = match parser.current_token() {
+        Token::Identifier(name) if parser.check_next(&Token::Assign) => Some(name.clone()),
+        _ => None,
+    };
+    if name.is_some() {
+        parser.advance();
+        parser.advance();
+    }
+
+    let negative = parser.match_token(&Token::Minus);
+    let value = match parser.current_token().clone() {
+        Token::IntegerLiteral(Some((value, _))) => AnnotationValue::Int(if negative { -value } else { value }),
+        Token::StringLiteral(Some(value)) if !negative => AnnotationValue::String(value),
+        Token::CharLiteral(Some(value)) if !negative => AnnotationValue::Char(value),
+        Token::True if !negative => AnnotationValue::Bool(true),
+        Token::False if !negative => AnnotationValue::Bool(false),
+        _ => {
+            return Err(parser.error(ErrorCodes::PARSER_INVALID_ANNOTATION, &format!(
+                "注解 @{} 的参数必须是字面量\n提示: 注解参数可以是整数、字符串、字符或布尔字面量，例如: @{}(\"text\") 或 @{}(count = 3)",
+                annotation, annotation, annotation
+            )));
+        }
+    };
+    parser.advance();
+
+    Ok(AnnotationArg { name, value })
+}
+
+/// 注解用在了不支持的声明上
+pub fn misplaced_annotation_error(annotation: &Annotation) -> cayError {
+    parser_error_with_file(
+        ErrorCodes::PARSER_INVALID_ANNOTATION,
+        annotation.loc.file.clone(),
+        annotation.loc.line,
+        annotation.loc.column,
+        format!(
+            "注解 '@{}' 不能用于此处\n提示: 注解只能用于类、接口、方法、字段和参数",
+            annotation.name
+        ),
+    )
+}
@@ -6,27 +6,36 @@ use crate::error::cayResult;
 use crate::lexer::Token;
 use crate::error::SourceLocation;
 use super::Parser;
-use super::types::{parse_type, is_type_token};
+use super::types::{parse_type, is_type_token, parse_type_args, parse_type_params};
 use super::expressions::parse_expression;
-use super::statements::{parse_block, parse_statement};
+use super::statements::parse_block;
+use super::annotations::{parse_annotation, misplaced_annotation_error};
+use super::properties::{parse_property, push_member};
+use super::recovery::{parse_member_recovering, parse_statement_recovering};
+use crate::diagnostic::ErrorCodes;
 
 /// 解析类声明
 pub fn parse_class(parser: &mut Parser) -> cayResult<ClassDecl> {
     let loc = parser.current_loc();
 
-    // 解析所有修饰符（包括 @main 注解）
-    let modifiers = parse_modifiers(parser)?;
+    // 解析注解和所有修饰符（包括 @main 注解）
+    let (annotations, modifiers) = parse_annotated_modifiers(parser)?;
 
     parser.consume(&Token::Class, "期望关键字 'class'\n提示: 类声明应以 'class' 开头，例如: class MyClass { ... }")?;
 
     let name = parser.consume_identifier("期望类名\n提示: 在 'class' 后应跟类名，例如: class MyClass { ... }")?;
 
+    // 泛型类型参数: class ArrayList<T, A: Allocator>
+    let type_params = parse_type_params(parser)?;
+    parser.push_type_params(&type_params);
+    let mut generic_supertypes = Vec::new();
+
     // 支持 extends 关键字或 : 符号作为继承语法
     let parent = if parser.match_token(&Token::Extends) {
-        Some(parser.consume_identifier("期望父类名\n提示: 在 'extends' 后应跟父类名，例如: class Child extends Parent { ... }")?)
+        Some(parse_supertype(parser, &mut generic_supertypes, "期望父类名\n提示: 在 'extends' 后应跟父类名，例如: class Child extends Parent { ... }")?)
     } else if parser.match_token(&Token::Colon) {
         // 保留 : 符号作为兼容语法
-        Some(parser.consume_identifier("期望父类名\n提示: 在 ':' 后应跟父类名，例如: class Child : Parent { ... }")?)
+        Some(parse_supertype(parser, &mut generic_supertypes, "期望父类名\n提示: 在 ':' 后应跟父类名，例如: class Child : Parent { ... }")?)
     } else {
         None
     };
@@ -35,7 +44,7 @@ pub fn parse_class(parser: &mut Parser) -> cayResult<ClassDecl> {
     let mut interfaces = Vec::new();
     if parser.match_token(&Token::Implements) {
         loop {
-            let interface_name = parser.consume_identifier("期望接口名\n提示: 在 'implements' 后应跟接口名，例如: class MyClass implements Interface1, Interface2 { ... }")?;
+            let interface_name = parse_supertype(parser, &mut generic_supertypes, "期望接口名\n提示: 在 'implements' 后应跟接口名，例如: class MyClass implements Interface1, Interface2 { ... }")?;
             interfaces.push(interface_name);
             if !parser.match_token(&Token::Comma) {
                 break;
@@ -43,59 +52,220 @@ pub fn parse_class(parser: &mut Parser) -> cayResult<ClassDecl> {
         }
     }
 
+    let permits = parse_permits(parser, &modifiers, "class", &name)?;
+
     parser.consume(&Token::LBrace, "期望 '{'\n提示: 类声明后应跟类体，使用 '{' 开始，例如: class MyClass { ... }")?;
 
     let mut members = Vec::new();
     while !parser.check(&Token::RBrace) && !parser.is_at_end() {
-        members.push(parse_class_member(parser)?);
+        if let Some(member) = parse_member_recovering(parser, parse_class_member)? {
+            push_member(&mut members, member);
+        }
     }
 
     parser.consume(&Token::RBrace, "期望 '}'\n提示: 类体应以 '}' 结束")?;
+    parser.pop_type_params();
 
     Ok(ClassDecl {
         name,
         modifiers,
+        type_params,
         parent,
         interfaces,
+        generic_supertypes,
+        permits,
+        annotations,
         members,
+        enclosing_class: None,
         loc,
     })
 }
 
+/// 解析 sealed 类或接口的 permits 列表，sealed 与 permits 必须同时出现
+fn parse_permits(parser: &mut Parser, modifiers: &[Modifier], keyword: &str, name: &str) -> cayResult<Vec<String>> {
+    let sealed = modifiers.contains(&Modifier::Sealed);
+    if !parser.match_token(&Token::Permits) {
+        if sealed {
+            return Err(parser.error(ErrorCodes::PARSER_INVALID_MODIFIER, &format!(
+                "sealed {} '{}' 缺少 permits 列表\n提示: 用 permits 列出允许的子类，例如: sealed {} {} permits A, B {{ ... }}",
+                keyword, name, keyword, name
+            )));
+        }
+        return Ok(Vec::new());
+    }
+    if !sealed {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_MODIFIER, &format!(
+            "只有 sealed {} 可以使用 permits\n提示: 在声明前添加 sealed 修饰符，例如: sealed {} {} permits ...",
+            keyword, keyword, name
+        )));
+    }
+    let mut permits = Vec::new();
+    loop {
+        permits.push(parser.consume_identifier("期望类名\n提示: 在 'permits' 后应跟允许的子类名，例如: permits Circle, Square")?);
+        if !parser.match_token(&Token::Comma) {
+            break;
+        }
+    }
+    Ok(permits)
+}
+
+/// 解析父类或接口名，泛型父类型（Base<T>）返回其单态化名称并记录到 generic_supertypes
+pub(super) fn parse_supertype(parser: &mut Parser, generic_supertypes: &mut Vec<Type>, message: &str) -> cayResult<String> {
+    let name = parser.consume_identifier(message)?;
+    if parser.is_generic_type(&name) && parser.check(&Token::Lt) {
+        let supertype = Type::Generic(name, parse_type_args(parser)?);
+        let mangled = supertype.resolve_generics().to_string();
+        generic_supertypes.push(supertype);
+        Ok(mangled)
+    } else {
+        Ok(name)
+    }
+}
+
 /// 解析接口声明
 pub fn parse_interface(parser: &mut Parser) -> cayResult<InterfaceDecl> {
     let loc = parser.current_loc();
 
-    // 解析修饰符
-    let modifiers = parse_modifiers(parser)?;
+    // 解析注解和修饰符
+    let (annotations, modifiers) = parse_annotated_modifiers(parser)?;
 
     parser.consume(&Token::Interface, "期望关键字 'interface'\n提示: 接口声明应以 'interface' 开头，例如: interface MyInterface { ... }")?;
 
     let name = parser.consume_identifier("期望接口名\n提示: 在 'interface' 后应跟接口名，例如: interface MyInterface { ... }")?;
 
+    // 泛型类型参数: interface Comparable<T>
+    let type_params = parse_type_params(parser)?;
+    parser.push_type_params(&type_params);
+    let permits = parse_permits(parser, &modifiers, "interface", &name)?;
+
     parser.consume(&Token::LBrace, "期望 '{'\n提示: 接口声明后应跟接口体，使用 '{' 开始，例如: interface MyInterface { ... }")?;
 
-    // 接口只能包含方法声明（没有方法体）
+    // 接口只能包含方法
     let mut methods = Vec::new();
     while !parser.check(&Token::RBrace) && !parser.is_at_end() {
-        methods.push(parse_interface_method(parser)?);
+        methods.extend(parse_member_recovering(parser, parse_interface_method)?);
     }
 
     parser.consume(&Token::RBrace, "期望 '}'\n提示: 接口体应以 '}' 结束")?;
+    parser.pop_type_params();
 
     Ok(InterfaceDecl {
         name,
         modifiers,
+        type_params,
+        permits,
+        annotations,
         methods,
         loc,
     })
 }
 
-/// 解析接口方法（只有声明，没有实现）
-fn parse_interface_method(parser: &mut Parser) -> cayResult<MethodDecl> {
+/// 解析枚举声明: enum Shape { Circle(double r), Rect(double w, double h), Empty }
+pub fn parse_enum(parser: &mut Parser) -> cayResult<EnumDecl> {
+    let loc = parser.current_loc();
+
+    let modifiers = parse_modifiers(parser)?;
+
+    parser.consume(&Token::Enum, "期望关键字 'enum'\n提示: 枚举声明应以 'enum' 开头，例如: enum Color { Red, Green }")?;
+
+    let name = parser.consume_identifier("期望枚举名\n提示: 在 'enum' 后应跟枚举名，例如: enum Color { Red, Green }")?;
+
+    parser.consume(&Token::LBrace, "期望 '{'\n提示: 枚举名后应跟枚举体，使用 '{' 开始，例如: enum Color { Red, Green }")?;
+
+    let mut variants = Vec::new();
+    while !parser.check(&Token::RBrace) && !parser.is_at_end() {
+        let variant_loc = parser.current_loc();
+        let variant_name = parser.consume_identifier("期望枚举变体名\n提示: 枚举体由逗号分隔的变体组成，例如: Circle(double r), Empty")?;
+
+        // 带负载的变体: Circle(double r)
+        let fields = if parser.match_token(&Token::LParen) {
+            let fields = parse_parameters(parser)?;
+            parser.consume(&Token::RParen, "期望 ')'\n提示: 变体负载列表应以 ')' 结束，例如: Circle(double r)")?;
+            fields
+        } else {
+            Vec::new()
+        };
+
+        variants.push(EnumVariant {
+            name: variant_name,
+            fields,
+            loc: variant_loc,
+        });
+
+        if !parser.match_token(&Token::Comma) {
+            break;
+        }
+    }
+
+    // 允许变体列表以分号结束: enum Color { Red, Green; }
+    parser.match_token(&Token::Semicolon);
+    parser.consume(&Token::RBrace, "期望 '}'\n提示: 枚举变体之间用 ',' 分隔，枚举体应以 '}' 结束")?;
+
+    if variants.is_empty() {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, "枚举至少需要一个变体\n提示: 例如: enum Color { Red, Green }"));
+    }
+
+    Ok(EnumDecl {
+        name,
+        modifiers,
+        variants,
+        loc,
+    })
+}
+
+/// 解析结构体声明: struct Point { double x; double y; }
+pub fn parse_struct(parser: &mut Parser) -> cayResult<StructDecl> {
     let loc = parser.current_loc();
+
     let modifiers = parse_modifiers(parser)?;
 
+    parser.consume(&Token::Struct, "期望关键字 'struct'\n提示: 结构体声明应以 'struct' 开头，例如: struct Point { double x; double y; }")?;
+
+    let name = parser.consume_identifier("期望结构体名\n提示: 在 'struct' 后应跟结构体名，例如: struct Point { double x; double y; }")?;
+
+    parser.consume(&Token::LBrace, "期望 '{'\n提示: 结构体名后应跟结构体体，使用 '{' 开始，例如: struct Point { double x; double y; }")?;
+
+    let mut fields = Vec::new();
+    while !parser.check(&Token::RBrace) && !parser.is_at_end() {
+        fields.extend(parse_member_recovering(parser, parse_struct_field)?);
+    }
+
+    parser.consume(&Token::RBrace, "期望 '}'\n提示: 结构体体应以 '}' 结束")?;
+
+    if fields.is_empty() {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, "结构体至少需要一个字段\n提示: 例如: struct Point { double x; double y; }"));
+    }
+
+    Ok(StructDecl {
+        name,
+        modifiers,
+        fields,
+        loc,
+    })
+}
+
+/// 解析结构体字段: double x;
+fn parse_struct_field(parser: &mut Parser) -> cayResult<StructField> {
+    let loc = parser.current_loc();
+    if !is_type_token(parser) {
+        return Err(parser.error(ErrorCodes::PARSER_EXPECTED_TYPE, "期望字段类型\n提示: 结构体只能包含字段声明，例如: double x;"));
+    }
+    let field_type = parse_type(parser)?;
+    let name = parser.consume_identifier("期望字段名\n提示: 字段类型后应跟字段名，例如: double x;")?;
+    if parser.check(&Token::Assign) {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, "结构体字段不能有初始值\n提示: 结构体的字段默认为零值，在 new Point(...) 中按声明顺序给出各字段的值"));
+    }
+    parser.consume(&Token::Semicolon, "期望 ';'\n提示: 结构体字段声明应以分号结束，例如: double x;")?;
+    Ok(StructField { name, field_type, loc })
+}
+
+/// 解析接口方法（抽象方法只有声明，default 和 static 方法带有实现）
+fn parse_interface_method(parser: &mut Parser) -> cayResult<MethodDecl> {
+    let loc = parser.current_loc();
+    let (annotations, modifiers) = parse_annotated_modifiers(parser)?;
+    let type_params = parse_type_params(parser)?;
+    parser.push_type_params(&type_params);
+
     let return_type = if parser.check(&Token::Void) {
         parser.advance();
         Type::Void
@@ -104,29 +274,64 @@ fn parse_interface_method(parser: &mut Parser) -> cayResult<MethodDecl> {
     };
 
     let name = parser.consume_identifier("期望方法名\n提示: 在返回类型后应跟方法名，例如: int calculate() { ... }")?;
+    let operator = parse_operator_symbol(parser, &name)?;
 
     parser.consume(&Token::LParen, "期望 '('\n提示: 方法名后应跟 '(' 开始参数列表，例如: int calculate() { ... }")?;
     let params = parse_parameters(parser)?;
     parser.consume(&Token::RParen, "期望 ')'\n提示: 参数列表应以 ')' 结束")?;
-
-    // 接口方法必须以分号结束，没有方法体
-    parser.consume(&Token::Semicolon, "期望 ';'\n提示: 接口方法声明应以 ';' 结束，例如: int calculate();")?;
+    let name = operator.map(|symbol| operator_method_name(symbol, params.len()).to_string()).unwrap_or(name);
+
+    // default 和 static 方法带有方法体，其余接口方法只有声明，以分号结束
+    let has_body = modifiers.contains(&Modifier::Default) || modifiers.contains(&Modifier::Static);
+    let body = if has_body {
+        if !parser.check(&Token::LBrace) {
+            return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, &format!(
+                "default 或 static 接口方法 '{}' 缺少方法体\n提示: 在参数列表后用 '{{ ... }}' 给出实现，例如: default void log() {{ println(\"...\"); }}",
+                name
+            )));
+        }
+        Some(parse_block(parser)?)
+    } else {
+        if parser.check(&Token::LBrace) {
+            return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, &format!(
+                "接口方法 '{}' 不能有方法体\n提示: 带实现的接口方法应标记为 default 或 static，例如: default void log() {{ ... }}",
+                name
+            )));
+        }
+        parser.consume(&Token::Semicolon, "期望 ';'\n提示: 接口方法声明应以 ';' 结束，例如: int calculate();")?;
+        None
+    };
+    parser.pop_type_params();
 
     Ok(MethodDecl {
         name,
         modifiers,
+        annotations,
+        type_params,
         return_type,
         params,
-        body: None,  // 接口方法没有方法体
+        body,
         loc,
     })
 }
 
-/// 解析类成员（字段、方法、构造函数、析构函数或初始化块）
+/// 解析类成员（字段、方法、构造函数、析构函数、初始化块或嵌套类）
 pub fn parse_class_member(parser: &mut Parser) -> cayResult<ClassMember> {
     // 向前看判断成员类型
     let checkpoint = parser.pos;
-    let modifiers = parse_modifiers(parser)?;
+    let (_, modifiers) = parse_annotated_modifiers(parser)?;
+
+    // 检查是否是嵌套类 [static] class Inner { ... }
+    if parser.check(&Token::Class) {
+        parser.pos = checkpoint;
+        return Ok(ClassMember::Class(parse_class(parser)?));
+    }
+
+    // 检查是否是属性 property int count { get; set; }
+    if parser.check(&Token::Property) {
+        parser.pos = checkpoint;
+        return Ok(ClassMember::Property(Box::new(parse_property(parser)?)));
+    }
     
     // 检查是否是静态初始化块 static { ... }
     if modifiers.contains(&Modifier::Static) && parser.check(&Token::LBrace) {
@@ -146,8 +351,8 @@ pub fn parse_class_member(parser: &mut Parser) -> cayResult<ClassMember> {
         return Ok(ClassMember::Destructor(parse_destructor(parser)?));
     }
     
-    // 如果是void，一定是方法返回类型
-    if parser.check(&Token::Void) {
+    // 如果是void或泛型参数列表，一定是方法
+    if parser.check(&Token::Void) || parser.check(&Token::Lt) {
         parser.pos = checkpoint;
         return Ok(ClassMember::Method(parse_method(parser)?));
     }
@@ -182,7 +387,7 @@ pub fn parse_class_member(parser: &mut Parser) -> cayResult<ClassMember> {
                 // 已经消耗了 {，直接解析语句直到 }
                 let mut statements = Vec::new();
                 while !parser.check(&Token::RBrace) && !parser.is_at_end() {
-                    statements.push(parse_statement(parser)?);
+                    statements.push(parse_statement_recovering(parser)?);
                 }
                 parser.consume(&Token::RBrace, "期望 '}'\n提示: 构造函数体应以 '}' 结束")?;
                 Block { statements, loc: parser.current_loc() }
@@ -209,7 +414,7 @@ pub fn parse_class_member(parser: &mut Parser) -> cayResult<ClassMember> {
         let member_type = parse_type(parser)?;
         let member_name = parser.consume_identifier("期望成员名\n提示: 类型后应跟字段名或方法名，例如: int count; 或 int calculate() { ... }")?;
 
-        if parser.check(&Token::LParen) {
+        if parser.check(&Token::LParen) || member_name == "operator" {
             // 是方法
             parser.pos = checkpoint;
             Ok(ClassMember::Method(parse_method(parser)?))
@@ -247,10 +452,6 @@ pub fn parse_class_member(parser: &mut Parser) -> cayResult<ClassMember> {
                 "类声明提前结束。可能的问题:\n    - 类体为空\n    - 前面的声明语法错误".to_string()
             ),
             // 关键字
-            crate::lexer::Token::Class => (
-                "关键字(class)".to_string(),
-                "类声明不能在类内部。可能的问题:\n    - 嵌套类不支持\n    - 类声明位置错误".to_string()
-            ),
             crate::lexer::Token::Interface => (
                 "关键字(interface)".to_string(),
                 "接口声明不能在类内部。可能的问题:\n    - 接口声明位置错误".to_string()
@@ -307,7 +508,7 @@ pub fn parse_class_member(parser: &mut Parser) -> cayResult<ClassMember> {
                 )
             }
         };
-        Err(parser.error(&format!(
+        Err(parser.error(ErrorCodes::PARSER_UNEXPECTED_TOKEN, &format!(
             "期望字段、方法、构造函数或析构函数声明，但遇到了 {}\n提示: {}",
             token_desc, suggestion
         )))
@@ -317,7 +518,7 @@ pub fn parse_class_member(parser: &mut Parser) -> cayResult<ClassMember> {
 /// 解析字段声明
 pub fn parse_field(parser: &mut Parser) -> cayResult<FieldDecl> {
     let loc = parser.current_loc();
-    let modifiers = parse_modifiers(parser)?;
+    let (annotations, modifiers) = parse_annotated_modifiers(parser)?;
     let field_type = parse_type(parser)?;
     let name = parser.consume_identifier("期望字段名\n提示: 类型后应跟字段名，例如: int count;")?;
 
@@ -333,6 +534,7 @@ pub fn parse_field(parser: &mut Parser) -> cayResult<FieldDecl> {
         name,
         field_type,
         modifiers,
+        annotations,
         initializer,
         loc,
     })
@@ -341,7 +543,11 @@ pub fn parse_field(parser: &mut Parser) -> cayResult<FieldDecl> {
 /// 解析方法声明
 pub fn parse_method(parser: &mut Parser) -> cayResult<MethodDecl> {
     let loc = parser.current_loc();
-    let modifiers = parse_modifiers(parser)?;
+    let (annotations, modifiers) = parse_annotated_modifiers(parser)?;
+
+    // 泛型方法类型参数: public static <T> T id(T x)
+    let type_params = parse_type_params(parser)?;
+    parser.push_type_params(&type_params);
     
     let return_type = if parser.check(&Token::Void) {
         parser.advance();
@@ -351,10 +557,12 @@ pub fn parse_method(parser: &mut Parser) -> cayResult<MethodDecl> {
     };
     
     let name = parser.consume_identifier("期望方法名\n提示: 返回类型后应跟方法名，例如: int calculate() { ... }")?;
+    let operator = parse_operator_symbol(parser, &name)?;
 
     parser.consume(&Token::LParen, "期望 '('\n提示: 方法名后应跟 '(' 开始参数列表，例如: int calculate() { ... }")?;
     let params = parse_parameters(parser)?;
     parser.consume(&Token::RParen, "期望 ')'\n提示: 参数列表应以 ')' 结束")?;
+    let name = operator.map(|symbol| operator_method_name(symbol, params.len()).to_string()).unwrap_or(name);
 
     // 检查是否是native方法或abstract方法（这两种都可以没有方法体）
     let is_native = modifiers.contains(&Modifier::Native);
@@ -366,10 +574,13 @@ pub fn parse_method(parser: &mut Parser) -> cayResult<MethodDecl> {
     } else {
         Some(parse_block(parser)?)
     };
+    parser.pop_type_params();
     
     Ok(MethodDecl {
         name,
         modifiers,
+        annotations,
+        type_params,
         return_type,
         params,
         body,
@@ -377,6 +588,54 @@ pub fn parse_method(parser: &mut Parser) -> cayResult<MethodDecl> {
     })
 }
 
+/// 解析运算符方法名 `operator+` 中的运算符，普通方法名返回 None
+fn parse_operator_symbol(parser: &mut Parser, name: &str) -> cayResult<Option<&'static str>> {
+    if name != "operator" || parser.check(&Token::LParen) {
+        return Ok(None);
+    }
+    let symbol = match parser.current_token() {
+        Token::Plus => "+",
+        Token::Minus => "-",
+        Token::Star => "*",
+        Token::Slash => "/",
+        Token::Percent => "%",
+        Token::EqEq => "==",
+        Token::LBracket => "[]",
+        Token::Lt | Token::Le | Token::Gt | Token::Ge => {
+            return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, "不支持重载比较运算符\n提示: <、<=、>、>= 通过 int compareTo(T other) 方法重载，返回负数、零或正数"));
+        }
+        Token::NotEq => {
+            return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, "不能单独重载 '!='\n提示: 重载 operator== 后 != 自动取其结果的反"));
+        }
+        _ => {
+            return Err(parser.error(ErrorCodes::PARSER_UNEXPECTED_TOKEN, "期望可重载的运算符\n提示: 可重载的运算符有 + - * / % == [] 和 []=，例如: Vec3 operator+(Vec3 other) { ... }"));
+        }
+    };
+    parser.advance();
+    if symbol == "[]" {
+        parser.consume(&Token::RBracket, "期望 ']'\n提示: 下标运算符写作 operator[] 或 operator[]=")?;
+        if parser.match_token(&Token::Assign) {
+            return Ok(Some("[]="));
+        }
+    }
+    Ok(Some(symbol))
+}
+
+/// 运算符方法对应的方法名：`operator+` 与名为 plus 的方法相同，一元 `operator-` 为 negate
+fn operator_method_name(symbol: &str, param_count: usize) -> &'static str {
+    match symbol {
+        "+" => "plus",
+        "-" if param_count == 0 => "negate",
+        "-" => "minus",
+        "*" => "times",
+        "/" => "div",
+        "%" => "rem",
+        "==" => "equals",
+        "[]" => "get",
+        _ => "set",
+    }
+}
+
 /// 解析构造函数声明
 /// 格式: [modifiers] ClassName([params]) [throws ...] { body }
 /// 或: [modifiers] ClassName([params]) : this(args) { body }
@@ -402,7 +661,7 @@ pub fn parse_constructor(parser: &mut Parser) -> cayResult<ConstructorDecl> {
         // 已经消耗了 {，直接解析语句直到 }
         let mut statements = Vec::new();
         while !parser.check(&Token::RBrace) && !parser.is_at_end() {
-            statements.push(parse_statement(parser)?);
+            statements.push(parse_statement_recovering(parser)?);
         }
         parser.consume(&Token::RBrace, "Expected '}' after constructor body")?;
         Block { statements, loc: parser.current_loc() }
@@ -453,7 +712,7 @@ fn parse_constructor_call(parser: &mut Parser) -> cayResult<ConstructorCallResul
         } else {
             let current_token = parser.current_token();
             let token_desc = super::utils::get_token_name(current_token);
-            return Err(parser.error(&format!(
+            return Err(parser.error(ErrorCodes::PARSER_UNEXPECTED_TOKEN, &format!(
                 "期望 'this' 或 'super'，但遇到了 {}\n\
                 提示: 构造函数链调用应使用 : this(args) 或 : super(args) 语法",
                 token_desc
@@ -563,12 +822,25 @@ pub fn parse_static_initializer(parser: &mut Parser) -> cayResult<Block> {
     parse_block(parser)
 }
 
-/// 解析修饰符列表（包括注解）
+/// 解析修饰符列表（包括 @main 和 @Override），不允许出现其他注解
 pub fn parse_modifiers(parser: &mut Parser) -> cayResult<Vec<Modifier>> {
+    let (annotations, modifiers) = parse_annotated_modifiers(parser)?;
+    if let Some(annotation) = annotations.first() {
+        return Err(misplaced_annotation_error(annotation));
+    }
+    Ok(modifiers)
+}
+
+/// 解析类、接口、方法和字段声明前的注解和修饰符，二者可以交替出现
+pub fn parse_annotated_modifiers(parser: &mut Parser) -> cayResult<(Vec<Annotation>, Vec<Modifier>)> {
+    let mut annotations = std::mem::take(&mut parser.pending_annotations);
     let mut modifiers = Vec::new();
     
     loop {
         match parser.current_token() {
+            Token::At => {
+                annotations.push(parse_annotation(parser)?);
+            }
             Token::Public => {
                 modifiers.push(Modifier::Public);
                 parser.advance();
@@ -593,10 +865,18 @@ pub fn parse_modifiers(parser: &mut Parser) -> cayResult<Vec<Modifier>> {
                 modifiers.push(Modifier::Abstract);
                 parser.advance();
             }
+            Token::Sealed => {
+                modifiers.push(Modifier::Sealed);
+                parser.advance();
+            }
             Token::Native => {
                 modifiers.push(Modifier::Native);
                 parser.advance();
             }
+            Token::Const => {
+                modifiers.push(Modifier::Const);
+                parser.advance();
+            }
             Token::AtOverride => {
                 modifiers.push(Modifier::Override);
                 parser.advance();
@@ -605,11 +885,15 @@ pub fn parse_modifiers(parser: &mut Parser) -> cayResult<Vec<Modifier>> {
                 modifiers.push(Modifier::Main);
                 parser.advance();
             }
+            Token::Default => {
+                modifiers.push(Modifier::Default);
+                parser.advance();
+            }
             _ => break,
         }
     }
     
-    Ok(modifiers)
+    Ok((annotations, modifiers))
 }
 
 /// 解析参数列表（支持可变参数）
@@ -625,11 +909,13 @@ pub fn parse_parameters(parser: &mut Parser) -> cayResult<Vec<ParameterInfo>> {
                 params.push(ParameterInfo::new_varargs("...".to_string(), Type::CVoid));
                 // 可变参数必须是最后一个参数
                 if parser.check(&Token::Comma) {
-                    return Err(parser.error("可变参数必须是最后一个参数\n提示: 可变参数(...)必须放在参数列表的最后"));
+                    return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, "可变参数必须是最后一个参数\n提示: 可变参数(...)必须放在参数列表的最后"));
                 }
                 break;
             }
 
+            let annotations = super::annotations::parse_annotations(parser)?;
+
             // 检查是否是可变参数类型（type...）
             let param_type = parse_type(parser)?;
 
@@ -639,15 +925,15 @@ pub fn parse_parameters(parser: &mut Parser) -> cayResult<Vec<ParameterInfo>> {
             if is_varargs {
                 // type... 形式的可变参数，需要一个名称
                 let name = parser.consume_identifier("期望参数名\n提示: 可变参数需要名称，例如: int... args")?;
-                params.push(ParameterInfo::new_varargs(name, param_type));
+                params.push(ParameterInfo { annotations, ..ParameterInfo::new_varargs(name, param_type) });
                 // 可变参数必须是最后一个参数
                 if parser.match_token(&Token::Comma) {
-                    return Err(parser.error("可变参数必须是最后一个参数\n提示: 可变参数(...)必须放在参数列表的最后"));
+                    return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, "可变参数必须是最后一个参数\n提示: 可变参数(...)必须放在参数列表的最后"));
                 }
                 break;
             } else {
                 let name = parser.consume_identifier("期望参数名\n提示: 参数需要名称，例如: int count")?;
-                params.push(ParameterInfo::new(name, param_type));
+                params.push(ParameterInfo { annotations, ..ParameterInfo::new(name, param_type) });
             }
 
             if !parser.match_token(&Token::Comma) {
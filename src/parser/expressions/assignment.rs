@@ -34,11 +34,11 @@ pub fn parse_assignment(parser: &mut Parser) -> cayResult<Expr> {
 /// 解析三元运算符表达式: condition ? true_expr : false_expr
 fn parse_ternary(parser: &mut Parser) -> cayResult<Expr> {
     let loc = parser.current_loc();
-    let condition = parse_or(parser)?;
+    let condition = parse_elvis(parser)?;
 
     // 检查是否有 ? 标记
     if parser.match_token(&crate::lexer::Token::Question) {
-        let true_branch = Box::new(parse_or(parser)?);
+        let true_branch = Box::new(parse_elvis(parser)?);
         parser.consume(&crate::lexer::Token::Colon, "期望 ':'\n提示: 三元运算符格式为 condition ? true_expr : false_expr")?;
         let false_branch = Box::new(parse_ternary(parser)?); // 右结合
 
@@ -53,6 +53,23 @@ fn parse_ternary(parser: &mut Parser) -> cayResult<Expr> {
     Ok(condition)
 }
 
+/// 解析 elvis 表达式: value ?: default（右结合，优先级低于 ||）
+fn parse_elvis(parser: &mut Parser) -> cayResult<Expr> {
+    let value = parse_or(parser)?;
+
+    let loc = parser.current_loc();
+    if parser.match_token(&crate::lexer::Token::Elvis) {
+        let default = parse_elvis(parser)?;
+        return Ok(Expr::Elvis(ElvisExpr {
+            value: Box::new(value),
+            default: Box::new(default),
+            loc,
+        }));
+    }
+
+    Ok(value)
+}
+
 /// 匹配赋值操作符
 pub fn match_assignment_op(parser: &mut Parser) -> Option<AssignOp> {
     if parser.check(&crate::lexer::Token::Assign) {
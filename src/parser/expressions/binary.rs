@@ -166,11 +166,20 @@ pub fn parse_comparison(parser: &mut Parser) -> cayResult<Expr> {
                 loc,
             });
         } else if parser.match_token(&crate::lexer::Token::InstanceOf) {
-            // 解析 instanceof 表达式
-            let target_type = super::super::types::parse_type(parser)?;
+            // 解析 instanceof 表达式，类型后的标识符是模式变量: obj instanceof Circle c
+            let target_type = super::super::types::parse_non_null_type(parser)?;
+            let binding = match parser.current_token() {
+                crate::lexer::Token::Identifier(name) => {
+                    let name = name.clone();
+                    parser.advance();
+                    Some(name)
+                }
+                _ => None,
+            };
             left = Expr::InstanceOf(InstanceOfExpr {
                 expr: Box::new(left),
                 target_type,
+                binding,
                 loc,
             });
         } else {
@@ -8,6 +8,7 @@ use super::super::Parser;
 use super::super::types::{parse_type, is_type_token};
 use super::super::statements::parse_statement;
 use super::assignment::parse_expression;
+use crate::diagnostic::ErrorCodes;
 
 /// 尝试解析 Lambda 表达式
 /// 假设已经消耗了 '('，需要解析参数列表和 -> 箭头
@@ -30,7 +31,7 @@ pub fn try_parse_lambda(parser: &mut Parser, loc: crate::error::SourceLocation)
     // 期望 ')'
     if !parser.check(&crate::lexer::Token::RParen) {
         let current_token = super::super::utils::get_token_name(parser.current_token());
-        return Err(parser.error(&format!(
+        return Err(parser.error(ErrorCodes::PARSER_EXPECTED_PAREN, &format!(
             "期望 ')'，但遇到了 {}\n提示: Lambda 参数列表应以 ')' 结束，例如: (x, y) -> x + y",
             current_token
         )));
@@ -40,7 +41,7 @@ pub fn try_parse_lambda(parser: &mut Parser, loc: crate::error::SourceLocation)
     // 期望 '->'
     if !parser.check(&crate::lexer::Token::Arrow) {
         let current_token = super::super::utils::get_token_name(parser.current_token());
-        return Err(parser.error(&format!(
+        return Err(parser.error(ErrorCodes::PARSER_UNEXPECTED_TOKEN, &format!(
             "期望 '->'，但遇到了 {}\n提示: Lambda 表达式格式为 (params) -> expr 或 (params) -> {{ body }}",
             current_token
         )));
@@ -85,11 +86,11 @@ fn parse_lambda_param(parser: &mut Parser) -> cayResult<LambdaParam> {
         } else {
             // 类型后面没有标识符，回退
             parser.pos = checkpoint;
-            Err(parser.error("期望参数名\n提示: 类型后应跟参数名，例如: (int x, int y) -> x + y"))
+            Err(parser.error(ErrorCodes::PARSER_EXPECTED_IDENTIFIER, "期望参数名\n提示: 类型后应跟参数名，例如: (int x, int y) -> x + y"))
         }
     } else {
         let current_token = super::super::utils::get_token_name(parser.current_token());
-        Err(parser.error(&format!(
+        Err(parser.error(ErrorCodes::PARSER_EXPECTED_TYPE, &format!(
             "期望类型或参数名，但遇到了 {}\n\
             提示: Lambda 参数可以是:\
             - 带类型: (int x, int y) -> ...\n\
@@ -112,7 +113,7 @@ fn parse_lambda_param(parser: &mut Parser) -> cayResult<LambdaParam> {
         })
     } else {
         let current_token = super::super::utils::get_token_name(parser.current_token());
-        Err(parser.error(&format!(
+        Err(parser.error(ErrorCodes::PARSER_EXPECTED_IDENTIFIER, &format!(
             "期望参数名，但遇到了 {}\n提示: Lambda 参数应为标识符，例如: (x, y) -> x + y",
             current_token
         )))
@@ -1,9 +1,10 @@
 //! 后缀表达式解析
 //!
-//! 处理函数调用、成员访问、数组索引、后缀自增自减等后缀表达式。
+//! 处理函数调用、成员访问、安全调用 `?.`、数组索引、后缀自增自减以及错误传播 `?` 等后缀表达式。
 
 use crate::ast::*;
-use crate::error::cayResult;
+use crate::error::{cayResult, parser_error_with_file, SourceLocation};
+use crate::diagnostic::ErrorCodes;
 use super::super::Parser;
 use super::primary::parse_primary;
 use super::assignment::parse_expression;
@@ -18,11 +19,7 @@ pub fn parse_postfix(parser: &mut Parser) -> cayResult<Expr> {
             // 函数调用
             let args = parse_arguments(parser)?;
             parser.consume(&crate::lexer::Token::RParen, "期望 ')'\n提示: 函数调用参数列表应以 ')' 结束")?;
-            expr = Expr::Call(CallExpr {
-                callee: Box::new(expr),
-                args,
-                loc,
-            });
+            expr = call_expression(expr, args, loc)?;
         } else if parser.match_token(&crate::lexer::Token::Dot) {
             // 成员访问
             let member = parser.consume_identifier("期望成员名\n提示: '.' 后应跟成员名，例如: obj.field 或 obj.method()")?;
@@ -31,6 +28,22 @@ pub fn parse_postfix(parser: &mut Parser) -> cayResult<Expr> {
                 member,
                 loc,
             });
+        } else if parser.match_token(&crate::lexer::Token::QuestionDot) {
+            // 安全调用: obj?.field 或 obj?.method(args)
+            let member = parser.consume_identifier("期望成员名\n提示: '?.' 后应跟成员名，例如: obj?.field 或 obj?.method()")?;
+            let args = if parser.match_token(&crate::lexer::Token::LParen) {
+                let args = parse_arguments(parser)?;
+                parser.consume(&crate::lexer::Token::RParen, "期望 ')'\n提示: 函数调用参数列表应以 ')' 结束")?;
+                Some(args)
+            } else {
+                None
+            };
+            expr = Expr::SafeCall(SafeCallExpr {
+                object: Box::new(expr),
+                member,
+                args,
+                loc,
+            });
         } else if parser.match_token(&crate::lexer::Token::LBracket) {
             // 数组索引访问: arr[index]
             let index = parse_expression(parser)?;
@@ -54,6 +67,13 @@ pub fn parse_postfix(parser: &mut Parser) -> cayResult<Expr> {
                 operand: Box::new(expr),
                 loc,
             });
+        } else if parser.check(&crate::lexer::Token::Question) && is_try_operator(parser) {
+            // 错误传播: parse(s)?
+            parser.advance();
+            expr = Expr::Try(TryExpr {
+                expr: Box::new(expr),
+                loc,
+            });
         } else {
             break;
         }
@@ -62,6 +82,44 @@ pub fn parse_postfix(parser: &mut Parser) -> cayResult<Expr> {
     Ok(expr)
 }
 
+/// 函数调用；内置的 `__cay_alloc(size[, align])` 和 `__cay_free(ptr)` 解析为内存分配/释放表达式
+fn call_expression(callee: Expr, mut args: Vec<Expr>, loc: SourceLocation) -> cayResult<Expr> {
+    let builtin = match &callee {
+        Expr::Identifier(ident) if ident.name == "__cay_alloc" || ident.name == "__cay_free" => ident.name.as_str(),
+        _ => return Ok(Expr::Call(CallExpr { callee: Box::new(callee), args, loc })),
+    };
+    match (builtin, args.len()) {
+        ("__cay_alloc", 1 | 2) => {
+            let align = if args.len() == 2 { args.pop().map(Box::new) } else { None };
+            let size = Box::new(args.remove(0));
+            Ok(Expr::Alloc(AllocExpr { size, align, loc }))
+        }
+        ("__cay_free", 1) => Ok(Expr::Dealloc(DeallocExpr { ptr: Box::new(args.remove(0)), loc })),
+        _ => {
+            let usage = if builtin == "__cay_alloc" { "__cay_alloc(size) 或 __cay_alloc(size, align)" } else { "__cay_free(ptr)" };
+            Err(parser_error_with_file(
+                ErrorCodes::PARSER_INVALID_EXPRESSION, loc.file, loc.line, loc.column,
+                format!("{} 的参数个数错误: 得到 {} 个\n提示: 用法为 {}", builtin, args.len(), usage),
+            ))
+        }
+    }
+}
+
+/// 判断当前的 `?` 是否是后缀错误传播运算符而不是三元运算符
+///
+/// 三元运算符的 `?` 后面总是跟着一个表达式，因此 `?` 后紧跟
+/// 不能开始表达式的记号（`;` `)` `.` 或二元运算符等）时视为错误传播。
+fn is_try_operator(parser: &Parser) -> bool {
+    use crate::lexer::Token;
+    [
+        Token::Semicolon, Token::RParen, Token::RBracket, Token::RBrace, Token::Comma,
+        Token::Colon, Token::Dot, Token::Question,
+        Token::Star, Token::Slash, Token::Percent,
+        Token::EqEq, Token::NotEq, Token::Lt, Token::Le, Token::Gt, Token::Ge,
+        Token::AndAnd, Token::OrOr,
+    ].iter().any(|token| parser.check_next(token))
+}
+
 /// 解析参数列表
 pub fn parse_arguments(parser: &mut Parser) -> cayResult<Vec<Expr>> {
     let mut args = Vec::new();
@@ -4,11 +4,17 @@
 
 use crate::ast::*;
 use crate::types::Type;
+use crate::lexer::IntSuffix;
 use crate::error::cayResult;
 use super::super::Parser;
-use super::super::types::is_type_token;
+use super::super::types::{is_type_token, parse_type_args};
 use super::lambda::try_parse_lambda;
 use super::assignment::parse_expression;
+use super::super::statements::parse_switch_expression;
+use super::super::classes::parse_class_member;
+use super::super::properties::push_member;
+use super::super::recovery::parse_member_recovering;
+use crate::diagnostic::ErrorCodes;
 
 /// 解析基本表达式
 pub fn parse_primary(parser: &mut Parser) -> cayResult<Expr> {
@@ -17,20 +23,48 @@ pub fn parse_primary(parser: &mut Parser) -> cayResult<Expr> {
     let token = parser.current_token().clone();
     match token {
         crate::lexer::Token::IntegerLiteral(Some((val, suffix))) => {
-            parser.advance();
-            let lit = match suffix {
-                Some('L') | Some('l') => LiteralValue::Int64(val),
+            let target_type = match suffix {
+                Some(IntSuffix::Long) => {
+                    parser.advance();
+                    return Ok(Expr::Literal(LiteralValue::Int64(val)));
+                }
                 None => {
+                    parser.advance();
                     // 默认整数字面量类型为 int32，但如果值超出范围，则视为 int64？
-                    if val >= i32::MIN as i64 && val <= i32::MAX as i64 {
+                    let lit = if val >= i32::MIN as i64 && val <= i32::MAX as i64 {
                         LiteralValue::Int32(val as i32)
                     } else {
                         LiteralValue::Int64(val)
-                    }
+                    };
+                    return Ok(Expr::Literal(lit));
                 }
-                _ => unreachable!(),
+                Some(IntSuffix::Byte) => Type::Int8,
+                Some(IntSuffix::UByte) => Type::UInt8,
+                Some(IntSuffix::Short) => Type::Int16,
+                Some(IntSuffix::UShort) => Type::UInt16,
+                Some(IntSuffix::UInt) => Type::UInt32,
+                Some(IntSuffix::ULong) => Type::UInt64,
             };
-            Ok(Expr::Literal(lit))
+            // 带类型后缀的字面量即转换为该类型的字面量；ulong 以 64 位模式保存在 i64 中
+            let out_of_range = target_type.integer_range()
+                .filter(|&(min, max)| target_type != Type::UInt64 && !(min..=max).contains(&(val as i128)));
+            if let Some((min, max)) = out_of_range {
+                return Err(parser.error(ErrorCodes::PARSER_LITERAL_OUT_OF_RANGE, &format!(
+                    "整数字面量 {} 超出 {} 的取值范围\n提示: {} 的取值范围是 [{}, {}]，需要截断高位时请显式转换，例如: ({}) {}",
+                    val, target_type, target_type, min, max, target_type, val
+                )));
+            }
+            parser.advance();
+            let literal = if target_type == Type::UInt64 || val > i32::MAX as i64 {
+                LiteralValue::Int64(val)
+            } else {
+                LiteralValue::Int32(val as i32)
+            };
+            Ok(Expr::Cast(CastExpr {
+                expr: Box::new(Expr::Literal(literal)),
+                target_type,
+                loc,
+            }))
         }
         crate::lexer::Token::FloatLiteral(Some((val, suffix))) => {
             parser.advance();
@@ -132,6 +166,10 @@ pub fn parse_primary(parser: &mut Parser) -> cayResult<Expr> {
             parser.consume(&crate::lexer::Token::RParen, "期望 ')'\n提示: 括号表达式应以 ')' 结束，例如: (x + y)")?;
             Ok(expr)
         }
+        crate::lexer::Token::Switch => {
+            // switch 表达式: switch (x) { case 1 -> a; default -> b; }
+            parse_switch_expression(parser)
+        }
         crate::lexer::Token::LBrace => {
             // 数组初始化: {1, 2, 3}
             parser.advance(); // 跳过 '{'
@@ -251,6 +289,8 @@ pub fn parse_primary(parser: &mut Parser) -> cayResult<Expr> {
                     "void 表示无返回值。可能的问题:\n    - 在表达式位置使用了类型关键字\n    - void 只能用于方法返回类型，不能作为变量类型"
                 ),
                 crate::lexer::Token::Int | crate::lexer::Token::Long | 
+                crate::lexer::Token::Byte | crate::lexer::Token::UByte | crate::lexer::Token::Short |
+                crate::lexer::Token::UShort | crate::lexer::Token::UInt | crate::lexer::Token::ULong |
                 crate::lexer::Token::Float | crate::lexer::Token::Double |
                 crate::lexer::Token::Bool | crate::lexer::Token::Char |
                 crate::lexer::Token::String => (
@@ -278,10 +318,6 @@ pub fn parse_primary(parser: &mut Parser) -> cayResult<Expr> {
                     "关键字(do)".to_string(),
                     "do 用于 do-while 循环。可能的问题:\n    - 在表达式位置使用了 do 语句\n    - do-while 语句格式错误，如: x = do 应该是 do { ... } while (cond);"
                 ),
-                crate::lexer::Token::Switch => (
-                    "关键字(switch)".to_string(),
-                    "switch 用于多分支选择。可能的问题:\n    - 在表达式位置使用了 switch 语句\n    - switch 语句格式错误，如: x = switch 应该是 switch (expr) { case 1: ... }"
-                ),
                 crate::lexer::Token::Case => (
                     "关键字(case)".to_string(),
                     "case 用于 switch 语句的分支。可能的问题:\n    - case 不在 switch 语句内\n    - case 后缺少常量值，如: case: 应该是 case 1:"
@@ -359,7 +395,7 @@ pub fn parse_primary(parser: &mut Parser) -> cayResult<Expr> {
                     }
                 }
             };
-            Err(parser.error(&format!(
+            Err(parser.error(ErrorCodes::PARSER_INVALID_EXPRESSION, &format!(
                 "期望表达式，但遇到了 {}\n提示: {}",
                 token_desc, suggestion
             )))
@@ -386,6 +422,7 @@ pub fn parse_new_expression(parser: &mut Parser, loc: crate::error::SourceLocati
                     // 空维度，只有在不是第一个维度时才允许
                     if sizes.is_empty() {
                         return Err(parser.error(
+                            ErrorCodes::PARSER_INVALID_EXPRESSION,
                             "数组第一个维度必须指定大小\n\
                             提示: 不规则数组语法为 new Type[size][]，第一个维度必须有大小"
                         ));
@@ -422,16 +459,24 @@ pub fn parse_new_expression(parser: &mut Parser, loc: crate::error::SourceLocati
 
         // 如果接下来是 '(' 则为对象创建: new ClassName(...)
         if parser.match_token(&crate::lexer::Token::LParen) {
-            // element_type should be Type::Object(name)
+            // element_type should be Type::Object(name)，结构体构造同样写作 new Point(...)
             match base_element_type {
-                crate::types::Type::Object(name) => {
+                crate::types::Type::Object(name) | crate::types::Type::Struct(name) => {
+                    let args = parse_arguments(parser)?;
+                    parser.consume(&crate::lexer::Token::RParen, "Expected ')' after arguments")?;
+                    let body = parse_anonymous_class_body(parser)?;
+                    return Ok(Expr::New(NewExpr { class_name: name, type_args: Vec::new(), args, body, loc }));
+                }
+                crate::types::Type::Generic(name, type_args) => {
+                    // 泛型类实例化: new Box<int>(...)
                     let args = parse_arguments(parser)?;
                     parser.consume(&crate::lexer::Token::RParen, "Expected ')' after arguments")?;
-                    return Ok(Expr::New(NewExpr { class_name: name, args, loc }));
+                    let body = parse_anonymous_class_body(parser)?;
+                    return Ok(Expr::New(NewExpr { class_name: name, type_args, args, body, loc }));
                 }
                 _ => {
                     let type_name = format!("{:?}", base_element_type);
-                    return Err(parser.error(&format!(
+                    return Err(parser.error(ErrorCodes::PARSER_INVALID_EXPRESSION, &format!(
                         "类型 {} 不能使用 'new Type()' 构造\n\
                         提示: 只有类类型可以使用 'new' 构造，基本类型应使用数组语法: new {}[size]",
                         type_name, type_name
@@ -442,6 +487,7 @@ pub fn parse_new_expression(parser: &mut Parser, loc: crate::error::SourceLocati
 
         // 否则既不是数组也不是对象构造，报错
         return Err(parser.error(
+            ErrorCodes::PARSER_EXPECTED_PAREN,
             "期望 '[' 或 '('\n\
             提示: new 表达式后应跟:\
             - 数组创建: new Type[size]\n\
@@ -454,18 +500,42 @@ pub fn parse_new_expression(parser: &mut Parser, loc: crate::error::SourceLocati
     parser.consume(&crate::lexer::Token::LParen, "期望 '('\n提示: 类名后应跟 '(' 开始参数列表，例如: new MyClass()")?;
     let args = parse_arguments(parser)?;
     parser.consume(&crate::lexer::Token::RParen, "期望 ')'\n提示: 参数列表应以 ')' 结束")?;
+    let body = parse_anonymous_class_body(parser)?;
     Ok(Expr::New(NewExpr {
         class_name,
+        type_args: Vec::new(),
         args,
+        body,
         loc,
     }))
 }
 
+/// 解析 new 表达式后可选的匿名类体: new Runnable() { public void run() { ... } }
+fn parse_anonymous_class_body(parser: &mut Parser) -> cayResult<Option<Vec<ClassMember>>> {
+    if !parser.match_token(&crate::lexer::Token::LBrace) {
+        return Ok(None);
+    }
+    let mut members = Vec::new();
+    while !parser.check(&crate::lexer::Token::RBrace) && !parser.is_at_end() {
+        if let Some(member) = parse_member_recovering(parser, parse_class_member)? {
+            push_member(&mut members, member);
+        }
+    }
+    parser.consume(&crate::lexer::Token::RBrace, "期望 '}'\n提示: 匿名类体应以 '}' 结束，例如: new Runnable() { public void run() { ... } }")?;
+    Ok(Some(members))
+}
+
 /// 获取类型名称
 fn get_type_name(token: &crate::lexer::Token) -> &'static str {
     match token {
         crate::lexer::Token::Int => "int",
         crate::lexer::Token::Long => "long",
+        crate::lexer::Token::Byte => "byte",
+        crate::lexer::Token::UByte => "ubyte",
+        crate::lexer::Token::Short => "short",
+        crate::lexer::Token::UShort => "ushort",
+        crate::lexer::Token::UInt => "uint",
+        crate::lexer::Token::ULong => "ulong",
         crate::lexer::Token::Float => "float",
         crate::lexer::Token::Double => "double",
         crate::lexer::Token::Bool => "bool",
@@ -481,6 +551,12 @@ pub fn parse_base_type(parser: &mut Parser) -> cayResult<Type> {
     match parser.current_token() {
         crate::lexer::Token::Int => { parser.advance(); Ok(Type::Int32) }
         crate::lexer::Token::Long => { parser.advance(); Ok(Type::Int64) }
+        crate::lexer::Token::Byte => { parser.advance(); Ok(Type::Int8) }
+        crate::lexer::Token::UByte => { parser.advance(); Ok(Type::UInt8) }
+        crate::lexer::Token::Short => { parser.advance(); Ok(Type::Int16) }
+        crate::lexer::Token::UShort => { parser.advance(); Ok(Type::UInt16) }
+        crate::lexer::Token::UInt => { parser.advance(); Ok(Type::UInt32) }
+        crate::lexer::Token::ULong => { parser.advance(); Ok(Type::UInt64) }
         crate::lexer::Token::Float => { parser.advance(); Ok(Type::Float32) }
         crate::lexer::Token::Double => { parser.advance(); Ok(Type::Float64) }
         crate::lexer::Token::Bool => { parser.advance(); Ok(Type::Bool) }
@@ -489,11 +565,21 @@ pub fn parse_base_type(parser: &mut Parser) -> cayResult<Type> {
         crate::lexer::Token::Identifier(name) => {
             let name = name.clone();
             parser.advance();
-            Ok(Type::Object(name))
+            if parser.is_type_param(&name) {
+                Ok(Type::TypeParam(name))
+            } else if parser.is_generic_type(&name) && parser.check(&crate::lexer::Token::Lt) {
+                Ok(Type::Generic(name, parse_type_args(parser)?))
+            } else if parser.is_enum_type(&name) {
+                Ok(Type::Enum(name))
+            } else if parser.is_struct_type(&name) {
+                Ok(Type::Struct(name))
+            } else {
+                Ok(Type::Object(name))
+            }
         }
         _ => {
             let current_token = super::super::utils::get_token_name(parser.current_token());
-            Err(parser.error(&format!(
+            Err(parser.error(ErrorCodes::PARSER_EXPECTED_TYPE, &format!(
                 "期望基本类型或类名，但遇到了 {}\n\
                 提示: 基本类型包括 int, long, float, double, bool, char, String",
                 current_token
@@ -92,8 +92,10 @@ pub fn parse_unary(parser: &mut Parser) -> cayResult<Expr> {
             // 解析类型
             match parse_type(parser) {
                 Ok(target_type) => {
-                    // 期望 RParen
-                    if parser.check(&crate::lexer::Token::RParen) {
+                    // 期望 RParen；`(x) -> ...` 是单参数 Lambda 而不是类型转换
+                    if parser.check(&crate::lexer::Token::RParen)
+                        && !parser.check_next(&crate::lexer::Token::Arrow)
+                    {
                         parser.advance();
                         // 成功解析类型转换，解析后面的表达式
                         let expr = parse_unary(parser)?;
@@ -8,11 +8,17 @@ mod types;
 mod statements;
 mod expressions;
 mod utils;
+mod packages;
+mod nested;
+mod records;
+mod properties;
+mod annotations;
+mod recovery;
 
 use crate::lexer::TokenWithLocation;
-use crate::ast::Program;
+use crate::ast::{Program, ImportDecl};
 use crate::error::cayResult;
-use crate::diagnostic::DiagnosticCollector;
+use crate::diagnostic::{Diagnostic, DiagnosticCollector, ErrorCodes};
 
 /// 语法分析器
 pub struct Parser {
@@ -26,29 +32,71 @@ pub struct Parser {
     source: Option<String>,
     /// 类型别名映射: 别名名称 -> 目标类型
     type_aliases: std::collections::HashMap<String, crate::types::Type>,
+    /// 源码中声明的泛型类/接口名（预扫描得到，用于区分 '<' 是泛型参数还是比较运算符）
+    generic_types: std::collections::HashSet<String>,
+    /// 源码中声明的枚举名（预扫描得到，类型位置上解析为 Type::Enum）
+    enum_types: std::collections::HashSet<String>,
+    /// 源码及导入模块中声明的接口名（区分匿名类是实现接口还是继承类）
+    interface_types: std::collections::HashSet<String>,
+    /// 源码及导入模块中声明的结构体名（类型位置上解析为 Type::Struct）
+    struct_types: std::collections::HashSet<String>,
+    /// 当前可见的泛型类型参数（类和泛型方法各占一层）
+    type_param_scopes: Vec<Vec<String>>,
+    /// 本文件中记录的组件，解析结束时据此展开记录的成员
+    records: std::collections::HashMap<String, records::RecordShape>,
+    /// 导入模块及本文件中的类、结构体信息（生成记录的 equals/hashCode/toString 时使用）
+    component_types: records::ComponentTypes,
+    /// 顶层声明前已解析、尚未交给类或接口声明的注解
+    pending_annotations: Vec<crate::ast::Annotation>,
+    /// 错误恢复：为 Some 时出错的语句、成员和顶层声明被跳过，错误记录在其中（见 recovery 模块）
+    recovered_errors: Option<Vec<crate::error::cayError>>,
 }
 
 impl Parser {
     /// 创建新的语法分析器
     pub fn new(tokens: Vec<TokenWithLocation>) -> Self {
-        Self { 
+        let mut parser = Self { 
             tokens, 
             pos: 0,
             diagnostics: DiagnosticCollector::new(),
             source: None,
             type_aliases: std::collections::HashMap::new(),
-        }
+            generic_types: std::collections::HashSet::new(),
+            enum_types: std::collections::HashSet::new(),
+            interface_types: std::collections::HashSet::new(),
+            struct_types: std::collections::HashSet::new(),
+            type_param_scopes: Vec::new(),
+            records: std::collections::HashMap::new(),
+            component_types: records::ComponentTypes::default(),
+            pending_annotations: Vec::new(),
+            recovered_errors: None,
+        };
+        nested::resolve_nested_class_names(&mut parser.tokens);
+        parser.collect_generic_types();
+        parser
     }
 
     /// 创建带源代码的语法分析器（用于内联IR解析）
     pub fn with_source(tokens: Vec<TokenWithLocation>, source: String) -> Self {
-        Self { 
+        let mut parser = Self { 
             tokens, 
             pos: 0,
             diagnostics: DiagnosticCollector::new(),
             source: Some(source),
             type_aliases: std::collections::HashMap::new(),
-        }
+            generic_types: std::collections::HashSet::new(),
+            enum_types: std::collections::HashSet::new(),
+            interface_types: std::collections::HashSet::new(),
+            struct_types: std::collections::HashSet::new(),
+            type_param_scopes: Vec::new(),
+            records: std::collections::HashMap::new(),
+            component_types: records::ComponentTypes::default(),
+            pending_annotations: Vec::new(),
+            recovered_errors: None,
+        };
+        nested::resolve_nested_class_names(&mut parser.tokens);
+        parser.collect_generic_types();
+        parser
     }
 
     /// 获取源代码
@@ -65,112 +113,173 @@ impl Parser {
     pub fn parse(&mut self) -> cayResult<Program> {
         let mut classes = Vec::new();
         let mut interfaces = Vec::new();
+        let mut enums = Vec::new();
+        let mut structs = Vec::new();
         let mut top_level_functions = Vec::new();
         let mut extern_declarations = Vec::new();
         let mut type_aliases = Vec::new();
 
+        let (package, imports) = packages::parse_module_header(self)?;
+
         while !self.is_at_end() {
-            if self.check(&crate::lexer::Token::Interface)
-                || (self.check(&crate::lexer::Token::Public) && self.check_next(&crate::lexer::Token::Interface))
-            {
-                interfaces.push(self.parse_interface()?);
-            } else if self.check(&crate::lexer::Token::Class)
-                || self.check(&crate::lexer::Token::Private)
-                || self.check(&crate::lexer::Token::Protected)
-                || self.check(&crate::lexer::Token::AtMain)
-            {
-                classes.push(self.parse_class()?);
-            } else if self.check(&crate::lexer::Token::Public) {
-                // 检查是否是顶层函数: public 返回类型 函数名()
-                if self.check_top_level_function() {
-                    top_level_functions.push(self.parse_top_level_function()?);
+            // 出错的顶层声明在启用错误恢复时被跳过
+            recovery::parse_declaration_recovering(self, |parser| {
+                // 顶层的注解由随后的类或接口声明取走，其他声明不能带注解
+                parser.pending_annotations = annotations::parse_annotations(parser)?;
+                if parser.check_interface_declaration() {
+                    interfaces.push(parser.parse_interface()?);
+                } else if parser.check(&crate::lexer::Token::Enum)
+                    || (parser.check(&crate::lexer::Token::Public) && parser.check_next(&crate::lexer::Token::Enum))
+                {
+                    enums.push(parser.parse_enum()?);
+                } else if parser.check(&crate::lexer::Token::Struct)
+                    || (parser.check(&crate::lexer::Token::Public) && parser.check_next(&crate::lexer::Token::Struct))
+                {
+                    structs.push(classes::parse_struct(parser)?);
+                } else if parser.check(&crate::lexer::Token::Record)
+                    || (parser.check(&crate::lexer::Token::Public) && parser.check_next(&crate::lexer::Token::Record))
+                {
+                    let (class, shape) = records::parse_record(parser)?;
+                    parser.records.insert(class.name.clone(), shape);
+                    classes.push(class);
+                } else if parser.check(&crate::lexer::Token::Class)
+                    || parser.check(&crate::lexer::Token::Sealed)
+                    || parser.check(&crate::lexer::Token::Private)
+                    || parser.check(&crate::lexer::Token::Protected)
+                    || parser.check(&crate::lexer::Token::AtMain)
+                {
+                    classes.push(parser.parse_class()?);
+                } else if parser.check(&crate::lexer::Token::Public) {
+                    // 检查是否是顶层函数: public 返回类型 函数名()
+                    if parser.check_top_level_function() {
+                        top_level_functions.push(parser.parse_top_level_function()?);
+                    } else {
+                        // 否则可能是 public class
+                        classes.push(parser.parse_class()?);
+                    }
+                } else if parser.check_top_level_function_return_type() {
+                    // 没有 public 修饰符的顶层函数
+                    top_level_functions.push(parser.parse_top_level_function_without_public()?);
+                } else if parser.check(&crate::lexer::Token::Extern) {
+                    extern_declarations.push(parser.parse_extern_declaration()?);
+                } else if parser.check(&crate::lexer::Token::Alias) {
+                    type_aliases.push(parser.parse_type_alias()?);
+                } else if parser.check(&crate::lexer::Token::Package) || parser.check(&crate::lexer::Token::Import) {
+                    let kw = utils::get_token_name(utils::current_token(parser));
+                    return Err(parser.error(ErrorCodes::PARSER_INVALID_IMPORT, &format!(
+                        "{} 声明位置错误\n提示: 包声明必须是文件中的第一条声明，导入声明必须紧随其后并位于所有类型声明之前",
+                        kw
+                    )));
                 } else {
-                    // 否则可能是 public class
-                    classes.push(self.parse_class()?);
+                    let current_token = utils::current_token(parser);
+                    let (token_desc, suggestion) = match current_token {
+                        crate::lexer::Token::Semicolon => (
+                            "分号(;)".to_string(),
+                            "顶层声明不能是空语句。可能的问题:\n    - 多余的逗号或分号\n    - 缺少声明内容".to_string()
+                        ),
+                        crate::lexer::Token::LBrace => (
+                            "左花括号({)".to_string(),
+                            "顶层声明不能以代码块开始。可能的问题:\n    - 缺少类或函数声明\n    - 代码块应在函数或方法体内".to_string()
+                        ),
+                        crate::lexer::Token::RBrace => (
+                            "右花括号(})".to_string(),
+                            "文件提前结束或多余的右花括号。可能的问题:\n    - 前面的声明缺少匹配的左花括号\n    - 多余的右花括号".to_string()
+                        ),
+                        crate::lexer::Token::LParen => (
+                            "左圆括号(()".to_string(),
+                            "顶层声明不能以括号开始。可能的问题:\n    - 缺少函数声明\n    - Lambda 表达式不能作为顶层声明".to_string()
+                        ),
+                        crate::lexer::Token::If | crate::lexer::Token::While |
+                        crate::lexer::Token::For | crate::lexer::Token::Do |
+                        crate::lexer::Token::Switch | crate::lexer::Token::Return |
+                        crate::lexer::Token::Break | crate::lexer::Token::Continue => {
+                            let kw = format!("{:?}", current_token).to_lowercase();
+                            (
+                                format!("关键字({})", kw),
+                                format!("{} 是控制流语句，不能作为顶层声明。可能的问题:\n    - 控制流语句只能在函数或方法体内使用\n    - 缺少函数声明", kw)
+                            )
+                        }
+                        crate::lexer::Token::Int | crate::lexer::Token::Long |
+                        crate::lexer::Token::Byte | crate::lexer::Token::UByte | crate::lexer::Token::Short |
+                        crate::lexer::Token::UShort | crate::lexer::Token::UInt | crate::lexer::Token::ULong |
+                        crate::lexer::Token::Float | crate::lexer::Token::Double |
+                        crate::lexer::Token::Bool | crate::lexer::Token::Char |
+                        crate::lexer::Token::String => {
+                            let kw = format!("{:?}", current_token).to_lowercase();
+                            (
+                                format!("关键字({})", kw),
+                                format!("类型 '{}' 不能单独作为顶层声明。可能的问题:\n    - 缺少变量或函数声明，如: {} x; 或 {} main() {{ ... }}\n    - 类型后缺少标识符", kw, kw, kw)
+                            )
+                        }
+                        crate::lexer::Token::Identifier(name) => (
+                            format!("标识符('{}')", name),
+                            format!("'{}' 不能作为顶层声明开始。可能的问题:\n    - 需要先声明类或函数\n    - 语句位置错误，应在函数体内\n    - 如果是方法调用，需要在函数或 main 函数中执行", name)
+                        ),
+                        crate::lexer::Token::IntegerLiteral(Some((val, _))) => (
+                            format!("整数({})", val),
+                            "整数字面量不能作为顶层声明。可能的问题:\n    - 缺少变量声明，如: int x = 10;\n    - 语句位置错误，应在函数体内".to_string()
+                        ),
+                        crate::lexer::Token::StringLiteral(Some(s)) => (
+                            format!("字符串(\"{}\")", s),
+                            "字符串字面量不能作为顶层声明。可能的问题:\n    - 缺少变量声明，如: String s = \"hello\";\n    - 语句位置错误，应在函数体内".to_string()
+                        ),
+                        crate::lexer::Token::Private | crate::lexer::Token::Protected |
+                        crate::lexer::Token::Static | crate::lexer::Token::Final |
+                        crate::lexer::Token::Abstract => {
+                            let kw = format!("{:?}", current_token).to_lowercase();
+                            (
+                                format!("关键字({})", kw),
+                                format!("修饰符 '{}' 不能单独作为顶层声明。可能的问题:\n    - 修饰符后缺少类或函数声明\n    - 顶层声明应以 class、interface 或 public 开始", kw)
+                            )
+                        }
+                        _ => {
+                            let token_name = utils::get_token_name(current_token);
+                            (
+                                token_name.clone(),
+                                format!("{} 不能作为顶层声明。有效的顶层声明包括:\n    - 类: class MyClass {{ ... }}\n    - 接口: interface MyInterface {{ ... }}\n    - 外部函数: extern {{ ... }}\n    - 类型别名: type MyType = int;\n    - 主函数: public int main() {{ ... }}", token_name)
+                            )
+                        }
+                    };
+                    return Err(parser.error(ErrorCodes::PARSER_UNEXPECTED_TOKEN, &format!(
+                        "期望类、接口、extern 声明或顶层函数声明，但遇到了 {}\n提示: {}",
+                        token_desc, suggestion
+                    )));
                 }
-            } else if self.check_top_level_function_return_type() {
-                // 没有 public 修饰符的顶层函数
-                top_level_functions.push(self.parse_top_level_function_without_public()?);
-            } else if self.check(&crate::lexer::Token::Extern) {
-                extern_declarations.push(self.parse_extern_declaration()?);
-            } else if self.check(&crate::lexer::Token::Alias) {
-                type_aliases.push(self.parse_type_alias()?);
-            } else {
-                let current_token = utils::current_token(self);
-                let (token_desc, suggestion) = match current_token {
-                    crate::lexer::Token::Semicolon => (
-                        "分号(;)".to_string(),
-                        "顶层声明不能是空语句。可能的问题:\n    - 多余的逗号或分号\n    - 缺少声明内容".to_string()
-                    ),
-                    crate::lexer::Token::LBrace => (
-                        "左花括号({)".to_string(),
-                        "顶层声明不能以代码块开始。可能的问题:\n    - 缺少类或函数声明\n    - 代码块应在函数或方法体内".to_string()
-                    ),
-                    crate::lexer::Token::RBrace => (
-                        "右花括号(})".to_string(),
-                        "文件提前结束或多余的右花括号。可能的问题:\n    - 前面的声明缺少匹配的左花括号\n    - 多余的右花括号".to_string()
-                    ),
-                    crate::lexer::Token::LParen => (
-                        "左圆括号(()".to_string(),
-                        "顶层声明不能以括号开始。可能的问题:\n    - 缺少函数声明\n    - Lambda 表达式不能作为顶层声明".to_string()
-                    ),
-                    crate::lexer::Token::If | crate::lexer::Token::While |
-                    crate::lexer::Token::For | crate::lexer::Token::Do |
-                    crate::lexer::Token::Switch | crate::lexer::Token::Return |
-                    crate::lexer::Token::Break | crate::lexer::Token::Continue => {
-                        let kw = format!("{:?}", current_token).to_lowercase();
-                        (
-                            format!("关键字({})", kw),
-                            format!("{} 是控制流语句，不能作为顶层声明。可能的问题:\n    - 控制流语句只能在函数或方法体内使用\n    - 缺少函数声明", kw)
-                        )
-                    }
-                    crate::lexer::Token::Int | crate::lexer::Token::Long |
-                    crate::lexer::Token::Float | crate::lexer::Token::Double |
-                    crate::lexer::Token::Bool | crate::lexer::Token::Char |
-                    crate::lexer::Token::String => {
-                        let kw = format!("{:?}", current_token).to_lowercase();
-                        (
-                            format!("关键字({})", kw),
-                            format!("类型 '{}' 不能单独作为顶层声明。可能的问题:\n    - 缺少变量或函数声明，如: {} x; 或 {} main() {{ ... }}\n    - 类型后缺少标识符", kw, kw, kw)
-                        )
-                    }
-                    crate::lexer::Token::Identifier(name) => (
-                        format!("标识符('{}')", name),
-                        format!("'{}' 不能作为顶层声明开始。可能的问题:\n    - 需要先声明类或函数\n    - 语句位置错误，应在函数体内\n    - 如果是方法调用，需要在函数或 main 函数中执行", name)
-                    ),
-                    crate::lexer::Token::IntegerLiteral(Some((val, _))) => (
-                        format!("整数({})", val),
-                        "整数字面量不能作为顶层声明。可能的问题:\n    - 缺少变量声明，如: int x = 10;\n    - 语句位置错误，应在函数体内".to_string()
-                    ),
-                    crate::lexer::Token::StringLiteral(Some(s)) => (
-                        format!("字符串(\"{}\")", s),
-                        "字符串字面量不能作为顶层声明。可能的问题:\n    - 缺少变量声明，如: String s = \"hello\";\n    - 语句位置错误，应在函数体内".to_string()
-                    ),
-                    crate::lexer::Token::Private | crate::lexer::Token::Protected |
-                    crate::lexer::Token::Static | crate::lexer::Token::Final |
-                    crate::lexer::Token::Abstract => {
-                        let kw = format!("{:?}", current_token).to_lowercase();
-                        (
-                            format!("关键字({})", kw),
-                            format!("修饰符 '{}' 不能单独作为顶层声明。可能的问题:\n    - 修饰符后缺少类或函数声明\n    - 顶层声明应以 class、interface 或 public 开始", kw)
-                        )
-                    }
-                    _ => {
-                        let token_name = utils::get_token_name(current_token);
-                        (
-                            token_name.clone(),
-                            format!("{} 不能作为顶层声明。有效的顶层声明包括:\n    - 类: class MyClass {{ ... }}\n    - 接口: interface MyInterface {{ ... }}\n    - 外部函数: extern {{ ... }}\n    - 类型别名: type MyType = int;\n    - 主函数: public int main() {{ ... }}", token_name)
-                        )
-                    }
-                };
-                return Err(self.error(&format!(
-                    "期望类、接口、extern 声明或顶层函数声明，但遇到了 {}\n提示: {}",
-                    token_desc, suggestion
-                )));
+                if let Some(annotation) = parser.pending_annotations.first() {
+                    return Err(annotations::misplaced_annotation_error(annotation));
+                }
+                Ok(())
+            })?;
+        }
+
+        self.component_types.add_declarations(&classes, &structs);
+        self.component_types.add_records(self.records.keys());
+        records::lower_records(&mut classes, &self.records, &self.component_types)?;
+        let classes = nested::lower_nested_classes(classes, &self.interface_types)?;
+
+        let mut declaration_packages = std::collections::HashMap::new();
+        if let Some(package) = &package {
+            let names = classes.iter().map(|c| &c.name)
+                .chain(interfaces.iter().map(|i| &i.name))
+                .chain(enums.iter().map(|e| &e.name))
+                .chain(structs.iter().map(|s| &s.name));
+            for name in names {
+                declaration_packages.insert(name.clone(), package.clone());
             }
         }
 
-        Ok(Program { classes, interfaces, top_level_functions, extern_declarations, type_aliases })
+        Ok(Program {
+            classes,
+            interfaces,
+            enums,
+            structs,
+            top_level_functions,
+            extern_declarations,
+            type_aliases,
+            package,
+            imports,
+            declaration_packages,
+        })
     }
 
     // 类解析方法
@@ -182,6 +291,10 @@ impl Parser {
         classes::parse_interface(self)
     }
 
+    fn parse_enum(&mut self) -> cayResult<crate::ast::EnumDecl> {
+        classes::parse_enum(self)
+    }
+
     fn parse_class_member(&mut self) -> cayResult<crate::ast::ClassMember> {
         classes::parse_class_member(self)
     }
@@ -366,8 +479,18 @@ impl Parser {
         utils::consume_identifier(self, message)
     }
     
-    fn error(&self, message: &str) -> crate::error::cayError {
-        utils::error(self, message)
+    fn error(&self, code: &'static str, message: &str) -> crate::error::cayError {
+        utils::error(self, code, message)
+    }
+
+    /// 检查是否是接口声明（[public] [sealed] interface）
+    fn check_interface_declaration(&self) -> bool {
+        let mut pos = self.pos;
+        while matches!(self.tokens.get(pos).map(|t| &t.token),
+            Some(crate::lexer::Token::Public | crate::lexer::Token::Sealed)) {
+            pos += 1;
+        }
+        matches!(self.tokens.get(pos).map(|t| &t.token), Some(crate::lexer::Token::Interface))
     }
 
     /// 检查是否是顶层函数（public 返回类型 函数名()）
@@ -387,6 +510,8 @@ impl Parser {
         match &self.tokens[pos].token {
             crate::lexer::Token::Int | crate::lexer::Token::Void |
             crate::lexer::Token::Long | crate::lexer::Token::Float |
+            crate::lexer::Token::Byte | crate::lexer::Token::UByte | crate::lexer::Token::Short |
+            crate::lexer::Token::UShort | crate::lexer::Token::UInt | crate::lexer::Token::ULong |
             crate::lexer::Token::Double | crate::lexer::Token::Bool |
             crate::lexer::Token::Char | crate::lexer::Token::String |
             crate::lexer::Token::Identifier(_) => {}
@@ -423,6 +548,8 @@ impl Parser {
         match &self.tokens[pos].token {
             crate::lexer::Token::Int | crate::lexer::Token::Void |
             crate::lexer::Token::Long | crate::lexer::Token::Float |
+            crate::lexer::Token::Byte | crate::lexer::Token::UByte | crate::lexer::Token::Short |
+            crate::lexer::Token::UShort | crate::lexer::Token::UInt | crate::lexer::Token::ULong |
             crate::lexer::Token::Double | crate::lexer::Token::Bool |
             crate::lexer::Token::Char | crate::lexer::Token::String => {}
             _ => return false,
@@ -603,7 +730,7 @@ impl Parser {
                     self.advance(); // 消费 ...
                     params.push(crate::types::ParameterInfo::new_varargs("...".to_string(), crate::types::Type::CVoid));
                     if self.check(&Token::Comma) {
-                        return Err(self.error("可变参数必须是最后一个参数\n提示: 可变参数(...)必须放在参数列表的最后"));
+                        return Err(self.error(ErrorCodes::PARSER_INVALID_DECLARATION, "可变参数必须是最后一个参数\n提示: 可变参数(...)必须放在参数列表的最后"));
                     }
                     break;
                 }
@@ -620,7 +747,7 @@ impl Parser {
                     let name = self.consume_identifier("期望参数名\n提示: 可变参数需要名称，例如: int... args")?;
                     params.push(crate::types::ParameterInfo::new_varargs(name, param_type));
                     if self.match_token(&Token::Comma) {
-                        return Err(self.error("可变参数必须是最后一个参数\n提示: 可变参数(...)必须放在参数列表的最后"));
+                        return Err(self.error(ErrorCodes::PARSER_INVALID_DECLARATION, "可变参数必须是最后一个参数\n提示: 可变参数(...)必须放在参数列表的最后"));
                     }
                     break;
                 } else {
@@ -714,6 +841,115 @@ impl Parser {
         self.type_aliases.get(name).cloned()
     }
 
+    /// 预扫描令牌流，收集所有泛型类/接口名（class Name< 或 interface Name<）以及枚举名（enum Name）
+    ///
+    /// 内置泛型枚举 Result 和 Option 未被同名类型遮蔽时也视为泛型类型。
+    fn collect_generic_types(&mut self) {
+        let mut declared = std::collections::HashSet::new();
+        for window in self.tokens.windows(2) {
+            if let (
+                crate::lexer::Token::Class | crate::lexer::Token::Interface | crate::lexer::Token::Enum
+                | crate::lexer::Token::Record,
+                crate::lexer::Token::Identifier(name),
+            ) = (&window[0].token, &window[1].token) {
+                declared.insert(name.clone());
+            }
+        }
+        for builtin in ["Result", "Option"] {
+            if !declared.contains(builtin) {
+                self.generic_types.insert(builtin.to_string());
+            }
+        }
+
+        for window in self.tokens.windows(3) {
+            let name = match &window[1].token {
+                crate::lexer::Token::Identifier(name) => name.clone(),
+                _ => continue,
+            };
+            let is_generic = matches!(window[2].token, crate::lexer::Token::Lt);
+            match window[0].token {
+                crate::lexer::Token::Class if is_generic => {
+                    self.generic_types.insert(name);
+                }
+                crate::lexer::Token::Interface => {
+                    if is_generic {
+                        self.generic_types.insert(name.clone());
+                    }
+                    self.interface_types.insert(name);
+                }
+                crate::lexer::Token::Enum => {
+                    self.enum_types.insert(name);
+                }
+                crate::lexer::Token::Struct => {
+                    self.struct_types.insert(name);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// 登记导入模块中声明的泛型类/接口、枚举、接口、结构体和嵌套类，使其在本文件中可用
+    pub fn declare_imported_types(&mut self, dependencies: &[Program]) {
+        let nested_classes: std::collections::HashSet<String> = dependencies.iter()
+            .flat_map(|program| program.classes.iter())
+            .filter(|c| c.enclosing_class.is_some())
+            .map(|c| c.name.clone())
+            .collect();
+        nested::collapse_qualified_class_names(&mut self.tokens, &nested_classes);
+
+        for program in dependencies {
+            let generic_classes = program.classes.iter()
+                .filter(|c| !c.type_params.is_empty())
+                .map(|c| &c.name);
+            let generic_interfaces = program.interfaces.iter()
+                .filter(|i| !i.type_params.is_empty())
+                .map(|i| &i.name);
+            for name in generic_classes.chain(generic_interfaces) {
+                self.generic_types.insert(name.clone());
+            }
+            for enum_decl in &program.enums {
+                self.enum_types.insert(enum_decl.name.clone());
+            }
+            for interface in &program.interfaces {
+                self.interface_types.insert(interface.name.clone());
+            }
+            for struct_decl in &program.structs {
+                self.struct_types.insert(struct_decl.name.clone());
+            }
+            self.component_types.add_declarations(&program.classes, &program.structs);
+        }
+    }
+
+    /// 检查名称是否是已声明的枚举
+    pub fn is_enum_type(&self, name: &str) -> bool {
+        self.enum_types.contains(name)
+    }
+
+    /// 检查名称是否是已声明的结构体
+    pub fn is_struct_type(&self, name: &str) -> bool {
+        self.struct_types.contains(name)
+    }
+
+    /// 检查名称是否是已声明的泛型类/接口
+    pub fn is_generic_type(&self, name: &str) -> bool {
+        self.generic_types.contains(name)
+    }
+
+    /// 检查名称是否是当前可见的泛型类型参数
+    pub fn is_type_param(&self, name: &str) -> bool {
+        self.type_param_scopes.iter().any(|scope| scope.iter().any(|p| p == name))
+    }
+
+    /// 进入泛型类型参数作用域
+    pub fn push_type_params(&mut self, params: &[crate::types::TypeParamInfo]) {
+        self.type_param_scopes.push(params.iter().map(|p| p.name.clone()).collect());
+    }
+
+    /// 退出泛型类型参数作用域
+    pub fn pop_type_params(&mut self) {
+        self.type_param_scopes.pop();
+    }
+
     /// 解析类型或函数指针类型
     fn parse_type_or_fn_ptr(&mut self) -> cayResult<crate::types::Type> {
         // 检查是否是函数指针类型: fn(...) -> ReturnType
@@ -798,3 +1034,37 @@ pub fn parse_with_source(tokens: Vec<TokenWithLocation>, source: String) -> cayR
     let mut parser = Parser::with_source(tokens, source);
     parser.parse()
 }
+
+/// 解析令牌流生成 AST，导入模块中的泛型类型和枚举在类型位置上可用
+pub fn parse_with_dependencies(tokens: Vec<TokenWithLocation>, source: String, dependencies: &[Program]) -> cayResult<Program> {
+    let mut parser = Parser::with_source(tokens, source);
+    parser.declare_imported_types(dependencies);
+    parser.parse()
+}
+
+/// 解析令牌流生成 AST，语法错误后恢复并继续解析，所有语法错误加入 `diagnostics`
+///
+/// 出错的语句在 AST 中留下 `Stmt::Error`，出错的成员和顶层声明被跳过。
+/// 有语法错误时返回的 AST 只用于编辑器等工具，不应继续做语义分析；无法恢复的错误（如包声明错误）返回 None。
+pub fn parse_recovering(tokens: Vec<TokenWithLocation>, source: String, dependencies: &[Program], diagnostics: &mut DiagnosticCollector) -> Option<Program> {
+    let mut parser = Parser::with_source(tokens, source);
+    parser.declare_imported_types(dependencies);
+    parser.enable_recovery();
+    let result = parser.parse();
+    for error in parser.take_recovered_errors() {
+        diagnostics.add(Diagnostic::from_error(&error));
+    }
+    match result {
+        Ok(program) => Some(program),
+        Err(error) => {
+            diagnostics.add(Diagnostic::from_error(&error));
+            None
+        }
+    }
+}
+
+/// 只解析文件开头的包声明和导入声明（用于在解析全文前加载依赖模块）
+pub fn parse_module_header(tokens: &[TokenWithLocation]) -> cayResult<(Option<String>, Vec<ImportDecl>)> {
+    let mut parser = Parser::new(tokens.to_vec());
+    packages::parse_module_header(&mut parser)
+}
@@ -0,0 +1,859 @@
+//! 嵌套类、内部类和匿名类
+//!
+//! 解析前先按类体作用域把嵌套类名改写为 `Outer$Inner`（`Outer.Inner` 合并为一个标识符），
+//! 解析结束时把嵌套类和匿名类提升为顶层类，语义分析和代码生成只看到扁平的类列表：
+//!
+//! - 静态嵌套类只是换了名字，访问外部类的静态成员时改写为 `Outer.x`；
+//! - 内部类（非 static 的嵌套类）增加隐藏字段 `this$0` 指向外部类实例，构造函数增加对应的
+//!   首个参数，`new Inner()` 传入当前的 `this`，对外部类实例成员的访问改写为 `this.this$0.x`；
+//! - 匿名类依次命名为 `Outer$1`、`Outer$2`…，实现给定的接口或继承给定的类；在实例上下文中
+//!   创建时同样是内部类，用到的外层局部变量在创建时按值复制到同名字段中。
+
+use std::collections::{HashMap, HashSet};
+use crate::ast::*;
+use crate::error::{cayError, cayResult, parser_error_with_file, SourceLocation};
+use crate::lexer::{Token, TokenWithLocation};
+use crate::types::{ParameterInfo, Type};
+use crate::diagnostic::ErrorCodes;
+
+/// 内部类中指向外部类实例的隐藏字段（以及构造函数参数）名
+const OUTER_INSTANCE: &str = "this$0";
+
+/// 把类体中声明的嵌套类名改写为 `Outer$Inner`，并合并 `Outer.Inner` 形式的限定名
+///
+/// 名称由内向外逐层在类体中查找，嵌套类遮蔽同名的外层类或顶层类；跟在 '.' 后的成员名不改写。
+pub fn resolve_nested_class_names(tokens: &mut Vec<TokenWithLocation>) {
+    let (bodies, nested) = collect_class_bodies(tokens);
+    if nested.is_empty() {
+        return;
+    }
+
+    let mut scopes: Vec<(String, usize)> = Vec::new();
+    let mut depth = 0;
+    for i in 0..tokens.len() {
+        let renamed = match &tokens[i].token {
+            Token::LBrace => {
+                depth += 1;
+                if let Some(class_name) = bodies.get(&i) {
+                    scopes.push((class_name.clone(), depth));
+                }
+                None
+            }
+            Token::RBrace => {
+                if scopes.last().is_some_and(|(_, body_depth)| *body_depth == depth) {
+                    scopes.pop();
+                }
+                depth = depth.saturating_sub(1);
+                None
+            }
+            Token::Identifier(name) if i == 0 || !matches!(tokens[i - 1].token, Token::Dot) => scopes.iter().rev()
+                .find_map(|(class_name, _)| nested.get(class_name).and_then(|members| members.get(name)))
+                .cloned(),
+            _ => None,
+        };
+        if let Some(mangled) = renamed {
+            tokens[i].token = Token::Identifier(mangled);
+        }
+    }
+
+    let known = nested.values().flat_map(|members| members.values().cloned()).collect();
+    collapse_qualified_class_names(tokens, &known);
+}
+
+/// 把 `Outer.Inner` 合并为一个标识符 `Outer$Inner`（`nested` 为已知的嵌套类名）
+pub fn collapse_qualified_class_names(tokens: &mut Vec<TokenWithLocation>, nested: &HashSet<String>) {
+    if nested.is_empty() {
+        return;
+    }
+
+    let original = std::mem::take(tokens);
+    let mut i = 0;
+    while i < original.len() {
+        let mut token = original[i].clone();
+        if let Token::Identifier(name) = &original[i].token {
+            let mut qualified = name.clone();
+            while let (Some(Token::Dot), Some(Token::Identifier(member))) = (
+                original.get(i + 1).map(|t| &t.token),
+                original.get(i + 2).map(|t| &t.token),
+            ) {
+                let candidate = format!("{}${}", qualified, member);
+                if !nested.contains(&candidate) {
+                    break;
+                }
+                qualified = candidate;
+                i += 2;
+            }
+            token.token = Token::Identifier(qualified);
+        }
+        tokens.push(token);
+        i += 1;
+    }
+}
+
+/// 找出每个类体的左花括号位置及其（改写后的）类名，以及每个类体中直接声明的嵌套类
+fn collect_class_bodies(tokens: &[TokenWithLocation]) -> (HashMap<usize, String>, HashMap<String, HashMap<String, String>>) {
+    let mut bodies = HashMap::new();
+    let mut nested: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut scopes: Vec<(String, usize)> = Vec::new();
+    let mut depth = 0;
+    let mut pending = None;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match &token.token {
+            Token::Class => {
+                let at_member_level = depth == scopes.last().map_or(0, |(_, body_depth)| *body_depth);
+                let name = match tokens.get(i + 1).map(|t| &t.token) {
+                    Some(Token::Identifier(name)) if at_member_level => name,
+                    _ => continue,
+                };
+                pending = Some(match scopes.last() {
+                    Some((outer, _)) => {
+                        let mangled = format!("{}${}", outer, name);
+                        nested.entry(outer.clone()).or_default().insert(name.clone(), mangled.clone());
+                        mangled
+                    }
+                    None => name.clone(),
+                });
+            }
+            Token::LBrace => {
+                depth += 1;
+                if let Some(class_name) = pending.take() {
+                    bodies.insert(i, class_name.clone());
+                    scopes.push((class_name, depth));
+                }
+            }
+            Token::RBrace => {
+                if scopes.last().is_some_and(|(_, body_depth)| *body_depth == depth) {
+                    scopes.pop();
+                }
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+    (bodies, nested)
+}
+
+/// 把嵌套类和匿名类提升为顶层类
+///
+/// `interface_types` 为源码及导入模块中声明的接口，用于判断匿名类是实现接口还是继承类。
+pub fn lower_nested_classes(classes: Vec<ClassDecl>, interface_types: &HashSet<String>) -> cayResult<Vec<ClassDecl>> {
+    let mut flat = Vec::new();
+    for class in classes {
+        hoist_nested_classes(class, &mut flat)?;
+    }
+
+    let mut lowering = NestedLowering {
+        index: ClassIndex::default(),
+        interface_types,
+        anonymous_classes: Vec::new(),
+        anonymous_counts: HashMap::new(),
+    };
+    for class in &flat {
+        let is_inner = class.enclosing_class.is_some() && !class.modifiers.contains(&Modifier::Static);
+        lowering.index.add_class(class, is_inner);
+    }
+    for class in &mut flat {
+        let mut members = std::mem::take(&mut class.members);
+        lowering.lower_members(&class.name, &mut members, &HashMap::new())?;
+        class.members = members;
+    }
+    flat.append(&mut lowering.anonymous_classes);
+
+    for class in &mut flat {
+        if lowering.index.inner.contains(&class.name) {
+            add_outer_instance(class, &lowering.index)?;
+        }
+    }
+    Ok(flat)
+}
+
+/// 把类成员中的嵌套类依次提升到 `out`（外部类在前）
+fn hoist_nested_classes(mut class: ClassDecl, out: &mut Vec<ClassDecl>) -> cayResult<()> {
+    let (nested, members): (Vec<ClassMember>, Vec<ClassMember>) = std::mem::take(&mut class.members)
+        .into_iter()
+        .partition(|member| matches!(member, ClassMember::Class(_)));
+    class.members = members;
+    let outer_name = class.name.clone();
+    let outer_is_generic = !class.type_params.is_empty();
+    out.push(class);
+
+    for member in nested {
+        if let ClassMember::Class(mut inner) = member {
+            if outer_is_generic && !inner.modifiers.contains(&Modifier::Static) {
+                return Err(error_at(ErrorCodes::PARSER_NESTED_CLASS, &inner.loc, format!(
+                    "泛型类 '{}' 中暂不支持内部类 '{}'\n提示: 将其声明为 static class；内部类不能使用外部类的类型参数",
+                    source_name(&outer_name), source_name(&inner.name)
+                )));
+            }
+            inner.enclosing_class = Some(outer_name.clone());
+            hoist_nested_classes(inner, out)?;
+        }
+    }
+    Ok(())
+}
+
+/// 按名称查找类成员的索引（沿源码中的父类链继承）
+#[derive(Default)]
+struct ClassIndex {
+    /// 类名 -> 字段名 -> 是否为 static
+    fields: HashMap<String, HashMap<String, bool>>,
+    /// 类名 -> 方法名 -> 是否所有重载都是 static
+    methods: HashMap<String, HashMap<String, bool>>,
+    parents: HashMap<String, String>,
+    enclosing: HashMap<String, String>,
+    /// 持有外部类实例的内部类和匿名类
+    inner: HashSet<String>,
+    generic: HashSet<String>,
+}
+
+impl ClassIndex {
+    fn add_class(&mut self, class: &ClassDecl, is_inner: bool) {
+        let fields = self.fields.entry(class.name.clone()).or_default();
+        let methods = self.methods.entry(class.name.clone()).or_default();
+        for member in &class.members {
+            match member {
+                ClassMember::Field(field) => {
+                    fields.insert(field.name.clone(), field.modifiers.contains(&Modifier::Static));
+                }
+                ClassMember::Method(method) => {
+                    let is_static = method.modifiers.contains(&Modifier::Static);
+                    methods.entry(method.name.clone())
+                        .and_modify(|all_static| *all_static &= is_static)
+                        .or_insert(is_static);
+                }
+                _ => {}
+            }
+        }
+        if let Some(parent) = &class.parent {
+            self.parents.insert(class.name.clone(), parent.clone());
+        }
+        if let Some(outer) = &class.enclosing_class {
+            self.enclosing.insert(class.name.clone(), outer.clone());
+        }
+        if is_inner {
+            self.inner.insert(class.name.clone());
+        }
+        if !class.type_params.is_empty() {
+            self.generic.insert(class.name.clone());
+        }
+    }
+
+    /// 查找类（含父类）中的字段或方法，返回其是否为 static
+    fn member(&self, class_name: &str, name: &str, is_method: bool) -> Option<bool> {
+        let table = if is_method { &self.methods } else { &self.fields };
+        let mut current = Some(class_name);
+        let mut visited = HashSet::new();
+        while let Some(class_name) = current.filter(|c| visited.insert(*c)) {
+            if let Some(is_static) = table.get(class_name).and_then(|members| members.get(name)) {
+                return Some(*is_static);
+            }
+            current = self.parents.get(class_name).map(String::as_str);
+        }
+        None
+    }
+
+    /// 类本身或任一外围类是否为泛型类
+    fn in_generic_class(&self, class_name: &str) -> bool {
+        let mut current = Some(class_name);
+        while let Some(class_name) = current {
+            if self.generic.contains(class_name) {
+                return true;
+            }
+            current = self.enclosing.get(class_name).map(String::as_str);
+        }
+        false
+    }
+}
+
+/// 方法体、构造函数体或字段初始化器的改写上下文
+struct BodyScope<'a> {
+    class_name: &'a str,
+    is_static: bool,
+    /// 局部变量和参数的声明类型，每个块一层
+    locals: Vec<HashMap<String, Type>>,
+    /// 匿名类体中可以捕获的外层局部变量
+    capturable: &'a HashMap<String, Type>,
+    /// 已捕获的外层局部变量（按首次使用的顺序）
+    captured: Vec<(String, Type)>,
+}
+
+impl<'a> BodyScope<'a> {
+    fn new(class_name: &'a str, is_static: bool, params: &[ParameterInfo], capturable: &'a HashMap<String, Type>) -> Self {
+        let params = params.iter().map(|p| (p.name.clone(), p.param_type.clone())).collect();
+        Self {
+            class_name,
+            is_static,
+            locals: vec![params],
+            capturable,
+            captured: Vec::new(),
+        }
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        self.locals.iter().any(|scope| scope.contains_key(name))
+    }
+
+    fn declare(&mut self, name: &str, var_type: Type) {
+        if let Some(scope) = self.locals.last_mut() {
+            scope.insert(name.to_string(), var_type);
+        }
+    }
+
+    /// 当前位置可见的所有局部变量（包括本身可以捕获的外层变量），供其中的匿名类捕获
+    fn visible_locals(&self) -> HashMap<String, Type> {
+        let mut visible = self.capturable.clone();
+        for scope in &self.locals {
+            visible.extend(scope.iter().map(|(name, var_type)| (name.clone(), var_type.clone())));
+        }
+        visible
+    }
+}
+
+/// 嵌套类改写状态
+struct NestedLowering<'a> {
+    index: ClassIndex,
+    interface_types: &'a HashSet<String>,
+    /// 改写过程中生成的匿名类
+    anonymous_classes: Vec<ClassDecl>,
+    /// 每个外围类已生成的匿名类数量
+    anonymous_counts: HashMap<String, usize>,
+}
+
+impl NestedLowering<'_> {
+    /// 改写类的所有成员，返回其中捕获的外层局部变量
+    fn lower_members(&mut self, class_name: &str, members: &mut [ClassMember], capturable: &HashMap<String, Type>) -> cayResult<Vec<(String, Type)>> {
+        let mut captured: Vec<(String, Type)> = Vec::new();
+        for member in members.iter_mut() {
+            let scope_captured = match member {
+                ClassMember::Method(method) => {
+                    let mut scope = BodyScope::new(class_name, method.modifiers.contains(&Modifier::Static), &method.params, capturable);
+                    if let Some(body) = &mut method.body {
+                        self.lower_block(body, &mut scope)?;
+                    }
+                    scope.captured
+                }
+                ClassMember::Field(field) => {
+                    let mut scope = BodyScope::new(class_name, field.modifiers.contains(&Modifier::Static), &[], capturable);
+                    if let Some(initializer) = &mut field.initializer {
+                        self.lower_expr(initializer, &mut scope)?;
+                    }
+                    scope.captured
+                }
+                ClassMember::Constructor(ctor) => {
+                    let mut scope = BodyScope::new(class_name, false, &ctor.params, capturable);
+                    if let Some(ConstructorCall::This(args) | ConstructorCall::Super(args)) = &mut ctor.constructor_call {
+                        for arg in args {
+                            self.lower_expr(arg, &mut scope)?;
+                        }
+                    }
+                    self.lower_block(&mut ctor.body, &mut scope)?;
+                    scope.captured
+                }
+                ClassMember::Destructor(dtor) => {
+                    let mut scope = BodyScope::new(class_name, false, &[], capturable);
+                    self.lower_block(&mut dtor.body, &mut scope)?;
+                    scope.captured
+                }
+                ClassMember::InstanceInitializer(block) => {
+                    let mut scope = BodyScope::new(class_name, false, &[], capturable);
+                    self.lower_block(block, &mut scope)?;
+                    scope.captured
+                }
+                ClassMember::StaticInitializer(block) => {
+                    let mut scope = BodyScope::new(class_name, true, &[], capturable);
+                    self.lower_block(block, &mut scope)?;
+                    scope.captured
+                }
+                // 类体中的嵌套类已经提升，这里只可能出现在匿名类体中
+                ClassMember::Class(nested) => {
+                    return Err(error_at(ErrorCodes::PARSER_NESTED_CLASS, &nested.loc, "匿名类中不能声明嵌套类\n提示: 把嵌套类声明在外部类中"));
+                }
+                // 访问器体已展开为紧随其后的访问方法
+                ClassMember::Property(_) => continue,
+            };
+            for (name, var_type) in scope_captured {
+                if !captured.iter().any(|(captured_name, _)| *captured_name == name) {
+                    captured.push((name, var_type));
+                }
+            }
+        }
+        Ok(captured)
+    }
+
+    fn lower_block(&mut self, block: &mut Block, scope: &mut BodyScope) -> cayResult<()> {
+        scope.locals.push(HashMap::new());
+        for stmt in &mut block.statements {
+            self.lower_stmt(stmt, scope)?;
+        }
+        scope.locals.pop();
+        Ok(())
+    }
+
+    fn lower_stmt(&mut self, stmt: &mut Stmt, scope: &mut BodyScope) -> cayResult<()> {
+        match stmt {
+            Stmt::Expr(expr) => self.lower_expr(expr, scope)?,
+            Stmt::VarDecl(var) => {
+                if let Some(initializer) = &mut var.initializer {
+                    self.lower_expr(initializer, scope)?;
+                }
+                scope.declare(&var.name, var.var_type.clone());
+            }
+            Stmt::Return(expr) => {
+                if let Some(expr) = expr {
+                    self.lower_expr(expr, scope)?;
+                }
+            }
+            Stmt::If(if_stmt) => {
+                self.lower_expr(&mut if_stmt.condition, scope)?;
+                self.lower_stmt(&mut if_stmt.then_branch, scope)?;
+                if let Some(else_branch) = &mut if_stmt.else_branch {
+                    self.lower_stmt(else_branch, scope)?;
+                }
+            }
+            Stmt::While(while_stmt) => {
+                self.lower_expr(&mut while_stmt.condition, scope)?;
+                self.lower_stmt(&mut while_stmt.body, scope)?;
+            }
+            Stmt::For(for_stmt) => {
+                scope.locals.push(HashMap::new());
+                if let Some(init) = &mut for_stmt.init {
+                    self.lower_stmt(init, scope)?;
+                }
+                if let Some(condition) = &mut for_stmt.condition {
+                    self.lower_expr(condition, scope)?;
+                }
+                if let Some(update) = &mut for_stmt.update {
+                    self.lower_expr(update, scope)?;
+                }
+                self.lower_stmt(&mut for_stmt.body, scope)?;
+                scope.locals.pop();
+            }
+            Stmt::ForEach(for_each) => {
+                self.lower_expr(&mut for_each.iterable, scope)?;
+                scope.locals.push(HashMap::new());
+                scope.declare(&for_each.var_name, for_each.var_type.clone());
+                self.lower_stmt(&mut for_each.body, scope)?;
+                scope.locals.pop();
+            }
+            Stmt::DoWhile(do_while) => {
+                self.lower_stmt(&mut do_while.body, scope)?;
+                self.lower_expr(&mut do_while.condition, scope)?;
+            }
+            Stmt::Switch(switch) => {
+                self.lower_expr(&mut switch.expr, scope)?;
+                for case in &mut switch.cases {
+                    scope.locals.push(pattern_bindings(&case.labels));
+                    if let Some(guard) = &mut case.guard {
+                        self.lower_expr(guard, scope)?;
+                    }
+                    for stmt in &mut case.body {
+                        self.lower_stmt(stmt, scope)?;
+                    }
+                    scope.locals.pop();
+                }
+                if let Some(default) = &mut switch.default {
+                    scope.locals.push(HashMap::new());
+                    for stmt in default {
+                        self.lower_stmt(stmt, scope)?;
+                    }
+                    scope.locals.pop();
+                }
+            }
+            Stmt::Block(block) => self.lower_block(block, scope)?,
+            Stmt::Scope(scope_stmt) => self.lower_block(&mut scope_stmt.body, scope)?,
+            Stmt::Defer(defer) => self.lower_stmt(&mut defer.body, scope)?,
+            Stmt::TryWithResources(try_stmt) => {
+                scope.locals.push(HashMap::new());
+                for resource in &mut try_stmt.resources {
+                    if let Some(initializer) = &mut resource.initializer {
+                        self.lower_expr(initializer, scope)?;
+                    }
+                    scope.declare(&resource.name, resource.var_type.clone());
+                }
+                self.lower_block(&mut try_stmt.body, scope)?;
+                scope.locals.pop();
+            }
+            Stmt::Break(_) | Stmt::Continue(_) | Stmt::InlineIr(_) | Stmt::Error(_) => {}
+        }
+        Ok(())
+    }
+
+    fn lower_expr(&mut self, expr: &mut Expr, scope: &mut BodyScope) -> cayResult<()> {
+        match expr {
+            Expr::Literal(_) => {}
+            Expr::Identifier(ident) => {
+                let ident = ident.clone();
+                if let Some(replacement) = self.resolve_outer_member(&ident, scope, false)? {
+                    *expr = replacement;
+                }
+            }
+            Expr::Binary(bin) => {
+                self.lower_expr(&mut bin.left, scope)?;
+                self.lower_expr(&mut bin.right, scope)?;
+            }
+            Expr::Unary(unary) => self.lower_expr(&mut unary.operand, scope)?,
+            Expr::Call(call) => {
+                match call.callee.as_mut() {
+                    Expr::Identifier(function) => {
+                        let function = function.clone();
+                        if let Some(replacement) = self.resolve_outer_member(&function, scope, true)? {
+                            *call.callee = replacement;
+                        }
+                    }
+                    callee => self.lower_expr(callee, scope)?,
+                }
+                for arg in &mut call.args {
+                    self.lower_expr(arg, scope)?;
+                }
+            }
+            Expr::MemberAccess(member) => self.lower_expr(&mut member.object, scope)?,
+            Expr::New(new_expr) => {
+                for arg in &mut new_expr.args {
+                    self.lower_expr(arg, scope)?;
+                }
+                if let Some(body) = new_expr.body.take() {
+                    self.lower_anonymous_class(new_expr, body, scope)?;
+                }
+                if let Some(outer) = self.index.enclosing.get(&new_expr.class_name).filter(|_| self.index.inner.contains(&new_expr.class_name)) {
+                    match self.outer_instance(scope, outer, &new_expr.loc) {
+                        Some(instance) => new_expr.args.insert(0, instance),
+                        None => {
+                            return Err(error_at(ErrorCodes::PARSER_NESTED_CLASS, &new_expr.loc, format!(
+                                "无法在此处创建内部类 '{}' 的实例：没有可用的外部类 '{}' 实例\n提示: 在 {} 的实例方法中创建它，或将其声明为 static class",
+                                source_name(&new_expr.class_name), source_name(outer), source_name(outer)
+                            )));
+                        }
+                    }
+                }
+            }
+            Expr::Assignment(assign) => {
+                self.lower_expr(&mut assign.target, scope)?;
+                self.lower_expr(&mut assign.value, scope)?;
+            }
+            Expr::Cast(cast) => self.lower_expr(&mut cast.expr, scope)?,
+            Expr::ArrayCreation(arr) => {
+                for size in &mut arr.sizes {
+                    self.lower_expr(size, scope)?;
+                }
+            }
+            Expr::ArrayAccess(arr) => {
+                self.lower_expr(&mut arr.array, scope)?;
+                self.lower_expr(&mut arr.index, scope)?;
+            }
+            Expr::ArrayInit(init) => {
+                for element in &mut init.elements {
+                    self.lower_expr(element, scope)?;
+                }
+            }
+            Expr::MethodRef(method_ref) => {
+                if let Some(object) = &mut method_ref.object {
+                    self.lower_expr(object, scope)?;
+                }
+            }
+            Expr::Lambda(lambda) => {
+                let params = lambda.params.iter()
+                    .map(|p| (p.name.clone(), p.param_type.clone().unwrap_or(Type::Auto)))
+                    .collect();
+                scope.locals.push(params);
+                match &mut lambda.body {
+                    LambdaBody::Expr(body) => self.lower_expr(body, scope)?,
+                    LambdaBody::Block(block) => self.lower_block(block, scope)?,
+                }
+                scope.locals.pop();
+            }
+            Expr::Ternary(ternary) => {
+                self.lower_expr(&mut ternary.condition, scope)?;
+                self.lower_expr(&mut ternary.true_branch, scope)?;
+                self.lower_expr(&mut ternary.false_branch, scope)?;
+            }
+            Expr::InstanceOf(instanceof) => {
+                self.lower_expr(&mut instanceof.expr, scope)?;
+                if let Some(binding) = &instanceof.binding {
+                    scope.declare(binding, instanceof.target_type.clone());
+                }
+            }
+            Expr::Alloc(alloc) => {
+                self.lower_expr(&mut alloc.size, scope)?;
+                if let Some(align) = &mut alloc.align {
+                    self.lower_expr(align, scope)?;
+                }
+            }
+            Expr::Dealloc(dealloc) => self.lower_expr(&mut dealloc.ptr, scope)?,
+            Expr::Try(try_expr) => self.lower_expr(&mut try_expr.expr, scope)?,
+            Expr::Switch(switch) => {
+                self.lower_expr(&mut switch.expr, scope)?;
+                for arm in &mut switch.arms {
+                    scope.locals.push(pattern_bindings(&arm.labels));
+                    if let Some(guard) = &mut arm.guard {
+                        self.lower_expr(guard, scope)?;
+                    }
+                    self.lower_expr(&mut arm.value, scope)?;
+                    scope.locals.pop();
+                }
+                if let Some(default) = &mut switch.default {
+                    self.lower_expr(default, scope)?;
+                }
+            }
+            Expr::SafeCall(safe_call) => {
+                self.lower_expr(&mut safe_call.object, scope)?;
+                for arg in safe_call.args.iter_mut().flatten() {
+                    self.lower_expr(arg, scope)?;
+                }
+            }
+            Expr::Elvis(elvis) => {
+                self.lower_expr(&mut elvis.value, scope)?;
+                self.lower_expr(&mut elvis.default, scope)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 解析未限定的名称：局部变量、本类（含父类）成员和匿名类捕获的变量保持不变，
+    /// 外部类的成员改写为 `Outer.x`（static）或 `this.this$0.x`（实例成员）
+    fn resolve_outer_member(&self, ident: &IdentifierExpr, scope: &mut BodyScope, is_method: bool) -> cayResult<Option<Expr>> {
+        let name = ident.name.as_str();
+        if name == "this" || name == "super" || scope.is_local(name) || self.index.member(scope.class_name, name, is_method).is_some() {
+            return Ok(None);
+        }
+        if let Some(var_type) = scope.capturable.get(name) {
+            if matches!(var_type, Type::Auto) {
+                return Err(error_at(ErrorCodes::PARSER_NESTED_CLASS, &ident.loc, format!(
+                    "匿名类不能捕获类型推断的局部变量 '{}'\n提示: 为 '{}' 声明显式类型，例如: int {} = ...;",
+                    name, name, name
+                )));
+            }
+            if !scope.captured.iter().any(|(captured, _)| captured == name) {
+                scope.captured.push((name.to_string(), var_type.clone()));
+            }
+            return Ok(None);
+        }
+
+        let mut current = scope.class_name.to_string();
+        let mut instance = if scope.is_static { None } else { Some(identifier("this", &ident.loc)) };
+        while let Some(outer) = self.index.enclosing.get(&current) {
+            instance = instance
+                .filter(|_| self.index.inner.contains(&current))
+                .map(|instance| member_access(instance, OUTER_INSTANCE, &ident.loc));
+            match (self.index.member(outer, name, is_method), instance) {
+                (Some(true), _) => return Ok(Some(member_access(identifier(outer, &ident.loc), name, &ident.loc))),
+                (Some(false), Some(instance)) => return Ok(Some(member_access(instance, name, &ident.loc))),
+                (Some(false), None) => {
+                    return Err(error_at(ErrorCodes::PARSER_NESTED_CLASS, &ident.loc, format!(
+                        "无法在此处访问外部类 '{}' 的实例成员 '{}'：没有可用的外部类实例\n提示: 只有内部类（非 static 的嵌套类）和在实例方法中创建的匿名类持有外部类实例",
+                        source_name(outer), name
+                    )));
+                }
+                (None, next) => instance = next,
+            }
+            current = outer.clone();
+        }
+        Ok(None)
+    }
+
+    /// 当前上下文中外部类 `target` 的实例表达式（this、this.this$0、this.this$0.this$0…）
+    fn outer_instance(&self, scope: &BodyScope, target: &str, loc: &SourceLocation) -> Option<Expr> {
+        if scope.is_static {
+            return None;
+        }
+        let mut current = scope.class_name;
+        let mut instance = identifier("this", loc);
+        while current != target {
+            if !self.index.inner.contains(current) {
+                return None;
+            }
+            instance = member_access(instance, OUTER_INSTANCE, loc);
+            current = self.index.enclosing.get(current).map(String::as_str)?;
+        }
+        Some(instance)
+    }
+
+    /// 把 `new Base(...) { ... }` 提升为具名类，表达式改为创建该类并传入捕获的变量
+    fn lower_anonymous_class(&mut self, new_expr: &mut NewExpr, body: Vec<ClassMember>, scope: &mut BodyScope) -> cayResult<()> {
+        if self.index.in_generic_class(scope.class_name) {
+            return Err(error_at(ErrorCodes::PARSER_NESTED_CLASS, &new_expr.loc, format!(
+                "泛型类 '{}' 中暂不支持匿名类\n提示: 改用具名类或 lambda 表达式",
+                source_name(scope.class_name)
+            )));
+        }
+        if !new_expr.args.is_empty() {
+            return Err(error_at(ErrorCodes::PARSER_NESTED_CLASS, &new_expr.loc, "匿名类不支持向父类构造函数传递参数\n提示: 匿名类只调用父类的无参构造函数，例如: new Base() { ... }"));
+        }
+        if let Some(ClassMember::Constructor(ctor)) = body.iter().find(|m| matches!(m, ClassMember::Constructor(_))) {
+            return Err(error_at(ErrorCodes::PARSER_NESTED_CLASS, &ctor.loc, "匿名类不能声明构造函数\n提示: 使用实例初始化块 { ... } 进行初始化"));
+        }
+
+        let count = self.anonymous_counts.entry(scope.class_name.to_string()).or_insert(0);
+        *count += 1;
+        let name = format!("{}${}", scope.class_name, count);
+
+        let mut generic_supertypes = Vec::new();
+        let supertype = if new_expr.type_args.is_empty() {
+            new_expr.class_name.clone()
+        } else {
+            let generic = Type::Generic(new_expr.class_name.clone(), std::mem::take(&mut new_expr.type_args));
+            let mangled = generic.resolve_generics().to_string();
+            generic_supertypes.push(generic);
+            mangled
+        };
+        let (parent, interfaces) = if self.interface_types.contains(&new_expr.class_name) {
+            (None, vec![supertype])
+        } else {
+            (Some(supertype), Vec::new())
+        };
+
+        let mut class = ClassDecl {
+            name: name.clone(),
+            modifiers: Vec::new(),
+            type_params: Vec::new(),
+            parent,
+            interfaces,
+            generic_supertypes,
+            permits: Vec::new(),
+            annotations: Vec::new(),
+            members: body,
+            enclosing_class: Some(scope.class_name.to_string()),
+            loc: new_expr.loc.clone(),
+        };
+        self.index.add_class(&class, !scope.is_static);
+
+        let capturable = scope.visible_locals();
+        let captured = self.lower_members(&name, &mut class.members, &capturable)?;
+        if !captured.is_empty() {
+            add_captured_fields(&mut class, &captured);
+        }
+        self.anonymous_classes.push(class);
+
+        new_expr.class_name = name;
+        new_expr.args = captured.iter().map(|(var, _)| identifier(var, &new_expr.loc)).collect();
+        for arg in &mut new_expr.args {
+            self.lower_expr(arg, scope)?;
+        }
+        Ok(())
+    }
+}
+
+/// 为匿名类增加保存捕获变量的字段，以及按捕获顺序接收它们的构造函数
+fn add_captured_fields(class: &mut ClassDecl, captured: &[(String, Type)]) {
+    let loc = class.loc.clone();
+    let mut params = Vec::new();
+    let mut statements = Vec::new();
+    for (name, var_type) in captured {
+        class.members.push(ClassMember::Field(FieldDecl {
+            name: name.clone(),
+            field_type: var_type.clone(),
+            modifiers: Vec::new(),
+            annotations: Vec::new(),
+            initializer: None,
+            loc: loc.clone(),
+        }));
+        params.push(ParameterInfo::new(name.clone(), var_type.clone()));
+        statements.push(assign_field(name, &loc));
+    }
+    class.members.push(ClassMember::Constructor(ConstructorDecl {
+        modifiers: Vec::new(),
+        params,
+        body: Block { statements, loc: loc.clone() },
+        constructor_call: None,
+        loc,
+    }));
+}
+
+/// 为内部类增加 `this$0` 字段，并让每个构造函数以首个参数接收外部类实例
+fn add_outer_instance(class: &mut ClassDecl, index: &ClassIndex) -> cayResult<()> {
+    let outer = match &class.enclosing_class {
+        Some(outer) => outer.clone(),
+        None => return Ok(()),
+    };
+    let loc = class.loc.clone();
+    let outer_type = Type::Object(outer.clone());
+
+    // 继承同一外部类的内部类时，外部类实例继续传给父类构造函数
+    let inner_parent = class.parent.as_ref().filter(|parent| index.inner.contains(*parent));
+    if let Some(parent) = inner_parent.filter(|parent| index.enclosing.get(*parent) != Some(&outer)) {
+        return Err(error_at(ErrorCodes::PARSER_NESTED_CLASS, &loc, format!(
+            "内部类 '{}' 不能继承其他外部类的内部类 '{}'\n提示: 将父类声明为 static class",
+            source_name(&class.name), source_name(parent)
+        )));
+    }
+    let passes_to_parent = inner_parent.is_some();
+
+    class.members.insert(0, ClassMember::Field(FieldDecl {
+        name: OUTER_INSTANCE.to_string(),
+        field_type: outer_type.clone(),
+        modifiers: Vec::new(),
+        annotations: Vec::new(),
+        initializer: None,
+        loc: loc.clone(),
+    }));
+    if !class.members.iter().any(|m| matches!(m, ClassMember::Constructor(_))) {
+        class.members.push(ClassMember::Constructor(ConstructorDecl {
+            modifiers: vec![Modifier::Public],
+            params: Vec::new(),
+            body: Block { statements: Vec::new(), loc: loc.clone() },
+            constructor_call: None,
+            loc: loc.clone(),
+        }));
+    }
+
+    for member in &mut class.members {
+        if let ClassMember::Constructor(ctor) = member {
+            ctor.params.insert(0, ParameterInfo::new(OUTER_INSTANCE.to_string(), outer_type.clone()));
+            let outer_arg = identifier(OUTER_INSTANCE, &ctor.loc);
+            match &mut ctor.constructor_call {
+                // this(...) 委托的构造函数负责保存外部类实例
+                Some(ConstructorCall::This(args)) => args.insert(0, outer_arg),
+                call => {
+                    if passes_to_parent {
+                        match call {
+                            Some(ConstructorCall::Super(args)) => args.insert(0, outer_arg),
+                            _ => *call = Some(ConstructorCall::Super(vec![outer_arg])),
+                        }
+                    }
+                    ctor.body.statements.insert(0, assign_field(OUTER_INSTANCE, &ctor.loc));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 变体模式和类型模式绑定的局部变量
+fn pattern_bindings(labels: &[CaseLabel]) -> HashMap<String, Type> {
+    labels.iter().flat_map(|label| match label {
+        CaseLabel::Pattern(pattern) => pattern.bindings.iter().map(|name| (name.clone(), Type::Auto)).collect(),
+        CaseLabel::Type(pattern) => vec![(pattern.binding.clone(), pattern.target_type.clone())],
+        _ => Vec::new(),
+    }).collect()
+}
+
+/// this.name = name;
+fn assign_field(name: &str, loc: &SourceLocation) -> Stmt {
+    Stmt::Expr(Expr::Assignment(AssignmentExpr {
+        target: Box::new(member_access(identifier("this", loc), name, loc)),
+        value: Box::new(identifier(name, loc)),
+        op: AssignOp::Assign,
+        loc: loc.clone(),
+    }))
+}
+
+fn identifier(name: &str, loc: &SourceLocation) -> Expr {
+    Expr::Identifier(IdentifierExpr { name: name.to_string(), loc: loc.clone() })
+}
+
+fn member_access(object: Expr, member: &str, loc: &SourceLocation) -> Expr {
+    Expr::MemberAccess(MemberAccessExpr {
+        object: Box::new(object),
+        member: member.to_string(),
+        loc: loc.clone(),
+    })
+}
+
+/// 嵌套类在源码中的写法（Outer$Inner -> Outer.Inner）
+fn source_name(name: &str) -> String {
+    name.replace('$', ".")
+}
+
+fn error_at(code: &'static str, loc: &SourceLocation, message: impl Into<String>) -> cayError {
+    parser_error_with_file(code, loc.file.clone(), loc.line, loc.column, message)
+}
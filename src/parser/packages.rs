@@ -0,0 +1,64 @@
+//! 包声明与导入声明解析
+
+use crate::ast::ImportDecl;
+use crate::error::{cayResult, parser_error_with_file};
+use crate::lexer::Token;
+use super::Parser;
+use crate::diagnostic::ErrorCodes;
+
+/// 解析文件开头的包声明和导入声明
+///
+/// 包声明最多一个且必须位于最前面，导入声明紧随其后、位于所有类型声明之前。
+pub fn parse_module_header(parser: &mut Parser) -> cayResult<(Option<String>, Vec<ImportDecl>)> {
+    let package = if parser.match_token(&Token::Package) {
+        let name = parse_package_name(parser)?;
+        parser.consume(&Token::Semicolon, "期望 ';'\n提示: 包声明应以分号结束，例如: package com.example;")?;
+        Some(name)
+    } else {
+        None
+    };
+
+    let mut imports = Vec::new();
+    while parser.check(&Token::Import) {
+        imports.push(parse_import(parser)?);
+    }
+    Ok((package, imports))
+}
+
+/// 解析包名: a.b.c
+fn parse_package_name(parser: &mut Parser) -> cayResult<String> {
+    let mut segments = vec![parser.consume_identifier("期望包名\n提示: 包声明的格式为 package a.b;")?];
+    while parser.match_token(&Token::Dot) {
+        segments.push(parser.consume_identifier("期望标识符\n提示: 包名由 '.' 分隔的标识符组成，例如: package com.example;")?);
+    }
+    Ok(segments.join("."))
+}
+
+/// 解析导入声明: import a.b.Class; 或 import a.b.*;
+fn parse_import(parser: &mut Parser) -> cayResult<ImportDecl> {
+    let loc = parser.current_loc();
+    parser.advance();
+
+    let mut segments = vec![parser.consume_identifier("期望包名\n提示: 导入声明的格式为 import a.b.Class; 或 import a.b.*;")?];
+    let mut wildcard = false;
+    while parser.match_token(&Token::Dot) {
+        if parser.match_token(&Token::Star) {
+            wildcard = true;
+            break;
+        }
+        segments.push(parser.consume_identifier("期望标识符\n提示: 导入路径由 '.' 分隔的标识符组成，例如: import a.b.Class;")?);
+    }
+    parser.consume(&Token::Semicolon, "期望 ';'\n提示: 导入声明应以分号结束，例如: import a.b.Class;")?;
+
+    let name = if wildcard { None } else { segments.pop() };
+    if segments.is_empty() {
+        return Err(parser_error_with_file(
+            ErrorCodes::PARSER_INVALID_IMPORT,
+            loc.file,
+            loc.line,
+            loc.column,
+            "导入声明缺少包名\n提示: 默认包中的类型无需导入；导入其他包中的类型时使用 import a.b.Class;",
+        ));
+    }
+    Ok(ImportDecl { package: segments.join("."), name, loc })
+}
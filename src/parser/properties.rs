@@ -0,0 +1,266 @@
+//! 属性（property）
+//!
+//! `property int count { get; private set; }` 是 getter/setter 样板代码的简写，在类体中展开为：
+//!
+//! - 访问方法 `getCount()` 和 `setCount(value)`，访问修饰符省略时与属性相同；
+//! - 自动属性（访问器都写成 `get;` / `set;`）还有一个与属性同名的 private 字段保存值，
+//!   只有 `get;` 的自动属性是只读的，字段为 final，只能用初始值或在构造函数中赋值；
+//! - 带访问器体的属性没有字段：`get { ... }` / `get -> 表达式;`，`set` 的访问器体通过 `value` 取得新值。
+//!
+//! `obj.count` 和 `obj.count = v` 由语义分析解析为访问方法调用，自动访问器直接读写字段。
+
+use crate::ast::*;
+use crate::error::{cayResult, parser_error_with_file, SourceLocation};
+use crate::lexer::Token;
+use crate::types::{ParameterInfo, Type};
+use super::Parser;
+use super::classes::{parse_annotated_modifiers, parse_modifiers};
+use super::expressions::parse_expression;
+use super::statements::parse_block;
+use super::types::parse_type;
+use crate::diagnostic::ErrorCodes;
+
+/// 生成代码的起始列号（与记录的生成代码一样放在源码用不到的列，见 records.rs）
+const SYNTHETIC_COLUMN: usize = 20_000;
+
+/// 解析属性声明: [注解] [修饰符] property 类型 名称 { 访问器... } [= 初始值;]
+pub fn parse_property(parser: &mut Parser) -> cayResult<PropertyDecl> {
+    let loc = parser.current_loc();
+    let (annotations, modifiers) = parse_annotated_modifiers(parser)?;
+    if let Some(modifier) = modifiers.iter().find(|m| !is_property_modifier(m)) {
+        return Err(error_at(ErrorCodes::PARSER_INVALID_MODIFIER, &loc, format!(
+            "修饰符 '{}' 不能用于属性\n提示: 属性只能使用访问修饰符和 static，例如: public static property int count {{ get; set; }}",
+            modifier_name(modifier)
+        )));
+    }
+    parser.consume(&Token::Property, "期望关键字 'property'")?;
+    let property_type = parse_type(parser)?;
+    let name = parser.consume_identifier("期望属性名\n提示: 类型后应跟属性名，例如: property int count { get; set; }")?;
+    parser.consume(&Token::LBrace, "期望 '{'\n提示: 属性名后应跟访问器列表，例如: property int count { get; private set; }")?;
+
+    let mut getter = None;
+    let mut setter = None;
+    while !parser.check(&Token::RBrace) && !parser.is_at_end() {
+        let accessor_loc = parser.current_loc();
+        let accessor_modifiers = parse_modifiers(parser)?;
+        if let Some(modifier) = accessor_modifiers.iter().find(|m| !is_access_modifier(m)) {
+            return Err(error_at(ErrorCodes::PARSER_INVALID_MODIFIER, &accessor_loc, format!(
+                "修饰符 '{}' 不能用于属性访问器\n提示: 访问器只能使用 public、private 或 protected，例如: private set;",
+                modifier_name(modifier)
+            )));
+        }
+        let kind = parser.consume_identifier("期望 'get' 或 'set'\n提示: 属性体由访问器组成，例如: { get; private set; }")?;
+        let slot = match kind.as_str() {
+            "get" => &mut getter,
+            "set" => &mut setter,
+            _ => {
+                return Err(error_at(ErrorCodes::PARSER_INVALID_DECLARATION, &accessor_loc, format!(
+                    "未知的属性访问器 '{}'\n提示: 属性只能有 get 和 set 访问器",
+                    kind
+                )));
+            }
+        };
+        if slot.is_some() {
+            return Err(error_at(ErrorCodes::PARSER_INVALID_DECLARATION, &accessor_loc, format!("属性 '{}' 的 {} 访问器重复声明", name, kind)));
+        }
+        let body = parse_accessor_body(parser, &kind)?;
+        *slot = Some(PropertyAccessor { modifiers: accessor_modifiers, body, loc: accessor_loc });
+    }
+    parser.consume(&Token::RBrace, "期望 '}'\n提示: 访问器列表应以 '}' 结束")?;
+
+    let initializer = if parser.match_token(&Token::Assign) {
+        let value = parse_expression(parser)?;
+        parser.consume(&Token::Semicolon, "期望 ';'\n提示: 属性初始值应以 ';' 结束，例如: property int count { get; set; } = 0;")?;
+        Some(value)
+    } else {
+        None
+    };
+
+    let property = PropertyDecl { name, property_type, modifiers, annotations, getter, setter, initializer, loc };
+    check_property(&property)?;
+    Ok(property)
+}
+
+/// 访问器体: `;`（自动访问器）、`{ ... }` 或 `-> 表达式;`
+fn parse_accessor_body(parser: &mut Parser, kind: &str) -> cayResult<Option<Block>> {
+    if parser.match_token(&Token::Semicolon) {
+        return Ok(None);
+    }
+    if parser.check(&Token::LBrace) {
+        return Ok(Some(parse_block(parser)?));
+    }
+    let loc = parser.current_loc();
+    parser.consume(&Token::Arrow, &format!(
+        "期望 ';'、'{{' 或 '->'\n提示: 访问器可以写成 {0};、{0} {{ ... }} 或 {0} -> 表达式;",
+        kind
+    ))?;
+    let value = parse_expression(parser)?;
+    parser.consume(&Token::Semicolon, "期望 ';'\n提示: 表达式形式的访问器应以 ';' 结束，例如: get -> width * height;")?;
+    let statement = if kind == "get" { Stmt::Return(Some(value)) } else { Stmt::Expr(value) };
+    Ok(Some(Block { statements: vec![statement], loc }))
+}
+
+/// 检查访问器组合：至少要有 get，自动访问器和带访问器体的访问器不能混用
+fn check_property(property: &PropertyDecl) -> cayResult<()> {
+    let getter = match &property.getter {
+        Some(getter) => getter,
+        None => {
+            return Err(error_at(ErrorCodes::PARSER_INVALID_DECLARATION, &property.loc, format!(
+                "属性 '{}' 缺少 get 访问器\n提示: 只写属性请改用普通方法，例如: void setValue(int value) {{ ... }}",
+                property.name
+            )));
+        }
+    };
+    match &property.setter {
+        Some(setter) if getter.body.is_some() != setter.body.is_some() => {
+            return Err(error_at(ErrorCodes::PARSER_INVALID_DECLARATION, &setter.loc, format!(
+                "属性 '{}' 的 get 和 set 必须同时是自动访问器或同时带有访问器体\n提示: 需要自定义逻辑时，把值保存在单独声明的字段中，例如: private int _count;",
+                property.name
+            )));
+        }
+        _ => {}
+    }
+    if property.initializer.is_some() && !property.is_auto() {
+        return Err(error_at(ErrorCodes::PARSER_INVALID_DECLARATION, &property.loc, format!(
+            "只有自动属性可以有初始值，但属性 '{}' 的访问器带有访问器体",
+            property.name
+        )));
+    }
+    Ok(())
+}
+
+/// 把成员加入类体：属性后面紧跟展开出的字段和访问方法
+pub fn push_member(members: &mut Vec<ClassMember>, member: ClassMember) {
+    let property = match member {
+        ClassMember::Property(property) => property,
+        other => {
+            members.push(other);
+            return;
+        }
+    };
+    let mut builder = AccessorBuilder { line: property.loc.line, file: property.loc.file.clone(), column: SYNTHETIC_COLUMN };
+    let generated = builder.expand(&property);
+    members.push(ClassMember::Property(property));
+    members.extend(generated);
+}
+
+/// 为属性生成字段和访问方法，生成的节点使用互不相同的位置
+struct AccessorBuilder {
+    file: Option<String>,
+    line: usize,
+    column: usize,
+}
+
+impl AccessorBuilder {
+    fn expand(&mut self, property: &PropertyDecl) -> Vec<ClassMember> {
+        let is_static = property.modifiers.contains(&Modifier::Static);
+        let mut members = Vec::new();
+
+        if property.is_auto() {
+            let mut modifiers = vec![Modifier::Private];
+            if is_static {
+                modifiers.push(Modifier::Static);
+            }
+            if property.setter.is_none() {
+                modifiers.push(Modifier::Final);
+            }
+            members.push(ClassMember::Field(FieldDecl {
+                name: property.name.clone(),
+                field_type: property.property_type.clone(),
+                modifiers,
+                annotations: Vec::new(),
+                initializer: property.initializer.clone(),
+                loc: property.loc.clone(),
+            }));
+        }
+
+        if let Some(getter) = &property.getter {
+            let body = match &getter.body {
+                Some(body) => body.clone(),
+                None => {
+                    let field = self.identifier(&property.name);
+                    Block { statements: vec![Stmt::Return(Some(field))], loc: getter.loc.clone() }
+                }
+            };
+            members.push(self.method(property, getter, property.accessor_name("get"), property.property_type.clone(), Vec::new(), body));
+        }
+
+        if let Some(setter) = &property.setter {
+            // 自动 setter 的参数名不能遮蔽同名字段
+            let param_name = if property.name == "value" { "newValue" } else { "value" };
+            let body = match &setter.body {
+                Some(body) => body.clone(),
+                None => {
+                    // 实例字段通过 this 赋值，静态字段直接赋值
+                    let field = if is_static {
+                        self.identifier(&property.name)
+                    } else {
+                        Expr::MemberAccess(MemberAccessExpr {
+                            object: Box::new(self.identifier("this")),
+                            member: property.name.clone(),
+                            loc: self.loc(),
+                        })
+                    };
+                    let value = self.identifier(param_name);
+                    let assignment = Expr::Assignment(AssignmentExpr {
+                        target: Box::new(field),
+                        value: Box::new(value),
+                        op: AssignOp::Assign,
+                        loc: self.loc(),
+                    });
+                    Block { statements: vec![Stmt::Expr(assignment)], loc: setter.loc.clone() }
+                }
+            };
+            let params = vec![ParameterInfo::new(param_name.to_string(), property.property_type.clone())];
+            members.push(self.method(property, setter, property.accessor_name("set"), Type::Void, params, body));
+        }
+        members
+    }
+
+    fn method(&mut self, property: &PropertyDecl, accessor: &PropertyAccessor, name: String, return_type: Type, params: Vec<ParameterInfo>, body: Block) -> ClassMember {
+        // 访问器自己的访问修饰符优先，否则沿用属性的
+        let mut modifiers: Vec<Modifier> = if accessor.modifiers.is_empty() {
+            property.modifiers.iter().filter(|m| is_access_modifier(m)).cloned().collect()
+        } else {
+            accessor.modifiers.clone()
+        };
+        if property.modifiers.contains(&Modifier::Static) {
+            modifiers.push(Modifier::Static);
+        }
+        ClassMember::Method(MethodDecl {
+            name,
+            modifiers,
+            annotations: Vec::new(),
+            type_params: Vec::new(),
+            return_type,
+            params,
+            body: Some(body),
+            loc: accessor.loc.clone(),
+        })
+    }
+
+    fn identifier(&mut self, name: &str) -> Expr {
+        Expr::Identifier(IdentifierExpr { name: name.to_string(), loc: self.loc() })
+    }
+
+    fn loc(&mut self) -> SourceLocation {
+        self.column += 1;
+        SourceLocation { file: self.file.clone(), line: self.line, column: self.column }
+    }
+}
+
+fn is_access_modifier(modifier: &Modifier) -> bool {
+    matches!(modifier, Modifier::Public | Modifier::Private | Modifier::Protected)
+}
+
+fn is_property_modifier(modifier: &Modifier) -> bool {
+    is_access_modifier(modifier) || *modifier == Modifier::Static
+}
+
+fn modifier_name(modifier: &Modifier) -> String {
+    format!("{:?}", modifier).to_lowercase()
+}
+
+fn error_at(code: &'static str, loc: &SourceLocation, message: impl Into<String>) -> crate::error::cayError {
+    parser_error_with_file(code, loc.file.clone(), loc.line, loc.column, message)
+}
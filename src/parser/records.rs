@@ -0,0 +1,569 @@
+//! 记录类（record）
+//!
+//! `record Point(int x, int y) { ... }` 是不可变数据类的简写，解析结束时展开为 final 类：
+//!
+//! - 每个组件对应一个 `private final` 字段和同名的无参访问方法 `x()`；
+//! - 规范构造函数按组件顺序接收参数，紧凑构造函数 `Point { ... }` 的语句在字段赋值之前执行，
+//!   可以校验或规范化参数；
+//! - `equals` 逐个组件比较（字符串用 equals，结构体逐字段比较），`hashCode` 按 `31 * h + c`
+//!   组合各组件的哈希值，`toString` 输出 `Point[x=1, y=2]`。
+//!
+//! 记录体中显式声明的同名成员不会被覆盖。equals/hashCode/toString 要等整个文件解析完，
+//! 知道组件类型是否声明了这些方法之后才能生成。
+
+use std::collections::{HashMap, HashSet};
+use crate::ast::*;
+use crate::error::{cayResult, parser_error_with_file, SourceLocation};
+use crate::lexer::Token;
+use crate::types::{ParameterInfo, Type};
+use super::Parser;
+use super::classes::{parse_annotated_modifiers, parse_class_member, parse_modifiers, parse_parameters, parse_supertype};
+use super::recovery::parse_member_recovering;
+use super::properties::push_member;
+use super::statements::parse_block;
+use crate::diagnostic::ErrorCodes;
+
+/// 生成代码的起始列号
+///
+/// 语义分析按源码位置向代码生成传递运算符重载、整数运算等信息，生成的每个节点都要有唯一的位置；
+/// 它们放在记录声明所在行上源码用不到的列。
+const SYNTHETIC_COLUMN: usize = 10_000;
+
+/// 记录的组件和紧凑构造函数体，解析结束时据此生成成员
+#[derive(Debug, Clone)]
+pub struct RecordShape {
+    pub components: Vec<ParameterInfo>,
+    pub compact_constructor: Option<Block>,
+}
+
+/// 生成 equals、hashCode、toString 时需要知道的组件类型信息（本文件及导入模块）
+#[derive(Debug, Default)]
+pub struct ComponentTypes {
+    /// 声明了无参 hashCode() 的类
+    hash_code: HashSet<String>,
+    /// 声明了无参 toString() 的类
+    to_string: HashSet<String>,
+    /// 结构体字段，逐字段比较、计算哈希和输出
+    struct_fields: HashMap<String, Vec<(String, Type)>>,
+}
+
+impl ComponentTypes {
+    /// 登记一组类和结构体（导入模块中的记录此时已经展开，自带 hashCode 和 toString）
+    pub fn add_declarations(&mut self, classes: &[ClassDecl], structs: &[StructDecl]) {
+        for class in classes {
+            if declares_method(&class.members, "hashCode", 0) {
+                self.hash_code.insert(class.name.clone());
+            }
+            if declares_method(&class.members, "toString", 0) {
+                self.to_string.insert(class.name.clone());
+            }
+        }
+        for struct_decl in structs {
+            let fields = struct_decl.fields.iter()
+                .map(|f| (f.name.clone(), f.field_type.clone()))
+                .collect();
+            self.struct_fields.insert(struct_decl.name.clone(), fields);
+        }
+    }
+
+    /// 登记本文件中的记录（生成的 hashCode 和 toString 尚未加入类体）
+    pub fn add_records<'a>(&mut self, names: impl Iterator<Item = &'a String>) {
+        for name in names {
+            self.hash_code.insert(name.clone());
+            self.to_string.insert(name.clone());
+        }
+    }
+}
+
+/// 解析记录声明: [修饰符] record Name(组件, ...) [implements I, ...] ( ';' | '{' 成员 '}' )
+///
+/// 返回只含显式声明成员的类，组件信息记录在解析器中，由 [`lower_records`] 补全其余成员。
+pub fn parse_record(parser: &mut Parser) -> cayResult<(ClassDecl, RecordShape)> {
+    let loc = parser.current_loc();
+    let (annotations, mut modifiers) = parse_annotated_modifiers(parser)?;
+
+    parser.consume(&Token::Record, "期望关键字 'record'\n提示: 记录声明应以 'record' 开头，例如: record Point(int x, int y);")?;
+    let name = parser.consume_identifier("期望记录名\n提示: 在 'record' 后应跟记录名，例如: record Point(int x, int y);")?;
+    if parser.check(&Token::Lt) {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, "记录不支持泛型类型参数\n提示: 为具体的组件类型分别声明记录，或改用泛型类"));
+    }
+
+    parser.consume(&Token::LParen, "期望 '('\n提示: 记录名后应跟组件列表，例如: record Point(int x, int y);")?;
+    let components = parse_parameters(parser)?;
+    parser.consume(&Token::RParen, "期望 ')'\n提示: 组件列表应以 ')' 结束，例如: record Point(int x, int y);")?;
+
+    let mut seen = HashSet::new();
+    for component in &components {
+        if component.is_varargs {
+            return Err(error_at(ErrorCodes::PARSER_INVALID_DECLARATION, &loc, format!(
+                "记录组件 '{}' 不能是可变参数\n提示: 改用数组类型，例如: record Path(String[] parts);",
+                component.name
+            )));
+        }
+        if !seen.insert(component.name.as_str()) {
+            return Err(error_at(ErrorCodes::PARSER_INVALID_DECLARATION, &loc, format!(
+                "记录 '{}' 的组件 '{}' 重复声明\n提示: 每个组件名只能出现一次",
+                name, component.name
+            )));
+        }
+    }
+
+    if parser.check(&Token::Extends) || parser.check(&Token::Colon) {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, "记录不能继承其他类\n提示: 记录隐式为 final 类，只能用 implements 实现接口"));
+    }
+
+    let mut generic_supertypes = Vec::new();
+    let mut interfaces = Vec::new();
+    if parser.match_token(&Token::Implements) {
+        loop {
+            interfaces.push(parse_supertype(parser, &mut generic_supertypes, "期望接口名\n提示: 在 'implements' 后应跟接口名，例如: record Circle(double r) implements Shape { ... }")?);
+            if !parser.match_token(&Token::Comma) {
+                break;
+            }
+        }
+    }
+
+    let mut members = Vec::new();
+    let mut compact_constructor = None;
+    if !parser.match_token(&Token::Semicolon) {
+        parser.consume(&Token::LBrace, "期望 '{' 或 ';'\n提示: 组件列表后应跟记录体，没有记录体时以 ';' 结束，例如: record Point(int x, int y);")?;
+        while !parser.check(&Token::RBrace) && !parser.is_at_end() {
+            let member_loc = parser.current_loc();
+            if let Some(body) = parse_compact_constructor(parser, &name)? {
+                if compact_constructor.is_some() {
+                    return Err(error_at(ErrorCodes::PARSER_INVALID_DECLARATION, &member_loc, format!(
+                        "记录 '{}' 只能有一个紧凑构造函数",
+                        name
+                    )));
+                }
+                compact_constructor = Some(body);
+                continue;
+            }
+            let member = match parse_member_recovering(parser, parse_class_member)? {
+                Some(member) => member,
+                None => continue,
+            };
+            match &member {
+                ClassMember::Field(field) if !field.modifiers.contains(&Modifier::Static) => {
+                    return Err(error_at(ErrorCodes::PARSER_INVALID_DECLARATION, &field.loc, format!(
+                        "记录 '{}' 中不能声明实例字段 '{}'\n提示: 记录的状态全部由组件列表决定，可以把它加入组件列表，或声明为 static 字段",
+                        name, field.name
+                    )));
+                }
+                ClassMember::Property(property) if property.is_auto() && !property.modifiers.contains(&Modifier::Static) => {
+                    return Err(error_at(ErrorCodes::PARSER_INVALID_DECLARATION, &property.loc, format!(
+                        "记录 '{}' 中不能声明自动属性 '{}'\n提示: 自动属性需要实例字段保存值，记录中的属性应带有访问器体，例如: property int area {{ get -> width * height; }}",
+                        name, property.name
+                    )));
+                }
+                _ => {}
+            }
+            push_member(&mut members, member);
+        }
+        parser.consume(&Token::RBrace, "期望 '}'\n提示: 记录体应以 '}' 结束")?;
+    }
+
+    if !modifiers.contains(&Modifier::Final) {
+        modifiers.push(Modifier::Final);
+    }
+
+    let class = ClassDecl {
+        name,
+        modifiers,
+        type_params: Vec::new(),
+        parent: None,
+        interfaces,
+        generic_supertypes,
+        permits: Vec::new(),
+        annotations,
+        members,
+        enclosing_class: None,
+        loc,
+    };
+    Ok((class, RecordShape { components, compact_constructor }))
+}
+
+/// 解析紧凑构造函数 `[public] Name { ... }`，不是紧凑构造函数时回到成员开头并返回 None
+fn parse_compact_constructor(parser: &mut Parser, record_name: &str) -> cayResult<Option<Block>> {
+    let checkpoint = parser.pos;
+    parse_modifiers(parser)?;
+    let is_compact = matches!(parser.current_token(), Token::Identifier(name) if name == record_name)
+        && parser.check_next(&Token::LBrace);
+    if !is_compact {
+        parser.pos = checkpoint;
+        return Ok(None);
+    }
+    parser.advance();
+    parse_block(parser).map(Some)
+}
+
+/// 为记录补全字段、规范构造函数、访问方法以及 equals、hashCode、toString
+pub fn lower_records(
+    classes: &mut [ClassDecl],
+    records: &HashMap<String, RecordShape>,
+    types: &ComponentTypes,
+) -> cayResult<()> {
+    for class in classes.iter_mut() {
+        if let Some(shape) = records.get(&class.name) {
+            let generated = RecordLowering::new(class, types).generate(class, shape)?;
+            class.members.splice(0..0, generated);
+        }
+    }
+    Ok(())
+}
+
+/// 检查类体中是否声明了给定名称和参数个数的方法
+fn declares_method(members: &[ClassMember], name: &str, param_count: usize) -> bool {
+    members.iter().any(|m| matches!(m, ClassMember::Method(method) if method.name == name && method.params.len() == param_count))
+}
+
+/// 生成一个记录的成员，每个生成的节点使用唯一的源码位置
+struct RecordLowering<'a> {
+    types: &'a ComponentTypes,
+    file: Option<String>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> RecordLowering<'a> {
+    fn new(class: &ClassDecl, types: &'a ComponentTypes) -> Self {
+        Self {
+            types,
+            file: class.loc.file.clone(),
+            line: class.loc.line,
+            column: SYNTHETIC_COLUMN,
+        }
+    }
+
+    fn generate(mut self, class: &ClassDecl, shape: &RecordShape) -> cayResult<Vec<ClassMember>> {
+        let mut members = Vec::new();
+        let loc = class.loc.clone();
+
+        for component in &shape.components {
+            members.push(ClassMember::Field(FieldDecl {
+                name: component.name.clone(),
+                field_type: component.param_type.clone(),
+                modifiers: vec![Modifier::Private, Modifier::Final],
+                annotations: component.annotations.clone(),
+                initializer: None,
+                loc: loc.clone(),
+            }));
+        }
+
+        let component_types: Vec<&Type> = shape.components.iter().map(|c| &c.param_type).collect();
+        let has_canonical = class.members.iter().any(|m| matches!(m,
+            ClassMember::Constructor(ctor) if ctor.params.iter().map(|p| &p.param_type).eq(component_types.iter().copied())
+        ));
+        if has_canonical {
+            if shape.compact_constructor.is_some() {
+                return Err(error_at(ErrorCodes::PARSER_INVALID_DECLARATION, &loc, format!(
+                    "记录 '{}' 不能同时声明紧凑构造函数和规范构造函数",
+                    class.name
+                )));
+            }
+        } else {
+            let mut statements = shape.compact_constructor.as_ref()
+                .map(|body| body.statements.clone())
+                .unwrap_or_default();
+            for component in &shape.components {
+                let target = self.this_field(&component.name);
+                let value = self.identifier(&component.name);
+                let assign_loc = self.loc();
+                statements.push(Stmt::Expr(Expr::Assignment(AssignmentExpr {
+                    target: Box::new(target),
+                    value: Box::new(value),
+                    op: AssignOp::Assign,
+                    loc: assign_loc,
+                })));
+            }
+            members.push(ClassMember::Constructor(ConstructorDecl {
+                modifiers: vec![Modifier::Public],
+                params: shape.components.clone(),
+                body: Block { statements, loc: loc.clone() },
+                constructor_call: None,
+                loc: loc.clone(),
+            }));
+        }
+
+        for component in &shape.components {
+            if !declares_method(&class.members, &component.name, 0) {
+                let value = self.this_field(&component.name);
+                members.push(self.method(&component.name, component.param_type.clone(), Vec::new(), vec![Stmt::Return(Some(value))], &loc));
+            }
+        }
+
+        if !class.members.iter().any(|m| matches!(m, ClassMember::Method(method) if method.name == "equals")) {
+            let members_equal = self.equals_body(shape);
+            let other = self.identifier("other");
+            let null = Expr::Literal(LiteralValue::Null);
+            let other_is_null = self.binary(other, BinaryOp::Eq, null);
+            let guard = Stmt::If(IfStmt {
+                condition: other_is_null,
+                then_branch: Box::new(Stmt::Return(Some(Expr::Literal(LiteralValue::Bool(false))))),
+                else_branch: None,
+                loc: self.loc(),
+            });
+            let params = vec![ParameterInfo::new("other".to_string(), Type::Object(class.name.clone()))];
+            members.push(self.method("equals", Type::Bool, params, vec![guard, Stmt::Return(Some(members_equal))], &loc));
+        }
+
+        if !declares_method(&class.members, "hashCode", 0) {
+            let mut hash = Expr::Literal(LiteralValue::Int32(0));
+            for component in &shape.components {
+                let path = vec![component.name.clone()];
+                let component_hash = self.hash(&component.param_type, &path);
+                hash = self.combine_hash(hash, component_hash);
+            }
+            members.push(self.method("hashCode", Type::Int32, Vec::new(), vec![Stmt::Return(Some(hash))], &loc));
+        }
+
+        if !declares_method(&class.members, "toString", 0) {
+            let text = self.describe(&class.name, shape.components.iter().map(|c| (c.name.clone(), c.param_type.clone())).collect(), &[]);
+            members.push(self.method("toString", Type::String, Vec::new(), vec![Stmt::Return(Some(text))], &loc));
+        }
+
+        Ok(members)
+    }
+
+    fn loc(&mut self) -> SourceLocation {
+        self.column += 1;
+        SourceLocation { file: self.file.clone(), line: self.line, column: self.column }
+    }
+
+    fn method(&mut self, name: &str, return_type: Type, params: Vec<ParameterInfo>, statements: Vec<Stmt>, loc: &SourceLocation) -> ClassMember {
+        ClassMember::Method(MethodDecl {
+            name: name.to_string(),
+            modifiers: vec![Modifier::Public],
+            annotations: Vec::new(),
+            type_params: Vec::new(),
+            return_type,
+            params,
+            body: Some(Block { statements, loc: loc.clone() }),
+            loc: loc.clone(),
+        })
+    }
+
+    fn identifier(&mut self, name: &str) -> Expr {
+        Expr::Identifier(IdentifierExpr { name: name.to_string(), loc: self.loc() })
+    }
+
+    fn member(&mut self, object: Expr, member: &str) -> Expr {
+        Expr::MemberAccess(MemberAccessExpr {
+            object: Box::new(object),
+            member: member.to_string(),
+            loc: self.loc(),
+        })
+    }
+
+    fn this_field(&mut self, name: &str) -> Expr {
+        let this = self.identifier("this");
+        self.member(this, name)
+    }
+
+    /// root.path[0].path[1]...，root 为 this 或 other
+    fn access(&mut self, root: &str, path: &[String]) -> Expr {
+        let mut expr = self.identifier(root);
+        for member in path {
+            expr = self.member(expr, member);
+        }
+        expr
+    }
+
+    fn binary(&mut self, left: Expr, op: BinaryOp, right: Expr) -> Expr {
+        Expr::Binary(BinaryExpr {
+            left: Box::new(left),
+            op,
+            right: Box::new(right),
+            loc: self.loc(),
+        })
+    }
+
+    fn call(&mut self, object: Expr, method: &str, args: Vec<Expr>) -> Expr {
+        let callee = self.member(object, method);
+        Expr::Call(CallExpr { callee: Box::new(callee), args, loc: self.loc() })
+    }
+
+    fn cast(&mut self, expr: Expr, target_type: Type) -> Expr {
+        Expr::Cast(CastExpr { expr: Box::new(expr), target_type, loc: self.loc() })
+    }
+
+    fn ternary(&mut self, condition: Expr, true_branch: Expr, false_branch: Expr) -> Expr {
+        Expr::Ternary(TernaryExpr {
+            condition: Box::new(condition),
+            true_branch: Box::new(true_branch),
+            false_branch: Box::new(false_branch),
+            loc: self.loc(),
+        })
+    }
+
+    /// object?.method(args)，组件可能为 null（开启空安全时字段上的 null 检查不会收窄类型）
+    fn safe_call(&mut self, object: Expr, method: &str, args: Vec<Expr>) -> Expr {
+        Expr::SafeCall(SafeCallExpr {
+            object: Box::new(object),
+            member: method.to_string(),
+            args: Some(args),
+            loc: self.loc(),
+        })
+    }
+
+    fn elvis(&mut self, value: Expr, default: Expr) -> Expr {
+        Expr::Elvis(ElvisExpr { value: Box::new(value), default: Box::new(default), loc: self.loc() })
+    }
+
+    fn is_null(&mut self, expr: Expr) -> Expr {
+        self.binary(expr, BinaryOp::Eq, Expr::Literal(LiteralValue::Null))
+    }
+
+    fn struct_fields(&self, ty: &Type) -> Option<Vec<(String, Type)>> {
+        match ty {
+            Type::Struct(name) => self.types.struct_fields.get(name).cloned(),
+            _ => None,
+        }
+    }
+
+    /// 各组件比较结果的合取
+    fn equals_body(&mut self, shape: &RecordShape) -> Expr {
+        let mut result: Option<Expr> = None;
+        for component in &shape.components {
+            let path = vec![component.name.clone()];
+            let equal = self.equality(&component.param_type, &path);
+            result = Some(match result {
+                Some(previous) => self.binary(previous, BinaryOp::And, equal),
+                None => equal,
+            });
+        }
+        result.unwrap_or(Expr::Literal(LiteralValue::Bool(true)))
+    }
+
+    /// this.path 与 other.path 是否相等
+    fn equality(&mut self, ty: &Type, path: &[String]) -> Expr {
+        let ty = unwrap_nullable(ty);
+        if let Some(fields) = self.struct_fields(ty) {
+            let mut result: Option<Expr> = None;
+            for (field, field_type) in fields {
+                let mut field_path = path.to_vec();
+                field_path.push(field);
+                let equal = self.equality(&field_type, &field_path);
+                result = Some(match result {
+                    Some(previous) => self.binary(previous, BinaryOp::And, equal),
+                    None => equal,
+                });
+            }
+            return result.unwrap_or(Expr::Literal(LiteralValue::Bool(true)));
+        }
+
+        let left = self.access("this", path);
+        let right = self.access("other", path);
+        if matches!(ty, Type::String) {
+            // this.s?.equals(other.s) ?: other.s == null（字符串的 == 比较引用）
+            let equals = self.safe_call(left, "equals", vec![right]);
+            let right_null = self.access("other", path);
+            let right_is_null = self.is_null(right_null);
+            return self.elvis(equals, right_is_null);
+        }
+        self.binary(left, BinaryOp::Eq, right)
+    }
+
+    /// 31 * hash + component
+    fn combine_hash(&mut self, hash: Expr, component: Expr) -> Expr {
+        let scaled = self.binary(Expr::Literal(LiteralValue::Int32(31)), BinaryOp::Mul, hash);
+        self.binary(scaled, BinaryOp::Add, component)
+    }
+
+    /// this.path 的 int 哈希值
+    fn hash(&mut self, ty: &Type, path: &[String]) -> Expr {
+        let ty = unwrap_nullable(ty);
+        if let Some(fields) = self.struct_fields(ty) {
+            let mut hash = Expr::Literal(LiteralValue::Int32(0));
+            for (field, field_type) in fields {
+                let mut field_path = path.to_vec();
+                field_path.push(field);
+                let field_hash = self.hash(&field_type, &field_path);
+                hash = self.combine_hash(hash, field_hash);
+            }
+            return hash;
+        }
+
+        let value = self.access("this", path);
+        match ty {
+            Type::Int32 => value,
+            Type::Int8 | Type::Int16 | Type::UInt8 | Type::UInt16 | Type::UInt32 | Type::Char
+            | Type::Float32 | Type::Float64 => self.cast(value, Type::Int32),
+            Type::Int64 | Type::UInt64 => {
+                // (int) (v ^ (v >>> 32))
+                let shifted_value = self.access("this", path);
+                let shifted = self.binary(shifted_value, BinaryOp::UnsignedShr, Expr::Literal(LiteralValue::Int32(32)));
+                let folded = self.binary(value, BinaryOp::BitXor, shifted);
+                self.cast(folded, Type::Int32)
+            }
+            Type::Bool => self.ternary(value, Expr::Literal(LiteralValue::Int32(1231)), Expr::Literal(LiteralValue::Int32(1237))),
+            Type::String => {
+                let hash_code = self.safe_call(value, "hashCode", Vec::new());
+                self.elvis(hash_code, Expr::Literal(LiteralValue::Int32(0)))
+            }
+            Type::Object(name) if self.types.hash_code.contains(name) => {
+                let hash_code = self.safe_call(value, "hashCode", Vec::new());
+                self.elvis(hash_code, Expr::Literal(LiteralValue::Int32(0)))
+            }
+            // 没有 hashCode 的引用类型不参与哈希（与按引用比较的 equals 保持一致）
+            _ => Expr::Literal(LiteralValue::Int32(0)),
+        }
+    }
+
+    /// "Name[a=..., b=...]"，path 为空时描述 this 本身的组件
+    fn describe(&mut self, name: &str, fields: Vec<(String, Type)>, path: &[String]) -> Expr {
+        let mut text = Expr::Literal(LiteralValue::String(format!("{}[", name)));
+        for (index, (field, field_type)) in fields.into_iter().enumerate() {
+            let separator = if index == 0 { "" } else { ", " };
+            let label = Expr::Literal(LiteralValue::String(format!("{}{}=", separator, field)));
+            text = self.binary(text, BinaryOp::Add, label);
+            let mut field_path = path.to_vec();
+            field_path.push(field);
+            let value = self.text(&field_type, &field_path);
+            text = self.binary(text, BinaryOp::Add, value);
+        }
+        self.binary(text, BinaryOp::Add, Expr::Literal(LiteralValue::String("]".to_string())))
+    }
+
+    /// this.path 的字符串表示
+    fn text(&mut self, ty: &Type, path: &[String]) -> Expr {
+        let ty = unwrap_nullable(ty);
+        if let (Some(fields), Type::Struct(name)) = (self.struct_fields(ty), ty) {
+            return self.describe(name, fields, path);
+        }
+
+        let value = self.access("this", path);
+        match ty {
+            Type::String => self.elvis(value, Expr::Literal(LiteralValue::String("null".to_string()))),
+            Type::Int32 | Type::Int64 | Type::Int8 | Type::Int16 | Type::UInt8 | Type::UInt16
+            | Type::UInt32 | Type::UInt64 | Type::Float32 | Type::Float64 | Type::Bool | Type::Char => {
+                let string_class = self.identifier("String");
+                self.call(string_class, "valueOf", vec![value])
+            }
+            Type::Object(class_name) if self.types.to_string.contains(class_name) => {
+                let text = self.safe_call(value, "toString", Vec::new());
+                self.elvis(text, Expr::Literal(LiteralValue::String("null".to_string())))
+            }
+            Type::Object(_) | Type::Array(_) | Type::Generic(..) => {
+                let is_null = self.is_null(value);
+                self.ternary(
+                    is_null,
+                    Expr::Literal(LiteralValue::String("null".to_string())),
+                    Expr::Literal(LiteralValue::String(ty.to_string())),
+                )
+            }
+            _ => Expr::Literal(LiteralValue::String(ty.to_string())),
+        }
+    }
+}
+
+fn unwrap_nullable(ty: &Type) -> &Type {
+    match ty {
+        Type::Nullable(inner) => inner,
+        other => other,
+    }
+}
+
+fn error_at(code: &'static str, loc: &SourceLocation, message: impl Into<String>) -> crate::error::cayError {
+    parser_error_with_file(code, loc.file.clone(), loc.line, loc.column, message)
+}
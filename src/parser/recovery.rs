@@ -0,0 +1,154 @@
+//! 语法错误恢复（panic 模式）
+//!
+//! 启用恢复后，语句、类成员和顶层声明出错时记录错误并跳过令牌直到同步点，然后继续解析：
+//! - 语句在 ';'、结束所在代码块的 '}' 或下一条语句的关键字处同步，AST 中留下 `Stmt::Error`
+//! - 类成员在 ';'、成员体结束的 '}' 或成员修饰符处同步，出错的成员被丢弃
+//! - 顶层声明在下一个声明关键字处同步，出错的声明被丢弃
+//!
+//! 未启用恢复时（`parse`、`parse_with_source` 等）遇到第一个错误立即返回。
+
+use crate::ast::Stmt;
+use crate::error::{cayError, cayResult, get_error_location};
+use crate::lexer::Token;
+use super::Parser;
+use super::statements::parse_statement;
+
+/// 同步点的层级
+#[derive(Clone, Copy, PartialEq)]
+enum SyncLevel {
+    Statement,
+    Member,
+    TopLevel,
+}
+
+impl Parser {
+    /// 启用错误恢复
+    pub(super) fn enable_recovery(&mut self) {
+        self.recovered_errors = Some(Vec::new());
+    }
+
+    /// 取出恢复过程中记录的错误，按位置排序
+    pub(super) fn take_recovered_errors(&mut self) -> Vec<cayError> {
+        let mut errors = self.recovered_errors.take().unwrap_or_default();
+        errors.sort_by_key(|error| get_error_location(error).unwrap_or((0, 0)));
+        errors
+    }
+
+    /// 记录可恢复的错误；未启用恢复时原样返回错误
+    ///
+    /// 同一位置只记录第一个错误（回溯后重新解析同一段代码时会再次遇到相同的错误）。
+    fn record_error(&mut self, error: cayError) -> cayResult<()> {
+        let errors = match &mut self.recovered_errors {
+            Some(errors) => errors,
+            None => return Err(error),
+        };
+        let location = get_error_location(&error);
+        if !errors.iter().any(|recorded| get_error_location(recorded) == location) {
+            errors.push(error);
+        }
+        Ok(())
+    }
+}
+
+/// 解析语句，出错时恢复并返回 `Stmt::Error`
+pub fn parse_statement_recovering(parser: &mut Parser) -> cayResult<Stmt> {
+    let start = parser.pos;
+    let loc = parser.current_loc();
+    match parse_statement(parser) {
+        Ok(stmt) => Ok(stmt),
+        Err(error) => {
+            parser.record_error(error)?;
+            synchronize(parser, start, SyncLevel::Statement);
+            Ok(Stmt::Error(loc))
+        }
+    }
+}
+
+/// 解析类成员，出错时恢复并返回 None
+pub fn parse_member_recovering<T>(parser: &mut Parser, parse: impl FnOnce(&mut Parser) -> cayResult<T>) -> cayResult<Option<T>> {
+    recover(parser, SyncLevel::Member, parse)
+}
+
+/// 解析顶层声明，出错时恢复并返回 None
+pub fn parse_declaration_recovering<T>(parser: &mut Parser, parse: impl FnOnce(&mut Parser) -> cayResult<T>) -> cayResult<Option<T>> {
+    recover(parser, SyncLevel::TopLevel, parse)
+}
+
+fn recover<T>(parser: &mut Parser, level: SyncLevel, parse: impl FnOnce(&mut Parser) -> cayResult<T>) -> cayResult<Option<T>> {
+    let start = parser.pos;
+    match parse(parser) {
+        Ok(value) => Ok(Some(value)),
+        Err(error) => {
+            parser.record_error(error)?;
+            synchronize(parser, start, level);
+            Ok(None)
+        }
+    }
+}
+
+/// 从出错位置跳过令牌直到同步点
+///
+/// 从 `start`（出错的语句、成员或声明的开头）到出错位置之间未闭合的 '{' 和 '(' 计入嵌套深度，
+/// 因此错误发生在 switch 体、方法体或 for 头部等内部时会跳过整个结构，而不是在其中的 ';' 处停下。
+fn synchronize(parser: &mut Parser, start: usize, level: SyncLevel) {
+    let (mut braces, mut parens) = nesting_depth(parser, start);
+    while !parser.is_at_end() {
+        match parser.current_token().clone() {
+            Token::LBrace => braces += 1,
+            // 所在代码块或类体的结束，由外层消耗；顶层多余的 '}' 直接跳过
+            Token::RBrace if braces == 0 && level != SyncLevel::TopLevel => break,
+            Token::RBrace if braces == 0 => {}
+            Token::RBrace => {
+                braces -= 1;
+                if braces == 0 && !parser.check_next(&Token::Else) {
+                    parser.advance();
+                    break;
+                }
+            }
+            Token::LParen => parens += 1,
+            Token::RParen => parens = parens.saturating_sub(1),
+            // 成员和声明的头部不会跨越 ';'，未闭合的 '(' 只在语句中（如 for 头部）才继续跳过
+            Token::Semicolon if braces == 0 && (parens == 0 || level != SyncLevel::Statement) => {
+                parser.advance();
+                break;
+            }
+            token if braces == 0 && parser.pos > start && starts_sync_point(&token, level) => break,
+            _ => {}
+        }
+        parser.advance();
+    }
+    // 保证前进，避免在同一个令牌上反复出错
+    if parser.pos == start && !parser.is_at_end() {
+        parser.advance();
+    }
+}
+
+/// `start` 到当前位置之间未闭合的 '{' 和 '(' 数
+fn nesting_depth(parser: &Parser, start: usize) -> (usize, usize) {
+    let end = parser.pos.min(parser.tokens.len());
+    parser.tokens[start.min(end)..end].iter().fold((0, 0), |(braces, parens), token| match token.token {
+        Token::LBrace => (braces + 1, parens),
+        Token::RBrace => (braces.saturating_sub(1), parens),
+        Token::LParen => (braces, parens + 1),
+        Token::RParen => (braces, parens.saturating_sub(1)),
+        _ => (braces, parens),
+    })
+}
+
+/// 令牌是否开始一个新的语句、成员或顶层声明
+fn starts_sync_point(token: &Token, level: SyncLevel) -> bool {
+    match level {
+        SyncLevel::Statement => matches!(token,
+            Token::If | Token::While | Token::For | Token::Do | Token::Switch | Token::Return
+            | Token::Break | Token::Continue | Token::Var | Token::Let | Token::Auto
+            | Token::Scope | Token::Defer | Token::Try | Token::InlineIr),
+        SyncLevel::Member => matches!(token,
+            Token::Public | Token::Private | Token::Protected | Token::Static | Token::Final
+            | Token::Abstract | Token::Native | Token::Const | Token::AtOverride | Token::At
+            | Token::Class | Token::Property | Token::Void),
+        SyncLevel::TopLevel => matches!(token,
+            Token::Public | Token::Private | Token::Protected | Token::Sealed | Token::Abstract
+            | Token::Final | Token::AtMain | Token::At | Token::Class | Token::Interface
+            | Token::Enum | Token::Struct | Token::Record | Token::Extern | Token::Alias),
+    }
+}
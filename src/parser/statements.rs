@@ -5,6 +5,8 @@ use crate::error::cayResult;
 use super::Parser;
 use super::types::{parse_type, is_primitive_type_token};
 use super::expressions::parse_expression;
+use super::recovery::parse_statement_recovering;
+use crate::diagnostic::ErrorCodes;
 
 /// 给语句添加标签
 fn add_label_to_stmt(stmt: Stmt, label: String) -> Stmt {
@@ -13,6 +15,10 @@ fn add_label_to_stmt(stmt: Stmt, label: String) -> Stmt {
             for_stmt.label = Some(label);
             Stmt::For(for_stmt)
         }
+        Stmt::ForEach(mut for_each) => {
+            for_each.label = Some(label);
+            Stmt::ForEach(for_each)
+        }
         Stmt::While(mut while_stmt) => {
             while_stmt.label = Some(label);
             Stmt::While(while_stmt)
@@ -42,7 +48,7 @@ pub fn parse_block(parser: &mut Parser) -> cayResult<Block> {
             break;
         }
         
-        statements.push(parse_statement(parser)?);
+        statements.push(parse_statement_recovering(parser)?);
     }
 
     parser.consume(&crate::lexer::Token::RBrace, "期望 '}'\n提示: 代码块以 '}' 结束")?;
@@ -80,6 +86,8 @@ pub fn parse_statement(parser: &mut Parser) -> cayResult<Stmt> {
         crate::lexer::Token::Do => parse_do_while_statement(parser),
         crate::lexer::Token::Switch => parse_switch_statement(parser),
         crate::lexer::Token::Scope => parse_scope_statement(parser),
+        crate::lexer::Token::Defer => parse_defer_statement(parser),
+        crate::lexer::Token::Try => parse_try_statement(parser),
         crate::lexer::Token::Return => parse_return_statement(parser),
         crate::lexer::Token::Break => {
             let _loc = parser.current_loc();
@@ -130,9 +138,20 @@ pub fn parse_statement(parser: &mut Parser) -> cayResult<Stmt> {
             if super::types::is_type_token(parser) {
                 // 尝试解析类型（不消耗最终位置）以判断是否紧跟变量名。
                 let checkpoint = parser.pos;
-                if super::types::parse_type(parser).is_ok() {
+                // `int? x` 之类的基本类型不能加 '?'，直接报告类型错误而不是按表达式解析
+                let nullable_primitive = super::types::is_primitive_type_token(parser)
+                    && parser.check_next(&crate::lexer::Token::Question);
+                let parsed = match super::types::parse_type(parser) {
+                    Err(err) if nullable_primitive => return Err(err),
+                    parsed => parsed,
+                };
+                if let Ok(ty) = parsed {
                     // 如果解析类型后当前token是标识符，则认为是变量声明
-                    if let crate::lexer::Token::Identifier(_) = parser.current_token() {
+                    // （`a ? b : c;` 形式的三元表达式语句也能解析出可空类型，变量名后必须是 = ; 或 ,）
+                    let is_declaration = !ty.is_nullable() || [
+                        crate::lexer::Token::Assign, crate::lexer::Token::Semicolon, crate::lexer::Token::Comma,
+                    ].iter().any(|token| parser.check_next(token));
+                    if is_declaration && matches!(parser.current_token(), crate::lexer::Token::Identifier(_)) {
                         parser.pos = checkpoint; // 回退到类型前位置
                         return parse_var_decl(parser);
                     }
@@ -314,6 +333,10 @@ pub fn parse_for_statement(parser: &mut Parser) -> cayResult<Stmt> {
 
     parser.consume(&crate::lexer::Token::LParen, "期望 '('\n提示: for 后应跟 '(' 开始循环头，例如: for (int i = 0; i < 10; i++) { ... }")?;
 
+    if is_for_each_header(parser) {
+        return parse_for_each_rest(parser, loc);
+    }
+
     let init = if parser.check(&crate::lexer::Token::Semicolon) {
         None
     } else {
@@ -347,6 +370,56 @@ pub fn parse_for_statement(parser: &mut Parser) -> cayResult<Stmt> {
     }))
 }
 
+/// 循环头中没有顶层的 ';' 而有顶层的 ':' 时是增强 for 循环: for (T x : iterable)
+fn is_for_each_header(parser: &Parser) -> bool {
+    let mut depth = 0usize;
+    let mut has_colon = false;
+    for token in &parser.tokens[parser.pos..] {
+        match token.token {
+            crate::lexer::Token::LParen | crate::lexer::Token::LBrace | crate::lexer::Token::LBracket => depth += 1,
+            crate::lexer::Token::RParen | crate::lexer::Token::RBrace | crate::lexer::Token::RBracket => {
+                if depth == 0 {
+                    return has_colon;
+                }
+                depth -= 1;
+            }
+            crate::lexer::Token::Semicolon if depth == 0 => return false,
+            crate::lexer::Token::Colon if depth == 0 => has_colon = true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// 解析增强 for 循环 '(' 之后的部分: [final] (类型 | var | let | auto) 变量名 ':' 表达式 ')' 语句
+fn parse_for_each_rest(parser: &mut Parser, loc: crate::error::SourceLocation) -> cayResult<Stmt> {
+    let is_final = parser.match_token(&crate::lexer::Token::Final);
+    let var_type = if parser.match_token(&crate::lexer::Token::Var)
+        || parser.match_token(&crate::lexer::Token::Let)
+        || parser.match_token(&crate::lexer::Token::Auto)
+    {
+        crate::types::Type::Auto
+    } else {
+        parse_type(parser)?
+    };
+    let var_name = parser.consume_identifier("期望循环变量名\n提示: 增强 for 循环的格式为 for (类型 变量名 : 数组或集合)，例如: for (int x : numbers) { ... }")?;
+    parser.consume(&crate::lexer::Token::Colon, "期望 ':'\n提示: 循环变量后应跟 ':' 和要遍历的数组或集合，例如: for (int x : numbers) { ... }")?;
+    let iterable = parse_expression(parser)?;
+    parser.consume(&crate::lexer::Token::RParen, "期望 ')'\n提示: for 循环头应以 ')' 结束，例如: for (int x : numbers) { ... }")?;
+
+    let body = Box::new(parse_statement(parser)?);
+
+    Ok(Stmt::ForEach(ForEachStmt {
+        var_type,
+        var_name,
+        is_final,
+        iterable,
+        body,
+        label: None,
+        loc,
+    }))
+}
+
 /// 解析 do-while 语句
 pub fn parse_do_while_statement(parser: &mut Parser) -> cayResult<Stmt> {
     let loc = parser.current_loc();
@@ -368,7 +441,38 @@ pub fn parse_do_while_statement(parser: &mut Parser) -> cayResult<Stmt> {
     }))
 }
 
+/// 解析 case 标签中的枚举变体模式: Red / Circle(r) / Shape.Rect(w, h)
+fn parse_enum_pattern(parser: &mut Parser) -> cayResult<EnumPattern> {
+    let loc = parser.current_loc();
+    let mut enum_name = None;
+    let mut variant = parser.consume_identifier("期望枚举变体名\n提示: 例如: case Circle(r):")?;
+
+    // 允许带枚举名限定: Shape.Rect
+    if parser.match_token(&crate::lexer::Token::Dot) {
+        enum_name = Some(variant);
+        variant = parser.consume_identifier("期望枚举变体名\n提示: 枚举名后应跟 '.' 和变体名，例如: case Shape.Rect(w, h):")?;
+    }
+
+    let mut bindings = Vec::new();
+    if parser.match_token(&crate::lexer::Token::LParen) {
+        if !parser.check(&crate::lexer::Token::RParen) {
+            loop {
+                bindings.push(parser.consume_identifier("期望绑定变量名\n提示: 变体负载按顺序绑定到变量，例如: case Rect(w, h):")?);
+                if !parser.match_token(&crate::lexer::Token::Comma) {
+                    break;
+                }
+            }
+        }
+        parser.consume(&crate::lexer::Token::RParen, "期望 ')'\n提示: 绑定列表应以 ')' 结束，例如: case Rect(w, h):")?;
+    }
+
+    Ok(EnumPattern { enum_name, variant, bindings, loc })
+}
+
 /// 解析 switch 语句
+///
+/// 分支可以写成 `case 1, 2:`（可穿透）或 `case 1, 2 -> stmt`（执行完直接跳出 switch），
+/// 同一个 switch 中不能混用两种写法。
 pub fn parse_switch_statement(parser: &mut Parser) -> cayResult<Stmt> {
     let loc = parser.current_loc();
     parser.advance(); // consume 'switch'
@@ -381,144 +485,309 @@ pub fn parse_switch_statement(parser: &mut Parser) -> cayResult<Stmt> {
     
     let mut cases = Vec::new();
     let mut default = None;
+    // 第一个分支决定整个 switch 使用 ':' 还是 '->'
+    let mut arrow_form: Option<bool> = None;
     
     while !parser.check(&crate::lexer::Token::RBrace) && !parser.is_at_end() {
         if parser.match_token(&crate::lexer::Token::Case) {
-            // 解析 case 值
-            let value = match *parser.current_token() {
-                crate::lexer::Token::IntegerLiteral(Some((v, _))) => {
-                    let val = v;  // v 是 i64
-                    parser.advance();
-                    val
-                }
-                _ => {
-                    let current_token = parser.current_token();
-                    let (token_desc, suggestion) = match current_token {
-                        crate::lexer::Token::Identifier(name) => (
-                            format!("标识符('{}')", name),
-                            format!("case 标签必须是整数常量。可能的问题:\n    - 使用了变量 '{}', 应使用常量，如: case 1:\n    - 需要定义常量: final int {} = 1;", name, name.to_uppercase())
-                        ),
-                        crate::lexer::Token::StringLiteral(Some(s)) => (
-                            format!("字符串(\"{}\")", s),
-                            "case 标签不支持字符串。可能的问题:\n    - 应使用整数常量，如: case 1:\n    - 如果需要字符串匹配，考虑使用 if-else 链".to_string()
-                        ),
-                        crate::lexer::Token::FloatLiteral(Some((val, _))) => (
-                            format!("浮点数({})", val),
-                            "case 标签必须是整数常量，不能使用浮点数。可能的问题:\n    - 应使用整数，如: case 1: 而不是 case 1.0:".to_string()
-                        ),
-                        crate::lexer::Token::True | crate::lexer::Token::False => (
-                            "布尔值".to_string(),
-                            "case 标签必须是整数常量。可能的问题:\n    - 应使用整数，如: case 1: 表示 true, case 0: 表示 false".to_string()
-                        ),
-                        crate::lexer::Token::Colon => (
-                            "冒号(:)".to_string(),
-                            "case 标签缺少值。可能的问题:\n    - case 后缺少整数常量，如: case : 应该是 case 1:".to_string()
-                        ),
-                        crate::lexer::Token::Semicolon => (
-                            "分号(;)".to_string(),
-                            "case 标签格式错误。可能的问题:\n    - case 后缺少值和冒号，如: case ; 应该是 case 1: ...;".to_string()
-                        ),
-                        crate::lexer::Token::Case => (
-                            "关键字(case)".to_string(),
-                            "case 标签重复或缺少值。可能的问题:\n    - 两个 case 之间缺少值，如: case case 1: 应该是 case 0: case 1:".to_string()
-                        ),
-                        crate::lexer::Token::Default => (
-                            "关键字(default)".to_string(),
-                            "default 标签位置错误。可能的问题:\n    - case 和 default 不能在同一位置\n    - default 应该单独使用: default:".to_string()
-                        ),
-                        _ => {
-                            let token_name = super::utils::get_token_name(current_token);
-                            (
-                                token_name.clone(),
-                                format!("case 标签必须是整数常量。可能的问题:\n    - 使用了不合法的值\n    - 应使用整数常量，如: case 1:")
-                            )
-                        }
-                    };
-                    return Err(parser.error(&format!(
-                        "期望整数常量，但遇到了 {}\n提示: {}",
-                        token_desc, suggestion
-                    )));
-                }
-            };
-            parser.consume(&crate::lexer::Token::Colon, "期望 ':'\n提示: case 值后应跟 ':'，例如: case 1:")?;
-            
-            // 解析 case 体（直到遇到另一个 case、default 或 }）
-            let mut body = Vec::new();
-            while !parser.check(&crate::lexer::Token::Case) && !parser.check(&crate::lexer::Token::Default)
-                && !parser.check(&crate::lexer::Token::RBrace) && !parser.is_at_end() {
-                body.push(parse_statement(parser)?);
-            }
-            
-            cases.push(Case { value, body });
+            let labels = parse_case_labels(parser)?;
+            let guard = parse_case_guard(parser)?;
+            let body = parse_case_body(parser, &mut arrow_form, "case 1")?;
+            cases.push(Case { labels, guard, body });
         } else if parser.match_token(&crate::lexer::Token::Default) {
-            parser.consume(&crate::lexer::Token::Colon, "期望 ':'\n提示: default 后应跟 ':'，例如: default:")?;
+            default = Some(parse_case_body(parser, &mut arrow_form, "default")?);
+        } else {
+            return Err(unexpected_switch_token(parser));
+        }
+    }
 
-            // 解析 default 体
-            let mut body = Vec::new();
-            while !parser.check(&crate::lexer::Token::Case) && !parser.check(&crate::lexer::Token::Default)
-                && !parser.check(&crate::lexer::Token::RBrace) && !parser.is_at_end() {
-                body.push(parse_statement(parser)?);
-            }
+    parser.consume(&crate::lexer::Token::RBrace, "期望 '}'\n提示: switch 体以 '}' 结束")?;
+    
+    Ok(Stmt::Switch(SwitchStmt {
+        expr,
+        cases,
+        default,
+        loc,
+    }))
+}
+
+/// 解析 switch 表达式: switch (cmd) { case "get", "head" -> 1; default -> 0; }
+pub fn parse_switch_expression(parser: &mut Parser) -> cayResult<Expr> {
+    let loc = parser.current_loc();
+    parser.advance(); // consume 'switch'
 
-            default = Some(body);
+    parser.consume(&crate::lexer::Token::LParen, "期望 '('\n提示: switch 后应跟 '(' 开始表达式，例如: int n = switch (x) { ... };")?;
+    let expr = parse_expression(parser)?;
+    parser.consume(&crate::lexer::Token::RParen, "期望 ')'\n提示: 表达式应以 ')' 结束，例如: int n = switch (x) { ... };")?;
+    parser.consume(&crate::lexer::Token::LBrace, "期望 '{'\n提示: switch 体以 '{' 开始，例如: int n = switch (x) { case 1 -> 10; default -> 0; };")?;
+
+    let mut arms = Vec::new();
+    let mut default = None;
+    while !parser.check(&crate::lexer::Token::RBrace) && !parser.is_at_end() {
+        if parser.match_token(&crate::lexer::Token::Case) {
+            let labels = parse_case_labels(parser)?;
+            let guard = parse_case_guard(parser)?;
+            let value = parse_switch_arm_value(parser, "case 1")?;
+            arms.push(SwitchArm { labels, guard, value });
+        } else if parser.match_token(&crate::lexer::Token::Default) {
+            if default.is_some() {
+                return Err(parser.error(ErrorCodes::PARSER_INVALID_STATEMENT, "switch 表达式中有多个 default 分支\n提示: 每个 switch 只能有一个 default 分支"));
+            }
+            default = Some(Box::new(parse_switch_arm_value(parser, "default")?));
         } else {
+            return Err(unexpected_switch_token(parser));
+        }
+    }
+
+    parser.consume(&crate::lexer::Token::RBrace, "期望 '}'\n提示: switch 体以 '}' 结束")?;
+
+    Ok(Expr::Switch(SwitchExpr {
+        expr: Box::new(expr),
+        arms,
+        default,
+        loc,
+    }))
+}
+
+/// 解析 switch 表达式分支的值: -> expr;
+fn parse_switch_arm_value(parser: &mut Parser, head: &str) -> cayResult<Expr> {
+    if parser.check(&crate::lexer::Token::Colon) {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_STATEMENT, &format!(
+            "switch 表达式的分支必须使用 '->'\n提示: 例如: {} -> value;", head
+        )));
+    }
+    parser.consume(&crate::lexer::Token::Arrow, "期望 '->'\n提示: switch 表达式的分支格式为 case 标签 -> 值;")?;
+    if parser.check(&crate::lexer::Token::LBrace) {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_STATEMENT, &format!(
+            "switch 表达式的分支只能是单个表达式\n提示: 例如: {} -> value;，需要多条语句时请使用 switch 语句", head
+        )));
+    }
+    let value = parse_expression(parser)?;
+    parser.consume(&crate::lexer::Token::Semicolon, "期望 ';'\n提示: switch 表达式的每个分支应以 ';' 结束，例如: case 1 -> 10;")?;
+    Ok(value)
+}
+
+/// 解析 case 标签后的分支体
+///
+/// `:` 形式的分支体延续到下一个 case、default 或 `}`；`->` 形式的分支体是一条语句，
+/// 执行完后跳出 switch，因此在末尾补上 break。
+fn parse_case_body(parser: &mut Parser, arrow_form: &mut Option<bool>, head: &str) -> cayResult<Vec<Stmt>> {
+    let is_arrow = if parser.match_token(&crate::lexer::Token::Arrow) {
+        true
+    } else {
+        parser.consume(&crate::lexer::Token::Colon, &format!(
+            "期望 ':' 或 '->'\n提示: case 标签后应跟 ':' 或 '->'，例如: {}: 或 {} -> ...", head, head
+        ))?;
+        false
+    };
+    match *arrow_form {
+        Some(previous) if previous != is_arrow => {
+            return Err(parser.error(
+                ErrorCodes::PARSER_INVALID_STATEMENT,
+                "同一个 switch 中不能混用 ':' 和 '->' 形式的分支\n提示: 全部使用 case 1: ... break; 或全部使用 case 1 -> ...;"
+            ));
+        }
+        _ => *arrow_form = Some(is_arrow),
+    }
+
+    let mut body = Vec::new();
+    if is_arrow {
+        body.push(parse_statement(parser)?);
+        body.push(Stmt::Break(None));
+    } else {
+        while !parser.check(&crate::lexer::Token::Case) && !parser.check(&crate::lexer::Token::Default)
+            && !parser.check(&crate::lexer::Token::RBrace) && !parser.is_at_end() {
+            body.push(parse_statement(parser)?);
+        }
+    }
+    Ok(body)
+}
+
+/// 解析 case 守卫条件: case "get" when verbose:
+fn parse_case_guard(parser: &mut Parser) -> cayResult<Option<Expr>> {
+    match parser.current_token() {
+        crate::lexer::Token::Identifier(name) if name == "when" => {
+            parser.advance();
+            Ok(Some(parse_expression(parser)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// 解析以逗号分隔的 case 标签: case 1, 2, 3
+fn parse_case_labels(parser: &mut Parser) -> cayResult<Vec<CaseLabel>> {
+    let mut labels = vec![parse_case_label(parser)?];
+    while parser.match_token(&crate::lexer::Token::Comma) {
+        labels.push(parse_case_label(parser)?);
+    }
+    Ok(labels)
+}
+
+/// 解析单个 case 标签；字面量或常量名后还有运算符时整体按常量表达式解析: case BASE + 1:
+fn parse_case_label(parser: &mut Parser) -> cayResult<CaseLabel> {
+    let checkpoint = parser.pos;
+    let loc = parser.current_loc();
+    let starts_expression = matches!(parser.current_token(),
+        crate::lexer::Token::LParen | crate::lexer::Token::Tilde | crate::lexer::Token::Bang);
+    if !starts_expression {
+        // 以标识符或负号开头的标签可能是常量函数调用或表达式: case square(3): / case -MAX:
+        let may_be_expression = matches!(parser.current_token(),
+            crate::lexer::Token::Identifier(_) | crate::lexer::Token::Minus);
+        match parse_simple_case_label(parser) {
+            Ok(label) if matches!(label, CaseLabel::Type(_)) || is_case_label_end(parser) => return Ok(label),
+            Err(err) if !may_be_expression => return Err(err),
+            _ => parser.pos = checkpoint,
+        }
+    }
+    let expr = parse_expression(parser)?;
+    Ok(CaseLabel::Const(ConstLabel { expr, loc }))
+}
+
+/// case 标签之后应是 ':'、'->'、','（下一个标签）或守卫关键字 when
+fn is_case_label_end(parser: &Parser) -> bool {
+    match parser.current_token() {
+        crate::lexer::Token::Colon | crate::lexer::Token::Arrow | crate::lexer::Token::Comma => true,
+        crate::lexer::Token::Identifier(name) => name == "when",
+        _ => false,
+    }
+}
+
+/// 解析单个简单 case 标签：整数、字符、字符串字面量，或以标识符开头的常量/枚举变体模式
+fn parse_simple_case_label(parser: &mut Parser) -> cayResult<CaseLabel> {
+    match *parser.current_token() {
+        crate::lexer::Token::IntegerLiteral(Some((v, _))) => {
+            parser.advance();
+            Ok(CaseLabel::Int(v))
+        }
+        crate::lexer::Token::Minus => {
+            parser.advance();
+            match *parser.current_token() {
+                crate::lexer::Token::IntegerLiteral(Some((v, _))) => {
+                    parser.advance();
+                    Ok(CaseLabel::Int(-v))
+                }
+                _ => Err(parser.error(ErrorCodes::PARSER_UNEXPECTED_TOKEN, "期望整数\n提示: 负数标签应写成 case -1:")),
+            }
+        }
+        crate::lexer::Token::CharLiteral(Some(c)) => {
+            parser.advance();
+            Ok(CaseLabel::Char(c))
+        }
+        crate::lexer::Token::StringLiteral(Some(ref s)) => {
+            let s = s.clone();
+            parser.advance();
+            Ok(CaseLabel::String(s))
+        }
+        crate::lexer::Token::Identifier(_) if is_type_pattern(parser) => {
+            // 类型模式: case Circle c:
+            let loc = parser.current_loc();
+            let target_type = super::types::parse_non_null_type(parser)?;
+            let binding = parser.consume_identifier("期望模式变量名\n提示: 类型模式的格式为 case 类型 变量名，例如: case Circle c ->")?;
+            Ok(CaseLabel::Type(TypePattern { target_type, binding, loc }))
+        }
+        crate::lexer::Token::Identifier(_) => {
+            // 枚举变体模式: case Red: / case Circle(r):，或常量: case MAX: / case Limits.MAX:
+            Ok(CaseLabel::Pattern(parse_enum_pattern(parser)?))
+        }
+        _ => {
             let current_token = parser.current_token();
             let (token_desc, suggestion) = match current_token {
-                crate::lexer::Token::RBrace => (
-                    "右花括号(})".to_string(),
-                    "switch 体为空或提前结束。可能的问题:\n    - switch 语句缺少 case 或 default 分支\n    - 在添加分支前关闭了 switch 体".to_string()
+                crate::lexer::Token::FloatLiteral(Some((val, _))) => (
+                    format!("浮点数({})", val),
+                    "case 标签不能使用浮点数。可能的问题:\n    - 应使用整数，如: case 1: 而不是 case 1.0:".to_string()
+                ),
+                crate::lexer::Token::True | crate::lexer::Token::False => (
+                    "布尔值".to_string(),
+                    "case 标签不能使用布尔值。可能的问题:\n    - 布尔条件应使用 if-else\n    - 或使用整数，如: case 1: 表示 true, case 0: 表示 false".to_string()
+                ),
+                crate::lexer::Token::Colon => (
+                    "冒号(:)".to_string(),
+                    "case 标签缺少值。可能的问题:\n    - case 后缺少常量，如: case : 应该是 case 1:".to_string()
                 ),
                 crate::lexer::Token::Semicolon => (
                     "分号(;)".to_string(),
-                    "switch 体内不能直接放置分号。可能的问题:\n    - 多余的空语句\n    - 语句位置错误，应在 case 标签后".to_string()
+                    "case 标签格式错误。可能的问题:\n    - case 后缺少值和冒号，如: case ; 应该是 case 1: ...;".to_string()
                 ),
-                crate::lexer::Token::Identifier(name) => (
-                    format!("标识符('{}')", name),
-                    format!("switch 体内只能包含 case 或 default 标签。可能的问题:\n    - 缺少 case 关键字，如: {}: 应该是 case 1:\n    - 语句位置错误，应在 case 标签后", name)
+                crate::lexer::Token::Case => (
+                    "关键字(case)".to_string(),
+                    "case 标签重复或缺少值。可能的问题:\n    - 两个 case 之间缺少值，如: case case 1: 应该是 case 0: case 1:\n    - 多个标签应使用逗号分隔，如: case 0, 1:".to_string()
                 ),
-                crate::lexer::Token::IntegerLiteral(Some((val, _))) => (
-                    format!("整数({})", val),
-                    format!("switch 体内只能包含 case 或 default 标签。可能的问题:\n    - 缺少 case 关键字，如: {}: 应该是 case {}:", val, val)
-                ),
-                crate::lexer::Token::If | crate::lexer::Token::While |
-                crate::lexer::Token::For | crate::lexer::Token::Return => {
-                    let kw = format!("{:?}", current_token).to_lowercase();
-                    (
-                        format!("关键字({})", kw),
-                        format!("{} 语句必须在 case 或 default 标签后。可能的问题:\n    - 缺少 case 标签\n    - 语句缩进错误", kw)
-                    )
-                }
-                crate::lexer::Token::LBrace => (
-                    "左花括号({)".to_string(),
-                    "switch 体内不能直接嵌套代码块。可能的问题:\n    - 代码块应在 case 标签后\n    - 考虑使用 case 1: {{ ... }} 语法".to_string()
-                ),
-                crate::lexer::Token::Colon => (
-                    "冒号(:)".to_string(),
-                    "冒号位置错误。可能的问题:\n    - case 标签缺少值，如: case : 应该是 case 1:\n    - 多余的冒号".to_string()
+                crate::lexer::Token::Default => (
+                    "关键字(default)".to_string(),
+                    "default 标签位置错误。可能的问题:\n    - case 和 default 不能在同一位置\n    - default 应该单独使用: default:".to_string()
                 ),
                 _ => {
                     let token_name = super::utils::get_token_name(current_token);
                     (
                         token_name.clone(),
-                        format!("switch 体内只能包含 case 或 default 标签。可能的问题:\n    - 语句位置错误\n    - 缺少 case/default 关键字")
+                        "case 标签必须是常量。可能的问题:\n    - 使用了不合法的值\n    - 应使用整数、字符、字符串字面量、static final 常量或常量表达式，如: case 1: / case 'a': / case \"get\": / case MAX: / case MAX + 1:".to_string()
                     )
                 }
             };
-            return Err(parser.error(&format!(
-                "期望 'case' 或 'default'，但遇到了 {}\n提示: {}",
+            Err(parser.error(ErrorCodes::PARSER_UNEXPECTED_TOKEN, &format!(
+                "期望 case 常量，但遇到了 {}\n提示: {}",
                 token_desc, suggestion
-            )));
+            )))
         }
     }
+}
 
-    parser.consume(&crate::lexer::Token::RBrace, "期望 '}'\n提示: switch 体以 '}' 结束")?;
-    
-    Ok(Stmt::Switch(SwitchStmt {
-        expr,
-        cases,
-        default,
-        loc,
-    }))
+/// 类名后紧跟变量名（守卫关键字 when 除外）时是类型模式: case Circle c
+fn is_type_pattern(parser: &Parser) -> bool {
+    match parser.tokens.get(parser.pos + 1).map(|t| &t.token) {
+        Some(crate::lexer::Token::Identifier(name)) => name != "when",
+        _ => false,
+    }
+}
+
+/// switch 体中出现了 case/default 以外的内容
+fn unexpected_switch_token(parser: &Parser) -> crate::error::cayError {
+    let current_token = parser.current_token();
+    let (token_desc, suggestion) = match current_token {
+        crate::lexer::Token::RBrace => (
+            "右花括号(})".to_string(),
+            "switch 体为空或提前结束。可能的问题:\n    - switch 语句缺少 case 或 default 分支\n    - 在添加分支前关闭了 switch 体".to_string()
+        ),
+        crate::lexer::Token::Semicolon => (
+            "分号(;)".to_string(),
+            "switch 体内不能直接放置分号。可能的问题:\n    - 多余的空语句\n    - 语句位置错误，应在 case 标签后".to_string()
+        ),
+        crate::lexer::Token::Identifier(name) => (
+            format!("标识符('{}')", name),
+            format!("switch 体内只能包含 case 或 default 标签。可能的问题:\n    - 缺少 case 关键字，如: {}: 应该是 case 1:\n    - 语句位置错误，应在 case 标签后", name)
+        ),
+        crate::lexer::Token::IntegerLiteral(Some((val, _))) => (
+            format!("整数({})", val),
+            format!("switch 体内只能包含 case 或 default 标签。可能的问题:\n    - 缺少 case 关键字，如: {}: 应该是 case {}:", val, val)
+        ),
+        crate::lexer::Token::If | crate::lexer::Token::While |
+        crate::lexer::Token::For | crate::lexer::Token::Return => {
+            let kw = format!("{:?}", current_token).to_lowercase();
+            (
+                format!("关键字({})", kw),
+                format!("{} 语句必须在 case 或 default 标签后。可能的问题:\n    - 缺少 case 标签\n    - 语句缩进错误", kw)
+            )
+        }
+        crate::lexer::Token::LBrace => (
+            "左花括号({)".to_string(),
+            "switch 体内不能直接嵌套代码块。可能的问题:\n    - 代码块应在 case 标签后\n    - 考虑使用 case 1: {{ ... }} 语法".to_string()
+        ),
+        crate::lexer::Token::Colon => (
+            "冒号(:)".to_string(),
+            "冒号位置错误。可能的问题:\n    - case 标签缺少值，如: case : 应该是 case 1:\n    - 多余的冒号".to_string()
+        ),
+        _ => {
+            let token_name = super::utils::get_token_name(current_token);
+            (
+                token_name.clone(),
+                format!("switch 体内只能包含 case 或 default 标签。可能的问题:\n    - 语句位置错误\n    - 缺少 case/default 关键字")
+            )
+        }
+    };
+    parser.error(ErrorCodes::PARSER_UNEXPECTED_TOKEN, &format!(
+        "期望 'case' 或 'default'，但遇到了 {}\n提示: {}",
+        token_desc, suggestion
+    ))
 }
 
 /// 解析 return 语句
@@ -564,6 +833,101 @@ pub fn parse_scope_statement(parser: &mut Parser) -> cayResult<Stmt> {
     Ok(Stmt::Scope(ScopeStmt { body, loc }))
 }
 
+/// 解析 defer 语句
+/// 被延迟的语句在所在代码块退出时执行，包括 return、break 和 continue 离开代码块
+///
+/// 语法: defer statement
+///
+/// 示例:
+///   defer file.close();
+///   defer { lock.release(); println("released"); }
+pub fn parse_defer_statement(parser: &mut Parser) -> cayResult<Stmt> {
+    let loc = parser.current_loc();
+    parser.advance(); // consume 'defer'
+
+    if parser.check(&crate::lexer::Token::Semicolon) {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_STATEMENT, "defer 后缺少要延迟执行的语句\n提示: 例如: defer file.close();"));
+    }
+    if parser.check(&crate::lexer::Token::Defer) {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_STATEMENT, "defer 不能嵌套\n提示: 多个 defer 按后进先出的顺序执行，直接依次书写即可"));
+    }
+
+    let body = parse_statement(parser)?;
+    if matches!(body, Stmt::VarDecl(_)) {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_STATEMENT, "defer 不能延迟变量声明\n提示: 在 defer 之前声明变量，例如: File f = ...; defer f.close();"));
+    }
+
+    Ok(Stmt::Defer(DeferStmt { body: Box::new(body), loc }))
+}
+
+/// 解析 try-with-resources 语句
+/// 资源在代码块退出时按声明的相反顺序调用 close()
+///
+/// 语法: try (Type name = expr; Type name = expr) { statements... }
+///
+/// 示例:
+///   try (File f = new File("a.txt", FileMode.read())) {
+///       println(f.readLine());
+///   } // f.close() 在这里自动调用
+pub fn parse_try_statement(parser: &mut Parser) -> cayResult<Stmt> {
+    let loc = parser.current_loc();
+    parser.advance(); // consume 'try'
+
+    if !parser.check(&crate::lexer::Token::LParen) {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_STATEMENT, "try 语句缺少资源声明\n提示: try 用于自动关闭资源，例如: try (File f = new File(path, mode)) { ... }"));
+    }
+    parser.advance(); // consume '('
+
+    let mut resources = Vec::new();
+    loop {
+        // 允许最后一个资源后的 ';'
+        if !resources.is_empty() && parser.check(&crate::lexer::Token::RParen) {
+            break;
+        }
+        resources.push(parse_resource(parser)?);
+        if !parser.match_token(&crate::lexer::Token::Semicolon) {
+            break;
+        }
+    }
+    parser.consume(&crate::lexer::Token::RParen, "期望 ')'\n提示: 多个资源之间用 ';' 分隔，例如: try (File a = ...; File b = ...) { ... }")?;
+
+    let body = parse_block(parser)?;
+
+    Ok(Stmt::TryWithResources(TryStmt { resources, body, loc }))
+}
+
+/// 解析 try-with-resources 中的一个资源声明: [final] Type name = expr 或 var name = expr
+fn parse_resource(parser: &mut Parser) -> cayResult<VarDecl> {
+    let loc = parser.current_loc();
+    let is_final = parser.match_token(&crate::lexer::Token::Final);
+
+    let var_type = if parser.match_token(&crate::lexer::Token::Var)
+        || parser.match_token(&crate::lexer::Token::Let)
+        || parser.match_token(&crate::lexer::Token::Auto)
+    {
+        crate::types::Type::Auto
+    } else {
+        parse_type(parser)?
+    };
+
+    let name = parser.consume_identifier("期望资源变量名\n提示: 资源声明的格式为 类型 变量名 = 表达式，例如: File f = new File(path, mode)")?;
+    if !parser.match_token(&crate::lexer::Token::Assign) {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_STATEMENT, &format!(
+            "资源 '{}' 缺少初始化表达式\n提示: try 的资源必须在声明时初始化，例如: try (File {} = new File(path, mode)) {{ ... }}",
+            name, name
+        )));
+    }
+    let initializer = parse_expression(parser)?;
+
+    Ok(VarDecl {
+        name,
+        var_type,
+        initializer: Some(initializer),
+        is_final,
+        loc,
+    })
+}
+
 /// 解析内联IR语句
 /// 
 /// 语法: __ir { raw_llvm_ir_lines... }
@@ -669,6 +1033,7 @@ fn extract_inline_ir_from_source(source: &str, start_loc: &crate::error::SourceL
 
     // 如果直接提取失败，返回错误
     Err(crate::error::parser_error(
+        ErrorCodes::PARSER_INVALID_STATEMENT,
         start_loc.line,
         start_loc.column,
         "无法从源代码提取内联IR块"
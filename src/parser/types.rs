@@ -1,14 +1,40 @@
 //! 类型解析
 
-use crate::types::Type;
+use crate::types::{Type, TypeParamInfo};
 use crate::error::cayResult;
 use super::Parser;
+use crate::diagnostic::ErrorCodes;
 
-/// 解析类型（支持多维数组和指针，以及类型别名）
+/// 解析类型（支持多维数组和指针，以及类型别名），引用类型后可以跟 `?` 表示可空: String?
 pub fn parse_type(parser: &mut Parser) -> cayResult<Type> {
+    let ty = parse_non_null_type(parser)?;
+    if !parser.check(&crate::lexer::Token::Question) {
+        return Ok(ty);
+    }
+    let nullable_allowed = matches!(ty,
+        Type::String | Type::Object(_) | Type::Array(_) | Type::Enum(_) | Type::Generic(_, _) | Type::TypeParam(_)
+    );
+    if !nullable_allowed {
+        return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, &format!(
+            "类型 {} 不能声明为可空类型\n提示: 只有引用类型（String、类、数组、枚举）可以加 '?'，基本类型和结构体的值不会是 null",
+            ty
+        )));
+    }
+    parser.advance();
+    Ok(Type::Nullable(Box::new(ty)))
+}
+
+/// 解析不带可空标记的类型（instanceof 的目标类型等）
+pub fn parse_non_null_type(parser: &mut Parser) -> cayResult<Type> {
     let base_type = match parser.current_token() {
         crate::lexer::Token::Int => { parser.advance(); Type::Int32 }
         crate::lexer::Token::Long => { parser.advance(); Type::Int64 }
+        crate::lexer::Token::Byte => { parser.advance(); Type::Int8 }
+        crate::lexer::Token::UByte => { parser.advance(); Type::UInt8 }
+        crate::lexer::Token::Short => { parser.advance(); Type::Int16 }
+        crate::lexer::Token::UShort => { parser.advance(); Type::UInt16 }
+        crate::lexer::Token::UInt => { parser.advance(); Type::UInt32 }
+        crate::lexer::Token::ULong => { parser.advance(); Type::UInt64 }
         crate::lexer::Token::Float => { parser.advance(); Type::Float32 }
         crate::lexer::Token::Double => { parser.advance(); Type::Float64 }
         crate::lexer::Token::Bool => { parser.advance(); Type::Bool }
@@ -38,8 +64,20 @@ pub fn parse_type(parser: &mut Parser) -> cayResult<Type> {
         crate::lexer::Token::Identifier(name) => {
             let name = name.clone();
             parser.advance();
-            // 检查是否是已定义的类型别名
-            if let Some(aliased_type) = parser.get_type_alias(&name) {
+            if parser.is_type_param(&name) {
+                // 泛型类型参数: T
+                Type::TypeParam(name)
+            } else if parser.is_generic_type(&name) && parser.check(&crate::lexer::Token::Lt) {
+                // 泛型实例化: Box<int>
+                Type::Generic(name, parse_type_args(parser)?)
+            } else if parser.is_enum_type(&name) {
+                // 枚举类型: Shape
+                Type::Enum(name)
+            } else if parser.is_struct_type(&name) {
+                // 结构体类型: Point
+                Type::Struct(name)
+            } else if let Some(aliased_type) = parser.get_type_alias(&name) {
+                // 检查是否是已定义的类型别名
                 aliased_type
             } else {
                 Type::Object(name)
@@ -138,7 +176,7 @@ pub fn parse_type(parser: &mut Parser) -> cayResult<Type> {
                     )
                 }
             };
-            return Err(parser.error(&format!(
+            return Err(parser.error(ErrorCodes::PARSER_EXPECTED_TYPE, &format!(
                 "期望类型，但遇到了 {}\n提示: {}",
                 token_desc, suggestion
             )));
@@ -160,10 +198,80 @@ pub fn parse_type(parser: &mut Parser) -> cayResult<Type> {
     Ok(result_type)
 }
 
+/// 解析泛型类型实参列表: <int, String>
+pub fn parse_type_args(parser: &mut Parser) -> cayResult<Vec<Type>> {
+    parser.consume(&crate::lexer::Token::Lt, "期望 '<'\n提示: 泛型类型实参应以 '<' 开始，例如: Box<int>")?;
+    let mut args = Vec::new();
+    loop {
+        args.push(parse_type(parser)?);
+        if !parser.match_token(&crate::lexer::Token::Comma) {
+            break;
+        }
+    }
+    consume_generic_close(parser, "期望 '>'\n提示: 泛型类型实参列表应以 '>' 结束，例如: Box<int>")?;
+    Ok(args)
+}
+
+/// 解析泛型类型参数声明（可选）: <T, A: Allocator>
+///
+/// 上界可以使用 ':' 或 'extends' 声明，上界为类名或接口名。
+pub fn parse_type_params(parser: &mut Parser) -> cayResult<Vec<TypeParamInfo>> {
+    let mut params: Vec<TypeParamInfo> = Vec::new();
+    if !parser.match_token(&crate::lexer::Token::Lt) {
+        return Ok(params);
+    }
+    loop {
+        let name = parser.consume_identifier("期望类型参数名\n提示: 泛型参数列表应为标识符，例如: class Box<T> { ... }")?;
+        if params.iter().any(|p| p.name == name) {
+            return Err(parser.error(ErrorCodes::PARSER_INVALID_DECLARATION, &format!(
+                "重复的类型参数 '{}'\n提示: 同一个泛型参数列表中的类型参数名必须唯一", name
+            )));
+        }
+        let bound = if parser.match_token(&crate::lexer::Token::Colon)
+            || parser.match_token(&crate::lexer::Token::Extends)
+        {
+            Some(parser.consume_identifier("期望类型参数上界\n提示: 上界应为类名或接口名，例如: <A: Allocator>")?)
+        } else {
+            None
+        };
+        params.push(TypeParamInfo { name, bound });
+        if !parser.match_token(&crate::lexer::Token::Comma) {
+            break;
+        }
+    }
+    consume_generic_close(parser, "期望 '>'\n提示: 泛型参数列表应以 '>' 结束，例如: class Box<T> { ... }")?;
+    Ok(params)
+}
+
+/// 消费泛型列表的结束 '>'
+///
+/// 嵌套泛型 Box<Box<int>> 的结尾会被词法分析为 '>>'，此时把令牌拆分为多个 '>'
+/// 再消费第一个，拆分结果保留在令牌流中，回溯后重新解析仍然成立。
+fn consume_generic_close(parser: &mut Parser, message: &str) -> cayResult<()> {
+    let pos = parser.pos;
+    let split = match parser.current_token() {
+        crate::lexer::Token::Gt => 0,
+        crate::lexer::Token::Shr => 1,
+        crate::lexer::Token::UnsignedShr => 2,
+        _ => return Err(parser.error(ErrorCodes::PARSER_UNEXPECTED_TOKEN, message)),
+    };
+    if split > 0 {
+        parser.tokens[pos].token = crate::lexer::Token::Gt;
+        let rest = parser.tokens[pos].clone();
+        for _ in 0..split {
+            parser.tokens.insert(pos + 1, rest.clone());
+        }
+    }
+    parser.advance();
+    Ok(())
+}
+
 /// 检查当前token是否是类型token
 pub fn is_type_token(parser: &Parser) -> bool {
     matches!(parser.current_token(),
         crate::lexer::Token::Int | crate::lexer::Token::Long | crate::lexer::Token::Float |
+        crate::lexer::Token::Byte | crate::lexer::Token::UByte | crate::lexer::Token::Short |
+        crate::lexer::Token::UShort | crate::lexer::Token::UInt | crate::lexer::Token::ULong |
         crate::lexer::Token::Double | crate::lexer::Token::Bool | crate::lexer::Token::String |
         crate::lexer::Token::Char | crate::lexer::Token::Void | crate::lexer::Token::Identifier(_) |
         // FFI 类型
@@ -181,6 +289,8 @@ pub fn is_type_token(parser: &Parser) -> bool {
 pub fn is_primitive_type_token(parser: &Parser) -> bool {
     matches!(parser.current_token(),
         crate::lexer::Token::Int | crate::lexer::Token::Long | crate::lexer::Token::Float |
+        crate::lexer::Token::Byte | crate::lexer::Token::UByte | crate::lexer::Token::Short |
+        crate::lexer::Token::UShort | crate::lexer::Token::UInt | crate::lexer::Token::ULong |
         crate::lexer::Token::Double | crate::lexer::Token::Bool | crate::lexer::Token::String |
         crate::lexer::Token::Char
     )
@@ -141,7 +141,7 @@ pub fn consume<'a>(parser: &'a mut Parser, token: &Token, message: &str) -> cayR
         
         parser.diagnostics.add(diagnostic);
         
-        Err(parser_error_with_file(loc.file, loc.line, loc.column, &detailed_message))
+        Err(parser_error_with_file(error_code, loc.file, loc.line, loc.column, &detailed_message))
     }
 }
 
@@ -166,14 +166,14 @@ pub fn consume_identifier(parser: &mut Parser, message: &str) -> cayResult<Strin
         
         parser.diagnostics.add(diagnostic);
         
-        Err(parser_error_with_file(loc.file, loc.line, loc.column, message))
+        Err(parser_error_with_file(ErrorCodes::PARSER_EXPECTED_IDENTIFIER, loc.file, loc.line, loc.column, message))
     }
 }
 
 /// 创建错误
-pub fn error(parser: &Parser, message: &str) -> cayError {
+pub fn error(parser: &Parser, code: &'static str, message: &str) -> cayError {
     let loc = current_full_loc(parser);
-    parser_error_with_file(loc.file, loc.line, loc.column, message)
+    parser_error_with_file(code, loc.file, loc.line, loc.column, message)
 }
 
 /// 创建详细的语法错误
@@ -189,7 +189,7 @@ pub fn create_parser_error(parser: &mut Parser, error_code: &'static str, messag
     );
     
     parser.diagnostics.add(diagnostic);
-    parser_error_with_file(loc.file, loc.line, loc.column, &message)
+    parser_error_with_file(error_code, loc.file, loc.line, loc.column, &message)
 }
 
 /// 检查下一个令牌是否匹配给定令牌
@@ -215,11 +215,23 @@ pub fn get_token_name(token: &Token) -> String {
         Token::Static => "static".to_string(),
         Token::Final => "final".to_string(),
         Token::Abstract => "abstract".to_string(),
+        Token::Sealed => "sealed".to_string(),
+        Token::Permits => "permits".to_string(),
         Token::Class => "class".to_string(),
         Token::Interface => "interface".to_string(),
+        Token::Enum => "enum".to_string(),
+        Token::Struct => "struct".to_string(),
+        Token::Record => "record".to_string(),
+        Token::Property => "property".to_string(),
         Token::Void => "void".to_string(),
         Token::Int => "int".to_string(),
         Token::Long => "long".to_string(),
+        Token::Byte => "byte".to_string(),
+        Token::UByte => "ubyte".to_string(),
+        Token::Short => "short".to_string(),
+        Token::UShort => "ushort".to_string(),
+        Token::UInt => "uint".to_string(),
+        Token::ULong => "ulong".to_string(),
         Token::Float => "float".to_string(),
         Token::Double => "double".to_string(),
         Token::Bool => "bool".to_string(),
@@ -237,6 +249,8 @@ pub fn get_token_name(token: &Token) -> String {
         Token::Break => "break".to_string(),
         Token::Continue => "continue".to_string(),
         Token::Scope => "scope".to_string(),
+        Token::Defer => "defer".to_string(),
+        Token::Try => "try".to_string(),
         Token::New => "new".to_string(),
         Token::This => "this".to_string(),
         Token::Super => "super".to_string(),
@@ -252,6 +266,7 @@ pub fn get_token_name(token: &Token) -> String {
         Token::Null => "null".to_string(),
         Token::AtMain => "@main".to_string(),
         Token::AtOverride => "@Override".to_string(),
+        Token::At => "@".to_string(),
         Token::LParen => "(".to_string(),
         Token::RParen => ")".to_string(),
         Token::LBrace => "{".to_string(),
@@ -266,6 +281,8 @@ pub fn get_token_name(token: &Token) -> String {
         Token::DoubleColon => "::".to_string(),
         Token::Arrow => "->".to_string(),
         Token::Question => "?".to_string(),
+        Token::QuestionDot => "?.".to_string(),
+        Token::Elvis => "?:".to_string(),
         Token::Plus => "+".to_string(),
         Token::Minus => "-".to_string(),
         Token::Star => "*".to_string(),
@@ -321,7 +338,10 @@ pub fn get_token_name(token: &Token) -> String {
         Token::Sysv64 => "sysv64".to_string(),
         Token::Win64 => "win64".to_string(),
         Token::Native => "native".to_string(),
+        Token::Const => "const".to_string(),
         Token::InlineIr => "__ir".to_string(),
+        Token::Package => "package".to_string(),
+        Token::Import => "import".to_string(),
         Token::Alias => "alias".to_string(),
         Token::Fn => "fn".to_string(),
     }
@@ -331,6 +351,7 @@ pub fn get_token_name(token: &Token) -> String {
 pub fn is_type_token(parser: &Parser) -> bool {
     matches!(current_token(parser),
         Token::Int | Token::Long | Token::Float | Token::Double |
+        Token::Byte | Token::UByte | Token::Short | Token::UShort | Token::UInt | Token::ULong |
         Token::Bool | Token::String | Token::Char | Token::Void |
         Token::Auto | Token::Var | Token::Let |
         Token::CInt | Token::CLong | Token::CShort | Token::CChar |
@@ -351,7 +372,7 @@ pub fn synchronize(parser: &mut Parser) {
         }
         
         match current_token(parser) {
-            Token::Class | Token::Interface | Token::Public | 
+            Token::Class | Token::Interface | Token::Enum | Token::Struct | Token::Record | Token::Public | 
             Token::Private | Token::Protected | Token::If | 
             Token::While | Token::For | Token::Return => {
                 return;
@@ -16,6 +16,7 @@
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use crate::error::{cayResult, cayError};
+use crate::diagnostic::ErrorCodes;
 
 /// 源位置信息
 #[derive(Debug, Clone)]
@@ -53,6 +54,14 @@ impl SourceMap {
         self.mappings.len()
     }
 
+    /// 转换为词法分析器使用的映射表: 输出行号（1-based）-> (原始文件, 原始行号)
+    pub fn to_line_map(&self) -> std::collections::HashMap<usize, (String, usize)> {
+        self.mappings.iter()
+            .enumerate()
+            .map(|(idx, pos)| (idx + 1, (pos.file.clone(), pos.line)))
+            .collect()
+    }
+
     /// 序列化为字符串（用于嵌入到预处理后的代码中）
     pub fn serialize(&self) -> String {
         self.mappings.iter()
@@ -200,6 +209,10 @@ impl Preprocessor {
 
         for (line_number, line) in lines.iter().enumerate() {
             let line_number = line_number + 1; // 转换为1-based
+
+            // struct 本来就按 C 布局，#[repr(C)] 属性不产生代码，替换为空白后按普通代码行处理
+            let without_attribute = Self::strip_repr_c_attribute(line);
+            let line = without_attribute.as_deref().unwrap_or(line);
             
             // 检查是否是预处理指令行（以 # 开头，可以有前导空白）
             let trimmed = line.trim_start();
@@ -248,6 +261,7 @@ impl Preprocessor {
         // 检查条件编译栈是否为空
         if !self.conditional_stack.is_empty() {
             return Err(cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_UNCLOSED_DIRECTIVE,
                 file: Some(file_path.to_string()),
                 line: lines.len(),
                 column: 1,
@@ -340,6 +354,7 @@ impl Preprocessor {
             }
             _ => {
                 Err(cayError::Preprocessor {
+                    code: ErrorCodes::PREPROCESSOR_UNKNOWN_DIRECTIVE,
                     file: Some(file_path.to_string()),
                     line: line_num,
                     column: 1,
@@ -350,6 +365,19 @@ impl Preprocessor {
         }
     }
 
+    /// 以 `#[repr(C)]` 开头的行去掉该属性（保留列号），其他行返回 None
+    fn strip_repr_c_attribute(line: &str) -> Option<String> {
+        let trimmed = line.trim_start();
+        let rest = trimmed.strip_prefix("#[")?.trim_start()
+            .strip_prefix("repr")?.trim_start()
+            .strip_prefix('(')?.trim_start()
+            .strip_prefix('C')?.trim_start()
+            .strip_prefix(')')?.trim_start()
+            .strip_prefix(']')?;
+        let indent = line.len() - trimmed.len();
+        Some(format!("{}{}{}", &line[..indent], " ".repeat(trimmed.len() - rest.len()), rest))
+    }
+
     /// 移除 C 风格块注释 /* ... */
     fn remove_block_comments(s: &str) -> String {
         let mut result = String::with_capacity(s.len());
@@ -461,6 +489,7 @@ impl Preprocessor {
             Directive::Error(message) => {
                 if !self.skipping {
                     return Err(cayError::Preprocessor {
+                        code: ErrorCodes::PREPROCESSOR_USER_ERROR,
                         file: Some(file_path.to_string()),
                         line: line_num,
                         column: 1,
@@ -507,6 +536,7 @@ impl Preprocessor {
         
         if trimmed.is_empty() {
             return Err(cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_INCLUDE_ERROR,
                 file: Some(file_path.to_string()),
                 line: line_num,
                 column: 1,
@@ -526,6 +556,7 @@ impl Preprocessor {
             Ok((path.to_string(), false))
         } else {
             Err(cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_INCLUDE_ERROR,
                 file: Some(file_path.to_string()),
                 line: line_num,
                 column: 1,
@@ -543,6 +574,7 @@ impl Preprocessor {
         // 首先检测循环包含（基于当前处理链）- 使用完整路径
         if self.include_stack.contains(&full_path) {
             return Err(cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_CIRCULAR_INCLUDE,
                 file: Some(current_file.to_string()),
                 line: 1,
                 column: 1,
@@ -559,6 +591,7 @@ impl Preprocessor {
         // 读取文件内容
         let content = std::fs::read_to_string(&full_path)
             .map_err(|e| cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_INCLUDE_ERROR,
                 file: Some(current_file.to_string()),
                 line: 1,
                 column: 1,
@@ -604,6 +637,7 @@ impl Preprocessor {
             }
             
             Err(cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_INCLUDE_ERROR,
                 file: Some(current_file.to_string()),
                 line: 1,
                 column: 1,
@@ -634,6 +668,7 @@ impl Preprocessor {
             }
 
                         Err(cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_INCLUDE_ERROR,
                 file: Some(current_file.to_string()),
                 line: 1,
                 column: 1,
@@ -649,6 +684,7 @@ impl Preprocessor {
         
         if trimmed.is_empty() {
             return Err(cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_DEFINE_ERROR,
                 file: Some(file_path.to_string()),
                 line: line_num,
                 column: 1,
@@ -667,6 +703,7 @@ impl Preprocessor {
         
         if name.is_empty() {
             return Err(cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_INVALID_MACRO,
                 file: Some(file_path.to_string()),
                 line: line_num,
                 column: 1,
@@ -703,6 +740,7 @@ impl Preprocessor {
         
         if trimmed.is_empty() {
             return Err(cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_IFDEF_ERROR,
                 file: Some(file_path.to_string()),
                 line: line_num,
                 column: 1,
@@ -716,6 +754,7 @@ impl Preprocessor {
         
         if name.is_empty() {
             return Err(cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_IFDEF_ERROR,
                 file: Some(file_path.to_string()),
                 line: line_num,
                 column: 1,
@@ -830,6 +869,7 @@ impl Preprocessor {
             }
             None => {
                 return Err(cayError::Preprocessor {
+                    code: ErrorCodes::PREPROCESSOR_IFDEF_ERROR,
                     file: Some(file_path.to_string()),
                     line: 1,
                     column: 1,
@@ -866,6 +906,7 @@ impl Preprocessor {
     fn pop_conditional(&mut self, file_path: &str) -> cayResult<()> {
         if self.conditional_stack.pop().is_none() {
             return Err(cayError::Preprocessor {
+                code: ErrorCodes::PREPROCESSOR_IFDEF_ERROR,
                 file: Some(file_path.to_string()),
                 line: 1,
                 column: 0,
@@ -955,4 +996,16 @@ mod tests {
         ).unwrap();
         assert!(result.contains("int x = 1"));
     }
+
+    #[test]
+    fn test_repr_c_attribute() {
+        let mut pp = Preprocessor::new(".");
+        let result = pp.process(
+            "#[repr(C)]\nstruct P { int x; }\n#[repr(C)] struct Q { int y; }",
+            "test.cay"
+        ).unwrap();
+        assert!(result.contains("struct P { int x; }"));
+        assert!(result.contains("           struct Q { int y; }"));
+        assert!(!result.contains("repr"));
+    }
 }
\ No newline at end of file
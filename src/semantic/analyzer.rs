@@ -1,13 +1,15 @@
 //! 语义分析器核心实现
 
 use crate::ast::*;
-use crate::types::{Type, ParameterInfo, ClassInfo, MethodInfo, FieldInfo, TypeRegistry};
+use crate::types::{Type, ParameterInfo, ClassInfo, MethodInfo, FieldInfo, TypeRegistry, TypeParamInfo};
 use crate::error::{cayResult, semantic_error_with_file};
+use crate::diagnostic::{CompilationPhase, Diagnostic, DiagnosticCollector, ErrorCodes};
 use super::symbol_table::{SemanticSymbolTable, SemanticSymbolInfo};
 
 /// 语义分析错误信息（包含位置）
 #[derive(Debug, Clone)]
 pub struct SemanticErrorInfo {
+    pub code: &'static str,
     pub line: usize,
     pub column: usize,
     pub message: String,
@@ -24,12 +26,30 @@ pub struct SemanticAnalyzer {
     pub(super) current_method_is_static: bool,  // 当前方法是否是静态方法
     pub(super) current_method_is_constructor: bool,  // 当前是否是构造函数
     pub(super) errors: Vec<SemanticErrorInfo>,
+    /// 不影响编译的警告（如使用 @Deprecated 的声明）
+    pub(super) warnings: Vec<SemanticErrorInfo>,
     pub(super) current_file: Option<String>,  // 当前正在分析的文件路径
     /// 源映射表：输出行号 -> (原始文件, 原始行号)
     /// 用于根据AST中的原始行号反查对应的源文件
     pub(super) source_map: Option<std::collections::HashMap<usize, (String, usize)>>,
     /// 启用的语言特性
     pub(super) features: Vec<String>,
+    /// 当前可见的泛型类型参数（类和泛型方法）
+    pub(super) current_type_params: Vec<TypeParamInfo>,
+    /// 当前泛型实例化的嵌套深度
+    pub(super) instantiation_depth: usize,
+    /// 当前方法解析后的返回类型（用于检查 `?` 运算符）
+    pub(super) current_return_type: Option<Type>,
+    /// 尚未从上下文确定类型实参的内置变体构造: 表达式位置 -> 变体名
+    pub(super) pending_variants: std::collections::HashMap<crate::error::SourceLocation, String>,
+    /// 正在检查的嵌套 Lambda（由外到内），用于捕获分析
+    pub(super) lambda_frames: Vec<super::closures::LambdaFrame>,
+    /// 当前位置已确定不为 null 的可空变量（空安全的流敏感收窄）
+    pub(super) non_null_locals: std::collections::HashSet<String>,
+    /// 没有 default 但覆盖了所有枚举变体或子类型的 switch 语句（控制流分析视为覆盖所有值）
+    pub(super) exhaustive_switches: std::collections::HashSet<crate::error::SourceLocation>,
+    /// 中断检查的第一个错误（它也是 `errors` 的第一项时保存，见 `record_failure`）
+    pub(super) first_failure: Option<crate::error::cayError>,
 }
 
 impl SemanticAnalyzer {
@@ -47,9 +67,18 @@ impl SemanticAnalyzer {
             current_method_is_static: false,
             current_method_is_constructor: false,
             errors: Vec::new(),
+            warnings: Vec::new(),
             current_file: None,
             source_map: None,
             features,
+            current_type_params: Vec::new(),
+            instantiation_depth: 0,
+            current_return_type: None,
+            pending_variants: std::collections::HashMap::new(),
+            lambda_frames: Vec::new(),
+            non_null_locals: std::collections::HashSet::new(),
+            exhaustive_switches: std::collections::HashSet::new(),
+            first_failure: None,
         };
         
         // 注册内置函数
@@ -64,31 +93,14 @@ impl SemanticAnalyzer {
     }
 
     pub fn analyze(&mut self, program: &Program) -> cayResult<()> {
-        // 保存 program 引用以供类型推断使用
-        self.program = Some(std::rc::Rc::new(program.clone()));
-
-        // 第一遍：收集所有类定义
-        self.collect_classes(program)?;
-
-        // 注册运行时函数到 NetworkUtils 类
-        self.register_runtime_functions();
-
-        // 注册顶层函数到符号表
-        self.register_top_level_functions(program)?;
-
-        // 检查主类冲突（在收集类之后，类型检查之前）
-        self.check_main_class_conflicts(program)?;
-
-        // 第二遍：分析方法定义
-        self.analyze_methods(program)?;
-
-        // 第三遍：检查继承关系（包括 @Override 验证）
-        self.check_inheritance(program)?;
-
-        // 第四遍：类型检查
-        self.type_check_program(program)?;
+        self.run_passes(program);
 
         if !self.errors.is_empty() {
+            // 只有一个中断检查的错误时原样返回
+            let first_failure = self.first_failure.take();
+            if let (1, Some(error)) = (self.errors.len(), first_failure) {
+                return Err(error);
+            }
             // 使用第一个错误的行号/列号，后续错误拼接在消息中
             let first = &self.errors[0];
             let mut message = first.message.clone();
@@ -99,6 +111,7 @@ impl SemanticAnalyzer {
             // 使用第一个错误中存储的文件路径（现在每个错误都包含自己的文件路径）
             let error_file = first.file.clone();
             return Err(semantic_error_with_file(
+                first.code,
                 error_file,
                 first.line,
                 first.column,
@@ -109,6 +122,95 @@ impl SemanticAnalyzer {
         Ok(())
     }
 
+    /// 分析程序，每个语义错误和警告分别加入 `diagnostics`（`analyze` 把所有错误合并成一个）
+    pub fn analyze_collecting(&mut self, program: &Program, diagnostics: &mut DiagnosticCollector) {
+        self.run_passes(program);
+
+        for error in &self.errors {
+            let error = semantic_error_with_file(error.code, error.file.clone(), error.line, error.column, error.message.clone());
+            diagnostics.add(Diagnostic::from_error(&error));
+        }
+        for warning in self.warning_diagnostics() {
+            diagnostics.add(warning);
+        }
+    }
+
+    /// 分析产生的警告，转换为诊断信息
+    pub fn warning_diagnostics(&self) -> Vec<Diagnostic> {
+        self.warnings.iter().map(|warning| {
+            let location = crate::diagnostic::SourceLocation::new(warning.line, warning.column);
+            Diagnostic::warning(warning.code, CompilationPhase::Semantic, warning.message.clone(), location)
+                .with_file(warning.file.clone())
+        }).collect()
+    }
+
+    /// 依次执行各遍分析，错误记录在 `errors` 中
+    ///
+    /// 类型检查在出错的声明和语句之后继续；收集声明的各遍出错时后续的遍依赖不完整的信息，不再执行。
+    fn run_passes(&mut self, program: &Program) {
+        // 保存 program 引用以供类型推断使用
+        self.program = Some(std::rc::Rc::new(program.clone()));
+
+        // 登记各类型所属的包
+        self.register_packages(program);
+
+        // 第一遍：收集所有类定义
+        if let Err(error) = self.collect_classes(program) {
+            self.record_failure(error);
+            return;
+        }
+
+        // 注册运行时函数到 NetworkUtils 类
+        self.register_runtime_functions();
+
+        // 注册顶层函数到符号表
+        if let Err(error) = self.register_top_level_functions(program) {
+            self.record_failure(error);
+            return;
+        }
+
+        // 检查主类冲突（在收集类之后，类型检查之前）
+        if let Err(error) = self.check_main_class_conflicts(program) {
+            self.record_failure(error);
+        }
+
+        // 第二遍：分析方法定义
+        if let Err(error) = self.analyze_methods(program) {
+            self.record_failure(error);
+            return;
+        }
+
+        // 检查内置注解的用法
+        self.check_annotations(program);
+
+        // 检查 const 函数，折叠静态字段的常量初始值
+        self.check_const_functions(program);
+        self.fold_static_constants(program);
+
+        // 检查属性访问方法与显式声明的方法是否冲突
+        self.check_properties(program);
+
+        // 解析签名中的泛型实例化（注册单态化后的类和接口）
+        if let Err(error) = self.resolve_generic_signatures(program) {
+            self.record_failure(error);
+            return;
+        }
+
+        // 第三遍：检查继承关系（包括 @Override 验证）
+        if let Err(error) = self.check_inheritance(program) {
+            self.record_failure(error);
+        }
+
+        // 第四遍：类型检查
+        self.type_check_program(program);
+
+        // 确定赋值、缺少 return 和不可达语句
+        self.check_control_flow(program);
+
+        // 无法推断类型实参的 Ok/Err/None
+        self.report_pending_variants();
+    }
+
     /// 注册运行时函数到相应的类
     fn register_runtime_functions(&mut self) {
         // 向 NetworkUtils 类添加 __cay_buffer_to_string 方法
@@ -129,6 +231,8 @@ impl SemanticAnalyzer {
                 is_native: true,
                 is_override: false,
                 is_final: false,
+                type_params: Vec::new(),
+                annotations: Vec::new(),
             };
 
             class_info.add_method(method);
@@ -148,6 +252,7 @@ impl SemanticAnalyzer {
             for func in &program.top_level_functions {
                 if func.name != "main" {
                     return Err(crate::error::semantic_error(
+                        ErrorCodes::SEMANTIC_FEATURE_DISABLED,
                         func.loc.line,
                         func.loc.column,
                         format!("Cavvy是面向对象语言，不允许顶层函数 '{}'。请将函数定义在类中，或使用 -F=top_level_function 启用该特性。", func.name)
@@ -160,6 +265,7 @@ impl SemanticAnalyzer {
             // 检查函数名是否已存在（在当前作用域）
             if self.symbol_table.lookup_current(&func.name).is_some() {
                 return Err(crate::error::semantic_error(
+                    ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
                     func.loc.line,
                     func.loc.column,
                     format!("顶层函数 '{}' 已定义", func.name)
@@ -178,6 +284,8 @@ impl SemanticAnalyzer {
                 symbol_type: func_type,
                 is_final: true,
                 is_initialized: true,
+                nullable: false,
+                decl_loc: None,
             };
             self.symbol_table.declare(func.name.clone(), symbol_info);
         }
@@ -190,6 +298,11 @@ impl SemanticAnalyzer {
         &self.type_registry
     }
 
+    /// 分析过程中产生的警告
+    pub fn warnings(&self) -> &[SemanticErrorInfo] {
+        &self.warnings
+    }
+
     /// 设置当前文件路径（用于错误报告）
     pub fn set_current_file(&mut self, file: Option<String>) {
         self.current_file = file;
@@ -235,15 +348,16 @@ impl SemanticAnalyzer {
     }
 
     /// 报告语义错误（自动包含当前文件信息）
-    pub fn report_error(&self, line: usize, column: usize, message: impl Into<String>) -> crate::error::cayError {
+    pub fn report_error(&self, code: &'static str, line: usize, column: usize, message: impl Into<String>) -> crate::error::cayError {
         let msg = message.into();
-        semantic_error_with_file(self.current_file.clone(), line, column, msg)
+        semantic_error_with_file(code, self.current_file.clone(), line, column, msg)
     }
 
     /// 创建语义分析错误信息（自动解析文件路径）
-    pub fn create_error_info(&self, line: usize, column: usize, message: impl Into<String>) -> SemanticErrorInfo {
+    pub fn create_error_info(&self, code: &'static str, line: usize, column: usize, message: impl Into<String>) -> SemanticErrorInfo {
         let file = self.resolve_file_for_line(line);
         SemanticErrorInfo {
+            code,
             line,
             column,
             message: message.into(),
@@ -272,6 +386,10 @@ impl SemanticAnalyzer {
             Expr::MethodRef(e) => (e.loc.line, e.loc.column),
             Expr::Alloc(e) => (e.loc.line, e.loc.column),
             Expr::Dealloc(e) => (e.loc.line, e.loc.column),
+            Expr::Try(e) => (e.loc.line, e.loc.column),
+            Expr::Switch(e) => (e.loc.line, e.loc.column),
+            Expr::SafeCall(e) => (e.loc.line, e.loc.column),
+            Expr::Elvis(e) => (e.loc.line, e.loc.column),
         }
     }
 }
@@ -0,0 +1,168 @@
+//! 注解检查
+//!
+//! 用户定义的注解只做记录，供后续阶段查询；内置注解有固定的用法：
+//!
+//! - `@Deprecated` / `@Deprecated("说明")`：使用该类、方法或字段时给出警告；
+//! - `@Inline` / `@NoInline`：方法的 LLVM 函数属性 alwaysinline / noinline，二者不能同时使用；
+//! - `@Export("c_name")`：静态方法以 C 名称导出，名称在程序中唯一；
+//! - `@Test`：测试方法，必须是无参数、返回 void 的静态方法。
+
+use std::collections::HashMap;
+use crate::ast::*;
+use crate::error::SourceLocation;
+use crate::types::{MethodInfo, Type};
+use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
+
+const DEPRECATED: &str = Annotation::DEPRECATED;
+const INLINE: &str = Annotation::INLINE;
+const NO_INLINE: &str = Annotation::NO_INLINE;
+const EXPORT: &str = Annotation::EXPORT;
+const TEST: &str = Annotation::TEST;
+
+impl SemanticAnalyzer {
+    /// 检查类、接口、方法、字段和参数上内置注解的用法
+    pub(super) fn check_annotations(&mut self, program: &Program) {
+        let mut exports: HashMap<String, String> = HashMap::new();
+        for class in &program.classes {
+            self.check_declaration_annotations(&class.annotations, "a class");
+            for member in &class.members {
+                match member {
+                    ClassMember::Method(method) => {
+                        self.check_method_annotations(&class.name, method, &mut exports);
+                    }
+                    ClassMember::Field(field) => {
+                        self.check_declaration_annotations(&field.annotations, "a field");
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for interface in &program.interfaces {
+            self.check_declaration_annotations(&interface.annotations, "an interface");
+            for method in &interface.methods {
+                self.check_method_annotations(&interface.name, method, &mut exports);
+            }
+        }
+    }
+
+    /// 类、接口、字段和参数上只能使用 @Deprecated 和用户定义的注解
+    fn check_declaration_annotations(&mut self, annotations: &[Annotation], target: &str) {
+        for annotation in annotations {
+            match annotation.name.as_str() {
+                DEPRECATED => self.check_deprecated_args(annotation),
+                INLINE | NO_INLINE | EXPORT | TEST => {
+                    self.annotation_error(annotation, format!("@{} cannot be applied to {}; it is only allowed on methods", annotation.name, target));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn check_method_annotations(&mut self, owner: &str, method: &MethodDecl, exports: &mut HashMap<String, String>) {
+        let method_name = format!("{}.{}", owner, method.name);
+        let is_static = method.modifiers.contains(&Modifier::Static);
+        for annotation in &method.annotations {
+            match annotation.name.as_str() {
+                DEPRECATED => self.check_deprecated_args(annotation),
+                INLINE | NO_INLINE | TEST if !annotation.args.is_empty() => {
+                    self.annotation_error(annotation, format!("@{} does not take arguments", annotation.name));
+                }
+                EXPORT => {
+                    let c_name = match annotation.string_value() {
+                        Some(c_name) if annotation.args.len() == 1 && is_c_identifier(c_name) => c_name,
+                        _ => {
+                            self.annotation_error(annotation, "@Export requires a C function name, e.g. @Export(\"add\")".to_string());
+                            continue;
+                        }
+                    };
+                    if !is_static || method.body.is_none() {
+                        self.annotation_error(annotation, format!("@Export can only be applied to static methods with a body, but '{}' is not", method_name));
+                    } else if let Some(previous) = exports.get(c_name) {
+                        self.annotation_error(annotation, format!("Exported name '{}' is already used by '{}'", c_name, previous));
+                    } else {
+                        exports.insert(c_name.to_string(), method_name.clone());
+                    }
+                }
+                TEST if !is_static || !method.params.is_empty() || method.return_type != Type::Void => {
+                    self.annotation_error(annotation, format!("Test method '{}' must be static, return void and take no parameters", method_name));
+                }
+                _ => {}
+            }
+        }
+        if method.has_annotation(INLINE) && method.has_annotation(NO_INLINE) {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_ANNOTATION_ERROR,
+                method.loc.line,
+                method.loc.column,
+                format!("Method '{}' cannot be both @Inline and @NoInline", method_name),
+            ));
+        }
+        for param in &method.params {
+            self.check_declaration_annotations(&param.annotations, "a parameter");
+        }
+    }
+
+    fn check_deprecated_args(&mut self, annotation: &Annotation) {
+        let valid = match annotation.args.as_slice() {
+            [] => true,
+            [_] => annotation.string_value().is_some(),
+            _ => false,
+        };
+        if !valid {
+            self.annotation_error(annotation, "@Deprecated takes an optional message string, e.g. @Deprecated(\"use add instead\")".to_string());
+        }
+    }
+
+    fn annotation_error(&mut self, annotation: &Annotation, message: String) {
+        self.errors.push(self.create_error_info(ErrorCodes::SEMANTIC_ANNOTATION_ERROR, annotation.loc.line, annotation.loc.column, message));
+    }
+
+    /// 使用带 @Deprecated 的声明时警告，同一类中的使用不警告
+    pub(super) fn warn_if_deprecated(&mut self, owner: &str, name: &str, annotations: &[Annotation], loc: &SourceLocation) {
+        let deprecated = match annotations.iter().find(|annotation| annotation.name == DEPRECATED) {
+            Some(deprecated) => deprecated,
+            None => return,
+        };
+        if self.current_class.as_deref() == Some(owner) {
+            return;
+        }
+        let message = match deprecated.string_value() {
+            Some(note) => format!("'{}' is deprecated: {}", name, note),
+            None => format!("'{}' is deprecated", name),
+        };
+        self.warn(ErrorCodes::SEMANTIC_DEPRECATED_USAGE, loc.line, loc.column, message);
+    }
+
+    /// 调用带 @Deprecated 的方法时警告
+    pub(super) fn warn_if_deprecated_method(&mut self, method: &MethodInfo, loc: &SourceLocation) {
+        let name = format!("{}.{}", method.class_name, method.name);
+        self.warn_if_deprecated(&method.class_name, &name, &method.annotations, loc);
+    }
+
+    /// 创建带 @Deprecated 的类的对象时警告
+    pub(super) fn warn_if_deprecated_class(&mut self, class_name: &str, loc: &SourceLocation) {
+        let annotations = match self.type_registry.get_class(class_name) {
+            Some(class_info) if class_info.has_annotation(DEPRECATED) => class_info.annotations.clone(),
+            _ => return,
+        };
+        self.warn_if_deprecated(class_name, class_name, &annotations, loc);
+    }
+
+    /// 记录警告；表达式可能被多次推断类型，同一位置的相同警告只记录一次
+    pub(super) fn warn(&mut self, code: &'static str, line: usize, column: usize, message: String) {
+        let duplicate = self.warnings.iter()
+            .any(|warning| warning.line == line && warning.column == column && warning.message == message);
+        if !duplicate {
+            let warning = self.create_error_info(code, line, column, message);
+            self.warnings.push(warning);
+        }
+    }
+}
+
+/// 导出名必须是合法的 C 标识符
+fn is_c_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
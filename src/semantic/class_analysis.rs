@@ -2,8 +2,9 @@
 
 use crate::ast::{Program, ClassMember, Modifier, MethodDecl};
 use crate::types::{ClassInfo, FieldInfo, MethodInfo, ParameterInfo, Type};
-use crate::error::{cayResult, semantic_error};
+use crate::error::{cayResult, semantic_error, SourceLocation};
 use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
 
 impl SemanticAnalyzer {
     /// 检查主类冲突
@@ -57,6 +58,7 @@ impl SemanticAnalyzer {
                             .map(|(name, _)| name.clone())
                             .collect();
                         Err(crate::error::semantic_error(
+                            ErrorCodes::SEMANTIC_MAIN_CLASS,
                             0, 0,
                             format!(
                                 "多个类包含 main 方法: {}。请使用 @main 标记指定主类，例如：\n@main public class {} {{ ... }}",
@@ -75,6 +77,7 @@ impl SemanticAnalyzer {
                             .map(|(name, _)| name.clone())
                             .collect();
                         Err(crate::error::semantic_error(
+                            ErrorCodes::SEMANTIC_MAIN_CLASS,
                             0, 0,
                             format!(
                                 "多个类标记了 @main: {}。只能有一个主类。",
@@ -89,9 +92,16 @@ impl SemanticAnalyzer {
 
     /// 收集类定义
     pub fn collect_classes(&mut self, program: &Program) -> cayResult<()> {
+        // 枚举和结构体先于类收集，类的字段和方法签名可以引用它们
+        self.collect_enums(program)?;
+        self.collect_structs(program)?;
+
         // 首先收集接口定义
         for interface in &program.interfaces {
             let mut interface_info = crate::types::InterfaceInfo::new(interface.name.clone());
+            interface_info.type_params = interface.type_params.clone();
+            interface_info.is_sealed = interface.modifiers.contains(&Modifier::Sealed);
+            interface_info.permits = interface.permits.clone();
 
             // 收集接口方法
             for method in &interface.methods {
@@ -103,11 +113,16 @@ impl SemanticAnalyzer {
                     is_public: true,  // 接口方法默认是public
                     is_private: false,
                     is_protected: false,
-                    is_static: false,
+                    is_static: method.modifiers.contains(&Modifier::Static),
                     is_native: false,
                     is_override: false,
                     is_final: false,  // 接口方法不是final
+                    type_params: method.type_params.clone(),
+                    annotations: method.annotations.clone(),
                 };
+                if method.modifiers.contains(&Modifier::Default) {
+                    interface_info.default_methods.insert(method.name.clone());
+                }
                 interface_info.add_method(method_info);
             }
 
@@ -116,6 +131,12 @@ impl SemanticAnalyzer {
 
         // 然后收集类定义
         for class in &program.classes {
+            if let Some(outer) = &class.enclosing_class {
+                self.type_registry.enclosing_classes.insert(class.name.clone(), outer.clone());
+                if class.modifiers.contains(&Modifier::Private) {
+                    self.type_registry.private_nested_classes.insert(class.name.clone());
+                }
+            }
             let is_abstract = class.modifiers.contains(&Modifier::Abstract);
             let is_final = class.modifiers.contains(&Modifier::Final);
             let mut class_info = ClassInfo {
@@ -128,6 +149,12 @@ impl SemanticAnalyzer {
                 interfaces: class.interfaces.clone(),
                 is_abstract,
                 is_final,
+                is_sealed: class.modifiers.contains(&Modifier::Sealed),
+                permits: class.permits.clone(),
+                type_params: class.type_params.clone(),
+                generic_supertypes: class.generic_supertypes.clone(),
+                annotations: class.annotations.clone(),
+                properties: std::collections::HashMap::new(),
             };
 
             // 收集字段信息
@@ -136,10 +163,8 @@ impl SemanticAnalyzer {
                     ClassMember::Field(field) => {
                         let is_final = field.modifiers.contains(&Modifier::Final);
                         let is_static = field.modifiers.contains(&Modifier::Static);
-                        // static final 字段且初始化值为字面量时，标记为编译期常量
-                        let is_const_expr = is_static && is_final && field.initializer.as_ref().map_or(false, |e| {
-                            matches!(e, crate::ast::Expr::Literal(_))
-                        });
+                        // static final 字段的初始值能在编译期求值时标记为常量（见 fold_static_constants）
+                        let is_const_expr = false;
                         let field_info = FieldInfo {
                             name: field.name.clone(),
                             field_type: field.field_type.clone(),
@@ -149,6 +174,7 @@ impl SemanticAnalyzer {
                             is_static,
                             is_final,
                             is_const_expr,
+                            annotations: field.annotations.clone(),
                         };
                         class_info.fields.insert(field.name.clone(), field_info);
                     }
@@ -164,6 +190,9 @@ impl SemanticAnalyzer {
                     ClassMember::Destructor(_) => {
                         class_info.has_destructor = true;
                     }
+                    ClassMember::Property(property) => {
+                        class_info.properties.insert(property.name.clone(), Self::property_info(property));
+                    }
                     _ => {}
                 }
             }
@@ -192,6 +221,8 @@ impl SemanticAnalyzer {
                         is_native: method.modifiers.contains(&Modifier::Native),
                         is_override: method.modifiers.contains(&Modifier::Override),
                         is_final: method.modifiers.contains(&Modifier::Final),
+                        type_params: method.type_params.clone(),
+                        annotations: method.annotations.clone(),
                     };
 
                     if let Some(class_info) = self.type_registry.classes.get_mut(&class.name) {
@@ -205,7 +236,7 @@ impl SemanticAnalyzer {
 
     /// 检查继承关系
     /// 1. 验证父类是否存在
-    /// 2. 检查 final 类不能被继承
+    /// 2. 检查 final 类不能被继承，sealed 类和接口只能被 permits 列出的类继承或实现
     /// 3. 检测循环继承
     /// 4. 验证 @Override 注解
     /// 5. 检查 final 方法不能被重写
@@ -215,6 +246,7 @@ impl SemanticAnalyzer {
             if let Some(ref parent_name) = class.parent {
                 if !self.type_registry.class_exists(parent_name) {
                     return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_INHERITANCE_ERROR,
                         class.loc.line,
                         class.loc.column,
                         format!("Class '{}' extends undefined class '{}'", class.name, parent_name)
@@ -229,6 +261,7 @@ impl SemanticAnalyzer {
                 if let Some(parent_class) = self.type_registry.get_class(parent_name) {
                     if parent_class.is_final {
                         return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_FINAL_CLASS_INHERITANCE,
                             class.loc.line,
                             class.loc.column,
                             format!("Class '{}' cannot inherit from final class '{}'", class.name, parent_name)
@@ -238,6 +271,9 @@ impl SemanticAnalyzer {
             }
         }
 
+        // sealed 类和接口的 permits 列表与实际的子类一致
+        self.check_sealed_hierarchies(program)?;
+
         // 第三遍：检测循环继承
         for class in &program.classes {
             self.check_circular_inheritance(&class.name, &class.name, &mut Vec::new())?;
@@ -249,6 +285,69 @@ impl SemanticAnalyzer {
             self.check_final_method_override(class)?;
         }
 
+        // 第五遍：检查实现的接口并继承默认方法
+        self.check_interface_implementations(program)?;
+
+        Ok(())
+    }
+
+    /// 检查 sealed 类型：permits 列出的类必须存在并直接继承或实现它，其他类不能继承或实现它
+    fn check_sealed_hierarchies(&self, program: &Program) -> cayResult<()> {
+        for class in &program.classes {
+            if class.modifiers.contains(&Modifier::Sealed) && class.modifiers.contains(&Modifier::Final) {
+                return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_SEALED_HIERARCHY,
+                    class.loc.line,
+                    class.loc.column,
+                    format!("Class '{}' cannot be both sealed and final", class.name)
+                ));
+            }
+            let sealed_types = class.parent.iter().map(|parent| (parent, "extend"))
+                .chain(class.interfaces.iter().map(|interface| (interface, "implement")));
+            for (supertype, relation) in sealed_types {
+                match self.type_registry.sealed_permits(supertype) {
+                    Some(permits) if !permits.contains(&class.name) => {
+                        return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_SEALED_HIERARCHY,
+                            class.loc.line,
+                            class.loc.column,
+                            format!("Class '{}' is not permitted to {} sealed type '{}'", class.name, relation, supertype)
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let sealed = program.classes.iter()
+            .filter(|c| c.modifiers.contains(&Modifier::Sealed))
+            .map(|c| (&c.name, &c.permits, &c.loc))
+            .chain(program.interfaces.iter()
+                .filter(|i| i.modifiers.contains(&Modifier::Sealed))
+                .map(|i| (&i.name, &i.permits, &i.loc)));
+        for (name, permits, loc) in sealed {
+            for permitted in permits {
+                let direct = match self.type_registry.get_class(permitted) {
+                    Some(info) => info.parent.as_ref() == Some(name) || info.interfaces.contains(name),
+                    None => {
+                        return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_SEALED_HIERARCHY,
+                            loc.line,
+                            loc.column,
+                            format!("Permitted subclass '{}' of sealed type '{}' is not a defined class", permitted, name)
+                        ));
+                    }
+                };
+                if !direct {
+                    return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_SEALED_HIERARCHY,
+                        loc.line,
+                        loc.column,
+                        format!("Class '{}' is listed in permits of sealed type '{}' but does not directly extend or implement it", permitted, name)
+                    ));
+                }
+            }
+        }
         Ok(())
     }
 
@@ -256,6 +355,7 @@ impl SemanticAnalyzer {
     fn check_circular_inheritance(&self, original: &str, current: &str, visited: &mut Vec<String>) -> cayResult<()> {
         if visited.contains(&current.to_string()) {
             return Err(semantic_error(
+                ErrorCodes::SEMANTIC_CIRCULAR_INHERITANCE,
                 0, 0,
                 format!("Circular inheritance detected involving class '{}'", original)
             ));
@@ -276,11 +376,17 @@ impl SemanticAnalyzer {
         for member in &class.members {
             if let ClassMember::Method(method) = member {
                 if method.modifiers.contains(&Modifier::Override) {
+                    // 实现接口方法
+                    if self.overrides_interface_method(&class.name, method) {
+                        continue;
+                    }
+
                     // 检查父类是否存在
                     let parent_name = match &class.parent {
                         Some(p) => p,
                         None => {
                             return Err(semantic_error(
+                                ErrorCodes::SEMANTIC_OVERRIDE_ERROR,
                                 method.loc.line,
                                 method.loc.column,
                                 format!("Method '{}' has @Override annotation but class '{}' does not extend any class", 
@@ -292,6 +398,7 @@ impl SemanticAnalyzer {
                     // 检查父类中是否存在同名方法
                     if !self.method_exists_in_parent(parent_name, &method.name, &method.params, &method.return_type) {
                         return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_OVERRIDE_ERROR,
                             method.loc.line,
                             method.loc.column,
                             format!("Method '{}' has @Override annotation but does not override any method from parent class '{}'",
@@ -385,6 +492,7 @@ impl SemanticAnalyzer {
                             // 找到匹配的方法，检查是否是 final
                             if method.is_final {
                                 return Err(semantic_error(
+                                    ErrorCodes::SEMANTIC_OVERRIDE_ERROR,
                                     line,
                                     column,
                                     format!(
@@ -417,4 +525,36 @@ impl SemanticAnalyzer {
 
         types1.iter().zip(types2.iter()).all(|(t1, t2)| t1 == t2)
     }
+
+    /// 类所在的最外层类（顶层类返回自身，泛型实例按其模板计算）
+    pub(super) fn outermost_class<'a>(&'a self, name: &'a str) -> &'a str {
+        let mut current = self.generic_origin(name);
+        while let Some(outer) = self.type_registry.enclosing_classes.get(current) {
+            current = outer;
+        }
+        current
+    }
+
+    /// 当前代码能否访问 `owner` 的私有成员
+    ///
+    /// 同一个最外层类中的外部类、嵌套类和匿名类可以互相访问私有成员。
+    pub(super) fn can_access_private(&self, owner: &str) -> bool {
+        self.current_class.as_deref()
+            .is_some_and(|current| self.outermost_class(current) == self.outermost_class(owner))
+    }
+
+    /// 检查当前代码能否使用嵌套类：private 嵌套类只在其最外层类中可见
+    pub(super) fn check_nested_class_access(&self, type_name: &str, loc: &SourceLocation) -> cayResult<()> {
+        let origin = self.generic_origin(type_name);
+        if !self.type_registry.private_nested_classes.contains(origin) || self.can_access_private(origin) {
+            return Ok(());
+        }
+        let outer = self.type_registry.enclosing_classes.get(origin).map(String::as_str).unwrap_or_default();
+        Err(semantic_error(
+            ErrorCodes::SEMANTIC_ACCESS_VIOLATION,
+            loc.line,
+            loc.column,
+            format!("{} has private access in {}", origin.replace('$', "."), outer.replace('$', "."))
+        ))
+    }
 }
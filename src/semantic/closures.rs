@@ -0,0 +1,174 @@
+//! Lambda 闭包捕获分析
+//!
+//! Lambda 体中引用的外层局部变量称为捕获变量。`final` 变量、参数和 `this`
+//! 按值捕获，创建闭包时复制进环境结构体；其余局部变量按引用捕获，声明处改为
+//! 堆分配，闭包与外层方法共享同一存储，因此闭包可以修改它并在方法返回后继续使用。
+
+use crate::ast::*;
+use crate::types::{Type, FunctionType, CaptureInfo, LambdaInfo};
+use crate::error::{cayResult, semantic_error, SourceLocation};
+use super::analyzer::SemanticAnalyzer;
+use super::symbol_table::SemanticSymbolInfo;
+use crate::diagnostic::ErrorCodes;
+
+/// 正在检查的 Lambda
+pub(super) struct LambdaFrame {
+    /// 进入 Lambda 前符号表的层数，更外层作用域中的局部变量需要捕获
+    depth: usize,
+    captures: Vec<CaptureInfo>,
+}
+
+impl SemanticAnalyzer {
+    /// 查找局部符号；在 Lambda 体中引用外层局部变量时记录捕获
+    pub(super) fn lookup_local(&mut self, name: &str) -> Option<SemanticSymbolInfo> {
+        let (depth, info) = self.symbol_table.lookup_with_depth(name)?;
+        let info = info.clone();
+        // 全局作用域中只有顶层函数，不需要捕获
+        if depth > 0 {
+            self.note_capture(depth, &info);
+        }
+        Some(info)
+    }
+
+    /// 把外层符号加入所有跨越其作用域的 Lambda 的捕获列表
+    fn note_capture(&mut self, depth: usize, info: &SemanticSymbolInfo) {
+        let by_ref = !info.is_final && info.decl_loc.is_some();
+        let mut captured = false;
+        for frame in self.lambda_frames.iter_mut().filter(|f| depth < f.depth) {
+            captured = true;
+            if !frame.captures.iter().any(|c| c.name == info.name) {
+                frame.captures.push(CaptureInfo {
+                    name: info.name.clone(),
+                    var_type: info.symbol_type.clone(),
+                    by_ref,
+                });
+            }
+        }
+        if let (true, true, Some(loc)) = (captured, by_ref, &info.decl_loc) {
+            self.type_registry.boxed_locals.insert((loc.clone(), info.name.clone()));
+        }
+    }
+
+    /// 按值捕获的参数在 Lambda 体中只读
+    pub(super) fn check_captured_assignment(&self, name: &str, loc: &SourceLocation) -> cayResult<()> {
+        let by_value = self.lambda_frames.last()
+            .is_some_and(|frame| frame.captures.iter().any(|c| c.name == name && !c.by_ref));
+        if by_value {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_CAPTURED_ASSIGNMENT,
+                loc.line,
+                loc.column,
+                format!("Cannot assign to '{}' inside a lambda: parameters are captured by value", name)
+            ));
+        }
+        Ok(())
+    }
+
+    /// 推断 Lambda 表达式类型，并记录其签名与捕获列表
+    ///
+    /// 期望类型是参数个数相同的函数类型时，未标注类型的参数和返回类型取自期望类型；
+    /// 否则未标注的参数按 int 处理，返回类型由 Lambda 体推断。
+    pub(super) fn infer_lambda_type(&mut self, lambda: &LambdaExpr, expected: Option<&FunctionType>) -> cayResult<Type> {
+        // 没有期望类型时沿用之前在同一位置按期望类型推断出的签名
+        let recorded = match expected {
+            Some(_) => None,
+            None => self.type_registry.lambdas.get(&lambda.loc).map(|info| info.signature.clone()),
+        };
+        let expected = expected.or(recorded.as_ref())
+            .filter(|func| func.params.len() == lambda.params.len());
+
+        let mut param_types = Vec::new();
+        for (i, param) in lambda.params.iter().enumerate() {
+            let param_type = match (&param.param_type, expected) {
+                (Some(ty), _) => self.resolve_type(ty, lambda.loc.line, lambda.loc.column)?,
+                (None, Some(func)) => func.params[i].clone(),
+                (None, None) => Type::Int32,
+            };
+            param_types.push(param_type);
+        }
+        let expected_return = match expected {
+            Some(func) => Some(self.resolve_type(&func.return_type, lambda.loc.line, lambda.loc.column)?),
+            None => None,
+        };
+
+        // Lambda 体中的 `?` 只能从 Lambda 自身返回
+        let outer_return_type = std::mem::replace(&mut self.current_return_type, expected_return.clone());
+        self.lambda_frames.push(LambdaFrame { depth: self.symbol_table.depth(), captures: Vec::new() });
+        self.symbol_table.enter_scope();
+
+        for (param, param_type) in lambda.params.iter().zip(&param_types) {
+            self.symbol_table.declare(
+                param.name.clone(),
+                SemanticSymbolInfo {
+                    name: param.name.clone(),
+                    symbol_type: param_type.clone(),
+                    is_final: false,
+                    is_initialized: true,
+                    nullable: param.param_type.as_ref().is_some_and(|ty| ty.is_nullable()),
+                    decl_loc: None,
+                }
+            );
+        }
+
+        let body_type = self.infer_lambda_body(lambda, expected_return.as_ref());
+
+        self.symbol_table.exit_scope();
+        let frame = self.lambda_frames.pop();
+        self.current_return_type = outer_return_type;
+
+        let signature = FunctionType {
+            params: param_types,
+            return_type: Box::new(body_type?),
+            is_static: true,
+        };
+        self.type_registry.lambdas.insert(lambda.loc.clone(), LambdaInfo {
+            signature: signature.clone(),
+            captures: frame.map(|f| f.captures).unwrap_or_default(),
+        });
+        Ok(Type::Function(Box::new(signature)))
+    }
+
+    /// 检查 Lambda 体并返回其返回类型
+    fn infer_lambda_body(&mut self, lambda: &LambdaExpr, expected_return: Option<&Type>) -> cayResult<Type> {
+        match (&lambda.body, expected_return) {
+            // 期望 void 时表达式体只为副作用求值
+            (LambdaBody::Expr(expr), Some(Type::Void)) => {
+                self.infer_expr_type(expr)?;
+                Ok(Type::Void)
+            }
+            (LambdaBody::Expr(expr), Some(expected)) => {
+                let body_type = self.infer_expr_type_expecting(expr, expected)?;
+                if !self.types_compatible(&body_type, expected) {
+                    return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_RETURN_TYPE_MISMATCH,
+                        lambda.loc.line,
+                        lambda.loc.column,
+                        format!("Return type mismatch: expected {}, got {}", expected, body_type)
+                    ));
+                }
+                Ok(expected.clone())
+            }
+            (LambdaBody::Expr(expr), None) => self.infer_expr_type(expr),
+            (LambdaBody::Block(block), Some(expected)) => {
+                for stmt in &block.statements {
+                    self.type_check_statement(stmt, Some(expected))?;
+                }
+                Ok(expected.clone())
+            }
+            (LambdaBody::Block(block), None) => {
+                // 使用第一个 return 语句的类型
+                let mut inferred_return: Option<Type> = None;
+                for stmt in &block.statements {
+                    if let (None, Stmt::Return(ret_expr)) = (&inferred_return, stmt) {
+                        inferred_return = Some(match ret_expr {
+                            Some(e) => self.infer_expr_type(e)?,
+                            None => Type::Void,
+                        });
+                    }
+                    self.type_check_statement(stmt, inferred_return.as_ref())?;
+                }
+                Ok(inferred_return.unwrap_or(Type::Void))
+            }
+        }
+    }
+}
@@ -0,0 +1,1015 @@
+//! 编译期常量求值
+//!
+//! 常量表达式由字面量、static final 常量、算术/位/比较/逻辑运算、三元运算、类型转换、
+//! 字符串拼接和 const 函数调用组成。求值结果用于：
+//!
+//! - 静态字段的初始值，直接作为全局变量的初始值（`TypeRegistry::static_constants`）；
+//! - 数组创建的长度（`TypeRegistry::array_sizes`）；
+//! - switch 的 case 标签（`TypeRegistry::case_constants`）。
+//!
+//! 整数运算与运行时一致：按 32/64 位回绕，移位次数只取低 5/6 位。const 函数是带方法体的
+//! 静态方法，方法体只能使用参数、局部变量、赋值、if、循环和 return，求值时逐条解释执行。
+
+use std::collections::HashMap;
+use crate::ast::*;
+use crate::types::Type;
+use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
+
+/// 单次求值最多执行的语句数（含循环迭代和函数调用）
+const MAX_STEPS: usize = 1_000_000;
+/// const 函数调用的最大嵌套深度
+const MAX_CALL_DEPTH: usize = 128;
+
+/// 常量求值失败的原因
+#[derive(Debug, Clone)]
+pub(super) enum ConstError {
+    /// 表达式不是常量表达式，如引用了局部变量或调用了普通方法
+    NotConstant(String),
+    /// 表达式是常量表达式，但求值出错，如除以零
+    Failed(String),
+}
+
+impl ConstError {
+    /// 常量上下文中的错误描述，`what` 是小写开头的名词短语，如 "case label 'MAX'"
+    pub(super) fn describe(&self, what: &str) -> String {
+        match self {
+            ConstError::NotConstant(reason) => {
+                let mut chars = what.chars();
+                let capitalized: String = chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default();
+                format!("{} is not a constant: {}", capitalized, reason)
+            }
+            ConstError::Failed(reason) => format!("Cannot evaluate {} at compile time: {}", what, reason),
+        }
+    }
+}
+
+type ConstResult<T> = Result<T, ConstError>;
+
+fn not_constant<T>(message: String) -> ConstResult<T> {
+    Err(ConstError::NotConstant(message))
+}
+
+/// 语句执行后的控制流
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return(LiteralValue),
+}
+
+/// 求值上下文：顶层表达式、static final 常量的初始值或一次 const 函数调用
+struct Frame {
+    class_name: String,
+    /// const 函数的参数和局部变量: 名称 -> (声明类型, 当前值)
+    locals: HashMap<String, (Type, LiteralValue)>,
+}
+
+/// 常量求值器
+pub(super) struct ConstEvaluator<'a> {
+    program: &'a Program,
+    /// 顶层表达式所在作用域中的局部变量会遮蔽同名常量，它们不是常量
+    is_local: &'a dyn Fn(&str) -> bool,
+    steps: usize,
+    frames: Vec<Frame>,
+    /// 正在求值的 static final 常量，用于发现循环引用
+    fields_in_progress: Vec<String>,
+}
+
+impl<'a> ConstEvaluator<'a> {
+    pub(super) fn new(program: &'a Program, class_name: &str, is_local: &'a dyn Fn(&str) -> bool) -> Self {
+        Self {
+            program,
+            is_local,
+            steps: 0,
+            frames: vec![Frame { class_name: class_name.to_string(), locals: HashMap::new() }],
+            fields_in_progress: Vec::new(),
+        }
+    }
+
+    /// 对表达式求值
+    pub(super) fn eval(&mut self, expr: &Expr) -> ConstResult<LiteralValue> {
+        match expr {
+            Expr::Literal(LiteralValue::Null) => not_constant("null is not a constant".to_string()),
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Identifier(id) => self.eval_identifier(&id.name),
+            Expr::MemberAccess(member) => match self.class_qualifier(&member.object) {
+                Some(class_name) => self.eval_constant_field(&class_name, &member.member),
+                None => not_constant(format!("'{}' is not a static final constant", member.member)),
+            },
+            Expr::Binary(binary) => self.eval_binary(binary),
+            Expr::Unary(unary) => self.eval_unary(unary),
+            Expr::Ternary(ternary) => {
+                if self.eval_condition(&ternary.condition)? {
+                    self.eval(&ternary.true_branch)
+                } else {
+                    self.eval(&ternary.false_branch)
+                }
+            }
+            Expr::Cast(cast) => {
+                let value = self.eval(&cast.expr)?;
+                cast_value(value, &cast.target_type)
+            }
+            Expr::Call(call) => self.eval_call(call),
+            Expr::Assignment(assign) => self.eval_assignment(assign),
+            other => not_constant(format!("{} is not allowed in a constant expression", expr_kind(other))),
+        }
+    }
+
+    fn frame(&self) -> &Frame {
+        self.frames.last().expect("constant evaluation always has a frame")
+    }
+
+    fn frame_mut(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("constant evaluation always has a frame")
+    }
+
+    fn step(&mut self) -> ConstResult<()> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return Err(ConstError::Failed(format!("constant evaluation did not finish within {} steps", MAX_STEPS)));
+        }
+        Ok(())
+    }
+
+    /// 顶层表达式作用域中的局部变量（const 函数的局部变量在 Frame::locals 中）
+    fn is_outer_local(&self, name: &str) -> bool {
+        self.frames.len() == 1 && (self.is_local)(name)
+    }
+
+    fn eval_identifier(&mut self, name: &str) -> ConstResult<LiteralValue> {
+        if let Some((_, value)) = self.frame().locals.get(name) {
+            return Ok(value.clone());
+        }
+        if self.is_outer_local(name) {
+            return not_constant(format!("'{}' is a local variable", name));
+        }
+        let class_name = self.frame().class_name.clone();
+        self.eval_constant_field(&class_name, name)
+    }
+
+    /// `Limits.MAX` 中作为限定名的类（同名局部变量优先）
+    fn class_qualifier(&self, object: &Expr) -> Option<String> {
+        match object {
+            Expr::Identifier(id) if !self.frame().locals.contains_key(&id.name)
+                && !self.is_outer_local(&id.name)
+                && find_class(self.program, &id.name).is_some() => Some(id.name.clone()),
+            _ => None,
+        }
+    }
+
+    /// static final 常量的值（在声明它的类中对初始值求值）
+    pub(super) fn eval_constant_field(&mut self, class_name: &str, field_name: &str) -> ConstResult<LiteralValue> {
+        let (owner, field) = match find_field(self.program, class_name, field_name) {
+            Some(found) => found,
+            None => return not_constant(format!("'{}' is not a static final constant", field_name)),
+        };
+        let key = format!("{}.{}", owner.name, field.name);
+        if !is_static_final(field) {
+            return not_constant(format!("'{}' is not a static final field", key));
+        }
+        let initializer = match &field.initializer {
+            Some(initializer) => initializer,
+            None => return not_constant(format!("'{}' has no initializer", key)),
+        };
+        if self.fields_in_progress.contains(&key) {
+            return Err(ConstError::Failed(format!("constant '{}' depends on itself", key)));
+        }
+
+        self.fields_in_progress.push(key);
+        self.frames.push(Frame { class_name: owner.name.clone(), locals: HashMap::new() });
+        let result = self.eval(initializer).and_then(|value| coerce(value, &field.field_type));
+        self.frames.pop();
+        self.fields_in_progress.pop();
+        result
+    }
+
+    fn eval_condition(&mut self, expr: &Expr) -> ConstResult<bool> {
+        match self.eval(expr)? {
+            LiteralValue::Bool(value) => Ok(value),
+            other => not_constant(format!("condition must be boolean, got {}", value_type(&other))),
+        }
+    }
+
+    fn eval_binary(&mut self, binary: &BinaryExpr) -> ConstResult<LiteralValue> {
+        match binary.op {
+            // 逻辑运算短路求值
+            BinaryOp::And => Ok(LiteralValue::Bool(self.eval_condition(&binary.left)? && self.eval_condition(&binary.right)?)),
+            BinaryOp::Or => Ok(LiteralValue::Bool(self.eval_condition(&binary.left)? || self.eval_condition(&binary.right)?)),
+            op => {
+                let left = self.eval(&binary.left)?;
+                let right = self.eval(&binary.right)?;
+                binary_op(op, left, right)
+            }
+        }
+    }
+
+    fn eval_unary(&mut self, unary: &UnaryExpr) -> ConstResult<LiteralValue> {
+        let delta = match unary.op {
+            UnaryOp::PreInc | UnaryOp::PostInc => 1,
+            UnaryOp::PreDec | UnaryOp::PostDec => -1,
+            UnaryOp::AddressOf | UnaryOp::Deref => {
+                return not_constant("pointer operations are not allowed in a constant expression".to_string());
+            }
+            op => {
+                let value = self.eval(&unary.operand)?;
+                return unary_op(op, value);
+            }
+        };
+        let old = self.eval(&unary.operand)?;
+        let new = self.store_local(&unary.operand, binary_op(BinaryOp::Add, old.clone(), LiteralValue::Int32(delta))?)?;
+        match unary.op {
+            UnaryOp::PreInc | UnaryOp::PreDec => Ok(new),
+            _ => Ok(old),
+        }
+    }
+
+    fn eval_assignment(&mut self, assign: &AssignmentExpr) -> ConstResult<LiteralValue> {
+        let value = self.eval(&assign.value)?;
+        let op = match assign.op {
+            AssignOp::Assign => return self.store_local(&assign.target, value),
+            AssignOp::AddAssign => BinaryOp::Add,
+            AssignOp::SubAssign => BinaryOp::Sub,
+            AssignOp::MulAssign => BinaryOp::Mul,
+            AssignOp::DivAssign => BinaryOp::Div,
+            AssignOp::ModAssign => BinaryOp::Mod,
+        };
+        let current = self.eval(&assign.target)?;
+        let result = binary_op(op, current, value)?;
+        self.store_local(&assign.target, result)
+    }
+
+    /// 给 const 函数的局部变量赋值，值按声明类型转换
+    fn store_local(&mut self, target: &Expr, value: LiteralValue) -> ConstResult<LiteralValue> {
+        let name = match target {
+            Expr::Identifier(id) => &id.name,
+            _ => return not_constant("only local variables of const functions can be assigned".to_string()),
+        };
+        let declared = match self.frame().locals.get(name) {
+            Some((declared, _)) => declared.clone(),
+            None => return not_constant(format!("'{}' is not a local variable of a const function", name)),
+        };
+        let value = coerce(value, &declared)?;
+        self.frame_mut().locals.insert(name.clone(), (declared, value.clone()));
+        Ok(value)
+    }
+
+    fn eval_call(&mut self, call: &CallExpr) -> ConstResult<LiteralValue> {
+        let (class_name, method_name) = match call.callee.as_ref() {
+            Expr::Identifier(id) => (self.frame().class_name.clone(), id.name.clone()),
+            Expr::MemberAccess(member) => match self.class_qualifier(&member.object) {
+                Some(class_name) => (class_name, member.member.clone()),
+                None => return not_constant(format!("'{}' is not a const function", member.member)),
+            },
+            _ => return not_constant("only const functions can be called in a constant expression".to_string()),
+        };
+        let mut args = Vec::new();
+        for arg in &call.args {
+            args.push(self.eval(arg)?);
+        }
+
+        let class = match find_class(self.program, &class_name) {
+            Some(class) => class,
+            None => return not_constant(format!("'{}' is not a const function", method_name)),
+        };
+        // 按参数个数和参数类型选择重载
+        let candidates: Vec<&MethodDecl> = const_functions(class, &method_name)
+            .filter(|method| method.params.len() == args.len())
+            .collect();
+        if candidates.is_empty() {
+            return not_constant(format!("'{}.{}' is not a const function", class_name, method_name));
+        }
+        for method in candidates {
+            let params: ConstResult<Vec<LiteralValue>> = method.params.iter().zip(&args)
+                .map(|(param, arg)| coerce(arg.clone(), &param.param_type))
+                .collect();
+            if let Ok(params) = params {
+                return self.call_const_function(&class_name, method, params);
+            }
+        }
+        not_constant(format!("no overload of const function '{}.{}' accepts these arguments", class_name, method_name))
+    }
+
+    fn call_const_function(&mut self, class_name: &str, method: &MethodDecl, args: Vec<LiteralValue>) -> ConstResult<LiteralValue> {
+        self.step()?;
+        if self.frames.len() > MAX_CALL_DEPTH {
+            return Err(ConstError::Failed(format!("const function calls are nested more than {} levels deep", MAX_CALL_DEPTH)));
+        }
+        let body = match &method.body {
+            Some(body) => body,
+            None => return not_constant(format!("'{}.{}' has no body", class_name, method.name)),
+        };
+        let locals = method.params.iter().zip(args)
+            .map(|(param, value)| (param.name.clone(), (param.param_type.clone(), value)))
+            .collect();
+
+        self.frames.push(Frame { class_name: class_name.to_string(), locals });
+        let flow = self.exec_block(&body.statements);
+        self.frames.pop();
+        match flow? {
+            Flow::Return(value) => coerce(value, &method.return_type),
+            _ => Err(ConstError::Failed(format!("const function '{}.{}' finished without returning a value", class_name, method.name))),
+        }
+    }
+
+    fn exec_block(&mut self, statements: &[Stmt]) -> ConstResult<Flow> {
+        for stmt in statements {
+            match self.exec(stmt)? {
+                Flow::Normal => {}
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn exec(&mut self, stmt: &Stmt) -> ConstResult<Flow> {
+        self.step()?;
+        match stmt {
+            Stmt::Expr(expr) => {
+                self.eval(expr)?;
+                Ok(Flow::Normal)
+            }
+            Stmt::VarDecl(decl) => {
+                let value = match &decl.initializer {
+                    Some(initializer) => {
+                        let value = self.eval(initializer)?;
+                        coerce(value, &decl.var_type)?
+                    }
+                    None => zero_value(&decl.var_type)?,
+                };
+                let declared = match decl.var_type {
+                    Type::Auto => value_static_type(&value),
+                    ref declared => declared.clone(),
+                };
+                self.frame_mut().locals.insert(decl.name.clone(), (declared, value));
+                Ok(Flow::Normal)
+            }
+            Stmt::Return(Some(expr)) => Ok(Flow::Return(self.eval(expr)?)),
+            Stmt::If(if_stmt) => {
+                if self.eval_condition(&if_stmt.condition)? {
+                    self.exec(&if_stmt.then_branch)
+                } else if let Some(else_branch) = &if_stmt.else_branch {
+                    self.exec(else_branch)
+                } else {
+                    Ok(Flow::Normal)
+                }
+            }
+            Stmt::While(while_stmt) => {
+                while self.eval_condition(&while_stmt.condition)? {
+                    match self.exec(&while_stmt.body)? {
+                        Flow::Break => break,
+                        Flow::Return(value) => return Ok(Flow::Return(value)),
+                        Flow::Normal | Flow::Continue => {}
+                    }
+                }
+                Ok(Flow::Normal)
+            }
+            Stmt::DoWhile(do_while) => {
+                loop {
+                    match self.exec(&do_while.body)? {
+                        Flow::Break => break,
+                        Flow::Return(value) => return Ok(Flow::Return(value)),
+                        Flow::Normal | Flow::Continue => {}
+                    }
+                    if !self.eval_condition(&do_while.condition)? {
+                        break;
+                    }
+                }
+                Ok(Flow::Normal)
+            }
+            Stmt::For(for_stmt) => {
+                if let Some(init) = &for_stmt.init {
+                    self.exec(init)?;
+                }
+                loop {
+                    let keep_going = match &for_stmt.condition {
+                        Some(condition) => self.eval_condition(condition)?,
+                        None => true,
+                    };
+                    if !keep_going {
+                        break;
+                    }
+                    match self.exec(&for_stmt.body)? {
+                        Flow::Break => break,
+                        Flow::Return(value) => return Ok(Flow::Return(value)),
+                        Flow::Normal | Flow::Continue => {}
+                    }
+                    if let Some(update) = &for_stmt.update {
+                        self.eval(update)?;
+                    }
+                    self.step()?;
+                }
+                Ok(Flow::Normal)
+            }
+            Stmt::Block(block) => self.exec_block(&block.statements),
+            Stmt::Break(None) => Ok(Flow::Break),
+            Stmt::Continue(None) => Ok(Flow::Continue),
+            other => not_constant(format!("{} is not allowed in a const function", stmt_kind(other))),
+        }
+    }
+}
+
+impl SemanticAnalyzer {
+    /// 在当前类中对表达式求值；当前作用域中的局部变量不是常量
+    pub(super) fn evaluate_const(&self, expr: &Expr) -> Result<LiteralValue, ConstError> {
+        let program = match &self.program {
+            Some(program) => program.clone(),
+            None => return not_constant("the program is not available".to_string()),
+        };
+        let class_name = self.current_class.clone().unwrap_or_default();
+        let is_local = |name: &str| self.symbol_table.lookup(name).is_some();
+        ConstEvaluator::new(&program, &class_name, &is_local).eval(expr)
+    }
+
+    /// 折叠静态字段的初始值；能折叠的 static final 字段是编译期常量
+    pub(super) fn fold_static_constants(&mut self, program: &Program) {
+        let no_locals = |_: &str| false;
+        for class in &program.classes {
+            for member in &class.members {
+                let field = match member {
+                    ClassMember::Field(field) if field.modifiers.contains(&Modifier::Static) => field,
+                    _ => continue,
+                };
+                let initializer = match &field.initializer {
+                    Some(initializer) => initializer,
+                    None => continue,
+                };
+                let mut evaluator = ConstEvaluator::new(program, &class.name, &no_locals);
+                let result = match initializer {
+                    // 静态数组在程序入口处按常量长度分配
+                    Expr::ArrayCreation(array) => {
+                        let size = array.sizes.first()
+                            .and_then(|size| evaluator.eval(size).ok())
+                            .and_then(|size| as_integer(&size));
+                        if let Some(size) = size.filter(|size| *size >= 0) {
+                            self.type_registry.array_sizes.insert(array.loc.clone(), size);
+                        }
+                        continue;
+                    }
+                    // 常量经 eval_constant_field 求值，以便发现引用自身的初始值
+                    _ if is_static_final(field) => evaluator.eval_constant_field(&class.name, &field.name),
+                    _ => evaluator.eval(initializer).and_then(|value| coerce(value, &field.field_type)),
+                };
+                match result {
+                    Ok(value) => {
+                        let key = format!("{}.{}", class.name, field.name);
+                        self.type_registry.static_constants.insert(key, value);
+                        let info = self.type_registry.get_class_mut(&class.name)
+                            .and_then(|class_info| class_info.fields.get_mut(&field.name))
+                            .filter(|_| is_static_final(field));
+                        if let Some(info) = info {
+                            info.is_const_expr = true;
+                        }
+                    }
+                    // 不是常量的初始值不折叠；是常量但求值出错（如除以零）时报错
+                    Err(err @ ConstError::Failed(_)) => {
+                        self.errors.push(self.create_error_info(
+                            ErrorCodes::SEMANTIC_CONST_EVAL,
+                            field.loc.line,
+                            field.loc.column,
+                            err.describe(&format!("initializer of '{}.{}'", class.name, field.name)),
+                        ));
+                    }
+                    Err(ConstError::NotConstant(_)) => {}
+                }
+            }
+        }
+    }
+
+    /// 检查 const 修饰符的用法和 const 函数体
+    pub(super) fn check_const_functions(&mut self, program: &Program) {
+        for class in &program.classes {
+            if class.modifiers.contains(&Modifier::Const) {
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_CONST_EVAL,
+                    class.loc.line,
+                    class.loc.column,
+                    format!("'const' cannot be applied to class '{}'; only static methods can be const", class.name),
+                ));
+            }
+            for member in &class.members {
+                match member {
+                    ClassMember::Method(method) if method.modifiers.contains(&Modifier::Const) => {
+                        self.check_const_function(program, class, method);
+                    }
+                    ClassMember::Field(field) if field.modifiers.contains(&Modifier::Const) => {
+                        self.errors.push(self.create_error_info(
+                            ErrorCodes::SEMANTIC_CONST_EVAL,
+                            field.loc.line,
+                            field.loc.column,
+                            format!("'const' cannot be applied to field '{}.{}'; use 'static final' for constants", class.name, field.name),
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for interface in &program.interfaces {
+            for method in interface.methods.iter().filter(|method| method.modifiers.contains(&Modifier::Const)) {
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_CONST_EVAL,
+                    method.loc.line,
+                    method.loc.column,
+                    format!("'const' cannot be applied to interface method '{}.{}'", interface.name, method.name),
+                ));
+            }
+        }
+    }
+
+    fn check_const_function(&mut self, program: &Program, class: &ClassDecl, method: &MethodDecl) {
+        let name = format!("{}.{}", class.name, method.name);
+        let body = match &method.body {
+            Some(body) if method.modifiers.contains(&Modifier::Static) => body,
+            _ => {
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_CONST_EVAL,
+                    method.loc.line,
+                    method.loc.column,
+                    format!("Const function '{}' must be a static method with a body", name),
+                ));
+                return;
+            }
+        };
+        if !is_const_type(&method.return_type) {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_CONST_EVAL,
+                method.loc.line,
+                method.loc.column,
+                format!("Const function '{}' must return int, long, float, double, boolean, char or String, not {}", name, method.return_type),
+            ));
+        }
+        for param in &method.params {
+            if param.is_varargs || !is_const_type(&param.param_type) {
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_CONST_EVAL,
+                    method.loc.line,
+                    method.loc.column,
+                    format!("Parameter '{}' of const function '{}' has type {}; const functions only accept int, long, float, double, boolean, char and String", param.name, name, param.param_type),
+                ));
+            }
+        }
+
+        let mut checker = ConstBodyChecker {
+            program,
+            class,
+            locals: method.params.iter().map(|param| param.name.clone()).collect(),
+        };
+        if let Err((expr, reason)) = checker.check_block(&body.statements) {
+            let (line, column) = match expr.map(|expr| self.get_expr_location(expr)) {
+                Some((0, 0)) | None => (method.loc.line, method.loc.column),
+                Some(location) => location,
+            };
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_CONST_EVAL,
+                line,
+                column,
+                format!("Const function '{}' cannot be evaluated at compile time: {}", name, reason),
+            ));
+        }
+    }
+}
+
+/// 检查 const 函数体只使用可在编译期求值的语句和表达式
+struct ConstBodyChecker<'a> {
+    program: &'a Program,
+    class: &'a ClassDecl,
+    locals: Vec<String>,
+}
+
+/// 不允许的语句或表达式及原因
+type Violation<'e> = (Option<&'e Expr>, String);
+
+impl<'a> ConstBodyChecker<'a> {
+    fn check_block<'e>(&mut self, statements: &'e [Stmt]) -> Result<(), Violation<'e>> {
+        statements.iter().try_for_each(|stmt| self.check_stmt(stmt))
+    }
+
+    fn check_stmt<'e>(&mut self, stmt: &'e Stmt) -> Result<(), Violation<'e>> {
+        match stmt {
+            Stmt::Expr(expr) | Stmt::Return(Some(expr)) => self.check_expr(expr),
+            Stmt::VarDecl(decl) => {
+                if let Some(initializer) = &decl.initializer {
+                    self.check_expr(initializer)?;
+                }
+                if !matches!(decl.var_type, Type::Auto) && !is_const_type(&decl.var_type) {
+                    return Err((None, format!("local variable '{}' has type {}", decl.name, decl.var_type)));
+                }
+                self.locals.push(decl.name.clone());
+                Ok(())
+            }
+            Stmt::If(if_stmt) => {
+                self.check_expr(&if_stmt.condition)?;
+                self.check_stmt(&if_stmt.then_branch)?;
+                match &if_stmt.else_branch {
+                    Some(else_branch) => self.check_stmt(else_branch),
+                    None => Ok(()),
+                }
+            }
+            Stmt::While(while_stmt) => {
+                self.check_expr(&while_stmt.condition)?;
+                self.check_stmt(&while_stmt.body)
+            }
+            Stmt::DoWhile(do_while) => {
+                self.check_stmt(&do_while.body)?;
+                self.check_expr(&do_while.condition)
+            }
+            Stmt::For(for_stmt) => {
+                if let Some(init) = &for_stmt.init {
+                    self.check_stmt(init)?;
+                }
+                if let Some(condition) = &for_stmt.condition {
+                    self.check_expr(condition)?;
+                }
+                if let Some(update) = &for_stmt.update {
+                    self.check_expr(update)?;
+                }
+                self.check_stmt(&for_stmt.body)
+            }
+            Stmt::Block(block) => self.check_block(&block.statements),
+            Stmt::Break(None) | Stmt::Continue(None) => Ok(()),
+            Stmt::Return(None) => Err((None, "every return must have a value".to_string())),
+            other => Err((None, format!("{} is not allowed", stmt_kind(other)))),
+        }
+    }
+
+    fn check_expr<'e>(&mut self, expr: &'e Expr) -> Result<(), Violation<'e>> {
+        match expr {
+            Expr::Literal(LiteralValue::Null) => Err((Some(expr), "null is not a constant".to_string())),
+            Expr::Literal(_) => Ok(()),
+            Expr::Identifier(id) => {
+                if self.locals.contains(&id.name) || find_field(self.program, &self.class.name, &id.name).is_some_and(|(_, field)| is_static_final(field)) {
+                    Ok(())
+                } else {
+                    Err((Some(expr), format!("'{}' is not a parameter, local variable or static final constant", id.name)))
+                }
+            }
+            Expr::MemberAccess(member) => match self.qualifier(&member.object) {
+                Some(class_name) if find_field(self.program, class_name, &member.member).is_some_and(|(_, field)| is_static_final(field)) => Ok(()),
+                _ => Err((Some(expr), format!("'{}' is not a static final constant", member.member))),
+            },
+            Expr::Binary(binary) => {
+                self.check_expr(&binary.left)?;
+                self.check_expr(&binary.right)
+            }
+            Expr::Unary(unary) => match unary.op {
+                UnaryOp::AddressOf | UnaryOp::Deref => Err((Some(expr), "pointer operations are not allowed".to_string())),
+                UnaryOp::PreInc | UnaryOp::PreDec | UnaryOp::PostInc | UnaryOp::PostDec => self.check_local_target(&unary.operand),
+                _ => self.check_expr(&unary.operand),
+            },
+            Expr::Ternary(ternary) => {
+                self.check_expr(&ternary.condition)?;
+                self.check_expr(&ternary.true_branch)?;
+                self.check_expr(&ternary.false_branch)
+            }
+            Expr::Cast(cast) => self.check_expr(&cast.expr),
+            Expr::Assignment(assign) => {
+                self.check_local_target(&assign.target)?;
+                self.check_expr(&assign.value)
+            }
+            Expr::Call(call) => {
+                let (class_name, method_name) = match call.callee.as_ref() {
+                    Expr::Identifier(id) => (Some(self.class.name.as_str()), &id.name),
+                    Expr::MemberAccess(member) => (self.qualifier(&member.object), &member.member),
+                    _ => (None, &self.class.name),
+                };
+                let is_const = class_name.and_then(|class_name| find_class(self.program, class_name))
+                    .is_some_and(|class| const_functions(class, method_name).any(|method| method.params.len() == call.args.len()));
+                if !is_const {
+                    return Err((Some(expr), format!("'{}' is not a const function", method_name)));
+                }
+                call.args.iter().try_for_each(|arg| self.check_expr(arg))
+            }
+            other => Err((Some(expr), format!("{} is not allowed", expr_kind(other)))),
+        }
+    }
+
+    fn check_local_target<'e>(&self, target: &'e Expr) -> Result<(), Violation<'e>> {
+        match target {
+            Expr::Identifier(id) if self.locals.contains(&id.name) => Ok(()),
+            _ => Err((Some(target), "only parameters and local variables can be assigned".to_string())),
+        }
+    }
+
+    fn qualifier<'e>(&self, object: &'e Expr) -> Option<&'e str> {
+        match object {
+            Expr::Identifier(id) if !self.locals.contains(&id.name) && find_class(self.program, &id.name).is_some() => Some(&id.name),
+            _ => None,
+        }
+    }
+}
+
+fn find_class<'p>(program: &'p Program, name: &str) -> Option<&'p ClassDecl> {
+    program.classes.iter().find(|class| class.name == name)
+}
+
+/// 在类及其父类中查找字段，返回声明它的类
+fn find_field<'p>(program: &'p Program, class_name: &str, field_name: &str) -> Option<(&'p ClassDecl, &'p FieldDecl)> {
+    let mut class = find_class(program, class_name);
+    while let Some(current) = class {
+        let field = current.members.iter().find_map(|member| match member {
+            ClassMember::Field(field) if field.name == field_name => Some(field),
+            _ => None,
+        });
+        if let Some(field) = field {
+            return Some((current, field));
+        }
+        class = current.parent.as_deref().and_then(|parent| find_class(program, parent));
+    }
+    None
+}
+
+fn const_functions<'p>(class: &'p ClassDecl, name: &'p str) -> impl Iterator<Item = &'p MethodDecl> + 'p {
+    class.members.iter().filter_map(move |member| match member {
+        ClassMember::Method(method) if method.name == name
+            && method.modifiers.contains(&Modifier::Const)
+            && method.modifiers.contains(&Modifier::Static) => Some(method),
+        _ => None,
+    })
+}
+
+fn is_static_final(field: &FieldDecl) -> bool {
+    field.modifiers.contains(&Modifier::Static) && field.modifiers.contains(&Modifier::Final)
+}
+
+/// const 函数的参数、返回值和局部变量可以使用的类型
+fn is_const_type(ty: &Type) -> bool {
+    matches!(ty, Type::Int32 | Type::Int64 | Type::Float32 | Type::Float64 | Type::Bool | Type::Char | Type::String)
+}
+
+fn as_integer(value: &LiteralValue) -> Option<i64> {
+    match value {
+        LiteralValue::Int32(v) => Some(*v as i64),
+        LiteralValue::Int64(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn value_type(value: &LiteralValue) -> &'static str {
+    match value {
+        LiteralValue::Int32(_) => "int",
+        LiteralValue::Int64(_) => "long",
+        LiteralValue::Float32(_) => "float",
+        LiteralValue::Float64(_) => "double",
+        LiteralValue::String(_) => "String",
+        LiteralValue::Bool(_) => "boolean",
+        LiteralValue::Char(_) => "char",
+        LiteralValue::Null => "null",
+    }
+}
+
+fn value_static_type(value: &LiteralValue) -> Type {
+    match value {
+        LiteralValue::Int32(_) => Type::Int32,
+        LiteralValue::Int64(_) => Type::Int64,
+        LiteralValue::Float32(_) => Type::Float32,
+        LiteralValue::Float64(_) => Type::Float64,
+        LiteralValue::String(_) => Type::String,
+        LiteralValue::Bool(_) => Type::Bool,
+        LiteralValue::Char(_) => Type::Char,
+        LiteralValue::Null => Type::Auto,
+    }
+}
+
+fn zero_value(ty: &Type) -> ConstResult<LiteralValue> {
+    match ty {
+        Type::Int32 => Ok(LiteralValue::Int32(0)),
+        Type::Int64 => Ok(LiteralValue::Int64(0)),
+        Type::Float32 => Ok(LiteralValue::Float32(0.0)),
+        Type::Float64 => Ok(LiteralValue::Float64(0.0)),
+        Type::Bool => Ok(LiteralValue::Bool(false)),
+        Type::Char => Ok(LiteralValue::Char('\0')),
+        other => not_constant(format!("{} is not a constant type", other)),
+    }
+}
+
+/// 赋值、传参和返回时按目标类型转换（只做拓宽转换和字面量允许的 double 到 float）
+fn coerce(value: LiteralValue, ty: &Type) -> ConstResult<LiteralValue> {
+    use LiteralValue as V;
+    match (ty, value) {
+        (Type::Auto, value) => Ok(value),
+        (Type::Int32, V::Int32(v)) => Ok(V::Int32(v)),
+        (Type::Int32, V::Char(c)) => Ok(V::Int32(c as i32)),
+        (Type::Int64, V::Int32(v)) => Ok(V::Int64(v as i64)),
+        (Type::Int64, V::Int64(v)) => Ok(V::Int64(v)),
+        (Type::Int64, V::Char(c)) => Ok(V::Int64(c as i64)),
+        (Type::Float32 | Type::Float64, value @ (V::Int32(_) | V::Int64(_) | V::Float32(_) | V::Float64(_) | V::Char(_))) => cast_value(value, ty),
+        (Type::Char, V::Char(c)) => Ok(V::Char(c)),
+        (Type::Bool, V::Bool(b)) => Ok(V::Bool(b)),
+        (Type::String, V::String(s)) => Ok(V::String(s)),
+        (ty, value) => not_constant(format!("{} value cannot be used as {}", value_type(&value), ty)),
+    }
+}
+
+/// 显式类型转换；浮点数转整数向零取整，超出范围时取最大或最小值
+fn cast_value(value: LiteralValue, ty: &Type) -> ConstResult<LiteralValue> {
+    use LiteralValue as V;
+    let integer = match to_number(&value) {
+        Some(Number::Int(v, _)) => Some(v),
+        Some(Number::Float(v, _)) => Some(v as i64),
+        None => None,
+    };
+    let float = to_number(&value).map(|number| float_operand(number).0);
+    let converted = match (ty, &value) {
+        (Type::Bool, V::Bool(b)) => Some(V::Bool(*b)),
+        (Type::String, V::String(s)) => Some(V::String(s.clone())),
+        (Type::Int32, V::Float32(v)) => Some(V::Int32(*v as i32)),
+        (Type::Int32, V::Float64(v)) => Some(V::Int32(*v as i32)),
+        (Type::Int32, _) => integer.map(|v| V::Int32(v as i32)),
+        (Type::Int64, _) => integer.map(V::Int64),
+        (Type::Float32, _) => float.map(|v| V::Float32(v as f32)),
+        (Type::Float64, _) => float.map(V::Float64),
+        (Type::Char, _) => match integer {
+            Some(v) => match char::from_u32(v as u32) {
+                Some(c) => Some(V::Char(c)),
+                None => return Err(ConstError::Failed(format!("{} is not a valid char", v))),
+            },
+            None => None,
+        },
+        _ => None,
+    };
+    match converted {
+        Some(converted) => Ok(converted),
+        None => not_constant(format!("cannot convert {} to {} in a constant expression", value_type(&value), ty)),
+    }
+}
+
+/// 数值运算的操作数（char 提升为 int）
+#[derive(Clone, Copy)]
+enum Number {
+    Int(i64, bool),  // 值, 是否为 long
+    Float(f64, bool),  // 值, 是否为 double
+}
+
+fn to_number(value: &LiteralValue) -> Option<Number> {
+    match value {
+        LiteralValue::Int32(v) => Some(Number::Int(*v as i64, false)),
+        LiteralValue::Int64(v) => Some(Number::Int(*v, true)),
+        LiteralValue::Char(c) => Some(Number::Int(*c as i64, false)),
+        LiteralValue::Float32(v) => Some(Number::Float(*v as f64, false)),
+        LiteralValue::Float64(v) => Some(Number::Float(*v, true)),
+        _ => None,
+    }
+}
+
+/// 字符串拼接时操作数的文本
+fn concat_text(value: &LiteralValue) -> ConstResult<String> {
+    match value {
+        LiteralValue::String(s) => Ok(s.clone()),
+        LiteralValue::Int32(v) => Ok(v.to_string()),
+        LiteralValue::Int64(v) => Ok(v.to_string()),
+        LiteralValue::Char(c) => Ok(c.to_string()),
+        LiteralValue::Bool(b) => Ok(b.to_string()),
+        // 浮点数转字符串由运行时格式化，不在编译期拼接
+        other => not_constant(format!("{} values are not concatenated at compile time", value_type(other))),
+    }
+}
+
+fn binary_op(op: BinaryOp, left: LiteralValue, right: LiteralValue) -> ConstResult<LiteralValue> {
+    use LiteralValue as V;
+    if op == BinaryOp::Add && (matches!(left, V::String(_)) || matches!(right, V::String(_))) {
+        return Ok(V::String(concat_text(&left)? + &concat_text(&right)?));
+    }
+    if let (V::Bool(a), V::Bool(b)) = (&left, &right) {
+        let (a, b) = (*a, *b);
+        return match op {
+            BinaryOp::Eq => Ok(V::Bool(a == b)),
+            BinaryOp::Ne => Ok(V::Bool(a != b)),
+            BinaryOp::And | BinaryOp::BitAnd => Ok(V::Bool(a & b)),
+            BinaryOp::Or | BinaryOp::BitOr => Ok(V::Bool(a | b)),
+            BinaryOp::BitXor => Ok(V::Bool(a ^ b)),
+            _ => not_constant(format!("operator {:?} cannot be applied to boolean values", op)),
+        };
+    }
+    let (a, b) = match (to_number(&left), to_number(&right)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return not_constant(format!("operator {:?} cannot be applied to {} and {} at compile time", op, value_type(&left), value_type(&right))),
+    };
+
+    match (a, b) {
+        (Number::Int(a, a_long), Number::Int(b, b_long)) => {
+            // 移位结果的类型由左操作数决定
+            let is_long = match op {
+                BinaryOp::Shl | BinaryOp::Shr | BinaryOp::UnsignedShr => a_long,
+                _ => a_long || b_long,
+            };
+            int_op(op, a, b, is_long)
+        }
+        (a, b) => {
+            let (a, a_double) = float_operand(a);
+            let (b, b_double) = float_operand(b);
+            float_op(op, a, b, a_double || b_double)
+        }
+    }
+}
+
+fn float_operand(number: Number) -> (f64, bool) {
+    match number {
+        Number::Int(v, _) => (v as f64, false),
+        Number::Float(v, is_double) => (v, is_double),
+    }
+}
+
+/// 整数运算：int 按 32 位回绕，移位次数取低 5 位（long 为 64 位和低 6 位）
+fn int_op(op: BinaryOp, a: i64, b: i64, is_long: bool) -> ConstResult<LiteralValue> {
+    let wrap = |value: i64| if is_long { LiteralValue::Int64(value) } else { LiteralValue::Int32(value as i32) };
+    let shift = if is_long { (b & 63) as u32 } else { (b & 31) as u32 };
+    let result = match op {
+        BinaryOp::Add => a.wrapping_add(b),
+        BinaryOp::Sub => a.wrapping_sub(b),
+        BinaryOp::Mul => a.wrapping_mul(b),
+        BinaryOp::Div | BinaryOp::Mod if b == 0 => {
+            return Err(ConstError::Failed("division by zero".to_string()));
+        }
+        BinaryOp::Div => a.wrapping_div(b),
+        BinaryOp::Mod => a.wrapping_rem(b),
+        BinaryOp::BitAnd => a & b,
+        BinaryOp::BitOr => a | b,
+        BinaryOp::BitXor => a ^ b,
+        BinaryOp::Shl => a.wrapping_shl(shift),
+        BinaryOp::Shr => a >> shift,
+        BinaryOp::UnsignedShr if is_long => ((a as u64) >> shift) as i64,
+        BinaryOp::UnsignedShr => ((a as u32) >> shift) as i64,
+        BinaryOp::Eq => return Ok(LiteralValue::Bool(a == b)),
+        BinaryOp::Ne => return Ok(LiteralValue::Bool(a != b)),
+        BinaryOp::Lt => return Ok(LiteralValue::Bool(a < b)),
+        BinaryOp::Le => return Ok(LiteralValue::Bool(a <= b)),
+        BinaryOp::Gt => return Ok(LiteralValue::Bool(a > b)),
+        BinaryOp::Ge => return Ok(LiteralValue::Bool(a >= b)),
+        BinaryOp::And | BinaryOp::Or => {
+            return not_constant(format!("operator {:?} requires boolean operands", op));
+        }
+    };
+    Ok(wrap(result))
+}
+
+fn float_op(op: BinaryOp, a: f64, b: f64, is_double: bool) -> ConstResult<LiteralValue> {
+    let result = match op {
+        BinaryOp::Add => a + b,
+        BinaryOp::Sub => a - b,
+        BinaryOp::Mul => a * b,
+        BinaryOp::Div => a / b,
+        BinaryOp::Mod => a % b,
+        BinaryOp::Eq => return Ok(LiteralValue::Bool(a == b)),
+        BinaryOp::Ne => return Ok(LiteralValue::Bool(a != b)),
+        BinaryOp::Lt => return Ok(LiteralValue::Bool(a < b)),
+        BinaryOp::Le => return Ok(LiteralValue::Bool(a <= b)),
+        BinaryOp::Gt => return Ok(LiteralValue::Bool(a > b)),
+        BinaryOp::Ge => return Ok(LiteralValue::Bool(a >= b)),
+        _ => return not_constant(format!("operator {:?} cannot be applied to floating-point values", op)),
+    };
+    if is_double {
+        Ok(LiteralValue::Float64(result))
+    } else {
+        Ok(LiteralValue::Float32(result as f32))
+    }
+}
+
+fn unary_op(op: UnaryOp, value: LiteralValue) -> ConstResult<LiteralValue> {
+    use LiteralValue as V;
+    match (op, value) {
+        (UnaryOp::Not, V::Bool(b)) => Ok(V::Bool(!b)),
+        (UnaryOp::Neg, V::Int32(v)) => Ok(V::Int32(v.wrapping_neg())),
+        (UnaryOp::Neg, V::Int64(v)) => Ok(V::Int64(v.wrapping_neg())),
+        (UnaryOp::Neg, V::Char(c)) => Ok(V::Int32(-(c as i32))),
+        (UnaryOp::Neg, V::Float32(v)) => Ok(V::Float32(-v)),
+        (UnaryOp::Neg, V::Float64(v)) => Ok(V::Float64(-v)),
+        (UnaryOp::BitNot, V::Int32(v)) => Ok(V::Int32(!v)),
+        (UnaryOp::BitNot, V::Int64(v)) => Ok(V::Int64(!v)),
+        (UnaryOp::BitNot, V::Char(c)) => Ok(V::Int32(!(c as i32))),
+        (op, value) => not_constant(format!("operator {:?} cannot be applied to {} at compile time", op, value_type(&value))),
+    }
+}
+
+fn expr_kind(expr: &Expr) -> &'static str {
+    match expr {
+        Expr::New(_) => "object creation",
+        Expr::ArrayCreation(_) | Expr::ArrayInit(_) => "array creation",
+        Expr::ArrayAccess(_) => "array access",
+        Expr::MethodRef(_) => "a method reference",
+        Expr::Lambda(_) => "a lambda",
+        Expr::InstanceOf(_) => "instanceof",
+        Expr::Alloc(_) | Expr::Dealloc(_) => "memory allocation",
+        Expr::Try(_) => "the '?' operator",
+        Expr::Switch(_) => "a switch expression",
+        Expr::SafeCall(_) => "a safe call",
+        Expr::Elvis(_) => "the elvis operator",
+        Expr::Call(_) => "a method call",
+        Expr::MemberAccess(_) => "member access",
+        Expr::Assignment(_) => "assignment",
+        _ => "this expression",
+    }
+}
+
+fn stmt_kind(stmt: &Stmt) -> &'static str {
+    match stmt {
+        Stmt::ForEach(_) => "a for-each loop",
+        Stmt::Switch(_) => "a switch statement",
+        Stmt::Scope(_) => "a scope block",
+        Stmt::Defer(_) => "defer",
+        Stmt::TryWithResources(_) => "try-with-resources",
+        Stmt::Break(_) | Stmt::Continue(_) => "a labeled break or continue",
+        Stmt::InlineIr(_) => "inline IR",
+        Stmt::Return(None) => "a return without a value",
+        _ => "this statement",
+    }
+}
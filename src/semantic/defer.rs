@@ -0,0 +1,96 @@
+//! defer 与 try-with-resources 检查
+//!
+//! 被延迟的语句会在代码块的每条退出路径上重新生成，因此它自身不能再用 `return`、
+//! `break` 或 `continue` 离开 defer；跳转只允许落在 defer 内部的循环或 switch 上。
+//! try-with-resources 的资源必须是带无参 `close()` 实例方法的类或接口类型。
+
+use crate::ast::*;
+use crate::types::Type;
+use crate::error::{cayResult, semantic_error, SourceLocation};
+use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
+
+impl SemanticAnalyzer {
+    /// 类型检查 defer 语句
+    pub(super) fn type_check_defer(&mut self, defer: &DeferStmt, expected_return: Option<&Type>) -> cayResult<()> {
+        if let Some(jump) = Self::find_escaping_jump(&defer.body, false, false, &mut Vec::new()) {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_DEFER_ERROR,
+                defer.loc.line,
+                defer.loc.column,
+                format!("'{}' cannot leave a defer statement; deferred code runs while the enclosing block is exiting", jump)
+            ));
+        }
+        self.type_check_branch(&defer.body, expected_return)
+    }
+
+    /// 类型检查 try-with-resources 语句，资源只在 try 代码块内可见
+    pub(super) fn type_check_try_with_resources(&mut self, try_stmt: &TryStmt, expected_return: Option<&Type>) -> cayResult<()> {
+        self.symbol_table.enter_scope();
+        for resource in &try_stmt.resources {
+            self.type_check_statement(&Stmt::VarDecl(resource.clone()), expected_return)?;
+            let resource_type = self.symbol_table.lookup_current(&resource.name).map(|s| s.symbol_type.clone());
+            if let Some(resource_type) = resource_type {
+                self.check_closeable(&resource.name, &resource_type, &resource.loc)?;
+            }
+        }
+        self.type_check_statement(&Stmt::Block(try_stmt.body.clone()), expected_return)?;
+        self.symbol_table.exit_scope();
+        Ok(())
+    }
+
+    /// 资源类型必须有无参的 close() 实例方法
+    fn check_closeable(&self, name: &str, resource_type: &Type, loc: &SourceLocation) -> cayResult<()> {
+        let close = match resource_type {
+            Type::Object(type_name) if self.type_registry.interface_exists(type_name) => {
+                self.type_registry.get_interface_method(type_name, "close")
+            }
+            Type::Object(type_name) => self.type_registry.find_method(type_name, "close", &[]),
+            _ => None,
+        };
+        match close {
+            Some(method) if !method.is_static && method.params.is_empty() => Ok(()),
+            _ => Err(semantic_error(
+                ErrorCodes::SEMANTIC_DEFER_ERROR,
+                loc.line,
+                loc.column,
+                format!("Resource '{}' of type {} must have a close() method to be used in try-with-resources", name, resource_type)
+            )),
+        }
+    }
+
+    /// 查找会跳出 defer 的 return、break 或 continue
+    ///
+    /// `in_loop` 和 `in_switch` 表示语句位于 defer 内部的循环或 switch 中，`labels` 是其中循环的标签。
+    fn find_escaping_jump(stmt: &Stmt, in_loop: bool, in_switch: bool, labels: &mut Vec<String>) -> Option<&'static str> {
+        match stmt {
+            Stmt::Return(_) => Some("return"),
+            Stmt::Break(None) if !in_loop && !in_switch => Some("break"),
+            Stmt::Continue(None) if !in_loop => Some("continue"),
+            Stmt::Break(Some(label)) if !labels.contains(label) => Some("break"),
+            Stmt::Continue(Some(label)) if !labels.contains(label) => Some("continue"),
+            Stmt::If(if_stmt) => Self::find_escaping_jump(&if_stmt.then_branch, in_loop, in_switch, labels)
+                .or_else(|| if_stmt.else_branch.as_ref()
+                    .and_then(|e| Self::find_escaping_jump(e, in_loop, in_switch, labels))),
+            Stmt::While(WhileStmt { body, label, .. })
+            | Stmt::For(ForStmt { body, label, .. })
+            | Stmt::ForEach(ForEachStmt { body, label, .. })
+            | Stmt::DoWhile(DoWhileStmt { body, label, .. }) => {
+                labels.extend(label.iter().cloned());
+                let jump = Self::find_escaping_jump(body, true, in_switch, labels);
+                if label.is_some() {
+                    labels.pop();
+                }
+                jump
+            }
+            Stmt::Switch(switch) => switch.cases.iter()
+                .flat_map(|case| case.body.iter())
+                .chain(switch.default.iter().flatten())
+                .find_map(|s| Self::find_escaping_jump(s, in_loop, true, labels)),
+            Stmt::Block(block) | Stmt::Scope(ScopeStmt { body: block, .. }) | Stmt::TryWithResources(TryStmt { body: block, .. }) => {
+                block.statements.iter().find_map(|s| Self::find_escaping_jump(s, in_loop, in_switch, labels))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,341 @@
+//! 枚举类型检查
+//!
+//! 枚举变体按声明顺序编号，下标即运行时标签值。无负载变体通过 `Color.Red`
+//! 访问，带负载变体通过 `Shape.Circle(1.0)` 构造。对枚举值的 `switch` 必须
+//! 覆盖所有变体（或提供 `default`），`case Rect(w, h):` 按顺序绑定负载字段。
+//!
+//! 内置泛型枚举 `Result<T, E>` 和 `Option<T>` 的变体可以省略枚举名书写为
+//! `Ok(x)`、`Err(e)`、`Some(x)` 和 `None`，类型实参由期望类型（变量声明、
+//! return、赋值和实参）确定。后缀 `?` 在 Err/None 时提前返回当前方法。
+
+use crate::ast::*;
+use crate::types::{Type, EnumInfo, EnumVariantInfo};
+use crate::error::{cayError, cayResult, semantic_error, SourceLocation};
+use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
+
+impl SemanticAnalyzer {
+    /// 收集枚举定义
+    pub fn collect_enums(&mut self, program: &Program) -> cayResult<()> {
+        for enum_decl in &program.enums {
+            let mut variants: Vec<EnumVariantInfo> = Vec::new();
+            for variant in &enum_decl.variants {
+                if variants.iter().any(|v| v.name == variant.name) {
+                    return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
+                        variant.loc.line,
+                        variant.loc.column,
+                        format!("Duplicate variant '{}' in enum '{}'", variant.name, enum_decl.name)
+                    ));
+                }
+                let mut fields = Vec::new();
+                for field in &variant.fields {
+                    if fields.iter().any(|f: &crate::types::ParameterInfo| f.name == field.name) {
+                        return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
+                            variant.loc.line,
+                            variant.loc.column,
+                            format!("Duplicate field '{}' in variant '{}.{}'", field.name, enum_decl.name, variant.name)
+                        ));
+                    }
+                    let mut field = field.clone();
+                    field.param_type = self.resolve_type(&field.param_type, variant.loc.line, variant.loc.column)?;
+                    fields.push(field);
+                }
+                variants.push(EnumVariantInfo {
+                    name: variant.name.clone(),
+                    fields,
+                });
+            }
+
+            self.type_registry.register_enum(EnumInfo {
+                name: enum_decl.name.clone(),
+                variants,
+                type_params: Vec::new(),
+            }).map_err(|_| semantic_error(
+                ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
+                enum_decl.loc.line,
+                enum_decl.loc.column,
+                format!("Enum '{}' already defined", enum_decl.name)
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// 如果表达式是未被局部变量遮蔽的枚举名，返回枚举名
+    pub fn as_enum_name(&self, expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Identifier(ident)
+                if self.symbol_table.lookup(&ident.name).is_none() && self.type_registry.enum_exists(&ident.name) =>
+            {
+                Some(ident.name.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// 推断无负载变体访问的类型: Color.Red
+    pub fn infer_enum_variant_access(&mut self, enum_name: &str, member: &MemberAccessExpr) -> cayResult<Type> {
+        let enum_info = self.type_registry.get_enum(enum_name).cloned()
+            .ok_or_else(|| semantic_error(ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER, member.loc.line, member.loc.column, format!("Unknown enum '{}'", enum_name)))?;
+        match enum_info.find_variant(&member.member) {
+            Some((_, variant)) if variant.fields.is_empty() => Ok(Type::Enum(enum_name.to_string())),
+            Some((_, variant)) => Err(semantic_error(
+                ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT,
+                member.loc.line,
+                member.loc.column,
+                format!("Variant '{}.{}' has {} field(s) and must be constructed with arguments", enum_name, variant.name, variant.fields.len())
+            )),
+            None => Err(semantic_error(
+                ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
+                member.loc.line,
+                member.loc.column,
+                format!("Enum '{}' has no variant '{}'", enum_name, member.member)
+            )),
+        }
+    }
+
+    /// 推断带负载变体构造的类型: Shape.Circle(1.0)
+    pub fn infer_enum_variant_construction(&mut self, enum_name: &str, member: &MemberAccessExpr, call: &CallExpr) -> cayResult<Type> {
+        let enum_info = self.type_registry.get_enum(enum_name).cloned()
+            .ok_or_else(|| semantic_error(ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER, call.loc.line, call.loc.column, format!("Unknown enum '{}'", enum_name)))?;
+        let variant = match enum_info.find_variant(&member.member) {
+            Some((_, variant)) => variant,
+            None => return Err(semantic_error(
+                ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
+                call.loc.line,
+                call.loc.column,
+                format!("Enum '{}' has no variant '{}'", enum_name, member.member)
+            )),
+        };
+        if let Err(error) = self.check_arguments_compatible(&call.args, &variant.fields, call.loc.line, call.loc.column) {
+            return Err(semantic_error(
+                error.code(),
+                call.loc.line,
+                call.loc.column,
+                format!("Variant '{}.{}' cannot be applied to given types: {}", enum_name, variant.name, argument_mismatch(&error))
+            ));
+        }
+        Ok(Type::Enum(enum_name.to_string()))
+    }
+
+    /// 省略枚举名的内置变体: (泛型枚举名, 变体名)
+    fn builtin_variant(name: &str, is_call: bool) -> Option<(&'static str, &'static str)> {
+        match (name, is_call) {
+            ("Ok", true) => Some(("Result", "Ok")),
+            ("Err", true) => Some(("Result", "Err")),
+            ("Some", true) => Some(("Option", "Some")),
+            ("None", false) => Some(("Option", "None")),
+            _ => None,
+        }
+    }
+
+    /// 识别省略枚举名的内置变体构造 Ok(x)、Err(e)、Some(x) 和 None
+    ///
+    /// 同名的局部变量、字段、方法和顶层函数优先。
+    fn as_builtin_variant<'e>(&self, expr: &'e Expr) -> Option<(&'static str, &'static str, &'e [Expr], &'e SourceLocation)> {
+        let (ident, args, loc) = match expr {
+            Expr::Identifier(ident) => (ident, &[] as &[Expr], &ident.loc),
+            Expr::Call(call) => match call.callee.as_ref() {
+                Expr::Identifier(ident) => (ident, call.args.as_slice(), &call.loc),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let (template, variant) = Self::builtin_variant(&ident.name, matches!(expr, Expr::Call(_)))?;
+        if self.symbol_table.lookup(&ident.name).is_some() {
+            return None;
+        }
+        let current_class = self.current_class.as_ref().and_then(|name| self.type_registry.get_class(name));
+        if current_class.is_some_and(|c| c.fields.contains_key(&ident.name) || c.methods.contains_key(&ident.name)) {
+            return None;
+        }
+        Some((template, variant, args, loc))
+    }
+
+    /// 如果类型是内置泛型枚举的实例，返回 (模板名, 类型实参)
+    fn builtin_enum_instance(&self, ty: &Type) -> Option<(String, Vec<Type>)> {
+        match ty {
+            Type::Enum(name) => {
+                let origin = self.type_registry.enum_origin(name)?;
+                let (_, args) = self.type_registry.generic_instances.get(name)?;
+                Some((origin.to_string(), args.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// 在已知期望类型的位置推断表达式类型（变量初始化、return、赋值和实参）
+    ///
+    /// 期望类型用于确定 Ok/Err/None 等内置变体的类型实参和 Lambda 未标注的参数类型，
+    /// 并传递给 switch 表达式的各个分支；其他表达式按常规推断。
+    pub fn infer_expr_type_expecting(&mut self, expr: &Expr, expected: &Type) -> cayResult<Type> {
+        if let Some(literal_type) = Self::infer_integer_literal_expecting(expr, expected) {
+            return Ok(literal_type);
+        }
+        if let Expr::Lambda(lambda) = expr {
+            return match self.resolve_type(expected, lambda.loc.line, lambda.loc.column)? {
+                Type::Function(func) => self.infer_lambda_type(lambda, Some(&func)),
+                _ => self.infer_lambda_type(lambda, None),
+            };
+        }
+        if let Expr::Switch(switch) = expr {
+            let expected = self.resolve_type(expected, switch.loc.line, switch.loc.column)?;
+            return self.infer_switch_expr_type(switch, Some(&expected));
+        }
+        match self.as_builtin_variant(expr) {
+            Some((template, variant, args, loc)) => {
+                let expected = self.resolve_type(expected, loc.line, loc.column)?;
+                self.infer_builtin_variant(template, variant, args, loc, Some(&expected))
+            }
+            None => self.infer_expr_type(expr),
+        }
+    }
+
+    /// 推断没有期望类型时的内置变体构造
+    pub fn infer_builtin_variant_expr(&mut self, expr: &Expr) -> Option<cayResult<Type>> {
+        let (template, variant, args, loc) = self.as_builtin_variant(expr)?;
+        Some(self.infer_builtin_variant(template, variant, args, loc, None))
+    }
+
+    /// 推断内置变体构造的类型并记录其具体枚举实例
+    ///
+    /// 期望类型是同一泛型枚举的实例时使用该实例；`Some(x)` 可以由负载类型推断；
+    /// 其余情况返回未实例化的枚举（如 `Result`），等待调用处以期望类型再次推断。
+    fn infer_builtin_variant(&mut self, template: &str, variant: &str, args: &[Expr], loc: &SourceLocation, expected: Option<&Type>) -> cayResult<Type> {
+        let arity = self.type_registry.generic_enum_templates.get(template)
+            .and_then(|info| info.find_variant(variant))
+            .map_or(0, |(_, v)| v.fields.len());
+        if args.len() != arity {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT,
+                loc.line,
+                loc.column,
+                format!("Variant '{}' expects {} argument(s), got {}", variant, arity, args.len())
+            ));
+        }
+
+        let expected_instance = match expected {
+            Some(Type::Enum(name)) if self.type_registry.enum_origin(name) == Some(template) => Some(name.clone()),
+            _ => None,
+        };
+        // 同一位置之前已经确定过实例时沿用该实例
+        let mut instance = expected_instance.or_else(|| self.type_registry.variant_constructions.get(loc).cloned());
+        if instance.is_none() && variant == "Some" {
+            let payload = self.infer_expr_type(&args[0])?;
+            if payload == Type::Void {
+                return Err(semantic_error(ErrorCodes::SEMANTIC_VOID_ASSIGNMENT, loc.line, loc.column, "Variant 'Some' cannot hold a void value".to_string()));
+            }
+            if let Type::Enum(name) = self.resolve_type(&Type::Generic(template.to_string(), vec![payload]), loc.line, loc.column)? {
+                instance = Some(name);
+            }
+        }
+
+        let enum_name = match instance {
+            Some(enum_name) => enum_name,
+            None => {
+                for arg in args {
+                    self.infer_expr_type(arg)?;
+                }
+                self.pending_variants.insert(loc.clone(), variant.to_string());
+                return Ok(Type::Enum(template.to_string()));
+            }
+        };
+
+        let fields = self.type_registry.get_enum(&enum_name)
+            .and_then(|info| info.find_variant(variant))
+            .map(|(_, v)| v.fields.clone())
+            .unwrap_or_default();
+        if let Err(error) = self.check_arguments_compatible(args, &fields, loc.line, loc.column) {
+            return Err(semantic_error(
+                error.code(),
+                loc.line,
+                loc.column,
+                format!("Variant '{}' cannot be applied to given types: {}", variant, argument_mismatch(&error))
+            ));
+        }
+        self.pending_variants.remove(loc);
+        self.type_registry.variant_constructions.insert(loc.clone(), enum_name.clone());
+        Ok(Type::Enum(enum_name))
+    }
+
+    /// 报告无法从上下文确定类型实参的内置变体构造
+    pub fn report_pending_variants(&mut self) {
+        let mut pending: Vec<(SourceLocation, String)> = self.pending_variants.drain().collect();
+        pending.sort_by_key(|(loc, _)| (loc.line, loc.column));
+        for (loc, variant) in pending {
+            let example = if variant == "None" {
+                "Option<int> x = None;".to_string()
+            } else {
+                format!("Result<int, String> r = {}(...);", variant)
+            };
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_TYPE_INFERENCE,
+                loc.line,
+                loc.column,
+                format!("Cannot infer type arguments for '{}'; declare the expected type, e.g. '{}'", variant, example),
+            ));
+        }
+    }
+
+    /// 推断错误传播表达式 `expr?` 的类型：Result<T, E> 和 Option<T> 解包为 T
+    ///
+    /// 当前方法必须返回同一种内置枚举，且 Result 的错误类型 E 相同，
+    /// 这样 Err/None 可以不经转换直接作为当前方法的返回值。
+    pub fn infer_try_type(&mut self, try_expr: &TryExpr) -> cayResult<Type> {
+        let loc = &try_expr.loc;
+        let operand_type = self.infer_expr_type(&try_expr.expr)?;
+        let (origin, args) = match self.builtin_enum_instance(&operand_type) {
+            Some(instance) => instance,
+            None => return Err(semantic_error(
+                ErrorCodes::SEMANTIC_TRY_OPERATOR,
+                loc.line,
+                loc.column,
+                format!("Operator '?' requires a Result or Option value, found {}", self.type_registry.display_type(&operand_type))
+            )),
+        };
+
+        let return_type = match &self.current_return_type {
+            Some(return_type) => return_type.clone(),
+            None => return Err(semantic_error(
+                ErrorCodes::SEMANTIC_TRY_OPERATOR,
+                loc.line,
+                loc.column,
+                "Operator '?' can only be used inside a method that returns Result or Option".to_string()
+            )),
+        };
+        match self.builtin_enum_instance(&return_type) {
+            Some((return_origin, return_args)) if return_origin == origin => {
+                if origin == "Result" && return_args[1] != args[1] {
+                    return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_TRY_OPERATOR,
+                        loc.line,
+                        loc.column,
+                        format!("Operator '?' cannot propagate error type {} from a method returning {}: error types must match",
+                            self.type_registry.display_type(&args[1]), self.type_registry.display_type(&return_type))
+                    ));
+                }
+            }
+            _ => return Err(semantic_error(
+                ErrorCodes::SEMANTIC_TRY_OPERATOR,
+                loc.line,
+                loc.column,
+                format!("Operator '?' on {} requires the enclosing method to return {}, but it returns {}",
+                    self.type_registry.display_type(&operand_type), origin, self.type_registry.display_type(&return_type))
+            )),
+        }
+
+        if let Type::Enum(operand_name) = &operand_type {
+            self.type_registry.try_operators.insert(loc.clone(), operand_name.clone());
+        }
+        Ok(args[0].clone())
+    }
+}
+
+/// 实参不匹配的原因（不含错误类别前缀）
+fn argument_mismatch(error: &cayError) -> String {
+    match error {
+        cayError::Semantic { message, .. } => message.clone(),
+        other => other.to_string(),
+    }
+}
@@ -4,11 +4,12 @@ use crate::ast::*;
 use crate::types::Type;
 use crate::error::{cayResult, semantic_error, semantic_error_with_file};
 use super::analyzer::SemanticAnalyzer;
-use super::symbol_table::SemanticSymbolInfo;
+use super::const_eval::ConstError;
+use crate::diagnostic::ErrorCodes;
 
 /// 辅助函数：根据SourceLocation创建语义错误
-fn semantic_error_at_loc(loc: &crate::error::SourceLocation, message: impl Into<String>) -> crate::error::cayError {
-    semantic_error_with_file(loc.file.clone(), loc.line, loc.column, message)
+fn semantic_error_at_loc(code: &'static str, loc: &crate::error::SourceLocation, message: impl Into<String>) -> crate::error::cayError {
+    semantic_error_with_file(code, loc.file.clone(), loc.line, loc.column, message)
 }
 
 impl SemanticAnalyzer {
@@ -34,15 +35,18 @@ impl SemanticAnalyzer {
                     // 检查是否在静态上下文中访问 this
                     if self.current_method_is_static {
                         return Err(crate::error::semantic_error(
+                            ErrorCodes::SEMANTIC_STATIC_CONTEXT,
                             loc.line, loc.column,
                             "non-static variable this cannot be referenced from a static context".to_string()
                         ));
                     }
-                    // 返回当前类类型
+                    // 返回当前类类型（在 Lambda 体中同时记录对 this 的捕获）
+                    self.lookup_local("this");
                     if let Some(current_class_name) = &self.current_class {
                         return Ok(Type::Object(current_class_name.clone()));
                     }
                     return Err(crate::error::semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_OPERATION,
                         loc.line, loc.column,
                         "this can only be used inside a class".to_string()
                     ));
@@ -53,6 +57,7 @@ impl SemanticAnalyzer {
                     // 检查是否在静态上下文中访问 super
                     if self.current_method_is_static {
                         return Err(crate::error::semantic_error(
+                            ErrorCodes::SEMANTIC_STATIC_CONTEXT,
                             loc.line, loc.column,
                             "non-static variable super cannot be referenced from a static context".to_string()
                         ));
@@ -66,6 +71,7 @@ impl SemanticAnalyzer {
                         }
                     }
                     return Err(crate::error::semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_OPERATION,
                         loc.line, loc.column,
                         "super can only be used in a class that extends another class".to_string()
                     ));
@@ -80,6 +86,7 @@ impl SemanticAnalyzer {
                             } else if self.current_method_is_static {
                                 // 静态方法中不能访问非静态字段
                                 return Err(crate::error::semantic_error(
+                                    ErrorCodes::SEMANTIC_STATIC_CONTEXT,
                                     loc.line, loc.column,
                                     format!("non-static variable {} cannot be referenced from a static context", name)
                                 ));
@@ -95,6 +102,7 @@ impl SemanticAnalyzer {
                                         return Ok(field_info.field_type.clone());
                                     } else if self.current_method_is_static {
                                         return Err(crate::error::semantic_error(
+                                            ErrorCodes::SEMANTIC_STATIC_CONTEXT,
                                             loc.line, loc.column,
                                             format!("non-static variable {} cannot be referenced from a static context", name)
                                         ));
@@ -106,10 +114,24 @@ impl SemanticAnalyzer {
                     }
                 }
                 
-                if let Some(info) = self.symbol_table.lookup(name) {
-                    Ok(info.symbol_type.clone())
-                } else if self.type_registry.class_exists(name) {
-                    // 标识符是类名，返回类类型（用于静态成员访问）
+                // 省略枚举名的内置变体: None
+                if let Some(result) = self.infer_builtin_variant_expr(expr) {
+                    return result;
+                }
+
+                if let Some(info) = self.lookup_local(name) {
+                    return Ok(info.symbol_type);
+                }
+                // 类体中直接用名称访问属性
+                if let Some(property_type) = self.infer_implicit_property_read(ident)? {
+                    return Ok(property_type);
+                }
+
+                if self.type_registry.enum_exists(name) {
+                    // 标识符是枚举名（用于 Color.Red 等变体访问）
+                    Ok(Type::Enum(name.clone()))
+                } else if self.type_registry.class_exists(name) || self.type_registry.interface_exists(name) {
+                    // 标识符是类名或接口名，返回类类型（用于静态成员访问）
                     Ok(Type::Object(name.clone()))
                 } else {
                     Err(crate::error::undefined_identifier_error(
@@ -119,28 +141,93 @@ impl SemanticAnalyzer {
             }
             Expr::Binary(bin) => self.infer_binary_type(bin),
             Expr::Unary(unary) => self.infer_unary_type(unary),
-            Expr::Call(call) => self.infer_call_type(call),
-            Expr::MemberAccess(member) => self.infer_member_access_type(member),
-            Expr::New(new_expr) => self.infer_new_type(new_expr),
-            Expr::Assignment(assign) => self.infer_assignment_type(assign),
+            // 省略枚举名的内置变体: Ok(x)、Err(e)、Some(x)
+            Expr::Call(call) => match self.infer_builtin_variant_expr(expr) {
+                Some(result) => result,
+                None => {
+                    let result = self.infer_call_type(call)?;
+                    if let Expr::MemberAccess(member) = call.callee.as_ref() {
+                        self.check_dereference(&member.object);
+                    }
+                    self.check_nullable_arguments(call);
+                    Ok(result)
+                }
+            },
+            Expr::MemberAccess(member) => {
+                let result = self.infer_member_access_type(member)?;
+                self.check_dereference(&member.object);
+                Ok(result)
+            }
+            Expr::New(new_expr) => {
+                let result = self.infer_new_type(new_expr)?;
+                self.check_nullable_constructor_arguments(new_expr);
+                Ok(result)
+            }
+            Expr::Assignment(assign) => {
+                let result = self.infer_assignment_type(assign)?;
+                self.check_nullable_assignment(assign, &result);
+                Ok(result)
+            }
             Expr::Cast(cast) => self.infer_cast_type(cast),
             Expr::ArrayCreation(arr) => self.infer_array_creation_type(arr),
             Expr::ArrayInit(init) => self.infer_array_init_type(init),
-            Expr::ArrayAccess(arr) => self.infer_array_access_type(arr),
+            Expr::ArrayAccess(arr) => {
+                let result = self.infer_array_access_type(arr)?;
+                self.check_dereference(&arr.array);
+                Ok(result)
+            }
             Expr::MethodRef(method_ref) => self.infer_method_ref_type(method_ref),
-            Expr::Lambda(lambda) => self.infer_lambda_type(lambda),
+            Expr::Lambda(lambda) => self.infer_lambda_type(lambda, None),
             Expr::Ternary(ternary) => self.infer_ternary_type(ternary),
             Expr::InstanceOf(instanceof) => self.infer_instanceof_type(instanceof),
-            Expr::Alloc(_) => Ok(Type::Int64),  // 0.5.0.0: alloc 返回 long (指针)
-            Expr::Dealloc(_) => Ok(Type::Void), // 0.5.0.0: dealloc 返回 void
+            Expr::Alloc(alloc) => self.infer_alloc_type(alloc),
+            Expr::Dealloc(dealloc) => self.infer_dealloc_type(dealloc),
+            Expr::Try(try_expr) => self.infer_try_type(try_expr),
+            Expr::Switch(switch) => self.infer_switch_expr_type(switch, None),
+            Expr::SafeCall(safe_call) => self.infer_safe_call_type(safe_call, false),
+            Expr::Elvis(elvis) => self.infer_elvis_type(elvis),
+        }
+    }
+
+    /// 0.5.0.0: __cay_alloc(size[, align]) 返回 long（指针），大小和对齐必须是整数
+    fn infer_alloc_type(&mut self, alloc: &AllocExpr) -> cayResult<Type> {
+        for operand in std::iter::once(&alloc.size).chain(alloc.align.as_ref()) {
+            let operand_type = self.infer_expr_type(operand)?;
+            if !operand_type.is_integer() {
+                return Err(semantic_error_at_loc(
+                    ErrorCodes::SEMANTIC_TYPE_MISMATCH,
+                    &alloc.loc,
+                    format!("__cay_alloc size and alignment must be integer, got {}", operand_type)
+                ));
+            }
+        }
+        Ok(Type::Int64)
+    }
+
+    /// 0.5.0.0: __cay_free(ptr) 返回 void，指针必须是 __cay_alloc 返回的 long
+    fn infer_dealloc_type(&mut self, dealloc: &DeallocExpr) -> cayResult<Type> {
+        let ptr_type = self.infer_expr_type(&dealloc.ptr)?;
+        if ptr_type != Type::Int64 {
+            return Err(semantic_error_at_loc(
+                ErrorCodes::SEMANTIC_TYPE_MISMATCH,
+                &dealloc.loc,
+                format!("__cay_free expects a long pointer, got {}", ptr_type)
+            ));
         }
+        Ok(Type::Void)
     }
 
     /// 推断二元表达式类型
     fn infer_binary_type(&mut self, bin: &BinaryExpr) -> cayResult<Type> {
         let left_type = self.infer_expr_type(&bin.left)?;
-        let right_type = self.infer_expr_type(&bin.right)?;
-        
+        let right_type = self.infer_logical_right_operand(bin)?;
+        if let Some(result_type) = self.infer_binary_operator_call(bin, &left_type, &right_type)? {
+            return Ok(result_type);
+        }
+        if let Some(result_type) = self.infer_integer_binary(bin, &left_type, &right_type)? {
+            return Ok(result_type);
+        }
+
         match bin.op {
             BinaryOp::Add => {
                 // 字符串连接：两个操作数都必须是字符串
@@ -161,6 +248,7 @@ impl SemanticAnalyzer {
                     Ok(self.promote_types(&left_type, &right_type))
                 } else {
                     Err(semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_OPERAND,
                         bin.loc.line,
                         bin.loc.column,
                         format!("Cannot add {} and {}: addition requires both operands to be numeric or both to be strings", left_type, right_type)
@@ -173,6 +261,7 @@ impl SemanticAnalyzer {
                     if matches!(bin.op, BinaryOp::Div | BinaryOp::Mod) {
                         if let Expr::Literal(LiteralValue::Int32(0)) = bin.right.as_ref() {
                             return Err(semantic_error(
+                                ErrorCodes::SEMANTIC_DIVISION_BY_ZERO,
                                 bin.loc.line,
                                 bin.loc.column,
                                 "/ by zero".to_string()
@@ -180,6 +269,7 @@ impl SemanticAnalyzer {
                         }
                         if let Expr::Literal(LiteralValue::Int64(0)) = bin.right.as_ref() {
                             return Err(semantic_error(
+                                ErrorCodes::SEMANTIC_DIVISION_BY_ZERO,
                                 bin.loc.line,
                                 bin.loc.column,
                                 "/ by zero".to_string()
@@ -190,6 +280,7 @@ impl SemanticAnalyzer {
                     Ok(self.promote_types(&left_type, &right_type))
                 } else {
                     Err(semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_OPERAND,
                         bin.loc.line,
                         bin.loc.column,
                         format!("Cannot apply {:?} to {} and {}: operator requires numeric operands", bin.op, left_type, right_type)
@@ -197,6 +288,14 @@ impl SemanticAnalyzer {
                 }
             }
             BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+                if matches!(left_type, Type::Struct(_)) || matches!(right_type, Type::Struct(_)) {
+                    return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_OPERAND,
+                        bin.loc.line,
+                        bin.loc.column,
+                        format!("Operator {:?} cannot be applied to {} and {}: compare struct fields instead", bin.op, left_type, right_type)
+                    ));
+                }
                 Ok(Type::Bool)
             }
             BinaryOp::And | BinaryOp::Or => {
@@ -204,6 +303,7 @@ impl SemanticAnalyzer {
                     Ok(Type::Bool)
                 } else {
                     Err(semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_OPERAND,
                         bin.loc.line,
                         bin.loc.column,
                         "Logical operators require boolean operands"
@@ -215,6 +315,7 @@ impl SemanticAnalyzer {
                     Ok(self.promote_integer_types(&left_type, &right_type))
                 } else {
                     Err(semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_OPERAND,
                         bin.loc.line,
                         bin.loc.column,
                         format!("Bitwise operator {:?} requires integer operands, got {} and {}",
@@ -228,6 +329,7 @@ impl SemanticAnalyzer {
                     Ok(self.promote_integer_types(&left_type, &right_type))
                 } else {
                     Err(semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_OPERAND,
                         bin.loc.line,
                         bin.loc.column,
                         format!("Shift operator {:?} requires integer operands, got {} and {}",
@@ -242,6 +344,12 @@ impl SemanticAnalyzer {
     /// 推断一元表达式类型
     fn infer_unary_type(&mut self, unary: &UnaryExpr) -> cayResult<Type> {
         let operand_type = self.infer_expr_type(&unary.operand)?;
+        if matches!(unary.op, UnaryOp::PreInc | UnaryOp::PreDec | UnaryOp::PostInc | UnaryOp::PostDec) {
+            self.check_property_update(unary)?;
+        }
+        if let Some(result_type) = self.infer_unary_operator_call(unary, &operand_type)? {
+            return Ok(result_type);
+        }
         match unary.op {
             UnaryOp::Neg => Ok(operand_type),
             UnaryOp::Not => {
@@ -249,6 +357,7 @@ impl SemanticAnalyzer {
                     Ok(Type::Bool)
                 } else {
                     Err(semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_OPERAND,
                         unary.loc.line,
                         unary.loc.column,
                         "Cannot apply '!' to non-boolean"
@@ -277,6 +386,7 @@ impl SemanticAnalyzer {
                     _ => {
                         // 对于其他类型，报错
                         Err(semantic_error(
+                            ErrorCodes::SEMANTIC_INVALID_OPERAND,
                             unary.loc.line,
                             unary.loc.column,
                             format!("Cannot dereference non-pointer type '{}'", operand_type)
@@ -289,6 +399,18 @@ impl SemanticAnalyzer {
     }
 
     /// 推断函数调用类型
+    /// 推断 print 的参数；`"n = " + n` 这样的拼接整体推断失败时分别推断两侧
+    fn infer_print_arg(&mut self, arg: &Expr) -> cayResult<()> {
+        match (self.infer_expr_type(arg), arg) {
+            (Err(err), _) if Self::is_package_access_error(&err) => Err(err),
+            (Err(_), Expr::Binary(bin)) => {
+                self.infer_print_arg(&bin.left)?;
+                self.infer_print_arg(&bin.right)
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn infer_call_type(&mut self, call: &CallExpr) -> cayResult<Type> {
         // 首先处理标识符调用（内置函数、extern函数、方法调用等）
         // 这需要在函数指针检查之前，因为函数指针变量也是标识符
@@ -296,7 +418,14 @@ impl SemanticAnalyzer {
         if let Expr::Identifier(name) = call.callee.as_ref() {
             // 内置输入函数的类型推断
             match name.as_str() {
-                "print" | "println" => return Ok(Type::Void),
+                "print" | "println" => {
+                    // print 接受任意类型参数，不检查参数；仍推断一遍以记录其中的泛型方法调用和属性访问，
+                    // 跨包访问不可见成员的错误照常报告
+                    for arg in &call.args {
+                        self.infer_print_arg(arg)?;
+                    }
+                    return Ok(Type::Void);
+                }
                 "readInt" => return Ok(Type::Int32),
                 "readLong" => return Ok(Type::Int64),
                 "readFloat" => return Ok(Type::Float32),
@@ -308,7 +437,7 @@ impl SemanticAnalyzer {
                 "__cay_read_ptr" => {
                     // 检查参数数量
                     if call.args.len() != 1 {
-                        return Err(semantic_error(call.loc.line, call.loc.column,
+                        return Err(semantic_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, call.loc.line, call.loc.column,
                             format!("Function '__cay_read_ptr' requires 1 argument, but got {}", call.args.len())));
                     }
                     return Ok(Type::Int64);
@@ -316,7 +445,7 @@ impl SemanticAnalyzer {
                 "__cay_ptr_to_string" => {
                     // 检查参数数量
                     if call.args.len() != 1 {
-                        return Err(semantic_error(call.loc.line, call.loc.column,
+                        return Err(semantic_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, call.loc.line, call.loc.column,
                             format!("Function '__cay_ptr_to_string' requires 1 argument, but got {}", call.args.len())));
                     }
                     return Ok(Type::String);
@@ -324,7 +453,7 @@ impl SemanticAnalyzer {
                 "__cay_write_ptr" => {
                     // 检查参数数量
                     if call.args.len() != 2 {
-                        return Err(semantic_error(call.loc.line, call.loc.column,
+                        return Err(semantic_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, call.loc.line, call.loc.column,
                             format!("Function '__cay_write_ptr' requires 2 arguments, but got {}", call.args.len())));
                     }
                     return Ok(Type::Void);
@@ -332,7 +461,7 @@ impl SemanticAnalyzer {
                 "__cay_write_int" => {
                     // 检查参数数量
                     if call.args.len() != 2 {
-                        return Err(semantic_error(call.loc.line, call.loc.column,
+                        return Err(semantic_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, call.loc.line, call.loc.column,
                             format!("Function '__cay_write_int' requires 2 arguments, but got {}", call.args.len())));
                     }
                     return Ok(Type::Void);
@@ -355,14 +484,14 @@ impl SemanticAnalyzer {
                             if has_varargs {
                                 // 可变参数函数：参数数量 >= 固定参数数量
                                 if call.args.len() < fixed_param_count {
-                                    return Err(semantic_error(call.loc.line, call.loc.column,
+                                    return Err(semantic_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, call.loc.line, call.loc.column,
                                         format!("Function '{}' requires at least {} arguments, but got {}",
                                             name, fixed_param_count, call.args.len())));
                                 }
                             } else {
                                 // 非可变参数函数：参数数量必须匹配
                                 if call.args.len() != extern_func.params.len() {
-                                    return Err(semantic_error(call.loc.line, call.loc.column,
+                                    return Err(semantic_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, call.loc.line, call.loc.column,
                                         format!("Function '{}' requires {} arguments, but got {}",
                                             name, extern_func.params.len(), call.args.len())));
                                 }
@@ -391,6 +520,7 @@ impl SemanticAnalyzer {
                     let arg_type = self.infer_expr_type(arg)?;
                     if !self.types_compatible(&arg_type, &param.param_type) {
                         return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH,
                             call.loc.line,
                             call.loc.column,
                             format!("Argument {} type mismatch: expected {}, got {}",
@@ -401,6 +531,11 @@ impl SemanticAnalyzer {
                 return Ok(return_type);
             }
 
+            // 接口的 default 和 static 方法体中调用同一接口的方法
+            if let Some(return_type) = self.infer_unqualified_interface_call(name.as_ref(), call)? {
+                return Ok(return_type);
+            }
+
             // 尝试查找当前类的方法（无对象调用）- 支持方法重载
             if let Some(ref current_class) = self.current_class.clone() {
                 // 先推断所有参数类型
@@ -410,13 +545,17 @@ impl SemanticAnalyzer {
                 }
 
                 // 使用参数类型查找匹配的方法
-                if let Some(method_info) = self.type_registry.find_method(current_class, name.as_ref(), &arg_types) {
+                if let Some(method_info) = self.type_registry.find_method(current_class, name.as_ref(), &arg_types).cloned() {
+                    self.check_member_access(&method_info.class_name, &method_info.name, method_info.is_public, method_info.is_protected, &call.loc)?;
+                    self.warn_if_deprecated_method(&method_info, &call.loc);
+                    // 泛型方法：推断类型实参后检查
+                    if !method_info.type_params.is_empty() {
+                        return self.instantiate_generic_method_call(&method_info, call, &arg_types);
+                    }
                     let return_type = method_info.return_type.clone();
                     let params = method_info.params.clone();
                     // 检查参数类型兼容性（支持可变参数）
-                    if let Err(msg) = self.check_arguments_compatible(&call.args, &params, call.loc.line, call.loc.column) {
-                        return Err(semantic_error(call.loc.line, call.loc.column, msg));
-                    }
+                    self.check_arguments_compatible(&call.args, &params, call.loc.line, call.loc.column)?;
 
                     return Ok(return_type);
                 }
@@ -434,15 +573,18 @@ impl SemanticAnalyzer {
 
             if let Some((params, return_type)) = top_level_func_info {
                 // 找到顶层函数，检查参数类型兼容性
-                if let Err(msg) = self.check_arguments_compatible(&call.args, &params, call.loc.line, call.loc.column) {
-                    return Err(semantic_error(call.loc.line, call.loc.column, msg));
-                }
+                self.check_arguments_compatible(&call.args, &params, call.loc.line, call.loc.column)?;
                 return Ok(return_type);
             }
         }
 
         // 支持成员调用: obj.method(...) 或 ClassName.method()（静态方法）
         if let Expr::MemberAccess(member) = call.callee.as_ref() {
+            // 枚举变体构造: Shape.Circle(1.0)
+            if let Some(enum_name) = self.as_enum_name(&member.object) {
+                return self.infer_enum_variant_construction(&enum_name, member, call);
+            }
+
             // 推断对象类型
             let obj_type = self.infer_expr_type(&member.object)?;
 
@@ -462,14 +604,20 @@ impl SemanticAnalyzer {
 
                 if let Some(class_info) = self.type_registry.get_class(&class_name_str) {
                     // 使用参数类型查找匹配的静态方法
-                    if let Some(method_info) = class_info.find_method(&member.member, &arg_types) {
+                    if let Some(method_info) = class_info.find_method(&member.member, &arg_types).cloned() {
+                        if method_info.is_static {
+                            self.check_type_access(&class_name_str, &call.loc)?;
+                            self.check_member_access(&method_info.class_name, &method_info.name, method_info.is_public, method_info.is_protected, &call.loc)?;
+                            self.warn_if_deprecated_method(&method_info, &call.loc);
+                        }
+                        if method_info.is_static && !method_info.type_params.is_empty() {
+                            return self.instantiate_generic_method_call(&method_info, call, &arg_types);
+                        }
                         if method_info.is_static {
                             let return_type = method_info.return_type.clone();
                             let params = method_info.params.clone();
                             // 检查参数类型兼容性（支持可变参数）
-                            if let Err(msg) = self.check_arguments_compatible(&call.args, &params, call.loc.line, call.loc.column) {
-                                return Err(semantic_error(call.loc.line, call.loc.column, msg));
-                            }
+                            self.check_arguments_compatible(&call.args, &params, call.loc.line, call.loc.column)?;
 
                             return Ok(return_type);
                         }
@@ -484,6 +632,22 @@ impl SemanticAnalyzer {
                 }
             }
 
+            // 处理类型参数上的方法调用（通过上界解析）
+            if let Type::TypeParam(param_name) = &obj_type {
+                return self.infer_type_param_method_call(param_name, member, call);
+            }
+
+            // 接口方法调用: Interface.staticMethod() 或 接口类型实例上的方法
+            let interface_name = match &obj_type {
+                Type::Object(name) if self.type_registry.interface_exists(name) => Some(name.clone()),
+                _ => None,
+            };
+            if let Some(interface_name) = interface_name {
+                let static_ref = matches!(member.object.as_ref(), Expr::Identifier(name)
+                    if name.as_ref() == interface_name.as_str() && self.lookup_local(name.as_ref()).is_none());
+                return self.infer_interface_method_call(&interface_name, &member.member, call, static_ref);
+            }
+
             // 处理类实例方法调用 - 支持方法重载
             if let Type::Object(class_name) = &obj_type {
                 // 先推断所有参数类型
@@ -503,6 +667,7 @@ impl SemanticAnalyzer {
                             // 检查参数数量
                             if call.args.len() != params.len() {
                                 return Err(semantic_error(
+                                    ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT,
                                     call.loc.line,
                                     call.loc.column,
                                     format!("Function pointer field '{}' requires {} arguments, but got {}",
@@ -514,6 +679,7 @@ impl SemanticAnalyzer {
                                 let arg_type = self.infer_expr_type(arg)?;
                                 if !self.types_compatible(&arg_type, expected_type) {
                                     return Err(semantic_error(
+                                        ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH,
                                         call.loc.line,
                                         call.loc.column,
                                         format!("Argument {} type mismatch: expected {}, got {}", i + 1, expected_type, arg_type)
@@ -526,17 +692,21 @@ impl SemanticAnalyzer {
                 }
 
                 // 使用参数类型查找匹配的方法
-                if let Some(method_info) = self.type_registry.find_method(class_name, &member.member, &arg_types) {
+                if let Some(method_info) = self.type_registry.find_method(class_name, &member.member, &arg_types).cloned() {
+                    self.check_member_access(&method_info.class_name, &method_info.name, method_info.is_public, method_info.is_protected, &call.loc)?;
+                    self.warn_if_deprecated_method(&method_info, &call.loc);
+                    if !method_info.type_params.is_empty() {
+                        return self.instantiate_generic_method_call(&method_info, call, &arg_types);
+                    }
                     let return_type = method_info.return_type.clone();
                     let params = method_info.params.clone();
                     // 检查参数类型兼容性（支持可变参数）
-                    if let Err(msg) = self.check_arguments_compatible(&call.args, &params, call.loc.line, call.loc.column) {
-                        return Err(semantic_error(call.loc.line, call.loc.column, msg));
-                    }
+                    self.check_arguments_compatible(&call.args, &params, call.loc.line, call.loc.column)?;
 
                     return Ok(return_type);
                 } else {
                     return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_METHOD_NOT_FOUND,
                         call.loc.line,
                         call.loc.column,
                         format!("Unknown method '{}' for class {}", member.member, class_name)
@@ -548,7 +718,7 @@ impl SemanticAnalyzer {
         // 检查标识符是否是函数指针变量
         if let Expr::Identifier(name) = call.callee.as_ref() {
             // 首先检查是否是函数指针变量 - 先收集类型信息避免借用冲突
-            let func_ptr_info = self.symbol_table.lookup(name.as_ref()).and_then(|info| {
+            let func_ptr_info = self.lookup_local(name.as_ref()).and_then(|info| {
                 if let Type::Function(func_type) = &info.symbol_type {
                     Some((func_type.params.clone(), *func_type.return_type.clone()))
                 } else {
@@ -562,6 +732,7 @@ impl SemanticAnalyzer {
                 let actual_args = call.args.len();
                 if actual_args != expected_args {
                     return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT,
                         call.loc.line,
                         call.loc.column,
                         format!("Function pointer call requires {} arguments, but got {}", expected_args, actual_args)
@@ -572,6 +743,7 @@ impl SemanticAnalyzer {
                     let arg_type = self.infer_expr_type(arg)?;
                     if !self.types_compatible(&arg_type, expected_type) {
                         return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH,
                             call.loc.line,
                             call.loc.column,
                             format!("Argument {} type mismatch: expected {}, got {}", i + 1, expected_type, arg_type)
@@ -586,6 +758,7 @@ impl SemanticAnalyzer {
                 if let Some(class_info) = self.type_registry.get_class(current_class) {
                     if class_info.methods.contains_key(name.as_ref()) {
                         return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH,
                             call.loc.line,
                             call.loc.column,
                             format!("Method '{}' in class '{}' cannot be applied to given types: argument mismatch", name, current_class)
@@ -594,6 +767,7 @@ impl SemanticAnalyzer {
                 }
             }
             return Err(semantic_error(
+                ErrorCodes::SEMANTIC_METHOD_NOT_FOUND,
                 call.loc.line,
                 call.loc.column,
                 format!("Cannot find method '{}'", name)
@@ -603,6 +777,7 @@ impl SemanticAnalyzer {
         if let Expr::MemberAccess(member) = call.callee.as_ref() {
             if let Expr::Identifier(class_name) = &*member.object {
                 return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH,
                     call.loc.line,
                     call.loc.column,
                     format!("Method '{}' in class '{}' cannot be applied to given types: argument mismatch", member.member, class_name)
@@ -610,6 +785,7 @@ impl SemanticAnalyzer {
             }
             if let Type::Object(class_name) = self.infer_expr_type(&member.object)? {
                 return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH,
                     call.loc.line,
                     call.loc.column,
                     format!("Method '{}' in class '{}' cannot be applied to given types: argument mismatch", member.member, class_name)
@@ -626,6 +802,7 @@ impl SemanticAnalyzer {
             let actual_args = call.args.len();
             if actual_args != expected_args {
                 return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT,
                     call.loc.line,
                     call.loc.column,
                     format!("Function pointer call requires {} arguments, but got {}", expected_args, actual_args)
@@ -636,6 +813,7 @@ impl SemanticAnalyzer {
                 let arg_type = self.infer_expr_type(arg)?;
                 if !self.types_compatible(&arg_type, expected_type) {
                     return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH,
                         call.loc.line,
                         call.loc.column,
                         format!("Argument {} type mismatch: expected {}, got {}", i + 1, expected_type, arg_type)
@@ -646,6 +824,7 @@ impl SemanticAnalyzer {
         }
 
         Err(semantic_error(
+            ErrorCodes::SEMANTIC_METHOD_NOT_FOUND,
             call.loc.line,
             call.loc.column,
             "Cannot resolve method call".to_string()
@@ -654,31 +833,35 @@ impl SemanticAnalyzer {
 
     /// 推断成员访问类型
     fn infer_member_access_type(&mut self, member: &MemberAccessExpr) -> cayResult<Type> {
+        // 枚举变体访问: Color.Red
+        if let Some(enum_name) = self.as_enum_name(&member.object) {
+            return self.infer_enum_variant_access(&enum_name, member);
+        }
+
         // 检查是否是静态字段或方法访问: ClassName.fieldName 或 ClassName.methodName
         if let Expr::Identifier(class_name) = &*member.object {
+            if self.type_registry.get_class(class_name.as_ref()).is_some() && self.symbol_table.lookup(class_name.as_ref()).is_none() {
+                if let Some(property_type) = self.infer_property_read(class_name.as_ref(), member, true)? {
+                    return Ok(property_type);
+                }
+            }
             if let Some(class_info) = self.type_registry.get_class(class_name.as_ref()) {
                 // 首先检查字段
-                if let Some(field_info) = class_info.fields.get(&member.member) {
+                if let Some(field_info) = class_info.fields.get(&member.member).cloned() {
                     if field_info.is_static {
+                        self.check_type_access(class_name.as_ref(), &member.loc)?;
+                        self.check_member_access(class_name.as_ref(), &member.member, field_info.is_public, field_info.is_protected, &member.loc)?;
                         // 检查私有字段访问权限
-                        if !field_info.is_public {
-                            if let Some(current_class) = &self.current_class {
-                                if current_class != class_name.as_ref() {
-                                    return Err(semantic_error(
-                                        member.loc.line,
-                                        member.loc.column,
-                                        format!("{} has private access in {}", member.member, class_name)
-                                    ));
-                                }
-                            } else {
-                                return Err(semantic_error(
-                                    member.loc.line,
-                                    member.loc.column,
-                                    format!("{} has private access in {}", member.member, class_name)
-                                ));
-                            }
+                        if !field_info.is_public && !self.can_access_private(class_name.as_ref()) {
+                            return Err(semantic_error(
+                                ErrorCodes::SEMANTIC_ACCESS_VIOLATION,
+                                member.loc.line,
+                                member.loc.column,
+                                format!("{} has private access in {}", member.member, class_name)
+                            ));
                         }
-                        return Ok(field_info.field_type.clone());
+                        self.warn_if_deprecated(class_name.as_ref(), &format!("{}.{}", class_name, member.member), &field_info.annotations, &member.loc);
+                        return Ok(field_info.field_type);
                     }
                 }
                 
@@ -688,22 +871,13 @@ impl SemanticAnalyzer {
                     // 查找第一个静态方法（假设没有重载的静态方法）
                     if let Some(method_info) = methods.iter().find(|m| m.is_static) {
                         // 检查私有方法访问权限
-                        if !method_info.is_public {
-                            if let Some(current_class) = &self.current_class {
-                                if current_class != class_name.as_ref() {
-                                    return Err(semantic_error(
-                                        member.loc.line,
-                                        member.loc.column,
-                                        format!("{} has private access in {}", member.member, class_name)
-                                    ));
-                                }
-                            } else {
-                                return Err(semantic_error(
-                                    member.loc.line,
-                                    member.loc.column,
-                                    format!("{} has private access in {}", member.member, class_name)
-                                ));
-                            }
+                        if !method_info.is_public && !self.can_access_private(class_name.as_ref()) {
+                            return Err(semantic_error(
+                                ErrorCodes::SEMANTIC_ACCESS_VIOLATION,
+                                member.loc.line,
+                                member.loc.column,
+                                format!("{} has private access in {}", member.member, class_name)
+                            ));
                         }
                         // 返回函数指针类型
                         let param_types = method_info.params.iter()
@@ -721,8 +895,9 @@ impl SemanticAnalyzer {
             }
         }
 
-        // 成员访问类型检查
+        // 成员访问类型检查（类型参数按上界访问成员）
         let obj_type = self.infer_expr_type(&member.object)?;
+        let obj_type = self.erase_type_param(obj_type);
 
         // 特殊处理数组的 .length 属性
         if member.member == "length" {
@@ -745,6 +920,7 @@ impl SemanticAnalyzer {
             if let Expr::Identifier(name) = &*member.object {
                 if name == "this" {
                     return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_STATIC_CONTEXT,
                         member.loc.line,
                         member.loc.column,
                         format!("non-static variable {} cannot be referenced from a static context", member.member)
@@ -753,16 +929,25 @@ impl SemanticAnalyzer {
             }
         }
 
-        // 类成员访问
+        // 结构体字段访问
+        if let Type::Struct(struct_name) = &obj_type {
+            return self.infer_struct_field_access(struct_name, member);
+        }
+
+        // 类成员访问（属性优先于同名的自动属性字段）
         if let Type::Object(class_name) = obj_type {
+            if let Some(property_type) = self.infer_property_read(&class_name, member, false)? {
+                return Ok(property_type);
+            }
             if let Some(class_info) = self.type_registry.get_class(&class_name) {
-                if let Some(field_info) = class_info.fields.get(&member.member) {
+                if let Some(field_info) = class_info.fields.get(&member.member).cloned() {
                     // 检查静态方法中是否访问非静态字段
                     if self.current_method_is_static && !field_info.is_static {
                         // 检查是否是当前类的实例字段
                         if let Some(current_class) = &self.current_class {
                             if current_class == &class_name {
                                 return Err(semantic_error(
+                                    ErrorCodes::SEMANTIC_STATIC_CONTEXT,
                                     member.loc.line,
                                     member.loc.column,
                                     format!("non-static variable {} cannot be referenced from a static context", member.member)
@@ -771,28 +956,23 @@ impl SemanticAnalyzer {
                         }
                     }
                     
-                    // 检查私有字段访问权限
-                    if !field_info.is_public {
-                        if let Some(current_class) = &self.current_class {
-                            if current_class != &class_name {
-                                return Err(semantic_error(
-                                    member.loc.line,
-                                    member.loc.column,
-                                    format!("{} has private access in {}", member.member, class_name)
-                                ));
-                            }
-                        } else {
-                            return Err(semantic_error(
-                                member.loc.line,
-                                member.loc.column,
-                                format!("{} has private access in {}", member.member, class_name)
-                            ));
-                        }
+                    // 跨包访问先按包可见性检查，包内再检查私有字段访问权限
+                    // （泛型实例与其模板、同一最外层类中的嵌套类视为同一个类）
+                    self.check_member_access(&class_name, &member.member, field_info.is_public, field_info.is_protected, &member.loc)?;
+                    if !field_info.is_public && !self.can_access_private(&class_name) {
+                        return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_ACCESS_VIOLATION,
+                            member.loc.line,
+                            member.loc.column,
+                            format!("{} has private access in {}", member.member, class_name)
+                        ));
                     }
-                    return Ok(field_info.field_type.clone());
+                    self.warn_if_deprecated(&class_name, &format!("{}.{}", class_name, member.member), &field_info.annotations, &member.loc);
+                    return Ok(field_info.field_type);
                 }
             }
             return Err(semantic_error(
+                ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
                 member.loc.line,
                 member.loc.column,
                 format!("Unknown member '{}' for class {}", member.member, class_name)
@@ -800,6 +980,7 @@ impl SemanticAnalyzer {
         }
 
         Err(semantic_error(
+            ErrorCodes::SEMANTIC_INVALID_OPERATION,
             member.loc.line,
             member.loc.column,
             format!("Cannot access member '{}' on type {}", member.member, obj_type)
@@ -808,18 +989,49 @@ impl SemanticAnalyzer {
 
     /// 推断 new 表达式类型
     fn infer_new_type(&mut self, new_expr: &NewExpr) -> cayResult<Type> {
-        if let Some(class_info) = self.type_registry.get_class(&new_expr.class_name) {
+        // 结构体值: new Point(1.0, 2.0)
+        if self.type_registry.get_struct(&new_expr.class_name).is_some() {
+            return self.infer_struct_construction(new_expr);
+        }
+
+        // 泛型类实例化: new Box<int>() -> Box$i32
+        let class_name = if new_expr.type_args.is_empty() {
+            if self.type_registry.generic_templates.contains_key(&new_expr.class_name) {
+                return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_GENERIC_ERROR,
+                    new_expr.loc.line,
+                    new_expr.loc.column,
+                    format!("Generic class '{}' requires type arguments", new_expr.class_name)
+                ));
+            }
+            new_expr.class_name.clone()
+        } else {
+            let generic = Type::Generic(new_expr.class_name.clone(), new_expr.type_args.clone());
+            match self.resolve_type(&generic, new_expr.loc.line, new_expr.loc.column)? {
+                Type::Object(name) => name,
+                other => other.to_string(),
+            }
+        };
+        if let Some(class_info) = self.type_registry.get_class(&class_name) {
             // 检查是否是抽象类
             if class_info.is_abstract {
                 return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_ABSTRACT_CLASS_INSTANCE,
                     new_expr.loc.line,
                     new_expr.loc.column,
                     format!("Cannot instantiate abstract class '{}'", new_expr.class_name)
                 ));
             }
-            Ok(Type::Object(new_expr.class_name.clone()))
+            self.check_type_access(&class_name, &new_expr.loc)?;
+            // 跨包创建对象至少需要一个 public 构造函数（没有声明构造函数时使用默认构造函数）
+            if !class_info.constructors.is_empty() && !class_info.constructors.iter().any(|ctor| ctor.is_public) {
+                self.check_member_access(&class_name, &format!("{}()", new_expr.class_name), false, false, &new_expr.loc)?;
+            }
+            self.warn_if_deprecated_class(&class_name, &new_expr.loc);
+            Ok(Type::Object(class_name))
         } else {
             Err(semantic_error(
+                ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
                 new_expr.loc.line,
                 new_expr.loc.column,
                 format!("Unknown class: {}", new_expr.class_name)
@@ -829,11 +1041,12 @@ impl SemanticAnalyzer {
 
     /// 推断赋值表达式类型
     fn infer_assignment_type(&mut self, assign: &AssignmentExpr) -> cayResult<Type> {
-        // 检查是否是 final 变量重新赋值
+        // 检查是否是 final 变量重新赋值；声明时没有初始值的 final 局部变量由控制流分析检查只赋值一次
         if let Expr::Identifier(name) = &assign.target.as_ref() {
             if let Some(info) = self.symbol_table.lookup(name.as_ref()) {
-                if info.is_final {
+                if info.is_final && info.is_initialized {
                     return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_FINAL_REASSIGNMENT,
                         assign.loc.line,
                         assign.loc.column,
                         format!("Cannot assign a value to final variable '{}'", name)
@@ -842,20 +1055,82 @@ impl SemanticAnalyzer {
             }
         }
 
-        let target_type = self.infer_expr_type(&assign.target)?;
-        let value_type = self.infer_expr_type(&assign.value)?;
+        if let Some(value_type) = self.infer_index_assignment_call(assign)? {
+            return Ok(value_type);
+        }
+
+        // 属性赋值按 setter 检查，其余目标按变量、字段检查
+        let target_type = match self.infer_property_assignment(assign)? {
+            Some(property_type) => property_type,
+            None => {
+                let target_type = self.infer_expr_type(&assign.target)?;
+                if let Expr::Identifier(name) = assign.target.as_ref() {
+                    self.check_captured_assignment(name.as_ref(), &assign.loc)?;
+                }
+                self.check_struct_field_assignment(&assign.target, &assign.loc)?;
+                self.check_final_field_assignment(&assign.target, &assign.loc)?;
+                target_type
+            }
+        };
+        let value_type = if assign.op == AssignOp::Assign {
+            self.infer_expr_type_expecting(&assign.value, &target_type)?
+        } else {
+            let value_type = self.infer_expr_type(&assign.value)?;
+            // byte、uint 等的复合赋值隐含转换回目标类型: b += 1
+            if target_type.is_narrow_or_unsigned() && value_type.is_integer() {
+                target_type.clone()
+            } else {
+                value_type
+            }
+        };
 
         if self.types_compatible(&value_type, &target_type) {
             Ok(target_type)
         } else {
             Err(semantic_error(
+                ErrorCodes::SEMANTIC_TYPE_MISMATCH,
                 assign.loc.line,
                 assign.loc.column,
-                format!("Cannot assign {} to {}", value_type, target_type)
+                Self::integer_literal_out_of_range(&assign.value, &target_type)
+                    .unwrap_or_else(|| format!("Cannot assign {} to {}", value_type, target_type))
             ))
         }
     }
 
+    /// final 实例字段只能在本类的构造函数和实例初始化块中赋值
+    fn check_final_field_assignment(&mut self, target: &Expr, loc: &crate::error::SourceLocation) -> cayResult<()> {
+        let (class_name, field_name) = match target {
+            Expr::MemberAccess(member) => match member.object.as_ref() {
+                Expr::Identifier(object) => match self.symbol_table.lookup(object.as_ref()).map(|info| info.symbol_type.clone()) {
+                    Some(Type::Object(class_name)) => (class_name, member.member.clone()),
+                    _ => return Ok(()),
+                },
+                _ => return Ok(()),
+            },
+            // 不是局部变量的标识符是当前类的字段
+            Expr::Identifier(name) if self.symbol_table.lookup(name.as_ref()).is_none() => match &self.current_class {
+                Some(class_name) => (class_name.clone(), name.as_ref().to_string()),
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+
+        let is_final_field = self.type_registry.get_class(&class_name)
+            .and_then(|class_info| class_info.fields.get(&field_name))
+            .is_some_and(|field| field.is_final && !field.is_static);
+        let in_own_constructor = self.current_method_is_constructor
+            && self.current_class.as_deref() == Some(class_name.as_str());
+        if is_final_field && !in_own_constructor {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_FINAL_REASSIGNMENT,
+                loc.line,
+                loc.column,
+                format!("Cannot assign a value to final field '{}'", field_name)
+            ));
+        }
+        Ok(())
+    }
+
     /// 推断类型转换表达式类型
     ///
     /// 验证类型转换的合法性并返回目标类型。
@@ -879,7 +1154,7 @@ impl SemanticAnalyzer {
     /// 6. 其他组合：非法转换
     fn infer_cast_type(&mut self, cast: &CastExpr) -> cayResult<Type> {
         let source_type = self.infer_expr_type(&cast.expr)?;
-        let target_type = &cast.target_type;
+        let target_type = &self.resolve_type(&cast.target_type, cast.loc.line, cast.loc.column)?;
         
         // 相同类型，无需转换
         if source_type == *target_type {
@@ -891,6 +1166,7 @@ impl SemanticAnalyzer {
             Ok(target_type.clone())
         } else {
             Err(semantic_error_at_loc(
+                ErrorCodes::SEMANTIC_INVALID_CAST,
                 &cast.loc,
                 format!("Invalid cast from {} to {}", source_type, target_type)
             ))
@@ -926,6 +1202,11 @@ impl SemanticAnalyzer {
             (Type::Float64, Type::Int64) |
             (Type::Float64, Type::Float32) => true,
 
+            // byte/short/uint/ulong 与其他数值类型之间的转换（截断、扩展或改变符号）
+            (a, b) if (a.is_narrow_or_unsigned() || b.is_narrow_or_unsigned())
+                && Self::is_numeric_type_helper(a) && Self::is_numeric_type_helper(b) => true,
+            (a, Type::String) if a.is_narrow_or_unsigned() => true,
+
             // char 与数值类型之间的转换
             (Type::Char, Type::Int32) |
             (Type::Char, Type::Int64) |
@@ -1036,61 +1317,18 @@ impl SemanticAnalyzer {
         }
         
         // 检查 type_a 是否是 type_b 的子类型
-        if self.is_subtype_of_by_name(type_a, type_b) {
+        if self.is_subtype_of(type_a, type_b) {
             return true;
         }
         
         // 检查 type_b 是否是 type_a 的子类型
-        if self.is_subtype_of_by_name(type_b, type_a) {
+        if self.is_subtype_of(type_b, type_a) {
             return true;
         }
         
         false
     }
     
-    /// 通过类型名称检查子类型关系
-    ///
-    /// 辅助函数，用于检查一个类型是否是另一个类型的子类型
-    fn is_subtype_of_by_name(&self, subtype: &str, supertype: &str) -> bool {
-        // 相同类型
-        if subtype == supertype {
-            return true;
-        }
-        
-        // 所有类都是 Object 的子类型
-        if supertype == "Object" {
-            return self.type_registry.class_exists(subtype)
-                || subtype == "String"
-                || subtype == "Function";
-        }
-        
-        // 迭代遍历继承链
-        let mut current = subtype.to_string();
-        let mut visited = std::collections::HashSet::new();
-        
-        loop {
-            // 防止循环继承导致的无限循环
-            if !visited.insert(current.clone()) {
-                return false;
-            }
-            
-            if let Some(class_info) = self.type_registry.get_class(&current) {
-                match &class_info.parent {
-                    Some(parent) => {
-                        if parent == supertype {
-                            return true;
-                        }
-                        current = parent.clone();
-                    }
-                    None => return false,
-                }
-            } else {
-                // 内置类型检查
-                return (subtype == "String" || subtype == "Function") && supertype == "Object";
-            }
-        }
-    }
-
     /// 推断数组创建表达式类型
     fn infer_array_creation_type(&mut self, arr: &ArrayCreationExpr) -> cayResult<Type> {
         // 数组创建: new Type[size] 或 new Type[size1][size2]... 或 new Type[size][] (不规则数组)
@@ -1104,52 +1342,43 @@ impl SemanticAnalyzer {
             let size_type = self.infer_expr_type(size)?;
             if !size_type.is_integer() {
                 return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_INVALID_ARRAY_SIZE,
                     arr.loc.line,
                     arr.loc.column,
                     format!("Array size at dimension {} must be integer, got {}", i + 1, size_type)
                 ));
             }
-            // 检查负数数组大小（仅当大小是字面量或一元负号表达式时）
-            // 支持直接负数字面量如 -5（被解析为 Unary(Neg, Literal(5))）
-            if let Expr::Literal(LiteralValue::Int32(n)) = size {
-                if *n < 0 {
+            // 长度是常量表达式时检查是否为负数
+            match self.evaluate_const(size) {
+                Ok(LiteralValue::Int32(n)) if n < 0 => {
                     return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_ARRAY_SIZE,
                         arr.loc.line,
                         arr.loc.column,
                         format!("Array size cannot be negative: {}", n)
                     ));
                 }
-            }
-            if let Expr::Literal(LiteralValue::Int64(n)) = size {
-                if *n < 0 {
+                Ok(LiteralValue::Int64(n)) if n < 0 => {
                     return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_ARRAY_SIZE,
                         arr.loc.line,
                         arr.loc.column,
                         format!("Array size cannot be negative: {}", n)
                     ));
                 }
-            }
-            // 检查一元负号表达式如 -5
-            if let Expr::Unary(unary) = size {
-                if let UnaryOp::Neg = unary.op {
-                    if let Expr::Literal(LiteralValue::Int32(n)) = unary.operand.as_ref() {
-                        return Err(semantic_error(
-                            arr.loc.line,
-                            arr.loc.column,
-                            format!("Array size cannot be negative: -{}", n)
-                        ));
-                    }
-                    if let Expr::Literal(LiteralValue::Int64(n)) = unary.operand.as_ref() {
-                        return Err(semantic_error(
-                            arr.loc.line,
-                            arr.loc.column,
-                            format!("Array size cannot be negative: -{}", n)
-                        ));
-                    }
+                Err(ConstError::Failed(message)) => {
+                    return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_INVALID_ARRAY_SIZE,
+                        arr.loc.line,
+                        arr.loc.column,
+                        format!("Array size at dimension {} cannot be evaluated: {}", i + 1, message)
+                    ));
                 }
+                _ => {}
             }
         }
-        Ok(Type::Array(Box::new(arr.element_type.clone())))
+        let element_type = self.resolve_type(&arr.element_type, arr.loc.line, arr.loc.column)?;
+        Ok(Type::Array(Box::new(element_type)))
     }
 
     /// 推断数组初始化表达式类型
@@ -1159,6 +1388,7 @@ impl SemanticAnalyzer {
         // 实际类型会在变量声明时根据声明类型确定
         if init.elements.is_empty() {
             return Err(semantic_error(
+                ErrorCodes::SEMANTIC_TYPE_INFERENCE,
                 init.loc.line,
                 init.loc.column,
                 "Cannot infer type of empty array initializer".to_string()
@@ -1174,9 +1404,13 @@ impl SemanticAnalyzer {
         // 数组访问: arr[index]
         let array_type = self.infer_expr_type(&arr.array)?;
         let index_type = self.infer_expr_type(&arr.index)?;
+        if let Some(result_type) = self.infer_index_operator_call(arr, &array_type, &index_type)? {
+            return Ok(result_type);
+        }
 
         if !index_type.is_integer() {
             return Err(semantic_error_at_loc(
+                ErrorCodes::SEMANTIC_ARRAY_INDEX_TYPE,
                 &arr.loc,
                 format!("Array index must be integer, got {}", index_type)
             ));
@@ -1185,6 +1419,7 @@ impl SemanticAnalyzer {
         match array_type {
             Type::Array(element_type) => Ok(*element_type),
             _ => Err(semantic_error_at_loc(
+                ErrorCodes::SEMANTIC_INVALID_OPERAND,
                 &arr.loc,
                 format!("Cannot index non-array type {}", array_type)
             )),
@@ -1200,6 +1435,7 @@ impl SemanticAnalyzer {
             // 检查类是否存在
             if !self.type_registry.class_exists(class_name) {
                 return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
                     method_ref.loc.line,
                     method_ref.loc.column,
                     format!("Unknown class: {}", class_name)
@@ -1223,6 +1459,7 @@ impl SemanticAnalyzer {
                     }
                 } else {
                     return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_METHOD_NOT_FOUND,
                         method_ref.loc.line,
                         method_ref.loc.column,
                         format!("Unknown method '{}' for class {}", method_ref.method_name, class_name)
@@ -1256,62 +1493,6 @@ impl SemanticAnalyzer {
         Ok(Type::Object("Function".to_string()))
     }
 
-    /// 推断 Lambda 表达式类型
-    fn infer_lambda_type(&mut self, lambda: &LambdaExpr) -> cayResult<Type> {
-        // Lambda 表达式: (params) -> { body }
-        // 创建新的作用域
-        self.symbol_table.enter_scope();
-
-        // 添加 Lambda 参数到符号表
-        let mut param_types = Vec::new();
-        for param in &lambda.params {
-            let param_type = param.param_type.clone().unwrap_or(Type::Int32);
-            param_types.push(param_type.clone());
-            self.symbol_table.declare(
-                param.name.clone(),
-                SemanticSymbolInfo {
-                    name: param.name.clone(),
-                    symbol_type: param_type,
-                    is_final: false,
-                    is_initialized: true,
-                }
-            );
-        }
-
-        // 推断 Lambda 体类型
-        let return_type = match &lambda.body {
-            LambdaBody::Expr(expr) => {
-                let expr_type = self.infer_expr_type(expr)?;
-                Box::new(expr_type)
-            }
-            LambdaBody::Block(block) => {
-                // 分析块中的语句，查找 return 语句
-                let mut inferred_return: Option<Type> = None;
-                for stmt in &block.statements {
-                    if let Stmt::Return(ret_expr_opt) = stmt {
-                        if let Some(ret_expr) = ret_expr_opt {
-                            let ret_type = self.infer_expr_type(ret_expr)?;
-                            inferred_return = Some(ret_type);
-                        } else {
-                            inferred_return = Some(Type::Void);
-                        }
-                        break; // 使用第一个 return 语句的类型
-                    }
-                }
-                Box::new(inferred_return.unwrap_or(Type::Void))
-            }
-        };
-
-        self.symbol_table.exit_scope();
-
-        // 返回完整的函数类型
-        Ok(Type::Function(Box::new(crate::types::FunctionType {
-            params: param_types,
-            return_type,
-            is_static: true,
-        })))
-    }
-
     /// 推断三元运算符表达式类型
     fn infer_ternary_type(&mut self, ternary: &TernaryExpr) -> cayResult<Type> {
         // 推断条件表达式类型
@@ -1320,15 +1501,22 @@ impl SemanticAnalyzer {
         // 条件必须是布尔类型
         if cond_type != Type::Bool {
             return Err(semantic_error(
+                ErrorCodes::SEMANTIC_INVALID_OPERAND,
                 ternary.loc.line,
                 ternary.loc.column,
                 format!("Ternary operator condition must be boolean, got {}", cond_type)
             ));
         }
 
-        // 推断两个分支的类型
-        let true_type = self.infer_expr_type(&ternary.true_branch)?;
-        let false_type = self.infer_expr_type(&ternary.false_branch)?;
+        // 推断两个分支的类型（分支中按条件收窄可空变量）
+        let (when_true, when_false) = self.null_checks(&ternary.condition);
+        let (bound_true, bound_false) = ternary.condition.pattern_bindings();
+        let true_type = self.with_pattern_bindings(&bound_true, |analyzer| {
+            analyzer.with_non_null(&when_true, |analyzer| analyzer.infer_expr_type(&ternary.true_branch))
+        })?;
+        let false_type = self.with_pattern_bindings(&bound_false, |analyzer| {
+            analyzer.with_non_null(&when_false, |analyzer| analyzer.infer_expr_type(&ternary.false_branch))
+        })?;
 
         // 两个分支类型必须兼容
         if true_type == false_type {
@@ -1338,6 +1526,7 @@ impl SemanticAnalyzer {
             Ok(self.promote_types(&true_type, &false_type))
         } else {
             Err(semantic_error(
+                ErrorCodes::SEMANTIC_INCOMPATIBLE_TYPES,
                 ternary.loc.line,
                 ternary.loc.column,
                 format!("Ternary operator branches must have compatible types, got {} and {}", true_type, false_type)
@@ -1355,15 +1544,29 @@ impl SemanticAnalyzer {
             Type::Object(class_name) => {
                 if !self.type_registry.class_exists(class_name) && !self.type_registry.interface_exists(class_name) {
                     return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
                         instanceof.loc.line,
                         instanceof.loc.column,
                         format!("Unknown type in instanceof: {}", class_name)
                     ));
                 }
+                match expr_type.non_null() {
+                    Type::Object(value_type) if self.is_reference_type_name(value_type)
+                        && !self.types_may_match(value_type, class_name) => {
+                        return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_INCOMPATIBLE_TYPES,
+                            instanceof.loc.line,
+                            instanceof.loc.column,
+                            format!("A value of type {} can never be an instance of {}", value_type, class_name)
+                        ));
+                    }
+                    _ => {}
+                }
             }
             _ => {
                 // instanceof 只能用于引用类型
                 return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_INVALID_OPERAND,
                     instanceof.loc.line,
                     instanceof.loc.column,
                     format!("instanceof can only be used with reference types, got {}", instanceof.target_type)
@@ -1371,6 +1574,10 @@ impl SemanticAnalyzer {
             }
         }
 
+        if let Some(binding) = &instanceof.binding {
+            self.check_pattern_variable_name(binding, &instanceof.loc);
+        }
+
         // instanceof 返回布尔类型
         Ok(Type::Bool)
     }
@@ -1380,6 +1587,7 @@ impl SemanticAnalyzer {
         matches!(ty, 
             // 内置数值类型
             Type::Int32 | Type::Int64 | Type::Float32 | Type::Float64 | Type::Char |
+            Type::Int8 | Type::UInt8 | Type::Int16 | Type::UInt16 | Type::UInt32 | Type::UInt64 |
             // FFI 数值类型
             Type::CInt | Type::CUInt | Type::CLong |
             Type::CShort | Type::CUShort | Type::CChar | Type::CUChar |
@@ -0,0 +1,729 @@
+//! 控制流分析：确定赋值、final 局部变量的单次赋值、缺少 return 和不可达语句
+//!
+//! 沿方法体的控制流传递两个局部变量集合（规则与 Java 相同）：
+//! - 一定已赋值：读取不在其中的局部变量报错；
+//! - 可能已赋值：给不在声明处初始化的 final 局部变量赋值时，它不能已在其中。
+//!
+//! 不能正常结束的语句（return、break、continue、没有 break 的无限循环）之后的语句不可达，
+//! 报告警告。非 void 方法的方法体能正常结束时报告缺少 return。
+//!
+//! if 的条件为常量时两个分支仍视为可达（`if (DEBUG) { ... }` 不报告不可达），
+//! 循环条件为常量 true 时循环只能通过 break 结束。
+
+use std::collections::{HashMap, HashSet};
+use crate::ast::*;
+use crate::error::SourceLocation;
+use crate::types::{ParameterInfo, Type};
+use super::analyzer::SemanticAnalyzer;
+use super::const_eval::ConstEvaluator;
+use crate::diagnostic::ErrorCodes;
+
+/// 带位置的错误或警告消息
+pub(super) type FlowMessage = (SourceLocation, &'static str, String);
+
+/// 方法体中的局部变量（含参数）
+struct Local {
+    name: String,
+    is_final: bool,
+    /// 声明时已有值（带初始值的变量、参数、循环变量和模式变量）
+    initialized: bool,
+}
+
+/// 程序中某一点的分析状态
+#[derive(Clone)]
+struct FlowState {
+    reachable: bool,
+    /// 一定已赋值的局部变量
+    assigned: HashSet<usize>,
+    /// 可能已赋值的局部变量
+    maybe_assigned: HashSet<usize>,
+}
+
+impl FlowState {
+    /// 两条路径汇合后的状态；不可达的路径不影响结果
+    fn join(mut self, other: FlowState) -> FlowState {
+        if !other.reachable {
+            return self;
+        }
+        if !self.reachable {
+            return other;
+        }
+        self.assigned.retain(|id| other.assigned.contains(id));
+        self.maybe_assigned.extend(other.maybe_assigned);
+        self
+    }
+}
+
+/// break 和 continue 的目标：循环或 switch
+struct JumpTarget {
+    label: Option<String>,
+    is_loop: bool,
+    /// 跳出目标时的状态
+    breaks: Option<FlowState>,
+    /// 继续下一次循环时的状态
+    continues: Option<FlowState>,
+}
+
+/// 单个方法体的控制流分析
+pub(super) struct FlowAnalyzer<'a> {
+    program: &'a Program,
+    class_name: &'a str,
+    /// 没有 default 但覆盖了所有枚举变体或子类型的 switch 语句
+    exhaustive_switches: &'a HashSet<SourceLocation>,
+    locals: Vec<Local>,
+    scopes: Vec<HashMap<String, usize>>,
+    state: FlowState,
+    targets: Vec<JumpTarget>,
+    /// 大于 0 时不记录错误和警告（循环体的第一遍分析）
+    quiet: usize,
+    /// 最近分析的语句的位置，用于没有位置信息的语句
+    last_loc: Option<SourceLocation>,
+    errors: Vec<FlowMessage>,
+    warnings: Vec<FlowMessage>,
+}
+
+impl<'a> FlowAnalyzer<'a> {
+    pub(super) fn new(program: &'a Program, class_name: &'a str, exhaustive_switches: &'a HashSet<SourceLocation>) -> Self {
+        Self {
+            program,
+            class_name,
+            exhaustive_switches,
+            locals: Vec::new(),
+            scopes: vec![HashMap::new()],
+            state: FlowState { reachable: true, assigned: HashSet::new(), maybe_assigned: HashSet::new() },
+            targets: Vec::new(),
+            quiet: 0,
+            last_loc: None,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// 分析方法体，返回方法体能否正常结束
+    pub(super) fn check_body(&mut self, params: &[ParameterInfo], body: &Block) -> bool {
+        for param in params {
+            self.declare(&param.name, false, true);
+        }
+        self.check_block(body);
+        self.state.reachable
+    }
+
+    /// 分析结束后的错误和警告
+    pub(super) fn finish(self) -> (Vec<FlowMessage>, Vec<FlowMessage>) {
+        (self.errors, self.warnings)
+    }
+
+    // ==================== 状态和作用域 ====================
+
+    /// 不可达点的状态：所有变量都视为已赋值、都没有被赋值
+    fn unreachable(&self) -> FlowState {
+        FlowState { reachable: false, assigned: (0..self.locals.len()).collect(), maybe_assigned: HashSet::new() }
+    }
+
+    fn declare(&mut self, name: &str, is_final: bool, initialized: bool) {
+        let id = self.locals.len();
+        self.locals.push(Local { name: name.to_string(), is_final, initialized });
+        if initialized {
+            self.state.assigned.insert(id);
+            self.state.maybe_assigned.insert(id);
+        }
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), id);
+        }
+    }
+
+    /// 取出当前状态，之后的代码不可达
+    fn take_state(&mut self) -> FlowState {
+        let unreachable = self.unreachable();
+        std::mem::replace(&mut self.state, unreachable)
+    }
+
+    fn is_struct(&self, var_type: &Type) -> bool {
+        match var_type {
+            Type::Struct(_) => true,
+            Type::Object(name) => self.program.structs.iter().any(|s| s.name == *name),
+            _ => false,
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<usize> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).copied())
+    }
+
+    fn with_scope<T>(&mut self, check: impl FnOnce(&mut Self) -> T) -> T {
+        self.scopes.push(HashMap::new());
+        let result = check(self);
+        self.scopes.pop();
+        result
+    }
+
+    fn error(&mut self, code: &'static str, loc: &SourceLocation, message: String) {
+        if self.quiet == 0 && self.state.reachable && !self.errors.iter().any(|(l, _, m)| l == loc && *m == message) {
+            self.errors.push((loc.clone(), code, message));
+        }
+    }
+
+    // ==================== 语句 ====================
+
+    fn check_block(&mut self, block: &Block) {
+        // 多变量声明生成的块不创建作用域（与类型检查一致）
+        if !block.statements.is_empty() && block.statements.iter().all(|s| matches!(s, Stmt::VarDecl(_))) {
+            self.check_statements(&block.statements);
+        } else {
+            self.with_scope(|flow| flow.check_statements(&block.statements));
+        }
+    }
+
+    /// 依次分析语句；不能正常结束的语句之后的第一条语句报告不可达
+    ///
+    /// 不可达的语句没有位置信息（如 `return 0;`）时在它前面的语句处报告。
+    fn check_statements(&mut self, statements: &[Stmt]) {
+        let mut reported = !self.state.reachable;
+        let mut previous: Option<SourceLocation> = None;
+        for stmt in statements {
+            if !self.state.reachable && !reported && self.quiet == 0 {
+                reported = true;
+                let warning = match (statement_location(stmt), previous.take().or_else(|| self.last_loc.clone())) {
+                    (Some(loc), _) => Some((loc, ErrorCodes::SEMANTIC_UNREACHABLE_CODE, "Unreachable statement".to_string())),
+                    (None, Some(loc)) => Some((loc, ErrorCodes::SEMANTIC_UNREACHABLE_CODE, "Code after this statement is unreachable".to_string())),
+                    (None, None) => None,
+                };
+                self.warnings.extend(warning);
+            }
+            previous = statement_location(stmt);
+            self.check_statement(stmt);
+        }
+    }
+
+    fn check_statement(&mut self, stmt: &Stmt) {
+        if let Some(loc) = statement_location(stmt) {
+            self.last_loc = Some(loc);
+        }
+        match stmt {
+            Stmt::Expr(expr) => self.check_expr(expr),
+            Stmt::VarDecl(var) => {
+                if let Some(init) = &var.initializer {
+                    self.check_expr(init);
+                }
+                // 结构体按值存储，声明时各字段为零值
+                let initialized = var.initializer.is_some() || self.is_struct(&var.var_type);
+                self.declare(&var.name, var.is_final, initialized);
+            }
+            Stmt::Return(value) => {
+                if let Some(value) = value {
+                    self.check_expr(value);
+                }
+                self.state = self.unreachable();
+            }
+            Stmt::If(if_stmt) => self.check_if(if_stmt),
+            Stmt::While(while_stmt) => {
+                self.check_loop(while_stmt.label.as_ref(), |flow| {
+                    let (when_true, when_false) = flow.check_condition(&while_stmt.condition);
+                    flow.state = when_true;
+                    flow.with_scope(|flow| flow.check_statement(&while_stmt.body));
+                    flow.join_continues();
+                    when_false
+                });
+            }
+            Stmt::DoWhile(do_while) => {
+                self.check_loop(do_while.label.as_ref(), |flow| {
+                    flow.with_scope(|flow| flow.check_statement(&do_while.body));
+                    flow.join_continues();
+                    let (_, when_false) = flow.check_condition(&do_while.condition);
+                    when_false
+                });
+            }
+            Stmt::For(for_stmt) => {
+                self.with_scope(|flow| {
+                    if let Some(init) = &for_stmt.init {
+                        flow.check_statement(init);
+                    }
+                    flow.check_loop(for_stmt.label.as_ref(), |flow| {
+                        let (when_true, when_false) = match &for_stmt.condition {
+                            Some(condition) => flow.check_condition(condition),
+                            None => (flow.state.clone(), flow.unreachable()),
+                        };
+                        flow.state = when_true;
+                        flow.with_scope(|flow| flow.check_statement(&for_stmt.body));
+                        flow.join_continues();
+                        if let Some(update) = &for_stmt.update {
+                            flow.check_expr(update);
+                        }
+                        when_false
+                    });
+                });
+            }
+            Stmt::ForEach(for_each) => {
+                self.check_expr(&for_each.iterable);
+                self.with_scope(|flow| {
+                    flow.check_loop(for_each.label.as_ref(), |flow| {
+                        // 循环体可能一次也不执行
+                        let skipped = flow.state.clone();
+                        flow.declare(&for_each.var_name, for_each.is_final, true);
+                        flow.check_statement(&for_each.body);
+                        flow.join_continues();
+                        skipped.join(flow.state.clone())
+                    });
+                });
+            }
+            Stmt::Switch(switch_stmt) => self.check_switch(switch_stmt),
+            Stmt::Block(block) => self.check_block(block),
+            Stmt::Scope(scope_stmt) => self.check_block(&scope_stmt.body),
+            Stmt::Defer(defer) => {
+                // 被延迟的语句在代码块退出时执行，它的赋值不影响之后的语句
+                let saved = self.state.clone();
+                self.with_scope(|flow| flow.check_statement(&defer.body));
+                self.state = saved;
+            }
+            Stmt::TryWithResources(try_stmt) => {
+                self.with_scope(|flow| {
+                    for resource in &try_stmt.resources {
+                        flow.check_statement(&Stmt::VarDecl(resource.clone()));
+                    }
+                    flow.check_block(&try_stmt.body);
+                });
+            }
+            Stmt::Break(label) => self.jump(label.as_ref(), false),
+            Stmt::Continue(label) => self.jump(label.as_ref(), true),
+            Stmt::InlineIr(inline_ir) => {
+                // 内联 IR 可能直接写入局部变量，也可能直接返回
+                self.state.assigned.extend(0..self.locals.len());
+                self.state.maybe_assigned.extend(0..self.locals.len());
+                if inline_ir.raw_lines.iter().any(|line| line.trim_start().starts_with("ret ")) {
+                    self.state = self.unreachable();
+                }
+            }
+            Stmt::Error(_) => {}
+        }
+    }
+
+    fn check_if(&mut self, if_stmt: &IfStmt) {
+        let reachable = self.state.reachable;
+        let (mut when_true, mut when_false) = self.check_condition(&if_stmt.condition);
+        // 条件为常量时两个分支仍可达，只有确定赋值使用常量
+        when_true.reachable = reachable;
+        when_false.reachable = reachable;
+        self.state = when_true;
+        self.with_scope(|flow| flow.check_statement(&if_stmt.then_branch));
+        let after_then = std::mem::replace(&mut self.state, when_false);
+        if let Some(else_branch) = &if_stmt.else_branch {
+            self.with_scope(|flow| flow.check_statement(else_branch));
+        }
+        let after_else = self.state.clone();
+        self.state = after_then.join(after_else);
+    }
+
+    /// 分析循环；`check` 分析条件、循环体和更新部分，返回条件为假（循环正常结束）时的状态
+    ///
+    /// 循环体可能执行多次，第一遍分析得到循环体中可能赋值的变量，第二遍带着它们重新分析，
+    /// 这样在循环中给 final 变量赋值会被发现。
+    fn check_loop(&mut self, label: Option<&String>, check: impl Fn(&mut Self) -> FlowState) {
+        let entry = self.state.clone();
+        self.quiet += 1;
+        self.targets.push(JumpTarget { label: label.cloned(), is_loop: true, breaks: None, continues: None });
+        check(self);
+        let first = self.targets.pop().and_then(|target| target.continues);
+        self.quiet -= 1;
+        let mut repeated = std::mem::replace(&mut self.state, entry);
+        if let Some(continues) = first {
+            repeated = repeated.join(continues);
+        }
+        if repeated.reachable {
+            self.state.maybe_assigned.extend(repeated.maybe_assigned);
+        }
+
+        self.targets.push(JumpTarget { label: label.cloned(), is_loop: true, breaks: None, continues: None });
+        let finished = check(self);
+        let target = self.targets.pop();
+        self.state = match target.and_then(|target| target.breaks) {
+            Some(breaks) => finished.join(breaks),
+            None => finished,
+        };
+    }
+
+    /// 循环体结束后汇入 continue 时的状态（在条件或更新部分之前调用）
+    fn join_continues(&mut self) {
+        let continues = self.targets.last_mut().and_then(|target| target.continues.take());
+        if let Some(continues) = continues {
+            let state = self.take_state();
+            self.state = state.join(continues);
+        }
+    }
+
+    fn check_switch(&mut self, switch_stmt: &SwitchStmt) {
+        self.check_expr(&switch_stmt.expr);
+        let selected = self.state.clone();
+        self.targets.push(JumpTarget { label: None, is_loop: false, breaks: None, continues: None });
+        self.with_scope(|flow| {
+            // 上一个分支穿透时从它的结束状态进入
+            let mut falls_into = flow.unreachable();
+            for case in &switch_stmt.cases {
+                flow.state = selected.clone().join(falls_into);
+                flow.with_scope(|flow| {
+                    for label in &case.labels {
+                        flow.declare_label_bindings(label);
+                    }
+                    if let Some(guard) = &case.guard {
+                        flow.state = flow.check_condition(guard).0;
+                    }
+                    flow.check_statements(&case.body);
+                });
+                falls_into = flow.take_state();
+            }
+            flow.state = match &switch_stmt.default {
+                Some(default) => {
+                    flow.state = selected.clone().join(falls_into);
+                    flow.with_scope(|flow| flow.check_statements(default));
+                    flow.take_state()
+                }
+                None if flow.exhaustive_switches.contains(&switch_stmt.loc) => falls_into,
+                None => selected.clone().join(falls_into),
+            };
+        });
+        if let Some(breaks) = self.targets.pop().and_then(|target| target.breaks) {
+            let state = self.take_state();
+            self.state = state.join(breaks);
+        }
+    }
+
+    fn declare_label_bindings(&mut self, label: &CaseLabel) {
+        match label {
+            CaseLabel::Pattern(pattern) => {
+                for binding in &pattern.bindings {
+                    self.declare(binding, false, true);
+                }
+            }
+            CaseLabel::Type(pattern) => self.declare(&pattern.binding, false, true),
+            _ => {}
+        }
+    }
+
+    /// break 或 continue：把当前状态交给目标，之后的代码不可达
+    fn jump(&mut self, label: Option<&String>, is_continue: bool) {
+        let state = self.take_state();
+        if !state.reachable {
+            return;
+        }
+        let target = self.targets.iter_mut().rev().find(|target| match label {
+            Some(label) => target.label.as_ref() == Some(label),
+            None => target.is_loop || !is_continue,
+        });
+        if let Some(target) = target {
+            let slot = if is_continue { &mut target.continues } else { &mut target.breaks };
+            *slot = Some(match slot.take() {
+                Some(previous) => previous.join(state),
+                None => state,
+            });
+        }
+    }
+
+    // ==================== 表达式 ====================
+
+    /// 分析条件表达式，返回条件为真、为假时的状态
+    fn check_condition(&mut self, condition: &Expr) -> (FlowState, FlowState) {
+        match condition {
+            Expr::Binary(bin) if bin.op == BinaryOp::And => {
+                let (left_true, left_false) = self.check_condition(&bin.left);
+                self.state = left_true;
+                let (right_true, right_false) = self.check_condition(&bin.right);
+                (right_true, left_false.join(right_false))
+            }
+            Expr::Binary(bin) if bin.op == BinaryOp::Or => {
+                let (left_true, left_false) = self.check_condition(&bin.left);
+                self.state = left_false;
+                let (right_true, right_false) = self.check_condition(&bin.right);
+                (left_true.join(right_true), right_false)
+            }
+            Expr::Unary(unary) if unary.op == UnaryOp::Not => {
+                let (when_true, when_false) = self.check_condition(&unary.operand);
+                (when_false, when_true)
+            }
+            _ => {
+                self.check_expr(condition);
+                match self.constant_condition(condition) {
+                    Some(true) => (self.state.clone(), self.unreachable()),
+                    Some(false) => (self.unreachable(), self.state.clone()),
+                    None => (self.state.clone(), self.state.clone()),
+                }
+            }
+        }
+    }
+
+    /// 条件是否是编译期常量 true 或 false
+    fn constant_condition(&self, condition: &Expr) -> Option<bool> {
+        match condition {
+            Expr::Literal(LiteralValue::Bool(value)) => Some(*value),
+            Expr::Identifier(_) | Expr::MemberAccess(_) | Expr::Binary(_) | Expr::Call(_) => {
+                let is_local = |name: &str| self.lookup(name).is_some();
+                match ConstEvaluator::new(self.program, self.class_name, &is_local).eval(condition) {
+                    Ok(LiteralValue::Bool(value)) => Some(value),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn check_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(_) => {}
+            Expr::Identifier(ident) => self.read(ident),
+            Expr::Binary(bin) if matches!(bin.op, BinaryOp::And | BinaryOp::Or) => {
+                let (when_true, when_false) = self.check_condition(expr);
+                self.state = when_true.join(when_false);
+            }
+            Expr::Binary(bin) => {
+                self.check_expr(&bin.left);
+                self.check_expr(&bin.right);
+            }
+            Expr::Unary(unary) => match (unary.op, unary.operand.as_ref()) {
+                // &x 常用于让外部函数写入变量
+                (UnaryOp::AddressOf, Expr::Identifier(ident)) => {
+                    if let Some(id) = self.lookup(&ident.name) {
+                        self.state.assigned.insert(id);
+                        self.state.maybe_assigned.insert(id);
+                    }
+                }
+                (UnaryOp::PreInc | UnaryOp::PreDec | UnaryOp::PostInc | UnaryOp::PostDec, Expr::Identifier(ident)) => {
+                    self.read(ident);
+                    self.assign(ident, &unary.loc);
+                }
+                _ => self.check_expr(&unary.operand),
+            },
+            Expr::Call(call) => {
+                self.check_expr(&call.callee);
+                for arg in &call.args {
+                    self.check_expr(arg);
+                }
+            }
+            Expr::MemberAccess(member) => self.check_expr(&member.object),
+            Expr::New(new_expr) => {
+                for arg in &new_expr.args {
+                    self.check_expr(arg);
+                }
+            }
+            Expr::Assignment(assign) => match assign.target.as_ref() {
+                Expr::Identifier(ident) => {
+                    if assign.op != AssignOp::Assign {
+                        self.read(ident);
+                    }
+                    self.check_expr(&assign.value);
+                    self.assign(ident, &assign.loc);
+                }
+                target => {
+                    self.check_expr(target);
+                    self.check_expr(&assign.value);
+                }
+            },
+            Expr::Cast(cast) => self.check_expr(&cast.expr),
+            Expr::ArrayCreation(creation) => {
+                for size in &creation.sizes {
+                    self.check_expr(size);
+                }
+            }
+            Expr::ArrayAccess(access) => {
+                self.check_expr(&access.array);
+                self.check_expr(&access.index);
+            }
+            Expr::ArrayInit(init) => {
+                for element in &init.elements {
+                    self.check_expr(element);
+                }
+            }
+            Expr::MethodRef(method_ref) => {
+                if let Some(object) = &method_ref.object {
+                    self.check_expr(object);
+                }
+            }
+            Expr::Lambda(lambda) => self.check_lambda(lambda),
+            Expr::Ternary(ternary) => {
+                let (when_true, when_false) = self.check_condition(&ternary.condition);
+                self.state = when_true;
+                self.check_expr(&ternary.true_branch);
+                let after_true = std::mem::replace(&mut self.state, when_false);
+                self.check_expr(&ternary.false_branch);
+                let after_false = self.state.clone();
+                self.state = after_true.join(after_false);
+            }
+            Expr::InstanceOf(instanceof) => self.check_expr(&instanceof.expr),
+            Expr::Alloc(alloc) => {
+                self.check_expr(&alloc.size);
+                if let Some(align) = &alloc.align {
+                    self.check_expr(align);
+                }
+            }
+            Expr::Dealloc(dealloc) => self.check_expr(&dealloc.ptr),
+            Expr::Try(try_expr) => self.check_expr(&try_expr.expr),
+            Expr::Switch(switch) => {
+                self.check_expr(&switch.expr);
+                let selected = self.state.clone();
+                let mut after = self.unreachable();
+                for arm in &switch.arms {
+                    self.state = selected.clone();
+                    self.with_scope(|flow| {
+                        for label in &arm.labels {
+                            flow.declare_label_bindings(label);
+                        }
+                        if let Some(guard) = &arm.guard {
+                            flow.state = flow.check_condition(guard).0;
+                        }
+                        flow.check_expr(&arm.value);
+                    });
+                    after = after.join(self.state.clone());
+                }
+                if let Some(default) = &switch.default {
+                    self.state = selected.clone();
+                    self.check_expr(default);
+                    after = after.join(self.state.clone());
+                }
+                self.state = if switch.arms.is_empty() && switch.default.is_none() { selected } else { after };
+            }
+            // 右侧可能不求值，其中的赋值不算数
+            Expr::SafeCall(safe_call) => {
+                self.check_expr(&safe_call.object);
+                let evaluated = self.state.clone();
+                for arg in safe_call.args.iter().flatten() {
+                    self.check_expr(arg);
+                }
+                self.state = evaluated;
+            }
+            Expr::Elvis(elvis) => {
+                self.check_expr(&elvis.value);
+                let evaluated = self.state.clone();
+                self.check_expr(&elvis.default);
+                self.state = evaluated;
+            }
+        }
+    }
+
+    /// Lambda 体在创建之后才执行：其中读取的外层变量必须已赋值，其中的赋值和跳转不影响外层
+    fn check_lambda(&mut self, lambda: &LambdaExpr) {
+        let saved = self.state.clone();
+        let outer_targets = std::mem::take(&mut self.targets);
+        self.with_scope(|flow| {
+            for param in &lambda.params {
+                flow.declare(&param.name, false, true);
+            }
+            match &lambda.body {
+                LambdaBody::Expr(body) => flow.check_expr(body),
+                LambdaBody::Block(block) => flow.check_block(block),
+            }
+        });
+        self.targets = outer_targets;
+        self.state = saved;
+    }
+
+    fn read(&mut self, ident: &IdentifierExpr) {
+        let id = match self.lookup(&ident.name) {
+            Some(id) => id,
+            None => return,
+        };
+        if !self.state.assigned.contains(&id) {
+            self.error(ErrorCodes::SEMANTIC_UNINITIALIZED_VARIABLE, &ident.loc, format!("Variable '{}' might not have been initialized", ident.name));
+            // 同一个变量只报告一次
+            self.state.assigned.insert(id);
+        }
+    }
+
+    fn assign(&mut self, ident: &IdentifierExpr, loc: &SourceLocation) {
+        let id = match self.lookup(&ident.name) {
+            Some(id) => id,
+            None => return,
+        };
+        // 声明时带初始值的 final 变量由类型检查报告
+        let local = &self.locals[id];
+        if local.is_final && !local.initialized && self.state.maybe_assigned.contains(&id) {
+            self.error(ErrorCodes::SEMANTIC_FINAL_REASSIGNMENT, loc, format!("Variable '{}' might already have been assigned", local.name));
+        }
+        self.state.assigned.insert(id);
+        self.state.maybe_assigned.insert(id);
+    }
+}
+
+/// 语句的位置；没有位置信息的语句（return、break、continue）返回 None
+fn statement_location(stmt: &Stmt) -> Option<SourceLocation> {
+    match stmt {
+        // 字面量没有位置信息
+        Stmt::Expr(Expr::Literal(_)) | Stmt::Return(Some(Expr::Literal(_))) => None,
+        Stmt::Return(None) | Stmt::Break(_) | Stmt::Continue(_) => None,
+        Stmt::Expr(expr) | Stmt::Return(Some(expr)) => Some(expr.location().clone()),
+        Stmt::VarDecl(var) => Some(var.loc.clone()),
+        Stmt::If(if_stmt) => Some(if_stmt.loc.clone()),
+        Stmt::While(while_stmt) => Some(while_stmt.loc.clone()),
+        Stmt::For(for_stmt) => Some(for_stmt.loc.clone()),
+        Stmt::ForEach(for_each) => Some(for_each.loc.clone()),
+        Stmt::DoWhile(do_while) => Some(do_while.loc.clone()),
+        Stmt::Switch(switch_stmt) => Some(switch_stmt.loc.clone()),
+        Stmt::Block(block) => Some(block.loc.clone()),
+        Stmt::Scope(scope_stmt) => Some(scope_stmt.loc.clone()),
+        Stmt::Defer(defer) => Some(defer.loc.clone()),
+        Stmt::TryWithResources(try_stmt) => Some(try_stmt.loc.clone()),
+        Stmt::InlineIr(inline_ir) => Some(inline_ir.loc.clone()),
+        Stmt::Error(loc) => Some(loc.clone()),
+    }
+}
+
+impl SemanticAnalyzer {
+    /// 检查所有方法体的控制流
+    pub(super) fn check_control_flow(&mut self, program: &Program) {
+        for class in &program.classes {
+            for member in &class.members {
+                match member {
+                    ClassMember::Method(method) => {
+                        if let Some(body) = &method.body {
+                            self.check_body_flow(program, &class.name, &method.params, body, Some(method));
+                        }
+                    }
+                    ClassMember::Constructor(ctor) => {
+                        self.check_body_flow(program, &class.name, &ctor.params, &ctor.body, None);
+                    }
+                    ClassMember::Destructor(dtor) => {
+                        self.check_body_flow(program, &class.name, &[], &dtor.body, None);
+                    }
+                    ClassMember::InstanceInitializer(block) | ClassMember::StaticInitializer(block) => {
+                        self.check_body_flow(program, &class.name, &[], block, None);
+                    }
+                    ClassMember::Field(_) | ClassMember::Class(_) | ClassMember::Property(_) => {}
+                }
+            }
+        }
+        for interface in &program.interfaces {
+            for method in &interface.methods {
+                if let Some(body) = &method.body {
+                    self.check_body_flow(program, &interface.name, &method.params, body, Some(method));
+                }
+            }
+        }
+        for func in &program.top_level_functions {
+            let completes = self.check_body_flow(program, "", &func.params, &func.body, None);
+            if completes && func.return_type != Type::Void {
+                self.report_missing_return(&func.name, &func.loc);
+            }
+        }
+    }
+
+    /// 检查一个方法体，返回方法体能否正常结束；`method` 为 None 时是构造函数、析构函数或初始化块
+    fn check_body_flow(&mut self, program: &Program, class_name: &str, params: &[ParameterInfo], body: &Block, method: Option<&MethodDecl>) -> bool {
+        let mut flow = FlowAnalyzer::new(program, class_name, &self.exhaustive_switches);
+        let completes = flow.check_body(params, body);
+        let (errors, warnings) = flow.finish();
+        for (loc, code, message) in errors {
+            self.errors.push(self.create_error_info(code, loc.line, loc.column, message));
+        }
+        for (loc, code, message) in warnings {
+            self.warn(code, loc.line, loc.column, message);
+        }
+        match method {
+            Some(method) if completes && method.return_type != Type::Void => {
+                self.report_missing_return(&method.name, &method.loc);
+            }
+            _ => {}
+        }
+        completes
+    }
+
+    fn report_missing_return(&mut self, name: &str, loc: &SourceLocation) {
+        let message = format!("Missing return statement in method '{}'", name);
+        self.errors.push(self.create_error_info(ErrorCodes::SEMANTIC_MISSING_RETURN, loc.line, loc.column, message));
+    }
+}
@@ -0,0 +1,165 @@
+//! 增强 for 循环检查
+//!
+//! `for (T x : source)` 支持三类数据源：数组按下标遍历；`range(a, b[, step])` 的参数为
+//! int 或 long，展开为不分配内存的计数循环（任一参数为 long 时元素为 long）；对象先调用
+//! `iterator()` 再循环 `hasNext()`/`next()`，或者对象本身带 `hasNext()`/`next()`。
+//! caylibs/Iterable.cay 中的 `Iterable<T>`/`Iterator<T>` 接口描述了这套协议，但不要求实现它们。
+
+use crate::ast::*;
+use crate::types::{Type, ForEachKind, ForEachInfo};
+use crate::error::{cayResult, semantic_error};
+use super::analyzer::SemanticAnalyzer;
+use super::symbol_table::SemanticSymbolInfo;
+use crate::diagnostic::ErrorCodes;
+
+impl SemanticAnalyzer {
+    /// 类型检查增强 for 循环，循环变量和隐藏的迭代器变量只在循环内可见
+    pub(super) fn type_check_for_each(&mut self, for_each: &ForEachStmt, expected_return: Option<&Type>) -> cayResult<()> {
+        self.symbol_table.enter_scope();
+        let (kind, source, element) = self.for_each_source(for_each)?;
+
+        let declared = self.resolve_type(&for_each.var_type, for_each.loc.line, for_each.loc.column)?;
+        let variable = if declared == Type::Auto {
+            element.clone()
+        } else {
+            if !self.types_compatible(&element, &declared) {
+                return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_TYPE_MISMATCH,
+                    for_each.loc.line,
+                    for_each.loc.column,
+                    format!("Cannot assign {} elements to loop variable '{}' of type {}", element, for_each.var_name, declared)
+                ));
+            }
+            declared
+        };
+        let nullable = if for_each.var_type == Type::Auto { element.is_nullable() } else { for_each.var_type.is_nullable() };
+
+        self.type_registry.for_each_loops.insert(for_each.loc.clone(), ForEachInfo {
+            kind,
+            source,
+            variable: variable.clone(),
+        });
+        self.symbol_table.declare(
+            for_each.var_name.clone(),
+            SemanticSymbolInfo {
+                name: for_each.var_name.clone(),
+                symbol_type: variable,
+                is_final: for_each.is_final,
+                is_initialized: true,
+                nullable,
+                decl_loc: Some(for_each.loc.clone()),
+            }
+        );
+        self.forget_assigned_in(&for_each.body);
+        self.type_check_branch(&for_each.body, expected_return)?;
+        self.symbol_table.exit_scope();
+        Ok(())
+    }
+
+    /// 确定循环的形式、隐藏变量 `iter` 的类型和元素类型
+    fn for_each_source(&mut self, for_each: &ForEachStmt) -> cayResult<(ForEachKind, Type, Type)> {
+        match for_each.range_args() {
+            Some(args) if !self.range_is_user_defined() => {
+                let counter = self.range_counter_type(args)?;
+                return Ok((ForEachKind::Range, counter.clone(), counter));
+            }
+            _ => {}
+        }
+
+        let iterable_type = self.infer_expr_type(&for_each.iterable)?;
+        if let Type::Array(element) = iterable_type.non_null() {
+            self.check_dereference(&for_each.iterable);
+            let element = element.as_ref().clone();
+            return Ok((ForEachKind::Array, iterable_type.non_null().clone(), element));
+        }
+
+        let kind = if self.has_instance_method(&iterable_type, "iterator") {
+            ForEachKind::Iterable
+        } else if self.has_instance_method(&iterable_type, "hasNext") && self.has_instance_method(&iterable_type, "next") {
+            ForEachKind::Iterator
+        } else {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_NOT_ITERABLE,
+                for_each.loc.line,
+                for_each.loc.column,
+                format!("for-each requires an array, range(...) or an object with iterator() or hasNext()/next(), got {}", iterable_type)
+            ));
+        };
+        self.check_dereference(&for_each.iterable);
+        let iterator_type = match kind {
+            ForEachKind::Iterable => self.infer_expr_type(&for_each.iterator_expr())?,
+            _ => iterable_type.non_null().clone(),
+        };
+        if !(self.has_instance_method(&iterator_type, "hasNext") && self.has_instance_method(&iterator_type, "next")) {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_NOT_ITERABLE,
+                for_each.loc.line,
+                for_each.loc.column,
+                format!("Iterator type {} returned by iterator() must have hasNext() and next() methods", iterator_type)
+            ));
+        }
+
+        self.symbol_table.declare(
+            for_each.hidden_name("iter"),
+            SemanticSymbolInfo {
+                name: for_each.hidden_name("iter"),
+                symbol_type: iterator_type.non_null().clone(),
+                is_final: true,
+                is_initialized: true,
+                nullable: false,
+                decl_loc: None,
+            }
+        );
+        let has_next = self.infer_expr_type(&for_each.has_next_expr())?;
+        if has_next != Type::Bool {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_NOT_ITERABLE,
+                for_each.loc.line,
+                for_each.loc.column,
+                format!("hasNext() of {} must return bool, got {}", iterator_type, has_next)
+            ));
+        }
+        let element = self.infer_expr_type(&for_each.next_expr())?;
+        if element == Type::Void {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_NOT_ITERABLE,
+                for_each.loc.line,
+                for_each.loc.column,
+                format!("next() of {} must return a value", iterator_type)
+            ));
+        }
+        Ok((kind, iterator_type.non_null().clone(), element))
+    }
+
+    /// 局部变量或当前类（含父类）的方法名为 range 时，`range(...)` 按普通调用处理
+    fn range_is_user_defined(&self) -> bool {
+        self.symbol_table.lookup("range").is_some()
+            || self.current_class.as_ref()
+                .is_some_and(|class| self.type_registry.get_method(class, "range").is_some())
+    }
+
+    /// range 的参数必须是整数，任一参数为 long 时计数器为 long
+    fn range_counter_type(&mut self, args: &[Expr]) -> cayResult<Type> {
+        let mut counter = Type::Int32;
+        for arg in args {
+            match self.infer_expr_type(arg)? {
+                Type::Int8 | Type::Int16 | Type::Int32 => {}
+                Type::Int64 => counter = Type::Int64,
+                other => {
+                    let (line, column) = self.get_expr_location(arg);
+                    return Err(semantic_error(ErrorCodes::SEMANTIC_NOT_ITERABLE, line, column, format!("range() arguments must be int or long, got {}", other)));
+                }
+            }
+        }
+        Ok(counter)
+    }
+
+    /// 类或接口类型是否有指定名称的方法
+    fn has_instance_method(&self, ty: &Type, method_name: &str) -> bool {
+        match ty.non_null() {
+            Type::Object(name) => self.type_registry.get_method(name, method_name).is_some()
+                || self.type_registry.get_interface_method(name, method_name).is_some(),
+            _ => false,
+        }
+    }
+}
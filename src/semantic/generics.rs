@@ -0,0 +1,423 @@
+//! 泛型类型检查
+//!
+//! 泛型模板（类、接口、方法）只以类型参数检查一次；每个具体实例化
+//! （如 `ArrayList<int>`）在这里注册为单态化名称（`ArrayList$i32`）的类信息，
+//! 供后续类型检查和代码生成阶段的单态化使用。
+
+use std::collections::HashMap;
+use crate::ast::*;
+use crate::types::{Type, TypeParamInfo, MethodInfo, ClassInfo, GenericMethodCall, mangle_generic_name};
+use crate::error::{cayResult, semantic_error};
+use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
+
+/// 泛型实例化的最大嵌套深度（防止 `class A<T> { A<A<T>> x; }` 无限展开）
+const MAX_INSTANTIATION_DEPTH: usize = 32;
+
+impl SemanticAnalyzer {
+    /// 解析类型中的泛型实例化：检查类型实参数量和上界，并注册单态化后的类/接口
+    ///
+    /// 返回的类型中不再包含 `Type::Generic`，泛型实例化被替换为 `Type::Object(单态化名称)`。
+    pub fn resolve_type(&mut self, ty: &Type, line: usize, column: usize) -> cayResult<Type> {
+        match ty {
+            // 可空标记由空安全检查单独跟踪（见 nullability.rs），解析后的类型不再区分
+            Type::Nullable(inner) => self.resolve_type(inner, line, column),
+            Type::Generic(name, args) => {
+                let mut resolved_args = Vec::new();
+                for arg in args {
+                    resolved_args.push(self.resolve_type(arg, line, column)?);
+                }
+                self.instantiate_generic(name, resolved_args, line, column)
+            }
+            Type::Array(inner) => Ok(Type::Array(Box::new(self.resolve_type(inner, line, column)?))),
+            Type::Pointer(inner) => Ok(Type::Pointer(Box::new(self.resolve_type(inner, line, column)?))),
+            Type::Function(func_type) => {
+                let mut params = Vec::new();
+                for param in &func_type.params {
+                    params.push(self.resolve_type(param, line, column)?);
+                }
+                let return_type = self.resolve_type(&func_type.return_type, line, column)?;
+                Ok(Type::Function(Box::new(crate::types::FunctionType {
+                    params,
+                    return_type: Box::new(return_type),
+                    is_static: func_type.is_static,
+                })))
+            }
+            _ => Ok(ty.clone()),
+        }
+    }
+
+    /// 实例化泛型类或接口，返回单态化后的类型
+    fn instantiate_generic(&mut self, name: &str, args: Vec<Type>, line: usize, column: usize) -> cayResult<Type> {
+        let type_params = if let Some(template) = self.type_registry.generic_templates.get(name) {
+            template.type_params.clone()
+        } else if let Some(template) = self.type_registry.generic_interface_templates.get(name) {
+            template.type_params.clone()
+        } else if let Some(template) = self.type_registry.generic_enum_templates.get(name) {
+            template.type_params.clone()
+        } else {
+            return Err(semantic_error(ErrorCodes::SEMANTIC_GENERIC_ERROR, line, column, format!("Type '{}' is not generic", name)));
+        };
+
+        if type_params.len() != args.len() {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_GENERIC_ERROR,
+                line,
+                column,
+                format!("Generic type '{}' expects {} type argument(s), got {}", name, type_params.len(), args.len())
+            ));
+        }
+
+        for (param, arg) in type_params.iter().zip(args.iter()) {
+            self.check_type_bound(param, arg, name, line, column)?;
+        }
+
+        // 模板内部以自身类型参数引用自身（class Node<T> { Node<T> next; }）即为当前类
+        let is_self_reference = self.current_class.as_deref() == Some(name)
+            && type_params.iter().zip(args.iter()).all(|(p, a)| *a == Type::TypeParam(p.name.clone()));
+        if is_self_reference {
+            return Ok(Type::Object(name.to_string()));
+        }
+
+        let mangled = mangle_generic_name(name, &args);
+        if self.type_registry.enum_exists(&mangled) {
+            return Ok(Type::Enum(mangled));
+        }
+        if self.type_registry.generic_instances.contains_key(&mangled) {
+            return Ok(Type::Object(mangled));
+        }
+
+        // 泛型枚举实例只需替换负载字段类型，不会递归展开
+        if let Some(template) = self.type_registry.generic_enum_templates.get(name).cloned() {
+            if self.type_registry.class_exists(&mangled) {
+                return Err(semantic_error(ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION, line, column, format!("Enum '{}' already defined", mangled)));
+            }
+            self.type_registry.generic_instances.insert(mangled.clone(), (name.to_string(), args.clone()));
+            self.type_registry.register_enum(template.instantiate(&mangled, &args))?;
+            return Ok(Type::Enum(mangled));
+        }
+
+        if self.instantiation_depth >= MAX_INSTANTIATION_DEPTH {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_GENERIC_ERROR,
+                line,
+                column,
+                format!("Generic instantiation of '{}' is nested too deeply", name)
+            ));
+        }
+        self.type_registry.generic_instances.insert(mangled.clone(), (name.to_string(), args.clone()));
+        self.instantiation_depth += 1;
+
+        let result = if let Some(template) = self.type_registry.generic_templates.get(name).cloned() {
+            let mut info = template.instantiate(&mangled, &args);
+            self.resolve_class_signatures(&mut info, line, column)
+                .and_then(|_| self.type_registry.register_class(info))
+        } else {
+            let template = self.type_registry.generic_interface_templates[name].clone();
+            let mut info = template.instantiate(&mangled, &args);
+            self.resolve_methods(info.methods.values_mut(), line, column)
+                .and_then(|_| self.type_registry.register_interface(info))
+        };
+        self.instantiation_depth -= 1;
+        result?;
+
+        Ok(Type::Object(mangled))
+    }
+
+    /// 检查类型实参是否满足类型参数的上界
+    fn check_type_bound(&self, param: &TypeParamInfo, arg: &Type, generic_name: &str, line: usize, column: usize) -> cayResult<()> {
+        let bound = match &param.bound {
+            Some(bound) => bound,
+            None => return Ok(()),
+        };
+        let satisfied = match arg {
+            Type::Object(class_name) => self.class_satisfies_bound(class_name, bound),
+            // 类型参数作为实参时，使用其自身的上界判断
+            Type::TypeParam(name) => self.current_type_params.iter()
+                .find(|p| p.name == *name)
+                .and_then(|p| p.bound.as_ref())
+                .is_some_and(|b| b == bound || self.class_satisfies_bound(b, bound)),
+            _ => false,
+        };
+        if satisfied {
+            Ok(())
+        } else {
+            Err(semantic_error(
+                ErrorCodes::SEMANTIC_GENERIC_ERROR,
+                line,
+                column,
+                format!(
+                    "Type argument {} does not satisfy bound '{}' of type parameter '{}' in '{}'",
+                    arg, bound, param.name, generic_name
+                )
+            ))
+        }
+    }
+
+    /// 检查类（或接口）是否是上界的子类或实现了上界接口
+    ///
+    /// 上界为泛型接口名时，实现该接口的任意实例化（Comparable_Num）也满足上界。
+    fn class_satisfies_bound(&self, class_name: &str, bound: &str) -> bool {
+        let mut current = Some(class_name.to_string());
+        let mut visited = Vec::new();
+        while let Some(name) = current {
+            if visited.contains(&name) {
+                return false;
+            }
+            if name == bound || self.generic_origin(&name) == bound {
+                return true;
+            }
+            let class_info = match self.type_registry.get_class(&name) {
+                Some(info) => info,
+                None => return false,
+            };
+            if class_info.interfaces.iter().any(|i| i == bound || self.generic_origin(i) == bound) {
+                return true;
+            }
+            current = class_info.parent.clone();
+            visited.push(name);
+        }
+        false
+    }
+
+    /// 获取单态化类名对应的泛型名（非泛型实例返回自身）
+    pub fn generic_origin<'a>(&'a self, name: &'a str) -> &'a str {
+        self.type_registry.generic_instances
+            .get(name)
+            .map(|(origin, _)| origin.as_str())
+            .unwrap_or(name)
+    }
+
+    /// 解析类成员签名中的泛型实例化
+    ///
+    /// 泛型方法的签名保持原样，在每次调用时根据推断出的类型实参替换后再解析。
+    fn resolve_class_signatures(&mut self, info: &mut ClassInfo, line: usize, column: usize) -> cayResult<()> {
+        for field in info.fields.values_mut() {
+            field.field_type = self.resolve_type(&field.field_type, line, column)?;
+        }
+        for ctor in &mut info.constructors {
+            for param in &mut ctor.params {
+                param.param_type = self.resolve_type(&param.param_type, line, column)?;
+            }
+        }
+        for methods in info.methods.values_mut() {
+            self.resolve_methods(methods.iter_mut(), line, column)?;
+        }
+        for supertype in info.generic_supertypes.clone() {
+            self.resolve_type(&supertype, line, column)?;
+        }
+        Ok(())
+    }
+
+    /// 解析非泛型方法签名中的泛型实例化
+    fn resolve_methods<'m>(&mut self, methods: impl Iterator<Item = &'m mut MethodInfo>, line: usize, column: usize) -> cayResult<()> {
+        for method in methods {
+            if !method.type_params.is_empty() {
+                continue;
+            }
+            for param in &mut method.params {
+                param.param_type = self.resolve_type(&param.param_type, line, column)?;
+            }
+            method.return_type = self.resolve_type(&method.return_type, line, column)?;
+        }
+        Ok(())
+    }
+
+    /// 解析所有类和接口签名中的泛型实例化
+    ///
+    /// 在收集方法之后执行：先保存泛型模板的原始签名（用于实例化），
+    /// 再把每个类/接口签名中的 `Box<int>` 替换为单态化类型并注册实例。
+    pub fn resolve_generic_signatures(&mut self, program: &Program) -> cayResult<()> {
+        for class in program.classes.iter().filter(|c| !c.type_params.is_empty()) {
+            if let Some(info) = self.type_registry.get_class(&class.name) {
+                self.type_registry.generic_templates.insert(class.name.clone(), info.clone());
+            }
+        }
+        for interface in program.interfaces.iter().filter(|i| !i.type_params.is_empty()) {
+            if let Some(info) = self.type_registry.get_interface(&interface.name) {
+                self.type_registry.generic_interface_templates.insert(interface.name.clone(), info.clone());
+            }
+        }
+
+        for interface in &program.interfaces {
+            self.current_type_params = interface.type_params.clone();
+            if let Some(mut info) = self.type_registry.interfaces.get(&interface.name).cloned() {
+                self.resolve_methods(info.methods.values_mut(), interface.loc.line, interface.loc.column)?;
+                self.type_registry.interfaces.insert(interface.name.clone(), info);
+            }
+        }
+
+        for class in &program.classes {
+            self.current_class = Some(class.name.clone());
+            self.current_type_params = class.type_params.clone();
+            if let Some(mut info) = self.type_registry.get_class(&class.name).cloned() {
+                self.resolve_class_signatures(&mut info, class.loc.line, class.loc.column)?;
+                self.type_registry.classes.insert(class.name.clone(), info);
+            }
+        }
+
+        self.current_class = None;
+        self.current_type_params.clear();
+        Ok(())
+    }
+
+    /// 类型参数按上界擦除（T: Shape -> Shape），无上界时保持不变
+    pub fn erase_type_param(&self, ty: Type) -> Type {
+        if let Type::TypeParam(name) = &ty {
+            if let Some(bound) = self.type_param_bound(name) {
+                return Type::Object(bound);
+            }
+        }
+        ty
+    }
+
+    /// 查找当前可见类型参数的上界
+    fn type_param_bound(&self, name: &str) -> Option<String> {
+        self.current_type_params.iter()
+            .rev()
+            .find(|p| p.name == name)
+            .and_then(|p| p.bound.clone())
+    }
+
+    /// 推断类型参数上的方法调用: T: Comparable 时的 a.compareTo(b)
+    ///
+    /// 上界为泛型接口时按 F-有界 处理，即 `T: Comparable` 视为 `T: Comparable<T>`。
+    pub fn infer_type_param_method_call(&mut self, param_name: &str, member: &MemberAccessExpr, call: &CallExpr) -> cayResult<Type> {
+        let bound = match self.type_param_bound(param_name) {
+            Some(bound) => bound,
+            None => {
+                return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_GENERIC_ERROR,
+                    call.loc.line,
+                    call.loc.column,
+                    format!("Cannot call method '{}' on type parameter '{}' without a bound", member.member, param_name)
+                ));
+            }
+        };
+
+        let mut arg_types = Vec::new();
+        for arg in &call.args {
+            arg_types.push(self.infer_expr_type(arg)?);
+        }
+
+        let method = if let Some(method) = self.type_registry.find_method(&bound, &member.member, &arg_types) {
+            Some(method.clone())
+        } else if let Some(interface) = self.type_registry.get_interface(&bound) {
+            interface.methods.get(&member.member).map(|method| {
+                let bindings: HashMap<String, Type> = interface.type_params.iter()
+                    .map(|p| (p.name.clone(), Type::TypeParam(param_name.to_string())))
+                    .collect();
+                method.substitute(&bindings)
+            })
+        } else {
+            None
+        };
+
+        let method = match method {
+            Some(method) => method,
+            None => {
+                return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_METHOD_NOT_FOUND,
+                    call.loc.line,
+                    call.loc.column,
+                    format!("Unknown method '{}' for type parameter '{}' with bound '{}'", member.member, param_name, bound)
+                ));
+            }
+        };
+
+        if !method.type_params.is_empty() {
+            return self.instantiate_generic_method_call(&method, call, &arg_types);
+        }
+        self.check_arguments_compatible(&call.args, &method.params, call.loc.line, call.loc.column)?;
+        Ok(method.return_type.clone())
+    }
+
+    /// 推断泛型方法调用的类型实参，检查参数并记录实例化信息
+    pub fn instantiate_generic_method_call(&mut self, method: &MethodInfo, call: &CallExpr, arg_types: &[Type]) -> cayResult<Type> {
+        let mut bindings = HashMap::new();
+        for (param, arg_type) in method.params.iter().zip(arg_types.iter()) {
+            self.unify_type_param(&method.type_params, &param.param_type, arg_type, &mut bindings, call)?;
+        }
+
+        let mut type_args = Vec::new();
+        for type_param in &method.type_params {
+            match bindings.get(&type_param.name) {
+                Some(arg) => {
+                    self.check_type_bound(type_param, arg, &method.name, call.loc.line, call.loc.column)?;
+                    type_args.push(arg.clone());
+                }
+                None => {
+                    return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_TYPE_INFERENCE,
+                        call.loc.line,
+                        call.loc.column,
+                        format!("Cannot infer type argument '{}' for generic method '{}'", type_param.name, method.name)
+                    ));
+                }
+            }
+        }
+
+        let mut params = Vec::new();
+        for param in &method.params {
+            let mut param = param.clone();
+            param.param_type = self.resolve_type(&param.param_type.substitute(&bindings), call.loc.line, call.loc.column)?;
+            params.push(param);
+        }
+        self.check_arguments_compatible(&call.args, &params, call.loc.line, call.loc.column)?;
+        let return_type = self.resolve_type(&method.return_type.substitute(&bindings), call.loc.line, call.loc.column)?;
+
+        self.type_registry.generic_method_calls.insert(call.loc.clone(), GenericMethodCall {
+            class_name: method.class_name.clone(),
+            method_name: method.name.clone(),
+            type_args,
+        });
+
+        Ok(return_type)
+    }
+
+    /// 根据形参类型和实参类型推断方法类型参数的绑定
+    fn unify_type_param(
+        &self,
+        type_params: &[TypeParamInfo],
+        param_type: &Type,
+        arg_type: &Type,
+        bindings: &mut HashMap<String, Type>,
+        call: &CallExpr,
+    ) -> cayResult<()> {
+        match (param_type, arg_type) {
+            (Type::TypeParam(name), _) if type_params.iter().any(|p| p.name == *name) => {
+                match bindings.get(name) {
+                    Some(bound) if bound == arg_type || self.types_compatible(arg_type, bound) => {}
+                    Some(bound) if self.types_compatible(bound, arg_type) => {
+                        bindings.insert(name.clone(), arg_type.clone());
+                    }
+                    Some(bound) => {
+                        return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_GENERIC_ERROR,
+                            call.loc.line,
+                            call.loc.column,
+                            format!("Conflicting type arguments for '{}': {} and {}", name, bound, arg_type)
+                        ));
+                    }
+                    None => {
+                        bindings.insert(name.clone(), arg_type.clone());
+                    }
+                }
+            }
+            (Type::Array(param_elem), Type::Array(arg_elem)) => {
+                self.unify_type_param(type_params, param_elem, arg_elem, bindings, call)?;
+            }
+            (Type::Generic(name, param_args), Type::Object(class_name)) => {
+                if let Some((origin, arg_args)) = self.type_registry.generic_instances.get(class_name) {
+                    if origin == name {
+                        for (p, a) in param_args.iter().zip(arg_args.iter()) {
+                            self.unify_type_param(type_params, p, a, bindings, call)?;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
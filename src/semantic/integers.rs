@@ -0,0 +1,99 @@
+//! byte、short、uint、ulong 等整数类型的检查
+//!
+//! 不足 int 的整数（byte、ubyte、short、ushort）参与运算时先提升为 int，两个操作数再提升为
+//! 位宽较大的类型，位宽相同时取无符号类型（见 `promote_types`）。运算的操作数类型记录在
+//! `TypeRegistry::integer_operations` 中，代码生成据此选择零扩展或符号扩展，以及 `udiv`、
+//! `urem`、`icmp ult`、`lshr` 等无符号指令。
+//!
+//! 赋值时只允许不损失数值的扩展；取值范围内的整数字面量可以直接赋给这些类型。
+
+use crate::ast::*;
+use crate::types::{Type, IntegerOperation};
+use crate::error::{cayResult, semantic_error};
+use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
+
+impl SemanticAnalyzer {
+    /// 推断有 byte、short、uint、ulong 等类型操作数的二元运算，其他运算返回 None
+    pub(super) fn infer_integer_binary(&mut self, bin: &BinaryExpr, left_type: &Type, right_type: &Type) -> cayResult<Option<Type>> {
+        if !left_type.is_narrow_or_unsigned() && !right_type.is_narrow_or_unsigned() {
+            return Ok(None);
+        }
+        let is_number = |ty: &Type| ty.is_integer() || matches!(ty, Type::Char | Type::Float32 | Type::Float64);
+        let both_numbers = is_number(left_type) && is_number(right_type);
+        let both_integers = left_type.is_integer() && right_type.is_integer();
+
+        let (operand_type, result_type) = match bin.op {
+            BinaryOp::Add if *left_type == Type::String || *right_type == Type::String => (Type::String, Type::String),
+            BinaryOp::And | BinaryOp::Or => return Ok(None),
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod if both_numbers => {
+                if matches!(bin.op, BinaryOp::Div | BinaryOp::Mod)
+                    && matches!(bin.right.as_ref(), Expr::Literal(LiteralValue::Int32(0)) | Expr::Literal(LiteralValue::Int64(0)))
+                {
+                    return Err(semantic_error(ErrorCodes::SEMANTIC_DIVISION_BY_ZERO, bin.loc.line, bin.loc.column, "/ by zero".to_string()));
+                }
+                let promoted = self.promote_types(left_type, right_type);
+                (promoted.clone(), promoted)
+            }
+            BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge if both_numbers => {
+                (self.promote_types(left_type, right_type), Type::Bool)
+            }
+            BinaryOp::BitAnd | BinaryOp::BitOr | BinaryOp::BitXor if both_integers => {
+                let promoted = self.promote_integer_types(left_type, right_type);
+                (promoted.clone(), promoted)
+            }
+            // 移位的结果类型是提升后的左操作数类型，右操作数只提供移位位数
+            BinaryOp::Shl | BinaryOp::Shr | BinaryOp::UnsignedShr if both_integers => {
+                let promoted = self.promote_integer_types(left_type, left_type);
+                (promoted.clone(), promoted)
+            }
+            _ => {
+                return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_INVALID_OPERAND,
+                    bin.loc.line,
+                    bin.loc.column,
+                    format!("Cannot apply {:?} to {} and {}", bin.op, left_type, right_type)
+                ));
+            }
+        };
+
+        self.type_registry.integer_operations.insert(bin.loc.clone(), IntegerOperation {
+            left: left_type.clone(),
+            right: right_type.clone(),
+            operand_type,
+        });
+        Ok(Some(result_type))
+    }
+
+    /// 期望类型是 byte、short、uint 等时，取值范围内的整数字面量（可带负号）具有期望类型
+    pub(super) fn infer_integer_literal_expecting(expr: &Expr, expected: &Type) -> Option<Type> {
+        if !expected.is_narrow_or_unsigned() {
+            return None;
+        }
+        let value = Self::integer_literal_value(expr)?;
+        let (min, max) = expected.integer_range()?;
+        (min..=max).contains(&value).then(|| expected.clone())
+    }
+
+    /// 整数字面量超出目标类型取值范围时的错误信息
+    pub(super) fn integer_literal_out_of_range(expr: &Expr, target: &Type) -> Option<String> {
+        if !target.is_narrow_or_unsigned() {
+            return None;
+        }
+        let value = Self::integer_literal_value(expr)?;
+        let (min, max) = target.integer_range()?;
+        if (min..=max).contains(&value) {
+            return None;
+        }
+        Some(format!("Integer literal {} is out of range for {} [{}, {}]", value, target, min, max))
+    }
+
+    fn integer_literal_value(expr: &Expr) -> Option<i128> {
+        match expr {
+            Expr::Literal(LiteralValue::Int32(value)) => Some(*value as i128),
+            Expr::Literal(LiteralValue::Int64(value)) => Some(*value as i128),
+            Expr::Unary(UnaryExpr { op: UnaryOp::Neg, operand, .. }) => Self::integer_literal_value(operand).map(|value| -value),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! 接口实现检查
+//!
+//! 类可以实现多个接口。接口中的 `default` 方法带有默认实现，类及其父类都没有声明同名方法时
+//! 由类继承：方法签名注入到类信息中，代码生成时以该类为 `this` 重新生成方法体。
+//! 同名方法在多个直接实现的接口中有默认实现（或一个接口给出默认实现、另一个只声明）时，
+//! 类必须自行覆盖该方法。`static` 接口方法只能通过接口名调用：`Shape.unit()`。
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::ast::*;
+use crate::types::{Type, MethodInfo};
+use crate::error::{cayResult, semantic_error, SourceLocation};
+use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
+
+impl SemanticAnalyzer {
+    /// 检查接口声明和类实现的接口，并让类继承接口的默认方法（父类先于子类处理）
+    pub fn check_interface_implementations(&mut self, program: &Program) -> cayResult<()> {
+        for interface in &program.interfaces {
+            self.check_interface_declaration(interface)?;
+        }
+
+        let classes: HashMap<&str, &ClassDecl> = program.classes.iter()
+            .filter(|c| c.type_params.is_empty())
+            .map(|c| (c.name.as_str(), c))
+            .collect();
+        let mut done = HashSet::new();
+        for class in program.classes.iter().filter(|c| c.type_params.is_empty()) {
+            self.inherit_default_methods(class, &classes, &mut done)?;
+        }
+        Ok(())
+    }
+
+    /// 泛型接口按实例单态化，不支持带方法体的方法
+    fn check_interface_declaration(&self, interface: &InterfaceDecl) -> cayResult<()> {
+        for method in &interface.methods {
+            let is_default = method.modifiers.contains(&Modifier::Default);
+            let is_static = method.modifiers.contains(&Modifier::Static);
+            if is_default && is_static {
+                return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_INTERFACE_METHOD,
+                    method.loc.line,
+                    method.loc.column,
+                    format!("Interface method '{}.{}' cannot be both default and static", interface.name, method.name)
+                ));
+            }
+            if (is_default || is_static) && !interface.type_params.is_empty() {
+                return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_INTERFACE_METHOD,
+                    method.loc.line,
+                    method.loc.column,
+                    format!(
+                        "Generic interface '{}' cannot declare {} method '{}'",
+                        interface.name, if is_default { "default" } else { "static" }, method.name
+                    )
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn inherit_default_methods(&mut self, class: &ClassDecl, classes: &HashMap<&str, &ClassDecl>, done: &mut HashSet<String>) -> cayResult<()> {
+        if !done.insert(class.name.clone()) {
+            return Ok(());
+        }
+        if let Some(parent) = class.parent.as_deref().and_then(|p| classes.get(p)) {
+            self.inherit_default_methods(parent, classes, done)?;
+        }
+
+        let interfaces = match self.type_registry.get_class(&class.name) {
+            Some(info) => info.interfaces.clone(),
+            None => return Ok(()),
+        };
+
+        // 方法名 -> 声明了该实例方法的直接接口、是否带默认实现以及方法签名
+        let mut providers: BTreeMap<String, Vec<(String, bool, MethodInfo)>> = BTreeMap::new();
+        for interface_name in &interfaces {
+            let interface = match self.type_registry.get_interface(interface_name) {
+                Some(interface) => interface,
+                None => {
+                    let message = if self.type_registry.class_exists(interface_name) {
+                        format!("Class '{}' implements class '{}'; use 'extends' to inherit from a class", class.name, interface_name)
+                    } else {
+                        format!("Class '{}' implements undefined interface '{}'", class.name, interface_name)
+                    };
+                    return Err(semantic_error(ErrorCodes::SEMANTIC_INHERITANCE_ERROR, class.loc.line, class.loc.column, message));
+                }
+            };
+            for method in interface.methods.values().filter(|m| !m.is_static) {
+                let is_default = interface.default_methods.contains(&method.name);
+                providers.entry(method.name.clone())
+                    .or_default()
+                    .push((interface_name.clone(), is_default, method.clone()));
+            }
+        }
+
+        for (method_name, sources) in providers {
+            // 类或父类中的方法优先于接口的默认实现
+            if self.type_registry.get_method(&class.name, &method_name).is_some() {
+                continue;
+            }
+            let (default_source, mut method) = match sources.iter().find(|(_, is_default, _)| *is_default) {
+                Some((interface_name, _, method)) => (interface_name.clone(), method.clone()),
+                None => continue,
+            };
+            if let Some((other, other_is_default, _)) = sources.iter().find(|(name, _, _)| *name != default_source) {
+                let message = if *other_is_default {
+                    format!(
+                        "Class '{}' inherits conflicting default methods '{}' from interfaces '{}' and '{}'; override '{}' in '{}'",
+                        class.name, method_name, default_source, other, method_name, class.name
+                    )
+                } else {
+                    format!(
+                        "Class '{}' inherits default method '{}' from interface '{}' that conflicts with abstract '{}' in interface '{}'; override '{}' in '{}'",
+                        class.name, method_name, default_source, method_name, other, method_name, class.name
+                    )
+                };
+                return Err(semantic_error(ErrorCodes::SEMANTIC_INTERFACE_IMPL_ERROR, class.loc.line, class.loc.column, message));
+            }
+
+            method.class_name = class.name.clone();
+            if let Some(class_info) = self.type_registry.classes.get_mut(&class.name) {
+                class_info.add_method(method);
+            }
+            self.type_registry.inherited_defaults
+                .entry(class.name.clone())
+                .or_default()
+                .push((default_source, method_name));
+        }
+        Ok(())
+    }
+
+    /// 方法是否实现了类（或父类）所实现接口中的同名同参方法，用于 @Override 检查
+    pub(super) fn overrides_interface_method(&self, class_name: &str, method: &MethodDecl) -> bool {
+        let mut current = self.type_registry.get_class(class_name);
+        while let Some(class_info) = current {
+            let declared = class_info.interfaces.iter()
+                .filter_map(|name| self.type_registry.get_interface(name))
+                .filter_map(|interface| interface.methods.get(&method.name))
+                .any(|m| {
+                    !m.is_static
+                        && m.params.len() == method.params.len()
+                        && m.params.iter().zip(method.params.iter()).all(|(a, b)| a.param_type == b.param_type)
+                });
+            if declared {
+                return true;
+            }
+            current = class_info.parent.as_deref().and_then(|p| self.type_registry.get_class(p));
+        }
+        false
+    }
+
+    /// 推断接口方法调用的类型
+    ///
+    /// `static_ref` 为 true 时调用写作 `Interface.method()`，只能调用 static 方法；
+    /// 否则接收者是接口类型的实例（或 default 方法中的 `this`），只能调用实例方法。
+    pub(super) fn infer_interface_method_call(&mut self, interface_name: &str, method_name: &str, call: &CallExpr, static_ref: bool) -> cayResult<Type> {
+        let method = self.interface_method(interface_name, method_name, &call.loc)?;
+        if static_ref && !method.is_static {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_INTERFACE_METHOD,
+                call.loc.line,
+                call.loc.column,
+                format!("Cannot call instance method '{}.{}' on the interface itself", interface_name, method_name)
+            ));
+        }
+        if !static_ref && method.is_static {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_INTERFACE_METHOD,
+                call.loc.line,
+                call.loc.column,
+                format!(
+                    "Static interface method '{}.{}' must be called through the interface name: {}.{}(...)",
+                    interface_name, method_name, interface_name, method_name
+                )
+            ));
+        }
+        if static_ref {
+            self.check_type_access(interface_name, &call.loc)?;
+        }
+        self.check_arguments_compatible(&call.args, &method.params, call.loc.line, call.loc.column)?;
+        Ok(method.return_type)
+    }
+
+    /// 接口方法体中不带接收者的调用: `area()` 即 `this.area()`
+    pub(super) fn infer_unqualified_interface_call(&mut self, method_name: &str, call: &CallExpr) -> cayResult<Option<Type>> {
+        let interface_name = match &self.current_class {
+            Some(name) if self.type_registry.interface_exists(name) => name.clone(),
+            _ => return Ok(None),
+        };
+        let is_static = match self.type_registry.get_interface_method(&interface_name, method_name) {
+            Some(method) => method.is_static,
+            None => return Ok(None),
+        };
+        if !is_static && self.current_method_is_static {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_STATIC_CONTEXT,
+                call.loc.line,
+                call.loc.column,
+                format!("Cannot call instance method '{}.{}' from a static interface method", interface_name, method_name)
+            ));
+        }
+        self.infer_interface_method_call(&interface_name, method_name, call, is_static).map(Some)
+    }
+
+    fn interface_method(&self, interface_name: &str, method_name: &str, loc: &SourceLocation) -> cayResult<MethodInfo> {
+        self.type_registry.get_interface_method(interface_name, method_name)
+            .cloned()
+            .ok_or_else(|| semantic_error(
+                ErrorCodes::SEMANTIC_METHOD_NOT_FOUND,
+                loc.line,
+                loc.column,
+                format!("Unknown method '{}' for interface {}", method_name, interface_name)
+            ))
+    }
+}
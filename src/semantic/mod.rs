@@ -10,6 +10,24 @@ mod class_analysis;
 mod type_check;
 mod expr_inference;
 mod type_utils;
+mod generics;
+mod enums;
+mod structs;
+mod closures;
+mod switches;
+mod packages;
+mod operators;
+mod interfaces;
+mod defer;
+mod integers;
+mod nullability;
+mod for_each;
+mod patterns;
+mod annotations;
+mod const_eval;
+mod properties;
+mod recovery;
+mod flow;
 
 // 公开导出
 pub use symbol_table::{SemanticSymbolTable, SemanticSymbolInfo};
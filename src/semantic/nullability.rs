@@ -0,0 +1,544 @@
+//! 空安全：可空类型 T?、安全调用 ?.、elvis 运算符 ?: 和基于控制流的 null 检查
+//!
+//! 引用类型默认不可为 null，`String?` 等可空类型与内部类型的表示相同，可空性记录在局部变量
+//! 的符号（`SemanticSymbolInfo::nullable`）以及字段、参数和返回类型的声明上。在
+//! `if (x != null)`、`x != null && ...`、`if (x == null) return;` 等条件之后，x 在相应的
+//! 分支和后续语句中视为非空（记录在 `non_null_locals` 中），对 x 重新赋值会更新这一状态。
+//!
+//! 检查只在启用 `null_safety` 特性（`-F=null_safety`）时进行：可能为 null 的值不能赋给、
+//! 传给或作为非空引用类型返回，也不能直接访问其成员或下标。`?.` 和 `?:` 不需要启用特性。
+
+use std::collections::HashSet;
+use crate::ast::*;
+use crate::types::{Type, SafeCallInfo};
+use crate::error::{cayResult, semantic_error};
+use super::analyzer::SemanticAnalyzer;
+use super::symbol_table::SemanticSymbolInfo;
+use crate::diagnostic::ErrorCodes;
+
+impl SemanticAnalyzer {
+    /// 是否启用了空安全检查
+    pub(super) fn null_safety_enabled(&self) -> bool {
+        self.features.contains(&"null_safety".to_string())
+    }
+
+    /// 推断安全调用 `obj?.member` 的类型
+    ///
+    /// 对象绑定到一个非空的临时变量后按普通成员访问推断。结果为基本类型时只能作为
+    /// elvis 的左操作数（`s?.length() ?: 0`），由 elvis 提供对象为 null 时的值。
+    pub(super) fn infer_safe_call_type(&mut self, safe_call: &SafeCallExpr, has_default: bool) -> cayResult<Type> {
+        let receiver = self.infer_expr_type(&safe_call.object)?;
+        if !receiver.is_reference_type() {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_NULL_SAFETY,
+                safe_call.loc.line,
+                safe_call.loc.column,
+                format!("Safe call '?.' requires a reference type, got {}", receiver)
+            ));
+        }
+
+        self.symbol_table.enter_scope();
+        self.symbol_table.declare(
+            safe_call.receiver_name(),
+            SemanticSymbolInfo {
+                name: safe_call.receiver_name(),
+                symbol_type: receiver.clone(),
+                is_final: true,
+                is_initialized: true,
+                nullable: false,
+                decl_loc: None,
+            }
+        );
+        let result = self.infer_expr_type(&safe_call.access_expr());
+        self.symbol_table.exit_scope();
+        let result = result?;
+
+        if !result.is_reference_type() && result != Type::Void && !has_default {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_NULL_SAFETY,
+                safe_call.loc.line,
+                safe_call.loc.column,
+                format!(
+                    "Safe call '?.{}' yields {}, which cannot be null; provide a default with '?:'",
+                    safe_call.member, result
+                )
+            ));
+        }
+        self.type_registry.safe_calls.insert(safe_call.loc.clone(), SafeCallInfo {
+            receiver,
+            result: result.clone(),
+        });
+        Ok(result)
+    }
+
+    /// 推断 elvis 表达式 `value ?: default` 的类型
+    pub(super) fn infer_elvis_type(&mut self, elvis: &ElvisExpr) -> cayResult<Type> {
+        let value_type = match elvis.value.as_ref() {
+            Expr::SafeCall(safe_call) => self.infer_safe_call_type(safe_call, true)?,
+            value => self.infer_expr_type(value)?,
+        };
+        let value_is_safe_call = matches!(elvis.value.as_ref(), Expr::SafeCall(_));
+        if !value_type.is_reference_type() && !value_is_safe_call {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_NULL_SAFETY,
+                elvis.loc.line,
+                elvis.loc.column,
+                format!("Left operand of '?:' has type {}, which is never null", value_type)
+            ));
+        }
+
+        let default_type = self.infer_expr_type_expecting(&elvis.default, &value_type)?;
+        if !self.types_compatible(&default_type, &value_type) {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_NULL_SAFETY,
+                elvis.loc.line,
+                elvis.loc.column,
+                format!("Default value of '?:' must be compatible with {}, got {}", value_type, default_type)
+            ));
+        }
+        self.type_registry.elvis_types.insert(elvis.loc.clone(), value_type.clone());
+        Ok(value_type)
+    }
+
+    /// 表达式的值是否可能为 null
+    pub(super) fn expr_may_be_null(&mut self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Literal(LiteralValue::Null) => true,
+            Expr::Identifier(ident) => {
+                if self.non_null_locals.contains(&ident.name) {
+                    return false;
+                }
+                match self.symbol_table.lookup(&ident.name) {
+                    Some(info) => info.nullable,
+                    None => self.current_class.clone()
+                        .and_then(|class_name| self.declared_field_type(&class_name, &ident.name))
+                        .is_some_and(|ty| ty.is_nullable()),
+                }
+            }
+            Expr::MemberAccess(member) => self.receiver_class(&member.object)
+                .and_then(|class_name| self.declared_field_type(&class_name, &member.member))
+                .is_some_and(|ty| ty.is_nullable()),
+            Expr::Call(call) => self.declared_signature(call)
+                .is_some_and(|(_, return_type)| return_type.is_nullable()),
+            Expr::SafeCall(_) => true,
+            Expr::Elvis(elvis) => self.expr_may_be_null(&elvis.default),
+            Expr::Ternary(ternary) => {
+                let (when_true, when_false) = self.null_checks(&ternary.condition);
+                self.with_non_null(&when_true, |analyzer| analyzer.expr_may_be_null(&ternary.true_branch))
+                    || self.with_non_null(&when_false, |analyzer| analyzer.expr_may_be_null(&ternary.false_branch))
+            }
+            Expr::Cast(cast) => cast.target_type.is_nullable(),
+            Expr::Assignment(assign) => self.expr_may_be_null(&assign.value),
+            _ => false,
+        }
+    }
+
+    /// 条件成立和不成立时分别可以确定不为 null 的变量
+    pub(super) fn null_checks(&self, condition: &Expr) -> (Vec<String>, Vec<String>) {
+        match condition {
+            Expr::Binary(bin) if matches!(bin.op, BinaryOp::Ne | BinaryOp::Eq) => {
+                let checked = match (bin.left.as_ref(), bin.right.as_ref()) {
+                    (Expr::Identifier(ident), Expr::Literal(LiteralValue::Null))
+                    | (Expr::Literal(LiteralValue::Null), Expr::Identifier(ident)) => vec![ident.name.clone()],
+                    _ => Vec::new(),
+                };
+                if bin.op == BinaryOp::Ne {
+                    (checked, Vec::new())
+                } else {
+                    (Vec::new(), checked)
+                }
+            }
+            Expr::Binary(bin) if bin.op == BinaryOp::And => {
+                let (left_true, left_false) = self.null_checks(&bin.left);
+                let (right_true, right_false) = self.null_checks(&bin.right);
+                let when_false = left_false.into_iter().filter(|name| right_false.contains(name)).collect();
+                (left_true.into_iter().chain(right_true).collect(), when_false)
+            }
+            Expr::Binary(bin) if bin.op == BinaryOp::Or => {
+                let (left_true, left_false) = self.null_checks(&bin.left);
+                let (right_true, right_false) = self.null_checks(&bin.right);
+                let when_true = left_true.into_iter().filter(|name| right_true.contains(name)).collect();
+                (when_true, left_false.into_iter().chain(right_false).collect())
+            }
+            Expr::Unary(unary) if unary.op == UnaryOp::Not => {
+                let (when_true, when_false) = self.null_checks(&unary.operand);
+                (when_false, when_true)
+            }
+            Expr::InstanceOf(instanceof) => match instanceof.expr.as_ref() {
+                Expr::Identifier(ident) => (vec![ident.name.clone()], Vec::new()),
+                _ => (Vec::new(), Vec::new()),
+            },
+            _ => (Vec::new(), Vec::new()),
+        }
+    }
+
+    /// 在 `names` 视为非空的状态下执行 `f`，之后恢复原来的状态
+    pub(super) fn with_non_null<R>(&mut self, names: &[String], f: impl FnOnce(&mut Self) -> R) -> R {
+        let saved = self.non_null_locals.clone();
+        self.non_null_locals.extend(names.iter().cloned());
+        let result = f(self);
+        self.non_null_locals = saved;
+        result
+    }
+
+    /// 推断 && 和 || 的右操作数：`x != null && x.length() > 0` 中右侧的 x 不为 null
+    pub(super) fn infer_logical_right_operand(&mut self, bin: &BinaryExpr) -> cayResult<Type> {
+        let (when_true, when_false) = self.null_checks(&bin.left);
+        let (bound_true, bound_false) = bin.left.pattern_bindings();
+        let (names, bindings) = match bin.op {
+            BinaryOp::And => (when_true, bound_true),
+            BinaryOp::Or => (when_false, bound_false),
+            _ => (Vec::new(), Vec::new()),
+        };
+        self.with_pattern_bindings(&bindings, |analyzer| {
+            analyzer.with_non_null(&names, |analyzer| analyzer.infer_expr_type(&bin.right))
+        })
+    }
+
+    /// 检查 if 语句：两个分支分别按条件收窄，之后的语句只保留两条路径上都成立的收窄
+    ///
+    /// 一个分支总是跳出（return、break、continue）时，if 之后的状态就是另一个分支结束时的状态，
+    /// 因此 `if (x == null) return;` 之后 x 不为 null。条件中 instanceof 引入的模式变量按同样的规则可见。
+    pub(super) fn type_check_if_narrowing(&mut self, if_stmt: &IfStmt, expected_return: Option<&Type>) -> cayResult<()> {
+        let (when_true, when_false) = self.null_checks(&if_stmt.condition);
+        let (bound_true, bound_false) = if_stmt.condition.pattern_bindings();
+        let saved = self.non_null_locals.clone();
+
+        self.non_null_locals.extend(when_true);
+        self.with_pattern_bindings(&bound_true, |analyzer| analyzer.type_check_branch(&if_stmt.then_branch, expected_return))?;
+        let after_then = std::mem::replace(&mut self.non_null_locals, saved);
+
+        self.non_null_locals.extend(when_false);
+        if let Some(else_branch) = &if_stmt.else_branch {
+            self.with_pattern_bindings(&bound_false, |analyzer| analyzer.type_check_branch(else_branch, expected_return))?;
+        }
+        let after_else = std::mem::take(&mut self.non_null_locals);
+
+        let then_exits = Self::always_exits(&if_stmt.then_branch);
+        let else_exits = if_stmt.else_branch.as_deref().is_some_and(Self::always_exits);
+        self.non_null_locals = if then_exits {
+            after_else
+        } else if else_exits {
+            after_then
+        } else {
+            after_then.intersection(&after_else).cloned().collect()
+        };
+        self.declare_escaping_pattern_bindings(if_stmt, then_exits, else_exits);
+        Ok(())
+    }
+
+    /// 循环体可能多次执行，进入循环前取消对循环中被赋值的变量的收窄
+    pub(super) fn forget_assigned_in(&mut self, body: &Stmt) {
+        let mut assigned = HashSet::new();
+        Self::collect_assigned_names(body, &mut assigned);
+        self.non_null_locals.retain(|name| !assigned.contains(name));
+    }
+
+    /// 检查局部变量的初始值，并记录可空变量是否已经确定不为 null
+    pub(super) fn check_nullable_initializer(&mut self, var: &VarDecl, var_type: &Type) {
+        let init = match &var.initializer {
+            Some(init) => init,
+            None => {
+                self.non_null_locals.remove(&var.name);
+                return;
+            }
+        };
+        let may_be_null = self.expr_may_be_null(init);
+        if may_be_null && self.null_safety_enabled() && Self::is_non_null_reference(&var.var_type, var_type) {
+            let message = format!(
+                "Cannot assign {} to non-null variable '{}' of type {}; declare it as {}?",
+                Self::describe_nullable_value(init), var.name, var_type, var_type
+            );
+            self.errors.push(self.create_error_info(ErrorCodes::SEMANTIC_NULL_SAFETY, var.loc.line, var.loc.column, message));
+        }
+        if var.var_type.is_nullable() && !may_be_null {
+            self.non_null_locals.insert(var.name.clone());
+        } else {
+            self.non_null_locals.remove(&var.name);
+        }
+    }
+
+    /// 检查 return 的值是否符合方法的可空性
+    pub(super) fn check_nullable_return(&mut self, value: &Expr, declared_return: &Type) {
+        if !self.null_safety_enabled() || declared_return.is_nullable() || !declared_return.is_reference_type() {
+            return;
+        }
+        if self.expr_may_be_null(value) {
+            let (line, column) = self.get_expr_location(value);
+            let method = self.current_method.clone().unwrap_or_default();
+            let message = format!(
+                "Cannot return {} from '{}' whose return type {} is non-null; declare it as {}?",
+                Self::describe_nullable_value(value), method, declared_return, declared_return
+            );
+            self.errors.push(self.create_error_info(ErrorCodes::SEMANTIC_NULL_SAFETY, line, column, message));
+        }
+    }
+
+    /// 检查赋值，并根据赋入的值更新变量的收窄状态
+    pub(super) fn check_nullable_assignment(&mut self, assign: &AssignmentExpr, target_type: &Type) {
+        if assign.op != AssignOp::Assign {
+            return;
+        }
+        let may_be_null = self.expr_may_be_null(&assign.value);
+        if let Expr::Identifier(ident) = assign.target.as_ref() {
+            if may_be_null {
+                self.non_null_locals.remove(&ident.name);
+            } else {
+                self.non_null_locals.insert(ident.name.clone());
+            }
+        }
+        if !may_be_null || !self.null_safety_enabled() || !target_type.is_reference_type() {
+            return;
+        }
+        let (target, declared) = match assign.target.as_ref() {
+            Expr::Identifier(ident) => {
+                let declared_nullable = match self.symbol_table.lookup(&ident.name) {
+                    Some(info) => info.nullable,
+                    None => self.current_class.clone()
+                        .and_then(|class_name| self.declared_field_type(&class_name, &ident.name))
+                        .is_some_and(|ty| ty.is_nullable()),
+                };
+                (ident.name.clone(), declared_nullable)
+            }
+            Expr::MemberAccess(member) => {
+                let declared_nullable = self.receiver_class(&member.object)
+                    .and_then(|class_name| self.declared_field_type(&class_name, &member.member))
+                    .is_some_and(|ty| ty.is_nullable());
+                (Self::describe_expr(&assign.target), declared_nullable)
+            }
+            _ => return,
+        };
+        if !declared {
+            let message = format!(
+                "Cannot assign {} to non-null variable '{}' of type {}; declare it as {}?",
+                Self::describe_nullable_value(&assign.value), target, target_type, target_type
+            );
+            self.errors.push(self.create_error_info(ErrorCodes::SEMANTIC_NULL_SAFETY, assign.loc.line, assign.loc.column, message));
+        }
+    }
+
+    /// 检查可能为 null 的值是否被直接解引用（访问成员、调用方法或取下标）
+    pub(super) fn check_dereference(&mut self, object: &Expr) {
+        if !self.null_safety_enabled() || !self.expr_may_be_null(object) {
+            return;
+        }
+        let (line, column) = self.get_expr_location(object);
+        let message = format!(
+            "Possible null dereference: '{}' is nullable; check it for null or use '?.'",
+            Self::describe_expr(object)
+        );
+        self.errors.push(self.create_error_info(ErrorCodes::SEMANTIC_NULL_SAFETY, line, column, message));
+    }
+
+    /// 检查方法调用的实参是否符合参数的可空性
+    pub(super) fn check_nullable_arguments(&mut self, call: &CallExpr) {
+        if !self.null_safety_enabled() {
+            return;
+        }
+        if let Some((params, _)) = self.declared_signature(call) {
+            let callee = Self::describe_expr(&call.callee);
+            self.check_arguments_against(&callee, &params, &call.args);
+        }
+    }
+
+    /// 检查构造函数调用的实参是否符合参数的可空性
+    pub(super) fn check_nullable_constructor_arguments(&mut self, new_expr: &NewExpr) {
+        if !self.null_safety_enabled() {
+            return;
+        }
+        let program = match &self.program {
+            Some(program) => program.clone(),
+            None => return,
+        };
+        let params = program.classes.iter()
+            .filter(|class| class.name == new_expr.class_name)
+            .flat_map(|class| class.members.iter())
+            .find_map(|member| match member {
+                ClassMember::Constructor(ctor) if ctor.params.len() == new_expr.args.len() => {
+                    Some(ctor.params.iter().map(|p| p.param_type.clone()).collect::<Vec<_>>())
+                }
+                _ => None,
+            });
+        if let Some(params) = params {
+            self.check_arguments_against(&new_expr.class_name, &params, &new_expr.args);
+        }
+    }
+
+    fn check_arguments_against(&mut self, callee: &str, params: &[Type], args: &[Expr]) {
+        for (i, (param, arg)) in params.iter().zip(args).enumerate() {
+            if param.is_nullable() || !matches!(param, Type::String | Type::Object(_) | Type::Array(_) | Type::Generic(_, _)) {
+                continue;
+            }
+            if self.expr_may_be_null(arg) {
+                let (line, column) = self.get_expr_location(arg);
+                let message = format!(
+                    "Cannot pass {} as argument {} of '{}', which expects non-null {}",
+                    Self::describe_nullable_value(arg), i + 1, callee, param
+                );
+                self.errors.push(self.create_error_info(ErrorCodes::SEMANTIC_NULL_SAFETY, line, column, message));
+            }
+        }
+    }
+
+    /// 声明类型不可空的引用类型（`declared` 为源代码中的类型，`resolved` 为解析后的类型）
+    fn is_non_null_reference(declared: &Type, resolved: &Type) -> bool {
+        !declared.is_nullable() && resolved.is_reference_type()
+    }
+
+    /// 对象表达式的类名（用于查找字段和方法的声明）
+    fn receiver_class(&mut self, object: &Expr) -> Option<String> {
+        match self.infer_expr_type(object).ok()? {
+            Type::Object(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// 被调用方法声明中的参数类型和返回类型（保留 `T?` 标记）
+    fn declared_signature(&mut self, call: &CallExpr) -> Option<(Vec<Type>, Type)> {
+        let program = self.program.clone()?;
+        let method = match call.callee.as_ref() {
+            Expr::MemberAccess(member) => {
+                let class_name = self.receiver_class(&member.object)?;
+                self.declared_method(&class_name, &member.member, call.args.len())
+            }
+            Expr::Identifier(ident) => {
+                let in_class = self.current_class.clone()
+                    .and_then(|class_name| self.declared_method(&class_name, &ident.name, call.args.len()));
+                if in_class.is_some() {
+                    in_class
+                } else {
+                    return program.top_level_functions.iter()
+                        .find(|f| f.name == ident.name && f.params.len() == call.args.len())
+                        .map(|f| (f.params.iter().map(|p| p.param_type.clone()).collect(), f.return_type.clone()));
+                }
+            }
+            _ => None,
+        }?;
+        Some((method.params.iter().map(|p| p.param_type.clone()).collect(), method.return_type))
+    }
+
+    /// 在类（及其父类）的声明中按名称和参数个数查找方法
+    fn declared_method(&self, class_name: &str, method_name: &str, arg_count: usize) -> Option<MethodDecl> {
+        let program = self.program.as_ref()?;
+        let mut current = Some(self.template_name(class_name));
+        while let Some(name) = current {
+            let class = program.classes.iter().find(|c| c.name == name)?;
+            let found = class.members.iter().find_map(|member| match member {
+                ClassMember::Method(method) if method.name == method_name && method.params.len() == arg_count => Some(method.clone()),
+                _ => None,
+            });
+            if found.is_some() {
+                return found;
+            }
+            current = class.parent.clone();
+        }
+        None
+    }
+
+    /// 在类（及其父类）的声明中查找字段的声明类型
+    fn declared_field_type(&self, class_name: &str, field_name: &str) -> Option<Type> {
+        let program = self.program.as_ref()?;
+        let mut current = Some(self.template_name(class_name));
+        while let Some(name) = current {
+            let class = program.classes.iter().find(|c| c.name == name)?;
+            let found = class.members.iter().find_map(|member| match member {
+                ClassMember::Field(field) if field.name == field_name => Some(field.field_type.clone()),
+                _ => None,
+            });
+            if found.is_some() {
+                return found;
+            }
+            current = class.parent.clone();
+        }
+        None
+    }
+
+    /// 泛型实例（Box_String）对应的模板名，其他类原样返回
+    fn template_name(&self, class_name: &str) -> String {
+        self.type_registry.generic_instances.get(class_name)
+            .map(|(origin, _)| origin.clone())
+            .unwrap_or_else(|| class_name.to_string())
+    }
+
+    /// 语句执行后是否一定不会继续执行下一条语句
+    fn always_exits(stmt: &Stmt) -> bool {
+        match stmt {
+            Stmt::Return(_) | Stmt::Break(_) | Stmt::Continue(_) => true,
+            Stmt::Block(block) => block.statements.last().is_some_and(Self::always_exits),
+            Stmt::If(if_stmt) => {
+                Self::always_exits(&if_stmt.then_branch)
+                    && if_stmt.else_branch.as_deref().is_some_and(Self::always_exits)
+            }
+            _ => false,
+        }
+    }
+
+    /// 收集语句中被赋值的变量名
+    fn collect_assigned_names(stmt: &Stmt, names: &mut HashSet<String>) {
+        match stmt {
+            Stmt::Expr(Expr::Assignment(assign)) => {
+                if let Expr::Identifier(ident) = assign.target.as_ref() {
+                    names.insert(ident.name.clone());
+                }
+            }
+            Stmt::Block(block) => {
+                for stmt in &block.statements {
+                    Self::collect_assigned_names(stmt, names);
+                }
+            }
+            Stmt::If(if_stmt) => {
+                Self::collect_assigned_names(&if_stmt.then_branch, names);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    Self::collect_assigned_names(else_branch, names);
+                }
+            }
+            Stmt::While(while_stmt) => Self::collect_assigned_names(&while_stmt.body, names),
+            Stmt::DoWhile(do_while) => Self::collect_assigned_names(&do_while.body, names),
+            Stmt::ForEach(for_each) => Self::collect_assigned_names(&for_each.body, names),
+            Stmt::For(for_stmt) => {
+                if let Some(update) = &for_stmt.update {
+                    Self::collect_assigned_names(&Stmt::Expr(update.clone()), names);
+                }
+                Self::collect_assigned_names(&for_stmt.body, names);
+            }
+            Stmt::Switch(switch_stmt) => {
+                for case in &switch_stmt.cases {
+                    for stmt in &case.body {
+                        Self::collect_assigned_names(stmt, names);
+                    }
+                }
+                if let Some(default) = &switch_stmt.default {
+                    for stmt in default {
+                        Self::collect_assigned_names(stmt, names);
+                    }
+                }
+            }
+            Stmt::Scope(scope_stmt) => {
+                for stmt in &scope_stmt.body.statements {
+                    Self::collect_assigned_names(stmt, names);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 错误信息中可空值的描述：null 字面量或 nullable value 'x'
+    fn describe_nullable_value(expr: &Expr) -> String {
+        match expr {
+            Expr::Literal(LiteralValue::Null) => "null".to_string(),
+            _ => format!("nullable value '{}'", Self::describe_expr(expr)),
+        }
+    }
+
+    /// 错误信息中表达式的简短描述
+    fn describe_expr(expr: &Expr) -> String {
+        match expr {
+            Expr::Identifier(ident) => ident.name.clone(),
+            Expr::MemberAccess(member) => format!("{}.{}", Self::describe_expr(&member.object), member.member),
+            Expr::Call(call) => format!("{}()", Self::describe_expr(&call.callee)),
+            Expr::SafeCall(safe_call) => format!("{}?.{}", Self::describe_expr(&safe_call.object), safe_call.member),
+            Expr::Literal(LiteralValue::Null) => "null".to_string(),
+            _ => "expression".to_string(),
+        }
+    }
+}
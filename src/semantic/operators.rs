@@ -0,0 +1,207 @@
+//! 运算符重载
+//!
+//! 左操作数是类实例时，运算符解析为该类（或其父类）的实例方法，代码生成时降级为普通方法调用：
+//!
+//! | 运算符 | 方法 |
+//! |--------|------|
+//! | `a + b`、`a - b`、`a * b`、`a / b`、`a % b` | `plus`、`minus`、`times`、`div`、`rem` |
+//! | `-a` | `negate()` |
+//! | `a == b`、`a != b` | `bool equals(T other)`，`!=` 取反 |
+//! | `a < b`、`a <= b`、`a > b`、`a >= b` | `int compareTo(T other)` 与 0 比较 |
+//! | `a[i]`、`a[i] = v` | `get(i)`、`set(i, v)` |
+//!
+//! 方法既可以用 Java 风格的名字声明，也可以写成 `operator+`、`operator==`、`operator[]` 等形式。
+//! 类声明了 `equals` 时 `==` 不再比较引用；与 `null` 比较始终比较引用。
+
+use crate::ast::*;
+use crate::types::{Type, MethodInfo, OperatorCall};
+use crate::error::{cayResult, semantic_error, SourceLocation};
+use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
+
+impl SemanticAnalyzer {
+    /// 解析二元运算符重载，返回运算结果类型；左操作数不是类实例或不适用重载时返回 None
+    pub(super) fn infer_binary_operator_call(&mut self, bin: &BinaryExpr, left_type: &Type, right_type: &Type) -> cayResult<Option<Type>> {
+        let class_name = match self.operator_class(left_type) {
+            Some(class_name) => class_name,
+            None => return Ok(None),
+        };
+        let (method_name, operator) = match bin.op {
+            BinaryOp::Add => ("plus", "+"),
+            BinaryOp::Sub => ("minus", "-"),
+            BinaryOp::Mul => ("times", "*"),
+            BinaryOp::Div => ("div", "/"),
+            BinaryOp::Mod => ("rem", "%"),
+            BinaryOp::Eq => ("equals", "=="),
+            BinaryOp::Ne => ("equals", "!="),
+            BinaryOp::Lt => ("compareTo", "<"),
+            BinaryOp::Le => ("compareTo", "<="),
+            BinaryOp::Gt => ("compareTo", ">"),
+            BinaryOp::Ge => ("compareTo", ">="),
+            _ => return Ok(None),
+        };
+        let is_equality = matches!(bin.op, BinaryOp::Eq | BinaryOp::Ne);
+        let is_comparison = matches!(bin.op, BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge);
+
+        // 与 null 比较以及未声明 equals 的类之间的比较仍然比较引用
+        if is_equality
+            && (matches!(bin.right.as_ref(), Expr::Literal(LiteralValue::Null))
+                || self.type_registry.get_method(&class_name, method_name).is_none())
+        {
+            return Ok(None);
+        }
+
+        let method = self.resolve_operator_method(
+            &class_name,
+            method_name,
+            operator,
+            std::slice::from_ref(right_type),
+            &bin.loc,
+        )?;
+
+        let result_type = if is_equality {
+            self.expect_operator_return(&method, &Type::Bool, &bin.loc)?;
+            Type::Bool
+        } else if is_comparison {
+            self.expect_operator_return(&method, &Type::Int32, &bin.loc)?;
+            Type::Bool
+        } else {
+            method.return_type.clone()
+        };
+        self.record_operator_call(&bin.loc, method, result_type.clone());
+        Ok(Some(result_type))
+    }
+
+    /// 解析一元负号重载 `-a`
+    pub(super) fn infer_unary_operator_call(&mut self, unary: &UnaryExpr, operand_type: &Type) -> cayResult<Option<Type>> {
+        if unary.op != UnaryOp::Neg {
+            return Ok(None);
+        }
+        let class_name = match self.operator_class(operand_type) {
+            Some(class_name) => class_name,
+            None => return Ok(None),
+        };
+        let method = self.resolve_operator_method(&class_name, "negate", "unary -", &[], &unary.loc)?;
+        let result_type = method.return_type.clone();
+        self.record_operator_call(&unary.loc, method, result_type.clone());
+        Ok(Some(result_type))
+    }
+
+    /// 解析下标读取重载 `a[i]`
+    pub(super) fn infer_index_operator_call(&mut self, arr: &ArrayAccessExpr, object_type: &Type, index_type: &Type) -> cayResult<Option<Type>> {
+        let class_name = match self.operator_class(object_type) {
+            Some(class_name) => class_name,
+            None => return Ok(None),
+        };
+        let method = self.resolve_operator_method(&class_name, "get", "[]", std::slice::from_ref(index_type), &arr.loc)?;
+        let result_type = method.return_type.clone();
+        self.record_operator_call(&arr.loc, method, result_type.clone());
+        Ok(Some(result_type))
+    }
+
+    /// 解析下标赋值重载 `a[i] = v`，调用记录在赋值表达式的位置
+    pub(super) fn infer_index_assignment_call(&mut self, assign: &AssignmentExpr) -> cayResult<Option<Type>> {
+        let arr = match assign.target.as_ref() {
+            Expr::ArrayAccess(arr) => arr,
+            _ => return Ok(None),
+        };
+        let object_type = self.infer_expr_type(&arr.array)?;
+        let class_name = match self.operator_class(&object_type) {
+            Some(class_name) => class_name,
+            None => return Ok(None),
+        };
+        if assign.op != AssignOp::Assign {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_OPERATOR_OVERLOAD,
+                assign.loc.line,
+                assign.loc.column,
+                format!("Compound assignment is not supported on indexed {}; write 'a[i] = a[i] op v' instead", class_name)
+            ));
+        }
+        let index_type = self.infer_expr_type(&arr.index)?;
+        let value_type = self.infer_expr_type(&assign.value)?;
+        let method = self.resolve_operator_method(&class_name, "set", "[]=", &[index_type, value_type.clone()], &assign.loc)?;
+        self.record_operator_call(&assign.loc, method, value_type.clone());
+        Ok(Some(value_type))
+    }
+
+    /// 可以重载运算符的类：类型是已注册的类
+    fn operator_class(&self, operand_type: &Type) -> Option<String> {
+        match operand_type {
+            Type::Object(class_name) if self.type_registry.class_exists(class_name) => Some(class_name.clone()),
+            _ => None,
+        }
+    }
+
+    /// 查找运算符对应的实例方法
+    fn resolve_operator_method(&self, class_name: &str, method_name: &str, operator: &str, arg_types: &[Type], loc: &SourceLocation) -> cayResult<MethodInfo> {
+        if self.type_registry.get_method(class_name, method_name).is_none() {
+            let declaration = match operator_symbol(method_name) {
+                Some(symbol) => format!("declare 'operator{}' or a method named '{}'", symbol, method_name),
+                None => format!("declare a method named '{}'", method_name),
+            };
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_OPERATOR_OVERLOAD,
+                loc.line,
+                loc.column,
+                format!("Operator {} is not defined for {}: {}", operator, class_name, declaration)
+            ));
+        }
+        let method = self.type_registry.find_method(class_name, method_name, arg_types).ok_or_else(|| {
+            let args: Vec<String> = arg_types.iter().map(|t| t.to_string()).collect();
+            semantic_error(
+                ErrorCodes::SEMANTIC_OPERATOR_OVERLOAD,
+                loc.line,
+                loc.column,
+                format!(
+                    "Operator {} on {}: no overload of '{}' accepts ({})",
+                    operator, class_name, method_name, args.join(", ")
+                )
+            )
+        })?;
+        self.check_member_access(&method.class_name, &method.name, method.is_public, method.is_protected, loc)?;
+        if method.is_static {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_OPERATOR_OVERLOAD,
+                loc.line,
+                loc.column,
+                format!("Operator method '{}.{}' must not be static", method.class_name, method.name)
+            ));
+        }
+        Ok(method.clone())
+    }
+
+    fn expect_operator_return(&self, method: &MethodInfo, expected: &Type, loc: &SourceLocation) -> cayResult<()> {
+        if method.return_type == *expected {
+            return Ok(());
+        }
+        Err(semantic_error(
+            ErrorCodes::SEMANTIC_OPERATOR_OVERLOAD,
+            loc.line,
+            loc.column,
+            format!(
+                "'{}.{}' must return {} to be used as an operator, but returns {}",
+                method.class_name, method.name, expected, method.return_type
+            )
+        ))
+    }
+
+    fn record_operator_call(&mut self, loc: &SourceLocation, method: MethodInfo, result_type: Type) {
+        self.type_registry.operator_calls.insert(loc.clone(), OperatorCall { method, result_type });
+    }
+}
+
+/// 方法名对应的 `operator` 声明形式，用于错误提示；compareTo 只能以方法名声明
+fn operator_symbol(method_name: &str) -> Option<&'static str> {
+    match method_name {
+        "plus" => Some("+"),
+        "minus" | "negate" => Some("-"),
+        "times" => Some("*"),
+        "div" => Some("/"),
+        "rem" => Some("%"),
+        "equals" => Some("=="),
+        "get" => Some("[]"),
+        "set" => Some("[]="),
+        _ => None,
+    }
+}
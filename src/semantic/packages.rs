@@ -0,0 +1,85 @@
+//! 包可见性检查
+//!
+//! 同一个包（包括默认包）内的访问规则不变。跨包访问时，未标记 `public` 的类型、
+//! 方法和构造函数只在所属包内可见，`protected` 方法另外对子类可见。
+
+use crate::ast::{Modifier, Program};
+use crate::error::{cayError, cayResult, semantic_error, SourceLocation};
+use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
+
+impl SemanticAnalyzer {
+    /// 登记包中声明的类型所属的包，以及只在包内可见的类型
+    pub(super) fn register_packages(&mut self, program: &Program) {
+        for (name, package) in &program.declaration_packages {
+            self.type_registry.type_packages.insert(name.clone(), package.clone());
+        }
+        let package_private = program.classes.iter().map(|c| (&c.name, &c.modifiers))
+            .chain(program.interfaces.iter().map(|i| (&i.name, &i.modifiers)))
+            .chain(program.enums.iter().map(|e| (&e.name, &e.modifiers)))
+            .chain(program.structs.iter().map(|s| (&s.name, &s.modifiers)))
+            .filter(|(_, modifiers)| !modifiers.contains(&Modifier::Public));
+        for (name, _) in package_private {
+            self.type_registry.package_private_types.insert(name.clone());
+        }
+    }
+
+    /// 当前代码所在的包
+    fn current_package(&self) -> &str {
+        match &self.current_class {
+            Some(class_name) => self.type_registry.package_of(class_name),
+            None => "",
+        }
+    }
+
+    /// 检查当前代码能否使用类型 `type_name`
+    pub(super) fn check_type_access(&self, type_name: &str, loc: &SourceLocation) -> cayResult<()> {
+        self.check_nested_class_access(type_name, loc)?;
+        let package = self.type_registry.package_of(type_name);
+        if package == self.current_package()
+            || !self.type_registry.package_private_types.contains(self.generic_origin(type_name))
+        {
+            return Ok(());
+        }
+        Err(semantic_error(
+            ErrorCodes::SEMANTIC_ACCESS_VIOLATION,
+            loc.line,
+            loc.column,
+            format!(
+                "{} is not public in {}; cannot be accessed from outside package",
+                self.generic_origin(type_name),
+                package
+            )
+        ))
+    }
+
+    /// 检查当前代码能否调用 `owner` 中的方法或构造函数 `member`
+    pub(super) fn check_member_access(&self, owner: &str, member: &str, is_public: bool, is_protected: bool, loc: &SourceLocation) -> cayResult<()> {
+        if is_public || self.type_registry.package_of(owner) == self.current_package() {
+            return Ok(());
+        }
+        let in_subclass = self.current_class.as_deref()
+            .is_some_and(|current| self.is_subtype_of(current, owner));
+        if is_protected && in_subclass {
+            return Ok(());
+        }
+
+        let owner_name = self.generic_origin(owner);
+        let message = if is_protected {
+            format!("{} has protected access in {}", member, owner_name)
+        } else {
+            format!("{} is not public in {}; cannot be accessed from outside package", member, owner_name)
+        };
+        Err(semantic_error(ErrorCodes::SEMANTIC_ACCESS_VIOLATION, loc.line, loc.column, message))
+    }
+
+    /// 是否是包可见性检查产生的错误（print 参数中的其他推断错误会被忽略，这类错误仍需报告）
+    pub(super) fn is_package_access_error(err: &cayError) -> bool {
+        match err {
+            cayError::Semantic { message, .. } => {
+                message.ends_with("cannot be accessed from outside package") || message.contains(" has protected access in ")
+            }
+            _ => false,
+        }
+    }
+}
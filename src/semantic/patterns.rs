@@ -0,0 +1,122 @@
+//! 模式变量的作用域
+//!
+//! `o instanceof Circle c` 中的 c 只在条件成立的代码中可见：if 的 then 分支、while/for 的循环体、
+//! `&&` 的右操作数和三元表达式的真分支；条件取反时对应 else 分支、`||` 的右操作数和假分支。
+//! then 分支总是跳出时（`if (!(o instanceof Circle c)) return;`），c 在 if 之后仍然可见。
+//! switch 的类型模式 `case Circle c` 的变量只在该分支中可见。
+
+use std::collections::HashSet;
+use crate::ast::*;
+use crate::types::Type;
+use crate::error::SourceLocation;
+use super::analyzer::SemanticAnalyzer;
+use super::symbol_table::SemanticSymbolInfo;
+use crate::diagnostic::ErrorCodes;
+
+impl SemanticAnalyzer {
+    /// 在声明了 `bindings` 中模式变量的作用域内执行 `f`
+    pub(super) fn with_pattern_bindings<R>(&mut self, bindings: &[&InstanceOfExpr], f: impl FnOnce(&mut Self) -> R) -> R {
+        if bindings.is_empty() {
+            return f(self);
+        }
+        self.symbol_table.enter_scope();
+        self.declare_pattern_bindings(bindings);
+        let result = f(self);
+        self.symbol_table.exit_scope();
+        result
+    }
+
+    /// 在当前作用域声明 instanceof 引入的模式变量
+    pub(super) fn declare_pattern_bindings(&mut self, bindings: &[&InstanceOfExpr]) {
+        for instanceof in bindings {
+            if let Some(binding) = &instanceof.binding {
+                self.symbol_table.declare(binding.clone(), Self::pattern_symbol(binding, &instanceof.target_type));
+            }
+        }
+    }
+
+    /// if 语句之后仍然可见的模式变量：一个分支总是跳出时，if 之后的代码只能从另一个分支到达
+    pub(super) fn declare_escaping_pattern_bindings(&mut self, if_stmt: &IfStmt, then_exits: bool, else_exits: bool) {
+        let (when_true, when_false) = if_stmt.condition.pattern_bindings();
+        let escaping = match (then_exits, else_exits) {
+            (true, false) => when_false,
+            (false, true) => when_true,
+            _ => Vec::new(),
+        };
+        self.declare_pattern_bindings(&escaping);
+    }
+
+    /// 检查模式变量不与可见的局部变量或参数重名
+    pub(super) fn check_pattern_variable_name(&mut self, name: &str, loc: &SourceLocation) {
+        if self.symbol_table.lookup(name).is_some() {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
+                loc.line,
+                loc.column,
+                format!("Pattern variable '{}' is already defined in this scope", name),
+            ));
+        }
+    }
+
+    /// 在当前作用域声明 switch 类型模式的变量
+    pub(super) fn declare_pattern_variable(&mut self, name: &str, target_type: &Type, loc: &SourceLocation) {
+        self.check_pattern_variable_name(name, loc);
+        self.symbol_table.declare(name.to_string(), Self::pattern_symbol(name, target_type));
+    }
+
+    fn pattern_symbol(name: &str, target_type: &Type) -> SemanticSymbolInfo {
+        SemanticSymbolInfo {
+            name: name.to_string(),
+            symbol_type: target_type.clone(),
+            is_final: false,
+            is_initialized: true,
+            nullable: false,
+            decl_loc: None,
+        }
+    }
+
+    /// 静态类型为 `value_type` 的值是否可能是 `pattern_type` 的实例
+    ///
+    /// 两者有继承关系时可能匹配；类与接口之间，子类可能实现该接口，但 sealed 接口
+    /// 只能由 permits 列出的类及其子类实现。
+    pub(super) fn types_may_match(&self, value_type: &str, pattern_type: &str) -> bool {
+        if self.is_subtype_of(value_type, pattern_type) || self.is_subtype_of(pattern_type, value_type) {
+            return true;
+        }
+        match (self.type_registry.interface_exists(value_type), self.type_registry.interface_exists(pattern_type)) {
+            (false, false) => false,
+            (true, true) => true,
+            (true, false) => self.may_implement(pattern_type, value_type),
+            (false, true) => self.may_implement(value_type, pattern_type),
+        }
+    }
+
+    /// 类 `class_name` 或它的某个子类是否可能实现接口 `interface_name`
+    fn may_implement(&self, class_name: &str, interface_name: &str) -> bool {
+        match self.type_registry.sealed_permits(interface_name) {
+            Some(permits) => permits.iter()
+                .any(|permitted| self.is_subtype_of(permitted, class_name) || self.is_subtype_of(class_name, permitted)),
+            None => true,
+        }
+    }
+
+    /// 类或接口名（不含内置的 String 等）
+    pub(super) fn is_reference_type_name(&self, name: &str) -> bool {
+        self.type_registry.class_exists(name) || self.type_registry.interface_exists(name)
+    }
+
+    /// 无守卫的类型模式未覆盖的类型；sealed 接口和抽象 sealed 类没有自身的实例，
+    /// 它们的值由 permits 列出的子类覆盖
+    pub(super) fn uncovered_types(&self, type_name: &str, covered: &HashSet<String>) -> Vec<String> {
+        if covered.iter().any(|pattern_type| self.is_subtype_of(type_name, pattern_type)) {
+            return Vec::new();
+        }
+        let has_own_instances = self.type_registry.get_class(type_name).is_some_and(|class| !class.is_abstract);
+        match self.type_registry.sealed_permits(type_name) {
+            Some(permits) if !has_own_instances => permits.iter()
+                .flat_map(|permitted| self.uncovered_types(permitted, covered))
+                .collect(),
+            _ => vec![type_name.to_string()],
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! 属性访问
+//!
+//! 读取 `obj.count` 调用 getter，赋值 `obj.count = v` 调用 setter，两个访问器的访问权限分别检查。
+//! 自动属性的访问器只是读写同名的私有字段，代码生成直接读写该字段（即内联访问器）；
+//! 其余访问记录在 `TypeRegistry::property_reads` / `property_writes` 中，由代码生成降级为访问方法调用。
+//! 只有 get 的自动属性是只读的，只能在本类的构造函数中赋值。
+//! 类体中可以直接用属性名访问属性，等同于 `this.name`（静态属性为 `ClassName.name`）。
+
+use crate::ast::*;
+use crate::error::{cayResult, semantic_error, SourceLocation};
+use crate::types::{AccessorInfo, PropertyCall, PropertyInfo, Type};
+use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
+
+impl SemanticAnalyzer {
+    /// 从属性声明生成类型注册表中的属性信息
+    pub(super) fn property_info(property: &PropertyDecl) -> PropertyInfo {
+        let accessor_info = |accessor: &PropertyAccessor, prefix: &str| {
+            // 访问器没有访问修饰符时沿用属性的
+            let modifiers = if accessor.modifiers.is_empty() { &property.modifiers } else { &accessor.modifiers };
+            AccessorInfo {
+                method_name: property.accessor_name(prefix),
+                is_public: modifiers.contains(&Modifier::Public),
+                is_private: modifiers.contains(&Modifier::Private),
+                is_protected: modifiers.contains(&Modifier::Protected),
+            }
+        };
+        PropertyInfo {
+            name: property.name.clone(),
+            property_type: property.property_type.clone(),
+            is_static: property.modifiers.contains(&Modifier::Static),
+            is_auto: property.is_auto(),
+            getter: property.getter.as_ref().map(|getter| accessor_info(getter, "get")),
+            setter: property.setter.as_ref().map(|setter| accessor_info(setter, "set")),
+            annotations: property.annotations.clone(),
+        }
+    }
+
+    /// 检查属性生成的访问方法没有与显式声明的方法重名
+    pub(super) fn check_properties(&mut self, program: &Program) {
+        for class in &program.classes {
+            for member in &class.members {
+                let property = match member {
+                    ClassMember::Property(property) => property,
+                    _ => continue,
+                };
+                let accessors = [
+                    (property.getter.is_some(), property.accessor_name("get"), 0),
+                    (property.setter.is_some(), property.accessor_name("set"), 1),
+                ];
+                for (declared, method_name, param_count) in accessors {
+                    let same_signature = class.members.iter()
+                        .filter(|m| matches!(m, ClassMember::Method(method) if method.name == method_name && method.params.len() == param_count))
+                        .count();
+                    if declared && same_signature > 1 {
+                        self.errors.push(self.create_error_info(
+                            ErrorCodes::SEMANTIC_PROPERTY_ERROR,
+                            property.loc.line,
+                            property.loc.column,
+                            format!("Property '{}.{}' conflicts with method '{}'; its accessor is generated with that name", class.name, property.name, method_name),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// 读取类 `class_name` 的属性 `member.member`，不是属性时返回 None
+    ///
+    /// `is_class_access` 表示通过类名访问（ClassName.name）。
+    pub(super) fn infer_property_read(&mut self, class_name: &str, member: &MemberAccessExpr, is_class_access: bool) -> cayResult<Option<Type>> {
+        let (owner, property) = match self.find_property(class_name, &member.member) {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        self.check_property_context(&property, is_class_access, &member.loc)?;
+        let getter = match &property.getter {
+            Some(getter) => getter.clone(),
+            None => {
+                return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_PROPERTY_ERROR,
+                    member.loc.line,
+                    member.loc.column,
+                    format!("Property '{}.{}' has no getter", owner, property.name),
+                ));
+            }
+        };
+        self.check_accessor_access(&owner, &property, &getter, "getter", &member.loc)?;
+        self.warn_if_deprecated(&owner, &format!("{}.{}", owner, property.name), &property.annotations, &member.loc);
+        if !property.is_auto {
+            self.record_property_call(&owner, &property, &getter, false, member.loc.clone());
+        }
+        Ok(Some(property.property_type))
+    }
+
+    /// 类体中直接用名称读取属性，名称不是属性时返回 None
+    ///
+    /// 自动属性由同名字段处理，这里只会遇到带访问器体的属性。
+    pub(super) fn infer_implicit_property_read(&mut self, ident: &IdentifierExpr) -> cayResult<Option<Type>> {
+        match self.implicit_property_member(ident)? {
+            Some((class_name, member)) => self.infer_property_read(&class_name, &member, false),
+            None => Ok(None),
+        }
+    }
+
+    /// 给属性赋值: obj.name = v / obj.name += v，返回属性类型；目标不是属性时返回 None
+    pub(super) fn infer_property_assignment(&mut self, assign: &AssignmentExpr) -> cayResult<Option<Type>> {
+        let (class_name, is_class_access, member) = match self.property_target(&assign.target)? {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        // 复合赋值先通过 getter 读取当前值
+        if assign.op != AssignOp::Assign {
+            if let Some(property_type) = self.infer_property_read(&class_name, &member, is_class_access)? {
+                self.check_property_write(&class_name, &member, is_class_access, Some(&assign.loc))?;
+                return Ok(Some(property_type));
+            }
+            return Ok(None);
+        }
+        self.check_property_write(&class_name, &member, is_class_access, Some(&assign.loc))
+    }
+
+    /// `++` / `--` 作用于属性时检查 setter；只有自动属性支持，其余属性需要改写为赋值
+    pub(super) fn check_property_update(&mut self, unary: &UnaryExpr) -> cayResult<()> {
+        let (class_name, is_class_access, member) = match self.property_target(&unary.operand)? {
+            Some(found) => found,
+            None => return Ok(()),
+        };
+        match self.find_property(&class_name, &member.member) {
+            Some((owner, property)) if !property.is_auto => Err(semantic_error(
+                ErrorCodes::SEMANTIC_PROPERTY_ERROR,
+                unary.loc.line,
+                unary.loc.column,
+                format!("Cannot increment or decrement property '{}.{}' whose accessors have bodies; use an assignment instead", owner, property.name),
+            )),
+            Some(_) => self.check_property_write(&class_name, &member, is_class_access, None).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// 检查属性能否赋值；`assign_loc` 为赋值位置，调用 setter 时在该位置记录
+    fn check_property_write(&mut self, class_name: &str, member: &MemberAccessExpr, is_class_access: bool, assign_loc: Option<&SourceLocation>) -> cayResult<Option<Type>> {
+        let (owner, property) = match self.find_property(class_name, &member.member) {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        self.check_property_context(&property, is_class_access, &member.loc)?;
+        match &property.setter {
+            Some(setter) => {
+                let setter = setter.clone();
+                self.check_accessor_access(&owner, &property, &setter, "setter", &member.loc)?;
+                self.warn_if_deprecated(&owner, &format!("{}.{}", owner, property.name), &property.annotations, &member.loc);
+                if let (false, Some(loc)) = (property.is_auto, assign_loc) {
+                    self.record_property_call(&owner, &property, &setter, true, loc.clone());
+                }
+            }
+            None => {
+                // 只读自动属性的字段可以在本类的构造函数中赋值
+                let in_own_constructor = self.current_method_is_constructor
+                    && self.current_class.as_deref() == Some(owner.as_str());
+                if !(property.is_auto && !property.is_static && in_own_constructor) {
+                    return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_PROPERTY_ERROR,
+                        member.loc.line,
+                        member.loc.column,
+                        format!("Cannot assign to read-only property '{}.{}'", owner, property.name),
+                    ));
+                }
+            }
+        }
+        Ok(Some(property.property_type))
+    }
+
+    /// 赋值或自增目标对应的属性访问: (对象所属的类, 是否通过类名访问, 成员访问)
+    fn property_target(&mut self, target: &Expr) -> cayResult<Option<(String, bool, MemberAccessExpr)>> {
+        match target {
+            Expr::MemberAccess(member) => Ok(self.property_object_class(&member.object)?
+                .map(|(class_name, is_class_access)| (class_name, is_class_access, member.clone()))),
+            // 局部变量和字段优先于同名属性
+            Expr::Identifier(ident) if self.symbol_table.lookup(&ident.name).is_none() && !self.is_current_class_field(&ident.name) => {
+                Ok(self.implicit_property_member(ident)?
+                    .map(|(class_name, member)| (class_name, false, member)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 把类体中的属性名 `name` 看作 `this.name`，静态上下文中不能访问实例属性
+    fn implicit_property_member(&mut self, ident: &IdentifierExpr) -> cayResult<Option<(String, MemberAccessExpr)>> {
+        let class_name = match &self.current_class {
+            Some(class_name) => class_name.clone(),
+            None => return Ok(None),
+        };
+        let property = match self.find_property(&class_name, &ident.name) {
+            Some((_, property)) => property,
+            None => return Ok(None),
+        };
+        let object = if property.is_static {
+            class_name.clone()
+        } else if self.current_method_is_static {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_STATIC_CONTEXT,
+                ident.loc.line,
+                ident.loc.column,
+                format!("non-static property {} cannot be referenced from a static context", property.name),
+            ));
+        } else {
+            // 在 Lambda 体中同时记录对 this 的捕获
+            self.lookup_local("this");
+            "this".to_string()
+        };
+        let member = MemberAccessExpr {
+            object: Box::new(Expr::Identifier(IdentifierExpr { name: object, loc: ident.loc.clone() })),
+            member: ident.name.clone(),
+            loc: ident.loc.clone(),
+        };
+        Ok(Some((class_name, member)))
+    }
+
+    /// 名称是否为当前类（含父类）的字段
+    fn is_current_class_field(&self, name: &str) -> bool {
+        let mut current = self.current_class.clone();
+        while let Some(class_name) = current {
+            match self.type_registry.get_class(&class_name) {
+                Some(class_info) if class_info.fields.contains_key(name) => return true,
+                Some(class_info) => current = class_info.parent.clone(),
+                None => return false,
+            }
+        }
+        false
+    }
+
+    /// 成员访问对象所属的类，以及是否通过类名访问；对象不是类的实例时返回 None
+    fn property_object_class(&mut self, object: &Expr) -> cayResult<Option<(String, bool)>> {
+        if let Expr::Identifier(id) = object {
+            let is_class_name = !matches!(id.name.as_str(), "this" | "super")
+                && self.symbol_table.lookup(&id.name).is_none()
+                && self.type_registry.get_class(&id.name).is_some();
+            if is_class_name {
+                return Ok(Some((id.name.clone(), true)));
+            }
+        }
+        let object_type = self.infer_expr_type(object)?;
+        match self.erase_type_param(object_type) {
+            Type::Object(class_name) => Ok(Some((class_name, false))),
+            _ => Ok(None),
+        }
+    }
+
+    /// 在类及其父类中查找属性，返回声明它的类
+    fn find_property(&self, class_name: &str, name: &str) -> Option<(String, PropertyInfo)> {
+        let mut current = Some(class_name.to_string());
+        while let Some(class_name) = current {
+            let class_info = self.type_registry.get_class(&class_name)?;
+            if let Some(property) = class_info.properties.get(name) {
+                return Some((class_name, property.clone()));
+            }
+            current = class_info.parent.clone();
+        }
+        None
+    }
+
+    /// 通过类名只能访问静态属性
+    fn check_property_context(&self, property: &PropertyInfo, is_class_access: bool, loc: &SourceLocation) -> cayResult<()> {
+        if is_class_access && !property.is_static {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_STATIC_CONTEXT,
+                loc.line,
+                loc.column,
+                format!("non-static property {} cannot be referenced from a static context", property.name),
+            ));
+        }
+        Ok(())
+    }
+
+    /// private 访问器只能在所属类中使用，其余访问器按包可见性检查
+    fn check_accessor_access(&self, owner: &str, property: &PropertyInfo, accessor: &AccessorInfo, kind: &str, loc: &SourceLocation) -> cayResult<()> {
+        if accessor.is_private && !self.can_access_private(owner) {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_ACCESS_VIOLATION,
+                loc.line,
+                loc.column,
+                format!("The {} of property '{}' has private access in {}", kind, property.name, owner),
+            ));
+        }
+        self.check_member_access(owner, &property.name, accessor.is_public, accessor.is_protected, loc)
+    }
+
+    /// 记录属性读写对应的访问方法调用（getter 没有参数，setter 有一个参数）
+    fn record_property_call(&mut self, owner: &str, property: &PropertyInfo, accessor: &AccessorInfo, is_setter: bool, loc: SourceLocation) {
+        let param_count = usize::from(is_setter);
+        let method = self.type_registry.get_class(owner)
+            .and_then(|class_info| class_info.methods.get(&accessor.method_name))
+            .and_then(|methods| methods.iter().find(|m| m.params.len() == param_count))
+            .cloned();
+        if let Some(method) = method {
+            let call = PropertyCall { method, property_type: property.property_type.clone() };
+            if is_setter {
+                self.type_registry.property_writes.insert(loc, call);
+            } else {
+                self.type_registry.property_reads.insert(loc, call);
+            }
+        }
+    }
+}
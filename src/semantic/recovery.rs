@@ -0,0 +1,112 @@
+//! 语义错误恢复
+//!
+//! 检查某个声明或语句出错时记录错误、恢复检查状态，然后继续检查下一个声明或语句，
+//! 使一次分析能报告程序中的全部语义错误。
+
+use crate::ast::Stmt;
+use crate::error::{cayError, get_error_location, get_error_message};
+use super::analyzer::SemanticAnalyzer;
+use super::symbol_table::SemanticSymbolInfo;
+
+/// 出错前的检查状态
+struct Checkpoint {
+    scope_depth: usize,
+    type_params: usize,
+    lambda_frames: usize,
+    instantiation_depth: usize,
+}
+
+impl SemanticAnalyzer {
+    /// 检查一个声明（方法、构造函数、初始化块或顶层函数），出错时记录错误并恢复到声明之外的状态
+    pub(super) fn check_declaration_recovering(&mut self, check: impl FnOnce(&mut Self) -> crate::error::cayResult<()>) {
+        let checkpoint = self.checkpoint();
+        if let Err(error) = check(self) {
+            self.record_failure(error);
+            self.restore(checkpoint);
+            self.current_method = None;
+            self.current_method_is_static = false;
+            self.current_method_is_constructor = false;
+            self.current_return_type = None;
+            self.non_null_locals.clear();
+        }
+    }
+
+    /// 检查一条语句，出错时记录错误并恢复到语句之前的作用域
+    ///
+    /// 出错的变量声明仍以声明的类型加入作用域，避免后续语句再报告未定义的变量。
+    pub(super) fn check_statement_recovering(&mut self, stmt: &Stmt, check: impl FnOnce(&mut Self) -> crate::error::cayResult<()>) {
+        let checkpoint = self.checkpoint();
+        if let Err(error) = check(self) {
+            self.record_failure(error);
+            self.restore(checkpoint);
+            if let Stmt::VarDecl(var) = stmt {
+                self.declare_failed_variable(var);
+            }
+        }
+    }
+
+    /// 记录中断检查的错误
+    ///
+    /// 第一个错误保留原始错误，只有这一个错误时 `analyze` 原样返回它。
+    pub(super) fn record_failure(&mut self, error: cayError) {
+        let (line, column) = get_error_location(&error).unwrap_or((0, 0));
+        let message = match &error {
+            cayError::Semantic { message, .. } => message.clone(),
+            _ => get_error_message(&error),
+        };
+        let info = self.create_error_info(error.code(), line, column, message);
+        let info = match crate::error::get_error_file(&error) {
+            Some(file) => super::SemanticErrorInfo { file: Some(file), ..info },
+            None => info,
+        };
+        // 同一表达式（如 Lambda 体）可能被推断多次，相同的错误只记录一次
+        let duplicate = self.errors.iter()
+            .any(|recorded| (recorded.line, recorded.column, &recorded.message) == (info.line, info.column, &info.message));
+        if duplicate {
+            return;
+        }
+        if self.errors.is_empty() {
+            self.first_failure = Some(error);
+        }
+        self.errors.push(info);
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            scope_depth: self.symbol_table.depth(),
+            type_params: self.current_type_params.len(),
+            lambda_frames: self.lambda_frames.len(),
+            instantiation_depth: self.instantiation_depth,
+        }
+    }
+
+    fn restore(&mut self, checkpoint: Checkpoint) {
+        while self.symbol_table.depth() > checkpoint.scope_depth {
+            self.symbol_table.exit_scope();
+        }
+        self.current_type_params.truncate(checkpoint.type_params);
+        self.lambda_frames.truncate(checkpoint.lambda_frames);
+        self.instantiation_depth = checkpoint.instantiation_depth;
+    }
+
+    fn declare_failed_variable(&mut self, var: &crate::ast::VarDecl) {
+        if self.symbol_table.lookup_current(&var.name).is_some() {
+            return;
+        }
+        let var_type = match self.resolve_type(&var.var_type, var.loc.line, var.loc.column) {
+            Ok(crate::types::Type::Auto) | Err(_) => return,
+            Ok(var_type) => var_type,
+        };
+        self.symbol_table.declare(
+            var.name.clone(),
+            SemanticSymbolInfo {
+                name: var.name.clone(),
+                symbol_type: var_type,
+                is_final: var.is_final,
+                is_initialized: true,
+                nullable: var.var_type.is_nullable(),
+                decl_loc: Some(var.loc.clone()),
+            }
+        );
+    }
+}
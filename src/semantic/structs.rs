@@ -0,0 +1,187 @@
+//! 结构体类型检查
+//!
+//! 结构体是值类型：`new Point(1.0, 2.0)` 按声明顺序给出各字段的值，`new Point()`
+//! 得到所有字段为零值的结构体，两者都不分配堆内存。赋值、传参和返回时复制整个值，
+//! 因此只能给可寻址的结构体（变量、数组元素、对象字段以及它们的字段）的字段赋值。
+//! 结构体不能按值包含自身，也不能为 null 或用 `==` 比较。
+
+use std::collections::HashSet;
+use crate::ast::*;
+use crate::types::{Type, ParameterInfo, StructInfo};
+use crate::error::{cayResult, semantic_error, SourceLocation};
+use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
+
+impl SemanticAnalyzer {
+    /// 收集结构体定义
+    pub fn collect_structs(&mut self, program: &Program) -> cayResult<()> {
+        for struct_decl in &program.structs {
+            let mut fields: Vec<ParameterInfo> = Vec::new();
+            for field in &struct_decl.fields {
+                if fields.iter().any(|f| f.name == field.name) {
+                    return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
+                        field.loc.line,
+                        field.loc.column,
+                        format!("Duplicate field '{}' in struct '{}'", field.name, struct_decl.name)
+                    ));
+                }
+                let field_type = self.resolve_type(&field.field_type, field.loc.line, field.loc.column)?;
+                if matches!(field_type, Type::Void | Type::CVoid | Type::Auto) {
+                    return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_STRUCT_ERROR,
+                        field.loc.line,
+                        field.loc.column,
+                        format!("Field '{}' of struct '{}' cannot have type {}", field.name, struct_decl.name, field_type)
+                    ));
+                }
+                fields.push(ParameterInfo::new(field.name.clone(), field_type));
+            }
+
+            self.type_registry.register_struct(StructInfo {
+                name: struct_decl.name.clone(),
+                fields,
+            }).map_err(|_| semantic_error(
+                ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
+                struct_decl.loc.line,
+                struct_decl.loc.column,
+                format!("Struct '{}' already defined", struct_decl.name)
+            ))?;
+        }
+
+        for struct_decl in &program.structs {
+            self.check_struct_recursion(struct_decl)?;
+        }
+        Ok(())
+    }
+
+    /// 结构体按值包含自身时大小无穷大
+    fn check_struct_recursion(&self, struct_decl: &StructDecl) -> cayResult<()> {
+        let mut visiting = HashSet::new();
+        if let Some(field) = self.struct_contains(&struct_decl.name, &struct_decl.name, &mut visiting) {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_STRUCT_ERROR,
+                struct_decl.loc.line,
+                struct_decl.loc.column,
+                format!(
+                    "Recursive struct '{}' contains itself by value through field '{}'; use an array or a class to refer to it",
+                    struct_decl.name, field
+                )
+            ));
+        }
+        Ok(())
+    }
+
+    /// `current` 是否按值包含 `target`，返回 `current` 中引出包含关系的字段
+    fn struct_contains(&self, current: &str, target: &str, visiting: &mut HashSet<String>) -> Option<String> {
+        if !visiting.insert(current.to_string()) {
+            return None;
+        }
+        let struct_info = self.type_registry.get_struct(current)?;
+        for field in &struct_info.fields {
+            let contains = match &field.param_type {
+                Type::Struct(inner) => inner == target || self.struct_contains(inner, target, visiting).is_some(),
+                _ => false,
+            };
+            if contains {
+                return Some(field.name.clone());
+            }
+        }
+        None
+    }
+
+    /// 推断结构体构造的类型: new Point(1.0, 2.0)
+    pub fn infer_struct_construction(&mut self, new_expr: &NewExpr) -> cayResult<Type> {
+        let struct_info = self.type_registry.get_struct(&new_expr.class_name).cloned()
+            .ok_or_else(|| semantic_error(ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER, new_expr.loc.line, new_expr.loc.column, format!("Unknown struct '{}'", new_expr.class_name)))?;
+        self.check_type_access(&struct_info.name, &new_expr.loc)?;
+
+        if new_expr.body.is_some() {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_STRUCT_ERROR,
+                new_expr.loc.line,
+                new_expr.loc.column,
+                format!("Struct '{}' cannot be extended by an anonymous class", struct_info.name)
+            ));
+        }
+        if !new_expr.args.is_empty() && new_expr.args.len() != struct_info.fields.len() {
+            return Err(semantic_error(
+                ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT,
+                new_expr.loc.line,
+                new_expr.loc.column,
+                format!(
+                    "Struct '{}' has {} field(s), but {} value(s) were given",
+                    struct_info.name, struct_info.fields.len(), new_expr.args.len()
+                )
+            ));
+        }
+        for (arg, field) in new_expr.args.iter().zip(struct_info.fields.iter()) {
+            let arg_type = self.infer_expr_type_expecting(arg, &field.param_type)?;
+            if !self.types_compatible(&arg_type, &field.param_type) {
+                return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH,
+                    new_expr.loc.line,
+                    new_expr.loc.column,
+                    format!(
+                        "Cannot initialize field '{}' of struct '{}' ({}) with {}",
+                        field.name, struct_info.name, field.param_type, arg_type
+                    )
+                ));
+            }
+        }
+        Ok(Type::Struct(struct_info.name))
+    }
+
+    /// 推断结构体字段访问的类型: p.x
+    pub fn infer_struct_field_access(&self, struct_name: &str, member: &MemberAccessExpr) -> cayResult<Type> {
+        let struct_info = self.type_registry.get_struct(struct_name)
+            .ok_or_else(|| semantic_error(ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER, member.loc.line, member.loc.column, format!("Unknown struct '{}'", struct_name)))?;
+        match struct_info.find_field(&member.member) {
+            Some((_, field)) => Ok(field.param_type.clone()),
+            None => Err(semantic_error(
+                ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
+                member.loc.line,
+                member.loc.column,
+                format!("Struct '{}' has no field '{}'", struct_name, member.member)
+            )),
+        }
+    }
+
+    /// 检查对结构体字段的赋值: 被赋值的结构体必须可寻址，且不是 final 变量
+    pub fn check_struct_field_assignment(&mut self, target: &Expr, loc: &SourceLocation) -> cayResult<()> {
+        let mut current = target;
+        while let Expr::MemberAccess(member) = current {
+            if !matches!(self.infer_expr_type(&member.object)?, Type::Struct(_)) {
+                // 对象字段通过对象引用赋值，与结构体无关
+                return Ok(());
+            }
+            match member.object.as_ref() {
+                Expr::Identifier(name) => {
+                    if self.symbol_table.lookup(name.as_ref()).is_some_and(|info| info.is_final) {
+                        return Err(semantic_error(
+                            ErrorCodes::SEMANTIC_FINAL_REASSIGNMENT,
+                            loc.line,
+                            loc.column,
+                            format!("Cannot assign to field '{}' of final struct variable '{}'", member.member, name)
+                        ));
+                    }
+                    return Ok(());
+                }
+                Expr::MemberAccess(_) => current = member.object.as_ref(),
+                Expr::ArrayAccess(_) => return Ok(()),
+                _ => {
+                    return Err(semantic_error(
+                        ErrorCodes::SEMANTIC_STRUCT_ERROR,
+                        loc.line,
+                        loc.column,
+                        format!(
+                            "Cannot assign to field '{}' of a temporary struct value; store it in a variable first",
+                            member.member
+                        )
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
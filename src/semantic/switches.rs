@@ -0,0 +1,587 @@
+//! switch 语句与 switch 表达式的语义检查
+//!
+//! 整数和字符 switch 的标签是整数、字符字面量或整数常量表达式；字符串 switch 的标签是字符串
+//! 字面量或字符串常量表达式（见 const_eval）；枚举 switch 的标签是变体模式；类和接口值上的
+//! switch 的标签是类型模式 `case Circle c`，按书写顺序匹配第一个类型相符的分支。带守卫的分支
+//! 可能不匹配，不参与重复标签、支配和穷尽性检查。
+
+use std::collections::HashSet;
+use crate::ast::*;
+use crate::types::{Type, EnumInfo};
+use crate::error::{cayResult, semantic_error, SourceLocation};
+use super::analyzer::SemanticAnalyzer;
+use super::symbol_table::SemanticSymbolInfo;
+use crate::diagnostic::ErrorCodes;
+
+/// switch 所匹配的值
+enum SwitchSubject {
+    Integer,
+    String,
+    Enum(EnumInfo),
+    /// 类或接口类型的值，按类型模式匹配
+    Object(String),
+}
+
+/// 正在检查的分支
+struct CaseContext {
+    label_count: usize,
+    /// 没有守卫的分支一定匹配，参与重复标签和穷尽性检查
+    unguarded: bool,
+    /// 上一个分支会穿透进入本分支
+    falls_into: bool,
+}
+
+impl SemanticAnalyzer {
+    /// 类型检查 switch 语句
+    pub(super) fn type_check_switch(&mut self, switch_stmt: &SwitchStmt, expected_return: Option<&Type>) -> cayResult<()> {
+        let subject = self.switch_subject(&switch_stmt.expr, &switch_stmt.loc)?;
+        let is_enum = matches!(subject, SwitchSubject::Enum(_) | SwitchSubject::Object(_));
+        let mut covered = HashSet::new();
+
+        // 整数和字符串 case 之间可以穿透，共享同一个作用域；枚举变体分支和类型模式分支各自拥有作用域
+        if !is_enum {
+            self.symbol_table.enter_scope();
+        }
+        for (idx, case) in switch_stmt.cases.iter().enumerate() {
+            let falls_into = idx > 0 && Self::case_falls_through(&switch_stmt.cases[idx - 1]);
+            if is_enum {
+                self.symbol_table.enter_scope();
+            }
+            self.check_case_head(&subject, &switch_stmt.loc, &case.labels, case.guard.as_ref(), falls_into, &mut covered)?;
+            for stmt in &case.body {
+                self.check_statement_recovering(stmt, |analyzer| analyzer.type_check_statement(stmt, expected_return));
+            }
+            if is_enum {
+                self.symbol_table.exit_scope();
+            }
+        }
+
+        match (&switch_stmt.default, &subject) {
+            (Some(default), _) => {
+                self.symbol_table.enter_scope();
+                for stmt in default {
+                    self.check_statement_recovering(stmt, |analyzer| analyzer.type_check_statement(stmt, expected_return));
+                }
+                self.symbol_table.exit_scope();
+            }
+            (None, SwitchSubject::Enum(info)) => {
+                self.check_enum_exhaustive(info, &covered, &switch_stmt.expr, &switch_stmt.loc);
+                self.exhaustive_switches.insert(switch_stmt.loc.clone());
+            }
+            (None, SwitchSubject::Object(type_name)) => {
+                self.check_type_exhaustive(type_name, &covered, &switch_stmt.loc);
+                self.exhaustive_switches.insert(switch_stmt.loc.clone());
+            }
+            (None, _) => {}
+        }
+        if !is_enum {
+            self.symbol_table.exit_scope();
+        }
+        Ok(())
+    }
+
+    /// 推断 switch 表达式的类型：各分支的值类型相同或都是数值类型（取提升后的类型）
+    ///
+    /// switch 表达式必须对每个值都产生结果，因此需要 default 分支、覆盖枚举的所有变体或覆盖被匹配值的类型。
+    pub(super) fn infer_switch_expr_type(&mut self, switch: &SwitchExpr, expected: Option<&Type>) -> cayResult<Type> {
+        let subject = self.switch_subject(&switch.expr, &switch.loc)?;
+        let mut covered = HashSet::new();
+        let mut result: Option<Type> = None;
+
+        for arm in &switch.arms {
+            self.symbol_table.enter_scope();
+            let arm_type = self.check_case_head(&subject, &switch.loc, &arm.labels, arm.guard.as_ref(), false, &mut covered)
+                .and_then(|_| self.infer_switch_arm_type(&arm.value, expected));
+            self.symbol_table.exit_scope();
+            result = Some(self.unify_switch_arm_types(result, arm_type?, &switch.loc)?);
+        }
+
+        match (&switch.default, &subject) {
+            (Some(default), _) => {
+                let default_type = self.infer_switch_arm_type(default, expected)?;
+                result = Some(self.unify_switch_arm_types(result, default_type, &switch.loc)?);
+            }
+            (None, SwitchSubject::Enum(info)) => self.check_enum_exhaustive(info, &covered, &switch.expr, &switch.loc),
+            (None, SwitchSubject::Object(type_name)) => self.check_type_exhaustive(type_name, &covered, &switch.loc),
+            (None, _) => {
+                return Err(semantic_error(
+                    ErrorCodes::SEMANTIC_NON_EXHAUSTIVE_SWITCH,
+                    switch.loc.line,
+                    switch.loc.column,
+                    "Switch expression must have a default branch".to_string()
+                ));
+            }
+        }
+
+        let result = match result {
+            Some(ty) => ty,
+            None => return Err(semantic_error(
+                ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                switch.loc.line,
+                switch.loc.column,
+                "Switch expression must have at least one branch".to_string()
+            )),
+        };
+        self.type_registry.switch_types.insert(switch.loc.clone(), result.clone());
+        Ok(result)
+    }
+
+    fn infer_switch_arm_type(&mut self, value: &Expr, expected: Option<&Type>) -> cayResult<Type> {
+        match expected {
+            Some(expected) => self.infer_expr_type_expecting(value, expected),
+            None => self.infer_expr_type(value),
+        }
+    }
+
+    fn unify_switch_arm_types(&self, previous: Option<Type>, arm_type: Type, loc: &SourceLocation) -> cayResult<Type> {
+        let previous = match previous {
+            Some(previous) => previous,
+            None => return Ok(arm_type),
+        };
+        if previous == arm_type {
+            Ok(previous)
+        } else if Self::is_numeric_type(&previous) && Self::is_numeric_type(&arm_type) {
+            Ok(self.promote_types(&previous, &arm_type))
+        } else {
+            Err(semantic_error(
+                ErrorCodes::SEMANTIC_INCOMPATIBLE_TYPES,
+                loc.line,
+                loc.column,
+                format!("Switch expression branches must have compatible types, got {} and {}", previous, arm_type)
+            ))
+        }
+    }
+
+    /// 确定 switch 的匹配方式；枚举 switch 和类型模式 switch 记录具体类型名供代码生成使用
+    fn switch_subject(&mut self, selector: &Expr, loc: &SourceLocation) -> cayResult<SwitchSubject> {
+        let selector_type = self.infer_expr_type(selector)?;
+        match selector_type.non_null() {
+            Type::Object(type_name) if self.type_registry.class_exists(type_name) || self.type_registry.interface_exists(type_name) => {
+                self.type_registry.type_switches.insert(loc.clone(), type_name.clone());
+                return Ok(SwitchSubject::Object(type_name.clone()));
+            }
+            _ => {}
+        }
+        match &selector_type {
+            Type::Int32 | Type::Int64 | Type::Char => Ok(SwitchSubject::Integer),
+            ty if ty.is_narrow_or_unsigned() => Ok(SwitchSubject::Integer),
+            Type::String => Ok(SwitchSubject::String),
+            Type::Enum(enum_name) => match self.type_registry.get_enum(enum_name) {
+                Some(info) => {
+                    let info = info.clone();
+                    self.type_registry.enum_switches.insert(loc.clone(), enum_name.clone());
+                    Ok(SwitchSubject::Enum(info))
+                }
+                None => Ok(SwitchSubject::Integer),
+            },
+            _ => {
+                let (line, column) = self.get_expr_location(selector);
+                Err(semantic_error(
+                    ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                    line,
+                    column,
+                    format!("Cannot switch on a value of type {}; expected an integer, char, string, enum, class or interface", selector_type)
+                ))
+            }
+        }
+    }
+
+    /// 检查分支的标签和守卫；枚举变体模式的绑定声明在当前作用域中
+    fn check_case_head(
+        &mut self,
+        subject: &SwitchSubject,
+        switch_loc: &SourceLocation,
+        labels: &[CaseLabel],
+        guard: Option<&Expr>,
+        falls_into: bool,
+        covered: &mut HashSet<String>,
+    ) -> cayResult<()> {
+        let case = CaseContext { label_count: labels.len(), unguarded: guard.is_none(), falls_into };
+        for label in labels {
+            match subject {
+                SwitchSubject::Enum(info) => self.check_variant_label(info, switch_loc, label, &case, covered),
+                SwitchSubject::Object(type_name) => self.check_type_label(type_name, switch_loc, label, &case, covered),
+                _ => self.check_constant_label(subject, switch_loc, label, case.unguarded, covered),
+            }
+        }
+
+        if let Some(guard) = guard {
+            let guard_type = self.infer_expr_type(guard)?;
+            if guard_type != Type::Bool {
+                let (line, column) = match self.get_expr_location(guard) {
+                    (0, 0) => (switch_loc.line, switch_loc.column),
+                    location => location,
+                };
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                    line,
+                    column,
+                    format!("Case guard must be boolean, got {}", guard_type),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 检查整数或字符串 switch 的标签，解析引用的 static final 常量
+    fn check_constant_label(&mut self, subject: &SwitchSubject, switch_loc: &SourceLocation, label: &CaseLabel, unguarded: bool, covered: &mut HashSet<String>) {
+        let (value, loc) = match label {
+            CaseLabel::Type(pattern) => {
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                    pattern.loc.line,
+                    pattern.loc.column,
+                    format!("Type pattern '{} {}' requires a switch on a class or interface value", pattern.target_type, pattern.binding),
+                ));
+                return;
+            }
+            CaseLabel::Int(v) => (LiteralValue::Int64(*v), switch_loc),
+            CaseLabel::Char(c) => (LiteralValue::Char(*c), switch_loc),
+            CaseLabel::String(s) => (LiteralValue::String(s.clone()), switch_loc),
+            CaseLabel::Pattern(pattern) => {
+                // case twice(BASE): 的写法与变体模式相同，调用 const 函数时按常量表达式求值
+                if !pattern.bindings.is_empty() && !self.is_const_function_call(pattern) {
+                    self.errors.push(self.create_error_info(
+                        ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                        pattern.loc.line,
+                        pattern.loc.column,
+                        format!("Variant pattern '{}' requires a switch on an enum value", pattern.variant),
+                    ));
+                    return;
+                }
+                let name = match &pattern.enum_name {
+                    Some(class_name) => format!("{}.{}", class_name, pattern.variant),
+                    None => pattern.variant.clone(),
+                };
+                match self.evaluate_const(&Self::pattern_expr(pattern)) {
+                    Ok(value) => {
+                        self.type_registry.case_constants.insert(pattern.loc.clone(), value.clone());
+                        (value, &pattern.loc)
+                    }
+                    Err(err) => {
+                        self.errors.push(self.create_error_info(
+                            ErrorCodes::SEMANTIC_CONST_EVAL,
+                            pattern.loc.line,
+                            pattern.loc.column,
+                            err.describe(&format!("case label '{}'", name)),
+                        ));
+                        return;
+                    }
+                }
+            }
+            CaseLabel::Const(label) => match self.evaluate_const(&label.expr) {
+                Ok(value) => {
+                    self.type_registry.case_constants.insert(label.loc.clone(), value.clone());
+                    (value, &label.loc)
+                }
+                Err(err) => {
+                    self.errors.push(self.create_error_info(
+                        ErrorCodes::SEMANTIC_CONST_EVAL,
+                        label.loc.line,
+                        label.loc.column,
+                        err.describe("case label"),
+                    ));
+                    return;
+                }
+            },
+        };
+
+        let key = match (subject, &value) {
+            (SwitchSubject::Integer, LiteralValue::Int32(v)) => v.to_string(),
+            (SwitchSubject::Integer, LiteralValue::Int64(v)) => v.to_string(),
+            (SwitchSubject::Integer, LiteralValue::Char(c)) => (*c as i64).to_string(),
+            (SwitchSubject::String, LiteralValue::String(s)) => format!("{:?}", s),
+            _ => {
+                let expected = match subject {
+                    SwitchSubject::String => "a string",
+                    _ => "an integer or char",
+                };
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                    loc.line,
+                    loc.column,
+                    format!("Case label {} does not match the switch value: expected {} constant", Self::describe_literal(&value), expected),
+                ));
+                return;
+            }
+        };
+        if unguarded && !covered.insert(key) {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                loc.line,
+                loc.column,
+                format!("Duplicate case label {}", Self::describe_literal(&value)),
+            ));
+        }
+    }
+
+    fn describe_literal(value: &LiteralValue) -> String {
+        match value {
+            LiteralValue::Int32(v) => v.to_string(),
+            LiteralValue::Int64(v) => v.to_string(),
+            LiteralValue::Char(c) => format!("'{}'", c),
+            LiteralValue::String(s) => format!("{:?}", s),
+            LiteralValue::Bool(b) => b.to_string(),
+            LiteralValue::Float32(f) => f.to_string(),
+            LiteralValue::Float64(f) => f.to_string(),
+            LiteralValue::Null => "null".to_string(),
+        }
+    }
+
+    /// 以标识符开头的标签对应的表达式: MAX、Limits.MAX 或 const 函数调用 twice(BASE)
+    fn pattern_expr(pattern: &EnumPattern) -> Expr {
+        let name = Expr::Identifier(IdentifierExpr { name: pattern.variant.clone(), loc: pattern.loc.clone() });
+        let target = match &pattern.enum_name {
+            Some(class_name) => Expr::MemberAccess(MemberAccessExpr {
+                object: Box::new(Expr::Identifier(IdentifierExpr { name: class_name.clone(), loc: pattern.loc.clone() })),
+                member: pattern.variant.clone(),
+                loc: pattern.loc.clone(),
+            }),
+            None => name,
+        };
+        if pattern.bindings.is_empty() {
+            return target;
+        }
+        Expr::Call(CallExpr {
+            callee: Box::new(target),
+            args: pattern.bindings.iter()
+                .map(|arg| Expr::Identifier(IdentifierExpr { name: arg.clone(), loc: pattern.loc.clone() }))
+                .collect(),
+            loc: pattern.loc.clone(),
+        })
+    }
+
+    /// 标签是否调用了当前类或限定类中的 const 函数
+    fn is_const_function_call(&self, pattern: &EnumPattern) -> bool {
+        let class_name = match pattern.enum_name.as_ref().or(self.current_class.as_ref()) {
+            Some(class_name) => class_name,
+            None => return false,
+        };
+        self.program.as_ref()
+            .and_then(|program| program.classes.iter().find(|c| &c.name == class_name))
+            .is_some_and(|class| class.members.iter().any(|member| matches!(member,
+                ClassMember::Method(method) if method.name == pattern.variant && method.modifiers.contains(&Modifier::Const))))
+    }
+
+    /// 检查枚举 switch 的变体模式并声明绑定变量
+    fn check_variant_label(&mut self, info: &EnumInfo, switch_loc: &SourceLocation, label: &CaseLabel, case: &CaseContext, covered: &mut HashSet<String>) {
+        let enum_name = &info.name;
+        let pattern = match label {
+            CaseLabel::Pattern(pattern) => pattern,
+            other => {
+                let found = match other {
+                    CaseLabel::Int(v) => format!("integer case {}", v),
+                    CaseLabel::Char(c) => format!("char case '{}'", c),
+                    CaseLabel::String(s) => format!("string case {:?}", s),
+                    CaseLabel::Type(pattern) => format!("type pattern '{} {}'", pattern.target_type, pattern.binding),
+                    CaseLabel::Const(_) => "constant expression case".to_string(),
+                    CaseLabel::Pattern(_) => unreachable!(),
+                };
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                    switch_loc.line,
+                    switch_loc.column,
+                    format!("Switch on enum '{}' requires variant patterns, found {}", enum_name, found),
+                ));
+                return;
+            }
+        };
+
+        // 泛型枚举实例的模式可以用模板名限定: case Result.Ok(v):
+        let origin = self.type_registry.enum_origin(enum_name).map(str::to_string);
+        match &pattern.enum_name {
+            Some(qualifier) if qualifier != enum_name && origin.as_deref() != Some(qualifier.as_str()) => {
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                    pattern.loc.line,
+                    pattern.loc.column,
+                    format!("Pattern '{}.{}' does not match switch on enum '{}'", qualifier, pattern.variant, enum_name),
+                ));
+            }
+            _ => {}
+        }
+
+        let variant = match info.find_variant(&pattern.variant) {
+            Some((_, variant)) => variant,
+            None => {
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
+                    pattern.loc.line,
+                    pattern.loc.column,
+                    format!("Enum '{}' has no variant '{}'", enum_name, pattern.variant),
+                ));
+                return;
+            }
+        };
+
+        if case.unguarded && !covered.insert(variant.name.clone()) {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                pattern.loc.line,
+                pattern.loc.column,
+                format!("Duplicate case for variant '{}.{}'", enum_name, variant.name),
+            ));
+        }
+
+        if pattern.bindings.is_empty() {
+            return;
+        }
+        if case.label_count > 1 {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                pattern.loc.line,
+                pattern.loc.column,
+                format!("Case '{}' binds payload fields and cannot share a case with other labels", variant.name),
+            ));
+        }
+        if pattern.bindings.len() != variant.fields.len() {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                pattern.loc.line,
+                pattern.loc.column,
+                format!("Variant '{}.{}' has {} field(s), but pattern binds {}",
+                    enum_name, variant.name, variant.fields.len(), pattern.bindings.len()),
+            ));
+        }
+        // 绑定只在匹配到该变体时才有值，不能从上一个 case 穿透进入
+        if case.falls_into {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                pattern.loc.line,
+                pattern.loc.column,
+                format!("Case '{}' binds payload fields and cannot be reached by fall-through; end the previous case with break or return", variant.name),
+            ));
+        }
+
+        for (binding, field) in pattern.bindings.iter().zip(variant.fields.iter()) {
+            self.symbol_table.declare(
+                binding.clone(),
+                SemanticSymbolInfo {
+                    name: binding.clone(),
+                    symbol_type: field.param_type.clone(),
+                    is_final: false,
+                    is_initialized: true,
+                    nullable: false,
+                    decl_loc: None,
+                }
+            );
+        }
+    }
+
+    /// 没有 default 的枚举 switch 必须覆盖所有变体
+    fn check_enum_exhaustive(&mut self, info: &EnumInfo, covered: &HashSet<String>, selector: &Expr, switch_loc: &SourceLocation) {
+        let missing: Vec<&str> = info.variants.iter()
+            .filter(|v| !covered.contains(&v.name))
+            .map(|v| v.name.as_str())
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        let (line, column) = match self.get_expr_location(selector) {
+            (0, 0) => (switch_loc.line, switch_loc.column),
+            location => location,
+        };
+        self.errors.push(self.create_error_info(
+            ErrorCodes::SEMANTIC_NON_EXHAUSTIVE_SWITCH,
+            line,
+            column,
+            format!("Switch on enum '{}' is not exhaustive: missing variant(s) {}", info.name, missing.join(", ")),
+        ));
+    }
+
+    /// 检查类型模式标签并声明模式变量
+    ///
+    /// 模式的类型必须可能匹配被匹配值的类型；位于同类型或父类型的无守卫分支之后的分支永远不会匹配。
+    fn check_type_label(&mut self, subject_type: &str, switch_loc: &SourceLocation, label: &CaseLabel, case: &CaseContext, covered: &mut HashSet<String>) {
+        let pattern = match label {
+            CaseLabel::Type(pattern) => pattern,
+            other => {
+                let found = match other {
+                    CaseLabel::Int(v) => format!("integer case {}", v),
+                    CaseLabel::Char(c) => format!("char case '{}'", c),
+                    CaseLabel::String(s) => format!("string case {:?}", s),
+                    CaseLabel::Pattern(pattern) => format!("case '{}'", pattern.variant),
+                    CaseLabel::Const(_) => "constant expression case".to_string(),
+                    CaseLabel::Type(_) => unreachable!(),
+                };
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                    switch_loc.line,
+                    switch_loc.column,
+                    format!("Switch on {} requires type patterns such as 'case {} x', found {}", subject_type, subject_type, found),
+                ));
+                return;
+            }
+        };
+
+        let target = match &pattern.target_type {
+            Type::Object(name) if self.type_registry.class_exists(name) || self.type_registry.interface_exists(name) => name.clone(),
+            other => {
+                self.errors.push(self.create_error_info(
+                    ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
+                    pattern.loc.line,
+                    pattern.loc.column,
+                    format!("Unknown type in type pattern: {}", other),
+                ));
+                return;
+            }
+        };
+        if !self.types_may_match(subject_type, &target) {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                pattern.loc.line,
+                pattern.loc.column,
+                format!("Type pattern '{} {}' can never match a value of type {}", target, pattern.binding, subject_type),
+            ));
+        }
+        if let Some(dominating) = covered.iter().find(|earlier| self.is_subtype_of(&target, earlier)) {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                pattern.loc.line,
+                pattern.loc.column,
+                format!("Case '{} {}' is dominated by an earlier case for {}", target, pattern.binding, dominating),
+            ));
+        }
+        if case.unguarded {
+            covered.insert(target.clone());
+        }
+
+        if case.label_count > 1 {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                pattern.loc.line,
+                pattern.loc.column,
+                format!("Case '{} {}' binds a pattern variable and cannot share a case with other labels", target, pattern.binding),
+            ));
+        }
+        if case.falls_into {
+            self.errors.push(self.create_error_info(
+                ErrorCodes::SEMANTIC_SWITCH_ERROR,
+                pattern.loc.line,
+                pattern.loc.column,
+                format!("Case '{} {}' binds a pattern variable and cannot be reached by fall-through; end the previous case with break or return", target, pattern.binding),
+            ));
+        }
+        self.declare_pattern_variable(&pattern.binding, &pattern.target_type, &pattern.loc);
+    }
+
+    /// 没有 default 的类型模式 switch 必须有覆盖被匹配值类型的无守卫分支，
+    /// sealed 类型也可以由覆盖全部允许子类的分支覆盖
+    fn check_type_exhaustive(&mut self, subject_type: &str, covered: &HashSet<String>, switch_loc: &SourceLocation) {
+        let missing = self.uncovered_types(subject_type, covered);
+        if missing.is_empty() {
+            return;
+        }
+        self.errors.push(self.create_error_info(
+            ErrorCodes::SEMANTIC_NON_EXHAUSTIVE_SWITCH,
+            switch_loc.line,
+            switch_loc.column,
+            format!("Switch on {} is not exhaustive: add a default branch or a case for {}", subject_type, missing.join(", ")),
+        ));
+    }
+
+    /// case 体执行完后是否会穿透到下一个 case
+    fn case_falls_through(case: &Case) -> bool {
+        !matches!(case.body.last(), Some(Stmt::Break(_)) | Some(Stmt::Return(_)) | Some(Stmt::Continue(_)))
+    }
+}
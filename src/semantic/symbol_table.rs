@@ -15,6 +15,10 @@ pub struct SemanticSymbolInfo {
     pub symbol_type: Type,
     pub is_final: bool,
     pub is_initialized: bool,
+    /// 是否声明为可空类型 T?（空安全检查使用）
+    pub nullable: bool,
+    /// 局部变量的声明位置（参数、this 等为 None）
+    pub decl_loc: Option<crate::error::SourceLocation>,
 }
 
 impl SemanticSymbolTable {
@@ -47,6 +51,21 @@ impl SemanticSymbolTable {
         None
     }
 
+    /// 查找符号并返回其所在作用域的层级（0 为全局作用域）
+    pub fn lookup_with_depth(&self, name: &str) -> Option<(usize, &SemanticSymbolInfo)> {
+        for (depth, scope) in self.scopes.iter().enumerate().rev() {
+            if let Some(info) = scope.get(name) {
+                return Some((depth, info));
+            }
+        }
+        None
+    }
+
+    /// 当前作用域层数
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
     pub fn lookup_current(&self, name: &str) -> Option<&SemanticSymbolInfo> {
         self.scopes.last().and_then(|s| s.get(name))
     }
@@ -5,171 +5,224 @@ use crate::types::{Type, ParameterInfo};
 use crate::error::cayResult;
 use super::analyzer::SemanticAnalyzer;
 use super::symbol_table::SemanticSymbolInfo;
+use crate::diagnostic::ErrorCodes;
 
 impl SemanticAnalyzer {
-    /// 类型检查程序
-    pub fn type_check_program(&mut self, program: &Program) -> cayResult<()> {
+    /// 类型检查程序，出错的声明之后继续检查其余的声明
+    pub fn type_check_program(&mut self, program: &Program) {
         for class in &program.classes {
             self.current_class = Some(class.name.clone());
+            self.current_type_params = class.type_params.clone();
             
             for member in &class.members {
-                match member {
-                    ClassMember::Method(method) => {
-                        self.current_method = Some(method.name.clone());
-                        self.current_method_is_static = method.modifiers.contains(&Modifier::Static);
-                        self.current_method_is_constructor = false;
-                        self.symbol_table.enter_scope();
-                        
-                        // 非静态方法需要添加 this
-                        if !self.current_method_is_static {
-                            if let Some(current_class) = &self.current_class {
-                                self.symbol_table.declare(
-                                    "this".to_string(),
-                                    SemanticSymbolInfo {
-                                        name: "this".to_string(),
-                                        symbol_type: Type::Object(current_class.clone()),
-                                        is_final: true,
-                                        is_initialized: true,
-                                    }
-                                );
-                            }
-                        }
-                        
-                        // 添加参数到符号表
-                        for param in &method.params {
-                            self.symbol_table.declare(
-                                param.name.clone(),
-                                SemanticSymbolInfo {
-                                    name: param.name.clone(),
-                                    symbol_type: param.param_type.clone(),
-                                    is_final: false,
-                                    is_initialized: true,
-                                }
-                            );
-                        }
-                        
-                        // 类型检查方法体
-                        if let Some(body) = &method.body {
-                            self.type_check_statement(&Stmt::Block(body.clone()), Some(&method.return_type))?;
-                        }
-                        
-                        self.symbol_table.exit_scope();
-                        self.current_method = None;
-                        self.current_method_is_static = false;
-                    }
-                    ClassMember::Field(_) => {
-                        // 字段类型检查暂不实现
-                    }
-                    ClassMember::Constructor(ctor) => {
-                        // 构造函数类型检查
-                        self.current_method_is_static = false;
-                        self.current_method_is_constructor = true;
-                        self.symbol_table.enter_scope();
-                        
-                        // 添加 this 到符号表
-                        self.symbol_table.declare(
-                            "this".to_string(),
-                            SemanticSymbolInfo {
-                                name: "this".to_string(),
-                                symbol_type: Type::Object(class.name.clone()),
-                                is_final: true,
-                                is_initialized: true,
-                            }
-                        );
-                        
-                        // 添加参数到符号表
-                        for param in &ctor.params {
-                            self.symbol_table.declare(
-                                param.name.clone(),
-                                SemanticSymbolInfo {
-                                    name: param.name.clone(),
-                                    symbol_type: param.param_type.clone(),
-                                    is_final: false,
-                                    is_initialized: true,
-                                }
-                            );
-                        }
-                        
-                        // 类型检查构造函数体
-                        self.type_check_statement(&Stmt::Block(ctor.body.clone()), Some(&Type::Void))?;
-                        
-                        self.symbol_table.exit_scope();
-                        self.current_method_is_constructor = false;
-                    }
-                    ClassMember::Destructor(dtor) => {
-                        // 析构函数类型检查
-                        self.current_method_is_static = false;
-                        self.current_method_is_constructor = false;
-                        self.symbol_table.enter_scope();
-                        
-                        // 添加 this 到符号表
-                        self.symbol_table.declare(
-                            "this".to_string(),
-                            SemanticSymbolInfo {
-                                name: "this".to_string(),
-                                symbol_type: Type::Object(class.name.clone()),
-                                is_final: true,
-                                is_initialized: true,
-                            }
-                        );
-                        
-                        // 类型检查析构函数体
-                        self.type_check_statement(&Stmt::Block(dtor.body.clone()), Some(&Type::Void))?;
-                        
-                        self.symbol_table.exit_scope();
-                    }
-                    ClassMember::InstanceInitializer(block) => {
-                        // 实例初始化块类型检查
-                        self.current_method_is_static = false;
-                        self.current_method_is_constructor = false;
-                        self.symbol_table.enter_scope();
-                        self.type_check_statement(&Stmt::Block(block.clone()), Some(&Type::Void))?;
-                        self.symbol_table.exit_scope();
-                    }
-                    ClassMember::StaticInitializer(block) => {
-                        // 静态初始化块类型检查
-                        self.current_method_is_static = true;
-                        self.current_method_is_constructor = false;
-                        self.symbol_table.enter_scope();
-                        self.type_check_statement(&Stmt::Block(block.clone()), Some(&Type::Void))?;
-                        self.symbol_table.exit_scope();
-                        self.current_method_is_static = false;
-                    }
-                }
+                self.check_declaration_recovering(|analyzer| analyzer.type_check_member(class, member));
             }
             
             self.current_class = None;
+            self.current_type_params.clear();
+        }
+
+        // 类型检查接口的 default 和 static 方法体
+        for interface in &program.interfaces {
+            self.current_class = Some(interface.name.clone());
+            for method in interface.methods.iter().filter(|m| m.body.is_some()) {
+                self.check_declaration_recovering(|analyzer| analyzer.type_check_method(method));
+            }
+            self.current_class = None;
         }
 
         // 类型检查顶层函数
         for func in &program.top_level_functions {
-            self.current_class = None;  // 顶层函数不属于任何类
-            self.current_method = Some(func.name.clone());
-            self.current_method_is_static = true;  // 顶层函数都是静态的
-            self.current_method_is_constructor = false;
-            self.symbol_table.enter_scope();
+            self.check_declaration_recovering(|analyzer| analyzer.type_check_top_level_function(func));
+        }
+    }
 
-            // 添加参数到符号表
-            for param in &func.params {
+    /// 类型检查类成员
+    fn type_check_member(&mut self, class: &ClassDecl, member: &ClassMember) -> cayResult<()> {
+        match member {
+            ClassMember::Method(method) => {
+                self.type_check_method(method)?;
+            }
+            ClassMember::Field(_) => {
+                // 字段类型检查暂不实现
+            }
+            ClassMember::Constructor(ctor) => {
+                // 构造函数类型检查
+                self.current_method_is_static = false;
+                self.current_method_is_constructor = true;
+                self.symbol_table.enter_scope();
+                
+                // 添加 this 到符号表
                 self.symbol_table.declare(
-                    param.name.clone(),
+                    "this".to_string(),
                     SemanticSymbolInfo {
-                        name: param.name.clone(),
-                        symbol_type: param.param_type.clone(),
-                        is_final: false,
+                        name: "this".to_string(),
+                        symbol_type: Type::Object(class.name.clone()),
+                        is_final: true,
                         is_initialized: true,
+                        nullable: false,
+                        decl_loc: None,
                     }
                 );
+                
+                // 添加参数到符号表
+                for param in &ctor.params {
+                    let param_type = self.resolve_type(&param.param_type, ctor.loc.line, ctor.loc.column)?;
+                    self.symbol_table.declare(
+                        param.name.clone(),
+                        SemanticSymbolInfo {
+                            name: param.name.clone(),
+                            symbol_type: param_type,
+                            is_final: false,
+                            is_initialized: true,
+                            nullable: param.param_type.is_nullable(),
+                            decl_loc: None,
+                        }
+                    );
+                }
+                
+                // 类型检查构造函数体
+                self.type_check_statement(&Stmt::Block(ctor.body.clone()), Some(&Type::Void))?;
+                
+                self.symbol_table.exit_scope();
+                self.current_method_is_constructor = false;
+            }
+            ClassMember::Destructor(dtor) => {
+                // 析构函数类型检查
+                self.current_method_is_static = false;
+                self.current_method_is_constructor = false;
+                self.symbol_table.enter_scope();
+                
+                // 添加 this 到符号表
+                self.symbol_table.declare(
+                    "this".to_string(),
+                    SemanticSymbolInfo {
+                        name: "this".to_string(),
+                        symbol_type: Type::Object(class.name.clone()),
+                        is_final: true,
+                        is_initialized: true,
+                        nullable: false,
+                        decl_loc: None,
+                    }
+                );
+                
+                // 类型检查析构函数体
+                self.type_check_statement(&Stmt::Block(dtor.body.clone()), Some(&Type::Void))?;
+                
+                self.symbol_table.exit_scope();
+            }
+            ClassMember::InstanceInitializer(block) => {
+                // 实例初始化块类型检查（在构造函数中执行，可以给 final 字段赋值）
+                self.current_method_is_static = false;
+                self.current_method_is_constructor = true;
+                self.symbol_table.enter_scope();
+                self.type_check_statement(&Stmt::Block(block.clone()), Some(&Type::Void))?;
+                self.symbol_table.exit_scope();
+                self.current_method_is_constructor = false;
+            }
+            ClassMember::StaticInitializer(block) => {
+                // 静态初始化块类型检查
+                self.current_method_is_static = true;
+                self.current_method_is_constructor = false;
+                self.symbol_table.enter_scope();
+                self.type_check_statement(&Stmt::Block(block.clone()), Some(&Type::Void))?;
+                self.symbol_table.exit_scope();
+                self.current_method_is_static = false;
             }
+            // 嵌套类在解析结束时已提升到顶层；属性的访问方法作为普通方法检查
+            ClassMember::Class(_) | ClassMember::Property(_) => {}
+        }
+        Ok(())
+    }
 
-            // 类型检查函数体
-            self.type_check_statement(&Stmt::Block(func.body.clone()), Some(&func.return_type))?;
+    /// 类型检查顶层函数
+    fn type_check_top_level_function(&mut self, func: &TopLevelFunction) -> cayResult<()> {
+        self.current_class = None;  // 顶层函数不属于任何类
+        self.current_method = Some(func.name.clone());
+        self.current_method_is_static = true;  // 顶层函数都是静态的
+        self.current_method_is_constructor = false;
+        self.symbol_table.enter_scope();
 
-            self.symbol_table.exit_scope();
-            self.current_method = None;
-            self.current_method_is_static = false;
+        // 添加参数到符号表
+        for param in &func.params {
+            let param_type = self.resolve_type(&param.param_type, func.loc.line, func.loc.column)?;
+            self.symbol_table.declare(
+                param.name.clone(),
+                SemanticSymbolInfo {
+                    name: param.name.clone(),
+                    symbol_type: param_type,
+                    is_final: false,
+                    is_initialized: true,
+                    nullable: param.param_type.is_nullable(),
+                    decl_loc: None,
+                }
+            );
         }
 
+        // 类型检查函数体
+        self.current_return_type = Some(self.resolve_type(&func.return_type, func.loc.line, func.loc.column)?);
+        self.type_check_statement(&Stmt::Block(func.body.clone()), Some(&func.return_type))?;
+
+        self.symbol_table.exit_scope();
+        self.current_return_type = None;
+        self.current_method = None;
+        self.current_method_is_static = false;
+        Ok(())
+    }
+
+    /// 类型检查方法体（类方法以及接口的 default 和 static 方法）
+    fn type_check_method(&mut self, method: &MethodDecl) -> cayResult<()> {
+        let outer_type_params = self.current_type_params.len();
+        self.current_method = Some(method.name.clone());
+        self.current_method_is_static = method.modifiers.contains(&Modifier::Static);
+        self.current_method_is_constructor = false;
+        self.current_type_params.extend(method.type_params.iter().cloned());
+        self.symbol_table.enter_scope();
+        
+        // 非静态方法需要添加 this
+        if !self.current_method_is_static {
+            if let Some(current_class) = &self.current_class {
+                self.symbol_table.declare(
+                    "this".to_string(),
+                    SemanticSymbolInfo {
+                        name: "this".to_string(),
+                        symbol_type: Type::Object(current_class.clone()),
+                        is_final: true,
+                        is_initialized: true,
+                        nullable: false,
+                        decl_loc: None,
+                    }
+                );
+            }
+        }
+        
+        // 添加参数到符号表
+        for param in &method.params {
+            let param_type = self.resolve_type(&param.param_type, method.loc.line, method.loc.column)?;
+            self.symbol_table.declare(
+                param.name.clone(),
+                SemanticSymbolInfo {
+                    name: param.name.clone(),
+                    symbol_type: param_type,
+                    is_final: false,
+                    is_initialized: true,
+                    nullable: param.param_type.is_nullable(),
+                    decl_loc: None,
+                }
+            );
+        }
+        
+        // 类型检查方法体
+        self.current_return_type = Some(self.resolve_type(&method.return_type, method.loc.line, method.loc.column)?);
+        if let Some(body) = &method.body {
+            self.type_check_statement(&Stmt::Block(body.clone()), Some(&method.return_type))?;
+        }
+        
+        self.symbol_table.exit_scope();
+        self.current_return_type = None;
+        self.current_method = None;
+        self.current_method_is_static = false;
+        self.current_type_params.truncate(outer_type_params);
         Ok(())
     }
 
@@ -183,6 +236,7 @@ impl SemanticAnalyzer {
                 // 检查当前作用域中是否已存在同名变量
                 if self.symbol_table.lookup_current(&var.name).is_some() {
                     self.errors.push(self.create_error_info(
+                        ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
                         var.loc.line,
                         var.loc.column,
                         format!(
@@ -193,7 +247,7 @@ impl SemanticAnalyzer {
                     return Ok(());
                 }
 
-                let mut var_type = var.var_type.clone();
+                let mut var_type = self.resolve_type(&var.var_type, var.loc.line, var.loc.column)?;
                 
                 // 处理 auto 类型推断
                 if var_type == Type::Auto {
@@ -201,6 +255,7 @@ impl SemanticAnalyzer {
                         var_type = self.infer_expr_type(init)?;
                     } else {
                         self.errors.push(self.create_error_info(
+                            ErrorCodes::SEMANTIC_TYPE_INFERENCE,
                             var.loc.line,
                             var.loc.column,
                             "'auto' variable declaration requires an initializer",
@@ -210,18 +265,14 @@ impl SemanticAnalyzer {
                 }
                 
                 if let Some(init) = &var.initializer {
-                    let init_type = self.infer_expr_type(init)?;
+                    let init_type = self.infer_expr_type_expecting(init, &var_type)?;
                     if !self.types_compatible(&init_type, &var_type) {
-                        self.errors.push(self.create_error_info(
-                            var.loc.line,
-                            var.loc.column,
-                            format!(
-                                "Cannot assign {} to {}",
-                                init_type, var_type
-                            ),
-                        ));
+                        let message = Self::integer_literal_out_of_range(init, &var_type)
+                            .unwrap_or_else(|| format!("Cannot assign {} to {}", init_type, var_type));
+                        self.errors.push(self.create_error_info(ErrorCodes::SEMANTIC_TYPE_MISMATCH, var.loc.line, var.loc.column, message));
                     }
                 }
+                self.check_nullable_initializer(var, &var_type);
                 
                 self.symbol_table.declare(
                     var.name.clone(),
@@ -230,16 +281,24 @@ impl SemanticAnalyzer {
                         symbol_type: var_type,
                         is_final: var.is_final,
                         is_initialized: var.initializer.is_some(),
+                        nullable: var.var_type.is_nullable(),
+                        decl_loc: Some(var.loc.clone()),
                     }
                 );
             }
             Stmt::Return(expr) => {
                 let return_type = if let Some(e) = expr {
-                    self.infer_expr_type(e)?
+                    match expected_return {
+                        Some(expected) => self.infer_expr_type_expecting(e, expected)?,
+                        None => self.infer_expr_type(e)?,
+                    }
                 } else {
                     Type::Void
                 };
                 
+                if let (Some(e), Some(expected)) = (expr, expected_return) {
+                    self.check_nullable_return(e, expected);
+                }
                 if let Some(expected) = expected_return {
                     if !self.types_compatible(&return_type, expected) {
                         // 尝试从表达式获取位置信息
@@ -249,6 +308,7 @@ impl SemanticAnalyzer {
                             (0, 0)
                         };
                         self.errors.push(self.create_error_info(
+                            ErrorCodes::SEMANTIC_RETURN_TYPE_MISMATCH,
                             line,
                             column,
                             format!(
@@ -265,21 +325,91 @@ impl SemanticAnalyzer {
                 if is_multi_var_decl {
                     // 多变量声明不创建新作用域，在当前作用域内声明所有变量
                     for stmt in &block.statements {
-                        if let Stmt::VarDecl(var) = stmt {
-                            self.type_check_statement(&Stmt::VarDecl(var.clone()), expected_return)?;
-                        }
+                        self.check_statement_recovering(stmt, |analyzer| analyzer.type_check_statement(stmt, expected_return));
                     }
                 } else {
+                    // 块中声明的变量在块结束后不再可见，块中取消的收窄保持取消
+                    let outer_non_null = self.non_null_locals.clone();
                     self.symbol_table.enter_scope();
                     for stmt in &block.statements {
-                        self.type_check_statement(stmt, expected_return)?;
+                        // 出错的语句之后继续检查同一代码块中的其余语句
+                        self.check_statement_recovering(stmt, |analyzer| analyzer.type_check_statement(stmt, expected_return));
                     }
                     self.symbol_table.exit_scope();
+                    self.non_null_locals.retain(|name| outer_non_null.contains(name));
+                }
+            }
+            Stmt::If(if_stmt) => {
+                self.infer_expr_type(&if_stmt.condition)?;
+                self.type_check_if_narrowing(if_stmt, expected_return)?;
+            }
+            Stmt::While(while_stmt) => {
+                self.forget_assigned_in(&while_stmt.body);
+                self.infer_expr_type(&while_stmt.condition)?;
+                let (when_true, _) = self.null_checks(&while_stmt.condition);
+                let (bindings, _) = while_stmt.condition.pattern_bindings();
+                self.with_pattern_bindings(&bindings, |analyzer| {
+                    analyzer.with_non_null(&when_true, |analyzer| analyzer.type_check_branch(&while_stmt.body, expected_return))
+                })?;
+            }
+            Stmt::DoWhile(do_while) => {
+                self.forget_assigned_in(&do_while.body);
+                self.type_check_branch(&do_while.body, expected_return)?;
+                self.infer_expr_type(&do_while.condition)?;
+            }
+            Stmt::For(for_stmt) => {
+                // for 初始化变量只在循环内可见
+                self.symbol_table.enter_scope();
+                if let Some(init) = &for_stmt.init {
+                    self.type_check_statement(init, expected_return)?;
                 }
+                self.forget_assigned_in(stmt);
+                if let Some(condition) = &for_stmt.condition {
+                    self.infer_expr_type(condition)?;
+                }
+                if let Some(update) = &for_stmt.update {
+                    self.infer_expr_type(update)?;
+                }
+                let when_true = for_stmt.condition.as_ref()
+                    .map(|condition| self.null_checks(condition).0)
+                    .unwrap_or_default();
+                let bindings = for_stmt.condition.as_ref()
+                    .map(|condition| condition.pattern_bindings().0)
+                    .unwrap_or_default();
+                self.with_pattern_bindings(&bindings, |analyzer| {
+                    analyzer.with_non_null(&when_true, |analyzer| analyzer.type_check_branch(&for_stmt.body, expected_return))
+                })?;
+                self.symbol_table.exit_scope();
+            }
+            Stmt::ForEach(for_each) => {
+                self.type_check_for_each(for_each, expected_return)?;
+            }
+            Stmt::Switch(switch_stmt) => {
+                self.type_check_switch(switch_stmt, expected_return)?;
+            }
+            Stmt::Scope(scope_stmt) => {
+                self.type_check_statement(&Stmt::Block(scope_stmt.body.clone()), expected_return)?;
+            }
+            Stmt::Defer(defer) => {
+                self.type_check_defer(defer, expected_return)?;
+            }
+            Stmt::TryWithResources(try_stmt) => {
+                self.type_check_try_with_resources(try_stmt, expected_return)?;
             }
             _ => {}
         }
         
         Ok(())
     }
+
+    /// 类型检查分支语句，单条语句的分支也拥有独立作用域
+    pub(super) fn type_check_branch(&mut self, stmt: &Stmt, expected_return: Option<&Type>) -> cayResult<()> {
+        if matches!(stmt, Stmt::Block(_)) {
+            return self.type_check_statement(stmt, expected_return);
+        }
+        self.symbol_table.enter_scope();
+        let result = self.type_check_statement(stmt, expected_return);
+        self.symbol_table.exit_scope();
+        result
+    }
 }
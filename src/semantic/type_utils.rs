@@ -2,8 +2,9 @@
 
 use crate::ast::Expr;
 use crate::types::{Type, ParameterInfo};
-use crate::error::cayResult;
+use crate::error::{cayError, cayResult, semantic_error};
 use super::analyzer::SemanticAnalyzer;
+use crate::diagnostic::ErrorCodes;
 
 impl SemanticAnalyzer {
     /// 检查类型兼容性
@@ -15,9 +16,19 @@ impl SemanticAnalyzer {
             return true;
         }
 
-        // null 可以赋值给任何引用类型（包括 string 和指针）
+        // 可空性由空安全检查单独处理，这里只比较内部类型
+        if from.is_nullable() || to.is_nullable() {
+            return self.types_compatible(from.non_null(), to.non_null());
+        }
+
+        // 未解析的泛型实例化按单态化类型比较
+        if matches!(from, Type::Generic(_, _)) || matches!(to, Type::Generic(_, _)) {
+            return self.types_compatible(&from.resolve_generics(), &to.resolve_generics());
+        }
+
+        // null 可以赋值给任何引用类型（包括 string 和指针），结构体是值类型，不能为 null
         if let Type::Object(obj_name) = from {
-            if obj_name == "Object" {
+            if obj_name == "Object" && !matches!(to, Type::Struct(_)) {
                 // null 是 Object 类型，可以赋值给 String 或其他引用类型
                 return true;
             }
@@ -32,6 +43,15 @@ impl SemanticAnalyzer {
 
         // 基本类型之间的兼容
         match (from, to) {
+            // byte/short/uint/ulong 与同宽的 FFI 类型可以互换
+            (Type::Int8, Type::CChar) | (Type::CChar, Type::Int8) => true,
+            (Type::UInt8, Type::CUChar) | (Type::CUChar, Type::UInt8) => true,
+            (Type::Int16, Type::CShort) | (Type::CShort, Type::Int16) => true,
+            (Type::UInt16, Type::CUShort) | (Type::CUShort, Type::UInt16) => true,
+            (Type::UInt32, Type::CUInt) | (Type::CUInt, Type::UInt32) => true,
+            (Type::UInt64, Type::SizeT | Type::UIntPtr) | (Type::SizeT | Type::UIntPtr, Type::UInt64) => true,
+            // 其余只允许不损失数值的扩展，收窄或改变符号需要显式转换
+            (from, to) if from.is_narrow_or_unsigned() || to.is_narrow_or_unsigned() => from.widens_to(to),
             (Type::Int32, Type::Int64) => true,
             (Type::Int32, Type::Float32) => true,
             (Type::Int32, Type::Float64) => true,
@@ -42,6 +62,12 @@ impl SemanticAnalyzer {
                 // 检查继承关系：from_name 是否是 to_name 的子类
                 self.is_subtype_of(from_name, to_name)
             }
+            // 泛型枚举实例在未解析的签名中表现为同名的单态化类型
+            (Type::Enum(from_name), Type::Object(to_name)) | (Type::Object(from_name), Type::Enum(to_name)) => from_name == to_name,
+            // 尚未确定类型实参的内置变体（None、Err(e) 等）兼容该泛型枚举的任意实例
+            (Type::Enum(from_name), Type::Enum(to_name)) => {
+                self.type_registry.enum_origin(to_name) == Some(from_name.as_str())
+            }
             // char 可以赋值给 int (ASCII 码值)
             (Type::Char, Type::Int32) => true,
             (Type::Char, Type::Int64) => true,
@@ -136,7 +162,12 @@ impl SemanticAnalyzer {
         match (left, right) {
             (Type::Float64, _) | (_, Type::Float64) => Type::Float64,
             (Type::Float32, _) | (_, Type::Float32) => Type::Float32,
+            // 位宽大的整数类型优先，位宽相同时无符号类型优先
+            (Type::UInt64, _) | (_, Type::UInt64) => Type::UInt64,
             (Type::Int64, _) | (_, Type::Int64) => Type::Int64,
+            (Type::UInt32, _) | (_, Type::UInt32) => Type::UInt32,
+            // byte、ubyte、short、ushort 在运算中提升为 int
+            (Type::Int8 | Type::UInt8 | Type::Int16 | Type::UInt16, _) | (_, Type::Int8 | Type::UInt8 | Type::Int16 | Type::UInt16) => Type::Int32,
             // char 类型在算术运算中提升为 int32
             (Type::Char, Type::Char) => Type::Int32,
             (Type::Char, Type::Int32) | (Type::Int32, Type::Char) => Type::Int32,
@@ -152,6 +183,7 @@ impl SemanticAnalyzer {
         matches!(ty, 
             // 内置数值类型
             Type::Int32 | Type::Int64 | Type::Float32 | Type::Float64 | Type::Char |
+            Type::Int8 | Type::UInt8 | Type::Int16 | Type::UInt16 | Type::UInt32 | Type::UInt64 |
             // FFI 数值类型
             Type::CInt | Type::CUInt | Type::CLong |
             Type::CShort | Type::CUShort | Type::CChar | Type::CUChar |
@@ -163,7 +195,8 @@ impl SemanticAnalyzer {
     /// 检查 subtype 是否是 supertype 的子类型
     ///
     /// 通过递归遍历继承层次结构来确定类型兼容性。
-    /// 子类可以赋值给父类（里氏替换原则）。
+    /// 子类可以赋值给父类（里氏替换原则），实现了接口的类（包括从父类继承接口的子类）
+    /// 可以赋值给该接口。
     ///
     /// # Arguments
     /// * `subtype` - 待检查的子类型名称
@@ -175,16 +208,17 @@ impl SemanticAnalyzer {
     /// # Algorithm
     /// 时间复杂度: O(h)，其中 h 是继承链的高度
     /// 空间复杂度: O(1)，迭代实现避免递归栈溢出
-    fn is_subtype_of(&self, subtype: &str, supertype: &str) -> bool {
+    pub(super) fn is_subtype_of(&self, subtype: &str, supertype: &str) -> bool {
         // 相同类型必然是子类型
         if subtype == supertype {
             return true;
         }
         
-        // 特殊处理：所有类都是 Object 的子类型
+        // 特殊处理：所有类和接口都是 Object 的子类型
         if supertype == "Object" {
             // 检查 subtype 是否是一个有效的类名（不是内置类型别名）
             return self.type_registry.class_exists(subtype)
+                || self.type_registry.interface_exists(subtype)
                 || subtype == "String"
                 || subtype == "Function";
         }
@@ -200,6 +234,9 @@ impl SemanticAnalyzer {
             }
             
             if let Some(class_info) = self.type_registry.get_class(&current) {
+                if class_info.interfaces.iter().any(|i| i == supertype) {
+                    return true;
+                }
                 match &class_info.parent {
                     Some(parent) => {
                         if parent == supertype {
@@ -220,18 +257,20 @@ impl SemanticAnalyzer {
     /// 整数类型提升
     pub fn promote_integer_types(&self, left: &Type, right: &Type) -> Type {
         match (left, right) {
+            (Type::UInt64, _) | (_, Type::UInt64) => Type::UInt64,
             (Type::Int64, _) | (_, Type::Int64) => Type::Int64,
+            (Type::UInt32, _) | (_, Type::UInt32) => Type::UInt32,
             _ => Type::Int32,
         }
     }
 
     /// 检查参数是否与参数定义兼容（支持可变参数）
-    pub fn check_arguments_compatible(&mut self, args: &[Expr], params: &[ParameterInfo], _line: usize, _column: usize) -> Result<(), String> {
+    pub fn check_arguments_compatible(&mut self, args: &[Expr], params: &[ParameterInfo], line: usize, column: usize) -> cayResult<()> {
         if params.is_empty() {
             if args.is_empty() {
                 return Ok(());
             } else {
-                return Err(format!("Expected 0 arguments, got {}", args.len()));
+                return Err(semantic_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, format!("Expected 0 arguments, got {}", args.len())));
             }
         }
 
@@ -240,15 +279,15 @@ impl SemanticAnalyzer {
         if params[last_idx].is_varargs {
             // 可变参数：至少需要 params.len() - 1 个参数
             if args.len() < last_idx {
-                return Err(format!("Expected at least {} arguments, got {}", last_idx, args.len()));
+                return Err(semantic_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, format!("Expected at least {} arguments, got {}", last_idx, args.len())));
             }
 
             // 检查固定参数
             for i in 0..last_idx {
-                let arg_type = self.infer_expr_type(&args[i]).map_err(|e| e.to_string())?;
+                let arg_type = self.infer_expr_type_expecting(&args[i], &params[i].param_type).map_err(|e| argument_error(e, line, column))?;
                 if !self.types_compatible(&arg_type, &params[i].param_type) {
-                    return Err(format!("Argument {} type mismatch: expected {}, got {}",
-                        i + 1, params[i].param_type, arg_type));
+                    return Err(semantic_error(ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH, line, column, format!("Argument {} type mismatch: expected {}, got {}",
+                        i + 1, params[i].param_type, arg_type)));
                 }
             }
 
@@ -262,7 +301,7 @@ impl SemanticAnalyzer {
 
             // 如果只有一个参数且类型匹配数组类型，直接接受（传递数组给可变参数）
             if args.len() == last_idx + 1 {
-                let arg_type = self.infer_expr_type(&args[last_idx]).map_err(|e| e.to_string())?;
+                let arg_type = self.infer_expr_type(&args[last_idx]).map_err(|e| argument_error(e, line, column))?;
                 if self.types_compatible(&arg_type, vararg_param_type) {
                     // 参数类型与可变参数的数组类型匹配，直接接受
                     return Ok(());
@@ -271,23 +310,23 @@ impl SemanticAnalyzer {
 
             // 否则，按元素类型检查每个参数
             for i in last_idx..args.len() {
-                let arg_type = self.infer_expr_type(&args[i]).map_err(|e| e.to_string())?;
+                let arg_type = self.infer_expr_type(&args[i]).map_err(|e| argument_error(e, line, column))?;
                 if !self.types_compatible(&arg_type, vararg_element_type) {
-                    return Err(format!("Varargs argument {} type mismatch: expected {}, got {}",
-                        i + 1, vararg_element_type, arg_type));
+                    return Err(semantic_error(ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH, line, column, format!("Varargs argument {} type mismatch: expected {}, got {}",
+                        i + 1, vararg_element_type, arg_type)));
                 }
             }
         } else {
             // 非可变参数：参数数量必须完全匹配
             if params.len() != args.len() {
-                return Err(format!("Expected {} arguments, got {}", params.len(), args.len()));
+                return Err(semantic_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, format!("Expected {} arguments, got {}", params.len(), args.len())));
             }
 
             for (i, (arg, param)) in args.iter().zip(params.iter()).enumerate() {
-                let arg_type = self.infer_expr_type(arg).map_err(|e| e.to_string())?;
+                let arg_type = self.infer_expr_type_expecting(arg, &param.param_type).map_err(|e| argument_error(e, line, column))?;
                 if !self.types_compatible(&arg_type, &param.param_type) {
-                    return Err(format!("Argument {} type mismatch: expected {}, got {}",
-                        i + 1, param.param_type, arg_type));
+                    return Err(semantic_error(ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH, line, column, format!("Argument {} type mismatch: expected {}, got {}",
+                        i + 1, param.param_type, arg_type)));
                 }
             }
         }
@@ -300,108 +339,119 @@ impl SemanticAnalyzer {
         match method_name {
             "length" => {
                 if !args.is_empty() {
-                    return Err(self.report_error(line, column, "String.length() takes no arguments".to_string()));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.length() takes no arguments".to_string()));
                 }
                 Ok(Type::Int32)
             }
             "substring" => {
                 if args.is_empty() || args.len() > 2 {
-                    return Err(self.report_error(line, column, "String.substring() takes 1 or 2 arguments".to_string()));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.substring() takes 1 or 2 arguments".to_string()));
                 }
                 // 检查参数类型
                 for (i, arg) in args.iter().enumerate() {
                     let arg_type = self.infer_expr_type(arg)?;
                     if !arg_type.is_integer() {
-                        return Err(self.report_error(line, column, format!("Argument {} of substring() must be integer, got {}", i + 1, arg_type)));
+                        return Err(self.report_error(ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH, line, column, format!("Argument {} of substring() must be integer, got {}", i + 1, arg_type)));
                     }
                 }
                 Ok(Type::String)
             }
             "indexOf" => {
                 if args.len() != 1 {
-                    return Err(self.report_error(line, column, "String.indexOf() takes 1 argument".to_string()));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.indexOf() takes 1 argument".to_string()));
                 }
                 let arg_type = self.infer_expr_type(&args[0])?;
                 if arg_type != Type::String {
-                    return Err(self.report_error(line, column, format!("Argument of indexOf() must be string, got {}", arg_type)));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH, line, column, format!("Argument of indexOf() must be string, got {}", arg_type)));
                 }
                 Ok(Type::Int32)
             }
             "lastIndexOf" => {
                 if args.len() != 1 {
-                    return Err(self.report_error(line, column, "String.lastIndexOf() takes 1 argument".to_string()));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.lastIndexOf() takes 1 argument".to_string()));
                 }
                 let arg_type = self.infer_expr_type(&args[0])?;
                 if arg_type != Type::String {
-                    return Err(self.report_error(line, column, format!("Argument of lastIndexOf() must be string, got {}", arg_type)));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH, line, column, format!("Argument of lastIndexOf() must be string, got {}", arg_type)));
                 }
                 Ok(Type::Int32)
             }
             "charAt" => {
                 if args.len() != 1 {
-                    return Err(self.report_error(line, column, "String.charAt() takes 1 argument".to_string()));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.charAt() takes 1 argument".to_string()));
                 }
                 let arg_type = self.infer_expr_type(&args[0])?;
                 if !arg_type.is_integer() {
-                    return Err(self.report_error(line, column, format!("Argument of charAt() must be integer, got {}", arg_type)));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH, line, column, format!("Argument of charAt() must be integer, got {}", arg_type)));
                 }
                 Ok(Type::Char)
             }
             "replace" => {
                 if args.len() != 2 {
-                    return Err(self.report_error(line, column, "String.replace() takes 2 arguments".to_string()));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.replace() takes 2 arguments".to_string()));
                 }
                 for (i, arg) in args.iter().enumerate() {
                     let arg_type = self.infer_expr_type(arg)?;
                     if arg_type != Type::String {
-                        return Err(self.report_error(line, column, format!("Argument {} of replace() must be string, got {}", i + 1, arg_type)));
+                        return Err(self.report_error(ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH, line, column, format!("Argument {} of replace() must be string, got {}", i + 1, arg_type)));
                     }
                 }
                 Ok(Type::String)
             }
             "isEmpty" => {
                 if !args.is_empty() {
-                    return Err(self.report_error(line, column, "String.isEmpty() takes no arguments".to_string()));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.isEmpty() takes no arguments".to_string()));
                 }
                 Ok(Type::Bool)
             }
+            "hashCode" => {
+                if !args.is_empty() {
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.hashCode() takes no arguments".to_string()));
+                }
+                Ok(Type::Int32)
+            }
             "equals" => {
                 if args.len() != 1 {
-                    return Err(self.report_error(line, column, "String.equals() takes 1 argument".to_string()));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.equals() takes 1 argument".to_string()));
                 }
                 let arg_type = self.infer_expr_type(&args[0])?;
                 if arg_type != Type::String {
-                    return Err(self.report_error(line, column, format!("Argument of equals() must be string, got {}", arg_type)));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH, line, column, format!("Argument of equals() must be string, got {}", arg_type)));
                 }
                 Ok(Type::Bool)
             }
             "c_str" => {
                 if !args.is_empty() {
-                    return Err(self.report_error(line, column, "String.c_str() takes no arguments".to_string()));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.c_str() takes no arguments".to_string()));
                 }
                 Ok(Type::Int64)  // 返回 long 类型，与 StringBuilder.cay 中的使用一致
             }
             "startsWith" => {
                 if args.len() != 1 {
-                    return Err(self.report_error(line, column, "String.startsWith() takes 1 argument".to_string()));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.startsWith() takes 1 argument".to_string()));
                 }
                 let arg_type = self.infer_expr_type(&args[0])?;
                 if arg_type != Type::String {
-                    return Err(self.report_error(line, column, format!("Argument of startsWith() must be string, got {}", arg_type)));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH, line, column, format!("Argument of startsWith() must be string, got {}", arg_type)));
                 }
                 Ok(Type::Bool)
             }
             "endsWith" => {
                 if args.len() != 1 {
-                    return Err(self.report_error(line, column, "String.endsWith() takes 1 argument".to_string()));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_WRONG_ARGUMENT_COUNT, line, column, "String.endsWith() takes 1 argument".to_string()));
                 }
                 let arg_type = self.infer_expr_type(&args[0])?;
                 if arg_type != Type::String {
-                    return Err(self.report_error(line, column, format!("Argument of endsWith() must be string, got {}", arg_type)));
+                    return Err(self.report_error(ErrorCodes::SEMANTIC_ARGUMENT_TYPE_MISMATCH, line, column, format!("Argument of endsWith() must be string, got {}", arg_type)));
                 }
                 Ok(Type::Bool)
             }
-            _ => Err(self.report_error(line, column, format!("Unknown String method '{}'", method_name))),
+            _ => Err(self.report_error(ErrorCodes::SEMANTIC_METHOD_NOT_FOUND, line, column, format!("Unknown String method '{}'", method_name))),
         }
     }
 }
+
+/// 实参的类型推断错误，报告在调用处（保留原错误的代码）
+fn argument_error(error: cayError, line: usize, column: usize) -> cayError {
+    semantic_error(error.code(), line, column, error.to_string())
+}
@@ -1,11 +1,18 @@
 use std::fmt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use crate::diagnostic::ErrorCodes;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Type {
     Void,
     Int32,
     Int64,
+    Int8,    // byte
+    UInt8,   // ubyte
+    Int16,   // short
+    UInt16,  // ushort
+    UInt32,  // uint
+    UInt64,  // ulong
     Float32,
     Float64,
     Bool,
@@ -35,6 +42,13 @@ pub enum Type {
     Pointer(Box<Type>),  // 通用指针类型: Pointer(CVoid) = void*
     // FFI 结构体类型
     Struct(String),      // 命名结构体: Struct("SDL_Window")
+    // 泛型
+    TypeParam(String),          // 泛型类型参数: T
+    Generic(String, Vec<Type>), // 泛型实例化: Box<int>
+    // 枚举
+    Enum(String),               // 枚举类型（按引用传递的带标签结构体）: Enum("Shape")
+    // 空安全
+    Nullable(Box<Type>),        // 可空引用类型: String?，与内部类型的表示相同
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -44,6 +58,13 @@ pub struct FunctionType {
     pub is_static: bool,
 }
 
+/// 泛型类型参数信息: T 或 A: Allocator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeParamInfo {
+    pub name: String,
+    pub bound: Option<String>,  // 上界（类或接口名）
+}
+
 #[derive(Debug, Clone)]
 pub struct ClassInfo {
     pub name: String,
@@ -55,6 +76,34 @@ pub struct ClassInfo {
     pub interfaces: Vec<String>,  // 实现的接口列表
     pub is_abstract: bool,  // 是否是抽象类
     pub is_final: bool,  // 是否是final类（禁止继承）
+    pub is_sealed: bool,  // 是否是sealed类（只允许 permits 列出的类继承）
+    pub permits: Vec<String>,  // sealed 类允许的直接子类
+    pub type_params: Vec<TypeParamInfo>,  // 泛型类型参数（非泛型类为空）
+    pub generic_supertypes: Vec<Type>,  // 泛型父类/接口（如 extends Base<T>）
+    pub annotations: Vec<crate::ast::Annotation>,
+    pub properties: HashMap<String, PropertyInfo>,  // 属性: obj.name 读写对应的访问方法
+}
+
+/// 属性信息（property 声明）
+#[derive(Debug, Clone)]
+pub struct PropertyInfo {
+    pub name: String,
+    pub property_type: Type,
+    pub is_static: bool,
+    /// 自动属性：访问器直接读写同名的私有字段，访问时内联为字段读写
+    pub is_auto: bool,
+    pub getter: Option<AccessorInfo>,
+    pub setter: Option<AccessorInfo>,
+    pub annotations: Vec<crate::ast::Annotation>,
+}
+
+/// 属性访问器：对应的访问方法名和访问权限
+#[derive(Debug, Clone)]
+pub struct AccessorInfo {
+    pub method_name: String,
+    pub is_public: bool,
+    pub is_private: bool,
+    pub is_protected: bool,
 }
 
 /// 构造函数信息
@@ -66,10 +115,65 @@ pub struct ConstructorInfo {
     pub is_protected: bool,
 }
 
+/// 枚举信息
+#[derive(Debug, Clone)]
+pub struct EnumInfo {
+    pub name: String,
+    pub variants: Vec<EnumVariantInfo>,  // 按声明顺序，下标即运行时标签值
+    pub type_params: Vec<TypeParamInfo>,  // 泛型类型参数（非泛型枚举为空）
+}
+
+/// 枚举变体信息
+#[derive(Debug, Clone)]
+pub struct EnumVariantInfo {
+    pub name: String,
+    pub fields: Vec<ParameterInfo>,  // 负载字段（无负载变体为空）
+}
+
+impl EnumInfo {
+    /// 按名称查找变体，返回 (标签值, 变体信息)
+    pub fn find_variant(&self, name: &str) -> Option<(usize, &EnumVariantInfo)> {
+        self.variants.iter().enumerate().find(|(_, v)| v.name == name)
+    }
+
+    /// 用类型实参实例化泛型枚举模板，生成单态化名称下的具体枚举
+    pub fn instantiate(&self, name: &str, args: &[Type]) -> EnumInfo {
+        let bindings = generic_bindings(&self.type_params, args);
+        EnumInfo {
+            name: name.to_string(),
+            variants: self.variants.iter().map(|v| EnumVariantInfo {
+                name: v.name.clone(),
+                fields: v.fields.iter()
+                    .map(|f| ParameterInfo::new(f.name.clone(), f.param_type.substitute(&bindings)))
+                    .collect(),
+            }).collect(),
+            type_params: Vec::new(),
+        }
+    }
+}
+
+/// 结构体信息
+#[derive(Debug, Clone)]
+pub struct StructInfo {
+    pub name: String,
+    pub fields: Vec<ParameterInfo>,  // 按声明顺序，下标即 LLVM 结构体中的字段位置
+}
+
+impl StructInfo {
+    /// 按名称查找字段，返回 (字段位置, 字段信息)
+    pub fn find_field(&self, name: &str) -> Option<(usize, &ParameterInfo)> {
+        self.fields.iter().enumerate().find(|(_, f)| f.name == name)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct InterfaceInfo {
     pub name: String,
     pub methods: HashMap<String, MethodInfo>,
+    pub type_params: Vec<TypeParamInfo>,  // 泛型类型参数（非泛型接口为空）
+    pub default_methods: HashSet<String>,  // 带默认实现的方法名
+    pub is_sealed: bool,  // 是否是sealed接口（只允许 permits 列出的类实现）
+    pub permits: Vec<String>,  // sealed 接口允许的实现类
 }
 
 impl ClassInfo {
@@ -140,6 +244,69 @@ impl ClassInfo {
         }
     }
 
+    /// 以给定类型实参实例化泛型类（只替换类型参数，嵌套的泛型实例化保持不变）
+    pub fn instantiate(&self, name: &str, args: &[Type]) -> ClassInfo {
+        let bindings = generic_bindings(&self.type_params, args);
+        let mut info = self.clone();
+        info.name = name.to_string();
+        info.type_params = Vec::new();
+        for methods in info.methods.values_mut() {
+            for method in methods.iter_mut() {
+                *method = method.substitute(&bindings);
+                method.class_name = name.to_string();
+            }
+        }
+        for field in info.fields.values_mut() {
+            field.field_type = field.field_type.substitute(&bindings);
+        }
+        for property in info.properties.values_mut() {
+            property.property_type = property.property_type.substitute(&bindings);
+        }
+        for ctor in &mut info.constructors {
+            for param in &mut ctor.params {
+                param.param_type = param.param_type.substitute(&bindings);
+            }
+        }
+        // 泛型父类/接口随类型实参一起替换，并更新对应的单态化名称
+        let mut supertypes = Vec::new();
+        for supertype in &self.generic_supertypes {
+            let old_name = supertype.resolve_generics().to_string();
+            let substituted = supertype.substitute(&bindings);
+            let new_name = substituted.resolve_generics().to_string();
+            if info.parent.as_deref() == Some(old_name.as_str()) {
+                info.parent = Some(new_name.clone());
+            }
+            for iface in &mut info.interfaces {
+                if *iface == old_name {
+                    *iface = new_name.clone();
+                }
+            }
+            supertypes.push(substituted);
+        }
+        info.generic_supertypes = supertypes;
+        info
+    }
+
+    /// 对类中所有成员签名的类型应用变换
+    pub fn map_types(&mut self, f: &mut impl FnMut(&Type) -> Type) {
+        for methods in self.methods.values_mut() {
+            for method in methods.iter_mut() {
+                method.map_types(f);
+            }
+        }
+        for field in self.fields.values_mut() {
+            field.field_type = f(&field.field_type);
+        }
+        for property in self.properties.values_mut() {
+            property.property_type = f(&property.property_type);
+        }
+        for ctor in &mut self.constructors {
+            for param in &mut ctor.params {
+                param.param_type = f(&param.param_type);
+            }
+        }
+    }
+
     /// 根据方法名查找第一个匹配的方法（用于无参数的情况）
     pub fn find_method_by_name(&self, name: &str) -> Option<&MethodInfo> {
         self.methods.get(name)?.first()
@@ -150,10 +317,26 @@ impl ClassInfo {
         if param_type == arg_type {
             return true;
         }
+        // 泛型方法的类型参数可以匹配任意实参，具体类型在调用处推断
+        if matches!(param_type, Type::TypeParam(_)) {
+            return true;
+        }
+        // 类型参数出现在数组或泛型实例内部时按结构匹配，类型实参的一致性由调用处的推断检查
+        match (param_type, arg_type) {
+            (Type::Array(param_elem), Type::Array(arg_elem)) if param_elem.is_generic() => {
+                return Self::types_match(param_elem, arg_elem);
+            }
+            (Type::Generic(origin, _), Type::Object(instance)) => {
+                return instance.strip_prefix(origin.as_str()).is_some_and(|rest| rest.starts_with('$'));
+            }
+            _ => {}
+        }
         // 允许 int -> long, int -> float, int -> double 等隐式转换
         // 也允许 double -> float 的显式转换（用于字面量）
         match (param_type, arg_type) {
             (Type::Int64, Type::Int32) => true,
+            // byte/short/uint/ulong 等只接受不损失数值的扩展
+            (param, arg) if param.is_narrow_or_unsigned() || arg.is_narrow_or_unsigned() => arg.widens_to(param),
             (Type::Float32, Type::Int32) => true,
             (Type::Float64, Type::Int32) => true,
             (Type::Float64, Type::Int64) => true,
@@ -178,6 +361,10 @@ impl ClassInfo {
             (Type::Pointer(_), Type::Object(obj_name)) if obj_name == "Object" => true,
             // 数组类型可以匹配指针类型（数组退化为指针）
             (Type::Pointer(_), Type::Array(_)) => true,
+            // 尚未确定类型实参的内置变体（None、Err(e) 等）可以匹配该泛型枚举的任意实例
+            (Type::Enum(param_name), Type::Enum(arg_name)) => {
+                param_name.strip_prefix(arg_name.as_str()).is_some_and(|rest| rest.starts_with('$'))
+            }
             // 函数指针类型匹配：允许将静态方法作为函数指针传递
             (Type::Function(expected), Type::Function(actual)) => {
                 // 检查返回类型是否匹配
@@ -210,6 +397,8 @@ pub struct MethodInfo {
     pub is_native: bool,
     pub is_override: bool,  // 标记是否是重写方法
     pub is_final: bool,  // 是否是final方法（禁止重写）
+    pub type_params: Vec<TypeParamInfo>,  // 泛型方法的类型参数
+    pub annotations: Vec<crate::ast::Annotation>,
 }
 
 #[derive(Debug, Clone)]
@@ -222,6 +411,7 @@ pub struct FieldInfo {
     pub is_static: bool,
     pub is_final: bool,  // 是否是final字段（编译期常量）
     pub is_const_expr: bool,  // 是否是编译期常量（static final且初始化值为常量）
+    pub annotations: Vec<crate::ast::Annotation>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -229,6 +419,7 @@ pub struct ParameterInfo {
     pub name: String,
     pub param_type: Type,
     pub is_varargs: bool,  // 是否为可变参数
+    pub annotations: Vec<crate::ast::Annotation>,
 }
 
 impl ParameterInfo {
@@ -237,6 +428,7 @@ impl ParameterInfo {
             name,
             param_type,
             is_varargs: false,
+            annotations: Vec::new(),
         }
     }
 
@@ -246,16 +438,45 @@ impl ParameterInfo {
             name,
             param_type: Type::Array(Box::new(param_type)),
             is_varargs: true,
+            annotations: Vec::new(),
         }
     }
 }
 
+impl crate::ast::Annotated for ParameterInfo {
+    fn annotations(&self) -> &[crate::ast::Annotation] {
+        &self.annotations
+    }
+}
+
+impl crate::ast::Annotated for MethodInfo {
+    fn annotations(&self) -> &[crate::ast::Annotation] {
+        &self.annotations
+    }
+}
+
+impl crate::ast::Annotated for FieldInfo {
+    fn annotations(&self) -> &[crate::ast::Annotation] {
+        &self.annotations
+    }
+}
+
+impl crate::ast::Annotated for ClassInfo {
+    fn annotations(&self) -> &[crate::ast::Annotation] {
+        &self.annotations
+    }
+}
+
 impl Type {
     pub fn size_in_bytes(&self) -> usize {
         match self {
             Type::Void => 0,
             Type::Int32 => 4,
             Type::Int64 => 8,
+            Type::Int8 | Type::UInt8 => 1,
+            Type::Int16 | Type::UInt16 => 2,
+            Type::UInt32 => 4,
+            Type::UInt64 => 8,
             Type::Float32 => 4,
             Type::Float64 => 8,
             Type::Bool => 1,
@@ -283,7 +504,12 @@ impl Type {
             Type::CBool => 1,      // C bool 通常为 1 字节
             // FFI 指针和结构体
             Type::Pointer(_) => 8, // 指针大小 (64位系统)
-            Type::Struct(_) => 8,  // 结构体作为指针传递，实际大小由编译器决定
+            Type::Struct(_) => 8,  // 结构体按值存储，实际大小由字段布局决定（代码生成时按 LLVM 类型计算）
+            // 泛型在单态化后都是引用或具体类型，未替换时按引用处理
+            Type::TypeParam(_) => 8,
+            Type::Generic(_, _) => 8,
+            Type::Enum(_) => 8,  // 指向带标签结构体的指针
+            Type::Nullable(inner) => inner.size_in_bytes(),
         }
     }
 
@@ -294,6 +520,7 @@ impl Type {
             // 内置数值类型
             Type::Int32 | 
             Type::Int64 | 
+            Type::Int8 | Type::UInt8 | Type::Int16 | Type::UInt16 | Type::UInt32 | Type::UInt64 |
             Type::Float32 | 
             Type::Float64 | 
             Type::Bool | 
@@ -307,11 +534,189 @@ impl Type {
     }
 
     pub fn is_reference_type(&self) -> bool {
-        matches!(self, Type::String | Type::Object(_) | Type::Array(_))
+        matches!(self, Type::String | Type::Object(_) | Type::Array(_) | Type::Enum(_) | Type::Nullable(_))
+    }
+
+    /// 是否是可空类型 T?
+    pub fn is_nullable(&self) -> bool {
+        matches!(self, Type::Nullable(_))
+    }
+
+    /// 去掉可空标记后的类型：String? -> String，其他类型原样返回
+    pub fn non_null(&self) -> &Type {
+        match self {
+            Type::Nullable(inner) => inner.non_null(),
+            other => other,
+        }
     }
 
     pub fn is_integer(&self) -> bool {
-        matches!(self, Type::Int32 | Type::Int64)
+        self.integer_bits().is_some()
+    }
+
+    /// byte、ubyte、short、ushort、uint、ulong：参与运算时需要按位宽和符号提升的整数类型
+    pub fn is_narrow_or_unsigned(&self) -> bool {
+        matches!(self, Type::Int8 | Type::UInt8 | Type::Int16 | Type::UInt16 | Type::UInt32 | Type::UInt64)
+    }
+
+    /// 无符号整数类型：除法、比较、右移和扩展按无符号处理
+    pub fn is_unsigned(&self) -> bool {
+        matches!(self, Type::UInt8 | Type::UInt16 | Type::UInt32 | Type::UInt64)
+    }
+
+    /// 整数类型的位宽
+    pub fn integer_bits(&self) -> Option<u32> {
+        match self {
+            Type::Int8 | Type::UInt8 => Some(8),
+            Type::Int16 | Type::UInt16 => Some(16),
+            Type::Int32 | Type::UInt32 => Some(32),
+            Type::Int64 | Type::UInt64 => Some(64),
+            _ => None,
+        }
+    }
+
+    /// 整数类型的取值范围
+    pub fn integer_range(&self) -> Option<(i128, i128)> {
+        let bits = self.integer_bits()?;
+        if self.is_unsigned() {
+            Some((0, (1i128 << bits) - 1))
+        } else {
+            Some((-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1))
+        }
+    }
+
+    /// 是否可以不经转换、不损失数值地隐式扩展为 `target`
+    ///
+    /// 整数只能扩展为更宽的整数，有符号数不能扩展为无符号数；任何整数都可以转换为浮点数。
+    pub fn widens_to(&self, target: &Type) -> bool {
+        match (self.integer_bits(), target.integer_bits()) {
+            (Some(from), Some(to)) => from < to && (self.is_unsigned() || !target.is_unsigned()),
+            (Some(_), None) => matches!(target, Type::Float32 | Type::Float64),
+            _ => false,
+        }
+    }
+
+    /// 用类型实参替换类型参数（T -> int）
+    pub fn substitute(&self, bindings: &HashMap<String, Type>) -> Type {
+        match self {
+            Type::TypeParam(name) => bindings.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Type::Generic(name, args) => Type::Generic(
+                name.clone(),
+                args.iter().map(|a| a.substitute(bindings)).collect(),
+            ),
+            Type::Array(inner) => Type::Array(Box::new(inner.substitute(bindings))),
+            Type::Pointer(inner) => Type::Pointer(Box::new(inner.substitute(bindings))),
+            Type::Nullable(inner) => Type::Nullable(Box::new(inner.substitute(bindings))),
+            Type::Function(func_type) => Type::Function(Box::new(FunctionType {
+                params: func_type.params.iter().map(|p| p.substitute(bindings)).collect(),
+                return_type: Box::new(func_type.return_type.substitute(bindings)),
+                is_static: func_type.is_static,
+            })),
+            _ => self.clone(),
+        }
+    }
+
+    /// 将泛型实例化类型替换为单态化后的类类型（Box<int> -> Box$i32）
+    ///
+    /// 单态化后的类型不再区分是否可空。
+    pub fn resolve_generics(&self) -> Type {
+        match self {
+            Type::Nullable(inner) => inner.resolve_generics(),
+            Type::Generic(name, args) => {
+                let args: Vec<Type> = args.iter().map(|a| a.resolve_generics()).collect();
+                Type::Object(mangle_generic_name(name, &args))
+            }
+            Type::Array(inner) => Type::Array(Box::new(inner.resolve_generics())),
+            Type::Pointer(inner) => Type::Pointer(Box::new(inner.resolve_generics())),
+            Type::Function(func_type) => Type::Function(Box::new(FunctionType {
+                params: func_type.params.iter().map(|p| p.resolve_generics()).collect(),
+                return_type: Box::new(func_type.return_type.resolve_generics()),
+                is_static: func_type.is_static,
+            })),
+            _ => self.clone(),
+        }
+    }
+
+    /// 检查类型中是否包含泛型实例化或类型参数
+    pub fn is_generic(&self) -> bool {
+        match self {
+            Type::TypeParam(_) | Type::Generic(_, _) => true,
+            Type::Array(inner) | Type::Pointer(inner) | Type::Nullable(inner) => inner.is_generic(),
+            Type::Function(func_type) => {
+                func_type.params.iter().any(|p| p.is_generic()) || func_type.return_type.is_generic()
+            }
+            _ => false,
+        }
+    }
+
+    /// 类型在单态化名称中的表示
+    fn mangle_component(&self) -> String {
+        match self {
+            Type::Int32 => "i32".to_string(),
+            Type::Int64 => "i64".to_string(),
+            Type::Int8 => "i8".to_string(),
+            Type::UInt8 => "u8".to_string(),
+            Type::Int16 => "i16".to_string(),
+            Type::UInt16 => "u16".to_string(),
+            Type::UInt32 => "u32".to_string(),
+            Type::UInt64 => "u64".to_string(),
+            Type::Float32 => "f32".to_string(),
+            Type::Float64 => "f64".to_string(),
+            Type::Bool => "bool".to_string(),
+            Type::Char => "char".to_string(),
+            Type::String => "String".to_string(),
+            Type::Object(name) | Type::TypeParam(name) | Type::Struct(name) | Type::Enum(name) => name.clone(),
+            Type::Array(inner) => format!("arr_{}", inner.mangle_component()),
+            Type::Pointer(inner) => format!("ptr_{}", inner.mangle_component()),
+            Type::Generic(name, args) => mangle_generic_name(name, args),
+            Type::Nullable(inner) => inner.mangle_component(),
+            other => other.to_string().replace(' ', "_"),
+        }
+    }
+}
+
+/// 生成泛型实例的单态化名称: ArrayList<int> -> ArrayList$i32
+///
+/// 标识符不能包含 `$`，单态化名称不会与用户声明的类型（如 `ArrayList_i32`）重名。
+pub fn mangle_generic_name(name: &str, args: &[Type]) -> String {
+    let mut mangled = name.to_string();
+    for arg in args {
+        mangled.push('$');
+        mangled.push_str(&arg.mangle_component());
+    }
+    mangled
+}
+
+/// 根据类型参数和类型实参构建替换表
+pub fn generic_bindings(params: &[TypeParamInfo], args: &[Type]) -> HashMap<String, Type> {
+    params.iter()
+        .zip(args.iter())
+        .map(|(p, a)| (p.name.clone(), a.clone()))
+        .collect()
+}
+
+impl MethodInfo {
+    /// 替换方法签名中的类型参数
+    pub fn substitute(&self, bindings: &HashMap<String, Type>) -> MethodInfo {
+        // 方法自身的类型参数会遮蔽同名的类类型参数
+        let mut bindings = bindings.clone();
+        for type_param in &self.type_params {
+            bindings.remove(&type_param.name);
+        }
+        let mut method = self.clone();
+        for param in &mut method.params {
+            param.param_type = param.param_type.substitute(&bindings);
+        }
+        method.return_type = method.return_type.substitute(&bindings);
+        method
+    }
+
+    /// 对方法签名中的所有类型应用变换
+    pub fn map_types(&mut self, f: &mut impl FnMut(&Type) -> Type) {
+        for param in &mut self.params {
+            param.param_type = f(&param.param_type);
+        }
+        self.return_type = f(&self.return_type);
     }
 }
 
@@ -321,6 +726,12 @@ impl fmt::Display for Type {
             Type::Void => write!(f, "void"),
             Type::Int32 => write!(f, "int"),
             Type::Int64 => write!(f, "long"),
+            Type::Int8 => write!(f, "byte"),
+            Type::UInt8 => write!(f, "ubyte"),
+            Type::Int16 => write!(f, "short"),
+            Type::UInt16 => write!(f, "ushort"),
+            Type::UInt32 => write!(f, "uint"),
+            Type::UInt64 => write!(f, "ulong"),
             Type::Float32 => write!(f, "float"),
             Type::Float64 => write!(f, "double"),
             Type::Bool => write!(f, "bool"),
@@ -358,14 +769,167 @@ impl fmt::Display for Type {
             // FFI 指针和结构体
             Type::Pointer(inner) => write!(f, "{}*", inner),
             Type::Struct(name) => write!(f, "struct {}", name),
+            Type::TypeParam(name) => write!(f, "{}", name),
+            Type::Enum(name) => write!(f, "{}", name),
+            Type::Generic(name, args) => {
+                write!(f, "{}<", name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ">")
+            }
+            Type::Nullable(inner) => write!(f, "{}?", inner),
         }
     }
 }
 
+/// 泛型方法调用的实例化信息（由语义分析记录，供单态化使用）
+#[derive(Debug, Clone)]
+pub struct GenericMethodCall {
+    pub class_name: String,
+    pub method_name: String,
+    pub type_args: Vec<Type>,
+}
+
+/// Lambda 捕获的外层变量
+#[derive(Debug, Clone)]
+pub struct CaptureInfo {
+    pub name: String,
+    pub var_type: Type,
+    /// 按引用捕获（非 final 局部变量，与外层共享同一存储）；否则按值复制
+    pub by_ref: bool,
+}
+
+/// Lambda 的签名与捕获列表（由语义分析记录，供代码生成构造闭包）
+#[derive(Debug, Clone)]
+pub struct LambdaInfo {
+    pub signature: FunctionType,
+    pub captures: Vec<CaptureInfo>,
+}
+
+/// 重载运算符对应的方法调用（由语义分析记录，代码生成降级为普通方法调用）
+#[derive(Debug, Clone)]
+pub struct OperatorCall {
+    /// 语义分析按操作数类型选定的方法（plus、equals、compareTo、get 等）
+    pub method: MethodInfo,
+    /// 整个运算表达式的类型
+    pub result_type: Type,
+}
+
+/// 属性读写对应的访问方法调用（由语义分析记录，代码生成降级为普通方法调用；自动访问器不记录，直接读写字段）
+#[derive(Debug, Clone)]
+pub struct PropertyCall {
+    /// getter（读取）或 setter（赋值）
+    pub method: MethodInfo,
+    pub property_type: Type,
+}
+
+/// 涉及 byte、short、uint、ulong 等类型的二元运算（由语义分析记录，代码生成据此选择扩展方式和无符号指令）
+#[derive(Debug, Clone)]
+pub struct IntegerOperation {
+    pub left: Type,
+    pub right: Type,
+    /// 两个操作数提升后的运算类型；字符串拼接时为 string
+    pub operand_type: Type,
+}
+
+/// 安全调用 `obj?.member`（由语义分析记录，代码生成据此绑定对象并确定结果类型）
+#[derive(Debug, Clone)]
+pub struct SafeCallInfo {
+    /// 对象的（非空）类型
+    pub receiver: Type,
+    /// 成员访问或方法调用的结果类型
+    pub result: Type,
+}
+
+/// 增强 for 循环的形式
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForEachKind {
+    /// 按下标遍历数组
+    Array,
+    /// `range(a, b[, step])`：不分配内存的计数循环
+    Range,
+    /// 先调用 `iterator()`，再在返回的迭代器上循环 `hasNext()`/`next()`
+    Iterable,
+    /// 对象本身带 `hasNext()`/`next()`
+    Iterator,
+}
+
+/// 增强 for 循环（由语义分析记录，代码生成据此展开循环）
+#[derive(Debug, Clone)]
+pub struct ForEachInfo {
+    pub kind: ForEachKind,
+    /// 隐藏变量 `iter` 的类型：数组、迭代器，range 时为计数类型
+    pub source: Type,
+    /// 循环变量的类型（var/auto 时为元素类型）
+    pub variable: Type,
+}
+
 #[derive(Debug, Clone)]
 pub struct TypeRegistry {
     pub classes: HashMap<String, ClassInfo>,
     pub interfaces: HashMap<String, InterfaceInfo>,
+    pub enums: HashMap<String, EnumInfo>,
+    pub structs: HashMap<String, StructInfo>,
+    /// 泛型类模板的原始签名（类型参数未替换，用于实例化）
+    pub generic_templates: HashMap<String, ClassInfo>,
+    /// 泛型接口模板的原始签名
+    pub generic_interface_templates: HashMap<String, InterfaceInfo>,
+    /// 泛型实例: 单态化名称 -> (泛型名, 类型实参)
+    pub generic_instances: HashMap<String, (String, Vec<Type>)>,
+    /// 泛型方法调用: 调用位置 -> 实例化信息
+    pub generic_method_calls: HashMap<crate::error::SourceLocation, GenericMethodCall>,
+    /// 泛型枚举模板（内置 Result<T, E> 和 Option<T>）
+    pub generic_enum_templates: HashMap<String, EnumInfo>,
+    /// 省略枚举名的内置变体构造（Ok(x)、None 等）: 表达式位置 -> 具体枚举名
+    pub variant_constructions: HashMap<crate::error::SourceLocation, String>,
+    /// `?` 运算符: 表达式位置 -> 操作数的具体枚举名
+    pub try_operators: HashMap<crate::error::SourceLocation, String>,
+    /// 枚举上的 switch: 语句位置 -> 具体枚举名
+    pub enum_switches: HashMap<crate::error::SourceLocation, String>,
+    /// Lambda 表达式: 表达式位置 -> 签名与捕获列表
+    pub lambdas: HashMap<crate::error::SourceLocation, LambdaInfo>,
+    /// 被 Lambda 按引用捕获、需要分配在堆上的局部变量: (声明位置, 变量名)
+    pub boxed_locals: HashSet<(crate::error::SourceLocation, String)>,
+    /// 引用 static final 常量的 case 标签: 标签位置 -> 常量值
+    pub case_constants: HashMap<crate::error::SourceLocation, crate::ast::LiteralValue>,
+    /// 初始值可在编译期求值的静态字段: Class.field -> 折叠后的值（作为全局变量的初始值）
+    pub static_constants: HashMap<String, crate::ast::LiteralValue>,
+    /// 长度为常量表达式的静态数组字段: 数组创建位置 -> 第一维长度
+    pub array_sizes: HashMap<crate::error::SourceLocation, i64>,
+    /// switch 表达式: 表达式位置 -> 结果类型
+    pub switch_types: HashMap<crate::error::SourceLocation, Type>,
+    /// 重载运算符: 运算符位置（下标赋值为赋值位置） -> 对应的方法调用
+    pub operator_calls: HashMap<crate::error::SourceLocation, OperatorCall>,
+    /// 属性读取: 成员访问（或类体中属性名）位置 -> getter 调用
+    pub property_reads: HashMap<crate::error::SourceLocation, PropertyCall>,
+    /// 属性赋值: 赋值位置 -> setter 调用（赋值位置与目标的起始位置相同，因此与读取分开记录）
+    pub property_writes: HashMap<crate::error::SourceLocation, PropertyCall>,
+    /// byte、short、uint、ulong 等整数参与的二元运算: 运算符位置 -> 操作数类型
+    pub integer_operations: HashMap<crate::error::SourceLocation, IntegerOperation>,
+    /// 安全调用 `?.`: 运算符位置 -> 对象与结果类型
+    pub safe_calls: HashMap<crate::error::SourceLocation, SafeCallInfo>,
+    /// elvis 运算符 `?:`: 运算符位置 -> 结果类型
+    pub elvis_types: HashMap<crate::error::SourceLocation, Type>,
+    /// 增强 for 循环: 语句位置 -> 循环形式与类型
+    pub for_each_loops: HashMap<crate::error::SourceLocation, ForEachInfo>,
+    /// 按类型模式匹配的 switch: switch 位置 -> 被匹配值的类或接口名
+    pub type_switches: HashMap<crate::error::SourceLocation, String>,
+    /// 类从直接实现的接口继承、未自行覆盖的默认方法: 类名 -> [(接口名, 方法名)]
+    ///
+    /// 代码生成以这些类为 `this` 重新生成接口中的方法体。
+    pub inherited_defaults: HashMap<String, Vec<(String, String)>>,
+    /// 包中声明的类型（类型名即限定名 a.b.Class）-> 所属包；默认包中的类型不在表中
+    pub type_packages: HashMap<String, String>,
+    /// 未标记 public、只在所属包内可见的类型
+    pub package_private_types: HashSet<String>,
+    /// 嵌套类、内部类和匿名类 -> 直接外围类
+    pub enclosing_classes: HashMap<String, String>,
+    /// 声明为 private 的嵌套类，只在其最外层类中可见
+    pub private_nested_classes: HashSet<String>,
 }
 
 impl TypeRegistry {
@@ -373,6 +937,35 @@ impl TypeRegistry {
         let mut registry = Self {
             classes: HashMap::new(),
             interfaces: HashMap::new(),
+            enums: HashMap::new(),
+            structs: HashMap::new(),
+            generic_templates: HashMap::new(),
+            generic_interface_templates: HashMap::new(),
+            generic_instances: HashMap::new(),
+            generic_method_calls: HashMap::new(),
+            generic_enum_templates: HashMap::new(),
+            variant_constructions: HashMap::new(),
+            try_operators: HashMap::new(),
+            enum_switches: HashMap::new(),
+            lambdas: HashMap::new(),
+            boxed_locals: HashSet::new(),
+            case_constants: HashMap::new(),
+            static_constants: HashMap::new(),
+            array_sizes: HashMap::new(),
+            switch_types: HashMap::new(),
+            operator_calls: HashMap::new(),
+            property_reads: HashMap::new(),
+            property_writes: HashMap::new(),
+            integer_operations: HashMap::new(),
+            safe_calls: HashMap::new(),
+            elvis_types: HashMap::new(),
+            for_each_loops: HashMap::new(),
+            type_switches: HashMap::new(),
+            inherited_defaults: HashMap::new(),
+            type_packages: HashMap::new(),
+            package_private_types: HashSet::new(),
+            enclosing_classes: HashMap::new(),
+            private_nested_classes: HashSet::new(),
         };
 
         // 注册内置类 String（用于支持 String.valueOf() 等静态方法调用）
@@ -381,9 +974,64 @@ impl TypeRegistry {
         // 注册内置类 Integer（用于支持 Integer.parseInt() 等静态方法调用）
         registry.register_builtin_integer_class();
 
+        // 注册内置泛型枚举 Result<T, E> 和 Option<T>
+        registry.register_builtin_enum_templates();
+
         registry
     }
 
+    /// 注册内置泛型枚举模板
+    ///
+    /// `Result<T, E> { Ok(T value), Err(E error) }` 和 `Option<T> { Some(T value), None }`。
+    /// 错误变体固定放在标签 1，使 `?` 运算符可以只比较标签而不关心具体实例。
+    fn register_builtin_enum_templates(&mut self) {
+        let type_param = |name: &str| TypeParamInfo { name: name.to_string(), bound: None };
+        let variant = |name: &str, fields: Vec<ParameterInfo>| EnumVariantInfo { name: name.to_string(), fields };
+
+        self.generic_enum_templates.insert("Result".to_string(), EnumInfo {
+            name: "Result".to_string(),
+            variants: vec![
+                variant("Ok", vec![ParameterInfo::new("value".to_string(), Type::TypeParam("T".to_string()))]),
+                variant("Err", vec![ParameterInfo::new("error".to_string(), Type::TypeParam("E".to_string()))]),
+            ],
+            type_params: vec![type_param("T"), type_param("E")],
+        });
+        self.generic_enum_templates.insert("Option".to_string(), EnumInfo {
+            name: "Option".to_string(),
+            variants: vec![
+                variant("Some", vec![ParameterInfo::new("value".to_string(), Type::TypeParam("T".to_string()))]),
+                variant("None", Vec::new()),
+            ],
+            type_params: vec![type_param("T")],
+        });
+    }
+
+    /// 泛型枚举实例对应的模板名（Result$i32$String -> Result）
+    pub fn enum_origin(&self, name: &str) -> Option<&str> {
+        if !self.enums.contains_key(name) {
+            return None;
+        }
+        self.generic_instances.get(name)
+            .map(|(origin, _)| origin.as_str())
+            .filter(|origin| self.generic_enum_templates.contains_key(*origin))
+    }
+
+    /// 诊断信息中的类型写法：泛型实例还原为模板名加类型实参（Result$i32$String -> Result<int, String>）
+    pub fn display_type(&self, ty: &Type) -> String {
+        match ty {
+            Type::Object(name) | Type::Enum(name) => match self.generic_instances.get(name) {
+                Some((origin, args)) => {
+                    let args: Vec<String> = args.iter().map(|arg| self.display_type(arg)).collect();
+                    format!("{}<{}>", origin, args.join(", "))
+                }
+                None => ty.to_string(),
+            },
+            Type::Array(inner) => format!("{}[]", self.display_type(inner)),
+            Type::String => "String".to_string(),
+            _ => ty.to_string(),
+        }
+    }
+
     /// 注册内置 String 类
     fn register_builtin_string_class(&mut self) {
         // 创建 String 类信息
@@ -397,6 +1045,12 @@ impl TypeRegistry {
             interfaces: Vec::new(),
             is_abstract: false,
             is_final: true,  // String 是 final 类，不能被继承
+            is_sealed: false,
+            permits: Vec::new(),
+            type_params: Vec::new(),
+            generic_supertypes: Vec::new(),
+            annotations: Vec::new(),
+            properties: HashMap::new(),
         };
 
         // 添加 String.valueOf() 方法（各种重载版本）
@@ -408,6 +1062,7 @@ impl TypeRegistry {
                 name: "value".to_string(),
                 param_type: Type::Int32,
                 is_varargs: false,
+                annotations: Vec::new(),
             }],
             return_type: Type::String,
             is_static: true,
@@ -417,6 +1072,8 @@ impl TypeRegistry {
             is_native: false,
             is_final: true,
             is_override: false,
+            annotations: Vec::new(),
+            type_params: Vec::new(),
         });
 
         // valueOf(long)
@@ -427,6 +1084,7 @@ impl TypeRegistry {
                 name: "value".to_string(),
                 param_type: Type::Int64,
                 is_varargs: false,
+                annotations: Vec::new(),
             }],
             return_type: Type::String,
             is_static: true,
@@ -436,6 +1094,8 @@ impl TypeRegistry {
             is_native: false,
             is_final: true,
             is_override: false,
+            annotations: Vec::new(),
+            type_params: Vec::new(),
         });
 
         // valueOf(float)
@@ -446,6 +1106,7 @@ impl TypeRegistry {
                 name: "value".to_string(),
                 param_type: Type::Float32,
                 is_varargs: false,
+                annotations: Vec::new(),
             }],
             return_type: Type::String,
             is_static: true,
@@ -455,6 +1116,8 @@ impl TypeRegistry {
             is_native: false,
             is_final: true,
             is_override: false,
+            annotations: Vec::new(),
+            type_params: Vec::new(),
         });
 
         // valueOf(double)
@@ -465,6 +1128,7 @@ impl TypeRegistry {
                 name: "value".to_string(),
                 param_type: Type::Float64,
                 is_varargs: false,
+                annotations: Vec::new(),
             }],
             return_type: Type::String,
             is_static: true,
@@ -474,6 +1138,8 @@ impl TypeRegistry {
             is_native: false,
             is_final: true,
             is_override: false,
+            annotations: Vec::new(),
+            type_params: Vec::new(),
         });
 
         // valueOf(boolean)
@@ -484,6 +1150,7 @@ impl TypeRegistry {
                 name: "value".to_string(),
                 param_type: Type::Bool,
                 is_varargs: false,
+                annotations: Vec::new(),
             }],
             return_type: Type::String,
             is_static: true,
@@ -493,6 +1160,8 @@ impl TypeRegistry {
             is_native: false,
             is_final: true,
             is_override: false,
+            annotations: Vec::new(),
+            type_params: Vec::new(),
         });
 
         // valueOf(char)
@@ -503,6 +1172,7 @@ impl TypeRegistry {
                 name: "value".to_string(),
                 param_type: Type::Char,
                 is_varargs: false,
+                annotations: Vec::new(),
             }],
             return_type: Type::String,
             is_static: true,
@@ -512,6 +1182,8 @@ impl TypeRegistry {
             is_native: false,
             is_final: true,
             is_override: false,
+            annotations: Vec::new(),
+            type_params: Vec::new(),
         });
 
         // valueOf(String) - 返回自身
@@ -522,6 +1194,7 @@ impl TypeRegistry {
                 name: "value".to_string(),
                 param_type: Type::String,
                 is_varargs: false,
+                annotations: Vec::new(),
             }],
             return_type: Type::String,
             is_static: true,
@@ -531,8 +1204,34 @@ impl TypeRegistry {
             is_native: false,
             is_final: true,
             is_override: false,
+            annotations: Vec::new(),
+            type_params: Vec::new(),
         });
 
+        // valueOf(ubyte/ushort/uint/ulong) - 按无符号格式输出
+        for unsigned in [Type::UInt8, Type::UInt16, Type::UInt32, Type::UInt64] {
+            string_class.add_method(MethodInfo {
+                name: "valueOf".to_string(),
+                class_name: "String".to_string(),
+                params: vec![ParameterInfo {
+                    name: "value".to_string(),
+                    param_type: unsigned,
+                    is_varargs: false,
+                    annotations: Vec::new(),
+                }],
+                return_type: Type::String,
+                is_static: true,
+                is_public: true,
+                is_private: false,
+                is_protected: false,
+                is_native: false,
+                is_final: true,
+                is_override: false,
+                annotations: Vec::new(),
+                type_params: Vec::new(),
+            });
+        }
+
         // 注册 String 类
         self.classes.insert("String".to_string(), string_class);
     }
@@ -550,6 +1249,12 @@ impl TypeRegistry {
             interfaces: Vec::new(),
             is_abstract: false,
             is_final: true,  // Integer 是 final 类，不能被继承
+            is_sealed: false,
+            permits: Vec::new(),
+            type_params: Vec::new(),
+            generic_supertypes: Vec::new(),
+            annotations: Vec::new(),
+            properties: HashMap::new(),
         };
 
         // 添加 Integer.parseInt(String) 方法
@@ -560,6 +1265,7 @@ impl TypeRegistry {
                 name: "s".to_string(),
                 param_type: Type::String,
                 is_varargs: false,
+                annotations: Vec::new(),
             }],
             return_type: Type::Int32,
             is_static: true,
@@ -569,16 +1275,25 @@ impl TypeRegistry {
             is_native: false,
             is_final: true,
             is_override: false,
+            annotations: Vec::new(),
+            type_params: Vec::new(),
         });
 
         // 注册 Integer 类
         self.classes.insert("Integer".to_string(), integer_class);
     }
 
+    /// 类型所属的包（泛型实例按其模板计算），默认包为空字符串
+    pub fn package_of(&self, name: &str) -> &str {
+        let origin = self.generic_instances.get(name).map(|(origin, _)| origin.as_str()).unwrap_or(name);
+        self.type_packages.get(origin).map(String::as_str).unwrap_or("")
+    }
+
     pub fn register_class(&mut self, class_info: ClassInfo) -> crate::error::cayResult<()> {
         let name = class_info.name.clone();
         if self.classes.contains_key(&name) {
             return Err(crate::error::semantic_error(
+                ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
                 0, 0,
                 format!("Class '{}' already defined", name)
             ));
@@ -591,6 +1306,7 @@ impl TypeRegistry {
         let name = interface_info.name.clone();
         if self.interfaces.contains_key(&name) {
             return Err(crate::error::semantic_error(
+                ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
                 0, 0,
                 format!("Interface '{}' already defined", name)
             ));
@@ -607,6 +1323,58 @@ impl TypeRegistry {
         self.interfaces.contains_key(name)
     }
 
+    /// sealed 类或接口允许的直接子类，不是 sealed 类型时返回 None
+    pub fn sealed_permits(&self, name: &str) -> Option<&[String]> {
+        match self.classes.get(name) {
+            Some(class) if class.is_sealed => Some(class.permits.as_slice()),
+            Some(_) => None,
+            None => self.interfaces.get(name).filter(|i| i.is_sealed).map(|i| i.permits.as_slice()),
+        }
+    }
+
+    /// 查找接口中声明的方法（接口方法不支持重载）
+    pub fn get_interface_method(&self, interface_name: &str, method_name: &str) -> Option<&MethodInfo> {
+        self.interfaces.get(interface_name)?.methods.get(method_name)
+    }
+
+    pub fn register_enum(&mut self, enum_info: EnumInfo) -> crate::error::cayResult<()> {
+        let name = enum_info.name.clone();
+        if self.enums.contains_key(&name) || self.classes.contains_key(&name) {
+            return Err(crate::error::semantic_error(
+                ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
+                0, 0,
+                format!("Enum '{}' already defined", name)
+            ));
+        }
+        self.enums.insert(name, enum_info);
+        Ok(())
+    }
+
+    pub fn get_enum(&self, name: &str) -> Option<&EnumInfo> {
+        self.enums.get(name)
+    }
+
+    pub fn enum_exists(&self, name: &str) -> bool {
+        self.enums.contains_key(name)
+    }
+
+    pub fn register_struct(&mut self, struct_info: StructInfo) -> crate::error::cayResult<()> {
+        let name = struct_info.name.clone();
+        if self.structs.contains_key(&name) || self.enums.contains_key(&name) || self.classes.contains_key(&name) {
+            return Err(crate::error::semantic_error(
+                ErrorCodes::SEMANTIC_DUPLICATE_DEFINITION,
+                0, 0,
+                format!("Struct '{}' already defined", name)
+            ));
+        }
+        self.structs.insert(name, struct_info);
+        Ok(())
+    }
+
+    pub fn get_struct(&self, name: &str) -> Option<&StructInfo> {
+        self.structs.get(name)
+    }
+
     pub fn get_class(&self, name: &str) -> Option<&ClassInfo> {
         self.classes.get(name)
     }
@@ -667,10 +1435,32 @@ impl InterfaceInfo {
         Self {
             name,
             methods: HashMap::new(),
+            type_params: Vec::new(),
+            default_methods: HashSet::new(),
+            is_sealed: false,
+            permits: Vec::new(),
         }
     }
 
     pub fn add_method(&mut self, method: MethodInfo) {
         self.methods.insert(method.name.clone(), method);
     }
+
+    /// 类必须自己实现的方法：既不是 default 也不是 static
+    pub fn is_abstract_method(&self, method: &MethodInfo) -> bool {
+        !method.is_static && !self.default_methods.contains(&method.name)
+    }
+
+    /// 以给定类型实参实例化泛型接口
+    pub fn instantiate(&self, name: &str, args: &[Type]) -> InterfaceInfo {
+        let bindings = generic_bindings(&self.type_params, args);
+        let mut info = self.clone();
+        info.name = name.to_string();
+        info.type_params = Vec::new();
+        for method in info.methods.values_mut() {
+            *method = method.substitute(&bindings);
+            method.class_name = name.to_string();
+        }
+        info
+    }
 }
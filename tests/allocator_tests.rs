@@ -5,6 +5,7 @@
 //! - Arena 线性分配器
 //! - scope 栈作用域语句
 //! - 分配器接口多态
+//! - __cay_alloc / __cay_free 内置函数
 
 mod common;
 use common::compile_and_run_eol;
@@ -25,6 +26,18 @@ fn test_scope_statement() {
             "Test should complete, got: {}", output);
 }
 
+/// 测试 __cay_alloc / __cay_free 内置函数
+#[test]
+fn test_cay_alloc_builtins() {
+    let output = compile_and_run_eol("examples/test_cay_alloc.cay")
+        .expect("__cay_alloc test should compile and run");
+
+    assert!(output.contains("true"),
+            "Allocations should return non-null pointers, got: {}", output);
+    assert!(output.contains("cay_alloc done"),
+            "Test should complete, got: {}", output);
+}
+
 /// 测试 GlobalAlloc 全局分配器
 #[test]
 fn test_global_alloc() {
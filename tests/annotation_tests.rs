@@ -0,0 +1,124 @@
+//! Cavvy 语言注解集成测试
+//!
+//! 测试用户定义的注解及其查询接口、@Deprecated 警告、@Inline/@NoInline 函数属性、
+//! @Export 导出别名和 @Test 测试方法，以及内置注解的用法检查
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+use cavvy::ast::{Annotated, AnnotationValue, ClassMember, Program};
+use cavvy::codegen::IRGenerator;
+use cavvy::semantic::SemanticAnalyzer;
+
+const EXAMPLE: &str = "examples/test_annotations.cay";
+
+/// 解析并分析示例程序
+fn analyze_example() -> (Program, SemanticAnalyzer) {
+    let source = std::fs::read_to_string(EXAMPLE).expect("annotation example should exist");
+    let tokens = cavvy::lexer::lex(&source).expect("annotation example should lex");
+    let program = cavvy::parser::parse_with_source(tokens, source).expect("annotation example should parse");
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&program).expect("annotation example should pass semantic analysis");
+    (program, analyzer)
+}
+
+// ==================== 0.5.2.0 注解测试 ====================
+
+#[test]
+fn test_annotations_run() {
+    let output = compile_and_run_eol(EXAMPLE).expect("annotation example should compile and run");
+    assert!(output.contains("square(7) = 49"), "@Inline methods should still be callable, got: {}", output);
+    assert!(output.contains("cube(3) = 27"), "@NoInline methods should still be callable, got: {}", output);
+    assert!(output.contains("add(2, 3) = 5"), "@Export methods should keep their Cavvy name, got: {}", output);
+    assert!(output.contains("plus(4, 5) = 9"), "@Deprecated methods should only produce warnings, got: {}", output);
+}
+
+#[test]
+fn test_annotation_query_api() {
+    let (program, _) = analyze_example();
+    let math = program.classes.iter().find(|c| c.name == "MathUtils").expect("MathUtils should be parsed");
+    let component = math.annotation("Component").expect("user-defined class annotations should be kept");
+    assert_eq!(component.arg("name"), Some(&AnnotationValue::String("math".to_string())));
+    assert_eq!(component.arg("version"), Some(&AnnotationValue::Int(2)));
+
+    let add = math.members.iter().find_map(|m| match m {
+        ClassMember::Method(method) if method.name == "add" => Some(method),
+        _ => None,
+    }).expect("add should be parsed");
+    assert_eq!(add.annotation("Export").and_then(|a| a.string_value()), Some("cavvy_add"));
+    assert!(add.params.iter().all(|p| p.has_annotation("Positive")), "parameter annotations should be kept");
+
+    let tests: Vec<String> = program.annotated_methods("Test").iter()
+        .map(|(class, method)| format!("{}.{}", class.name, method.name))
+        .collect();
+    assert_eq!(tests, vec!["AnnotationTests.testSquare", "AnnotationTests.testCube"]);
+}
+
+#[test]
+fn test_deprecated_warnings() {
+    let (_, analyzer) = analyze_example();
+    let warnings: Vec<&str> = analyzer.warnings().iter().map(|w| w.message.as_str()).collect();
+    assert!(warnings.contains(&"'MathUtils.plus' is deprecated: use add instead"), "Calls to deprecated methods should warn, got: {:?}", warnings);
+    assert!(warnings.contains(&"'MathUtils.MAX' is deprecated: use LIMIT instead"), "Deprecated fields should warn, got: {:?}", warnings);
+    assert_eq!(warnings.len(), 2, "Each use should warn once, got: {:?}", warnings);
+}
+
+#[test]
+fn test_inline_and_export_codegen() {
+    let (program, analyzer) = analyze_example();
+    let mut generator = IRGenerator::new();
+    generator.set_type_registry(analyzer.get_type_registry().clone());
+    let ir = generator.generate(&program, "").expect("annotation example should generate IR");
+    assert!(ir.lines().any(|l| l.contains("square") && l.starts_with("define") && l.ends_with("alwaysinline {")),
+            "@Inline should add alwaysinline");
+    assert!(ir.lines().any(|l| l.contains("cube") && l.starts_with("define") && l.ends_with("noinline {")),
+            "@NoInline should add noinline");
+    assert!(ir.contains("@cavvy_add = alias i32 (i32, i32), i32 (i32, i32)*"),
+            "@Export should emit an alias with the C name");
+}
+
+#[test]
+fn test_error_annotation_inline_conflict() {
+    let error = compile_eol_expect_error("examples/errors/error_annotation_inline_conflict.cay")
+        .expect("@Inline together with @NoInline should fail to compile");
+    assert!(error.contains("Method 'MathUtils.square' cannot be both @Inline and @NoInline"),
+            "Should report the conflicting annotations, got: {}", error);
+}
+
+#[test]
+fn test_error_annotation_export_instance() {
+    let error = compile_eol_expect_error("examples/errors/error_annotation_export_instance.cay")
+        .expect("@Export on an instance method should fail to compile");
+    assert!(error.contains("@Export can only be applied to static methods with a body, but 'Counter.next' is not"),
+            "Should report the non-static export, got: {}", error);
+}
+
+#[test]
+fn test_error_annotation_export_duplicate() {
+    let error = compile_eol_expect_error("examples/errors/error_annotation_export_duplicate.cay")
+        .expect("duplicate export names should fail to compile");
+    assert!(error.contains("Exported name 'add' is already used by 'IntMath.add'"),
+            "Should report the duplicate export, got: {}", error);
+}
+
+#[test]
+fn test_error_annotation_test_signature() {
+    let error = compile_eol_expect_error("examples/errors/error_annotation_test_signature.cay")
+        .expect("a @Test method with parameters should fail to compile");
+    assert!(error.contains("Test method 'Tests.checkSum' must be static, return void and take no parameters"),
+            "Should report the invalid test method, got: {}", error);
+}
+
+#[test]
+fn test_error_annotation_misplaced() {
+    let error = compile_eol_expect_error("examples/errors/error_annotation_misplaced.cay")
+        .expect("an annotation on an enum should fail to compile");
+    assert!(error.contains("注解 '@Flags' 不能用于此处"), "Should report the misplaced annotation, got: {}", error);
+}
+
+#[test]
+fn test_error_annotation_non_literal_arg() {
+    let error = compile_eol_expect_error("examples/errors/error_annotation_non_literal_arg.cay")
+        .expect("a non-literal annotation argument should fail to compile");
+    assert!(error.contains("注解 @Deprecated 的参数必须是字面量"), "Should report the non-literal argument, got: {}", error);
+}
@@ -0,0 +1,57 @@
+//! Cavvy 语言 Lambda 闭包集成测试
+//!
+//! 测试 Lambda 按值/按引用捕获外层变量，以及通过函数类型调用闭包
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 闭包测试 ====================
+
+#[test]
+fn test_closure_captures_by_value() {
+    let output = compile_and_run_eol("examples/test_closures.cay").expect("closures example should compile and run");
+    assert!(output.contains("add5(1) = 6") && output.contains("add10(1) = 11"),
+            "Each closure should keep its own captured parameter, got: {}", output);
+    assert!(output.contains("hi bob") && output.contains("chosen = 10"),
+            "final variables should be copied into the closure, got: {}", output);
+}
+
+#[test]
+fn test_closure_captures_by_reference() {
+    let output = compile_and_run_eol("examples/test_closures.cay").expect("closures example should compile and run");
+    assert!(output.contains("first = 3") && output.contains("second = 1"),
+            "Counters should survive the method that created them, got: {}", output);
+    assert!(output.contains("total = 14"),
+            "Writes inside the closure should be visible outside, got: {}", output);
+    assert!(output.contains("scale(3.0) = 1.500000") && output.contains("scale(3.0) = 6.000000"),
+            "Writes outside should be visible inside the closure, got: {}", output);
+}
+
+#[test]
+fn test_closure_nested_and_this() {
+    let output = compile_and_run_eol("examples/test_closures.cay").expect("closures example should compile and run");
+    assert!(output.contains("outer(20) = 121"),
+            "Nested lambdas should capture through the enclosing lambda, got: {}", output);
+    assert!(output.contains("balance = 42"),
+            "Lambdas should capture this, got: {}", output);
+}
+
+#[test]
+fn test_closure_and_plain_lambda_calls() {
+    let output = compile_and_run_eol("examples/test_closures.cay").expect("closures example should compile and run");
+    assert!(output.contains("applyTwice(add5, 0) = 10"),
+            "Closures should be callable through a function type parameter, got: {}", output);
+    assert!(output.contains("applyTwice(triple, 2) = 18") && output.contains("applyTwice(+step, 2) = 10"),
+            "Lambdas with and without captures should share the calling convention, got: {}", output);
+}
+
+#[test]
+fn test_error_lambda_assign_captured_param() {
+    let error = compile_eol_expect_error("examples/errors/error_lambda_assign_captured_param.cay")
+        .expect("assigning a captured parameter should fail to compile");
+    assert!(
+        error.contains("parameters are captured by value"),
+        "Should report the by-value capture, got: {}",
+        error
+    );
+}
@@ -140,6 +140,43 @@ pub fn compile_eol_expect_error(source_path: &str) -> Result<String, String> {
     Ok(stderr)
 }
 
+/// 带特性标志编译 EOL 文件，期望编译失败，返回错误信息
+///
+/// # Arguments
+/// * `source_path` - EOL 源代码文件路径（相对于项目根目录）
+/// * `features` - 特性标志列表，如 &["-F=null_safety"]
+///
+/// # Returns
+/// * `Ok(String)` - 编译失败时返回 stderr 字符串
+/// * `Err(String)` - 编译成功时返回错误
+pub fn compile_eol_expect_error_with_features(source_path: &str, features: &[&str]) -> Result<String, String> {
+    // 使用唯一ID生成输出文件名，避免测试冲突
+    let unique_id = format!("{}_{:?}", std::process::id(), std::thread::current().id());
+    let exe_path = source_path.replace(".cay", &format!("_{}.exe", unique_id));
+    let ir_path = source_path.replace(".cay", &format!("_{}.ll", unique_id));
+
+    let mut args = vec![source_path, &exe_path];
+    for feature in features {
+        args.push(feature);
+    }
+
+    let output = Command::new("./target/release/cayc.exe")
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to execute cayc: {}", e))?;
+
+    // 清理可能生成的文件
+    let _ = fs::remove_file(&exe_path);
+    let _ = fs::remove_file(&ir_path);
+
+    if output.status.success() {
+        return Err("Expected compilation to fail, but it succeeded".to_string());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    Ok(stderr)
+}
+
 /// 编译并运行 EOL 文件，期望执行失败（用于运行时错误测试），返回错误信息
 ///
 /// 用于测试应该产生运行时错误的代码。
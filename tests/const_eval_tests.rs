@@ -0,0 +1,135 @@
+//! Cavvy 语言编译期常量求值集成测试
+//!
+//! 测试 static final 常量折叠、const 函数、常量数组大小、常量表达式 case 标签，
+//! 以及无法在编译期求值时的诊断信息
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+use cavvy::ast::{LiteralValue, Program};
+use cavvy::codegen::IRGenerator;
+use cavvy::semantic::SemanticAnalyzer;
+
+const EXAMPLE: &str = "examples/test_const_eval.cay";
+
+/// 解析并分析示例程序
+fn analyze_example() -> (Program, SemanticAnalyzer) {
+    let source = std::fs::read_to_string(EXAMPLE).expect("const eval example should exist");
+    let tokens = cavvy::lexer::lex(&source).expect("const eval example should lex");
+    let program = cavvy::parser::parse_with_source(tokens, source).expect("const eval example should parse");
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&program).expect("const eval example should pass semantic analysis");
+    (program, analyzer)
+}
+
+// ==================== 0.5.2.0 编译期常量求值测试 ====================
+
+#[test]
+fn test_const_eval_run() {
+    let output = compile_and_run_eol(EXAMPLE).expect("const eval example should compile and run");
+    assert!(output.contains("BUFFER = 4112"), "Constants should fold across classes, got: {}", output);
+    assert!(output.contains("FACT5 = 120") && output.contains("FIB10 = 55"),
+            "Const functions should be evaluated at compile time, got: {}", output);
+    assert!(output.contains("NAME = buf4112_xtrue"), "String constants should fold, got: {}", output);
+    assert!(output.contains("table.length = 32"), "Array sizes should accept const function calls, got: {}", output);
+    assert!(output.contains("2-3 KB") && output.contains("4!") && output.contains("twice buffer") && output.contains("base+1"),
+            "Case labels should accept constant expressions, got: {}", output);
+    assert!(output.contains("factorial(6) = 720"), "Const functions should still be callable at runtime, got: {}", output);
+}
+
+#[test]
+fn test_const_eval_static_constants() {
+    let (_, analyzer) = analyze_example();
+    let constants = &analyzer.get_type_registry().static_constants;
+    assert!(matches!(constants.get("ConstEval.MASK"), Some(LiteralValue::Int32(64512))), "~ and & should fold");
+    assert!(matches!(constants.get("ConstEval.FACT5"), Some(LiteralValue::Int32(120))), "Loops in const functions should run");
+    assert!(matches!(constants.get("ConstEval.LARGE"), Some(LiteralValue::Bool(true))), "Boolean constants should fold");
+    assert!(matches!(constants.get("ConstEval.NAME"), Some(LiteralValue::String(s)) if s == "buf4112_xtrue"),
+            "String concatenation should fold");
+    assert!(matches!(constants.get("Limits.BIG"), Some(LiteralValue::Int64(68719476736))), "Long shifts should fold");
+}
+
+#[test]
+fn test_const_eval_case_constants() {
+    let (_, analyzer) = analyze_example();
+    let values: Vec<&LiteralValue> = analyzer.get_type_registry().case_constants.values().collect();
+    for expected in [2048, 3072, 24, 8224, 17] {
+        assert!(values.iter().any(|v| matches!(v, LiteralValue::Int32(n) if *n == expected)),
+                "case label {} should be folded, got: {:?}", expected, values);
+    }
+    assert!(values.iter().any(|v| matches!(v, LiteralValue::String(s) if s == "low")),
+            "string case labels should be folded, got: {:?}", values);
+}
+
+#[test]
+fn test_const_eval_codegen() {
+    let (program, analyzer) = analyze_example();
+    let mut generator = IRGenerator::new();
+    generator.set_type_registry(analyzer.get_type_registry().clone());
+    let ir = generator.generate(&program, "").expect("const eval example should generate IR");
+    assert!(ir.contains("@ConstEval.FIB10_s = private global i32 55"), "Const function results should become global initializers");
+    assert!(ir.contains("@ConstEval.RATIO_s = private global double 0x4080100000000000"), "Double constants should be folded");
+    assert!(ir.contains("@Limits.BIG_s = private global i64 68719476736"), "Long constants should be folded");
+}
+
+#[test]
+fn test_error_const_case_label_local() {
+    let error = compile_eol_expect_error("examples/errors/error_const_case_label_local.cay")
+        .expect("a local variable as case label should fail to compile");
+    assert!(error.contains("Case label 'limit' is not a constant: 'limit' is a local variable"),
+            "Should report the non-constant case label, got: {}", error);
+}
+
+#[test]
+fn test_error_const_division_by_zero() {
+    let error = compile_eol_expect_error("examples/errors/error_const_division_by_zero.cay")
+        .expect("division by zero in a constant should fail to compile");
+    assert!(error.contains("Cannot evaluate initializer of 'ConstDivision.PER_ITEM' at compile time: division by zero"),
+            "Should report the division by zero, got: {}", error);
+}
+
+#[test]
+fn test_error_const_case_division_by_zero() {
+    let error = compile_eol_expect_error("examples/errors/error_const_case_division_by_zero.cay")
+        .expect("division by zero in a case label should fail to compile");
+    assert!(error.contains("Cannot evaluate case label at compile time: division by zero"),
+            "Should report the failing case label, got: {}", error);
+}
+
+#[test]
+fn test_error_const_function_non_const_call() {
+    let error = compile_eol_expect_error("examples/errors/error_const_function_non_const_call.cay")
+        .expect("a const function calling a non-const function should fail to compile");
+    assert!(error.contains("Const function 'ConstCall.scaled' cannot be evaluated at compile time: 'next' is not a const function"),
+            "Should report the non-const call, got: {}", error);
+}
+
+#[test]
+fn test_error_const_function_not_static() {
+    let error = compile_eol_expect_error("examples/errors/error_const_function_not_static.cay")
+        .expect("an instance const function should fail to compile");
+    assert!(error.contains("Const function 'ConstInstance.twice' must be a static method with a body"),
+            "Should report the instance const function, got: {}", error);
+}
+
+#[test]
+fn test_error_const_step_limit() {
+    let error = compile_eol_expect_error("examples/errors/error_const_step_limit.cay")
+        .expect("a non-terminating const function should fail to compile");
+    assert!(error.contains("constant evaluation did not finish within 1000000 steps"),
+            "Should report the step limit, got: {}", error);
+}
+
+#[test]
+fn test_error_const_cyclic() {
+    let error = compile_eol_expect_error("examples/errors/error_const_cyclic.cay")
+        .expect("cyclic constants should fail to compile");
+    assert!(error.contains("constant 'ConstCycle.A' depends on itself"), "Should report the cycle, got: {}", error);
+}
+
+#[test]
+fn test_error_const_array_negative_size() {
+    let error = compile_eol_expect_error("examples/errors/error_const_array_negative_size.cay")
+        .expect("a negative constant array size should fail to compile");
+    assert!(error.contains("Array size cannot be negative: -16"), "Should report the negative size, got: {}", error);
+}
@@ -0,0 +1,91 @@
+//! Cavvy 语言 defer 与 try-with-resources 集成测试
+//!
+//! 测试 defer 语句、try-with-resources 和 scope 块析构在正常结束、return、break、continue
+//! 等所有出口上按后进先出顺序执行清理，以及跳出 defer、资源缺少 close() 等错误
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+/// 运行 defer 示例，返回输出的各行
+fn run_defer_example() -> Vec<String> {
+    compile_and_run_eol("examples/test_defer.cay")
+        .expect("defer example should compile and run")
+        .lines()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// 输出中是否连续出现给定的各行（清理动作的顺序）
+fn has_sequence(lines: &[String], expected: &[&str]) -> bool {
+    lines.windows(expected.len()).any(|window| window.iter().zip(expected).all(|(line, e)| line == e))
+}
+
+// ==================== 0.5.2.0 defer 测试 ====================
+
+#[test]
+fn test_defer_on_return() {
+    let lines = run_defer_example();
+    assert!(has_sequence(&lines, &["defer inner", "defer outer 2", "defer outer 1", "compute(3) = 6"]),
+            "Early return should run all pending defers innermost first, got: {:?}", lines);
+    assert!(has_sequence(&lines, &["no early return", "defer outer 2", "defer outer 1", "compute(0) = 0"]),
+            "Skipped defers must not run on return, got: {:?}", lines);
+}
+
+#[test]
+fn test_defer_on_break_and_continue() {
+    let lines = run_defer_example();
+    assert!(has_sequence(&lines, &["end of iteration 0", "end of iteration 1", "end of inner 2.0"]),
+            "continue should run the loop body's defers, got: {:?}", lines);
+    assert!(has_sequence(&lines, &["end of inner 3.0", "end of iteration 3", "open a"]),
+            "Labeled break should run defers of every block it leaves, got: {:?}", lines);
+    assert!(!lines.iter().any(|line| line == "end of iteration 4"),
+            "Loop should stop after break, got: {:?}", lines);
+}
+
+#[test]
+fn test_try_with_resources() {
+    let lines = run_defer_example();
+    assert!(has_sequence(&lines, &["error path", "close b", "close a"]),
+            "Resources should be closed in reverse order on return, got: {:?}", lines);
+    assert!(has_sequence(&lines, &["using a and b", "close b", "close a", "after try"]),
+            "Resources should be closed at the end of the try block, got: {:?}", lines);
+}
+
+#[test]
+fn test_scope_destructors() {
+    let lines = run_defer_example();
+    assert!(has_sequence(&lines, &["in scope", "free buffer 2", "free buffer 1", "after scope"]),
+            "scope should destroy its objects in reverse order, got: {:?}", lines);
+}
+
+#[test]
+fn test_defer_uses_declaration_scope() {
+    let lines = run_defer_example();
+    assert!(has_sequence(&lines, &["shadowed counter = 100", "counter = 1"]),
+            "Deferred code should use the variables visible at the defer, got: {:?}", lines);
+}
+
+#[test]
+fn test_defer_on_try_operator() {
+    let lines = run_defer_example();
+    assert!(has_sequence(&lines, &["open f", "close f", "cleanup", "propagate err: bad input"]),
+            "'?' returning early should close resources and run defers, got: {:?}", lines);
+    assert!(has_sequence(&lines, &["checked 1", "loop cleanup 1", "loop cleanup 2", "loop err: two"]),
+            "'?' inside a loop body should run that iteration's defer, got: {:?}", lines);
+}
+
+#[test]
+fn test_defer_return_error() {
+    let error = compile_eol_expect_error("examples/errors/error_defer_return.cay")
+        .expect("return inside defer should fail to compile");
+    assert!(error.contains("'return' cannot leave a defer statement"),
+            "Expected defer jump error, got: {}", error);
+}
+
+#[test]
+fn test_try_resource_without_close_error() {
+    let error = compile_eol_expect_error("examples/errors/error_try_resource_without_close.cay")
+        .expect("resource without close() should fail to compile");
+    assert!(error.contains("must have a close() method"),
+            "Expected missing close() error, got: {}", error);
+}
@@ -180,7 +180,7 @@ fn test_source_span() {
 
 #[test]
 fn test_lexer_diagnostics_collection() {
-    let source = "int x = 42 @;";  // @ 是非法字符
+    let source = "int x = 42 `;";  // ` 是非法字符
     let (_tokens, diagnostics) = lex_with_diagnostics(source);
     
     // 应该产生错误
@@ -0,0 +1,70 @@
+//! Cavvy 语言枚举功能集成测试
+//!
+//! 测试 C 风格枚举、带负载的变体、负载绑定以及 switch 穷尽性检查
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 枚举测试 ====================
+
+#[test]
+fn test_c_style_enum() {
+    let output = compile_and_run_eol("examples/test_enum.cay").expect("enum example should compile and run");
+    assert!(output.contains("color: green") && output.contains("blue: blue"),
+            "Switch on C-style enum should match variants, got: {}", output);
+    assert!(output.contains("c is green: yes") && output.contains("c is not red: yes"),
+            "Unit variants should compare with == and !=, got: {}", output);
+}
+
+#[test]
+fn test_enum_payload_switch() {
+    let output = compile_and_run_eol("examples/test_enum.cay").expect("enum example should compile and run");
+    assert!(output.contains("circle area: 12.000000") && output.contains("rect area: 12.000000"),
+            "Switch should bind payload fields, got: {}", output);
+    assert!(output.contains("square area: 25.000000") && output.contains("empty area: 0.000000"),
+            "Int payloads and unit variants should work, got: {}", output);
+    assert!(output.contains("scaled rect area: 48.000000"),
+            "Variants should be constructible from bound payloads, got: {}", output);
+}
+
+#[test]
+fn test_enum_grouped_cases() {
+    let output = compile_and_run_eol("examples/test_enum.cay").expect("enum example should compile and run");
+    assert!(output.contains("describe rect: rect") && output.contains("describe square rect: square rect"),
+            "Payload bindings should be usable in nested statements, got: {}", output);
+    assert!(output.contains("describe circle: round-ish") && output.contains("describe empty: empty"),
+            "Grouped cases without bindings should fall through, got: {}", output);
+}
+
+#[test]
+fn test_error_enum_non_exhaustive() {
+    let error = compile_eol_expect_error("examples/errors/error_enum_non_exhaustive.cay")
+        .expect("switch missing a variant should fail to compile");
+    assert!(
+        error.contains("not exhaustive") && error.contains("Rect"),
+        "Should report the missing variant, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_enum_unknown_variant() {
+    let error = compile_eol_expect_error("examples/errors/error_enum_unknown_variant.cay")
+        .expect("unknown variant should fail to compile");
+    assert!(
+        error.contains("has no variant 'Yellow'"),
+        "Should report unknown variant, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_enum_pattern_arity() {
+    let error = compile_eol_expect_error("examples/errors/error_enum_pattern_arity.cay")
+        .expect("pattern binding count mismatch should fail to compile");
+    assert!(
+        error.contains("has 2 field(s), but pattern binds 1"),
+        "Should report binding count mismatch, got: {}",
+        error
+    );
+}
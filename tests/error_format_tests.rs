@@ -0,0 +1,246 @@
+//! Cavvy 机器可读诊断输出（--error-format）集成测试
+//!
+//! 测试 JSON 文档、SARIF 日志和单行格式，以及命令行工具的输出
+
+use std::process::Command;
+
+use cavvy::diagnostic::*;
+use cavvy::error_format::{self, ErrorFormat, SCHEMA_VERSION};
+use cavvy::semantic::SemanticAnalyzer;
+use serde_json::Value;
+
+/// 带范围、修复建议和相关信息的诊断
+fn sample_diagnostics() -> Vec<Diagnostic> {
+    vec![
+        Diagnostic::error(
+            ErrorCodes::SEMANTIC_UNDEFINED_IDENTIFIER,
+            CompilationPhase::Semantic,
+            "未定义标识符: 'cout'",
+            SourceLocation::new(3, 9),
+        )
+        .with_span(SourceSpan::new(3, 9, 3, 13))
+        .with_suggestion(FixSuggestion::new("改为已声明的变量 count").with_replacement("count", SourceSpan::new(3, 9, 3, 13)))
+        .with_suggestion(FixSuggestion::new("或者先声明变量"))
+        .with_related_info("count 在这里声明", SourceLocation::new(2, 13)),
+        Diagnostic::warning(
+            ErrorCodes::SEMANTIC_TYPE_MISMATCH,
+            CompilationPhase::Parser,
+            "第一行\n第二行",
+            SourceLocation::new(7, 1),
+        )
+        .with_file(Some("lib\\util.cay".to_string()))
+        .with_details("详细说明"),
+    ]
+}
+
+/// 分析源代码并收集诊断
+fn analyze(source: &str) -> Vec<Diagnostic> {
+    let tokens = cavvy::lexer::lex(source).expect("source should lex");
+    let program = cavvy::parser::parse_with_source(tokens, source.to_string()).expect("source should parse");
+    let mut diagnostics = DiagnosticCollector::new();
+    SemanticAnalyzer::new().analyze_collecting(&program, &mut diagnostics);
+    diagnostics.diagnostics().to_vec()
+}
+
+// ==================== 0.5.2.0 选项解析 ====================
+
+#[test]
+fn test_error_format_parse() {
+    for format in [ErrorFormat::Human, ErrorFormat::Short, ErrorFormat::Json, ErrorFormat::Sarif] {
+        assert_eq!(ErrorFormat::parse(format.name()), Some(format));
+    }
+    assert_eq!(ErrorFormat::parse("xml"), None);
+    assert_eq!(ErrorFormat::default(), ErrorFormat::Human);
+    assert!(ErrorFormat::Json.is_machine_readable() && ErrorFormat::Sarif.is_machine_readable());
+    assert!(!ErrorFormat::Short.is_machine_readable() && !ErrorFormat::Human.is_machine_readable());
+}
+
+#[test]
+fn test_error_format_from_args() {
+    let args: Vec<String> = ["cayc", "--error-format=sarif", "--error-format", "json", "-O2", "--error-format"]
+        .iter().map(|s| s.to_string()).collect();
+
+    let mut i = 1;
+    assert_eq!(ErrorFormat::from_args(&args, &mut i), Some(Ok(ErrorFormat::Sarif)));
+    assert_eq!(i, 1);
+
+    i = 2;
+    assert_eq!(ErrorFormat::from_args(&args, &mut i), Some(Ok(ErrorFormat::Json)));
+    assert_eq!(i, 3, "The value argument should be consumed");
+
+    i = 4;
+    assert_eq!(ErrorFormat::from_args(&args, &mut i), None);
+
+    i = 5;
+    assert!(matches!(ErrorFormat::from_args(&args, &mut i), Some(Err(_))));
+
+    let bad = vec!["cayc".to_string(), "--error-format=xml".to_string()];
+    i = 1;
+    let err = ErrorFormat::from_args(&bad, &mut i).unwrap().unwrap_err();
+    assert!(err.contains("xml"), "got: {}", err);
+}
+
+// ==================== 0.5.2.0 输出格式 ====================
+
+#[test]
+fn test_error_format_json() {
+    let doc = error_format::to_json("cayc", "src/main.cay", &sample_diagnostics());
+    assert_eq!(doc["version"], SCHEMA_VERSION);
+    assert_eq!(doc["tool"]["name"], "cayc");
+    assert_eq!(doc["summary"]["errors"], 1);
+    assert_eq!(doc["summary"]["warnings"], 1);
+
+    let first = &doc["diagnostics"][0];
+    assert_eq!(first["code"], "E4001");
+    assert_eq!(first["severity"], "error");
+    assert_eq!(first["phase"], "semantic");
+    assert_eq!(first["file"], "src/main.cay");
+    assert_eq!(first["details"], Value::Null);
+    assert_eq!(first["location"]["line"], 3);
+    assert_eq!(first["location"]["column"], 9);
+    assert_eq!(first["span"]["end"]["column"], 13);
+    assert_eq!(first["suggestions"][0]["replacement"], "count");
+    assert_eq!(first["suggestions"][0]["span"]["start"]["line"], 3);
+    assert_eq!(first["suggestions"][1]["replacement"], Value::Null);
+    assert_eq!(first["related"][0]["message"], "count 在这里声明");
+    assert_eq!(first["related"][0]["location"]["column"], 13);
+
+    let second = &doc["diagnostics"][1];
+    assert_eq!(second["severity"], "warning");
+    assert_eq!(second["phase"], "parser");
+    assert_eq!(second["file"], "lib\\util.cay");
+    assert_eq!(second["details"], "详细说明");
+    assert_eq!(second["span"], Value::Null);
+}
+
+#[test]
+fn test_error_format_json_unknown_location() {
+    let diagnostic = Diagnostic::error("E0001", CompilationPhase::Preprocessor, "无法读取", SourceLocation::default());
+    let doc = error_format::to_json("cayc", "main.cay", &[diagnostic.clone()]);
+    assert_eq!(doc["diagnostics"][0]["location"], Value::Null);
+    assert_eq!(error_format::format_short(&diagnostic, "main.cay"), "main.cay: error[E0001]: 无法读取");
+}
+
+#[test]
+fn test_error_format_sarif() {
+    let log = error_format::to_sarif("cay-check", "src/main.cay", &sample_diagnostics());
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "cay-check");
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), 2);
+
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "E4001");
+    assert_eq!(rules[result["ruleIndex"].as_u64().unwrap() as usize]["id"], "E4001");
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["properties"]["phase"], "semantic");
+    let region = &result["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(region["startLine"], 3);
+    assert_eq!(region["startColumn"], 9);
+    assert_eq!(region["endColumn"], 13);
+
+    let fix = &result["fixes"][0]["artifactChanges"][0];
+    assert_eq!(fix["artifactLocation"]["uri"], "src/main.cay");
+    assert_eq!(fix["replacements"][0]["insertedContent"]["text"], "count");
+    assert_eq!(fix["replacements"][0]["deletedRegion"]["startColumn"], 9);
+    assert_eq!(result["properties"]["suggestions"][0], "或者先声明变量");
+    assert_eq!(result["relatedLocations"][0]["physicalLocation"]["region"]["startLine"], 2);
+
+    let warning = &run["results"][1];
+    assert_eq!(warning["level"], "warning");
+    assert_eq!(warning["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "lib/util.cay");
+    assert_eq!(warning["properties"]["details"], "详细说明");
+    assert!(warning.get("fixes").is_none());
+}
+
+#[test]
+fn test_error_format_short() {
+    let diagnostics = sample_diagnostics();
+    assert_eq!(error_format::format_short(&diagnostics[0], "src/main.cay"),
+               "src/main.cay:3:9: error[E4001]: 未定义标识符: 'cout'");
+    assert_eq!(error_format::format_short(&diagnostics[1], "src/main.cay"),
+               "lib\\util.cay:7:1: warning[E4003]: 第一行");
+}
+
+#[test]
+fn test_error_format_semantic_analysis() {
+    let source = "public class Main {\n    public static void main() {\n        int x = undefinedVar;\n    }\n}\n";
+    let doc = error_format::to_json("cayc", "main.cay", &analyze(source));
+    let first = &doc["diagnostics"][0];
+    assert_eq!(first["code"], "E4001", "got: {}", doc);
+    assert_eq!(first["phase"], "semantic");
+    assert_eq!(first["location"]["line"], 3);
+}
+
+// ==================== 0.5.2.0 命令行 ====================
+
+#[test]
+fn test_error_format_cay_check_cli() {
+    let path = std::env::temp_dir().join(format!("cavvy_error_format_{}.cay", std::process::id()));
+    std::fs::write(&path, "public class Main {\n    public static void main() {\n        int x = undefinedVar;\n    }\n}\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cay-check"))
+        .arg("--error-format=json")
+        .arg(&path)
+        .output()
+        .expect("cay-check should run");
+    let _ = std::fs::remove_file(&path);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let doc: Value = serde_json::from_str(&stdout).unwrap_or_else(|e| panic!("stdout should be one JSON document ({}): {}", e, stdout));
+    assert_eq!(doc["version"], SCHEMA_VERSION);
+    assert_eq!(doc["tool"]["name"], "cay-check");
+    assert_eq!(doc["diagnostics"][0]["code"], "E4001");
+    assert_eq!(doc["diagnostics"][0]["location"]["line"], 3);
+}
+
+/// 运行命令行工具并把标准输出解析为 JSON 文档
+fn run_tool_json(binary: &str, args: &[&str]) -> Value {
+    let output = Command::new(binary).args(args).output().expect("tool should run");
+    assert!(!output.status.success(), "{} should fail on invalid source", binary);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    serde_json::from_str(&stdout).unwrap_or_else(|e| panic!("stdout should be one JSON document ({}): {}", e, stdout))
+}
+
+#[test]
+fn test_error_format_reports_every_error_cli() {
+    // 与 cay-check 相同，cayc 和 cay-run 的 json/sarif 输出为每个错误生成单独的诊断
+    let source = "public class Main {\n    public static void main() {\n        int a = missingOne;\n        String s = 1;\n        int b = missingTwo;\n    }\n}\n";
+    let path = std::env::temp_dir().join(format!("cavvy_error_format_many_{}.cay", std::process::id()));
+    std::fs::write(&path, source).unwrap();
+    let path = path.to_str().unwrap().to_string();
+    let output = std::env::temp_dir().join(format!("cavvy_error_format_many_{}.exe", std::process::id()));
+    let output = output.to_str().unwrap();
+
+    let expected: Vec<(String, u64)> = vec![("E4001".into(), 3), ("E4003".into(), 4), ("E4001".into(), 5)];
+    let tools = [
+        ("cay-check", env!("CARGO_BIN_EXE_cay-check"), vec![path.as_str()]),
+        ("cayc", env!("CARGO_BIN_EXE_cayc"), vec![path.as_str(), output]),
+        ("cay-run", env!("CARGO_BIN_EXE_cay-run"), vec![path.as_str()]),
+    ];
+    for (name, binary, args) in &tools {
+        let mut json_args = vec!["--error-format=json"];
+        json_args.extend(args);
+        let doc = run_tool_json(binary, &json_args);
+        let found: Vec<(String, u64)> = doc["diagnostics"].as_array().unwrap().iter()
+            .map(|d| (d["code"].as_str().unwrap().to_string(), d["location"]["line"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(found, expected,
+                   "{} json output: {}", name, doc);
+        assert_eq!(doc["summary"]["errors"], 3, "{} json output: {}", name, doc);
+
+        let mut sarif_args = vec!["--error-format=sarif"];
+        sarif_args.extend(args);
+        let log = run_tool_json(binary, &sarif_args);
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        let found: Vec<(String, u64)> = results.iter()
+            .map(|r| (r["ruleId"].as_str().unwrap().to_string(),
+                      r["locations"][0]["physicalLocation"]["region"]["startLine"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(found, expected,
+                   "{} sarif output: {}", name, log);
+    }
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,152 @@
+//! Cavvy 语言错误恢复集成测试
+//!
+//! 测试解析器的 panic 模式恢复（语句、成员和顶层声明处同步）和语义分析在出错后继续检查，
+//! 一次编译报告文件中的全部错误
+
+mod common;
+use common::compile_eol_expect_error;
+
+use cavvy::ast::{ClassMember, Program, Stmt};
+use cavvy::diagnostic::{CompilationPhase, DiagnosticCollector};
+use cavvy::semantic::SemanticAnalyzer;
+
+const SYNTAX_EXAMPLE: &str = "examples/errors/error_recovery_syntax.cay";
+const SEMANTIC_EXAMPLE: &str = "examples/errors/error_recovery_semantic.cay";
+
+/// 以错误恢复模式解析源代码
+fn parse_recovering(source: &str, diagnostics: &mut DiagnosticCollector) -> Option<Program> {
+    let tokens = cavvy::lexer::lex(source).expect("source should lex");
+    cavvy::parser::parse_recovering(tokens, source.to_string(), &[], diagnostics)
+}
+
+/// 收集示例程序的全部语义错误
+fn analyze_collecting(path: &str, diagnostics: &mut DiagnosticCollector) {
+    let source = std::fs::read_to_string(path).expect("example should exist");
+    let tokens = cavvy::lexer::lex(&source).expect("example should lex");
+    let program = cavvy::parser::parse_with_source(tokens, source).expect("example should parse");
+    SemanticAnalyzer::new().analyze_collecting(&program, diagnostics);
+}
+
+fn error_lines(diagnostics: &DiagnosticCollector) -> Vec<usize> {
+    diagnostics.diagnostics().iter().map(|d| d.location.line).collect()
+}
+
+// ==================== 0.5.2.0 语法错误恢复测试 ====================
+
+#[test]
+fn test_parser_reports_all_syntax_errors() {
+    let source = std::fs::read_to_string(SYNTAX_EXAMPLE).expect("example should exist");
+    let mut diagnostics = DiagnosticCollector::new();
+    let program = parse_recovering(&source, &mut diagnostics).expect("recovery should still produce a program");
+
+    assert_eq!(error_lines(&diagnostics), vec![4, 6, 11, 13, 16], "every syntax error should be reported once");
+    let codes: Vec<&str> = diagnostics.diagnostics().iter().map(|d| d.code.as_str()).collect();
+    assert_eq!(codes, vec!["E3002", "E3004", "E3008", "E3008", "E3002"]);
+    assert!(diagnostics.diagnostics().iter().all(|d| d.phase == CompilationPhase::Parser));
+
+    let main = program.classes[0].members.iter().find_map(|member| match member {
+        ClassMember::Method(method) if method.name == "main" => method.body.as_ref(),
+        _ => None,
+    }).expect("main should survive the errors before it");
+    assert!(main.statements.iter().any(|stmt| matches!(stmt, Stmt::Error(_))),
+            "statements with syntax errors should be replaced by error nodes");
+    assert!(main.statements.iter().any(|stmt| matches!(stmt, Stmt::VarDecl(var) if var.name == "y")),
+            "statements between errors should still be parsed");
+    assert!(matches!(main.statements.last(), Some(Stmt::Expr(_))), "statements after the last error should still be parsed");
+}
+
+#[test]
+fn test_parser_recovers_in_members_and_declarations() {
+    let source = r#"
+interface Shape {
+    int area(;
+    int perimeter();
+}
+
+struct Point {
+    int x;
+    int = 0;
+    int y;
+}
+
+int helper( {
+    return 1;
+}
+
+public class Main {
+    public static void main() {
+        switch (1) {
+            case 1:
+                int a = ;
+                break;
+        }
+        for (int i = 0; i < ; i++) {
+        }
+        println("done");
+    }
+}
+"#;
+    let mut diagnostics = DiagnosticCollector::new();
+    let program = parse_recovering(source, &mut diagnostics).expect("recovery should still produce a program");
+
+    assert_eq!(error_lines(&diagnostics), vec![3, 9, 13, 21, 24]);
+    assert_eq!(program.interfaces[0].methods.len(), 1, "the broken interface method should be dropped");
+    assert_eq!(program.structs[0].fields.len(), 2, "the broken struct field should be dropped");
+    assert_eq!(program.classes.len(), 1, "the class after a broken top-level function should be parsed");
+}
+
+#[test]
+fn test_parse_without_recovery_stops_at_first_error() {
+    let source = std::fs::read_to_string(SYNTAX_EXAMPLE).expect("example should exist");
+    let tokens = cavvy::lexer::lex(&source).expect("example should lex");
+    let error = cavvy::parser::parse_with_source(tokens, source).expect_err("example should fail to parse");
+    assert_eq!(cavvy::error::get_error_location(&error).map(|(line, _)| line), Some(4));
+}
+
+#[test]
+fn test_recovery_respects_max_errors() {
+    let source = std::fs::read_to_string(SYNTAX_EXAMPLE).expect("example should exist");
+    let mut diagnostics = DiagnosticCollector::new().with_max_errors(2);
+    parse_recovering(&source, &mut diagnostics);
+    assert_eq!(diagnostics.error_count(), 2);
+    assert!(diagnostics.is_max_errors_reached());
+}
+
+// ==================== 0.5.2.0 语义错误恢复测试 ====================
+
+#[test]
+fn test_analyzer_reports_all_semantic_errors() {
+    let mut diagnostics = DiagnosticCollector::new();
+    analyze_collecting(SEMANTIC_EXAMPLE, &mut diagnostics);
+
+    assert_eq!(error_lines(&diagnostics), vec![9, 10, 12, 17]);
+    let messages: Vec<&str> = diagnostics.diagnostics().iter().map(|d| d.message.as_str()).collect();
+    assert!(messages[0].contains("未定义标识符: 'total'"), "got: {:?}", messages);
+    assert!(messages.iter().all(|m| !m.contains("'a'")),
+            "a variable whose initializer failed should stay declared, got: {:?}", messages);
+    assert!(diagnostics.diagnostics().iter().all(|d| d.phase == CompilationPhase::Semantic));
+}
+
+#[test]
+fn test_analyze_keeps_single_error() {
+    let source = "public class Main {\n    public static void main() {\n        int a = total;\n    }\n}\n";
+    let tokens = cavvy::lexer::lex(source).expect("source should lex");
+    let program = cavvy::parser::parse_with_source(tokens, source.to_string()).expect("source should parse");
+    let error = SemanticAnalyzer::new().analyze(&program).expect_err("undefined identifier should fail");
+    let message = cavvy::error::get_error_message(&error);
+    assert!(message.contains("未定义标识符: 'total'") && !message.contains('\n'), "got: {}", message);
+}
+
+#[test]
+fn test_error_recovery_syntax() {
+    let error = compile_eol_expect_error(SYNTAX_EXAMPLE)
+        .expect("a file with syntax errors should fail to compile");
+    assert!(error.contains("期望分号 ';'"), "Should report the first syntax error, got: {}", error);
+}
+
+#[test]
+fn test_error_recovery_semantic() {
+    let error = compile_eol_expect_error(SEMANTIC_EXAMPLE)
+        .expect("a file with semantic errors should fail to compile");
+    assert!(error.contains("未定义标识符: 'total'"), "Should report the undefined identifier, got: {}", error);
+}
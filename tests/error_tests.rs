@@ -29,6 +29,28 @@ fn test_error_string_plus_float() {
     );
 }
 
+#[test]
+fn test_error_string_plus_int_in_loop() {
+    let error = compile_eol_expect_error("examples/errors/error_string_plus_int_in_loop.cay")
+        .expect("string + int inside a loop body should fail to compile");
+    assert!(
+        error.contains("Cannot add string and int"),
+        "Should report the same type error inside statement bodies, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_string_to_int_cast() {
+    let error = compile_eol_expect_error("examples/errors/error_string_to_int_cast.cay")
+        .expect("casting a string to int should fail to compile");
+    assert!(
+        error.contains("Invalid cast from string to int"),
+        "Should report invalid cast inside statement bodies, got: {}",
+        error
+    );
+}
+
 #[test]
 fn test_error_type_mismatch_assign() {
     let error = compile_eol_expect_error("examples/errors/error_type_mismatch_assign.cay")
@@ -0,0 +1,193 @@
+//! Cavvy 错误代码解释（cayc --explain）测试
+//!
+//! 检查每个错误代码都有解释，并且解释中的示例确实会（或不会）产生对应的错误
+
+use std::process::Command;
+
+use cavvy::codegen::IRGenerator;
+use cavvy::diagnostic::{DiagnosticCollector, ErrorCodes};
+use cavvy::explain::{Explanation, EXPLANATIONS};
+use cavvy::ir::IrBuilder;
+use cavvy::semantic::SemanticAnalyzer;
+
+/// 示例无法通过编译单个源文件复现的错误代码
+///
+/// 需要多个文件或链接器、只由 IrBuilder 报告、通常被语义分析提前拦截，或编译器目前不报告。
+const NOT_REPRODUCIBLE: &[&str] = &[
+    ErrorCodes::IO_ERROR,
+    ErrorCodes::PREPROCESSOR_CIRCULAR_INCLUDE,
+    ErrorCodes::IMPORT_NOT_FOUND,
+    ErrorCodes::IMPORT_PACKAGE_MISMATCH,
+    ErrorCodes::IMPORT_DUPLICATE_TYPE,
+    ErrorCodes::LEXER_INVALID_ESCAPE_SEQUENCE,
+    ErrorCodes::LEXER_INVALID_NUMBER_LITERAL,
+    ErrorCodes::LEXER_UNTERMINATED_COMMENT,
+    ErrorCodes::LEXER_INVALID_IDENTIFIER,
+    ErrorCodes::PARSER_MISSING_MAIN,
+    ErrorCodes::PARSER_MULTIPLE_MAIN,
+    ErrorCodes::SEMANTIC_UNUSED_VARIABLE,
+    ErrorCodes::CODEGEN_UNSUPPORTED_FEATURE,
+    ErrorCodes::CODEGEN_TYPE_CONVERSION_ERROR,
+    ErrorCodes::CODEGEN_SYMBOL_NOT_FOUND,
+    ErrorCodes::CODEGEN_INVALID_OPERATION,
+    ErrorCodes::CODEGEN_INTERNAL_ERROR,
+    ErrorCodes::LINKER_SYMBOL_NOT_FOUND,
+    ErrorCodes::LINKER_MULTIPLE_DEFINITION,
+    ErrorCodes::LINKER_LIBRARY_NOT_FOUND,
+];
+
+/// 编译源代码直到生成 LLVM IR，返回各阶段产生的诊断代码（包括警告）
+///
+/// 某个阶段出错后不再运行后续阶段。
+fn diagnostic_codes(source: &str) -> Vec<String> {
+    let source = match cavvy::preprocessor::preprocess(source, "explain.cay", ".") {
+        Ok(source) => source,
+        Err(error) => return vec![error.code().to_string()],
+    };
+    let tokens = match cavvy::lexer::lex(&source) {
+        Ok(tokens) => tokens,
+        Err(error) => return vec![error.code().to_string()],
+    };
+
+    let mut diagnostics = DiagnosticCollector::new();
+    let program = cavvy::parser::parse_recovering(tokens, source.clone(), &[], &mut diagnostics);
+    let program = match program {
+        Some(program) if !diagnostics.has_errors() => program,
+        _ => return diagnostics.diagnostics().iter().map(|d| d.code.clone()).collect(),
+    };
+
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze_collecting(&program, &mut diagnostics);
+    let mut codes: Vec<String> = diagnostics.diagnostics().iter().map(|d| d.code.clone()).collect();
+    if diagnostics.has_errors() {
+        return codes;
+    }
+
+    let mut generator = IRGenerator::new();
+    generator.set_type_registry(analyzer.get_type_registry().clone());
+    if let Err(error) = generator.generate(&program, "") {
+        codes.push(error.code().to_string());
+    }
+    codes
+}
+
+// ==================== 解释表 ====================
+
+#[test]
+fn test_every_code_has_explanation() {
+    let codes: Vec<&str> = EXPLANATIONS.iter().map(|explanation| explanation.code).collect();
+    assert_eq!(codes, ErrorCodes::ALL, "EXPLANATIONS 应与 ErrorCodes::ALL 一一对应且顺序相同");
+
+    for explanation in EXPLANATIONS {
+        let code = explanation.code;
+        assert_ne!(ErrorCodes::get_description(code), "未知错误", "{} 缺少简短描述", code);
+        assert!(!explanation.explanation.trim().is_empty(), "{} 缺少解释", code);
+        assert!(!explanation.failing.trim().is_empty(), "{} 缺少错误示例", code);
+        assert!(!explanation.fixed.trim().is_empty(), "{} 缺少修正示例", code);
+        assert_ne!(explanation.failing, explanation.fixed, "{} 的两个示例相同", code);
+    }
+}
+
+#[test]
+fn test_all_codes_listed() {
+    // ErrorCodes 中声明的每个代码常量都必须出现在 ALL 中
+    let source = include_str!("../src/diagnostic.rs");
+    let declared: Vec<&str> = source
+        .lines()
+        .filter(|line| line.trim_start().starts_with("pub const ") && line.contains(": &'static str = \"E"))
+        .filter_map(|line| line.split('"').nth(1))
+        .collect();
+
+    assert_eq!(declared.len(), ErrorCodes::ALL.len());
+    for code in declared {
+        assert!(ErrorCodes::ALL.contains(&code), "{} 没有加入 ErrorCodes::ALL", code);
+    }
+
+    let mut sorted = ErrorCodes::ALL.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    assert_eq!(sorted, ErrorCodes::ALL, "ErrorCodes::ALL 应按代码排序且没有重复");
+}
+
+#[test]
+fn test_explanation_find() {
+    assert_eq!(Explanation::find("E4003").map(|e| e.code), Some(ErrorCodes::SEMANTIC_TYPE_MISMATCH));
+    assert_eq!(Explanation::find(" e2002 ").map(|e| e.code), Some(ErrorCodes::LEXER_UNTERMINATED_STRING));
+    assert!(Explanation::find("E9999").is_none());
+    assert!(Explanation::find("").is_none());
+
+    let text = Explanation::find("E4001").unwrap().to_string();
+    assert!(text.starts_with("E4001: 未定义的标识符"));
+    assert!(text.contains("错误示例:") && text.contains("修正示例:"));
+    assert!(text.contains("    int total = cout + 1;"));
+}
+
+// ==================== 示例 ====================
+
+#[test]
+fn test_failing_examples_report_their_code() {
+    let mut failures = Vec::new();
+    for explanation in EXPLANATIONS.iter().filter(|e| !NOT_REPRODUCIBLE.contains(&e.code)) {
+        let codes = diagnostic_codes(explanation.failing);
+        if !codes.iter().any(|code| code == explanation.code) {
+            failures.push(format!("{}: 错误示例产生了 {:?}", explanation.code, codes));
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn test_fixed_examples_compile() {
+    let mut failures = Vec::new();
+    for explanation in EXPLANATIONS.iter().filter(|e| !NOT_REPRODUCIBLE.contains(&e.code)) {
+        let codes = diagnostic_codes(explanation.fixed);
+        if !codes.is_empty() {
+            failures.push(format!("{}: 修正示例产生了 {:?}", explanation.code, codes));
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn test_ir_builder_example() {
+    // E5001 只由结构化 IR 构建器报告
+    let explanation = Explanation::find(ErrorCodes::CODEGEN_UNSUPPORTED_FEATURE).unwrap();
+    let error_code = |source: &str| {
+        let tokens = cavvy::lexer::lex(source).expect("example should lex");
+        let program = cavvy::parser::parse_with_source(tokens, source.to_string()).expect("example should parse");
+        IrBuilder::new().build_from_ast(&program).err().map(|error| error.code())
+    };
+
+    assert_eq!(error_code(explanation.failing), Some(ErrorCodes::CODEGEN_UNSUPPORTED_FEATURE));
+    assert_eq!(error_code(explanation.fixed), None);
+}
+
+// ==================== cayc --explain ====================
+
+#[test]
+fn test_cayc_explain() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cayc"))
+        .args(["--explain", "e4001"])
+        .output()
+        .expect("Failed to execute cayc");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, Explanation::find("E4001").unwrap().to_string());
+}
+
+#[test]
+fn test_cayc_explain_unknown_code() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cayc"))
+        .args(["--explain", "E9999"])
+        .output()
+        .expect("Failed to execute cayc");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("E9999"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cayc"))
+        .arg("--explain")
+        .output()
+        .expect("Failed to execute cayc");
+    assert!(!output.status.success());
+}
@@ -0,0 +1,245 @@
+//! Cavvy 语言控制流分析集成测试
+//!
+//! 测试确定赋值（if/switch/循环/带标签 break）、final 局部变量只赋值一次、
+//! 非 void 方法缺少 return，以及 return、break、continue 之后的不可达语句警告
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+use cavvy::diagnostic::{DiagnosticCollector, Severity};
+use cavvy::semantic::SemanticAnalyzer;
+
+const EXAMPLE: &str = "examples/test_definite_assignment.cay";
+
+/// 分析源代码，返回分析器和收集到的诊断
+fn analyze_source(source: &str) -> (SemanticAnalyzer, DiagnosticCollector) {
+    let tokens = cavvy::lexer::lex(source).expect("source should lex");
+    let program = cavvy::parser::parse_with_source(tokens, source.to_string()).expect("source should parse");
+    let mut analyzer = SemanticAnalyzer::new();
+    let mut diagnostics = DiagnosticCollector::new();
+    analyzer.analyze_collecting(&program, &mut diagnostics);
+    (analyzer, diagnostics)
+}
+
+/// 把方法体包装进带 main 方法的类
+fn wrap(methods: &str) -> String {
+    format!("public class Main {{\n{}\n    public static void main() {{\n    }}\n}}\n", methods)
+}
+
+fn errors(diagnostics: &DiagnosticCollector) -> Vec<(usize, String, String)> {
+    diagnostics.diagnostics().iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| (d.location.line, d.code.clone(), d.message.clone()))
+        .collect()
+}
+
+fn warnings(analyzer: &SemanticAnalyzer) -> Vec<(usize, &str)> {
+    analyzer.warnings().iter().map(|w| (w.line, w.message.as_str())).collect()
+}
+
+// ==================== 0.5.2.0 确定赋值测试 ====================
+
+#[test]
+fn test_definite_assignment_run() {
+    let output = compile_and_run_eol(EXAMPLE).expect("definite assignment example should compile and run");
+    assert!(output.contains("loopForever = 11") && output.contains("forever2 = 4"),
+            "Methods ending in an infinite loop should not need a return, got: {}", output);
+    assert!(output.contains("branches = -1") && output.contains("viaSwitch = 20") && output.contains("labeled = 3"),
+            "Variables assigned on every path should be readable, got: {}", output);
+    assert!(output.contains("finalOnce = 1"), "A final local assigned once per branch should be allowed, got: {}", output);
+    assert!(output.contains("shortCircuit = 5") && output.contains("doLoop = 8"), "got: {}", output);
+}
+
+#[test]
+fn test_definite_assignment_example_is_clean() {
+    let source = std::fs::read_to_string(EXAMPLE).expect("example should exist");
+    let (analyzer, diagnostics) = analyze_source(&source);
+    assert!(!diagnostics.has_errors(), "got: {:?}", errors(&diagnostics));
+    assert!(analyzer.warnings().is_empty(), "got: {:?}", warnings(&analyzer));
+}
+
+#[test]
+fn test_uninitialized_reads() {
+    let source = wrap(r#"
+    public static int noElse(int n) {
+        int r;
+        if (n > 0) {
+            r = 1;
+        }
+        return r;
+    }
+
+    public static int noDefault(int n) {
+        int r;
+        switch (n) {
+            case 1:
+                r = 10;
+                break;
+        }
+        return r;
+    }
+
+    public static int afterLoop(int n) {
+        int r;
+        while (n > 0) {
+            r = n;
+            n--;
+        }
+        return r;
+    }
+
+    public static void inLambda() {
+        int z;
+        var f = () -> z + 1;
+    }
+"#);
+    let (_, diagnostics) = analyze_source(&source);
+    let errors = errors(&diagnostics);
+    let lines: Vec<usize> = errors.iter().map(|(line, _, _)| *line).collect();
+    assert_eq!(lines, vec![8, 18, 27, 32], "got: {:?}", errors);
+    assert!(errors.iter().all(|(_, code, _)| code == "E4006"), "got: {:?}", errors);
+    assert!(errors[0].2.contains("Variable 'r' might not have been initialized"), "got: {:?}", errors);
+    assert!(errors[3].2.contains("'z'"), "got: {:?}", errors);
+}
+
+#[test]
+fn test_final_local_assigned_once() {
+    let source = wrap(r#"
+    public static int twice(boolean b) {
+        final int y;
+        y = 1;
+        if (b) {
+            y = 2;
+        }
+        return y;
+    }
+
+    public static void inLoop() {
+        final int x;
+        for (int i = 0; i < 3; i++) {
+            x = i;
+        }
+    }
+
+    public static void initialized() {
+        final int k = 1;
+        k = 2;
+    }
+"#);
+    let (_, diagnostics) = analyze_source(&source);
+    let errors = errors(&diagnostics);
+    assert!(errors.iter().all(|(_, code, _)| code == "E4010"), "got: {:?}", errors);
+    let message_at = |line: usize| errors.iter().find(|(l, _, _)| *l == line).map(|(_, _, m)| m.as_str()).unwrap_or("");
+    assert_eq!(errors.len(), 3, "got: {:?}", errors);
+    assert!(message_at(7).contains("Variable 'y' might already have been assigned"), "got: {:?}", errors);
+    assert!(message_at(15).contains("Variable 'x' might already have been assigned"), "got: {:?}", errors);
+    assert!(message_at(21).contains("Cannot assign a value to final variable 'k'"), "got: {:?}", errors);
+}
+
+// ==================== 0.5.2.0 缺少 return 测试 ====================
+
+#[test]
+fn test_missing_return() {
+    let source = wrap(r#"
+    public static int ifOnly(int n) {
+        if (n > 0) {
+            return 1;
+        }
+    }
+
+    public static int loopWithBreak(int n) {
+        while (true) {
+            if (n > 0) {
+                break;
+            }
+            return 0;
+        }
+    }
+
+    public static int complete(int n) {
+        if (n > 0) {
+            return 1;
+        } else {
+            return 0;
+        }
+    }
+"#);
+    let (_, diagnostics) = analyze_source(&source);
+    let errors = errors(&diagnostics);
+    assert_eq!(errors.len(), 2, "got: {:?}", errors);
+    assert!(errors.iter().all(|(_, code, _)| code == "E4011"), "got: {:?}", errors);
+    assert!(errors[0].2.contains("Missing return statement in method 'ifOnly'"), "got: {:?}", errors);
+    assert!(errors[1].2.contains("'loopWithBreak'"), "got: {:?}", errors);
+}
+
+// ==================== 0.5.2.0 不可达语句测试 ====================
+
+#[test]
+fn test_unreachable_statement_warnings() {
+    let source = wrap(r#"
+    public static int afterReturn(int n) {
+        return n;
+        n++;
+    }
+
+    public static void afterJumps() {
+        for (int i = 0; i < 3; i++) {
+            if (i == 1) {
+                continue;
+                println("skipped");
+            }
+            break;
+            println("never");
+        }
+    }
+"#);
+    let (analyzer, diagnostics) = analyze_source(&source);
+    assert!(!diagnostics.has_errors(), "Unreachable code should only warn, got: {:?}", errors(&diagnostics));
+    let warnings = warnings(&analyzer);
+    let lines: Vec<usize> = warnings.iter().map(|(line, _)| *line).collect();
+    assert_eq!(lines, vec![5, 12, 15], "got: {:?}", warnings);
+    assert!(warnings.iter().all(|(_, message)| *message == "Unreachable statement"), "got: {:?}", warnings);
+    assert!(diagnostics.diagnostics().iter().all(|d| d.code == "E4028"),
+            "got: {:?}", diagnostics.diagnostics().iter().map(|d| &d.code).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_constant_conditions() {
+    let source = wrap(r#"
+    static final boolean DEBUG = false;
+
+    public static int spin(int n) {
+        while (true) {
+            n++;
+            if (n > 10) {
+                return n;
+            }
+        }
+    }
+
+    public static void debugOnly() {
+        int v;
+        if (DEBUG) {
+            println(v);
+        }
+    }
+"#);
+    let (analyzer, diagnostics) = analyze_source(&source);
+    let errors = errors(&diagnostics);
+    assert!(errors.is_empty(), "while (true) needs no return and dead branches are not checked, got: {:?}", errors);
+    assert!(analyzer.warnings().is_empty(), "got: {:?}", warnings(&analyzer));
+}
+
+#[test]
+fn test_error_uninitialized_variable() {
+    let error = compile_eol_expect_error("examples/errors/error_uninitialized_variable.cay")
+        .expect("reading an unassigned variable should fail to compile");
+    assert!(error.contains("Variable 'result' might not have been initialized"), "got: {}", error);
+}
+
+#[test]
+fn test_error_final_local_reassigned() {
+    let error = compile_eol_expect_error("examples/errors/error_final_local_reassigned.cay")
+        .expect("assigning a final local in a loop should fail to compile");
+    assert!(error.contains("Variable 'limit' might already have been assigned"), "got: {}", error);
+}
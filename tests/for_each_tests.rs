@@ -0,0 +1,63 @@
+//! Cavvy 语言增强 for 循环集成测试
+//!
+//! 测试数组遍历、range(a, b, step) 计数循环、实现 Iterable 接口或带 hasNext()/next() 的对象，
+//! 以及标签跳转、var 和 final 循环变量
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 增强 for 循环测试 ====================
+
+#[test]
+fn test_for_each_arrays() {
+    let output = compile_and_run_eol("examples/test_for_each.cay").expect("for-each example should compile and run");
+    assert!(output.contains("array sum: 14"), "for-each should visit every array element, got: {}", output);
+    assert!(output.contains("ann bob cid"), "for-each should iterate string arrays, got: {}", output);
+    assert!(output.contains("product sum: 36"), "Nested var loop variables should work, got: {}", output);
+}
+
+#[test]
+fn test_for_each_range() {
+    let output = compile_and_run_eol("examples/test_for_each.cay").expect("for-each example should compile and run");
+    let lines: Vec<&str> = output.lines().map(|l| l.trim()).collect();
+    assert_eq!(&lines[2..5], &["0 1 2 3 4", "10 7 4 1", "1 3 5 7"],
+               "range should count with default, negative and runtime steps, got: {}", output);
+    assert!(output.contains("long range total: 4999950000"), "range should count with long arguments, got: {}", output);
+}
+
+#[test]
+fn test_for_each_iterables() {
+    let output = compile_and_run_eol("examples/test_for_each.cay").expect("for-each example should compile and run");
+    assert!(output.contains("3 2 1"), "for-each should iterate Iterable implementations, got: {}", output);
+    assert!(output.contains("for each works"), "for-each should iterate objects with hasNext()/next(), got: {}", output);
+}
+
+#[test]
+fn test_for_each_labels() {
+    let output = compile_and_run_eol("examples/test_for_each.cay").expect("for-each example should compile and run");
+    assert!(output.contains("pairs: 12"), "Labeled break and continue should target for-each loops, got: {}", output);
+}
+
+#[test]
+fn test_error_for_each_not_iterable() {
+    let error = compile_eol_expect_error("examples/errors/error_for_each_not_iterable.cay")
+        .expect("iterating over an int should fail to compile");
+    assert!(error.contains("for-each requires an array"),
+            "Should report the missing iteration protocol, got: {}", error);
+}
+
+#[test]
+fn test_error_for_each_element_type() {
+    let error = compile_eol_expect_error("examples/errors/error_for_each_element_type.cay")
+        .expect("assigning string elements to an int loop variable should fail to compile");
+    assert!(error.contains("Cannot assign string elements to loop variable 'n' of type int"),
+            "Should report the element type mismatch, got: {}", error);
+}
+
+#[test]
+fn test_error_for_each_final_variable() {
+    let error = compile_eol_expect_error("examples/errors/error_for_each_final_variable.cay")
+        .expect("assigning to a final loop variable should fail to compile");
+    assert!(error.contains("Cannot assign a value to final variable 'n'"),
+            "Should reject assignments to final loop variables, got: {}", error);
+}
@@ -0,0 +1,94 @@
+//! Cavvy 语言泛型功能集成测试
+//!
+//! 测试泛型类、泛型接口、泛型方法、类型参数上界以及单态化
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 泛型测试 ====================
+
+#[test]
+fn test_generic_classes() {
+    let output = compile_and_run_eol("examples/test_generics.cay").expect("generics should compile and run");
+    assert!(output.contains("Box<int>: 42") && output.contains("Box<int> after set: 7"),
+            "Generic class Box<int> should work, got: {}", output);
+    assert!(output.contains("Box<String>: hello") && output.contains("Pair: 1 one"),
+            "Generic classes with reference and multiple type arguments should work, got: {}", output);
+    assert!(output.contains("Nested: 99"),
+            "Nested generic instantiation should work, got: {}", output);
+}
+
+#[test]
+fn test_generic_interface_and_array() {
+    let output = compile_and_run_eol("examples/test_generics.cay").expect("generics should compile and run");
+    assert!(output.contains("Stack count: 3") && output.contains("Stack pop: 3") && output.contains("Stack first: 1"),
+            "Generic class implementing generic interface should work, got: {}", output);
+    assert!(output.contains("Stack<String> pop: x"),
+            "Generic array field should work per instantiation, got: {}", output);
+}
+
+#[test]
+fn test_generic_methods() {
+    let output = compile_and_run_eol("examples/test_generics.cay").expect("generics should compile and run");
+    assert!(output.contains("pick int: 3") && output.contains("pick String: b"),
+            "Static generic method should be specialized per type argument, got: {}", output);
+    assert!(output.contains("pick in branch: yes"),
+            "Generic method call inside nested statements should be specialized, got: {}", output);
+    assert!(output.contains("loop body: {0} 40") && output.contains("loop body: {1} 41"),
+            "String.valueOf and Integer.parseInt should work in loop bodies, got: {}", output);
+    assert!(output.contains("Stack orElse: fallback"),
+            "Instance generic method should work, got: {}", output);
+    assert!(output.contains("max: 9"),
+            "Bounded generic method should call the bound's method, got: {}", output);
+}
+
+#[test]
+fn test_generic_method_over_arrays_and_instances() {
+    let output = compile_and_run_eol("examples/test_generics.cay").expect("generics should compile and run");
+    assert!(output.contains("head int[]: 5") && output.contains("head String[]: alpha"),
+            "T[] parameters should bind T from the array element type, got: {}", output);
+    assert!(output.contains("head in println: 5"),
+            "A generic call used as a println argument should be specialized, got: {}", output);
+    assert!(output.contains("unwrap Box<int>: 7"),
+            "Box<T> parameters should accept Box<int>, got: {}", output);
+}
+
+#[test]
+fn test_generic_instance_name_does_not_collide() {
+    let output = compile_and_run_eol("examples/test_generics.cay").expect("generics should compile and run");
+    assert!(output.contains("Box_i32: 11") && output.contains("Box<int>: 42"),
+            "A user class named like a generic instance should not collide with it, got: {}", output);
+}
+
+#[test]
+fn test_error_generic_arity() {
+    let error = compile_eol_expect_error("examples/errors/error_generic_arity.cay")
+        .expect("wrong number of type arguments should fail to compile");
+    assert!(
+        error.contains("expects 1 type argument"),
+        "Should report type argument count mismatch, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_generic_bound() {
+    let error = compile_eol_expect_error("examples/errors/error_generic_bound.cay")
+        .expect("unsatisfied bound should fail to compile");
+    assert!(
+        error.contains("does not satisfy bound"),
+        "Should report unsatisfied type parameter bound, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_generic_raw_type() {
+    let error = compile_eol_expect_error("examples/errors/error_generic_raw_type.cay")
+        .expect("generic class without type arguments should fail to compile");
+    assert!(
+        error.contains("requires type arguments"),
+        "Should report missing type arguments, got: {}",
+        error
+    );
+}
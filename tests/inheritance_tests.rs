@@ -5,6 +5,9 @@
 mod common;
 use common::{compile_and_run_eol, compile_eol_expect_error};
 
+use cavvy::codegen::IRGenerator;
+use cavvy::semantic::SemanticAnalyzer;
+
 // ==================== 0.4.0.x 继承体系测试 ====================
 
 #[test]
@@ -165,3 +168,25 @@ fn test_this_access() {
     assert!(output.contains("Get value: 42"),
             "Should output get value, got: {}", output);
 }
+
+#[test]
+fn test_inherited_method_call() {
+    let output = compile_and_run_eol("examples/test_inherited_call.cay")
+        .expect("Inherited method call test should compile and run");
+    assert!(output.contains("total = 3000000002"),
+            "Should call inherited methods with their return types, got: {}", output);
+}
+
+#[test]
+fn test_inherited_method_call_codegen() {
+    let source = std::fs::read_to_string("examples/test_inherited_call.cay").expect("example should exist");
+    let tokens = cavvy::lexer::lex(&source).expect("example should lex");
+    let program = cavvy::parser::parse_with_source(tokens, source).expect("example should parse");
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&program).expect("example should pass semantic analysis");
+    let mut generator = IRGenerator::new();
+    generator.set_type_registry(analyzer.get_type_registry().clone());
+    let ir = generator.generate(&program, "").expect("example should generate IR");
+    assert!(ir.contains("call i32 @Base.one(i8*"), "Inherited int method should be called as i32");
+    assert!(ir.contains("call i64 @Base.big(i8*"), "Inherited long method should be called as i64");
+}
@@ -0,0 +1,119 @@
+//! Cavvy 语言整数类型集成测试
+//!
+//! 测试 byte、ubyte、short、ushort、uint、ulong 的取值范围、字面量后缀、提升规则，
+//! 无符号除法、比较和移位，以及隐式收窄、改变符号和字面量越界等错误
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 整数类型测试 ====================
+
+#[test]
+fn test_integer_type_ranges() {
+    let output = compile_and_run_eol("examples/test_integer_types.cay").expect("integer types example should compile and run");
+    assert!(output.contains("byte min = -128") && output.contains("short min = -32768"),
+            "Signed small integers should keep their sign, got: {}", output);
+    assert!(output.contains("ubyte max = 255") && output.contains("ushort max = 65535"),
+            "Unsigned small integers should print as unsigned, got: {}", output);
+    assert!(output.contains("uint max = 4294967295") && output.contains("ulong max = 18446744073709551615"),
+            "uint and ulong should print as unsigned, got: {}", output);
+    assert!(output.contains("suffix: 128 -5 40000 7"),
+            "Literal suffixes should produce typed literals, got: {}", output);
+}
+
+#[test]
+fn test_unsigned_arithmetic() {
+    let output = compile_and_run_eol("examples/test_integer_types.cay").expect("integer types example should compile and run");
+    assert!(output.contains("udiv = 1333333333") && output.contains("urem = 3"),
+            "uint division should be unsigned, got: {}", output);
+    assert!(output.contains("ult = true") && output.contains("ulong cmp = true"),
+            "Unsigned comparisons should use unsigned predicates, got: {}", output);
+    assert!(output.contains("ulong div = 9223372036854775807"),
+            "ulong division should be unsigned, got: {}", output);
+}
+
+#[test]
+fn test_unsigned_shift() {
+    let output = compile_and_run_eol("examples/test_integer_types.cay").expect("integer types example should compile and run");
+    assert!(output.contains("byte >> 2 = -4"),
+            ">> on a signed byte should be an arithmetic shift, got: {}", output);
+    assert!(output.contains("ubyte >> 2 = 60") && output.contains("ulong >> 60 = 15"),
+            ">> on unsigned types should be a logical shift, got: {}", output);
+}
+
+#[test]
+fn test_integer_promotion_and_casts() {
+    let output = compile_and_run_eol("examples/test_integer_types.cay").expect("integer types example should compile and run");
+    assert!(output.contains("ubyte + byte = 127"),
+            "Small integers should promote to int with their own sign, got: {}", output);
+    assert!(output.contains("uint -> long = 4294967295") && output.contains("widen(uint) = 4294967295"),
+            "Widening uint should zero-extend, got: {}", output);
+    assert!(output.contains("ubyte -> double = 255.000000"),
+            "Unsigned to floating point should use an unsigned conversion, got: {}", output);
+    assert!(output.contains("(ubyte) 300 = 44") && output.contains("(byte) 200 = -56") && output.contains("(uint) -1 = 4294967295"),
+            "Explicit casts should truncate, got: {}", output);
+}
+
+#[test]
+fn test_binary_protocol_parsing() {
+    let output = compile_and_run_eol("examples/test_integer_types.cay").expect("integer types example should compile and run");
+    assert!(output.contains("u16[0] = 4862") && output.contains("u16[2] = 65281"),
+            "ubyte arrays should be read without sign extension, got: {}", output);
+    assert!(output.contains("packet = 255 4862"),
+            "Fields of small integer types should store their values, got: {}", output);
+    assert!(output.contains("checksum = 528"),
+            "Static uint fields should accumulate unsigned values, got: {}", output);
+    assert!(output.contains("format: 4294967295 255"),
+            "Format placeholders should print unsigned values, got: {}", output);
+}
+
+#[test]
+fn test_string_value_of_unsigned() {
+    let output = compile_and_run_eol("examples/test_integer_types.cay").expect("integer types example should compile and run");
+    assert!(output.contains("valueOf: 4294967295 18446744073709551615 255 65535 -128"),
+            "String.valueOf should format small and unsigned integers as numbers, got: {}", output);
+}
+
+#[test]
+fn test_error_byte_literal_out_of_range() {
+    let error = compile_eol_expect_error("examples/errors/error_byte_literal_out_of_range.cay")
+        .expect("assigning 200 to a byte should fail to compile");
+    assert!(
+        error.contains("Integer literal 200 is out of range for byte [-128, 127]"),
+        "Should report the byte range, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_int_to_ubyte_assignment() {
+    let error = compile_eol_expect_error("examples/errors/error_int_to_ubyte_assignment.cay")
+        .expect("implicitly narrowing int to ubyte should fail to compile");
+    assert!(
+        error.contains("Cannot assign int to ubyte"),
+        "Should reject the implicit narrowing, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_ulong_to_long_assignment() {
+    let error = compile_eol_expect_error("examples/errors/error_ulong_to_long_assignment.cay")
+        .expect("implicitly converting ulong to long should fail to compile");
+    assert!(
+        error.contains("Cannot assign ulong to long"),
+        "Should reject the sign change, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_ubyte_suffix_overflow() {
+    let error = compile_eol_expect_error("examples/errors/error_ubyte_suffix_overflow.cay")
+        .expect("an out-of-range uy literal should fail to compile");
+    assert!(
+        error.contains("整数字面量 300 超出 ubyte 的取值范围"),
+        "Should report the literal range, got: {}",
+        error
+    );
+}
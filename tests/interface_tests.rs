@@ -0,0 +1,71 @@
+//! Cavvy 语言接口集成测试
+//!
+//! 测试类实现多个接口、接口的 default 和 static 方法、经接口方法表（itable）的动态分派、
+//! 针对接口的 instanceof，以及默认方法冲突等错误
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 接口测试 ====================
+
+#[test]
+fn test_interface_dispatch() {
+    let output = compile_and_run_eol("examples/test_interfaces.cay").expect("interfaces example should compile and run");
+    assert!(output.contains("total area = 16.000000"),
+            "Calls through an interface-typed array should dispatch to each class, got: {}", output);
+    assert!(output.contains("named: Circle") && output.contains("named: Label"),
+            "Calls through a second interface should dispatch to the implementation, got: {}", output);
+}
+
+#[test]
+fn test_interface_default_methods() {
+    let output = compile_and_run_eol("examples/test_interfaces.cay").expect("interfaces example should compile and run");
+    assert!(output.contains("Circle with area 3.000000"),
+            "Default method should call the class's implementation, got: {}", output);
+    assert!(output.contains("Square of side 2.000000") && output.contains("Square of side 3.000000"),
+            "A class (and its subclass) overriding a default method should use the override, got: {}", output);
+    assert!(output.contains("scaled circle area = 12.000000") && output.contains("Circle with area 12.000000"),
+            "Default method from a second interface should be inherited, got: {}", output);
+}
+
+#[test]
+fn test_interface_static_methods() {
+    let output = compile_and_run_eol("examples/test_interfaces.cay").expect("interfaces example should compile and run");
+    assert!(output.contains("unit area = 1.000000"),
+            "Static interface method should be callable through the interface name, got: {}", output);
+}
+
+#[test]
+fn test_interface_instanceof() {
+    let output = compile_and_run_eol("examples/test_interfaces.cay").expect("interfaces example should compile and run");
+    assert!(output.contains("label instanceof Shape: false") && output.contains("label instanceof Named: true"),
+            "instanceof should check the implemented interfaces, got: {}", output);
+    assert!(output.contains("cube instanceof Scalable: true"),
+            "Subclasses should implement their parent's interfaces, got: {}", output);
+    assert!(output.contains("cube instanceof Named: false"),
+            "instanceof should be false for interfaces the class does not implement, got: {}", output);
+}
+
+#[test]
+fn test_interface_default_conflict_error() {
+    let error = compile_eol_expect_error("examples/errors/error_interface_default_conflict.cay")
+        .expect("conflicting default methods should fail to compile");
+    assert!(error.contains("conflicting default methods 'describe'") && error.contains("override 'describe' in 'Duck'"),
+            "Expected conflicting default methods error, got: {}", error);
+}
+
+#[test]
+fn test_interface_method_body_error() {
+    let error = compile_eol_expect_error("examples/errors/error_interface_method_body.cay")
+        .expect("abstract interface method with a body should fail to compile");
+    assert!(error.contains("不能有方法体"),
+            "Expected interface method body error, got: {}", error);
+}
+
+#[test]
+fn test_interface_static_via_instance_error() {
+    let error = compile_eol_expect_error("examples/errors/error_interface_static_via_instance.cay")
+        .expect("calling a static interface method through an instance should fail to compile");
+    assert!(error.contains("must be called through the interface name"),
+            "Expected static interface method error, got: {}", error);
+}
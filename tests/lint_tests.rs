@@ -0,0 +1,185 @@
+//! Cavvy 语言代码检查（cay-lint）集成测试
+//!
+//! 测试各条 lint 规则、cavly.toml 中的规则级别，以及 `// cay-lint: allow(...)` 注释
+
+use std::process::Command;
+
+mod common;
+use common::compile_and_run_eol;
+
+use cavvy::diagnostic::DiagnosticCollector;
+use cavvy::lint::{LintConfig, LintDiagnostic, LintLevel, LintRule, Linter, Suppressions};
+use cavvy::semantic::SemanticAnalyzer;
+
+const EXAMPLE: &str = "examples/test_lint.cay";
+
+/// 分析并检查源代码，不应用注释中的 allow
+fn lint_with(source: &str, config: &LintConfig) -> (Vec<LintDiagnostic>, DiagnosticCollector) {
+    let tokens = cavvy::lexer::lex(source).expect("source should lex");
+    let program = cavvy::parser::parse_with_source(tokens, source.to_string()).expect("source should parse");
+    let mut analyzer = SemanticAnalyzer::new();
+    let mut diagnostics = DiagnosticCollector::new();
+    analyzer.analyze_collecting(&program, &mut diagnostics);
+    let lints = Linter::new(analyzer.get_type_registry(), config).lint(&program);
+    (lints, diagnostics)
+}
+
+/// 分析并检查源代码（默认级别），应用注释中的 allow
+fn lint_source(source: &str) -> Vec<LintDiagnostic> {
+    let (mut lints, diagnostics) = lint_with(source, &LintConfig::new());
+    assert!(!diagnostics.has_errors(), "source should pass semantic analysis, got: {:?}",
+            diagnostics.diagnostics().iter().map(|d| &d.message).collect::<Vec<_>>());
+    Suppressions::parse(source).retain(&mut lints);
+    lints
+}
+
+fn rules(lints: &[LintDiagnostic]) -> Vec<(usize, &'static str)> {
+    lints.iter().map(|lint| (lint.loc.line, lint.rule)).collect()
+}
+
+/// 把方法包装进带 main 方法的类
+fn wrap(methods: &str) -> String {
+    format!("public class Main {{\n{}\n    public static void main() {{\n    }}\n}}\n", methods)
+}
+
+// ==================== 0.5.2.0 示例程序 ====================
+
+#[test]
+fn test_lint_example_run() {
+    let output = compile_and_run_eol(EXAMPLE).expect("lint example should compile and run");
+    assert!(output.contains("go") && output.contains("lint example done"), "got: {}", output);
+}
+
+#[test]
+fn test_lint_example_rules() {
+    let source = std::fs::read_to_string(EXAMPLE).expect("example should exist");
+    let lints = lint_source(&source);
+    assert_eq!(rules(&lints), vec![
+        (10, LintRule::UNUSED_PRIVATE_FIELD),
+        (13, LintRule::UNUSED_PRIVATE_METHOD),
+        (33, LintRule::UNUSED_PARAMETER),
+        (34, LintRule::UNUSED_VARIABLE),
+        (38, LintRule::STRING_EQUALITY),
+        (41, LintRule::EMPTY_BLOCK),
+        (43, LintRule::SELF_ASSIGNMENT),
+        (46, LintRule::SHADOWED_VARIABLE),
+        (49, LintRule::MISSING_DEFAULT),
+    ], "got: {:?}", lints);
+    assert!(lints.iter().all(|lint| lint.level == LintLevel::Warn), "got: {:?}", lints);
+    assert!(lints[0].message.contains("Private field 'Main.hidden' is never read"), "got: {:?}", lints);
+    assert!(lints[7].message.contains("shadows a variable declared at line 44"), "got: {:?}", lints);
+}
+
+// ==================== 0.5.2.0 规则级别 ====================
+
+#[test]
+fn test_lint_config_levels() {
+    let source = std::fs::read_to_string(EXAMPLE).expect("example should exist");
+    let mut config = LintConfig::new();
+    config.set_level(LintRule::STRING_EQUALITY, LintLevel::Deny).unwrap();
+    config.set_level(LintRule::UNUSED_PARAMETER, LintLevel::Allow).unwrap();
+    assert!(config.set_level("no_such_rule", LintLevel::Warn).is_err());
+
+    let (lints, _) = lint_with(&source, &config);
+    assert!(lints.iter().all(|lint| lint.rule != LintRule::UNUSED_PARAMETER), "got: {:?}", lints);
+    let denied: Vec<_> = lints.iter().filter(|lint| lint.level == LintLevel::Deny).map(|lint| lint.rule).collect();
+    assert_eq!(denied, vec![LintRule::STRING_EQUALITY]);
+    // 不应用注释时，被 allow 的行也会报告
+    assert!(lints.iter().any(|lint| lint.loc.line == 35 && lint.rule == LintRule::UNUSED_VARIABLE), "got: {:?}", lints);
+}
+
+#[test]
+fn test_lint_suppression_comments() {
+    let source = r#"
+int a = 1; // cay-lint: allow(unused_variable, shadowed_variable)
+// cay-lint: allow(empty_block)
+
+@Test
+void f() {
+int b = 2;
+"#;
+    let suppressions = Suppressions::parse(source);
+    assert!(suppressions.is_allowed("unused_variable", 2) && suppressions.is_allowed("shadowed_variable", 2));
+    assert!(!suppressions.is_allowed("empty_block", 2));
+    assert!(suppressions.is_allowed("empty_block", 5) && suppressions.is_allowed("empty_block", 6));
+    assert!(!suppressions.is_allowed("empty_block", 7));
+}
+
+// ==================== 0.5.2.0 单条规则 ====================
+
+#[test]
+fn test_lint_unused_and_shadowed() {
+    let source = wrap(r#"
+    public static int f(int used, int _skipped) {
+        int a = 1;
+        int b = 2;
+        b = 3;
+        int sum = used;
+        var g = (int used) -> used + 1;
+        return sum + g(1);
+    }
+"#);
+    let lints = lint_source(&source);
+    assert_eq!(rules(&lints), vec![
+        (4, LintRule::UNUSED_VARIABLE),
+        (5, LintRule::UNUSED_VARIABLE),
+        (8, LintRule::SHADOWED_VARIABLE),
+    ], "Writes do not count as reads and '_' parameters are skipped, got: {:?}", lints);
+}
+
+#[test]
+fn test_lint_string_equality() {
+    let source = wrap(r#"
+    static String name = "n";
+
+    public static boolean f(String s, int i) {
+        boolean a = s == "x";
+        boolean b = name != s + "y";
+        boolean c = s == null;
+        boolean d = i == 3;
+        return a && b && c && d;
+    }
+"#);
+    let lints = lint_source(&source);
+    assert_eq!(rules(&lints), vec![(6, LintRule::STRING_EQUALITY), (7, LintRule::STRING_EQUALITY)], "got: {:?}", lints);
+    assert!(lints[1].message.contains("'!='"), "got: {:?}", lints);
+}
+
+const UNFREED_ALLOC_SOURCE: &str = r#"
+    public static void f() {
+        long kept = __cay_alloc(64);
+        long freed = __cay_alloc(64, 8);
+        println(kept);
+        __cay_free(freed);
+    }
+"#;
+
+#[test]
+fn test_lint_unfreed_alloc() {
+    let lints = lint_source(&wrap(UNFREED_ALLOC_SOURCE));
+    assert_eq!(rules(&lints), vec![(4, LintRule::UNFREED_ALLOC)], "got: {:?}", lints);
+    assert!(lints[0].message.contains("'kept'"), "got: {:?}", lints);
+}
+
+#[test]
+fn test_lint_unfreed_alloc_cli() {
+    // 与 cay-lint 相同的路径：有语义错误时不做检查
+    let path = std::env::temp_dir().join(format!("cavvy_lint_alloc_{}.cay", std::process::id()));
+    std::fs::write(&path, wrap(UNFREED_ALLOC_SOURCE)).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_cay-lint"))
+        .arg(&path)
+        .output()
+        .expect("cay-lint should run");
+    let _ = std::fs::remove_file(&path);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("warning[unfreed_alloc]") && stderr.contains("'kept'"), "got: {}", stderr);
+    assert_eq!(stderr.matches("unfreed_alloc").count(), 1, "got: {}", stderr);
+}
+
+#[test]
+fn test_lint_clean_example() {
+    let source = std::fs::read_to_string("examples/test_definite_assignment.cay").expect("example should exist");
+    let lints = lint_source(&source);
+    assert!(lints.is_empty(), "got: {:?}", lints);
+}
@@ -0,0 +1,80 @@
+//! Cavvy 语言嵌套类集成测试
+//!
+//! 测试静态嵌套类、隐式持有外部类实例的内部类、内联实现接口或继承类的匿名类，
+//! 以及外部类与嵌套类之间的访问规则
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 嵌套类测试 ====================
+
+#[test]
+fn test_static_nested_class() {
+    let output = compile_and_run_eol("examples/test_nested_classes.cay").expect("nested classes example should compile and run");
+    assert!(output.contains("pair sum = 7"),
+            "Static nested classes should be usable as Outer.Inner, got: {}", output);
+}
+
+#[test]
+fn test_inner_class_captures_outer_instance() {
+    let output = compile_and_run_eol("examples/test_nested_classes.cay").expect("nested classes example should compile and run");
+    assert!(output.contains("added apple") && output.contains("added pear"),
+            "Inner classes should call outer instance methods, got: {}", output);
+    assert!(output.contains("items = 2") && output.contains("total = 7"),
+            "Inner classes should update outer instance fields, got: {}", output);
+    assert!(output.contains("receipt of cay"),
+            "Doubly nested inner classes should reach the outermost instance, got: {}", output);
+}
+
+#[test]
+fn test_anonymous_classes() {
+    let output = compile_and_run_eol("examples/test_nested_classes.cay").expect("nested classes example should compile and run");
+    assert!(output.contains("Hello, cay"),
+            "Anonymous classes should implement interfaces and capture locals, got: {}", output);
+    assert!(output.contains("area = 21"),
+            "Anonymous subclasses should see inherited methods, captured locals and outer fields, got: {}", output);
+}
+
+#[test]
+fn test_error_inner_class_static_context() {
+    let error = compile_eol_expect_error("examples/errors/error_inner_class_static_context.cay")
+        .expect("creating an inner class from a static method should fail to compile");
+    assert!(
+        error.contains("无法在此处创建内部类"),
+        "Should report the missing outer instance, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_private_nested_class() {
+    let error = compile_eol_expect_error("examples/errors/error_private_nested_class.cay")
+        .expect("using a private nested class from another class should fail to compile");
+    assert!(
+        error.contains("Outer.Secret has private access in Outer"),
+        "Should report the private nested class, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_static_nested_outer_instance() {
+    let error = compile_eol_expect_error("examples/errors/error_static_nested_outer_instance.cay")
+        .expect("a static nested class reading an outer instance field should fail to compile");
+    assert!(
+        error.contains("实例成员 'count'"),
+        "Should report the outer instance member, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_anonymous_capture_var() {
+    let error = compile_eol_expect_error("examples/errors/error_anonymous_capture_var.cay")
+        .expect("capturing a var local in an anonymous class should fail to compile");
+    assert!(
+        error.contains("类型推断的局部变量 'offset'"),
+        "Should report the inferred local, got: {}",
+        error
+    );
+}
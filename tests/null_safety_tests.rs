@@ -0,0 +1,100 @@
+//! Cavvy 语言空安全集成测试
+//!
+//! 测试可空类型 T?、if/&&/三元运算符与提前返回的 null 收窄、安全调用 ?. 和 elvis 运算符 ?:，
+//! 以及开启 -F=null_safety 后对 null 赋值、可空值解引用和传参的检查
+
+mod common;
+use common::{compile_and_run_eol_with_features, compile_eol_expect_error, compile_eol_expect_error_with_features};
+
+// ==================== 0.5.2.0 空安全测试 ====================
+
+#[test]
+fn test_safe_call_and_elvis() {
+    let output = compile_and_run_eol_with_features("examples/test_null_safety.cay", &["-F=null_safety"])
+        .expect("null safety example should compile and run");
+    assert!(output.contains("len found = 5") && output.contains("len missing = 0"),
+            "?. with ?: should fall back to the default for null, got: {}", output);
+    assert!(output.contains("elvis missing = default") && output.contains("elvis found = alpha"),
+            "?: should keep non-null values, got: {}", output);
+}
+
+#[test]
+fn test_null_narrowing() {
+    let output = compile_and_run_eol_with_features("examples/test_null_safety.cay", &["-F=null_safety"])
+        .expect("null safety example should compile and run");
+    assert!(output.contains("safeLength = -1 5"),
+            "An early return should narrow the parameter, got: {}", output);
+    assert!(output.contains("narrowed = 5") && output.contains("ternary = alpha"),
+            "Null checks in if and ?: should narrow the variable, got: {}", output);
+    assert!(!output.contains("should not print"),
+            "&& should short-circuit on null, got: {}", output);
+}
+
+#[test]
+fn test_safe_call_chain() {
+    let output = compile_and_run_eol_with_features("examples/test_null_safety.cay", &["-F=null_safety"])
+        .expect("null safety example should compile and run");
+    assert!(output.contains("second label = none") && output.contains("next label = node"),
+            "Safe calls on nullable fields should check the receiver, got: {}", output);
+    assert!(output.contains("next name = anonymous"),
+            "?: should also apply when the member itself is null, got: {}", output);
+    assert!(output.matches("hello from node").count() == 1,
+            "A void safe call on null should be skipped, got: {}", output);
+    assert!(output.contains("label = null"),
+            "A safe call without a default should yield null, got: {}", output);
+}
+
+#[test]
+fn test_error_null_to_non_null() {
+    let error = compile_eol_expect_error_with_features("examples/errors/error_null_to_non_null.cay", &["-F=null_safety"])
+        .expect("assigning null to a non-null String should fail to compile");
+    assert!(
+        error.contains("Cannot assign null to non-null variable 'name'"),
+        "Should reject null for a non-null variable, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_nullable_dereference() {
+    let error = compile_eol_expect_error_with_features("examples/errors/error_nullable_dereference.cay", &["-F=null_safety"])
+        .expect("dereferencing an unchecked nullable value should fail to compile");
+    assert!(
+        error.contains("Possible null dereference: 'name' is nullable"),
+        "Should report the unchecked dereference, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_nullable_argument() {
+    let error = compile_eol_expect_error_with_features("examples/errors/error_nullable_argument.cay", &["-F=null_safety"])
+        .expect("passing a nullable value to a non-null parameter should fail to compile");
+    assert!(
+        error.contains("Cannot pass nullable value 'name' as argument 1 of 'greet'"),
+        "Should reject the nullable argument, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_safe_call_primitive() {
+    let error = compile_eol_expect_error_with_features("examples/errors/error_safe_call_primitive.cay", &["-F=null_safety"])
+        .expect("a primitive safe call without a default should fail to compile");
+    assert!(
+        error.contains("Safe call '?.length' yields int, which cannot be null"),
+        "Should require a default for a primitive safe call, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_nullable_primitive() {
+    let error = compile_eol_expect_error("examples/errors/error_nullable_primitive.cay")
+        .expect("declaring int? should fail to compile");
+    assert!(
+        error.contains("类型 int 不能声明为可空类型"),
+        "Should reject nullable primitive types, got: {}",
+        error
+    );
+}
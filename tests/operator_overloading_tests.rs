@@ -0,0 +1,106 @@
+//! Cavvy 语言运算符重载集成测试
+//!
+//! 测试类的运算符方法（operator+、operator==、operator[] 等）和 Java 风格的方法名
+//! （plus、equals、compareTo），== 调用 equals 而非比较引用，以及相关的错误
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 运算符重载测试 ====================
+
+#[test]
+fn test_operator_arithmetic() {
+    let output = compile_and_run_eol("examples/test_operator_overloading.cay").expect("operator overloading example should compile and run");
+    assert!(output.contains("a + b = (5.000000, 7.000000, 9.000000)"),
+            "operator+ should be called for +, got: {}", output);
+    assert!(output.contains("b - a = (3.000000, 3.000000, 3.000000)"),
+            "operator- should be called for -, got: {}", output);
+    assert!(output.contains("-a = (-1.000000, -2.000000, -3.000000)"),
+            "Unary operator- should be called for negation, got: {}", output);
+    assert!(output.contains("(a + b) * 0.5 - a = (1.500000, 1.500000, 1.500000)"),
+            "Overloaded operators should chain, got: {}", output);
+}
+
+#[test]
+fn test_operator_overload_resolution() {
+    let output = compile_and_run_eol("examples/test_operator_overloading.cay").expect("operator overloading example should compile and run");
+    assert!(output.contains("a * 2 = (2.000000, 4.000000, 6.000000)"),
+            "a * double should pick operator*(double), got: {}", output);
+    assert!(output.contains("a * b = 32.000000"),
+            "a * Vec3 should pick operator*(Vec3), got: {}", output);
+}
+
+#[test]
+fn test_operator_equality() {
+    let output = compile_and_run_eol("examples/test_operator_overloading.cay").expect("operator overloading example should compile and run");
+    assert!(output.contains("a == same: true") && output.contains("a != same: false"),
+            "== should call operator== instead of comparing references, got: {}", output);
+    assert!(output.contains("a != null: true"),
+            "Comparing with null should compare references, got: {}", output);
+    assert!(output.contains("m1 == new Money(250): true"),
+            "== should call a method named equals, got: {}", output);
+    assert!(output.contains("t1 == t2: false") && output.contains("t1 == t3: true"),
+            "Classes without equals should compare references, got: {}", output);
+}
+
+#[test]
+fn test_operator_equality_with_null_operands() {
+    let output = compile_and_run_eol("examples/test_operator_overloading.cay").expect("operator overloading example should compile and run");
+    assert!(output.contains("m1 == none: false") && output.contains("none == m1: false"),
+            "Exactly one null operand should be unequal without calling equals, got: {}", output);
+    assert!(output.contains("none == other: true") && output.contains("none != m1: true"),
+            "Two null operands should be equal, got: {}", output);
+}
+
+#[test]
+fn test_operator_compare_to() {
+    let output = compile_and_run_eol("examples/test_operator_overloading.cay").expect("operator overloading example should compile and run");
+    assert!(output.contains("m1 > m2: true") && output.contains("m1 <= m2: false"),
+            "Comparison operators should call compareTo, got: {}", output);
+    assert!(output.contains("total >= m1: true") && output.contains("m2 < m1"),
+            "Comparison operators should call compareTo, got: {}", output);
+}
+
+#[test]
+fn test_operator_index() {
+    let output = compile_and_run_eol("examples/test_operator_overloading.cay").expect("operator overloading example should compile and run");
+    assert!(output.contains("a[1] = 2.000000"),
+            "operator[] should be called for indexing, got: {}", output);
+    assert!(output.contains("a after a[2] = 9 = (1.000000, 2.000000, 9.000000)"),
+            "operator[]= should be called for indexed assignment, got: {}", output);
+    assert!(output.contains("a[2] + a[0] = 10.000000"),
+            "operator[] results should be usable in expressions, got: {}", output);
+}
+
+#[test]
+fn test_error_operator_undefined() {
+    let error = compile_eol_expect_error("examples/errors/error_operator_undefined.cay")
+        .expect("adding objects without an operator method should fail to compile");
+    assert!(
+        error.contains("Operator + is not defined for Meters"),
+        "Should report the missing operator, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_operator_comparison_decl() {
+    let error = compile_eol_expect_error("examples/errors/error_operator_comparison_decl.cay")
+        .expect("declaring operator< should fail to compile");
+    assert!(
+        error.contains("不支持重载比较运算符"),
+        "Should point to compareTo, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_operator_equals_return() {
+    let error = compile_eol_expect_error("examples/errors/error_operator_equals_return.cay")
+        .expect("equals returning int should not be usable as ==");
+    assert!(
+        error.contains("'Id.equals' must return bool to be used as an operator"),
+        "Should report the equals return type, got: {}",
+        error
+    );
+}
@@ -0,0 +1,109 @@
+//! Cavvy 语言包与导入集成测试
+//!
+//! 测试 package/import 声明、模块加载，以及跨包访问时的可见性检查
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+use cavvy::semantic::SemanticAnalyzer;
+
+// ==================== 0.5.2.0 包与导入测试 ====================
+
+#[test]
+fn test_import_single_type() {
+    let output = compile_and_run_eol("examples/test_packages.cay").expect("packages example should compile and run");
+    assert!(output.contains("lengthSquared = 25"),
+            "Imported class should call its package-private helper, got: {}", output);
+    assert!(output.contains("unit = inch"),
+            "Imported enum should be usable in types and switches, got: {}", output);
+}
+
+#[test]
+fn test_import_wildcard_package() {
+    let output = compile_and_run_eol("examples/test_packages.cay").expect("packages example should compile and run");
+    assert!(output.contains("rect area = 10"),
+            "Wildcard import should load every class in the package, got: {}", output);
+    assert!(output.contains("corner = 2,5"),
+            "Modules should load their own imports, got: {}", output);
+}
+
+#[test]
+fn test_protected_access_from_subclass() {
+    let output = compile_and_run_eol("examples/test_packages.cay").expect("packages example should compile and run");
+    assert!(output.contains("square area = 16") && output.contains("scaled area = 160"),
+            "Subclasses in other packages should reach protected methods, got: {}", output);
+}
+
+#[test]
+fn test_package_private_type_rejected() {
+    let error = compile_eol_expect_error("examples/errors/error_import_package_private.cay")
+        .expect("using a package-private class from another package should fail");
+    assert!(error.contains("is not public in geometry.shapes"),
+            "Error should name the owning package, got: {}", error);
+}
+
+#[test]
+fn test_protected_access_outside_package_rejected() {
+    let error = compile_eol_expect_error("examples/errors/error_protected_access_outside_package.cay")
+        .expect("calling a protected method outside the package should fail");
+    assert!(error.contains("has protected access in geometry.shapes.Shape"),
+            "Error should use the qualified class name, got: {}", error);
+}
+
+#[test]
+fn test_package_private_field_outside_package_rejected() {
+    let error = compile_eol_expect_error("examples/errors/error_package_private_field.cay")
+        .expect("reading a package-private field outside the package should fail");
+    assert!(error.contains("sides is not public in geometry.shapes.Shape"),
+            "Error should use the qualified class name, got: {}", error);
+}
+
+#[test]
+fn test_type_declared_in_two_modules_rejected() {
+    let error = compile_eol_expect_error("examples/errors/error_import_duplicate_type.cay")
+        .expect("declaring an imported type name again should fail");
+    assert!(error.contains("导入的类型 'geometry.Point' 与 'errors.Point' 同名"),
+            "Error should name both qualified types, got: {}", error);
+}
+
+#[test]
+fn test_same_type_name_in_two_packages() {
+    let output = compile_and_run_eol("examples/test_packages_same_name.cay").expect("same-name example should compile and run");
+    assert!(output.contains("flat = 25"),
+            "Single-type import should pick geometry.Point, got: {}", output);
+    assert!(output.contains("deep = 9") && output.contains("unit z = 1"),
+            "space.Point should be usable by its qualified name and inside its package, got: {}", output);
+}
+
+#[test]
+fn test_same_type_name_registered_by_qualified_name() {
+    let path = "examples/test_packages_same_name.cay";
+    let source = std::fs::read_to_string(path).expect("same-name example should exist");
+    let tokens = cavvy::lexer::lex(&source).expect("same-name example should lex");
+    let program = cavvy::modules::parse_program(tokens, source, path, Vec::new()).expect("same-name example should parse");
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&program).expect("same-name example should pass semantic analysis");
+
+    let registry = analyzer.get_type_registry();
+    for (name, package) in [("geometry.Point", "geometry"), ("space.Point", "space")] {
+        assert!(registry.get_class(name).is_some(), "{} should be registered", name);
+        assert_eq!(registry.type_packages.get(name).map(String::as_str), Some(package));
+    }
+    assert!(registry.get_class("Point").is_none(), "Package types should not be registered by simple name");
+}
+
+#[test]
+fn test_ambiguous_wildcard_type_rejected() {
+    let error = compile_eol_expect_error("examples/errors/error_import_ambiguous_type.cay")
+        .expect("using a type name provided by two wildcard imports should fail");
+    assert!(error.contains("类型名 'Point' 不明确"),
+            "Error should report the ambiguous name, got: {}", error);
+}
+
+#[test]
+fn test_unresolved_import_rejected() {
+    let error = compile_eol_expect_error("examples/errors/error_import_unresolved.cay")
+        .expect("importing a missing type should fail");
+    assert!(error.contains("geometry.Missing"),
+            "Error should name the unresolved import, got: {}", error);
+}
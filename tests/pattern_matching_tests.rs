@@ -0,0 +1,76 @@
+//! Cavvy 语言模式匹配集成测试
+//!
+//! 测试 instanceof 绑定变量（if、取反提前返回、&&、三元表达式、while 条件）
+//! 以及 switch 语句和 switch 表达式中的类型模式、守卫、支配检查和穷尽性检查
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 模式匹配测试 ====================
+
+#[test]
+fn test_instanceof_bindings() {
+    let output = compile_and_run_eol("examples/test_pattern_matching.cay").expect("pattern matching example should compile and run");
+    assert!(output.contains("radius: 2.000000"), "instanceof should bind the matched value, got: {}", output);
+    assert!(output.contains("b is not a circle"), "A failed instanceof should take the else branch, got: {}", output);
+    assert!(output.contains("large square: 3.000000"), "Bindings should be usable on the right of &&, got: {}", output);
+    assert!(output.contains("named: circle"), "instanceof should bind interface types, got: {}", output);
+    assert!(output.contains("side: 3.000000"), "Bindings should be usable in the true branch of ?:, got: {}", output);
+    assert!(output.contains("steps: 2"), "Bindings should be usable in while loop bodies, got: {}", output);
+}
+
+#[test]
+fn test_instanceof_binding_after_early_return() {
+    let output = compile_and_run_eol("examples/test_pattern_matching.cay").expect("pattern matching example should compile and run");
+    assert!(output.contains("radiusOf(a): 2.000000"), "Bindings should stay in scope after a negated early return, got: {}", output);
+    assert!(output.contains("radiusOf(b): -1.000000"), "Non-matching values should return early, got: {}", output);
+}
+
+#[test]
+fn test_switch_type_patterns() {
+    let output = compile_and_run_eol("examples/test_pattern_matching.cay").expect("pattern matching example should compile and run");
+    assert!(output.contains("circle r=2.000000"), "Type patterns should bind the switch value, got: {}", output);
+    assert!(output.contains("big circle"), "Guards should see the pattern variable, got: {}", output);
+    assert!(output.contains("square side=3.000000"), "Later type patterns should be tried in order, got: {}", output);
+    assert!(output.contains("other shape"), "Unmatched values should reach default, got: {}", output);
+}
+
+#[test]
+fn test_switch_expression_type_patterns() {
+    let output = compile_and_run_eol("examples/test_pattern_matching.cay").expect("pattern matching example should compile and run");
+    assert!(output.contains("area(a): 12.000000"), "Switch expressions should match class patterns, got: {}", output);
+    assert!(output.contains("area(c2): 10.000000"), "Switch expressions should evaluate the matched arm, got: {}", output);
+    assert!(output.contains("area(shape): 0.000000"), "A total pattern should make the switch exhaustive, got: {}", output);
+}
+
+#[test]
+fn test_error_pattern_dominated_case() {
+    let error = compile_eol_expect_error("examples/errors/error_pattern_dominated_case.cay")
+        .expect("a case after a more general type pattern should fail to compile");
+    assert!(error.contains("Case 'Circle c' is dominated by an earlier case for Shape"),
+            "Should report the dominated case, got: {}", error);
+}
+
+#[test]
+fn test_error_pattern_switch_not_exhaustive() {
+    let error = compile_eol_expect_error("examples/errors/error_pattern_switch_not_exhaustive.cay")
+        .expect("a non-exhaustive type switch should fail to compile");
+    assert!(error.contains("Switch on Shape is not exhaustive"),
+            "Should report the missing cases, got: {}", error);
+}
+
+#[test]
+fn test_error_pattern_variable_redefined() {
+    let error = compile_eol_expect_error("examples/errors/error_pattern_variable_redefined.cay")
+        .expect("a pattern variable shadowing a local should fail to compile");
+    assert!(error.contains("Pattern variable 'c' is already defined in this scope"),
+            "Should report the redefined pattern variable, got: {}", error);
+}
+
+#[test]
+fn test_error_pattern_never_matches() {
+    let error = compile_eol_expect_error("examples/errors/error_pattern_never_matches.cay")
+        .expect("an unrelated type pattern should fail to compile");
+    assert!(error.contains("Type pattern 'Square q' can never match a value of type Circle"),
+            "Should report the impossible pattern, got: {}", error);
+}
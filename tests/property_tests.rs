@@ -0,0 +1,148 @@
+//! Cavvy 语言属性集成测试
+//!
+//! 测试自动属性、private set、只读属性、带访问器体的计算属性和静态属性，
+//! 以及访问器访问权限、只读赋值等诊断信息
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+use cavvy::ast::Program;
+use cavvy::codegen::IRGenerator;
+use cavvy::semantic::SemanticAnalyzer;
+
+const EXAMPLE: &str = "examples/test_properties.cay";
+
+/// 解析并分析示例程序
+fn analyze_example() -> (Program, SemanticAnalyzer) {
+    let source = std::fs::read_to_string(EXAMPLE).expect("property example should exist");
+    let tokens = cavvy::lexer::lex(&source).expect("property example should lex");
+    let program = cavvy::parser::parse_with_source(tokens, source).expect("property example should parse");
+    let mut analyzer = SemanticAnalyzer::new();
+    analyzer.analyze(&program).expect("property example should pass semantic analysis");
+    (program, analyzer)
+}
+
+// ==================== 0.5.2.0 属性测试 ====================
+
+#[test]
+fn test_properties_run() {
+    let output = compile_and_run_eol(EXAMPLE).expect("property example should compile and run");
+    assert!(output.contains("a.count = 2") && output.contains("b.count = 1"), "Auto properties should read and write the backing field, got: {}", output);
+    assert!(output.contains("a.label = first"), "Read-only properties should be assignable in the constructor, got: {}", output);
+    assert!(output.contains("created = 2"), "Static properties should be shared, got: {}", output);
+    assert!(output.contains("after reset = 0"), "Generated getters should be callable explicitly, got: {}", output);
+    assert!(output.contains("area = 42"), "Computed properties should call the getter, got: {}", output);
+    assert!(output.contains("negative width ignored") && output.contains("width = 6"),
+            "Setters with bodies should run their validation, got: {}", output);
+    assert!(output.contains("chained = 10 area = 70"), "A property assignment should evaluate to the assigned value, got: {}", output);
+    assert!(output.contains("explicit accessors = 10x2"), "Generated accessors should be callable explicitly, got: {}", output);
+    assert!(output.contains("fahrenheit = 212.000000") && output.contains("kelvin = 373.150000"),
+            "Double properties should round-trip through their accessors, got: {}", output);
+    assert!(output.contains("panel = 10 20 40"), "Accessor bodies should read and assign properties by bare name, got: {}", output);
+    assert!(output.contains("grown = 14 lookups = 1"),
+            "A compound property assignment should evaluate its receiver once, got: {}", output);
+}
+
+#[test]
+fn test_properties_generate_accessors() {
+    let (_, analyzer) = analyze_example();
+    let registry = analyzer.get_type_registry();
+    let counter = registry.get_class("Counter").expect("Counter should be registered");
+    let count = counter.properties.get("count").expect("count should be a property");
+    assert!(count.is_auto, "count should be an auto property");
+    assert!(count.setter.as_ref().is_some_and(|setter| setter.is_private), "count should have a private setter");
+    assert!(counter.fields.contains_key("count"), "Auto properties should have a backing field");
+    assert!(counter.methods.contains_key("getCount") && counter.methods.contains_key("setCount"),
+            "Accessors should be generated as methods");
+
+    let rect = registry.get_class("Rect").expect("Rect should be registered");
+    assert!(!rect.fields.contains_key("area"), "Properties with bodies should not have a backing field");
+    assert!(rect.properties.get("area").is_some_and(|area| area.setter.is_none()), "area should be get-only");
+}
+
+#[test]
+fn test_properties_resolve_accessor_calls() {
+    let (_, analyzer) = analyze_example();
+    let registry = analyzer.get_type_registry();
+    let reads: Vec<&str> = registry.property_reads.values().map(|call| call.method.name.as_str()).collect();
+    let writes: Vec<&str> = registry.property_writes.values().map(|call| call.method.name.as_str()).collect();
+    assert!(reads.contains(&"getArea") && reads.contains(&"getWidth") && reads.contains(&"getFahrenheit"),
+            "Reads of properties with bodies should call the getter, got: {:?}", reads);
+    assert!(writes.contains(&"setWidth") && writes.contains(&"setFahrenheit"),
+            "Writes of properties with bodies should call the setter, got: {:?}", writes);
+    assert!(!reads.contains(&"getCount") && !writes.contains(&"setHeight"),
+            "Auto accessors should be inlined as field access, got: {:?} / {:?}", reads, writes);
+}
+
+#[test]
+fn test_properties_codegen() {
+    let (program, analyzer) = analyze_example();
+    let mut generator = IRGenerator::new();
+    generator.set_type_registry(analyzer.get_type_registry().clone());
+    let ir = generator.generate(&program, "").expect("property example should generate IR");
+    assert!(ir.contains("define i32 @Counter.getCount(i8* %this)"), "Auto properties should still generate a getter");
+    assert!(ir.contains("call i32 @Rect.getArea(i8*"), "Computed properties should call their getter");
+    assert!(ir.contains("call void @Rect.__setWidth_i(i8*"), "Assignments should call the setter");
+    assert_eq!(ir.matches("call i8* @Panel.__find_oPanel(").count(), 1, "A compound property assignment should evaluate its receiver once");
+    assert!(ir.contains("call void @Panel.__setDoubled_i(i8*"), "Bare property assignments in accessors should call the setter");
+}
+
+#[test]
+fn test_error_property_private_setter() {
+    let error = compile_eol_expect_error("examples/errors/error_property_private_setter.cay")
+        .expect("assigning a property with a private setter should fail to compile");
+    assert!(error.contains("The setter of property 'count' has private access in Counter"),
+            "Should report the private setter, got: {}", error);
+}
+
+#[test]
+fn test_error_property_read_only() {
+    let error = compile_eol_expect_error("examples/errors/error_property_read_only.cay")
+        .expect("assigning a read-only property outside the constructor should fail to compile");
+    assert!(error.contains("Cannot assign to read-only property 'Person.name'"), "Should report the read-only property, got: {}", error);
+}
+
+#[test]
+fn test_error_property_computed_read_only() {
+    let error = compile_eol_expect_error("examples/errors/error_property_computed_read_only.cay")
+        .expect("assigning a get-only computed property should fail to compile");
+    assert!(error.contains("Cannot assign to read-only property 'Square.area'"), "Should report the read-only property, got: {}", error);
+}
+
+#[test]
+fn test_error_property_mixed_accessors() {
+    let error = compile_eol_expect_error("examples/errors/error_property_mixed_accessors.cay")
+        .expect("mixing auto and custom accessors should fail to compile");
+    assert!(error.contains("属性 'total' 的 get 和 set 必须同时是自动访问器或同时带有访问器体"),
+            "Should report the mixed accessors, got: {}", error);
+}
+
+#[test]
+fn test_error_property_missing_getter() {
+    let error = compile_eol_expect_error("examples/errors/error_property_missing_getter.cay")
+        .expect("a property without getter should fail to compile");
+    assert!(error.contains("属性 'secret' 缺少 get 访问器"), "Should report the missing getter, got: {}", error);
+}
+
+#[test]
+fn test_error_property_method_conflict() {
+    let error = compile_eol_expect_error("examples/errors/error_property_method_conflict.cay")
+        .expect("a property conflicting with a method should fail to compile");
+    assert!(error.contains("Property 'Gauge.level' conflicts with method 'getLevel'"), "Should report the conflict, got: {}", error);
+}
+
+#[test]
+fn test_error_property_static_context() {
+    let error = compile_eol_expect_error("examples/errors/error_property_static_context.cay")
+        .expect("reading an instance property through the class should fail to compile");
+    assert!(error.contains("non-static property width cannot be referenced from a static context"),
+            "Should report the static context, got: {}", error);
+}
+
+#[test]
+fn test_error_property_increment_computed() {
+    let error = compile_eol_expect_error("examples/errors/error_property_increment_computed.cay")
+        .expect("incrementing a property with accessor bodies should fail to compile");
+    assert!(error.contains("Cannot increment or decrement property 'Meter.value' whose accessors have bodies"),
+            "Should report the increment, got: {}", error);
+}
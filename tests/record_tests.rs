@@ -0,0 +1,77 @@
+//! Cavvy 语言记录类集成测试
+//!
+//! 测试 record 声明展开出的规范构造函数、访问方法、紧凑构造函数、equals、hashCode 和
+//! toString，显式声明的成员优先，以及记录组件的不可变性
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 记录类测试 ====================
+
+#[test]
+fn test_record_constructor_and_accessors() {
+    let output = compile_and_run_eol("examples/test_records.cay").expect("records example should compile and run");
+    let lines: Vec<&str> = output.lines().map(|l| l.trim()).collect();
+    assert_eq!(lines.first(), Some(&"7"), "Accessors should return the components, got: {}", output);
+    assert!(output.contains("12.000000"),
+            "Records should implement interfaces, got: {}", output);
+}
+
+#[test]
+fn test_record_to_string() {
+    let output = compile_and_run_eol("examples/test_records.cay").expect("records example should compile and run");
+    assert!(output.contains("Point[x=3, y=4]"),
+            "toString should list the components, got: {}", output);
+    assert!(output.contains("Person[name=Alice, age=30, id=10000000000, active=true]"),
+            "toString should format strings, longs and bools, got: {}", output);
+    assert!(output.contains("Circle[center=Point[x=3, y=4], radius=2.000000]"),
+            "toString should use the toString of record components, got: {}", output);
+    assert!(output.contains("Window[title=main, size=Size[width=800, height=600], tag=null]"),
+            "toString should print struct fields and null components, got: {}", output);
+    assert!(output.contains("21.500000 C"),
+            "An explicit toString should not be replaced, got: {}", output);
+}
+
+#[test]
+fn test_record_equals_and_hash_code() {
+    let output = compile_and_run_eol("examples/test_records.cay").expect("records example should compile and run");
+    let lines: Vec<&str> = output.lines().map(|l| l.trim()).collect();
+    // p.equals(q), p == q, p.equals(r), 哈希值相等, p.hashCode()
+    assert_eq!(&lines[2..7], &["true", "true", "false", "true", "97"],
+               "equals and hashCode should compare the components, got: {}", output);
+    // 含结构体和可空字符串组件的记录: w1.equals(w2), 哈希值相等, ..., w1.equals(w3)
+    assert_eq!(&lines[19..21], &["true", "true"],
+               "equals should compare struct fields and null components, got: {}", output);
+    assert_eq!(lines[22], "false", "Different components should not be equal, got: {}", output);
+}
+
+#[test]
+fn test_record_compact_constructor() {
+    let output = compile_and_run_eol("examples/test_records.cay").expect("records example should compile and run");
+    let lines: Vec<&str> = output.lines().map(|l| l.trim()).collect();
+    // nobody.age(), alice.isAdult(), nobody.isAdult(), Person.created
+    assert_eq!(&lines[8..12], &["0", "true", "false", "2"],
+               "The compact constructor should run before the fields are assigned, got: {}", output);
+}
+
+#[test]
+fn test_error_record_instance_field() {
+    let error = compile_eol_expect_error("examples/errors/error_record_instance_field.cay")
+        .expect("declaring an instance field in a record should fail to compile");
+    assert!(
+        error.contains("记录 'Point' 中不能声明实例字段 'label'"),
+        "Should reject instance fields in the record body, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_record_immutable() {
+    let error = compile_eol_expect_error("examples/errors/error_record_immutable.cay")
+        .expect("assigning a record component should fail to compile");
+    assert!(
+        error.contains("Cannot assign a value to final field 'x'"),
+        "Record components should be final, got: {}",
+        error
+    );
+}
@@ -0,0 +1,78 @@
+//! Cavvy 语言 Result/Option 与 ? 运算符集成测试
+//!
+//! 测试内置 Result<T, E>、Option<T>、省略枚举名的变体构造以及 ? 错误传播
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 Result/Option测试 ====================
+
+#[test]
+fn test_result_ok_and_err() {
+    let output = compile_and_run_eol("examples/test_result.cay").expect("result example should compile and run");
+    assert!(output.contains("Ok(7)") && output.contains("Err(not a digit: x)"),
+            "Ok and Err should be constructed and matched, got: {}", output);
+}
+
+#[test]
+fn test_try_propagates_error() {
+    let output = compile_and_run_eol("examples/test_result.cay").expect("result example should compile and run");
+    assert!(output.contains("Ok(9)"),
+            "? should unwrap Ok values, got: {}", output);
+    assert!(output.contains("Err(expected one digit, got '55')") && output.contains("Err(not a digit: q)"),
+            "? should return the first error early, got: {}", output);
+}
+
+#[test]
+fn test_option_some_and_none() {
+    let output = compile_and_run_eol("examples/test_result.cay").expect("result example should compile and run");
+    assert!(output.contains("Some(5)") && output.contains("Some(3)") && output.contains("None"),
+            "? on Option should propagate None, got: {}", output);
+    assert!(output.contains("missing: 1.000000") && output.contains("present: 2.500000"),
+            "None and Some should take their type from the declaration, got: {}", output);
+    assert!(output.contains("5000000000"),
+            "Ok payloads should be converted to the declared type, got: {}", output);
+}
+
+#[test]
+fn test_error_try_outside_result() {
+    let error = compile_eol_expect_error("examples/errors/error_try_outside_result.cay")
+        .expect("? in a void method should fail to compile");
+    assert!(
+        error.contains("requires the enclosing method to return Result"),
+        "Should report the incompatible return type, got: {}",
+        error
+    );
+    assert!(
+        error.contains("Result<int, String>"),
+        "Should render generic instances with their type arguments, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_try_error_mismatch() {
+    let error = compile_eol_expect_error("examples/errors/error_try_error_mismatch.cay")
+        .expect("? with a different error type should fail to compile");
+    assert!(
+        error.contains("error types must match"),
+        "Should report the error type mismatch, got: {}",
+        error
+    );
+    assert!(
+        error.contains("method returning Result<int, int>"),
+        "Should render generic instances with their type arguments, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_option_infer_none() {
+    let error = compile_eol_expect_error("examples/errors/error_option_infer_none.cay")
+        .expect("None without an expected type should fail to compile");
+    assert!(
+        error.contains("Cannot infer type arguments for 'None'"),
+        "Should report that None needs an expected type, got: {}",
+        error
+    );
+}
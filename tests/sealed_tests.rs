@@ -0,0 +1,63 @@
+//! Cavvy 语言 sealed 类和接口集成测试
+//!
+//! 测试 permits 列表检查、覆盖全部允许子类的穷尽 switch、
+//! instanceof 的不可能匹配检查以及 sealed 接口调用的去虚化
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 sealed 类型测试 ====================
+
+#[test]
+fn test_sealed_interface_dispatch() {
+    let output = compile_and_run_eol("examples/test_sealed.cay").expect("sealed example should compile and run");
+    assert!(output.contains("(2 + 3 * 4) = 14"), "Calls on sealed interfaces should reach every implementation, got: {}", output);
+    assert!(output.contains("single: 7"), "Calls on sealed interfaces should dispatch on the runtime class, got: {}", output);
+}
+
+#[test]
+fn test_sealed_switch_exhaustive_without_default() {
+    let output = compile_and_run_eol("examples/test_sealed.cay").expect("sealed example should compile and run");
+    assert!(output.contains("car with 4 seats"), "Switch over permitted subclasses should match, got: {}", output);
+    assert!(output.contains("truck carrying 10"), "Nested sealed classes should match their own case, got: {}", output);
+    assert!(output.contains("dump truck"), "Subclasses of permitted classes should match in order, got: {}", output);
+}
+
+#[test]
+fn test_error_sealed_not_permitted() {
+    let error = compile_eol_expect_error("examples/errors/error_sealed_not_permitted.cay")
+        .expect("implementing a sealed interface without permission should fail to compile");
+    assert!(error.contains("Class 'Hexagon' is not permitted to implement sealed type 'Shape'"),
+            "Should report the unpermitted implementation, got: {}", error);
+}
+
+#[test]
+fn test_error_sealed_permits_unrelated() {
+    let error = compile_eol_expect_error("examples/errors/error_sealed_permits_unrelated.cay")
+        .expect("permitting an unrelated class should fail to compile");
+    assert!(error.contains("does not directly extend or implement it"),
+            "Should report the unrelated permitted class, got: {}", error);
+}
+
+#[test]
+fn test_error_sealed_switch_not_exhaustive() {
+    let error = compile_eol_expect_error("examples/errors/error_sealed_switch_not_exhaustive.cay")
+        .expect("a switch missing a permitted subclass should fail to compile");
+    assert!(error.contains("add a default branch or a case for Mul"),
+            "Should name the missing permitted subclass, got: {}", error);
+}
+
+#[test]
+fn test_error_sealed_instanceof_never() {
+    let error = compile_eol_expect_error("examples/errors/error_sealed_instanceof_never.cay")
+        .expect("an impossible instanceof on a sealed interface should fail to compile");
+    assert!(error.contains("A value of type Dog can never be an instance of Shape"),
+            "Should report the impossible instanceof, got: {}", error);
+}
+
+#[test]
+fn test_error_sealed_missing_permits() {
+    let error = compile_eol_expect_error("examples/errors/error_sealed_missing_permits.cay")
+        .expect("a sealed class without permits should fail to compile");
+    assert!(error.contains("缺少 permits 列表"), "Should require a permits list, got: {}", error);
+}
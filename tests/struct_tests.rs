@@ -0,0 +1,95 @@
+//! Cavvy 语言结构体集成测试
+//!
+//! 测试按值复制的结构体：作为局部变量、对象字段和数组元素存放，按值传参和返回，
+//! 嵌套结构体，按 C 调用约定传给 extern 函数，以及相关的语义错误
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 结构体测试 ====================
+
+#[test]
+fn test_struct_copy_semantics() {
+    let output = compile_and_run_eol("examples/test_structs.cay").expect("structs example should compile and run");
+    assert!(output.contains("p.x = 1.500000, q.x = 10.000000"),
+            "Assigning a struct should copy the value, got: {}", output);
+    assert!(output.contains("after reset p.x = 1.500000"),
+            "Structs should be passed by value, got: {}", output);
+    assert!(output.contains("sum = 2.500000, 3.000000"),
+            "Structs should be returned by value, got: {}", output);
+    assert!(output.contains("zero = 0.000000, 0.000000"),
+            "new Point() should zero all fields, got: {}", output);
+}
+
+#[test]
+fn test_struct_storage() {
+    let output = compile_and_run_eol("examples/test_structs.cay").expect("structs example should compile and run");
+    assert!(output.contains("pts[2] = 4.000000, 2.500000"),
+            "Array elements should store structs in place, got: {}", output);
+    assert!(output.contains("area = 28.000000"),
+            "Nested struct fields should be readable and assignable, got: {}", output);
+    assert!(output.contains("origin = 5.000000, 6.000000") && output.contains("p unchanged = 1.500000"),
+            "Struct fields of objects should hold their own copy, got: {}", output);
+}
+
+#[test]
+fn test_struct_extern_call() {
+    let output = compile_and_run_eol("examples/test_structs.cay").expect("structs example should compile and run");
+    assert!(output.contains("17 / 5 = 3 rem 2"),
+            "Structs should follow the C calling convention in extern calls, got: {}", output);
+}
+
+#[test]
+fn test_error_struct_field_count() {
+    let error = compile_eol_expect_error("examples/errors/error_struct_field_count.cay")
+        .expect("constructing a struct with too few values should fail to compile");
+    assert!(
+        error.contains("Struct 'Point' has 2 field(s), but 1 value(s) were given"),
+        "Should report the field count, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_struct_null() {
+    let error = compile_eol_expect_error("examples/errors/error_struct_null.cay")
+        .expect("assigning null to a struct should fail to compile");
+    assert!(
+        error.contains("Cannot assign Object to struct Point"),
+        "Should reject null for a struct, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_struct_recursive() {
+    let error = compile_eol_expect_error("examples/errors/error_struct_recursive.cay")
+        .expect("a struct containing itself should fail to compile");
+    assert!(
+        error.contains("Recursive struct 'Node'"),
+        "Should report the recursive struct, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_struct_compare() {
+    let error = compile_eol_expect_error("examples/errors/error_struct_compare.cay")
+        .expect("comparing structs with == should fail to compile");
+    assert!(
+        error.contains("compare struct fields instead"),
+        "Should reject == on structs, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_struct_temporary_field() {
+    let error = compile_eol_expect_error("examples/errors/error_struct_temporary_field.cay")
+        .expect("assigning to a field of a temporary struct should fail to compile");
+    assert!(
+        error.contains("temporary struct value"),
+        "Should report the temporary struct, got: {}",
+        error
+    );
+}
@@ -0,0 +1,62 @@
+//! Cavvy 语言 switch 扩展集成测试
+//!
+//! 测试字符串/字符/常量标签、多标签、守卫、箭头分支以及 switch 表达式
+
+mod common;
+use common::{compile_and_run_eol, compile_eol_expect_error};
+
+// ==================== 0.5.2.0 switch 扩展测试 ====================
+
+#[test]
+fn test_switch_on_strings() {
+    let output = compile_and_run_eol("examples/test_switch_patterns.cay").expect("switch patterns example should compile and run");
+    assert!(output.contains("route(GET, /) = 200") && output.contains("route(HEAD, /x) = 404"),
+            "String labels sharing a case should match, got: {}", output);
+    assert!(output.contains("route(PUT, /items) = 201") && output.contains("route(DELETE, /) = 405"),
+            "Unmatched strings should reach default, got: {}", output);
+    assert!(output.contains("route(, /) = 400"), "Empty string label should match, got: {}", output);
+}
+
+#[test]
+fn test_switch_guards() {
+    let output = compile_and_run_eol("examples/test_switch_patterns.cay").expect("switch patterns example should compile and run");
+    assert!(output.contains("route(POST, /login) = 302") && output.contains("route(POST, /items) = 201"),
+            "A failed guard should fall back to later cases, got: {}", output);
+    assert!(output.contains("size(Circle(20)) = 3") && output.contains("size(Circle(5)) = 2"),
+            "Guards should see pattern bindings, got: {}", output);
+}
+
+#[test]
+fn test_switch_expression_and_arrow_cases() {
+    let output = compile_and_run_eol("examples/test_switch_patterns.cay").expect("switch patterns example should compile and run");
+    assert!(output.contains("kind(e) = vowel") && output.contains("kind(z) = other"),
+            "Char switch expression should work, got: {}", output);
+    assert!(output.contains("exactly low") && output.contains("exactly high") && output.contains("negative one"),
+            "Arrow cases with constant labels should work, got: {}", output);
+    assert!(output.contains("huge") && !output.contains("three"),
+            "Arrow cases should not fall through, got: {}", output);
+    assert!(output.contains("role(root) = admin") && output.contains("5000000000"),
+            "String constants and promoted expression results should work, got: {}", output);
+}
+
+#[test]
+fn test_error_switch_expression_no_default() {
+    let error = compile_eol_expect_error("examples/errors/error_switch_expression_no_default.cay")
+        .expect("switch expression without default should fail to compile");
+    assert!(
+        error.contains("must have a default branch"),
+        "Should report the missing default branch, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_error_switch_duplicate_label() {
+    let error = compile_eol_expect_error("examples/errors/error_switch_duplicate_label.cay")
+        .expect("duplicate case label should fail to compile");
+    assert!(
+        error.contains("Duplicate case label"),
+        "Should report the duplicate label, got: {}",
+        error
+    );
+}